@@ -6,6 +6,7 @@ static ALLOC: reth_cli_util::allocator::Allocator = reth_cli_util::allocator::ne
 use clap::{Args, Parser};
 use reth::cli::Cli;
 use reth_ethereum_cli::chainspec::EthereumChainSpecParser;
+use reth_exex_trace_index::TraceIndexExEx;
 use reth_node_builder::{
     engine_tree_config::{
         TreeConfig, DEFAULT_MEMORY_BLOCK_BUFFER_TARGET, DEFAULT_PERSISTENCE_THRESHOLD,
@@ -66,6 +67,7 @@ fn main() {
                 warn!(target: "reth::cli", "Experimental engine is default now, and the --engine.experimental flag is deprecated. To enable the legacy functionality, use --engine.legacy.");
             }
 
+            let trace_index = builder.config().rpc.rpc_trace_index;
             let use_legacy_engine = engine_args.legacy;
             match use_legacy_engine {
                 false => {
@@ -76,6 +78,9 @@ fn main() {
                         .with_types_and_provider::<EthereumNode, BlockchainProvider2<_>>()
                         .with_components(EthereumNode::components())
                         .with_add_ons(EthereumAddOns::default())
+                        .install_exex_if(trace_index, "trace-index", |ctx| async move {
+                            Ok(TraceIndexExEx::new(ctx)?.run())
+                        })
                         .launch_with_fn(|builder| {
                             let launcher = EngineNodeLauncher::new(
                                 builder.task_executor().clone(),
@@ -89,7 +94,13 @@ fn main() {
                 }
                 true => {
                     info!(target: "reth::cli", "Running with legacy engine");
-                    let handle = builder.launch_node(EthereumNode::default()).await?;
+                    let handle = builder
+                        .node(EthereumNode::default())
+                        .install_exex_if(trace_index, "trace-index", |ctx| async move {
+                            Ok(TraceIndexExEx::new(ctx)?.run())
+                        })
+                        .launch()
+                        .await?;
                     handle.node_exit_future.await
                 }
             }
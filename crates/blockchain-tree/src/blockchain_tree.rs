@@ -677,7 +677,9 @@ where
     ) -> Result<InsertPayloadOk, InsertBlockError> {
         match block.try_seal_with_senders() {
             Ok(block) => self.insert_block(block, BlockValidationKind::Exhaustive),
-            Err(block) => Err(InsertBlockError::sender_recovery_error(block)),
+            Err(block) => {
+                Err(self.on_insert_block_error(InsertBlockError::sender_recovery_error(block)))
+            }
         }
     }
 
@@ -781,15 +783,32 @@ where
 
         // validate block consensus rules
         if let Err(err) = self.validate_block(&block) {
-            return Err(InsertBlockError::consensus_error(err, block.block));
+            return Err(
+                self.on_insert_block_error(InsertBlockError::consensus_error(err, block.block))
+            );
         }
 
         let status = self
             .try_insert_validated_block(block.clone(), block_validation_kind)
-            .map_err(|kind| InsertBlockError::new(block.block, kind))?;
+            .map_err(|kind| self.on_insert_block_error(InsertBlockError::new(block.block, kind)))?;
         Ok(InsertPayloadOk::Inserted(status))
     }
 
+    /// Handles an error that occurred while inserting a block.
+    ///
+    /// If the block was rejected because it is invalid, it is recorded as a bad block in the
+    /// database.
+    fn on_insert_block_error(&self, error: InsertBlockError) -> InsertBlockError {
+        if error.kind().is_invalid_block() {
+            if let Err(err) =
+                self.externals.save_bad_block(error.block().clone(), error.kind().to_string())
+            {
+                warn!(target: "blockchain_tree", %err, "Failed to save bad block");
+            }
+        }
+        error
+    }
+
     /// Discard all blocks that precede block number from the buffer.
     pub fn remove_old_blocks(&mut self, block: BlockNumber) {
         self.state.buffered_blocks.remove_old_blocks(block);
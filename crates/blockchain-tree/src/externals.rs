@@ -5,10 +5,10 @@ use reth_consensus::Consensus;
 use reth_db::{static_file::HeaderMask, tables};
 use reth_db_api::{cursor::DbCursorRO, transaction::DbTx};
 use reth_node_types::NodeTypesWithDB;
-use reth_primitives::StaticFileSegment;
+use reth_primitives::{SealedBlock, StaticFileSegment};
 use reth_provider::{
-    providers::ProviderNodeTypes, ChainStateBlockReader, ChainStateBlockWriter, ProviderFactory,
-    StaticFileProviderFactory, StatsReader,
+    providers::ProviderNodeTypes, BadBlockWriter, ChainStateBlockReader, ChainStateBlockWriter,
+    ProviderFactory, StaticFileProviderFactory, StatsReader,
 };
 use reth_storage_errors::provider::ProviderResult;
 use std::{collections::BTreeMap, sync::Arc};
@@ -93,6 +93,13 @@ impl<N: ProviderNodeTypes, E> TreeExternals<N, E> {
         self.provider_factory.provider()?.last_finalized_block_number()
     }
 
+    pub(crate) fn save_bad_block(&self, block: SealedBlock, error: String) -> ProviderResult<()> {
+        let provider_rw = self.provider_factory.provider_rw()?;
+        provider_rw.save_bad_block(block, error)?;
+        provider_rw.commit()?;
+        Ok(())
+    }
+
     pub(crate) fn save_finalized_block_number(
         &self,
         block_number: BlockNumber,
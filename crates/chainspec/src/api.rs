@@ -52,6 +52,9 @@ pub trait EthChainSpec: Send + Sync + Unpin + Debug {
     /// The bootnodes for the chain, if any.
     fn bootnodes(&self) -> Option<Vec<NodeRecord>>;
 
+    /// The block number of the merge, if known.
+    fn paris_block(&self) -> Option<u64>;
+
     /// Returns `true` if this chain contains Optimism configuration.
     fn is_optimism(&self) -> bool {
         self.chain().is_optimism()
@@ -103,6 +106,10 @@ impl EthChainSpec for ChainSpec {
         self.bootnodes()
     }
 
+    fn paris_block(&self) -> Option<u64> {
+        self.paris_block_and_final_difficulty.map(|(block, _)| block)
+    }
+
     fn is_optimism(&self) -> bool {
         self.chain.is_optimism()
     }
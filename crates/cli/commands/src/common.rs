@@ -90,6 +90,7 @@ impl<C: ChainSpecParser<ChainSpec: EthChainSpec + EthereumHardforks>> Environmen
                 StaticFileProvider::read_only(sf_path, false)?,
             ),
         };
+        let sfp = sfp.with_segment_configs(config.static_files.segments());
 
         let provider_factory = self.create_provider_factory(&config, db, sfp)?;
         if access.is_read_write() {
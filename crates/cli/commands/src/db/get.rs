@@ -1,13 +1,19 @@
-use alloy_primitives::{hex, BlockHash};
+use alloy_primitives::{hex, Address, BlockHash};
 use clap::Parser;
 use reth_db::{
-    static_file::{ColumnSelectorOne, ColumnSelectorTwo, HeaderMask, ReceiptMask, TransactionMask},
+    static_file::{
+        AccountChangeSetMask, ColumnSelectorOne, ColumnSelectorThree, ColumnSelectorTwo,
+        HeaderMask, ReceiptMask, StorageChangeSetMask, TransactionMask,
+    },
     tables, RawKey, RawTable, Receipts, TableViewer, Transactions,
 };
-use reth_db_api::table::{Decompress, DupSort, Table};
+use reth_db_api::{
+    models::{AccountBeforeTx, CompactU64},
+    table::{Decompress, DupSort, Table},
+};
 use reth_db_common::DbTool;
 use reth_node_builder::NodeTypesWithDB;
-use reth_primitives::Header;
+use reth_primitives::{Header, StorageEntry};
 use reth_provider::{providers::ProviderNodeTypes, StaticFileProviderFactory};
 use reth_static_file_types::StaticFileSegment;
 use tracing::error;
@@ -71,6 +77,14 @@ impl Command {
                         table_key::<tables::Receipts>(&key)?,
                         <ReceiptMask<<Receipts as Table>::Value>>::MASK,
                     ),
+                    StaticFileSegment::AccountChangeSets => (
+                        serde_json::from_str::<u64>(&key)?,
+                        <AccountChangeSetMask<CompactU64, AccountBeforeTx>>::MASK,
+                    ),
+                    StaticFileSegment::StorageChangeSets => (
+                        serde_json::from_str::<u64>(&key)?,
+                        <StorageChangeSetMask<CompactU64, Address, StorageEntry>>::MASK,
+                    ),
                 };
 
                 let content = tool.provider_factory.static_file_provider().find_static_file(
@@ -112,6 +126,27 @@ impl Command {
                                     )?;
                                     println!("{}", serde_json::to_string_pretty(&receipt)?);
                                 }
+                                StaticFileSegment::AccountChangeSets => {
+                                    let block = CompactU64::decompress(content[0].as_slice())?;
+                                    let change =
+                                        AccountBeforeTx::decompress(content[1].as_slice())?;
+                                    println!(
+                                        "BlockNumber\n{}\n\nAccountBeforeTx\n{}",
+                                        *block,
+                                        serde_json::to_string_pretty(&change)?
+                                    );
+                                }
+                                StaticFileSegment::StorageChangeSets => {
+                                    let block = CompactU64::decompress(content[0].as_slice())?;
+                                    let address = Address::decompress(content[1].as_slice())?;
+                                    let entry = StorageEntry::decompress(content[2].as_slice())?;
+                                    println!(
+                                        "BlockNumber\n{}\n\nAddress\n{}\n\nStorageEntry\n{}",
+                                        *block,
+                                        address,
+                                        serde_json::to_string_pretty(&entry)?
+                                    );
+                                }
                             }
                         }
                     }
@@ -12,6 +12,7 @@ mod clear;
 mod diff;
 mod get;
 mod list;
+mod snapshot;
 mod stats;
 /// DB List TUI
 mod tui;
@@ -51,6 +52,10 @@ pub enum Subcommands {
     Version,
     /// Returns the full database path
     Path,
+    /// Writes a consistent snapshot of the database and the static files to a directory
+    Snapshot(snapshot::Command),
+    /// Restores a snapshot created by `reth db snapshot` into an empty datadir
+    RestoreSnapshot(snapshot::RestoreCommand),
 }
 
 /// `db_ro_exec` opens a database in read-only mode, and then execute with the provided command
@@ -72,15 +77,18 @@ impl<C: ChainSpecParser<ChainSpec: EthChainSpec + EthereumHardforks>> Command<C>
         let db_path = data_dir.db();
         let static_files_path = data_dir.static_files();
 
-        // ensure the provided datadir exist
-        eyre::ensure!(
-            data_dir.data_dir().is_dir(),
-            "Datadir does not exist: {:?}",
-            data_dir.data_dir()
-        );
-
-        // ensure the provided database exist
-        eyre::ensure!(db_path.is_dir(), "Database does not exist: {:?}", db_path);
+        // restoring a snapshot populates an empty datadir
+        if !matches!(self.command, Subcommands::RestoreSnapshot(_)) {
+            // ensure the provided datadir exist
+            eyre::ensure!(
+                data_dir.data_dir().is_dir(),
+                "Datadir does not exist: {:?}",
+                data_dir.data_dir()
+            );
+
+            // ensure the provided database exist
+            eyre::ensure!(db_path.is_dir(), "Database does not exist: {:?}", db_path);
+        }
 
         match self.command {
             // TODO: We'll need to add this on the DB trait.
@@ -151,6 +159,14 @@ impl<C: ChainSpecParser<ChainSpec: EthChainSpec + EthereumHardforks>> Command<C>
             Subcommands::Path => {
                 println!("{}", db_path.display());
             }
+            Subcommands::Snapshot(command) => {
+                db_ro_exec!(self.env, tool, N, {
+                    command.execute(&tool)?;
+                });
+            }
+            Subcommands::RestoreSnapshot(command) => {
+                command.execute(&db_path, &static_files_path)?;
+            }
         }
 
         Ok(())
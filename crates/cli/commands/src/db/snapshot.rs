@@ -0,0 +1,63 @@
+use clap::Parser;
+use reth_db_common::DbTool;
+use reth_provider::{
+    providers::{restore_snapshot, ProviderNodeTypes, SnapshotOptions},
+    StorageSnapshotProvider,
+};
+use std::path::{Path, PathBuf};
+use tracing::info;
+
+/// The arguments for the `reth db snapshot` command
+#[derive(Parser, Debug)]
+pub struct Command {
+    /// The directory to write the snapshot to, which must be empty or not exist yet.
+    dir: PathBuf,
+
+    /// Omit free pages from the database copy, which takes longer but yields a smaller file.
+    #[arg(long)]
+    compact: bool,
+
+    /// Copy static files below the finalized block instead of hard-linking them.
+    #[arg(long)]
+    copy_static_files: bool,
+}
+
+impl Command {
+    /// Execute `db snapshot` command
+    pub fn execute<N: ProviderNodeTypes>(self, tool: &DbTool<N>) -> eyre::Result<()> {
+        let options =
+            SnapshotOptions { compact: self.compact, copy_static_files: self.copy_static_files };
+        let manifest = tool.provider_factory.create_snapshot(&self.dir, options)?;
+
+        info!(
+            target: "reth::cli",
+            dir = ?self.dir,
+            block_number = manifest.block_number,
+            files = manifest.files.len(),
+            "Snapshot created"
+        );
+        Ok(())
+    }
+}
+
+/// The arguments for the `reth db restore-snapshot` command
+#[derive(Parser, Debug)]
+pub struct RestoreCommand {
+    /// The directory of the snapshot to restore.
+    dir: PathBuf,
+}
+
+impl RestoreCommand {
+    /// Execute `db restore-snapshot` command
+    pub fn execute(self, db_path: &Path, static_files_path: &Path) -> eyre::Result<()> {
+        let manifest = restore_snapshot(&self.dir, db_path, static_files_path)?;
+
+        info!(
+            target: "reth::cli",
+            dir = ?self.dir,
+            block_number = manifest.block_number,
+            "Snapshot restored"
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,174 @@
+//! Command that exports blocks from the database to Era1 files.
+use crate::common::{AccessRights, Environment, EnvironmentArgs};
+use alloy_primitives::BlockNumber;
+use clap::Parser;
+use reth_chainspec::{EthChainSpec, EthereumHardforks};
+use reth_cli::chainspec::ChainSpecParser;
+use reth_era::{era1_file_name, Era1Block, Era1Writer, ERA1_FILE_EXTENSION, MAX_BLOCKS_PER_ERA1};
+use reth_node_builder::NodeTypesWithEngine;
+use reth_provider::{
+    BlockNumReader, BlockReader, ChainSpecProvider, HeaderProvider, ProviderError, ReceiptProvider,
+};
+use std::{
+    io::BufWriter,
+    ops::RangeInclusive,
+    path::{Path, PathBuf},
+};
+use tracing::info;
+
+/// Exports blocks, receipts and total difficulty to Era1 files.
+///
+/// Each file holds one epoch of 8192 blocks and is named after the network, the epoch and the
+/// accumulator root of its blocks.
+#[derive(Debug, Parser)]
+pub struct ExportEraCommand<C: ChainSpecParser> {
+    #[command(flatten)]
+    env: EnvironmentArgs<C>,
+
+    /// The first block to export.
+    #[arg(long, value_name = "BLOCK", default_value_t = 0)]
+    first_block: BlockNumber,
+
+    /// The last block to export.
+    ///
+    /// Defaults to the last pre-merge block, or the last block in the database if the merge has
+    /// not happened yet.
+    #[arg(long, value_name = "BLOCK", verbatim_doc_comment)]
+    last_block: Option<BlockNumber>,
+
+    /// The directory to write the Era1 files to.
+    #[arg(value_name = "EXPORT_DIR", verbatim_doc_comment)]
+    directory: PathBuf,
+}
+
+impl<C: ChainSpecParser<ChainSpec: EthChainSpec + EthereumHardforks>> ExportEraCommand<C> {
+    /// Execute `export-era` command
+    pub async fn execute<N: NodeTypesWithEngine<ChainSpec = C::ChainSpec>>(
+        self,
+    ) -> eyre::Result<()> {
+        let Environment { provider_factory, .. } = self.env.init::<N>(AccessRights::RO)?;
+        let provider = provider_factory.provider()?;
+
+        let network = provider_factory.chain_spec().chain().to_string();
+        let last_block = match self.last_block {
+            Some(last_block) => last_block.min(provider.last_block_number()?),
+            None => provider.last_block_number()?,
+        };
+        // post-merge blocks have no difficulty, and are not part of Era1 files unless requested
+        let stop_at_merge = self.last_block.is_none();
+
+        export_era1_files(
+            &provider,
+            &network,
+            self.first_block..=last_block,
+            stop_at_merge,
+            &self.directory,
+        )?;
+
+        Ok(())
+    }
+}
+
+/// Exports the given blocks to Era1 files in `directory`, one file per epoch, and returns the
+/// paths of the written files.
+///
+/// If `stop_at_merge` is set, the export stops at the first block without difficulty.
+fn export_era1_files<P>(
+    provider: &P,
+    network: &str,
+    blocks: RangeInclusive<BlockNumber>,
+    stop_at_merge: bool,
+    directory: &Path,
+) -> eyre::Result<Vec<PathBuf>>
+where
+    P: BlockReader + HeaderProvider + ReceiptProvider,
+{
+    reth_fs_util::create_dir_all(directory)?;
+
+    let mut files = Vec::new();
+    let (mut next_block, last_block) = blocks.into_inner();
+    'epochs: while next_block <= last_block {
+        let epoch = next_block / MAX_BLOCKS_PER_ERA1 as u64;
+        let epoch_end = ((epoch + 1) * MAX_BLOCKS_PER_ERA1 as u64 - 1).min(last_block);
+
+        let tmp_path = directory.join(format!("{network}-{epoch:05}.{ERA1_FILE_EXTENSION}.tmp"));
+        let mut writer = Era1Writer::new(BufWriter::new(reth_fs_util::create_file(&tmp_path)?))?;
+
+        let mut reached_merge = false;
+        for number in next_block..=epoch_end {
+            let block = provider
+                .block(number.into())?
+                .ok_or(ProviderError::BlockBodyIndicesNotFound(number))?;
+            if stop_at_merge && number > 0 && block.difficulty.is_zero() {
+                reached_merge = true;
+                break
+            }
+
+            let receipts = provider
+                .receipts_by_block(number.into())?
+                .filter(|receipts| receipts.len() == block.body.transactions.len())
+                .ok_or_else(|| eyre::eyre!("receipts of block {number} are not available"))?;
+            let total_difficulty = provider
+                .header_td_by_number(number)?
+                .ok_or(ProviderError::TotalDifficultyNotFound(number))?;
+
+            writer.append_block(&Era1Block::new(block.seal_slow(), &receipts, total_difficulty))?;
+        }
+
+        if writer.is_empty() {
+            reth_fs_util::remove_file(&tmp_path)?;
+            break 'epochs
+        }
+
+        let blocks = writer.len();
+        let (_, accumulator) = writer.finish()?;
+        let path = directory.join(era1_file_name(network, epoch, accumulator));
+        reth_fs_util::rename(&tmp_path, &path)?;
+        info!(target: "reth::cli", path = %path.display(), blocks, %accumulator, "Exported Era1 file");
+        files.push(path);
+
+        if reached_merge {
+            info!(target: "reth::cli", "Reached the merge, stopping export");
+            break
+        }
+        next_block = epoch_end + 1;
+    }
+
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_db_common::init::init_genesis;
+    use reth_era::Era1File;
+    use reth_ethereum_cli::chainspec::EthereumChainSpecParser;
+    use reth_provider::test_utils::create_test_provider_factory;
+
+    #[test]
+    fn parse_export_era_command() {
+        let args: ExportEraCommand<EthereumChainSpecParser> =
+            ExportEraCommand::parse_from(["reth", "--last-block", "8191", "era"]);
+        assert_eq!(args.first_block, 0);
+        assert_eq!(args.last_block, Some(8191));
+        assert_eq!(args.directory, PathBuf::from("era"));
+    }
+
+    #[test]
+    fn export_genesis() {
+        let factory = create_test_provider_factory();
+        let genesis_hash = init_genesis(&factory).unwrap();
+        let dir = tempfile::tempdir().unwrap();
+
+        let files =
+            export_era1_files(&factory.provider().unwrap(), "mainnet", 0..=0, true, dir.path())
+                .unwrap();
+        assert_eq!(files.len(), 1);
+
+        let file = Era1File::open(&files[0]).unwrap();
+        file.verify().unwrap();
+        assert_eq!(file.blocks.len(), 1);
+        assert_eq!(file.blocks[0].block.hash(), genesis_hash);
+        assert_eq!(Some(file.blocks[0].total_difficulty), factory.header_td_by_number(0).unwrap());
+    }
+}
@@ -15,6 +15,7 @@ use reth_downloaders::{
     file_client::{ChunkedFileReader, FileClient, DEFAULT_BYTE_LEN_CHUNK_CHAIN_FILE},
     headers::reverse_headers::ReverseHeadersDownloaderBuilder,
 };
+use reth_era::{Era1File, ERA1_FILE_EXTENSION};
 use reth_evm::execute::BlockExecutorProvider;
 use reth_network_p2p::{
     bodies::downloader::BodyDownloader,
@@ -30,7 +31,10 @@ use reth_provider::{
 use reth_prune::PruneModes;
 use reth_stages::{prelude::*, Pipeline, StageId, StageSet};
 use reth_static_file::StaticFileProducer;
-use std::{path::PathBuf, sync::Arc};
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 use tokio::sync::watch;
 use tracing::{debug, error, info};
 
@@ -52,6 +56,9 @@ pub struct ImportCommand<C: ChainSpecParser> {
     ///
     /// The online stages (headers and bodies) are replaced by a file import, after which the
     /// remaining stages are executed.
+    ///
+    /// Era1 files (`.era1`) and directories of Era1 files are imported one file at a time,
+    /// after their accumulator roots have been verified.
     #[arg(value_name = "IMPORT_PATH", verbatim_doc_comment)]
     path: PathBuf,
 }
@@ -81,49 +88,52 @@ impl<C: ChainSpecParser<ChainSpec: EthChainSpec + EthereumHardforks>> ImportComm
         let consensus = Arc::new(EthBeaconConsensus::new(self.env.chain.clone()));
         info!(target: "reth::cli", "Consensus engine initialized");
 
-        // open file
-        let mut reader = ChunkedFileReader::new(&self.path, self.chunk_len).await?;
-
         let mut total_decoded_blocks = 0;
         let mut total_decoded_txns = 0;
 
-        while let Some(file_client) = reader.next_chunk::<FileClient>().await? {
-            // create a new FileClient from chunk read from file
-            info!(target: "reth::cli",
-                "Importing chain file chunk"
-            );
-
-            let tip = file_client.tip().ok_or(eyre::eyre!("file client has no tip"))?;
-            info!(target: "reth::cli", "Chain file chunk read");
-
-            total_decoded_blocks += file_client.headers_len();
-            total_decoded_txns += file_client.total_transactions();
-
-            let (mut pipeline, events) = build_import_pipeline(
-                &config,
-                provider_factory.clone(),
-                &consensus,
-                Arc::new(file_client),
-                StaticFileProducer::new(provider_factory.clone(), PruneModes::default()),
-                self.no_state,
-                executor.clone(),
-            )?;
-
-            // override the tip
-            pipeline.set_tip(tip);
-            debug!(target: "reth::cli", ?tip, "Tip manually set");
-
-            let provider = provider_factory.provider()?;
-
-            let latest_block_number =
-                provider.get_stage_checkpoint(StageId::Finish)?.map(|ch| ch.block_number);
-            tokio::spawn(reth_node_events::node::handle_events(None, latest_block_number, events));
-
-            // Run pipeline
-            info!(target: "reth::cli", "Starting sync pipeline");
-            tokio::select! {
-                res = pipeline.run() => res?,
-                _ = tokio::signal::ctrl_c() => {},
+        if is_era1_import(&self.path) {
+            for path in era1_files(&self.path)? {
+                let Some(file_client) = read_era1_file(&provider_factory, &path)? else {
+                    info!(target: "reth::cli", path = %path.display(), "Era1 file already imported, skipping");
+                    continue
+                };
+                info!(target: "reth::cli", path = %path.display(), "Era1 file verified");
+
+                total_decoded_blocks += file_client.headers_len();
+                total_decoded_txns += file_client.total_transactions();
+
+                import_file_client(
+                    &config,
+                    provider_factory.clone(),
+                    &consensus,
+                    file_client,
+                    self.no_state,
+                    executor.clone(),
+                )
+                .await?;
+            }
+        } else {
+            // open file
+            let mut reader = ChunkedFileReader::new(&self.path, self.chunk_len).await?;
+
+            while let Some(file_client) = reader.next_chunk::<FileClient>().await? {
+                // create a new FileClient from chunk read from file
+                info!(target: "reth::cli",
+                    "Importing chain file chunk"
+                );
+
+                total_decoded_blocks += file_client.headers_len();
+                total_decoded_txns += file_client.total_transactions();
+
+                import_file_client(
+                    &config,
+                    provider_factory.clone(),
+                    &consensus,
+                    file_client,
+                    self.no_state,
+                    executor.clone(),
+                )
+                .await?;
             }
         }
 
@@ -228,10 +238,173 @@ where
     Ok((pipeline, events))
 }
 
+/// Imports the blocks of a [`FileClient`] by running the import pipeline up to its tip.
+async fn import_file_client<N, C, E>(
+    config: &Config,
+    provider_factory: ProviderFactory<N>,
+    consensus: &Arc<C>,
+    file_client: FileClient,
+    disable_exec: bool,
+    executor: E,
+) -> eyre::Result<()>
+where
+    N: ProviderNodeTypes,
+    C: Consensus + 'static,
+    E: BlockExecutorProvider,
+{
+    let tip = file_client.tip().ok_or(eyre::eyre!("file client has no tip"))?;
+    info!(target: "reth::cli", "Chain file chunk read");
+
+    let (mut pipeline, events) = build_import_pipeline(
+        config,
+        provider_factory.clone(),
+        consensus,
+        Arc::new(file_client),
+        StaticFileProducer::new(provider_factory.clone(), PruneModes::default()),
+        disable_exec,
+        executor,
+    )?;
+
+    // override the tip
+    pipeline.set_tip(tip);
+    debug!(target: "reth::cli", ?tip, "Tip manually set");
+
+    let provider = provider_factory.provider()?;
+
+    let latest_block_number =
+        provider.get_stage_checkpoint(StageId::Finish)?.map(|ch| ch.block_number);
+    tokio::spawn(reth_node_events::node::handle_events(None, latest_block_number, events));
+
+    // Run pipeline
+    info!(target: "reth::cli", "Starting sync pipeline");
+    tokio::select! {
+        res = pipeline.run() => res?,
+        _ = tokio::signal::ctrl_c() => {},
+    }
+
+    Ok(())
+}
+
+/// Returns `true` if the import path is an Era1 file or a directory of Era1 files.
+fn is_era1_import(path: &Path) -> bool {
+    path.is_dir() || path.extension().is_some_and(|ext| ext == ERA1_FILE_EXTENSION)
+}
+
+/// Returns the Era1 files to import, ordered by file name and therefore by epoch.
+fn era1_files(path: &Path) -> eyre::Result<Vec<PathBuf>> {
+    if !path.is_dir() {
+        return Ok(vec![path.to_path_buf()])
+    }
+
+    let mut files = Vec::new();
+    for entry in reth_fs_util::read_dir(path)? {
+        let path = entry?.path();
+        if path.extension().is_some_and(|ext| ext == ERA1_FILE_EXTENSION) {
+            files.push(path);
+        }
+    }
+    files.sort_unstable();
+
+    if files.is_empty() {
+        eyre::bail!("no era1 files found in {}", path.display());
+    }
+    Ok(files)
+}
+
+/// Reads and verifies an Era1 file.
+///
+/// The blocks of the file must form a chain whose accumulator root matches the one stored in the
+/// file, and must extend the chain in the database. Returns `None` if all blocks of the file are
+/// already imported.
+fn read_era1_file<N>(
+    provider_factory: &ProviderFactory<N>,
+    path: &Path,
+) -> eyre::Result<Option<FileClient>>
+where
+    N: ProviderNodeTypes,
+    N::ChainSpec: EthereumHardforks,
+{
+    let file = Era1File::open(path)?;
+    file.verify()?;
+
+    let chain_spec = provider_factory.chain_spec();
+    for block in &file.blocks {
+        // pre-Byzantium receipts commit to intermediate state roots that are not stored
+        if chain_spec.is_byzantium_active_at_block(block.number()) {
+            block.verify_receipts_root()?;
+        }
+    }
+
+    let last_block_number = provider_factory.last_block_number()?;
+    if file.end_block() <= last_block_number {
+        return Ok(None)
+    }
+
+    let first = &file.blocks[0];
+    if first.number() == 0 {
+        if first.block.hash() != chain_spec.genesis_hash() {
+            eyre::bail!("era1 file {} does not match the genesis block", path.display());
+        }
+    } else {
+        let parent_number = first.number() - 1;
+        if parent_number > last_block_number {
+            eyre::bail!(
+                "era1 file {} starts at block {}, but the database ends at block {}",
+                path.display(),
+                first.number(),
+                last_block_number
+            );
+        }
+        let parent = provider_factory
+            .sealed_header(parent_number)?
+            .ok_or_else(|| ProviderError::HeaderNotFound(parent_number.into()))?;
+        let parent_td = provider_factory
+            .header_td_by_number(parent_number)?
+            .ok_or(ProviderError::TotalDifficultyNotFound(parent_number))?;
+        if parent.hash() != first.block.parent_hash ||
+            parent_td + first.block.difficulty != first.total_difficulty
+        {
+            eyre::bail!(
+                "era1 file {} does not extend the chain at block {}",
+                path.display(),
+                parent_number
+            );
+        }
+    }
+
+    Ok(Some(FileClient::from_blocks(file.blocks.into_iter().map(|block| block.block))))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use alloy_primitives::U256;
+    use reth_db_common::init::init_genesis;
+    use reth_era::{era1_file_name, Era1Block, Era1Writer};
     use reth_ethereum_cli::chainspec::{EthereumChainSpecParser, SUPPORTED_CHAINS};
+    use reth_primitives::{Block, BlockBody, Header};
+    use reth_provider::{test_utils::create_test_provider_factory, BlockReader};
+
+    /// Writes the blocks to an Era1 file of the given epoch and returns its path.
+    fn write_era1_file(dir: &Path, epoch: u64, blocks: &[Era1Block]) -> PathBuf {
+        let mut writer = Era1Writer::new(Vec::new()).unwrap();
+        for block in blocks {
+            writer.append_block(block).unwrap();
+        }
+        let (bytes, accumulator) = writer.finish().unwrap();
+        let path = dir.join(era1_file_name("mainnet", epoch, accumulator));
+        std::fs::write(&path, bytes).unwrap();
+        path
+    }
+
+    /// Returns a block with the given number and parent, and one more total difficulty than
+    /// `parent_td`.
+    fn child_block(number: u64, parent_hash: B256, parent_td: U256) -> Era1Block {
+        let header =
+            Header { number, parent_hash, difficulty: U256::from(1), ..Default::default() };
+        let block = Block { header, body: BlockBody::default() }.seal_slow();
+        Era1Block::new(block, &[], parent_td + U256::from(1))
+    }
 
     #[test]
     fn parse_common_import_command_chain_args() {
@@ -245,4 +418,36 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn era1_import_must_extend_the_chain() {
+        let factory = create_test_provider_factory();
+        let genesis_hash = init_genesis(&factory).unwrap();
+        let genesis = factory.block(0.into()).unwrap().unwrap().seal_slow();
+        let genesis_td = factory.header_td_by_number(0).unwrap().unwrap();
+        let genesis = Era1Block::new(genesis, &[], genesis_td);
+        let dir = tempfile::tempdir().unwrap();
+
+        // blocks that extend the genesis block are imported
+        let block = child_block(1, genesis_hash, genesis_td);
+        let path = write_era1_file(dir.path(), 0, &[genesis.clone(), block]);
+        assert!(read_era1_file(&factory, &path).unwrap().is_some());
+
+        // files whose blocks are all imported are skipped
+        let path = write_era1_file(dir.path(), 0, &[genesis]);
+        assert!(read_era1_file(&factory, &path).unwrap().is_none());
+
+        // a chain with a different genesis block is rejected
+        let other_genesis = child_block(0, B256::ZERO, U256::ZERO);
+        let block = child_block(1, other_genesis.block.hash(), other_genesis.total_difficulty);
+        let path = write_era1_file(dir.path(), 0, &[other_genesis, block]);
+        let err = read_era1_file(&factory, &path).unwrap_err();
+        assert!(err.to_string().contains("does not match the genesis block"), "{err}");
+
+        // files must not leave a gap to the last block in the database
+        let block = child_block(8192, B256::ZERO, U256::ZERO);
+        let path = write_era1_file(dir.path(), 1, &[block]);
+        let err = read_era1_file(&factory, &path).unwrap_err();
+        assert!(err.to_string().contains("starts at block 8192"), "{err}");
+    }
 }
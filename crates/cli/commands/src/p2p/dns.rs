@@ -0,0 +1,97 @@
+//! DNS publishing subcommand of P2P Debugging tool.
+
+use std::{
+    path::PathBuf,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use clap::Parser;
+use reth_cli_util::get_secret_key;
+use reth_dns_discovery::{publish::DEFAULT_ZONE_TTL, tree::LinkEntry, DnsTree};
+use reth_network::{dns_publish::DnsNodeListFilter, NetworkHandle};
+use reth_network_types::DEFAULT_REPUTATION;
+use reth_primitives::ForkFilter;
+use tracing::info;
+
+/// Builds a signed EIP-1459 node list of the discovered peers and writes it as DNS zone file.
+///
+/// Discv5 is always enabled, since only the signed records of peers discovered via discv5 can be
+/// published.
+#[derive(Parser, Debug)]
+pub struct Command {
+    /// The domain the node list is published at.
+    #[arg(long, value_name = "DOMAIN")]
+    domain: String,
+
+    /// Path to the secret key the tree root is signed with.
+    #[arg(long, value_name = "PATH")]
+    key: PathBuf,
+
+    /// Path of the zone file to write. Prints the zone file to stdout if not set.
+    #[arg(long, value_name = "FILE")]
+    output: Option<PathBuf>,
+
+    /// Sequence number of the tree. Defaults to the current unix timestamp.
+    #[arg(long)]
+    seq: Option<u64>,
+
+    /// Seconds to discover peers before the node list is built.
+    #[arg(long, value_name = "SECONDS", default_value_t = 60)]
+    wait: u64,
+
+    /// Minimum reputation of a peer to be published.
+    #[arg(long, default_value_t = DEFAULT_REPUTATION, allow_hyphen_values = true)]
+    min_reputation: i32,
+
+    /// Maximum number of peers to publish.
+    #[arg(long)]
+    max_nodes: Option<usize>,
+
+    /// Link to another node list, in the form `enrtree://<key>@<domain>`. Can be set multiple
+    /// times.
+    #[arg(long = "link", value_name = "ENRTREE")]
+    links: Vec<LinkEntry>,
+
+    /// TTL in seconds of the records in the zone file.
+    #[arg(long, default_value_t = DEFAULT_ZONE_TTL)]
+    ttl: u32,
+}
+
+impl Command {
+    /// Execute `p2p dns-publish` command.
+    pub async fn execute(
+        self,
+        network: NetworkHandle,
+        fork_filter: ForkFilter,
+    ) -> eyre::Result<()> {
+        let key = get_secret_key(&self.key)?;
+        let sequence_number = match self.seq {
+            Some(seq) => seq,
+            None => SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs(),
+        };
+
+        info!(target: "reth::cli", wait = self.wait, "Discovering peers");
+        tokio::time::sleep(Duration::from_secs(self.wait)).await;
+
+        let filter = DnsNodeListFilter {
+            fork_filter: Some(fork_filter),
+            min_reputation: self.min_reputation,
+            max_nodes: self.max_nodes,
+        };
+        let enrs = network.dns_node_list(&filter).await;
+        info!(target: "reth::cli", nodes = enrs.len(), "Building node list");
+
+        let tree = DnsTree::new(enrs, self.links, sequence_number, &key);
+        let zone = tree.to_zone_file(&self.domain, self.ttl);
+
+        match self.output {
+            Some(path) => {
+                reth_fs_util::write(&path, zone)?;
+                info!(target: "reth::cli", path = %path.display(), link = %tree.link(self.domain), "Wrote zone file");
+            }
+            None => print!("{zone}"),
+        }
+
+        Ok(())
+    }
+}
@@ -16,6 +16,8 @@ use reth_node_core::{
     utils::get_single_header,
 };
 
+mod dns;
+mod replay;
 mod rlpx;
 
 /// `reth p2p` command
@@ -71,11 +73,20 @@ pub enum Subcommands {
     },
     // RLPx utilities
     Rlpx(rlpx::Command),
+    /// Publish the discovered peers as EIP-1459 DNS node list
+    DnsPublish(dns::Command),
+    /// Replay a recorded peer session against a local node
+    Replay(replay::Command),
 }
 
 impl<C: ChainSpecParser<ChainSpec: EthChainSpec + EthereumHardforks>> Command<C> {
     /// Execute `p2p` command
-    pub async fn execute(self) -> eyre::Result<()> {
+    pub async fn execute(mut self) -> eyre::Result<()> {
+        // the replay runs against its own isolated node
+        if let Subcommands::Replay(command) = self.command {
+            return command.execute(self.chain).await
+        }
+
         let data_dir = self.datadir.clone().resolve_datadir(self.chain.chain());
         let config_path = self.config.clone().unwrap_or_else(|| data_dir.config());
 
@@ -90,6 +101,11 @@ impl<C: ChainSpecParser<ChainSpec: EthChainSpec + EthereumHardforks>> Command<C>
 
         config.peers.trusted_nodes_only = self.network.trusted_only;
 
+        // only peers discovered via discv5 can be published
+        if matches!(self.command, Subcommands::DnsPublish(_)) {
+            self.network.discovery.enable_discv5_discovery = true;
+        }
+
         let default_secret_key_path = data_dir.p2p_secret();
         let secret_key_path =
             self.network.p2p_secret_key.clone().unwrap_or(default_secret_key_path);
@@ -105,9 +121,9 @@ impl<C: ChainSpecParser<ChainSpec: EthChainSpec + EthereumHardforks>> Command<C>
             .apply(|builder| {
                 self.network.discovery.apply_to_builder(builder, rlpx_socket, boot_nodes)
             })
-            .build_with_noop_provider(self.chain)
-            .manager()
-            .await?;
+            .build_with_noop_provider(self.chain);
+        let fork_filter = net.fork_filter.clone();
+        let net = net.manager().await?;
         let network = net.handle().clone();
         tokio::task::spawn(net);
 
@@ -158,6 +174,10 @@ impl<C: ChainSpecParser<ChainSpec: EthChainSpec + EthereumHardforks>> Command<C>
             Subcommands::Rlpx(command) => {
                 command.execute().await?;
             }
+            Subcommands::DnsPublish(command) => {
+                command.execute(network, fork_filter).await?;
+            }
+            Subcommands::Replay(_) => unreachable!("handled above"),
         }
 
         Ok(())
@@ -0,0 +1,124 @@
+//! Session replay subcommand of P2P Debugging tool.
+
+use std::{
+    net::{Ipv4Addr, SocketAddr},
+    path::PathBuf,
+    sync::Arc,
+    time::Duration,
+};
+
+use clap::Parser;
+use futures::StreamExt;
+use reth_chainspec::{EthChainSpec, Hardforks};
+use reth_network::{
+    replay::{replay_session, ReplayConfig, ReplayTarget},
+    NetworkConfigBuilder, NetworkEventListenerProvider, NetworkInfo, SessionRecordReader,
+};
+use reth_network_peers::PeerId;
+
+/// Replays a session recorded with `--record-sessions` against a local, isolated node.
+///
+/// The node runs without discovery and only connects to the replaying peer, which sends the
+/// messages that were received from the recorded peer. The node has no database, so requests of
+/// the peer are not answered. The node's network events and the messages it sent are printed.
+#[derive(Parser, Debug)]
+pub struct Command {
+    /// Path to the session recording.
+    #[arg(value_name = "FILE")]
+    file: PathBuf,
+
+    /// The recorded peer to replay. Defaults to the first peer in the recording.
+    #[arg(long, value_name = "PEER_ID")]
+    peer: Option<PeerId>,
+
+    /// Preserve the original delays between the messages.
+    #[arg(long)]
+    paced: bool,
+
+    /// Seconds to wait for further messages from the node after the last message was replayed.
+    #[arg(long, value_name = "SECONDS", default_value_t = 5)]
+    linger: u64,
+
+    /// List the recorded peers and the number of messages received from each, then exit.
+    #[arg(long)]
+    list: bool,
+}
+
+impl Command {
+    /// Execute `p2p replay` command.
+    pub async fn execute<ChainSpec>(self, chain: Arc<ChainSpec>) -> eyre::Result<()>
+    where
+        ChainSpec: EthChainSpec + Hardforks + 'static,
+    {
+        let mut peers = Vec::<(PeerId, usize)>::new();
+        let mut messages = Vec::new();
+        for msg in SessionRecordReader::open(&self.file)? {
+            let msg = msg?;
+            if !msg.direction.is_received() {
+                continue
+            }
+            match peers.iter_mut().find(|(peer_id, _)| *peer_id == msg.peer_id) {
+                Some((_, count)) => *count += 1,
+                None => peers.push((msg.peer_id, 1)),
+            }
+            if self.peer.or_else(|| peers.first().map(|(peer_id, _)| *peer_id)) == Some(msg.peer_id)
+            {
+                messages.push(msg);
+            }
+        }
+
+        if self.list {
+            for (peer_id, count) in peers {
+                println!("{peer_id}: {count} messages");
+            }
+            return Ok(())
+        }
+
+        let Some(peer_id) = messages.first().map(|msg| msg.peer_id) else {
+            eyre::bail!("No messages received from the peer in {}", self.file.display())
+        };
+        println!("Replaying {} messages received from {peer_id}", messages.len());
+
+        let config = NetworkConfigBuilder::with_rng_secret_key()
+            .listener_addr(SocketAddr::from((Ipv4Addr::LOCALHOST, 0)))
+            .disable_discovery()
+            .build_with_noop_provider(chain);
+        let target_status = config.status;
+        let target_block_range = config.block_range;
+        let fork_filter = config.fork_filter.clone();
+        let network = config.manager().await?;
+        let handle = network.handle().clone();
+        tokio::task::spawn(network);
+
+        let mut events = handle.event_listener();
+        tokio::task::spawn(async move {
+            while let Some(event) = events.next().await {
+                println!("Network event: {event:?}");
+            }
+        });
+
+        let target = ReplayTarget {
+            peer_id: *handle.peer_id(),
+            addr: handle.local_addr(),
+            status: target_status,
+            block_range: target_block_range,
+            fork_filter,
+        };
+        let config = ReplayConfig { paced: self.paced, linger: Duration::from_secs(self.linger) };
+        let outcome = replay_session(target, messages, config).await?;
+
+        for msg in &outcome.received {
+            println!("Received from node: {msg:?}");
+        }
+        println!(
+            "Replayed {} messages, received {} messages",
+            outcome.sent,
+            outcome.received.len()
+        );
+        if let Some(err) = outcome.error {
+            println!("Session terminated: {err}");
+        }
+
+        Ok(())
+    }
+}
@@ -37,15 +37,19 @@ impl<C: ChainSpecParser<ChainSpec: EthChainSpec + EthereumHardforks>> Command<C>
 
         let tool = DbTool::new(provider_factory)?;
 
-        let static_file_segment = match self.stage {
-            StageEnum::Headers => Some(StaticFileSegment::Headers),
-            StageEnum::Bodies => Some(StaticFileSegment::Transactions),
-            StageEnum::Execution => Some(StaticFileSegment::Receipts),
-            _ => None,
+        let static_file_segments: &[StaticFileSegment] = match self.stage {
+            StageEnum::Headers => &[StaticFileSegment::Headers],
+            StageEnum::Bodies => &[StaticFileSegment::Transactions],
+            StageEnum::Execution => &[
+                StaticFileSegment::Receipts,
+                StaticFileSegment::AccountChangeSets,
+                StaticFileSegment::StorageChangeSets,
+            ],
+            _ => &[],
         };
 
         // Delete static file segment data before inserting the genesis header below
-        if let Some(static_file_segment) = static_file_segment {
+        for &static_file_segment in static_file_segments {
             let static_file_provider = tool.provider_factory.static_file_provider();
             let static_files = iter_static_files(static_file_provider.directory())?;
             if let Some(segment_static_files) = static_files.get(&static_file_segment) {
@@ -102,6 +106,8 @@ impl<C: ChainSpecParser<ChainSpec: EthChainSpec + EthereumHardforks>> Command<C>
 
                 reset_prune_checkpoint(tx, PruneSegment::Receipts)?;
                 reset_prune_checkpoint(tx, PruneSegment::ContractLogs)?;
+                reset_prune_checkpoint(tx, PruneSegment::AccountChangeSets)?;
+                reset_prune_checkpoint(tx, PruneSegment::StorageChangeSets)?;
                 reset_stage_checkpoint(tx, StageId::Execution)?;
 
                 let alloc = &self.env.chain.genesis().alloc;
@@ -145,6 +151,16 @@ impl<C: ChainSpecParser<ChainSpec: EthChainSpec + EthereumHardforks>> Command<C>
 
                 insert_genesis_history(&provider_rw.0, self.env.chain.genesis().alloc.iter())?;
             }
+            StageEnum::TransactionAppearances => {
+                tx.clear::<tables::TransactionAppearances>()?;
+                reset_prune_checkpoint(tx, PruneSegment::TransactionAppearances)?;
+                // the index is only maintained while the stage has a checkpoint, it's rebuilt by
+                // the pipeline if it's enabled
+                tx.delete::<tables::StageCheckpoints>(
+                    StageId::IndexTransactionAppearances.to_string(),
+                    None,
+                )?;
+            }
             StageEnum::TxLookup => {
                 tx.clear::<tables::TransactionHashNumbers>()?;
                 reset_prune_checkpoint(tx, PruneSegment::TransactionLookup)?;
@@ -40,8 +40,8 @@ use reth_provider::{
 use reth_stages::{
     stages::{
         AccountHashingStage, BodyStage, ExecutionStage, HeaderStage, IndexAccountHistoryStage,
-        IndexStorageHistoryStage, MerkleStage, SenderRecoveryStage, StorageHashingStage,
-        TransactionLookupStage,
+        IndexStorageHistoryStage, IndexTransactionAppearancesStage, MerkleStage,
+        SenderRecoveryStage, StorageHashingStage, TransactionLookupStage,
     },
     ExecInput, ExecOutput, ExecutionStageThresholds, Stage, StageError, StageExt, UnwindInput,
     UnwindOutput,
@@ -303,6 +303,12 @@ impl<C: ChainSpecParser<ChainSpec: EthChainSpec + EthereumHardforks>> Command<C>
                     )),
                     None,
                 ),
+                StageEnum::TransactionAppearances => (
+                    Box::new(IndexTransactionAppearancesStage::new(
+                        config.stages.index_transaction_appearances,
+                    )),
+                    None,
+                ),
                 _ => return Ok(()),
             };
         if let Some(unwind_stage) = &unwind_stage {
@@ -0,0 +1,33 @@
+//! `reth static-file` command.
+
+use clap::{Parser, Subcommand};
+use reth_chainspec::{EthChainSpec, EthereumHardforks};
+use reth_cli::chainspec::ChainSpecParser;
+use reth_node_builder::NodeTypesWithEngine;
+
+mod recompress;
+
+/// `reth static-file` command
+#[derive(Debug, Parser)]
+pub struct Command<C: ChainSpecParser> {
+    #[command(subcommand)]
+    command: Subcommands<C>,
+}
+
+/// `reth static-file` subcommands
+#[derive(Subcommand, Debug)]
+pub enum Subcommands<C: ChainSpecParser> {
+    /// Rewrites existing static files with the compression set in the configuration file.
+    Recompress(recompress::Command<C>),
+}
+
+impl<C: ChainSpecParser<ChainSpec: EthChainSpec + EthereumHardforks>> Command<C> {
+    /// Execute `static-file` command
+    pub async fn execute<N: NodeTypesWithEngine<ChainSpec = C::ChainSpec>>(
+        self,
+    ) -> eyre::Result<()> {
+        match self.command {
+            Subcommands::Recompress(command) => command.execute::<N>().await,
+        }
+    }
+}
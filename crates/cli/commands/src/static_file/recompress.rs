@@ -0,0 +1,66 @@
+use crate::common::{AccessRights, Environment, EnvironmentArgs};
+use clap::Parser;
+use reth_chainspec::{EthChainSpec, EthereumHardforks};
+use reth_cli::chainspec::ChainSpecParser;
+use reth_db::static_file::iter_static_files;
+use reth_node_builder::NodeTypesWithEngine;
+use reth_provider::StaticFileProviderFactory;
+use reth_static_file_types::StaticFileSegment;
+use std::time::Instant;
+use tracing::info;
+
+/// `reth static-file recompress` command
+///
+/// Compression of each segment is configured in the `[static_files]` section of the configuration
+/// file. Static files created afterwards already use it, so this is only required to apply it to
+/// existing ones.
+#[derive(Debug, Parser)]
+pub struct Command<C: ChainSpecParser> {
+    #[command(flatten)]
+    env: EnvironmentArgs<C>,
+
+    /// Static file segments to recompress. If not set, all segments are recompressed.
+    #[arg(long = "segment", value_enum)]
+    segments: Vec<StaticFileSegment>,
+}
+
+impl<C: ChainSpecParser<ChainSpec: EthChainSpec + EthereumHardforks>> Command<C> {
+    /// Execute `static-file recompress` command
+    pub async fn execute<N: NodeTypesWithEngine<ChainSpec = C::ChainSpec>>(
+        self,
+    ) -> eyre::Result<()> {
+        let Environment { provider_factory, .. } = self.env.init::<N>(AccessRights::RW)?;
+        let static_file_provider = provider_factory.static_file_provider();
+
+        let mut static_files = iter_static_files(static_file_provider.directory())?
+            .into_iter()
+            .filter(|(segment, _)| self.segments.is_empty() || self.segments.contains(segment))
+            .collect::<Vec<_>>();
+        static_files.sort_by_key(|(segment, _)| *segment);
+
+        for (segment, ranges) in static_files {
+            let config = static_file_provider.segment_config(segment);
+            let (mut total_size_before, mut total_size_after) = (0, 0);
+
+            for (block_range, _) in ranges {
+                let data_path = static_file_provider.directory().join(
+                    segment.filename(&static_file_provider.find_fixed_range(block_range.start())),
+                );
+                let size_before = reth_fs_util::metadata(&data_path)?.len();
+
+                let start = Instant::now();
+                static_file_provider.recompress_jar(segment, block_range.start())?;
+
+                let size_after = reth_fs_util::metadata(&data_path)?.len();
+                info!(target: "reth::cli", ?segment, ?block_range, size_before, size_after, elapsed = ?start.elapsed(), "Recompressed static file");
+
+                total_size_before += size_before;
+                total_size_after += size_after;
+            }
+
+            info!(target: "reth::cli", ?segment, ?config, total_size_before, total_size_after, "Recompressed segment");
+        }
+
+        Ok(())
+    }
+}
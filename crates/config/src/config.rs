@@ -4,6 +4,7 @@ use eyre::eyre;
 use reth_network_types::{PeersConfig, SessionsConfig};
 use reth_prune_types::PruneModes;
 use reth_stages_types::ExecutionStageThresholds;
+use reth_static_file_types::{SegmentConfig, StaticFileSegment};
 use serde::{Deserialize, Deserializer, Serialize};
 use std::{
     ffi::OsStr,
@@ -31,6 +32,8 @@ pub struct Config {
     pub peers: PeersConfig,
     /// Configuration for peer sessions.
     pub sessions: SessionsConfig,
+    /// Configuration for static files.
+    pub static_files: StaticFilesConfig,
 }
 
 impl Config {
@@ -120,6 +123,8 @@ pub struct StageConfig {
     pub index_account_history: IndexHistoryConfig,
     /// Index Storage History stage configuration.
     pub index_storage_history: IndexHistoryConfig,
+    /// Index Transaction Appearances stage configuration.
+    pub index_transaction_appearances: IndexTransactionAppearancesConfig,
     /// Common ETL related configuration.
     pub etl: EtlConfig,
 }
@@ -373,6 +378,23 @@ impl Default for IndexHistoryConfig {
     }
 }
 
+/// Transaction appearances indexing stage configuration.
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default)]
+pub struct IndexTransactionAppearancesConfig {
+    /// Whether the index is maintained. It's only used by the `ots_searchTransactions*` RPC
+    /// methods.
+    pub enabled: bool,
+    /// The maximum number of blocks to process before committing progress to the database.
+    pub commit_threshold: u64,
+}
+
+impl Default for IndexTransactionAppearancesConfig {
+    fn default() -> Self {
+        Self { enabled: false, commit_threshold: 100_000 }
+    }
+}
+
 /// Pruning configuration.
 #[derive(Debug, Clone, Deserialize, PartialEq, Eq, Serialize)]
 #[serde(default)]
@@ -409,6 +431,7 @@ impl PruneConfig {
                     receipts,
                     account_history,
                     storage_history,
+                    history,
                     receipts_log_filter,
                 },
         } = other;
@@ -424,6 +447,7 @@ impl PruneConfig {
         self.segments.receipts = self.segments.receipts.or(receipts);
         self.segments.account_history = self.segments.account_history.or(account_history);
         self.segments.storage_history = self.segments.storage_history.or(storage_history);
+        self.segments.history = self.segments.history.or(history);
 
         if self.segments.receipts_log_filter.0.is_empty() && !receipts_log_filter.0.is_empty() {
             self.segments.receipts_log_filter = receipts_log_filter;
@@ -431,6 +455,58 @@ impl PruneConfig {
     }
 }
 
+/// Static files configuration.
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default)]
+pub struct StaticFilesConfig {
+    /// Configuration of the headers segment.
+    pub headers: SegmentConfig,
+    /// Configuration of the transactions segment.
+    pub transactions: SegmentConfig,
+    /// Configuration of the receipts segment.
+    pub receipts: SegmentConfig,
+    /// Configuration of the account changesets segment.
+    pub account_changesets: SegmentConfig,
+    /// Configuration of the storage changesets segment.
+    pub storage_changesets: SegmentConfig,
+}
+
+impl Default for StaticFilesConfig {
+    fn default() -> Self {
+        Self {
+            headers: StaticFileSegment::Headers.config(),
+            transactions: StaticFileSegment::Transactions.config(),
+            receipts: StaticFileSegment::Receipts.config(),
+            account_changesets: StaticFileSegment::AccountChangeSets.config(),
+            storage_changesets: StaticFileSegment::StorageChangeSets.config(),
+        }
+    }
+}
+
+impl StaticFilesConfig {
+    /// Returns the configuration of the given segment.
+    pub const fn segment(&self, segment: StaticFileSegment) -> SegmentConfig {
+        match segment {
+            StaticFileSegment::Headers => self.headers,
+            StaticFileSegment::Transactions => self.transactions,
+            StaticFileSegment::Receipts => self.receipts,
+            StaticFileSegment::AccountChangeSets => self.account_changesets,
+            StaticFileSegment::StorageChangeSets => self.storage_changesets,
+        }
+    }
+
+    /// Returns the configuration of every segment.
+    pub const fn segments(&self) -> [(StaticFileSegment, SegmentConfig); 5] {
+        [
+            (StaticFileSegment::Headers, self.headers),
+            (StaticFileSegment::Transactions, self.transactions),
+            (StaticFileSegment::Receipts, self.receipts),
+            (StaticFileSegment::AccountChangeSets, self.account_changesets),
+            (StaticFileSegment::StorageChangeSets, self.storage_changesets),
+        ]
+    }
+}
+
 /// Helper type to support older versions of Duration deserialization.
 fn deserialize_duration<'de, D>(deserializer: D) -> Result<Option<Duration>, D::Error>
 where
@@ -456,6 +532,7 @@ mod tests {
     use alloy_primitives::Address;
     use reth_network_peers::TrustedPeer;
     use reth_prune_types::{PruneMode, PruneModes, ReceiptsLogPruneConfig};
+    use reth_static_file_types::{Compression, SegmentConfig, StaticFileSegment};
     use std::{collections::BTreeMap, path::Path, str::FromStr, time::Duration};
 
     fn with_tempdir(filename: &str, proc: fn(&std::path::Path)) {
@@ -943,6 +1020,7 @@ receipts = 'full'
                 receipts: Some(PruneMode::Distance(1000)),
                 account_history: None,
                 storage_history: Some(PruneMode::Before(5000)),
+                history: None,
                 receipts_log_filter: ReceiptsLogPruneConfig(BTreeMap::from([(
                     Address::random(),
                     PruneMode::Full,
@@ -958,6 +1036,7 @@ receipts = 'full'
                 receipts: Some(PruneMode::Full),
                 account_history: Some(PruneMode::Distance(2000)),
                 storage_history: Some(PruneMode::Distance(3000)),
+                history: Some(PruneMode::Before(15537394)),
                 receipts_log_filter: ReceiptsLogPruneConfig(BTreeMap::from([
                     (Address::random(), PruneMode::Distance(1000)),
                     (Address::random(), PruneMode::Before(2000)),
@@ -976,6 +1055,7 @@ receipts = 'full'
         assert_eq!(config1.segments.receipts, Some(PruneMode::Distance(1000)));
         assert_eq!(config1.segments.account_history, Some(PruneMode::Distance(2000)));
         assert_eq!(config1.segments.storage_history, Some(PruneMode::Before(5000)));
+        assert_eq!(config1.segments.history, Some(PruneMode::Before(15537394)));
         assert_eq!(config1.segments.receipts_log_filter, original_filter);
     }
 
@@ -1019,4 +1099,30 @@ connect_trusted_nodes_only = true
             assert!(conf.peers.trusted_nodes.contains(&node));
         }
     }
+
+    #[test]
+    fn test_static_files_config() {
+        let reth_toml = r#"
+    [static_files.headers]
+    compression = "zstd-dict"
+    zstd_level = 19
+
+    [static_files.receipts]
+    compression = "zstd"
+    "#;
+
+        let conf: Config = toml::from_str(reth_toml).unwrap();
+        assert_eq!(
+            conf.static_files.segment(StaticFileSegment::Headers),
+            SegmentConfig::new(Compression::ZstdWithDictionary).with_zstd_level(19)
+        );
+        assert_eq!(
+            conf.static_files.segment(StaticFileSegment::Receipts),
+            SegmentConfig::new(Compression::Zstd)
+        );
+        assert_eq!(
+            conf.static_files.segment(StaticFileSegment::Transactions),
+            StaticFileSegment::Transactions.config()
+        );
+    }
 }
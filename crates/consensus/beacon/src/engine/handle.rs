@@ -4,12 +4,13 @@ use crate::{
     engine::message::OnForkChoiceUpdated, BeaconConsensusEngineEvent, BeaconEngineMessage,
     BeaconForkChoiceUpdateError, BeaconOnNewPayloadError,
 };
+use alloy_primitives::BlockNumber;
 use alloy_rpc_types_engine::{
     CancunPayloadFields, ExecutionPayload, ForkchoiceState, ForkchoiceUpdated, PayloadStatus,
 };
 use futures::TryFutureExt;
 use reth_engine_primitives::EngineTypes;
-use reth_errors::RethResult;
+use reth_errors::{RethError, RethResult};
 use reth_tokio_util::{EventSender, EventStream};
 use tokio::sync::{mpsc::UnboundedSender, oneshot};
 
@@ -94,6 +95,14 @@ where
         let _ = self.to_engine.send(BeaconEngineMessage::TransitionConfigurationExchanged);
     }
 
+    /// Sends a message to the beacon consensus engine to rewind the canonical chain to the given
+    /// block and waits until all blocks above the new head were unwound.
+    pub async fn set_head(&self, block_number: BlockNumber) -> RethResult<()> {
+        let (tx, rx) = oneshot::channel();
+        let _ = self.to_engine.send(BeaconEngineMessage::SetHead { block_number, tx });
+        rx.await.map_err(|_| RethError::msg("beacon consensus engine unavailable"))?
+    }
+
     /// Creates a new [`BeaconConsensusEngineEvent`] listener stream.
     pub fn event_listener(&self) -> EventStream<BeaconConsensusEngineEvent> {
         self.event_sender.new_listener()
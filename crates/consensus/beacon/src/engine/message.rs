@@ -1,4 +1,5 @@
 use crate::engine::{error::BeaconOnNewPayloadError, forkchoice::ForkchoiceStatus};
+use alloy_primitives::BlockNumber;
 use alloy_rpc_types_engine::{
     CancunPayloadFields, ExecutionPayload, ForkChoiceUpdateResult, ForkchoiceState,
     ForkchoiceUpdateError, ForkchoiceUpdated, PayloadId, PayloadStatus, PayloadStatusEnum,
@@ -160,6 +161,13 @@ pub enum BeaconEngineMessage<Engine: EngineTypes> {
     },
     /// Message with exchanged transition configuration.
     TransitionConfigurationExchanged,
+    /// Message to rewind the canonical chain to the given block, dropping all blocks above it.
+    SetHead {
+        /// The number of the new canonical head.
+        block_number: BlockNumber,
+        /// The sender for returning whether the rewind was started.
+        tx: oneshot::Sender<RethResult<()>>,
+    },
 }
 
 impl<Engine: EngineTypes> Display for BeaconEngineMessage<Engine> {
@@ -186,6 +194,7 @@ impl<Engine: EngineTypes> Display for BeaconEngineMessage<Engine> {
             Self::TransitionConfigurationExchanged => {
                 write!(f, "TransitionConfigurationExchanged")
             }
+            Self::SetHead { block_number, .. } => write!(f, "SetHead({block_number})"),
         }
     }
 }
@@ -226,6 +226,8 @@ where
     event_sender: EventSender<BeaconConsensusEngineEvent>,
     /// Consensus engine metrics.
     metrics: EngineMetrics,
+    /// The target and the response channel of an in-progress `SetHead` request.
+    pending_set_head: Option<(BlockNumber, oneshot::Sender<RethResult<()>>)>,
 }
 
 impl<N, BT, Client> BeaconConsensusEngine<N, BT, Client>
@@ -325,6 +327,7 @@ where
             hooks: EngineHooksController::new(hooks),
             event_sender,
             metrics: EngineMetrics::default(),
+            pending_set_head: None,
         };
 
         let maybe_pipeline_target = match target {
@@ -386,6 +389,40 @@ where
         Ok(None)
     }
 
+    /// Rewinds the canonical chain to the given block by unwinding all blocks above it with the
+    /// pipeline.
+    ///
+    /// The response is sent once the pipeline run finished, see [`Self::on_pipeline_outcome`].
+    fn on_set_head(&mut self, block_number: BlockNumber, tx: oneshot::Sender<RethResult<()>>) {
+        match self.validate_set_head(block_number) {
+            Ok(()) => {
+                info!(target: "consensus::engine", block_number, "Rewinding canonical chain");
+                self.pending_set_head = Some((block_number, tx));
+                self.sync.set_pipeline_sync_target(PipelineTarget::Unwind(block_number));
+            }
+            Err(err) => {
+                let _ = tx.send(Err(err));
+            }
+        }
+    }
+
+    /// Returns an error if the block is not below the current canonical head or the pipeline is
+    /// already running.
+    fn validate_set_head(&self, block_number: BlockNumber) -> RethResult<()> {
+        if self.sync.is_pipeline_active() || self.pending_set_head.is_some() {
+            return Err(RethError::msg("cannot set head while the pipeline is running"))
+        }
+
+        let best_block_number = self.blockchain.best_block_number()?;
+        if block_number >= best_block_number {
+            return Err(RethError::msg(format!(
+                "block {block_number} is not below the canonical head {best_block_number}"
+            )))
+        }
+
+        Ok(())
+    }
+
     /// Process the result of attempting to make forkchoice state head hash canonical.
     ///
     /// # Returns
@@ -1446,11 +1483,31 @@ where
     /// Updates the internal sync state depending on the pipeline configuration,
     /// the outcome of the pipeline run and the last observed forkchoice state.
     fn on_pipeline_outcome(&mut self, ctrl: ControlFlow) -> RethResult<()> {
+        let pending_set_head = self.pending_set_head.take();
+
         // Pipeline unwound, memorize the invalid block and wait for CL for next sync target.
         if let ControlFlow::Unwind { bad_block, .. } = ctrl {
             warn!(target: "consensus::engine", invalid_hash=?bad_block.hash(), invalid_number=?bad_block.number, "Bad block detected in unwind");
             // update the `invalid_headers` cache with the new invalid header
             self.invalid_headers.insert(*bad_block);
+            if let Some((_, tx)) = pending_set_head {
+                let _ = tx.send(Err(RethError::msg("rewind aborted, bad block detected")));
+            }
+            return Ok(())
+        }
+
+        if let Some((target, tx)) = pending_set_head {
+            // the chain was rewound on request, drop the tree blocks that were built on top of the
+            // old head and wait for the CL to provide the next sync target
+            let res = match ctrl.block_number() {
+                Some(number) if number == target => {
+                    self.set_canonical_head(number)?;
+                    self.blockchain.update_block_hashes_and_clear_buffered()?;
+                    Ok(())
+                }
+                number => Err(RethError::msg(format!("rewind stopped at block {number:?}"))),
+            };
+            let _ = tx.send(res);
             return Ok(())
         }
 
@@ -1880,6 +1937,9 @@ where
                         BeaconEngineMessage::TransitionConfigurationExchanged => {
                             this.blockchain.on_transition_configuration_exchanged();
                         }
+                        BeaconEngineMessage::SetHead { block_number, tx } => {
+                            this.on_set_head(block_number, tx);
+                        }
                     }
                     continue
                 }
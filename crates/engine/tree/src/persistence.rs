@@ -2,8 +2,9 @@ use crate::metrics::PersistenceMetrics;
 use alloy_eips::BlockNumHash;
 use reth_chain_state::ExecutedBlock;
 use reth_errors::ProviderError;
+use reth_primitives::SealedBlock;
 use reth_provider::{
-    providers::ProviderNodeTypes, writer::UnifiedStorageWriter, BlockHashReader,
+    providers::ProviderNodeTypes, writer::UnifiedStorageWriter, BadBlockWriter, BlockHashReader,
     ChainStateBlockWriter, DatabaseProviderFactory, ProviderFactory, StaticFileProviderFactory,
 };
 use reth_prune::{PrunerError, PrunerOutput, PrunerWithFactory};
@@ -102,6 +103,11 @@ impl<N: ProviderNodeTypes> PersistenceService<N> {
                     provider.save_safe_block_number(safe_block)?;
                     provider.commit()?;
                 }
+                PersistenceAction::SaveBadBlock(block, error) => {
+                    let provider = self.provider.database_provider_rw()?;
+                    provider.save_bad_block(block, error)?;
+                    provider.commit()?;
+                }
             }
         }
         Ok(())
@@ -184,6 +190,9 @@ pub enum PersistenceAction {
 
     /// Update the persisted safe block on disk
     SaveSafeBlock(u64),
+
+    /// Record a block that was rejected during validation, together with the validation error
+    SaveBadBlock(SealedBlock, String),
 }
 
 /// A handle to the persistence service
@@ -267,6 +276,16 @@ impl PersistenceHandle {
         self.send_action(PersistenceAction::SaveSafeBlock(safe_block))
     }
 
+    /// Persists a block that was rejected during validation on disk, together with the error it
+    /// was rejected with.
+    pub fn save_bad_block(
+        &self,
+        block: SealedBlock,
+        error: String,
+    ) -> Result<(), SendError<PersistenceAction>> {
+        self.send_action(PersistenceAction::SaveBadBlock(block, error))
+    }
+
     /// Tells the persistence service to remove blocks above a certain block number. The removed
     /// blocks are returned by the service.
     ///
@@ -27,7 +27,7 @@ use reth_chain_state::{
 use reth_chainspec::EthereumHardforks;
 use reth_consensus::{Consensus, PostExecutionInput};
 use reth_engine_primitives::EngineTypes;
-use reth_errors::{ConsensusError, ProviderResult};
+use reth_errors::{ConsensusError, ProviderResult, RethError, RethResult};
 use reth_evm::execute::BlockExecutorProvider;
 use reth_payload_builder::PayloadBuilderHandle;
 use reth_payload_primitives::{PayloadAttributes, PayloadBuilder, PayloadBuilderAttributes};
@@ -41,7 +41,7 @@ use reth_provider::{
     TransactionVariant,
 };
 use reth_revm::database::StateProviderDatabase;
-use reth_stages_api::ControlFlow;
+use reth_stages_api::{ControlFlow, PipelineTarget};
 use reth_trie::{updates::TrieUpdates, HashedPostState, TrieInput};
 use reth_trie_parallel::parallel_root::{ParallelStateRoot, ParallelStateRootError};
 use std::{
@@ -211,6 +211,22 @@ impl TreeState {
         Some((executed, children))
     }
 
+    /// Removes all executed blocks above the given block number, including their trie updates.
+    pub(crate) fn remove_blocks_above(&mut self, block_number: BlockNumber) {
+        let hashes = self
+            .blocks_by_number
+            .range(block_number + 1..)
+            .flat_map(|(_, blocks)| blocks.iter().map(|b| b.block.hash()))
+            .collect::<Vec<_>>();
+
+        debug!(target: "engine::tree", ?block_number, count = hashes.len(), "Removing blocks above");
+        for hash in hashes {
+            self.remove_by_hash(hash);
+        }
+
+        self.persisted_trie_updates.retain(|_, (number, _)| *number <= block_number);
+    }
+
     /// Returns whether or not the hash is part of the canonical chain.
     pub(crate) fn is_canonical(&self, hash: B256) -> bool {
         let mut current_block = self.current_canonical_head.hash;
@@ -503,6 +519,8 @@ pub struct EngineApiTreeHandler<P, E, T: EngineTypes, Spec> {
     invalid_block_hook: Box<dyn InvalidBlockHook>,
     /// The engine API variant of this handler
     engine_kind: EngineApiKind,
+    /// The target and the response channel of an in-progress `SetHead` request.
+    pending_set_head: Option<(BlockNumber, oneshot::Sender<RethResult<()>>)>,
 }
 
 impl<P: Debug, E: Debug, T: EngineTypes + Debug, Spec: Debug> std::fmt::Debug
@@ -525,6 +543,7 @@ impl<P: Debug, E: Debug, T: EngineTypes + Debug, Spec: Debug> std::fmt::Debug
             .field("metrics", &self.metrics)
             .field("invalid_block_hook", &format!("{:p}", self.invalid_block_hook))
             .field("engine_kind", &self.engine_kind)
+            .field("pending_set_head", &self.pending_set_head.as_ref().map(|(num, _)| num))
             .finish()
     }
 }
@@ -573,6 +592,7 @@ where
             incoming_tx,
             invalid_block_hook: Box::new(NoopInvalidBlockHook),
             engine_kind,
+            pending_set_head: None,
         }
     }
 
@@ -1256,6 +1276,9 @@ where
                                 self.canonical_in_memory_state
                                     .on_transition_configuration_exchanged();
                             }
+                            BeaconEngineMessage::SetHead { block_number, tx } => {
+                                self.on_set_head(block_number, tx);
+                            }
                         }
                     }
                 }
@@ -1269,6 +1292,52 @@ where
         Ok(())
     }
 
+    /// Rewinds the canonical chain to the given block.
+    ///
+    /// The persisted blocks above the new head are unwound by a backfill run, afterwards the
+    /// in-memory blocks above the new head are discarded, see
+    /// [`Self::on_backfill_sync_finished`]. The response is sent once the rewind finished.
+    ///
+    /// Responds with an error if the block is not persisted yet or not below the current
+    /// canonical head.
+    fn on_set_head(&mut self, block_number: BlockNumber, tx: oneshot::Sender<RethResult<()>>) {
+        match self.validate_set_head(block_number) {
+            Ok(()) => {
+                info!(target: "engine::tree", block_number, "Rewinding canonical chain");
+                self.pending_set_head = Some((block_number, tx));
+                self.emit_event(EngineApiEvent::BackfillAction(BackfillAction::Start(
+                    PipelineTarget::Unwind(block_number),
+                )));
+            }
+            Err(err) => {
+                let _ = tx.send(Err(err));
+            }
+        }
+    }
+
+    /// Checks whether the canonical chain can be rewound to the given block.
+    fn validate_set_head(&self, block_number: BlockNumber) -> RethResult<()> {
+        if !self.backfill_sync_state.is_idle() || self.persistence_state.in_progress() {
+            return Err(RethError::msg("cannot set head while the database is being written to"))
+        }
+
+        let canonical_head = self.state.tree_state.canonical_block_number();
+        if block_number >= canonical_head {
+            return Err(RethError::msg(format!(
+                "block {block_number} is not below the canonical head {canonical_head}"
+            )))
+        }
+
+        let last_persisted = self.persistence_state.last_persisted_block.number;
+        if block_number > last_persisted {
+            return Err(RethError::msg(format!(
+                "block {block_number} is above the last persisted block {last_persisted}"
+            )))
+        }
+
+        Ok(())
+    }
+
     /// Invoked if the backfill sync has finished to target.
     ///
     /// At this point we consider the block synced to the backfill target.
@@ -1278,6 +1347,7 @@ where
     ///
     /// This will also do the necessary housekeeping of the tree state, this includes:
     ///  - removing all blocks below the backfill height
+    ///  - removing all blocks above the backfill height if the run was a `SetHead` rewind
     ///  - resetting the canonical in-memory state
     fn on_backfill_sync_finished(
         &mut self,
@@ -1285,17 +1355,26 @@ where
     ) -> Result<(), InsertBlockFatalError> {
         debug!(target: "engine::tree", "received backfill sync finished event");
         self.backfill_sync_state = BackfillSyncState::Idle;
+        let pending_set_head = self.pending_set_head.take();
 
         // Pipeline unwound, memorize the invalid block and wait for CL for next sync target.
         if let ControlFlow::Unwind { bad_block, .. } = ctrl {
             warn!(target: "engine::tree", invalid_hash=?bad_block.hash(), invalid_number=?bad_block.number, "Bad block detected in unwind");
             // update the `invalid_headers` cache with the new invalid header
             self.state.invalid_headers.insert(*bad_block);
+            if let Some((_, tx)) = pending_set_head {
+                let _ = tx.send(Err(RethError::msg("rewind aborted, bad block detected")));
+            }
             return Ok(())
         }
 
         // backfill height is the block number that the backfill finished at
-        let Some(backfill_height) = ctrl.block_number() else { return Ok(()) };
+        let Some(backfill_height) = ctrl.block_number() else {
+            if let Some((_, tx)) = pending_set_head {
+                let _ = tx.send(Err(RethError::msg("rewind made no progress")));
+            }
+            return Ok(())
+        };
 
         // state house keeping after backfill sync
         // remove all executed blocks below the backfill height
@@ -1313,6 +1392,10 @@ where
             self.persistence_state.last_persisted_block.hash,
             backfill_num_hash,
         );
+        if pending_set_head.is_some() {
+            // the blocks above the new head descend from the rewound chain
+            self.state.tree_state.remove_blocks_above(backfill_height);
+        }
         self.metrics.engine.executed_blocks.set(self.state.tree_state.block_count() as f64);
         self.metrics.tree.canonical_chain_height.set(backfill_height as f64);
 
@@ -1332,6 +1415,17 @@ where
             self.canonical_in_memory_state.set_canonical_head(new_head);
         }
 
+        if let Some((target, tx)) = pending_set_head {
+            // the chain was rewound on request, wait for the CL to provide the next sync target
+            let res = if backfill_height == target {
+                Ok(())
+            } else {
+                Err(RethError::msg(format!("rewind stopped at block {backfill_height}")))
+            };
+            let _ = tx.send(res);
+            return Ok(())
+        }
+
         // check if we need to run backfill again by comparing the most recent finalized height to
         // the backfill height
         let Some(sync_target_state) = self.state.forkchoice_state_tracker.sync_target_state()
@@ -2337,6 +2431,12 @@ where
             self.latest_valid_hash_for_invalid_payload(block.parent_hash)?
         };
 
+        // record the bad block on disk so it can be inspected later
+        if let Err(err) = self.persistence.save_bad_block(block.clone(), validation_err.to_string())
+        {
+            error!(target: "engine::tree", %err, "Failed to send bad block to persistence service");
+        }
+
         // keep track of the invalid header
         self.state.invalid_headers.insert(block.header);
         Ok(PayloadStatus::new(
@@ -3103,6 +3203,52 @@ mod tests {
         assert!(resp.payload_status.is_syncing());
     }
 
+    #[tokio::test]
+    async fn test_engine_set_head() {
+        let blocks: Vec<_> = TestBlockBuilder::default().get_executed_blocks(0..10).collect();
+        let mut test_harness = TestHarness::new(MAINNET.clone()).with_blocks(blocks.clone());
+        test_harness.tree.persistence_state.finish(blocks[5].block.hash(), blocks[5].block.number);
+
+        // only persisted blocks below the canonical head can become the new head
+        for block_number in [6, 9, 10] {
+            let (tx, rx) = oneshot::channel();
+            test_harness.tree.on_set_head(block_number, tx);
+            assert!(rx.await.unwrap().is_err());
+        }
+        assert!(test_harness.tree.pending_set_head.is_none());
+
+        let (tx, mut rx) = oneshot::channel();
+        test_harness
+            .tree
+            .on_engine_message(FromEngine::Request(
+                BeaconEngineMessage::SetHead { block_number: 3, tx }.into(),
+            ))
+            .unwrap();
+
+        let event = test_harness.from_tree_rx.recv().await.unwrap();
+        assert_matches!(
+            event,
+            EngineApiEvent::BackfillAction(BackfillAction::Start(PipelineTarget::Unwind(3)))
+        );
+        // the response is only sent once the rewind finished
+        assert_matches!(rx.try_recv(), Err(TryRecvError::Empty));
+
+        test_harness
+            .tree
+            .on_backfill_sync_finished(ControlFlow::Continue { block_number: 3 })
+            .unwrap();
+        assert!(rx.await.unwrap().is_ok());
+
+        // all in-memory blocks above the new head are gone
+        assert_eq!(test_harness.tree.state.tree_state.canonical_block_number(), 3);
+        assert_eq!(test_harness.tree.state.tree_state.block_count(), 0);
+        for block in &blocks[4..] {
+            assert!(test_harness.tree.state.tree_state.block_by_hash(block.block.hash()).is_none());
+        }
+        assert!(test_harness.tree.pending_set_head.is_none());
+        assert!(test_harness.tree.backfill_sync_state.is_idle());
+    }
+
     #[test]
     fn test_disconnected_payload() {
         let s = include_str!("../../test-data/holesky/2.rlp");
@@ -3384,6 +3530,46 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_tree_state_remove_blocks_above() {
+        let start_num_hash = BlockNumHash::default();
+        let mut tree_state = TreeState::new(start_num_hash);
+        let mut test_block_builder = TestBlockBuilder::default();
+        let blocks: Vec<_> = test_block_builder.get_executed_blocks(1..6).collect();
+
+        for block in &blocks {
+            tree_state.insert_executed(block.clone());
+        }
+        let fork_block =
+            test_block_builder.get_executed_block_with_number(4, blocks[2].block.hash());
+        tree_state.insert_executed(fork_block.clone());
+
+        for block in [&blocks[0], &blocks[3]] {
+            tree_state
+                .persisted_trie_updates
+                .insert(block.block.hash(), (block.block.number, Arc::new(TrieUpdates::default())));
+        }
+
+        tree_state.remove_blocks_above(2);
+
+        assert!(tree_state.blocks_by_hash.contains_key(&blocks[0].block.hash()));
+        assert!(tree_state.blocks_by_hash.contains_key(&blocks[1].block.hash()));
+        assert_eq!(tree_state.blocks_by_number.keys().copied().collect::<Vec<_>>(), vec![1, 2]);
+        for block in blocks.iter().skip(2).chain(std::iter::once(&fork_block)) {
+            assert!(!tree_state.blocks_by_hash.contains_key(&block.block.hash()));
+        }
+
+        assert!(!tree_state.parent_to_child.contains_key(&blocks[1].block.hash()));
+        assert!(!tree_state.parent_to_child.contains_key(&blocks[2].block.hash()));
+        assert_eq!(
+            tree_state.parent_to_child.get(&blocks[0].block.hash()),
+            Some(&HashSet::from_iter([blocks[1].block.hash()]))
+        );
+
+        assert!(tree_state.persisted_trie_updates.contains_key(&blocks[0].block.hash()));
+        assert!(!tree_state.persisted_trie_updates.contains_key(&blocks[3].block.hash()));
+    }
+
     #[tokio::test]
     async fn test_tree_state_on_new_head() {
         let chain_spec = MAINNET.clone();
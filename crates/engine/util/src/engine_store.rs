@@ -86,7 +86,8 @@ impl EngineMessageStore {
                 )?;
             }
             // noop
-            BeaconEngineMessage::TransitionConfigurationExchanged => (),
+            BeaconEngineMessage::TransitionConfigurationExchanged |
+            BeaconEngineMessage::SetHead { .. } => (),
         };
         Ok(())
     }
@@ -0,0 +1,92 @@
+use crate::MAX_BLOCKS_PER_ERA1;
+use alloy_primitives::{B256, U256};
+use sha2::{Digest, Sha256};
+
+/// Depth of the merkle tree over [`MAX_BLOCKS_PER_ERA1`] header records.
+const ACCUMULATOR_DEPTH: usize = MAX_BLOCKS_PER_ERA1.trailing_zeros() as usize;
+
+/// An entry of the header accumulator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HeaderRecord {
+    /// Hash of the block header.
+    pub block_hash: B256,
+    /// Total difficulty at the block.
+    pub total_difficulty: U256,
+}
+
+impl HeaderRecord {
+    /// Returns the SSZ hash tree root of the record.
+    fn tree_hash_root(&self) -> B256 {
+        hash_pair(self.block_hash.as_slice(), &self.total_difficulty.to_le_bytes::<32>())
+    }
+}
+
+/// Computes the root of the header accumulator of an Era1 file.
+///
+/// This is the SSZ hash tree root of `List[HeaderRecord, MAX_BLOCKS_PER_ERA1]`.
+///
+/// # Panics
+///
+/// If there are more than [`MAX_BLOCKS_PER_ERA1`] records.
+pub fn accumulator_root(records: &[HeaderRecord]) -> B256 {
+    assert!(records.len() <= MAX_BLOCKS_PER_ERA1, "too many header records");
+
+    let mut zero_hash = B256::ZERO;
+    let mut layer = records.iter().map(HeaderRecord::tree_hash_root).collect::<Vec<_>>();
+    for _ in 0..ACCUMULATOR_DEPTH {
+        if layer.len() % 2 == 1 {
+            layer.push(zero_hash);
+        }
+        layer = layer
+            .chunks_exact(2)
+            .map(|pair| hash_pair(pair[0].as_slice(), pair[1].as_slice()))
+            .collect();
+        zero_hash = hash_pair(zero_hash.as_slice(), zero_hash.as_slice());
+    }
+    let root = layer.first().copied().unwrap_or(zero_hash);
+
+    // mix in the length of the list
+    hash_pair(root.as_slice(), &U256::from(records.len()).to_le_bytes::<32>())
+}
+
+fn hash_pair(left: &[u8], right: &[u8]) -> B256 {
+    let mut hasher = Sha256::new();
+    hasher.update(left);
+    hasher.update(right);
+    B256::from_slice(&hasher.finalize())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::b256;
+
+    #[test]
+    fn empty_accumulator() {
+        // hash of the zero hashes up to depth 13, mixed with a length of zero
+        let mut zero_hash = B256::ZERO;
+        for _ in 0..13 {
+            zero_hash = hash_pair(zero_hash.as_slice(), zero_hash.as_slice());
+        }
+        assert_eq!(accumulator_root(&[]), hash_pair(zero_hash.as_slice(), &[0; 32]));
+    }
+
+    #[test]
+    fn accumulator_pads_with_zero_hashes() {
+        let record = HeaderRecord {
+            block_hash: b256!("d4e56740f876aef8c010b86a40d5f56745a118d0906a34e69aec8c0db1cb8fa3"),
+            total_difficulty: U256::from(0x400000000u64),
+        };
+
+        let mut root = record.tree_hash_root();
+        let mut zero_hash = B256::ZERO;
+        for _ in 0..13 {
+            root = hash_pair(root.as_slice(), zero_hash.as_slice());
+            zero_hash = hash_pair(zero_hash.as_slice(), zero_hash.as_slice());
+        }
+        let expected = hash_pair(root.as_slice(), &U256::from(1).to_le_bytes::<32>());
+
+        assert_eq!(accumulator_root(&[record]), expected);
+        assert_ne!(accumulator_root(&[record, record]), expected);
+    }
+}
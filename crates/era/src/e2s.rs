@@ -0,0 +1,191 @@
+//! e2store entry framing.
+//!
+//! Every entry starts with an 8 byte header: the entry type (2 bytes, little endian), the length
+//! of the data (4 bytes, little endian) and two reserved zero bytes.
+
+use crate::Era1Error;
+use std::io::{self, Read, Write};
+
+/// Length of the e2store entry header.
+pub const HEADER_LEN: usize = 8;
+
+/// Maximum length of the data of an entry accepted by [`E2StoreReader`].
+pub const MAX_ENTRY_LEN: usize = 64 * 1024 * 1024;
+
+/// Version entry, the first entry of every e2store file.
+pub const VERSION: u16 = 0x3265;
+/// Snappy compressed RLP encoded block header.
+pub const COMPRESSED_HEADER: u16 = 0x03;
+/// Snappy compressed RLP encoded block body.
+pub const COMPRESSED_BODY: u16 = 0x04;
+/// Snappy compressed RLP encoded list of block receipts.
+pub const COMPRESSED_RECEIPTS: u16 = 0x05;
+/// Total difficulty of a block as a 32 byte little endian integer.
+pub const TOTAL_DIFFICULTY: u16 = 0x06;
+/// Root of the header accumulator of an Era1 file.
+pub const ACCUMULATOR: u16 = 0x07;
+/// Offsets of the block tuples in an Era1 file.
+pub const BLOCK_INDEX: u16 = 0x3266;
+
+/// A single e2store entry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    /// The entry type.
+    pub entry_type: u16,
+    /// The entry data.
+    pub data: Vec<u8>,
+}
+
+impl Entry {
+    /// Creates a new entry.
+    pub const fn new(entry_type: u16, data: Vec<u8>) -> Self {
+        Self { entry_type, data }
+    }
+
+    /// Returns an error if the entry is not of the expected type.
+    pub const fn expect_type(&self, expected: u16) -> Result<(), Era1Error> {
+        if self.entry_type != expected {
+            return Err(Era1Error::UnexpectedEntry { expected, got: self.entry_type })
+        }
+        Ok(())
+    }
+}
+
+/// Reads e2store entries from a reader.
+#[derive(Debug)]
+pub struct E2StoreReader<R> {
+    reader: R,
+}
+
+impl<R: Read> E2StoreReader<R> {
+    /// Creates a new reader.
+    pub const fn new(reader: R) -> Self {
+        Self { reader }
+    }
+
+    /// Reads the next entry, or returns `None` at the end of the input.
+    pub fn read_entry(&mut self) -> Result<Option<Entry>, Era1Error> {
+        let mut header = [0; HEADER_LEN];
+        let mut read = 0;
+        while read < HEADER_LEN {
+            match self.reader.read(&mut header[read..]) {
+                Ok(0) if read == 0 => return Ok(None),
+                Ok(0) => return Err(Era1Error::TruncatedEntry),
+                Ok(n) => read += n,
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+                Err(err) => return Err(err.into()),
+            }
+        }
+
+        let entry_type = u16::from_le_bytes([header[0], header[1]]);
+        let len = u32::from_le_bytes([header[2], header[3], header[4], header[5]]) as usize;
+        let reserved = u16::from_le_bytes([header[6], header[7]]);
+        if reserved != 0 {
+            return Err(Era1Error::InvalidReserved(reserved))
+        }
+
+        if len > MAX_ENTRY_LEN {
+            return Err(Era1Error::InvalidEntryLength { entry_type, len })
+        }
+
+        // The buffer only grows with the data actually read, not with the length in the header.
+        let mut data = Vec::new();
+        (&mut self.reader).take(len as u64).read_to_end(&mut data)?;
+        if data.len() != len {
+            return Err(Era1Error::TruncatedEntry)
+        }
+
+        Ok(Some(Entry { entry_type, data }))
+    }
+
+    /// Returns the inner reader.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+/// Writes e2store entries to a writer.
+#[derive(Debug)]
+pub struct E2StoreWriter<W> {
+    writer: W,
+    /// Number of bytes written so far.
+    position: u64,
+}
+
+impl<W: Write> E2StoreWriter<W> {
+    /// Creates a new writer.
+    pub const fn new(writer: W) -> Self {
+        Self { writer, position: 0 }
+    }
+
+    /// Returns the number of bytes written so far.
+    pub const fn position(&self) -> u64 {
+        self.position
+    }
+
+    /// Writes an entry and returns the position it was written at.
+    pub fn write_entry(&mut self, entry_type: u16, data: &[u8]) -> Result<u64, Era1Error> {
+        let len = u32::try_from(data.len())
+            .map_err(|_| Era1Error::InvalidEntryLength { entry_type, len: data.len() })?;
+
+        let mut header = [0; HEADER_LEN];
+        header[..2].copy_from_slice(&entry_type.to_le_bytes());
+        header[2..6].copy_from_slice(&len.to_le_bytes());
+
+        self.writer.write_all(&header)?;
+        self.writer.write_all(data)?;
+
+        let position = self.position;
+        self.position += (HEADER_LEN + data.len()) as u64;
+        Ok(position)
+    }
+
+    /// Flushes the underlying writer.
+    pub fn flush(&mut self) -> Result<(), Era1Error> {
+        Ok(self.writer.flush()?)
+    }
+
+    /// Returns the inner writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entries_roundtrip() {
+        let mut writer = E2StoreWriter::new(Vec::new());
+        assert_eq!(writer.write_entry(VERSION, &[]).unwrap(), 0);
+        assert_eq!(writer.write_entry(TOTAL_DIFFICULTY, &[1; 32]).unwrap(), 8);
+        assert_eq!(writer.position(), 48);
+
+        let bytes = writer.into_inner();
+        assert_eq!(&bytes[..8], &[0x65, 0x32, 0, 0, 0, 0, 0, 0]);
+
+        let mut reader = E2StoreReader::new(bytes.as_slice());
+        assert_eq!(reader.read_entry().unwrap(), Some(Entry::new(VERSION, vec![])));
+        assert_eq!(reader.read_entry().unwrap(), Some(Entry::new(TOTAL_DIFFICULTY, vec![1; 32])));
+        assert_eq!(reader.read_entry().unwrap(), None);
+
+        let mut reader = E2StoreReader::new(&bytes[..20]);
+        reader.read_entry().unwrap();
+        assert!(matches!(reader.read_entry(), Err(Era1Error::TruncatedEntry)));
+    }
+
+    #[test]
+    fn rejects_oversized_entries() {
+        let mut header = [0; HEADER_LEN];
+        header[..2].copy_from_slice(&COMPRESSED_BODY.to_le_bytes());
+        header[2..6].copy_from_slice(&u32::MAX.to_le_bytes());
+
+        let mut reader = E2StoreReader::new(header.as_slice());
+        assert!(matches!(
+            reader.read_entry(),
+            Err(Era1Error::InvalidEntryLength { entry_type: COMPRESSED_BODY, len })
+                if len == u32::MAX as usize
+        ));
+    }
+}
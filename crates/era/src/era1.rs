@@ -0,0 +1,580 @@
+use crate::{
+    accumulator_root,
+    e2s::{self, E2StoreReader, E2StoreWriter, Entry},
+    Era1Error, HeaderRecord, ERA1_FILE_EXTENSION, MAX_BLOCKS_PER_ERA1,
+};
+use alloy_primitives::{hex, BlockNumber, Bytes, B256, U256};
+use alloy_rlp::{Decodable, Encodable};
+use reth_primitives::{Block, BlockBody, Header, Receipt, ReceiptWithBloom, SealedBlock};
+use reth_trie_common::root::ordered_trie_root_with_encoder;
+use std::{
+    fs::File,
+    io::{BufReader, Read, Write},
+    path::Path,
+};
+
+/// Maximum length of a decompressed header, body or list of receipts.
+const MAX_DECOMPRESSED_LEN: usize = 64 * 1024 * 1024;
+
+/// Returns the name of the Era1 file of the given network and epoch.
+///
+/// The name ends with the first four bytes of the accumulator root, e.g.
+/// `mainnet-00000-5ec1ffb8.era1`.
+pub fn era1_file_name(network: &str, epoch: u64, accumulator: B256) -> String {
+    format!("{network}-{epoch:05}-{}.{ERA1_FILE_EXTENSION}", hex::encode(&accumulator[..4]))
+}
+
+/// A block stored in an Era1 file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Era1Block {
+    /// The block.
+    pub block: SealedBlock,
+    /// The RLP encoded list of receipts of the block, as stored in the file.
+    ///
+    /// Receipts are kept encoded, because receipts of pre-Byzantium blocks carry an intermediate
+    /// state root instead of a status code and can not be decoded into a [`Receipt`].
+    pub receipts: Bytes,
+    /// The total difficulty at the block.
+    pub total_difficulty: U256,
+}
+
+impl Era1Block {
+    /// Creates a new Era1 block from a block, its receipts and the total difficulty at the block.
+    pub fn new(block: SealedBlock, receipts: &[Receipt], total_difficulty: U256) -> Self {
+        let receipts = receipts.iter().map(Receipt::with_bloom_ref).collect::<Vec<_>>();
+        let mut encoded = Vec::new();
+        receipts.encode(&mut encoded);
+        Self { block, receipts: encoded.into(), total_difficulty }
+    }
+
+    /// Returns the block number.
+    pub fn number(&self) -> BlockNumber {
+        self.block.number
+    }
+
+    /// Returns the accumulator record of the block.
+    pub fn header_record(&self) -> HeaderRecord {
+        HeaderRecord { block_hash: self.block.hash(), total_difficulty: self.total_difficulty }
+    }
+
+    /// Decodes the receipts of the block.
+    pub fn decode_receipts(&self) -> alloy_rlp::Result<Vec<ReceiptWithBloom>> {
+        Vec::<ReceiptWithBloom>::decode(&mut self.receipts.as_ref())
+    }
+
+    /// Calculates the receipts root from the encoded receipts.
+    pub fn calculate_receipts_root(&self) -> alloy_rlp::Result<B256> {
+        let mut buf = self.receipts.as_ref();
+        let mut payload = alloy_rlp::Header::decode_bytes(&mut buf, true)?;
+
+        let mut receipts = Vec::new();
+        while !payload.is_empty() {
+            let item = payload;
+            let header = alloy_rlp::Header::decode(&mut payload)?;
+            if payload.len() < header.payload_length {
+                return Err(alloy_rlp::Error::InputTooShort)
+            }
+            // legacy receipts are lists, typed receipts are strings wrapping the envelope
+            let encoded = if header.list {
+                &item[..item.len() - payload.len() + header.payload_length]
+            } else {
+                &payload[..header.payload_length]
+            };
+            receipts.push(encoded);
+            payload = &payload[header.payload_length..];
+        }
+
+        Ok(ordered_trie_root_with_encoder(&receipts, |receipt, buf| buf.extend_from_slice(receipt)))
+    }
+
+    /// Verifies that the receipts match the receipts root of the header.
+    pub fn verify_receipts_root(&self) -> Result<(), Era1Error> {
+        let got = self.calculate_receipts_root()?;
+        if got != self.block.receipts_root {
+            return Err(Era1Error::ReceiptsRootMismatch {
+                number: self.number(),
+                expected: self.block.receipts_root,
+                got,
+            })
+        }
+        Ok(())
+    }
+
+    /// Verifies that the block body matches the header.
+    pub fn verify_body(&self) -> Result<(), Era1Error> {
+        let mismatch = |field| Era1Error::BodyMismatch { number: self.number(), field };
+
+        if self.block.body.calculate_ommers_root() != self.block.ommers_hash {
+            return Err(mismatch("ommers hash"))
+        }
+        if self.block.ensure_transaction_root_valid().is_err() {
+            return Err(mismatch("transactions root"))
+        }
+        if self.block.body.calculate_withdrawals_root() != self.block.withdrawals_root {
+            return Err(mismatch("withdrawals root"))
+        }
+        Ok(())
+    }
+}
+
+/// Contents of an Era1 file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Era1File {
+    /// The blocks of the file, in ascending order.
+    pub blocks: Vec<Era1Block>,
+    /// The accumulator root stored in the file.
+    pub accumulator: B256,
+    /// The file name, if the file was read from a path, see [`Era1File::open`].
+    pub name: Option<String>,
+}
+
+impl Era1File {
+    /// Reads an Era1 file from the given path.
+    ///
+    /// The file name must be formatted as `{network}-{epoch}-{accumulator prefix}.era1`, and is
+    /// checked against the contents by [`Era1File::verify`].
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, Era1Error> {
+        let path = path.as_ref();
+        if parse_file_name(path).is_none() {
+            return Err(Era1Error::NameMismatch(path.display().to_string()))
+        }
+
+        let mut file = Self::read(BufReader::new(File::open(path)?))?;
+        file.name = path.file_name().map(|name| name.to_string_lossy().into_owned());
+        Ok(file)
+    }
+
+    /// Reads an Era1 file.
+    ///
+    /// This checks the structure of the file and the block index, but does not verify the
+    /// contents, see [`Era1File::verify`].
+    pub fn read(reader: impl Read) -> Result<Self, Era1Error> {
+        let mut reader = PositionedReader { reader: E2StoreReader::new(reader), position: 0 };
+
+        let (_, version) = reader.expect_entry(e2s::VERSION)?;
+        if !version.data.is_empty() {
+            return Err(Era1Error::InvalidEntryLength {
+                entry_type: e2s::VERSION,
+                len: version.data.len(),
+            })
+        }
+
+        let mut blocks = Vec::new();
+        let mut offsets = Vec::new();
+        let (accumulator, index_position, index) = loop {
+            let (position, entry) = reader.next_entry()?.ok_or(Era1Error::TruncatedEntry)?;
+            match entry.entry_type {
+                e2s::COMPRESSED_HEADER => {
+                    if blocks.len() == MAX_BLOCKS_PER_ERA1 {
+                        return Err(Era1Error::TooManyBlocks(blocks.len() + 1))
+                    }
+                    let header = Header::decode(&mut decompress(&entry)?.as_slice())?;
+                    let (_, body) = reader.expect_entry(e2s::COMPRESSED_BODY)?;
+                    let body = BlockBody::decode(&mut decompress(&body)?.as_slice())?;
+                    let (_, receipts) = reader.expect_entry(e2s::COMPRESSED_RECEIPTS)?;
+                    let receipts = decompress(&receipts)?;
+                    let (_, total_difficulty) = reader.expect_entry(e2s::TOTAL_DIFFICULTY)?;
+                    let total_difficulty = fixed_bytes::<32>(&total_difficulty)?;
+
+                    offsets.push(position);
+                    blocks.push(Era1Block {
+                        block: Block { header, body }.seal_slow(),
+                        receipts: receipts.into(),
+                        total_difficulty: U256::from_le_bytes(total_difficulty),
+                    });
+                }
+                e2s::ACCUMULATOR => {
+                    let accumulator = B256::from(fixed_bytes::<32>(&entry)?);
+                    let (index_position, index) = reader.expect_entry(e2s::BLOCK_INDEX)?;
+                    break (accumulator, index_position, index)
+                }
+                // other entries are allowed between the blocks and the accumulator
+                _ => {}
+            }
+        };
+
+        if reader.next_entry()?.is_some() {
+            return Err(Era1Error::InvalidBlockIndex)
+        }
+        if blocks.is_empty() {
+            return Err(Era1Error::Empty)
+        }
+
+        // starting-number | offsets... | count
+        let expected_len = 16 + offsets.len() * 8;
+        if index.data.len() != expected_len {
+            return Err(Era1Error::InvalidEntryLength {
+                entry_type: e2s::BLOCK_INDEX,
+                len: index.data.len(),
+            })
+        }
+        let mut words = index
+            .data
+            .chunks_exact(8)
+            .map(|word| u64::from_le_bytes(word.try_into().expect("chunks of 8 bytes")));
+        let starting_number = words.next().expect("length checked");
+        let count = words.next_back().expect("length checked");
+        let offsets_match = offsets
+            .iter()
+            .zip(words)
+            .all(|(offset, relative)| offset.wrapping_sub(index_position) == relative);
+        if starting_number != blocks[0].number() || count != blocks.len() as u64 || !offsets_match {
+            return Err(Era1Error::InvalidBlockIndex)
+        }
+
+        Ok(Self { blocks, accumulator, name: None })
+    }
+
+    /// Returns the number of the first block in the file.
+    pub fn start_block(&self) -> BlockNumber {
+        self.blocks.first().map(Era1Block::number).unwrap_or_default()
+    }
+
+    /// Returns the number of the last block in the file.
+    pub fn end_block(&self) -> BlockNumber {
+        self.blocks.last().map(Era1Block::number).unwrap_or_default()
+    }
+
+    /// Verifies that the file starts at the first block of the epoch in its name, and that the
+    /// accumulator root starts with the prefix in its name.
+    ///
+    /// Files that were not read from a path have no name and always pass.
+    pub fn verify_name(&self) -> Result<(), Era1Error> {
+        let Some(name) = &self.name else { return Ok(()) };
+
+        let matches_name = parse_file_name(Path::new(name)).is_some_and(|(epoch, prefix)| {
+            self.start_block() == epoch_start(epoch) && self.accumulator[..4] == prefix[..]
+        });
+        if !matches_name {
+            return Err(Era1Error::NameMismatch(name.clone()))
+        }
+        Ok(())
+    }
+
+    /// Verifies the contents of the file.
+    ///
+    /// This checks that the blocks form a chain, that the total difficulty of each block is the
+    /// total difficulty of its parent plus its difficulty, that the bodies match the headers, that
+    /// the accumulator root matches the blocks and that the file matches its name, see
+    /// [`Era1File::verify_name`].
+    ///
+    /// Receipts are not verified, see [`Era1Block::verify_receipts_root`].
+    pub fn verify(&self) -> Result<(), Era1Error> {
+        let mut parent: Option<&Era1Block> = None;
+        for block in &self.blocks {
+            let expected_total_difficulty = match parent {
+                Some(parent) => {
+                    if parent.number().checked_add(1) != Some(block.number()) ||
+                        block.block.parent_hash != parent.block.hash()
+                    {
+                        return Err(Era1Error::Discontinuity { number: block.number() })
+                    }
+                    Some(parent.total_difficulty + block.block.difficulty)
+                }
+                None if block.number() == 0 => Some(block.block.difficulty),
+                None => None,
+            };
+            if expected_total_difficulty.is_some_and(|td| td != block.total_difficulty) {
+                return Err(Era1Error::TotalDifficultyMismatch { number: block.number() })
+            }
+
+            block.verify_body()?;
+            parent = Some(block);
+        }
+
+        let records = self.blocks.iter().map(Era1Block::header_record).collect::<Vec<_>>();
+        let got = accumulator_root(&records);
+        if got != self.accumulator {
+            return Err(Era1Error::AccumulatorMismatch { expected: self.accumulator, got })
+        }
+
+        self.verify_name()
+    }
+}
+
+/// Writes blocks to an Era1 file.
+#[derive(Debug)]
+pub struct Era1Writer<W> {
+    writer: E2StoreWriter<W>,
+    /// Accumulator records of the written blocks.
+    records: Vec<HeaderRecord>,
+    /// Positions of the block tuples.
+    offsets: Vec<u64>,
+    /// Number of the first written block.
+    start_block: Option<BlockNumber>,
+}
+
+impl<W: Write> Era1Writer<W> {
+    /// Creates a new writer and writes the version entry.
+    pub fn new(writer: W) -> Result<Self, Era1Error> {
+        let mut writer = E2StoreWriter::new(writer);
+        writer.write_entry(e2s::VERSION, &[])?;
+        Ok(Self { writer, records: Vec::new(), offsets: Vec::new(), start_block: None })
+    }
+
+    /// Returns the number of written blocks.
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    /// Returns `true` if no blocks were written.
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// Appends the next block.
+    pub fn append_block(&mut self, block: &Era1Block) -> Result<(), Era1Error> {
+        if self.records.len() == MAX_BLOCKS_PER_ERA1 {
+            return Err(Era1Error::TooManyBlocks(self.records.len() + 1))
+        }
+        let start_block = *self.start_block.get_or_insert(block.number());
+        if block.number() != start_block + self.records.len() as u64 {
+            return Err(Era1Error::Discontinuity { number: block.number() })
+        }
+
+        let mut header = Vec::new();
+        block.block.header.encode(&mut header);
+        let mut body = Vec::new();
+        block.block.body.encode(&mut body);
+
+        let offset = self.writer.write_entry(e2s::COMPRESSED_HEADER, &compress(&header)?)?;
+        self.writer.write_entry(e2s::COMPRESSED_BODY, &compress(&body)?)?;
+        self.writer.write_entry(e2s::COMPRESSED_RECEIPTS, &compress(&block.receipts)?)?;
+        self.writer
+            .write_entry(e2s::TOTAL_DIFFICULTY, &block.total_difficulty.to_le_bytes::<32>())?;
+
+        self.offsets.push(offset);
+        self.records.push(block.header_record());
+        Ok(())
+    }
+
+    /// Writes the accumulator and the block index, and returns the inner writer together with the
+    /// accumulator root.
+    pub fn finish(mut self) -> Result<(W, B256), Era1Error> {
+        let start_block = self.start_block.ok_or(Era1Error::Empty)?;
+
+        let accumulator = accumulator_root(&self.records);
+        self.writer.write_entry(e2s::ACCUMULATOR, accumulator.as_slice())?;
+
+        // offsets are relative to the start of the block index entry
+        let index_position = self.writer.position();
+        let mut index = Vec::with_capacity(16 + self.offsets.len() * 8);
+        index.extend_from_slice(&start_block.to_le_bytes());
+        for offset in &self.offsets {
+            index.extend_from_slice(&offset.wrapping_sub(index_position).to_le_bytes());
+        }
+        index.extend_from_slice(&(self.offsets.len() as u64).to_le_bytes());
+        self.writer.write_entry(e2s::BLOCK_INDEX, &index)?;
+        self.writer.flush()?;
+
+        Ok((self.writer.into_inner(), accumulator))
+    }
+}
+
+/// An e2store reader that tracks the position of each entry.
+struct PositionedReader<R> {
+    reader: E2StoreReader<R>,
+    position: u64,
+}
+
+impl<R: Read> PositionedReader<R> {
+    /// Reads the next entry and its position.
+    fn next_entry(&mut self) -> Result<Option<(u64, Entry)>, Era1Error> {
+        let Some(entry) = self.reader.read_entry()? else { return Ok(None) };
+        let position = self.position;
+        self.position += (e2s::HEADER_LEN + entry.data.len()) as u64;
+        Ok(Some((position, entry)))
+    }
+
+    /// Reads the next entry and its position, and checks its type.
+    fn expect_entry(&mut self, entry_type: u16) -> Result<(u64, Entry), Era1Error> {
+        let (position, entry) = self.next_entry()?.ok_or(Era1Error::TruncatedEntry)?;
+        entry.expect_type(entry_type)?;
+        Ok((position, entry))
+    }
+}
+
+/// Compresses data with the snappy framing format.
+fn compress(data: &[u8]) -> Result<Vec<u8>, Era1Error> {
+    let mut encoder = snap::write::FrameEncoder::new(Vec::new());
+    encoder.write_all(data)?;
+    Ok(encoder.into_inner().map_err(|err| err.into_error())?)
+}
+
+/// Decompresses the data of an entry in the snappy framing format.
+///
+/// Fails if the decompressed data is longer than [`MAX_DECOMPRESSED_LEN`].
+fn decompress(entry: &Entry) -> Result<Vec<u8>, Era1Error> {
+    let mut decompressed = Vec::new();
+    snap::read::FrameDecoder::new(entry.data.as_slice())
+        .take(MAX_DECOMPRESSED_LEN as u64 + 1)
+        .read_to_end(&mut decompressed)?;
+    if decompressed.len() > MAX_DECOMPRESSED_LEN {
+        return Err(Era1Error::InvalidEntryLength {
+            entry_type: entry.entry_type,
+            len: decompressed.len(),
+        })
+    }
+    Ok(decompressed)
+}
+
+/// Parses the epoch and the accumulator root prefix from the name of an Era1 file, formatted as
+/// `{network}-{epoch}-{accumulator prefix}.era1`.
+pub(crate) fn parse_file_name(path: &Path) -> Option<(u64, [u8; 4])> {
+    if path.extension()? != ERA1_FILE_EXTENSION {
+        return None
+    }
+    let stem = path.file_stem()?.to_str()?;
+    let mut parts = stem.rsplitn(3, '-');
+    let accumulator_prefix = hex::decode(parts.next()?).ok()?.try_into().ok()?;
+    let epoch = parts.next()?.parse().ok()?;
+    parts.next()?;
+    Some((epoch, accumulator_prefix))
+}
+
+/// Returns the first block of an epoch.
+pub(crate) const fn epoch_start(epoch: u64) -> BlockNumber {
+    epoch.saturating_mul(MAX_BLOCKS_PER_ERA1 as u64)
+}
+
+/// Returns the data of a fixed size entry.
+fn fixed_bytes<const N: usize>(entry: &Entry) -> Result<[u8; N], Era1Error> {
+    entry.data.as_slice().try_into().map_err(|_| Era1Error::InvalidEntryLength {
+        entry_type: entry.entry_type,
+        len: entry.data.len(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::{Address, Log};
+    use reth_primitives::{proofs::calculate_receipt_root_no_memo, TxType};
+
+    fn test_blocks(count: u64) -> Vec<Era1Block> {
+        let mut blocks: Vec<Era1Block> = Vec::new();
+        for number in 0..count {
+            let receipts = vec![Receipt {
+                tx_type: if number % 2 == 0 { TxType::Legacy } else { TxType::Eip1559 },
+                success: true,
+                cumulative_gas_used: 21_000,
+                logs: vec![Log::new_unchecked(Address::with_last_byte(1), vec![], Bytes::new())],
+                ..Default::default()
+            }];
+            let parent = blocks.last();
+            let header = Header {
+                number,
+                parent_hash: parent.map(|parent| parent.block.hash()).unwrap_or_default(),
+                difficulty: U256::from(1_000 + number),
+                receipts_root: calculate_receipt_root_no_memo(&receipts.iter().collect::<Vec<_>>()),
+                ..Default::default()
+            };
+            let total_difficulty = parent.map(|parent| parent.total_difficulty).unwrap_or_default() +
+                header.difficulty;
+            let block = Block { header, body: BlockBody::default() }.seal_slow();
+            blocks.push(Era1Block::new(block, &receipts, total_difficulty));
+        }
+        blocks
+    }
+
+    fn write_blocks(blocks: &[Era1Block]) -> (Vec<u8>, B256) {
+        let mut writer = Era1Writer::new(Vec::new()).unwrap();
+        for block in blocks {
+            writer.append_block(block).unwrap();
+        }
+        writer.finish().unwrap()
+    }
+
+    #[test]
+    fn era1_roundtrip() {
+        let blocks = test_blocks(10);
+        let (bytes, accumulator) = write_blocks(&blocks);
+
+        let file = Era1File::read(bytes.as_slice()).unwrap();
+        assert_eq!(file.accumulator, accumulator);
+        assert_eq!(file.blocks, blocks);
+        assert_eq!((file.start_block(), file.end_block()), (0, 9));
+        file.verify().unwrap();
+
+        for block in &file.blocks {
+            block.verify_receipts_root().unwrap();
+            assert_eq!(block.decode_receipts().unwrap().len(), 1);
+        }
+    }
+
+    #[test]
+    fn era1_file_roundtrip() {
+        let blocks = test_blocks(3);
+        let dir = tempfile::tempdir().unwrap();
+        let (bytes, accumulator) = write_blocks(&blocks);
+        let path = dir.path().join(era1_file_name("mainnet", 0, accumulator));
+        std::fs::write(&path, bytes).unwrap();
+
+        assert!(path
+            .to_string_lossy()
+            .ends_with(&format!("mainnet-00000-{}.era1", hex::encode(&accumulator[..4]))));
+        let file = Era1File::open(&path).unwrap();
+        assert_eq!(file.blocks, blocks);
+        file.verify().unwrap();
+
+        // the accumulator root and the epoch must match the name
+        for name in
+            ["mainnet-00000-00000000.era1".to_string(), era1_file_name("mainnet", 1, accumulator)]
+        {
+            let renamed = dir.path().join(name);
+            std::fs::copy(&path, &renamed).unwrap();
+            let file = Era1File::open(&renamed).unwrap();
+            assert!(matches!(file.verify(), Err(Era1Error::NameMismatch(_))));
+        }
+
+        let renamed = dir.path().join("blocks.era1");
+        std::fs::copy(&path, &renamed).unwrap();
+        assert!(matches!(Era1File::open(&renamed), Err(Era1Error::NameMismatch(_))));
+    }
+
+    #[test]
+    fn era1_verify_detects_tampering() {
+        let blocks = test_blocks(5);
+        let (bytes, _) = write_blocks(&blocks);
+        let file = Era1File::read(bytes.as_slice()).unwrap();
+
+        let mut invalid = file.clone();
+        invalid.accumulator = B256::ZERO;
+        assert!(matches!(invalid.verify(), Err(Era1Error::AccumulatorMismatch { .. })));
+
+        let mut invalid = file.clone();
+        invalid.blocks[3].total_difficulty += U256::from(1);
+        assert!(matches!(invalid.verify(), Err(Era1Error::TotalDifficultyMismatch { number: 3 })));
+
+        let mut invalid = file.clone();
+        invalid.blocks.remove(2);
+        assert!(matches!(invalid.verify(), Err(Era1Error::Discontinuity { number: 3 })));
+
+        let mut invalid = file;
+        invalid.blocks[1].receipts = blocks[0].receipts.clone();
+        assert!(matches!(
+            invalid.blocks[1].verify_receipts_root(),
+            Err(Era1Error::ReceiptsRootMismatch { number: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn era1_writer_rejects_gaps() {
+        let blocks = test_blocks(3);
+        let mut writer = Era1Writer::new(Vec::new()).unwrap();
+        writer.append_block(&blocks[0]).unwrap();
+        assert!(matches!(
+            writer.append_block(&blocks[2]),
+            Err(Era1Error::Discontinuity { number: 2 })
+        ));
+    }
+
+    #[test]
+    fn era1_read_rejects_invalid_index() {
+        let (mut bytes, _) = write_blocks(&test_blocks(2));
+        // corrupt the block count at the end of the block index
+        let len = bytes.len();
+        bytes[len - 8] = 3;
+        assert!(matches!(Era1File::read(bytes.as_slice()), Err(Era1Error::InvalidBlockIndex)));
+    }
+}
@@ -0,0 +1,85 @@
+use alloy_primitives::{BlockNumber, B256};
+
+/// Errors that can occur when reading, writing or verifying Era1 files.
+#[derive(Debug, thiserror::Error)]
+pub enum Era1Error {
+    /// An I/O error occurred.
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    /// An entry could not be RLP decoded.
+    #[error(transparent)]
+    Rlp(#[from] alloy_rlp::Error),
+    /// The file ended in the middle of an entry.
+    #[error("truncated e2store entry")]
+    TruncatedEntry,
+    /// The e2store header has non-zero reserved bytes.
+    #[error("invalid reserved bytes in e2store header: {0:#06x}")]
+    InvalidReserved(u16),
+    /// An entry of a different type was expected.
+    #[error("unexpected e2store entry type {got:#06x}, expected {expected:#06x}")]
+    UnexpectedEntry {
+        /// The expected entry type.
+        expected: u16,
+        /// The entry type found in the file.
+        got: u16,
+    },
+    /// An entry has an invalid length.
+    #[error("invalid length {len} for e2store entry type {entry_type:#06x}")]
+    InvalidEntryLength {
+        /// The entry type.
+        entry_type: u16,
+        /// The length of the entry data.
+        len: usize,
+    },
+    /// The file contains no blocks.
+    #[error("era1 file contains no blocks")]
+    Empty,
+    /// The file contains more blocks than allowed.
+    #[error("era1 file contains {0} blocks, at most {max} are allowed", max = crate::MAX_BLOCKS_PER_ERA1)]
+    TooManyBlocks(usize),
+    /// The block index does not match the blocks in the file.
+    #[error("block index does not match the blocks in the file")]
+    InvalidBlockIndex,
+    /// A block does not follow the previous block.
+    #[error("block {number} does not follow the previous block")]
+    Discontinuity {
+        /// The number of the block.
+        number: BlockNumber,
+    },
+    /// A block body does not match its header.
+    #[error("body of block {number} does not match the header: {field} mismatch")]
+    BodyMismatch {
+        /// The number of the block.
+        number: BlockNumber,
+        /// The header field that does not match.
+        field: &'static str,
+    },
+    /// The receipts of a block do not match its header.
+    #[error("receipts root mismatch for block {number}: expected {expected}, got {got}")]
+    ReceiptsRootMismatch {
+        /// The number of the block.
+        number: BlockNumber,
+        /// The receipts root in the header.
+        expected: B256,
+        /// The receipts root computed from the file.
+        got: B256,
+    },
+    /// The total difficulty of a block is not the total difficulty of its parent plus its
+    /// difficulty.
+    #[error("invalid total difficulty for block {number}")]
+    TotalDifficultyMismatch {
+        /// The number of the block.
+        number: BlockNumber,
+    },
+    /// The blocks of a file do not match the epoch or the accumulator root in its name.
+    #[error("era1 file {0} does not match its name")]
+    NameMismatch(String),
+    /// The accumulator root computed from the blocks does not match the one in the file.
+    #[error("accumulator root mismatch: expected {expected}, got {got}")]
+    AccumulatorMismatch {
+        /// The accumulator root stored in the file.
+        expected: B256,
+        /// The accumulator root computed from the blocks.
+        got: B256,
+    },
+}
@@ -0,0 +1,157 @@
+use crate::{
+    era1::{epoch_start, parse_file_name},
+    Era1Block, Era1Error, Era1File, MAX_BLOCKS_PER_ERA1,
+};
+use alloy_primitives::BlockNumber;
+use schnellru::{ByLength, LruMap};
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+};
+
+/// Number of Era1 files kept in memory by [`Era1History`].
+const CACHED_FILES: u32 = 4;
+
+/// Serves blocks from a directory of Era1 files, e.g. to re-hydrate expired history on demand.
+///
+/// Files are indexed by the epoch in their name and only read when one of their blocks is
+/// requested. Every file is verified the first time it's read, and the most recently read files
+/// are kept in memory.
+#[derive(Debug)]
+pub struct Era1History {
+    /// Era1 files by epoch.
+    files: BTreeMap<u64, IndexedFile>,
+    /// The most recently read files by epoch.
+    cached: Mutex<LruMap<u64, Arc<Era1File>, ByLength>>,
+}
+
+/// An Era1 file of [`Era1History`].
+#[derive(Debug)]
+struct IndexedFile {
+    path: PathBuf,
+    /// Whether the file was already verified, so it's not verified again after being evicted
+    /// from the cache.
+    verified: AtomicBool,
+}
+
+impl Era1History {
+    /// Indexes the Era1 files in the given directory.
+    pub fn open(dir: impl AsRef<Path>) -> Result<Self, Era1Error> {
+        let mut files = BTreeMap::new();
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            if let Some((epoch, _)) = parse_file_name(&path) {
+                files.insert(epoch, IndexedFile { path, verified: AtomicBool::new(false) });
+            }
+        }
+        Ok(Self { files, cached: Mutex::new(LruMap::new(ByLength::new(CACHED_FILES))) })
+    }
+
+    /// Returns the range of blocks covered by the indexed files, if any.
+    pub fn block_range(&self) -> Option<(BlockNumber, BlockNumber)> {
+        let (first, _) = self.files.first_key_value()?;
+        let (last, _) = self.files.last_key_value()?;
+        Some((epoch_start(*first), epoch_start(*last + 1) - 1))
+    }
+
+    /// Returns the block with the given number, or [`None`] if there is no Era1 file for it.
+    pub fn block(&self, number: BlockNumber) -> Result<Option<Era1Block>, Era1Error> {
+        let epoch = number / MAX_BLOCKS_PER_ERA1 as u64;
+        let Some(indexed) = self.files.get(&epoch) else { return Ok(None) };
+
+        let cached = self.lock_cache().get(&epoch).cloned();
+        let file = match cached {
+            Some(file) => file,
+            None => {
+                // The file is read without holding the lock, so that requests for cached files
+                // don't wait on the I/O. Concurrent misses for the same file may read it twice.
+                let file = Arc::new(indexed.read(epoch)?);
+                self.lock_cache().insert(epoch, file.clone());
+                file
+            }
+        };
+
+        let index = number.checked_sub(file.start_block()).map(|index| index as usize);
+        Ok(index.and_then(|index| file.blocks.get(index)).cloned())
+    }
+
+    fn lock_cache(&self) -> std::sync::MutexGuard<'_, LruMap<u64, Arc<Era1File>, ByLength>> {
+        self.cached.lock().unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+impl IndexedFile {
+    /// Reads the Era1 file and checks that it matches the epoch and accumulator in its name.
+    ///
+    /// The contents are only verified the first time the file is read.
+    fn read(&self, epoch: u64) -> Result<Era1File, Era1Error> {
+        let file = Era1File::open(&self.path)?;
+        if file.start_block() != epoch_start(epoch) {
+            return Err(Era1Error::NameMismatch(self.path.display().to_string()))
+        }
+
+        if self.verified.load(Ordering::Relaxed) {
+            file.verify_name()?;
+        } else {
+            file.verify()?;
+            self.verified.store(true, Ordering::Relaxed);
+        }
+
+        Ok(file)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{era1_file_name, Era1Writer};
+    use alloy_primitives::U256;
+    use reth_primitives::{Block, BlockBody, Header};
+
+    #[test]
+    fn parse_era1_file_names() {
+        assert_eq!(
+            parse_file_name(Path::new("mainnet-00012-5ec1ffb8.era1")),
+            Some((12, [0x5e, 0xc1, 0xff, 0xb8]))
+        );
+        assert_eq!(parse_file_name(Path::new("my-network-00001-00000000.era1")), Some((1, [0; 4])));
+        assert_eq!(parse_file_name(Path::new("mainnet-00012-5ec1ffb8.era")), None);
+        assert_eq!(parse_file_name(Path::new("00012-5ec1ffb8.era1")), None);
+    }
+
+    #[test]
+    fn serves_blocks_of_verified_files() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let mut writer = Era1Writer::new(Vec::new()).unwrap();
+        let mut parent_hash = Default::default();
+        let mut total_difficulty = U256::ZERO;
+        for number in 0..3 {
+            let header =
+                Header { number, parent_hash, difficulty: U256::from(1), ..Default::default() };
+            let block = Block { header, body: BlockBody::default() }.seal_slow();
+            parent_hash = block.hash();
+            total_difficulty += U256::from(1);
+            writer.append_block(&Era1Block::new(block, &[], total_difficulty)).unwrap();
+        }
+        let (bytes, accumulator) = writer.finish().unwrap();
+        std::fs::write(dir.path().join(era1_file_name("mainnet", 0, accumulator)), &bytes).unwrap();
+
+        let history = Era1History::open(dir.path()).unwrap();
+        assert_eq!(history.block_range(), Some((0, 8191)));
+        assert_eq!(history.block(2).unwrap().map(|block| block.block.hash()), Some(parent_hash));
+        assert_eq!(history.block(3).unwrap(), None);
+        assert_eq!(history.block(8192).unwrap(), None);
+        assert!(history.files[&0].verified.load(Ordering::Relaxed));
+        assert_eq!(history.lock_cache().len(), 1);
+
+        // files that don't match their name are rejected
+        std::fs::write(dir.path().join("mainnet-00001-00000000.era1"), &bytes).unwrap();
+        let history = Era1History::open(dir.path()).unwrap();
+        assert!(matches!(history.block(8192), Err(Era1Error::NameMismatch(_))));
+    }
+}
@@ -0,0 +1,40 @@
+//! Era1 history archives.
+//!
+//! Era1 files store pre-merge history in the [e2store] format: up to [`MAX_BLOCKS_PER_ERA1`]
+//! consecutive blocks with their receipts and total difficulty, followed by the root of the
+//! header accumulator and an index of block offsets.
+//!
+//! ```text
+//! era1 := Version | block-tuple* | other-entries* | Accumulator | BlockIndex
+//! block-tuple := CompressedHeader | CompressedBody | CompressedReceipts | TotalDifficulty
+//! ```
+//!
+//! [e2store]: https://github.com/status-im/nimbus-eth2/blob/stable/docs/e2store.md
+
+#![doc(
+    html_logo_url = "https://raw.githubusercontent.com/paradigmxyz/reth/main/assets/reth-docs.png",
+    html_favicon_url = "https://avatars0.githubusercontent.com/u/97369466?s=256",
+    issue_tracker_base_url = "https://github.com/paradigmxyz/reth/issues/"
+)]
+#![cfg_attr(not(test), warn(unused_crate_dependencies))]
+#![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
+
+mod accumulator;
+pub use accumulator::{accumulator_root, HeaderRecord};
+
+pub mod e2s;
+
+mod era1;
+pub use era1::{era1_file_name, Era1Block, Era1File, Era1Writer};
+
+mod error;
+pub use error::Era1Error;
+
+mod history;
+pub use history::Era1History;
+
+/// Maximum number of blocks in a single Era1 file.
+pub const MAX_BLOCKS_PER_ERA1: usize = 8192;
+
+/// File extension of Era1 files.
+pub const ERA1_FILE_EXTENSION: &str = "era1";
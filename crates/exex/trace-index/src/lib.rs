@@ -0,0 +1,416 @@
+//! Execution extension that maintains an index of the addresses that appeared in the traces of
+//! each block.
+//!
+//! The index is stored in the `TraceAppearances` table and allows `trace_filter` requests with
+//! `fromAddress`/`toAddress` filters to only re-execute the blocks in which one of the requested
+//! addresses appeared.
+
+#![doc(
+    html_logo_url = "https://raw.githubusercontent.com/paradigmxyz/reth/main/assets/reth-docs.png",
+    html_favicon_url = "https://avatars0.githubusercontent.com/u/97369466?s=256",
+    issue_tracker_base_url = "https://github.com/paradigmxyz/reth/issues/"
+)]
+#![cfg_attr(not(test), warn(unused_crate_dependencies))]
+#![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
+
+use alloy_eips::BlockNumHash;
+use alloy_primitives::{Address, BlockHash, BlockNumber};
+use alloy_rpc_types_trace::parity::{Action, TraceOutput};
+use futures::TryStreamExt;
+use reth_chainspec::EthereumHardforks;
+use reth_evm::{system_calls::SystemCaller, ConfigureEvm, ConfigureEvmEnv};
+use reth_execution_types::Chain;
+use reth_exex::{ExExContext, ExExEvent, ExExHead, ExExNotification};
+use reth_node_api::{FullNodeComponents, NodeTypes};
+use reth_primitives::SealedBlockWithSenders;
+use reth_provider::{
+    providers::BundleStateProvider, BlockHashReader, ChainSpecProvider, DBProvider,
+    DatabaseProviderFactory, EvmEnvProvider, StageCheckpointReader, StateProvider,
+    StateProviderBox, StateProviderFactory, TraceIndexReader, TraceIndexWriter,
+};
+use reth_revm::database::StateProviderDatabase;
+use reth_stages_types::StageId;
+use revm::{
+    db::CacheDB,
+    primitives::{EnvWithHandlerCfg, ResultAndState},
+    DatabaseCommit,
+};
+use revm_inspectors::tracing::{TracingInspector, TracingInspectorConfig};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::Arc,
+};
+use tracing::debug;
+
+/// Execution extension that keeps the trace index in sync with the canonical chain.
+///
+/// Every committed block is re-executed with a parity tracer and the addresses that appear in its
+/// traces are appended to the index. Reverted blocks are re-executed as well, to find the index
+/// entries that need to be unwound.
+///
+/// Only blocks that the node has persisted are written to the index, so that its tip is always a
+/// block of the database after a restart. The appearances of the blocks above it are kept in
+/// memory until the node persists them.
+pub struct TraceIndexExEx<Node: FullNodeComponents> {
+    ctx: ExExContext<Node>,
+    /// Committed chains with blocks that are not persisted yet, oldest first.
+    ///
+    /// Their execution outcomes provide the state of the blocks that can't be read from the
+    /// database.
+    unpersisted: Vec<Arc<Chain>>,
+    /// Hashes of the committed blocks that are not in the index yet, and the addresses that
+    /// appeared in their traces.
+    pending: BTreeMap<BlockNumber, (BlockHash, BTreeSet<Address>)>,
+}
+
+impl<Node> TraceIndexExEx<Node>
+where
+    Node: FullNodeComponents,
+    Node::Types: NodeTypes<ChainSpec: EthereumHardforks>,
+    <Node::Provider as DatabaseProviderFactory>::ProviderRW: TraceIndexWriter,
+{
+    /// Creates the execution extension and resumes the index from its persisted tip.
+    ///
+    /// If the index is not maintained yet, it is built starting from genesis.
+    pub fn new(mut ctx: ExExContext<Node>) -> eyre::Result<Self> {
+        let number = ctx.provider().trace_index_tip()?.unwrap_or_default();
+        let hash = ctx
+            .provider()
+            .block_hash(number)?
+            .ok_or_else(|| eyre::eyre!("missing hash of trace index tip {number}"))?;
+        ctx.set_notifications_with_head(ExExHead { block: BlockNumHash::new(number, hash) });
+        Ok(Self { ctx, unpersisted: Vec::new(), pending: BTreeMap::new() })
+    }
+
+    /// Processes the notifications of the node until the notification stream ends.
+    pub async fn run(mut self) -> eyre::Result<()> {
+        while let Some(notification) = self.ctx.notifications.try_next().await? {
+            self.on_notification(&notification)?;
+        }
+
+        Ok(())
+    }
+
+    /// Reverts and commits the chains of the notification, then writes all blocks that were
+    /// persisted in the meantime to the index.
+    fn on_notification(&mut self, notification: &ExExNotification) -> eyre::Result<()> {
+        if let Some(reverted) = notification.reverted_chain() {
+            self.revert(&reverted)?;
+        }
+        if let Some(committed) = notification.committed_chain() {
+            self.commit(committed)?;
+        }
+        self.persist()
+    }
+
+    /// Drops the pending blocks of the reverted chain and unwinds the index if it already covers
+    /// some of them.
+    fn revert(&mut self, chain: &Chain) -> eyre::Result<()> {
+        let fork = chain.fork_block().number;
+        self.pending.retain(|number, _| *number <= fork);
+        self.unpersisted.retain(|unpersisted| unpersisted.first().number <= fork);
+
+        let provider = self.ctx.provider();
+        if provider.trace_index_tip()?.unwrap_or_default() > fork {
+            // the fork block is below the tip of the index, hence persisted
+            let appearances = self.trace_chain(chain, &[])?;
+            let addresses = appearances
+                .into_values()
+                .flat_map(|(_, addresses)| addresses)
+                .collect::<BTreeSet<_>>();
+
+            let provider = provider.database_provider_rw()?;
+            provider.unwind_trace_index(addresses, fork)?;
+            provider.commit()?;
+            debug!(target: "exex::trace_index", tip = fork, "Unwound trace index");
+        }
+
+        Ok(())
+    }
+
+    /// Traces the blocks of the committed chain and keeps their appearances until the blocks are
+    /// persisted.
+    fn commit(&mut self, chain: Arc<Chain>) -> eyre::Result<()> {
+        let appearances = self.trace_chain(&chain, &self.unpersisted)?;
+        self.pending.extend(appearances);
+        self.unpersisted.push(chain);
+        Ok(())
+    }
+
+    /// Writes the pending blocks up to the highest block persisted by the node to the index.
+    fn persist(&mut self) -> eyre::Result<()> {
+        let provider = self.ctx.provider();
+        let persisted = provider
+            .get_stage_checkpoint(StageId::Finish)?
+            .map(|checkpoint| checkpoint.block_number)
+            .unwrap_or_default();
+
+        let Some((&number, &(hash, _))) = self.pending.range(..=persisted).next_back() else {
+            return Ok(())
+        };
+        if provider.block_hash(number)? != Some(hash) {
+            debug!(target: "exex::trace_index", number, %hash, "Pending block is not persisted");
+            return Ok(())
+        }
+
+        let pending = self.pending.split_off(&(number + 1));
+        let mut appearances = BTreeMap::<Address, Vec<BlockNumber>>::new();
+        for (block_number, (_, addresses)) in std::mem::replace(&mut self.pending, pending) {
+            for address in addresses {
+                appearances.entry(address).or_default().push(block_number);
+            }
+        }
+
+        let provider = provider.database_provider_rw()?;
+        provider.insert_trace_index(appearances, number)?;
+        provider.commit()?;
+        debug!(target: "exex::trace_index", tip = number, "Extended trace index");
+
+        self.unpersisted.retain(|chain| chain.tip().number > number);
+        self.ctx.events.send(ExExEvent::FinishedHeight(BlockNumHash::new(number, hash)))?;
+
+        Ok(())
+    }
+
+    /// Re-executes all blocks of the chain and returns, for every block, its hash and the
+    /// addresses that appeared in its traces.
+    ///
+    /// The state of the fork block is read from the given unpersisted chains, or from the
+    /// database if it's not part of them.
+    fn trace_chain(
+        &self,
+        chain: &Chain,
+        unpersisted: &[Arc<Chain>],
+    ) -> eyre::Result<BTreeMap<BlockNumber, (BlockHash, BTreeSet<Address>)>> {
+        // the parent states are not necessarily persisted, so they are rebuilt from the fork block
+        // and the execution outcome of the chain, which is reverted one block at a time starting
+        // from the tip
+        let fork_state = self.state_at(unpersisted, chain.fork_block())?;
+        let mut state = BundleStateProvider::new(fork_state, chain.execution_outcome().clone());
+
+        let mut appearances = BTreeMap::new();
+        for block in chain.blocks().values().rev() {
+            let addresses =
+                if state.block_execution_data_provider.revert_to(block.number.saturating_sub(1)) {
+                    self.trace_block(block, &state)?
+                } else {
+                    // the parent is the fork block
+                    self.trace_block(block, &state.state_provider)?
+                };
+            appearances.insert(block.number, (block.hash(), addresses));
+        }
+
+        Ok(appearances)
+    }
+
+    /// Returns the state after the given block, using the execution outcome of the unpersisted
+    /// chain that contains it, or the database otherwise.
+    fn state_at(
+        &self,
+        unpersisted: &[Arc<Chain>],
+        block: BlockNumHash,
+    ) -> eyre::Result<StateProviderBox> {
+        for (index, chain) in unpersisted.iter().enumerate().rev() {
+            if chain.block_number(block.hash).is_some() {
+                let outcome = chain.execution_outcome_at_block(block.number).ok_or_else(|| {
+                    eyre::eyre!("missing execution outcome of block {}", block.number)
+                })?;
+                let fork_state = self.state_at(&unpersisted[..index], chain.fork_block())?;
+                return Ok(Box::new(BundleStateProvider::new(fork_state, outcome)))
+            }
+        }
+
+        Ok(self.ctx.provider().history_by_block_hash(block.hash)?)
+    }
+
+    /// Re-executes the block on top of the given state and returns all addresses that appeared in
+    /// its traces, including the block and ommer beneficiaries.
+    fn trace_block(
+        &self,
+        block: &SealedBlockWithSenders,
+        parent_state: impl StateProvider,
+    ) -> eyre::Result<BTreeSet<Address>> {
+        let provider = self.ctx.provider();
+        let evm_config = self.ctx.evm_config();
+
+        let (cfg, block_env) = provider.env_with_header(&block.header, evm_config.clone())?;
+        let mut db = CacheDB::new(StateProviderDatabase::new(parent_state));
+
+        SystemCaller::new(evm_config.clone(), provider.chain_spec())
+            .pre_block_beacon_root_contract_call(
+                &mut db,
+                &cfg,
+                &block_env,
+                block.parent_beacon_block_root,
+            )?;
+
+        let mut addresses = BTreeSet::from([block.beneficiary]);
+        addresses.extend(block.body.ommers.iter().map(|ommer| ommer.beneficiary));
+
+        for (signer, tx) in block.transactions_with_sender() {
+            let env = EnvWithHandlerCfg::new_with_cfg_env(
+                cfg.clone(),
+                block_env.clone(),
+                evm_config.tx_env(tx, *signer),
+            );
+            let mut inspector = TracingInspector::new(TracingInspectorConfig::default_parity());
+            let ResultAndState { state, .. } =
+                evm_config.evm_with_env_and_inspector(&mut db, env, &mut inspector).transact()?;
+            db.commit(state);
+
+            for trace in inspector.into_parity_builder().into_transaction_traces() {
+                match trace.action {
+                    Action::Call(call) => addresses.extend([call.from, call.to]),
+                    Action::Create(create) => {
+                        addresses.insert(create.from);
+                    }
+                    Action::Selfdestruct(selfdestruct) => {
+                        addresses.extend([selfdestruct.address, selfdestruct.refund_address])
+                    }
+                    Action::Reward(reward) => {
+                        addresses.insert(reward.author);
+                    }
+                }
+                if let Some(TraceOutput::Create(created)) = trace.result {
+                    addresses.insert(created.address);
+                }
+            }
+        }
+
+        Ok(addresses)
+    }
+}
+
+impl<Node: FullNodeComponents> std::fmt::Debug for TraceIndexExEx<Node> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TraceIndexExEx").field("head", &self.ctx.head).finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::{B256, U256};
+    use reth_db::{tables, transaction::DbTxMut};
+    use reth_execution_types::ExecutionOutcome;
+    use reth_exex_test_utils::test_exex_context;
+    use reth_primitives::{Block, Header};
+    use reth_provider::{
+        providers::ProviderNodeTypes, BlockWriter, ProviderFactory, StageCheckpointWriter,
+    };
+    use reth_stages_types::StageCheckpoint;
+
+    fn block(
+        number: BlockNumber,
+        parent_hash: B256,
+        beneficiary: Address,
+    ) -> SealedBlockWithSenders {
+        Block {
+            header: Header { number, parent_hash, beneficiary, ..Default::default() },
+            body: Default::default(),
+        }
+        .seal_slow()
+        .seal_with_senders()
+        .expect("block without transactions")
+    }
+
+    fn chain(blocks: Vec<SealedBlockWithSenders>) -> Arc<Chain> {
+        let first = blocks[0].number;
+        let receipts = std::iter::repeat(Vec::new()).take(blocks.len()).collect();
+        let outcome = ExecutionOutcome::new(Default::default(), receipts, first, Vec::new());
+        Arc::new(Chain::new(blocks, outcome, None))
+    }
+
+    /// Persists the blocks the same way the node does, and moves the persisted height to the
+    /// last one.
+    fn persist_blocks<N: ProviderNodeTypes>(
+        factory: &ProviderFactory<N>,
+        blocks: &[&SealedBlockWithSenders],
+    ) -> eyre::Result<()> {
+        let provider = factory.provider_rw()?;
+        for block in blocks {
+            provider.insert_block((*block).clone())?;
+        }
+        let tip = blocks.last().expect("blocks").number;
+        provider.save_stage_checkpoint(StageId::Finish, StageCheckpoint::new(tip))?;
+        provider.commit()?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn commit_revert_restart() -> eyre::Result<()> {
+        let (ctx, mut handle) = test_exex_context().await?;
+        let factory = handle.provider_factory.clone();
+        let address = |byte| Address::with_last_byte(byte);
+
+        let block_1 = block(1, handle.genesis.hash(), address(1));
+        let block_2 = block(2, block_1.hash(), address(2));
+        let block_3 = block(3, block_2.hash(), address(3));
+        let block_2_reorg = block(2, block_1.hash(), address(4));
+        let block_3_reorg = block(3, block_2_reorg.hash(), address(5));
+        let addresses = (1..=5).map(address).collect::<Vec<_>>();
+
+        // executing a block requires the total difficulty of its header, also for blocks that are
+        // only kept in memory
+        let provider = factory.provider_rw()?;
+        for number in 1..=3 {
+            provider
+                .tx_ref()
+                .put::<tables::HeaderTerminalDifficulties>(number, U256::ZERO.into())?;
+        }
+        provider.commit()?;
+
+        let mut exex = TraceIndexExEx::new(ctx)?;
+
+        // blocks 1 and 2 are persisted, block 3 only exists in memory and is not indexed yet
+        persist_blocks(&factory, &[&block_1, &block_2])?;
+        exex.on_notification(&ExExNotification::ChainCommitted {
+            new: chain(vec![block_1.clone(), block_2.clone(), block_3.clone()]),
+        })?;
+        handle.assert_event_finished_height(block_2.num_hash())?;
+        assert_eq!(factory.trace_index_tip()?, Some(2));
+        assert_eq!(factory.trace_index_blocks(&addresses, 0..=3)?, vec![1, 2]);
+
+        // the node unwinds block 2 and replaces it with an in-memory block, the reorged block 2
+        // has to be removed from the index and the new one must not be indexed yet
+        let provider = factory.provider_rw()?;
+        provider.tx_ref().delete::<tables::CanonicalHeaders>(2, None)?;
+        provider.tx_ref().delete::<tables::HeaderNumbers>(block_2.hash(), None)?;
+        provider.save_stage_checkpoint(StageId::Finish, StageCheckpoint::new(1))?;
+        provider.commit()?;
+        exex.on_notification(&ExExNotification::ChainReorged {
+            old: chain(vec![block_2, block_3]),
+            new: chain(vec![block_2_reorg.clone()]),
+        })?;
+        handle.assert_events_empty();
+        assert_eq!(factory.trace_index_tip()?, Some(1));
+        assert_eq!(factory.trace_index_blocks(&addresses, 0..=3)?, vec![1]);
+
+        // the new block 3 is executed on top of the in-memory state of block 2
+        exex.on_notification(&ExExNotification::ChainCommitted {
+            new: chain(vec![block_3_reorg.clone()]),
+        })?;
+        handle.assert_events_empty();
+        assert_eq!(factory.trace_index_tip()?, Some(1));
+
+        // after a crash the index resumes from its tip, which is still a persisted block
+        let mut exex = TraceIndexExEx::new(exex.ctx)?;
+        assert_eq!(factory.block_hash(1)?, Some(block_1.hash()));
+
+        // the unpersisted blocks are committed again and indexed once they are persisted
+        persist_blocks(&factory, &[&block_2_reorg, &block_3_reorg])?;
+        exex.on_notification(&ExExNotification::ChainCommitted {
+            new: chain(vec![block_2_reorg, block_3_reorg.clone()]),
+        })?;
+        handle.assert_event_finished_height(block_3_reorg.num_hash())?;
+        assert_eq!(factory.trace_index_tip()?, Some(3));
+        assert_eq!(factory.trace_index_blocks(&addresses, 0..=3)?, vec![1, 2, 3]);
+        assert_eq!(
+            factory.trace_index_blocks(&[address(2), address(3)], 0..=3)?,
+            Vec::<BlockNumber>::new()
+        );
+        assert_eq!(factory.trace_index_blocks(&[address(4)], 0..=3)?, vec![2]);
+
+        Ok(())
+    }
+}
@@ -112,6 +112,16 @@ impl BanList {
         self.banned_peers.contains_key(peer_id)
     }
 
+    /// Returns the timestamp until which the peer is banned, if it is banned temporarily.
+    pub fn peer_banned_until(&self, peer_id: &PeerId) -> Option<Instant> {
+        self.banned_peers.get(peer_id).copied().flatten()
+    }
+
+    /// Returns an iterator over all temporarily banned IPs and until when they are banned.
+    pub fn temporarily_banned_ips(&self) -> impl Iterator<Item = (IpAddr, Instant)> + '_ {
+        self.banned_ips.iter().filter_map(|(ip, until)| until.map(|until| (*ip, until)))
+    }
+
     /// Unbans the ip address
     pub fn unban_ip(&mut self, ip: &IpAddr) {
         self.banned_ips.remove(ip);
@@ -185,6 +195,22 @@ mod tests {
         assert!(!banlist.is_banned_ip(&ip));
     }
 
+    #[test]
+    fn temporary_bans() {
+        let peer = PeerId::random();
+        let ip = IpAddr::from([1, 1, 1, 1]);
+        let until = Instant::now();
+        let mut banlist = BanList::default();
+        banlist.ban_peer(peer);
+        banlist.ban_ip(IpAddr::from([2, 2, 2, 2]));
+        assert_eq!(banlist.peer_banned_until(&peer), None);
+
+        banlist.ban_peer_until(peer, until);
+        banlist.ban_ip_until(ip, until);
+        assert_eq!(banlist.peer_banned_until(&peer), Some(until));
+        assert_eq!(banlist.temporarily_banned_ips().collect::<Vec<_>>(), vec![(ip, until)]);
+    }
+
     #[test]
     fn cannot_ban_non_global() {
         let mut ip = IpAddr::from([0, 0, 0, 0]);
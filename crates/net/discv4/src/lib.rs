@@ -374,6 +374,23 @@ impl Discv4 {
         self.send_to_service(cmd);
     }
 
+    /// Sets the advertised udp port
+    ///
+    /// This will update our [`NodeRecord`]'s udp port and the udp port of the EIP-868 [`Enr`].
+    /// The port the service is bound to is not changed.
+    pub fn set_udp_port(&self, port: u16) {
+        let cmd = Discv4Command::SetUdpPort(port);
+        self.send_to_service(cmd);
+    }
+
+    /// Sets the external IP address
+    ///
+    /// This will update our [`NodeRecord`]'s address and the IP of the EIP-868 [`Enr`].
+    pub fn set_external_ip_addr(&self, external_ip: IpAddr) {
+        let cmd = Discv4Command::SetExternalIpAddr(external_ip);
+        self.send_to_service(cmd);
+    }
+
     /// Sets the pair in the EIP-868 [`Enr`] of the node.
     ///
     /// If the key already exists, this will update it.
@@ -625,6 +642,21 @@ impl Discv4Service {
         }
     }
 
+    /// Sets the given port as the node's udp port in the node record announced in discovery
+    pub fn set_udp_port(&mut self, port: u16) {
+        if self.local_node_record.udp_port != port {
+            debug!(target: "discv4", %port, "Update udp port");
+            self.local_node_record.udp_port = port;
+            if self.local_node_record.address.is_ipv4() {
+                let _ = self.local_eip_868_enr.set_udp4(port, &self.secret_key);
+            } else {
+                let _ = self.local_eip_868_enr.set_udp6(port, &self.secret_key);
+            }
+            let mut lock = self.shared_node_record.lock();
+            *lock = self.local_node_record;
+        }
+    }
+
     /// Returns the [`PeerId`] that identifies this node
     pub const fn local_peer_id(&self) -> &PeerId {
         &self.local_node_record.id
@@ -1649,6 +1681,12 @@ impl Discv4Service {
                             let _ = self.local_eip_868_enr.set_tcp6(port, &self.secret_key);
                         }
                     }
+                    Discv4Command::SetUdpPort(port) => {
+                        self.set_udp_port(port);
+                    }
+                    Discv4Command::SetExternalIpAddr(ip) => {
+                        self.set_external_ip_addr(ip);
+                    }
 
                     Discv4Command::Terminated => {
                         // terminate the service
@@ -1930,6 +1968,8 @@ impl Default for ReceiveCache {
 enum Discv4Command {
     Add(NodeRecord),
     SetTcpPort(u16),
+    SetUdpPort(u16),
+    SetExternalIpAddr(IpAddr),
     SetEIP868RLPPair { key: Vec<u8>, rlp: Bytes },
     Ban(PeerId, IpAddr),
     BanPeer(PeerId),
@@ -2648,6 +2688,20 @@ mod tests {
         let _ = discv4.lookup_self().await;
     }
 
+    #[tokio::test]
+    async fn test_set_udp_port() {
+        let (discv4, mut service) = create_discv4().await;
+        let local_addr = service.local_addr();
+        let external_port = if local_addr.port() == 30303 { 30304 } else { 30303 };
+
+        service.set_udp_port(external_port);
+
+        assert_eq!(discv4.node_record().udp_port, external_port);
+        assert_eq!(service.local_eip_868_enr.udp4(), Some(external_port));
+        // the socket is not rebound
+        assert_eq!(service.local_addr(), local_addr);
+    }
+
     #[tokio::test]
     async fn test_requests_timeout() {
         reth_tracing::init_test_tracing();
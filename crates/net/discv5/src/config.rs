@@ -72,6 +72,9 @@ pub struct ConfigBuilder {
     /// NOTE: IP address of `RLPx` socket overwrites IP address of same IP version in
     /// [`discv5::ListenConfig`].
     tcp_socket: SocketAddr,
+    /// Additional `RLPx` TCP socket of the other IP version to advertise, if the node runs
+    /// dual-stack.
+    tcp_socket_dual_stack: Option<SocketAddr>,
     /// List of `(key, rlp-encoded-value)` tuples that should be advertised in local node record
     /// (in addition to tcp port, udp port and fork).
     other_enr_kv_pairs: Vec<(&'static [u8], Bytes)>,
@@ -95,6 +98,7 @@ impl ConfigBuilder {
             bootstrap_nodes,
             fork,
             tcp_socket,
+            tcp_socket_dual_stack,
             other_enr_kv_pairs,
             lookup_interval,
             bootstrap_lookup_interval,
@@ -107,6 +111,7 @@ impl ConfigBuilder {
             bootstrap_nodes,
             fork: fork.map(|(key, fork_id)| (key, fork_id.fork_id)),
             tcp_socket,
+            tcp_socket_dual_stack,
             other_enr_kv_pairs,
             lookup_interval: Some(lookup_interval),
             bootstrap_lookup_interval: Some(bootstrap_lookup_interval),
@@ -177,6 +182,14 @@ impl ConfigBuilder {
         self
     }
 
+    /// Sets an additional tcp socket of the other IP version to advertise in the local
+    /// [`Enr`](discv5::enr::Enr), making `RLPx` reachable over both IPv4 and IPv6. Ignored if it
+    /// has the same IP version as the socket set with [`tcp_socket`](Self::tcp_socket).
+    pub const fn tcp_socket_dual_stack(mut self, socket: SocketAddr) -> Self {
+        self.tcp_socket_dual_stack = Some(socket);
+        self
+    }
+
     /// Adds an additional kv-pair to include in the local [`Enr`](discv5::enr::Enr). Takes the key
     /// to use for the kv-pair and the rlp encoded value.
     pub fn add_enr_kv_pair(mut self, key: &'static [u8], value: Bytes) -> Self {
@@ -221,6 +234,7 @@ impl ConfigBuilder {
             bootstrap_nodes,
             fork,
             tcp_socket,
+            tcp_socket_dual_stack,
             other_enr_kv_pairs,
             lookup_interval,
             bootstrap_lookup_interval,
@@ -228,12 +242,19 @@ impl ConfigBuilder {
             discovered_peer_filter,
         } = self;
 
+        let tcp_socket_dual_stack =
+            tcp_socket_dual_stack.filter(|socket| socket.is_ipv4() != tcp_socket.is_ipv4());
+
         let mut discv5_config = discv5_config.unwrap_or_else(|| {
             discv5::ConfigBuilder::new(DEFAULT_DISCOVERY_V5_LISTEN_CONFIG).build()
         });
 
         discv5_config.listen_config =
             amend_listen_config_wrt_rlpx(&discv5_config.listen_config, tcp_socket.ip());
+        if let Some(socket) = tcp_socket_dual_stack {
+            discv5_config.listen_config =
+                amend_listen_config_wrt_rlpx(&discv5_config.listen_config, socket.ip());
+        }
 
         let fork = fork.map(|(key, fork_id)| (key, fork_id.into()));
 
@@ -251,6 +272,7 @@ impl ConfigBuilder {
             bootstrap_nodes,
             fork,
             tcp_socket,
+            tcp_socket_dual_stack,
             other_enr_kv_pairs,
             lookup_interval,
             bootstrap_lookup_interval,
@@ -276,6 +298,8 @@ pub struct Config {
     /// NOTE: IP address of `RLPx` socket overwrites IP address of same IP version in
     /// [`discv5::ListenConfig`].
     pub(super) tcp_socket: SocketAddr,
+    /// `RLPx` TCP socket of the other IP version to advertise, if the node runs dual-stack.
+    pub(super) tcp_socket_dual_stack: Option<SocketAddr>,
     /// Additional kv-pairs (besides tcp port, udp port and fork) that should be advertised to
     /// peers by including in local node record.
     pub(super) other_enr_kv_pairs: Vec<(&'static [u8], Bytes)>,
@@ -300,6 +324,7 @@ impl Config {
             bootstrap_nodes: HashSet::default(),
             fork: None,
             tcp_socket: rlpx_tcp_socket,
+            tcp_socket_dual_stack: None,
             other_enr_kv_pairs: Vec::new(),
             lookup_interval: None,
             bootstrap_lookup_interval: None,
@@ -345,6 +370,11 @@ impl Config {
     pub const fn rlpx_socket(&self) -> &SocketAddr {
         &self.tcp_socket
     }
+
+    /// Returns the `RLPx` (TCP) socket of the other IP version, if the node runs dual-stack.
+    pub const fn rlpx_socket_dual_stack(&self) -> Option<&SocketAddr> {
+        self.tcp_socket_dual_stack.as_ref()
+    }
 }
 
 /// Returns the IPv4 discovery socket if one is configured.
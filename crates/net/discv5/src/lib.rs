@@ -155,6 +155,13 @@ impl Discv5 {
         enr.try_into().ok()
     }
 
+    /// Returns the [`Enr`] of the given peer, if the peer is in the local routing table or has an
+    /// active session.
+    pub fn find_enr(&self, peer_id: PeerId) -> Option<Enr<SecretKey>> {
+        let node_id = discv4_id_to_discv5_id(peer_id).ok()?;
+        self.discv5.find_enr(&node_id).map(|enr| EnrCombinedKeyWrapper(enr).into())
+    }
+
     /// Spawns [`discv5::Discv5`]. Returns [`discv5::Discv5`] handle in reth compatible wrapper type
     /// [`Discv5`], a receiver of [`discv5::Event`]s from the underlying node, and the local
     /// [`Enr`](discv5::Enr) converted into the reth compatible [`NodeRecord`] type.
@@ -330,7 +337,12 @@ impl Discv5 {
             "discovered peer"
         );
 
-        Some(DiscoveredPeer { node_record, fork_id })
+        // don't fall back to the socket we already dial
+        let fallback_tcp = self
+            .fallback_rlpx_socket(enr, socket)
+            .filter(|fallback| *fallback != node_record.tcp_addr());
+
+        Some(DiscoveredPeer { node_record, fork_id, fallback_tcp })
     }
 
     /// Tries to convert an [`Enr`](discv5::Enr) into the backwards compatible type [`NodeRecord`],
@@ -348,14 +360,62 @@ impl Discv5 {
         let Some(tcp_port) = (match self.rlpx_ip_mode {
             IpMode::Ip4 => enr.tcp4(),
             IpMode::Ip6 => enr.tcp6(),
-            _ => unimplemented!("dual-stack support not implemented for rlpx"),
+            // prefer the IP version the peer was reached at over discovery
+            IpMode::DualStack => {
+                if socket.is_ipv4() {
+                    enr.tcp4()
+                } else {
+                    enr.tcp6()
+                }
+            }
         }) else {
+            // the peer doesn't advertise rlpx over the IP version of the source socket, try to
+            // reach it over the other IP version instead
+            if matches!(self.rlpx_ip_mode, IpMode::DualStack) {
+                if let Some(tcp) = self.fallback_rlpx_socket(enr, socket) {
+                    let udp_port = match tcp {
+                        SocketAddr::V4(_) => enr.udp4(),
+                        SocketAddr::V6(_) => enr.udp6(),
+                    };
+                    return Ok(NodeRecord {
+                        address: tcp.ip(),
+                        tcp_port: tcp.port(),
+                        udp_port: udp_port.unwrap_or(tcp.port()),
+                        id,
+                    })
+                }
+            }
+
             return Err(Error::IpVersionMismatchRlpx(self.rlpx_ip_mode))
         };
 
         Ok(NodeRecord { address: socket.ip(), tcp_port, udp_port: socket.port(), id })
     }
 
+    /// Returns the `RLPx` socket the [`Enr`](discv5::Enr) advertises for the IP version other than
+    /// that of the given source socket. Only returns a socket if the local `RLPx` network runs
+    /// dual-stack, in which case this can be used to fall back to the other IP version if the peer
+    /// is unreachable over the first one.
+    pub fn fallback_rlpx_socket(
+        &self,
+        enr: &discv5::Enr,
+        socket: SocketAddr,
+    ) -> Option<SocketAddr> {
+        if !matches!(self.rlpx_ip_mode, IpMode::DualStack) {
+            return None
+        }
+        match socket {
+            SocketAddr::V4(_) => {
+                let ip = enr.ip6().filter(|ip| !ip.is_unspecified())?;
+                Some((ip, enr.tcp6()?).into())
+            }
+            SocketAddr::V6(_) => {
+                let ip = enr.ip4().filter(|ip| !ip.is_unspecified())?;
+                Some((ip, enr.tcp4()?).into())
+            }
+        }
+    }
+
     /// Applies filtering rules on an ENR. Returns [`Ok`](FilterOutcome::Ok) if peer should be
     /// passed up to app, and [`Ignore`](FilterOutcome::Ignore) if peer should instead be dropped.
     pub fn filter_discovered_peer(&self, enr: &discv5::Enr) -> FilterOutcome {
@@ -417,6 +477,10 @@ pub struct DiscoveredPeer {
     pub node_record: NodeRecord,
     /// [`ForkId`] extracted from ENR w.r.t. configured
     pub fork_id: Option<ForkId>,
+    /// `RLPx` socket of the other IP version, advertised in the ENR, to fall back to if the peer
+    /// is unreachable at the socket in the node record. Only set if the local `RLPx` network runs
+    /// dual-stack.
+    pub fallback_tcp: Option<SocketAddr>,
 }
 
 /// Builds the local ENR with the supplied key.
@@ -426,7 +490,9 @@ pub fn build_local_enr(
 ) -> (Enr<SecretKey>, NodeRecord, Option<&'static [u8]>, IpMode) {
     let mut builder = discv5::enr::Enr::builder();
 
-    let Config { discv5_config, fork, tcp_socket, other_enr_kv_pairs, .. } = config;
+    let Config {
+        discv5_config, fork, tcp_socket, tcp_socket_dual_stack, other_enr_kv_pairs, ..
+    } = config;
 
     let socket = match discv5_config.listen_config {
         ListenConfig::Ipv4 { ip, port } => {
@@ -434,7 +500,6 @@ pub fn build_local_enr(
                 builder.ip4(ip);
             }
             builder.udp4(port);
-            builder.tcp4(tcp_socket.port());
 
             (ip, port).into()
         }
@@ -443,7 +508,6 @@ pub fn build_local_enr(
                 builder.ip6(ip);
             }
             builder.udp6(port);
-            builder.tcp6(tcp_socket.port());
 
             (ip, port).into()
         }
@@ -452,7 +516,6 @@ pub fn build_local_enr(
                 builder.ip4(ipv4);
             }
             builder.udp4(ipv4_port);
-            builder.tcp4(tcp_socket.port());
 
             if ipv6 != Ipv6Addr::UNSPECIFIED {
                 builder.ip6(ipv6);
@@ -463,7 +526,19 @@ pub fn build_local_enr(
         }
     };
 
-    let rlpx_ip_mode = if tcp_socket.is_ipv4() { IpMode::Ip4 } else { IpMode::Ip6 };
+    // advertise rlpx, over both IP versions if the node runs dual-stack
+    for tcp_socket in std::iter::once(tcp_socket).chain(tcp_socket_dual_stack) {
+        match tcp_socket {
+            SocketAddr::V4(socket) => builder.tcp4(socket.port()),
+            SocketAddr::V6(socket) => builder.tcp6(socket.port()),
+        };
+    }
+
+    let rlpx_ip_mode = match (tcp_socket, tcp_socket_dual_stack) {
+        (_, Some(_)) => IpMode::DualStack,
+        (SocketAddr::V4(_), None) => IpMode::Ip4,
+        (SocketAddr::V6(_), None) => IpMode::Ip6,
+    };
 
     // identifies which network node is on
     let network_stack_id = fork.as_ref().map(|(network_stack_id, fork_value)| {
@@ -660,6 +735,10 @@ mod test {
     use tracing::trace;
 
     fn discv5_noop() -> Discv5 {
+        discv5_noop_with_ip_mode(IpMode::Ip4)
+    }
+
+    fn discv5_noop_with_ip_mode(rlpx_ip_mode: IpMode) -> Discv5 {
         let sk = CombinedKey::generate_secp256k1();
         Discv5 {
             discv5: Arc::new(
@@ -670,7 +749,7 @@ mod test {
                 )
                 .unwrap(),
             ),
-            rlpx_ip_mode: IpMode::Ip4,
+            rlpx_ip_mode,
             fork_key: None,
             discovered_peer_filter: MustNotIncludeKeys::default(),
             metrics: Discv5Metrics::default(),
@@ -774,6 +853,48 @@ mod test {
         )
     }
 
+    #[test]
+    fn discovered_enr_dual_stack_fallback() {
+        reth_tracing::init_test_tracing();
+
+        // rig test
+        const REMOTE_RLPX_PORT_IPV4: u16 = 30303;
+        const REMOTE_RLPX_PORT_IPV6: u16 = 30304;
+        let remote_socket: SocketAddr = "104.28.44.25:9000".parse().unwrap();
+        let remote_ipv6: Ipv6Addr = "2001:db8::1".parse().unwrap();
+        let remote_key = CombinedKey::generate_secp256k1();
+        let remote_enr = Enr::builder()
+            .tcp4(REMOTE_RLPX_PORT_IPV4)
+            .ip6(remote_ipv6)
+            .tcp6(REMOTE_RLPX_PORT_IPV6)
+            .build(&remote_key)
+            .unwrap();
+
+        // test
+
+        // single stack doesn't fall back
+        let discv5 = discv5_noop();
+        let peer = discv5.on_discovered_peer(&remote_enr, remote_socket).unwrap();
+        assert_eq!(peer.fallback_tcp, None);
+
+        // dual-stack dials over the IP version of the source socket and falls back to the other
+        let discv5 = discv5_noop_with_ip_mode(IpMode::DualStack);
+        let peer = discv5.on_discovered_peer(&remote_enr, remote_socket).unwrap();
+        assert_eq!(
+            peer.node_record.tcp_addr(),
+            SocketAddr::new(remote_socket.ip(), REMOTE_RLPX_PORT_IPV4)
+        );
+        assert_eq!(peer.fallback_tcp, Some((remote_ipv6, REMOTE_RLPX_PORT_IPV6).into()));
+
+        // dual-stack dials over the other IP version if the peer doesn't advertise rlpx over the
+        // IP version of the source socket
+        let remote_enr =
+            Enr::builder().ip6(remote_ipv6).tcp6(REMOTE_RLPX_PORT_IPV6).build(&remote_key).unwrap();
+        let peer = discv5.on_discovered_peer(&remote_enr, remote_socket).unwrap();
+        assert_eq!(peer.node_record.tcp_addr(), (remote_ipv6, REMOTE_RLPX_PORT_IPV6).into());
+        assert_eq!(peer.fallback_tcp, None);
+    }
+
     // Copied from sigp/discv5 with slight modification (U256 type)
     // <https://github.com/sigp/discv5/blob/master/src/kbucket/key.rs#L89-L101>
     #[allow(unreachable_pub)]
@@ -894,4 +1015,22 @@ mod test {
         assert_eq!(fork_id, decoded_fork_id);
         assert_eq!(TCP_PORT, enr.tcp4().unwrap()); // listen config is defaulting to ip mode ipv4
     }
+
+    #[test]
+    fn build_dual_stack_enr_from_config() {
+        const TCP_PORT: u16 = 30303;
+
+        let config = Config::builder((Ipv4Addr::UNSPECIFIED, TCP_PORT).into())
+            .tcp_socket_dual_stack((Ipv6Addr::UNSPECIFIED, TCP_PORT).into())
+            .build();
+
+        let sk = SecretKey::new(&mut thread_rng());
+        let (enr, _, _, ip_mode) = build_local_enr(&sk, &config);
+
+        assert!(matches!(ip_mode, IpMode::DualStack));
+        assert_eq!(TCP_PORT, enr.tcp4().unwrap());
+        assert_eq!(TCP_PORT, enr.tcp6().unwrap());
+        assert!(enr.udp4().is_some());
+        assert!(enr.udp6().is_some());
+    }
 }
@@ -22,6 +22,7 @@ use crate::{
 pub use config::DnsDiscoveryConfig;
 use enr::Enr;
 pub use error::ParseDnsEntryError;
+pub use publish::DnsTree;
 use reth_ethereum_forks::{EnrForkIdEntry, ForkId};
 use reth_network_peers::{pk2id, NodeRecord};
 use schnellru::{ByLength, LruMap};
@@ -51,6 +52,7 @@ use tracing::{debug, trace};
 
 mod config;
 mod error;
+pub mod publish;
 mod query;
 pub mod resolver;
 mod sync;
@@ -0,0 +1,230 @@
+//! Building and signing of [EIP-1459](https://eips.ethereum.org/EIPS/eip-1459) node lists.
+//!
+//! A [`DnsTree`] is the publishing side of the ENR tree: node records and links are arranged into
+//! a merkle tree of TXT records, the root of which is signed with the tree's key. The records can
+//! then be rendered as a DNS zone file, see [`DnsTree::to_zone_file`].
+
+use crate::tree::{BranchEntry, LinkEntry, NodeEntry, TreeRootEntry};
+use alloy_primitives::{keccak256, Bytes};
+use data_encoding::BASE32_NOPAD;
+use enr::Enr;
+use secp256k1::{Message, SecretKey, SECP256K1};
+use std::{collections::BTreeMap, fmt::Write};
+
+/// Size in bytes of the hash abbreviation used as subdomain of a tree entry.
+const HASH_ABBREV_SIZE: usize = 16;
+
+/// Maximum number of children of a branch entry, so that the entry fits into a single TXT record.
+///
+/// A TXT record holds 370 bytes of content and each child takes up 26 bytes of base32-encoded hash
+/// abbreviation plus a separating comma.
+const MAX_CHILDREN: usize = 370 / (HASH_ABBREV_SIZE * 8 / 5 + 1);
+
+/// Maximum length of a single character-string in a TXT record, see RFC 1035.
+const MAX_TXT_STRING_LEN: usize = 255;
+
+/// Default TTL in seconds of the records in a rendered zone file.
+pub const DEFAULT_ZONE_TTL: u32 = 1800;
+
+/// A signed EIP-1459 ENR tree.
+#[derive(Debug, Clone)]
+pub struct DnsTree {
+    /// The signed root of the tree.
+    root: TreeRootEntry,
+    /// Public key of the key that signed the root.
+    pubkey: secp256k1::PublicKey,
+    /// All entries of the tree, keyed by subdomain.
+    entries: BTreeMap<String, String>,
+}
+
+// === impl DnsTree ===
+
+impl DnsTree {
+    /// Builds the tree from the given node records and links to other trees, and signs it with the
+    /// given key.
+    ///
+    /// Node records are sorted by node ID, so that the same set of records always results in the
+    /// same tree.
+    pub fn new(
+        enrs: impl IntoIterator<Item = Enr<SecretKey>>,
+        links: impl IntoIterator<Item = LinkEntry<SecretKey>>,
+        sequence_number: u64,
+        key: &SecretKey,
+    ) -> Self {
+        let mut enrs = enrs.into_iter().collect::<Vec<_>>();
+        enrs.sort_by_key(|enr| enr.node_id().raw());
+        enrs.dedup_by_key(|enr| enr.node_id().raw());
+
+        let mut links = links.into_iter().map(|link| link.to_string()).collect::<Vec<_>>();
+        links.sort();
+        links.dedup();
+
+        let mut entries = BTreeMap::new();
+        let enr_root = build_subtree(
+            enrs.into_iter().map(|enr| NodeEntry { enr }.to_string()).collect(),
+            &mut entries,
+        );
+        let link_root = build_subtree(links, &mut entries);
+
+        let mut root = TreeRootEntry {
+            enr_root: subdomain(&enr_root),
+            link_root: subdomain(&link_root),
+            sequence_number,
+            signature: Bytes::new(),
+        };
+        entries.insert(root.enr_root.clone(), enr_root);
+        entries.insert(root.link_root.clone(), link_root);
+
+        root.signature = sign_root(&root, key);
+
+        Self { root, pubkey: key.public_key(SECP256K1), entries }
+    }
+
+    /// Returns the signed root entry of the tree.
+    pub const fn root(&self) -> &TreeRootEntry {
+        &self.root
+    }
+
+    /// Returns the link to this tree, when published at the given domain.
+    pub fn link(&self, domain: impl Into<String>) -> LinkEntry<SecretKey> {
+        LinkEntry { domain: domain.into(), pubkey: self.pubkey }
+    }
+
+    /// Returns all TXT records of the tree, when published at the given domain, as pairs of fully
+    /// qualified name and record content.
+    ///
+    /// The first record is the root entry, published at the domain itself.
+    pub fn records(&self, domain: &str) -> Vec<(String, String)> {
+        let domain = domain.trim_end_matches('.');
+        std::iter::once((domain.to_string(), self.root.to_string()))
+            .chain(
+                self.entries
+                    .iter()
+                    .map(|(subdomain, entry)| (format!("{subdomain}.{domain}"), entry.clone())),
+            )
+            .collect()
+    }
+
+    /// Renders the tree as DNS zone file, when published at the given domain.
+    ///
+    /// Record content that exceeds the maximum length of a TXT character-string is split into
+    /// multiple strings.
+    pub fn to_zone_file(&self, domain: &str, ttl: u32) -> String {
+        let mut zone = String::new();
+        for (name, content) in self.records(domain) {
+            let strings = content
+                .as_bytes()
+                .chunks(MAX_TXT_STRING_LEN)
+                .map(|chunk| format!("\"{}\"", String::from_utf8_lossy(chunk)))
+                .collect::<Vec<_>>()
+                .join(" ");
+            let _ = writeln!(zone, "{name}. {ttl} IN TXT {strings}");
+        }
+        zone
+    }
+}
+
+/// Builds a subtree of the given entries, inserting all but the subtree root into `entries`.
+/// Returns the text of the subtree root.
+fn build_subtree(mut leaves: Vec<String>, entries: &mut BTreeMap<String, String>) -> String {
+    if leaves.len() == 1 {
+        return leaves.remove(0)
+    }
+
+    if leaves.len() <= MAX_CHILDREN {
+        let children = leaves
+            .into_iter()
+            .map(|leaf| {
+                let hash = subdomain(&leaf);
+                entries.insert(hash.clone(), leaf);
+                hash
+            })
+            .collect();
+        return BranchEntry { children }.to_string()
+    }
+
+    let mut subtrees = Vec::with_capacity(leaves.len().div_ceil(MAX_CHILDREN));
+    while !leaves.is_empty() {
+        let rest = leaves.split_off(leaves.len().min(MAX_CHILDREN));
+        let subtree = build_subtree(leaves, entries);
+        entries.insert(subdomain(&subtree), subtree.clone());
+        subtrees.push(subtree);
+        leaves = rest;
+    }
+    build_subtree(subtrees, entries)
+}
+
+/// Returns the subdomain of an entry, the base32 encoded abbreviated keccak256 hash of its text.
+fn subdomain(entry: &str) -> String {
+    BASE32_NOPAD.encode(&keccak256(entry.as_bytes())[..HASH_ABBREV_SIZE])
+}
+
+/// Returns the 65 byte recoverable signature over the root entry content.
+fn sign_root(root: &TreeRootEntry, key: &SecretKey) -> Bytes {
+    let msg = Message::from_digest(keccak256(root.content().as_bytes()).0);
+    let (rec_id, sig) = SECP256K1.sign_ecdsa_recoverable(&msg, key).serialize_compact();
+
+    let mut signature = sig.to_vec();
+    signature.push(rec_id.to_i32() as u8);
+    signature.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tree::DnsEntry;
+    use secp256k1::rand::thread_rng;
+    use std::net::Ipv4Addr;
+
+    fn enr(port: u16) -> Enr<SecretKey> {
+        let key = SecretKey::new(&mut thread_rng());
+        Enr::builder().ip4(Ipv4Addr::LOCALHOST).tcp4(port).udp4(port).build(&key).unwrap()
+    }
+
+    #[test]
+    fn build_and_verify_tree() {
+        let key = SecretKey::new(&mut thread_rng());
+        let enrs = (0..40).map(|i| enr(30303 + i)).collect::<Vec<_>>();
+        let tree = DnsTree::new(enrs.clone(), [], 1, &key);
+
+        // root is signed with the tree key
+        assert!(tree.root().verify::<SecretKey>(&tree.link("nodes.example.org").pubkey));
+        assert_eq!(tree.root().signature.len(), 65);
+
+        // every entry is published under its hash and parses
+        let records = tree.records("nodes.example.org");
+        let mut found = 0;
+        for (name, content) in &records[1..] {
+            let (hash, domain) = name.split_once('.').unwrap();
+            assert_eq!(domain, "nodes.example.org");
+            assert_eq!(hash, subdomain(content));
+            match content.parse::<DnsEntry<SecretKey>>().unwrap() {
+                DnsEntry::Node(entry) => {
+                    assert!(enrs.contains(&entry.enr));
+                    found += 1;
+                }
+                DnsEntry::Branch(entry) => assert!(entry.children.len() <= MAX_CHILDREN),
+                _ => unreachable!(),
+            }
+        }
+        assert_eq!(found, enrs.len());
+
+        // the root parses back
+        let root = records[0].1.parse::<TreeRootEntry>().unwrap();
+        assert_eq!(&root, tree.root());
+    }
+
+    #[test]
+    fn zone_file_splits_long_records() {
+        let key = SecretKey::new(&mut thread_rng());
+        let tree = DnsTree::new([enr(30303)], [], 1, &key);
+
+        let zone = tree.to_zone_file("nodes.example.org.", DEFAULT_ZONE_TTL);
+        assert!(zone.starts_with("nodes.example.org. 1800 IN TXT \"enrtree-root:v1 e="));
+        for line in zone.lines() {
+            for string in line.split('"').skip(1).step_by(2) {
+                assert!(string.len() <= MAX_TXT_STRING_LEN);
+            }
+        }
+    }
+}
@@ -127,7 +127,7 @@ impl TreeRootEntry {
     /// ```text
     /// e=<enr-root> l=<link-root> seq=<sequence-number> sig=<signature>
     /// ```
-    fn content(&self) -> String {
+    pub(crate) fn content(&self) -> String {
         format!(
             "{} e={} l={} seq={}",
             ROOT_V1_PREFIX, self.enr_root, self.link_root, self.sequence_number
@@ -210,8 +210,14 @@ impl BranchEntry {
             Ok(hash.to_string())
         }
 
+        // a branch without children is valid, e.g. the root of an empty link subtree
+        let input = input.trim();
+        if input.is_empty() {
+            return Ok(Self { children: Vec::new() })
+        }
+
         let children =
-            input.trim().split(',').map(ensure_valid_hash).collect::<ParseEntryResult<Vec<_>>>()?;
+            input.split(',').map(ensure_valid_hash).collect::<ParseEntryResult<Vec<_>>>()?;
         Ok(Self { children })
     }
 }
@@ -424,6 +430,14 @@ mod tests {
         assert!(res.is_err());
     }
 
+    #[test]
+    fn parse_empty_branch_entry() {
+        let s = "enrtree-branch:";
+        let entry: BranchEntry = s.parse().unwrap();
+        assert!(entry.children.is_empty());
+        assert_eq!(entry.to_string(), s);
+    }
+
     #[test]
     fn parse_link_entry() {
         let s = "enrtree://AM5FCQLWIZX2QFPNJAP7VUERCCRNGRHWZG3YYHIUV7BVDQ5FDPRT2@nodes.example.org";
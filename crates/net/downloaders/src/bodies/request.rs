@@ -109,7 +109,14 @@ where
         tracing::trace!(target: "downloaders::bodies", request_len = req.len(), "Requesting bodies");
         let client = Arc::clone(&self.client);
         self.last_request_len = Some(req.len());
-        self.fut = Some(client.get_block_bodies_with_priority(req, priority));
+        // hint the range of blocks so that the request is only routed to peers that serve it
+        let range_hint = self
+            .pending_headers
+            .front()
+            .zip(self.pending_headers.back())
+            .map(|(first, last)| first.number..=last.number);
+        self.fut =
+            Some(client.get_block_bodies_with_priority_and_range_hint(req, priority, range_hint));
     }
 
     /// Process block response.
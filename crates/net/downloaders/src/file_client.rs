@@ -12,7 +12,7 @@ use reth_network_p2p::{
     priority::Priority,
 };
 use reth_network_peers::PeerId;
-use reth_primitives::{BlockBody, Header, SealedHeader};
+use reth_primitives::{BlockBody, Header, SealedBlock, SealedHeader};
 use thiserror::Error;
 use tokio::{fs::File, io::AsyncReadExt};
 use tokio_stream::StreamExt;
@@ -92,6 +92,23 @@ impl FileClient {
         Ok(Self::from_reader(&reader[..], file_len).await?.file_client)
     }
 
+    /// Creates a new file client from blocks that have already been read.
+    pub fn from_blocks(blocks: impl IntoIterator<Item = SealedBlock>) -> Self {
+        let mut headers = HashMap::default();
+        let mut hash_to_number = HashMap::default();
+        let mut bodies = HashMap::default();
+
+        for block in blocks {
+            let (header, body) = block.split_header_body();
+            let (header, hash) = header.split();
+            hash_to_number.insert(hash, header.number);
+            headers.insert(header.number, header);
+            bodies.insert(hash, body);
+        }
+
+        Self { headers, hash_to_number, bodies }
+    }
+
     /// Get the tip hash of the chain.
     pub fn tip(&self) -> Option<B256> {
         self.headers.get(&self.max_block()?).map(|h| h.hash_slow())
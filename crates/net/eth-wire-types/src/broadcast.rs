@@ -58,6 +58,35 @@ pub struct BlockHashNumber {
     pub number: u64,
 }
 
+/// Announces the range of blocks a peer can serve, introduced in `eth/69`.
+///
+/// This replaces the block announcements of earlier versions and is sent whenever the served
+/// range of the peer changes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(arbitrary::Arbitrary))]
+#[add_arbitrary_tests(rlp)]
+pub struct BlockRangeUpdate {
+    /// The earliest block the peer can serve.
+    pub earliest: u64,
+    /// The latest block the peer can serve.
+    pub latest: u64,
+    /// The hash of the latest block.
+    pub latest_hash: B256,
+}
+
+impl BlockRangeUpdate {
+    /// Returns `true` if the range is well-formed, i.e. `earliest` does not exceed `latest`.
+    pub const fn is_valid(&self) -> bool {
+        self.earliest <= self.latest
+    }
+
+    /// Returns `true` if the given block is within the announced range.
+    pub const fn contains(&self, block: u64) -> bool {
+        self.earliest <= block && block <= self.latest
+    }
+}
+
 impl From<Vec<BlockHashNumber>> for NewBlockHashes {
     fn from(v: Vec<BlockHashNumber>) -> Self {
         Self(v)
@@ -154,7 +183,7 @@ impl NewPooledTransactionHashes {
                 matches!(version, EthVersion::Eth67 | EthVersion::Eth66)
             }
             Self::Eth68(_) => {
-                matches!(version, EthVersion::Eth68)
+                matches!(version, EthVersion::Eth68 | EthVersion::Eth69)
             }
         }
     }
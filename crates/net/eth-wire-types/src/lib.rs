@@ -9,7 +9,7 @@
 #![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
 
 mod status;
-pub use status::{Status, StatusBuilder};
+pub use status::{Status, StatusBuilder, StatusEth69, StatusMessage};
 
 pub mod version;
 pub use version::{EthVersion, ProtocolVersion};
@@ -40,3 +40,5 @@ pub use disconnect_reason::*;
 
 pub mod capability;
 pub use capability::*;
+
+pub mod snap;
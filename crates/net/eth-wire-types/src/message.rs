@@ -1,4 +1,4 @@
-//! Implements Ethereum wire protocol for versions 66, 67, and 68.
+//! Implements Ethereum wire protocol for versions 66, 67, 68 and 69.
 //! Defines structs/enums for messages, request-response pairs, and broadcasts.
 //! Handles compatibility with [`EthVersion`].
 //!
@@ -7,9 +7,10 @@
 //! Reference: [Ethereum Wire Protocol](https://github.com/ethereum/wiki/wiki/Ethereum-Wire-Protocol).
 
 use super::{
-    broadcast::NewBlockHashes, BlockBodies, BlockHeaders, GetBlockBodies, GetBlockHeaders,
-    GetNodeData, GetPooledTransactions, GetReceipts, NewBlock, NewPooledTransactionHashes66,
-    NewPooledTransactionHashes68, NodeData, PooledTransactions, Receipts, Status, Transactions,
+    broadcast::NewBlockHashes, BlockBodies, BlockHeaders, BlockRangeUpdate, GetBlockBodies,
+    GetBlockHeaders, GetNodeData, GetPooledTransactions, GetReceipts, NewBlock,
+    NewPooledTransactionHashes66, NewPooledTransactionHashes68, NodeData, PooledTransactions,
+    Receipts, Receipts69, Status, StatusEth69, StatusMessage, Transactions,
 };
 use crate::{EthVersion, SharedTransactions};
 
@@ -48,11 +49,26 @@ impl ProtocolMessage {
         let message_type = EthMessageID::decode(buf)?;
 
         let message = match message_type {
-            EthMessageID::Status => EthMessage::Status(Status::decode(buf)?),
+            EthMessageID::Status => {
+                let status = if version >= EthVersion::Eth69 {
+                    StatusMessage::Eth69(StatusEth69::decode(buf)?)
+                } else {
+                    StatusMessage::Legacy(Status::decode(buf)?)
+                };
+                EthMessage::Status(status)
+            }
             EthMessageID::NewBlockHashes => {
+                if version >= EthVersion::Eth69 {
+                    return Err(MessageError::Invalid(version, EthMessageID::NewBlockHashes))
+                }
                 EthMessage::NewBlockHashes(NewBlockHashes::decode(buf)?)
             }
-            EthMessageID::NewBlock => EthMessage::NewBlock(Box::new(NewBlock::decode(buf)?)),
+            EthMessageID::NewBlock => {
+                if version >= EthVersion::Eth69 {
+                    return Err(MessageError::Invalid(version, EthMessageID::NewBlock))
+                }
+                EthMessage::NewBlock(Box::new(NewBlock::decode(buf)?))
+            }
             EthMessageID::Transactions => EthMessage::Transactions(Transactions::decode(buf)?),
             EthMessageID::NewPooledTransactionHashes => {
                 if version >= EthVersion::Eth68 {
@@ -98,7 +114,7 @@ impl ProtocolMessage {
             }
             EthMessageID::NodeData => {
                 if version >= EthVersion::Eth67 {
-                    return Err(MessageError::Invalid(version, EthMessageID::GetNodeData))
+                    return Err(MessageError::Invalid(version, EthMessageID::NodeData))
                 }
                 let request_pair = RequestPair::<NodeData>::decode(buf)?;
                 EthMessage::NodeData(request_pair)
@@ -108,8 +124,19 @@ impl ProtocolMessage {
                 EthMessage::GetReceipts(request_pair)
             }
             EthMessageID::Receipts => {
-                let request_pair = RequestPair::<Receipts>::decode(buf)?;
-                EthMessage::Receipts(request_pair)
+                if version >= EthVersion::Eth69 {
+                    let request_pair = RequestPair::<Receipts69>::decode(buf)?;
+                    EthMessage::Receipts69(request_pair)
+                } else {
+                    let request_pair = RequestPair::<Receipts>::decode(buf)?;
+                    EthMessage::Receipts(request_pair)
+                }
+            }
+            EthMessageID::BlockRangeUpdate => {
+                if version < EthVersion::Eth69 {
+                    return Err(MessageError::Invalid(version, EthMessageID::BlockRangeUpdate))
+                }
+                EthMessage::BlockRangeUpdate(BlockRangeUpdate::decode(buf)?)
             }
         };
         Ok(Self { message_type, message })
@@ -162,7 +189,7 @@ impl From<EthBroadcastMessage> for ProtocolBroadcastMessage {
     }
 }
 
-/// Represents a message in the eth wire protocol, versions 66, 67 and 68.
+/// Represents a message in the eth wire protocol, versions 66, 67, 68 and 69.
 ///
 /// The ethereum wire protocol is a set of messages that are broadcast to the network in two
 /// styles:
@@ -179,11 +206,15 @@ impl From<EthBroadcastMessage> for ProtocolBroadcastMessage {
 /// The `eth/68` changes only `NewPooledTransactionHashes` to include `types` and `sized`. For
 /// it, `NewPooledTransactionHashes` is renamed as [`NewPooledTransactionHashes66`] and
 /// [`NewPooledTransactionHashes68`] is defined.
+///
+/// The `eth/69` replaces the total difficulty in the [`Status`] message with the served block
+/// range, see [`StatusEth69`], drops the bloom from receipts, see [`Receipts69`], removes
+/// [`NewBlockHashes`] and [`NewBlock`] and adds [`BlockRangeUpdate`].
 #[derive(Clone, Debug, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum EthMessage {
     /// Represents a Status message required for the protocol handshake.
-    Status(Status),
+    Status(StatusMessage),
     /// Represents a `NewBlockHashes` message broadcast to the network.
     NewBlockHashes(NewBlockHashes),
     /// Represents a `NewBlock` message broadcast to the network.
@@ -215,6 +246,10 @@ pub enum EthMessage {
     GetReceipts(RequestPair<GetReceipts>),
     /// Represents a Receipts request-response pair.
     Receipts(RequestPair<Receipts>),
+    /// Represents a Receipts request-response pair for eth/69, without blooms.
+    Receipts69(RequestPair<Receipts69>),
+    /// Represents a `BlockRangeUpdate` message broadcast to the network.
+    BlockRangeUpdate(BlockRangeUpdate),
 }
 
 impl EthMessage {
@@ -237,7 +272,8 @@ impl EthMessage {
             Self::GetNodeData(_) => EthMessageID::GetNodeData,
             Self::NodeData(_) => EthMessageID::NodeData,
             Self::GetReceipts(_) => EthMessageID::GetReceipts,
-            Self::Receipts(_) => EthMessageID::Receipts,
+            Self::Receipts(_) | Self::Receipts69(_) => EthMessageID::Receipts,
+            Self::BlockRangeUpdate(_) => EthMessageID::BlockRangeUpdate,
         }
     }
 }
@@ -261,6 +297,8 @@ impl Encodable for EthMessage {
             Self::NodeData(data) => data.encode(out),
             Self::GetReceipts(request) => request.encode(out),
             Self::Receipts(receipts) => receipts.encode(out),
+            Self::Receipts69(receipts) => receipts.encode(out),
+            Self::BlockRangeUpdate(range) => range.encode(out),
         }
     }
     fn length(&self) -> usize {
@@ -281,6 +319,8 @@ impl Encodable for EthMessage {
             Self::NodeData(data) => data.length(),
             Self::GetReceipts(request) => request.length(),
             Self::Receipts(receipts) => receipts.length(),
+            Self::Receipts69(receipts) => receipts.length(),
+            Self::BlockRangeUpdate(range) => range.length(),
         }
     }
 }
@@ -363,12 +403,18 @@ pub enum EthMessageID {
     GetReceipts = 0x0f,
     /// Represents receipts.
     Receipts = 0x10,
+    /// Announces the served block range, since eth/69.
+    BlockRangeUpdate = 0x11,
 }
 
 impl EthMessageID {
-    /// Returns the max value.
-    pub const fn max() -> u8 {
-        Self::Receipts as u8
+    /// Returns the max message id of the given version.
+    pub const fn max(version: EthVersion) -> u8 {
+        if version.is_eth69() {
+            Self::BlockRangeUpdate as u8
+        } else {
+            Self::Receipts as u8
+        }
     }
 }
 
@@ -399,6 +445,7 @@ impl Decodable for EthMessageID {
             0x0e => Self::NodeData,
             0x0f => Self::GetReceipts,
             0x10 => Self::Receipts,
+            0x11 => Self::BlockRangeUpdate,
             _ => return Err(alloy_rlp::Error::Custom("Invalid message ID")),
         };
         buf.advance(1);
@@ -426,6 +473,7 @@ impl TryFrom<usize> for EthMessageID {
             0x0e => Ok(Self::NodeData),
             0x0f => Ok(Self::GetReceipts),
             0x10 => Ok(Self::Receipts),
+            0x11 => Ok(Self::BlockRangeUpdate),
             _ => Err("Invalid message ID"),
         }
     }
@@ -494,7 +542,8 @@ where
 mod tests {
     use super::MessageError;
     use crate::{
-        message::RequestPair, EthMessage, EthMessageID, GetNodeData, NodeData, ProtocolMessage,
+        message::RequestPair, BlockRangeUpdate, EthMessage, EthMessageID, EthVersion, GetNodeData,
+        NewBlockHashes, NodeData, ProtocolMessage, Receipts, Receipts69, Status, StatusMessage,
     };
     use alloy_primitives::hex;
     use alloy_rlp::{Decodable, Encodable, Error};
@@ -524,6 +573,36 @@ mod tests {
         assert!(matches!(msg, Err(MessageError::Invalid(..))));
     }
 
+    #[test]
+    fn test_eth69_messages() {
+        let new_block_hashes = EthMessage::NewBlockHashes(NewBlockHashes(vec![]));
+        let buf = encode(ProtocolMessage::from(new_block_hashes));
+        let msg = ProtocolMessage::decode_message(EthVersion::Eth69, &mut &buf[..]);
+        assert!(matches!(msg, Err(MessageError::Invalid(..))));
+
+        let range = BlockRangeUpdate { earliest: 1, latest: 2, latest_hash: Default::default() };
+        let buf = encode(ProtocolMessage::from(EthMessage::BlockRangeUpdate(range)));
+        let msg = ProtocolMessage::decode_message(EthVersion::Eth68, &mut &buf[..]);
+        assert!(matches!(msg, Err(MessageError::Invalid(..))));
+        let msg = ProtocolMessage::decode_message(EthVersion::Eth69, &mut &buf[..]).unwrap();
+        assert_eq!(msg.message, EthMessage::BlockRangeUpdate(range));
+
+        let receipts = RequestPair { request_id: 1, message: Receipts69(vec![vec![]]) };
+        let buf = encode(ProtocolMessage::from(EthMessage::Receipts69(receipts.clone())));
+        let msg = ProtocolMessage::decode_message(EthVersion::Eth69, &mut &buf[..]).unwrap();
+        assert_eq!(msg.message, EthMessage::Receipts69(receipts));
+        let msg = ProtocolMessage::decode_message(EthVersion::Eth68, &mut &buf[..]).unwrap();
+        assert_eq!(
+            msg.message,
+            EthMessage::Receipts(RequestPair { request_id: 1, message: Receipts(vec![vec![]]) })
+        );
+
+        let status = StatusMessage::new(EthVersion::Eth69, Status::default(), range);
+        let buf = encode(ProtocolMessage::from(EthMessage::Status(status)));
+        let msg = ProtocolMessage::decode_message(EthVersion::Eth69, &mut &buf[..]).unwrap();
+        assert_eq!(msg.message, EthMessage::Status(status));
+    }
+
     #[test]
     fn request_pair_encode() {
         let request_pair = RequestPair { request_id: 1337, message: vec![5u8] };
@@ -3,7 +3,7 @@
 use alloy_primitives::B256;
 use alloy_rlp::{RlpDecodableWrapper, RlpEncodableWrapper};
 use reth_codecs_derive::add_arbitrary_tests;
-use reth_primitives::ReceiptWithBloom;
+use reth_primitives::{Receipt, ReceiptWithBloom};
 
 /// A request for transaction receipts from the given block hashes.
 #[derive(Clone, Debug, PartialEq, Eq, RlpEncodableWrapper, RlpDecodableWrapper, Default)]
@@ -26,9 +26,53 @@ pub struct Receipts(
     pub Vec<Vec<ReceiptWithBloom>>,
 );
 
+/// The `eth/69` response to [`GetReceipts`].
+///
+/// Unlike [`Receipts`], the receipts are sent without their bloom filter, each receipt is encoded
+/// as `[tx-type, post-state-or-status, cumulative-gas, logs]`. The bloom can be recomputed from the
+/// logs.
+#[derive(Clone, Debug, PartialEq, Eq, RlpEncodableWrapper, RlpDecodableWrapper, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(arbitrary::Arbitrary))]
+#[add_arbitrary_tests(rlp)]
+pub struct Receipts69(
+    /// Each receipt list should correspond to a block hash in the request.
+    pub Vec<Vec<Receipt>>,
+);
+
+impl Receipts69 {
+    /// Converts the receipts into [`Receipts`], computing the bloom of every receipt.
+    pub fn into_receipts_with_bloom(self) -> Receipts {
+        Receipts(
+            self.0
+                .into_iter()
+                .map(|receipts| receipts.into_iter().map(Receipt::with_bloom).collect())
+                .collect(),
+        )
+    }
+}
+
+impl From<Receipts> for Receipts69 {
+    fn from(receipts: Receipts) -> Self {
+        Self(
+            receipts
+                .0
+                .into_iter()
+                .map(|receipts| receipts.into_iter().map(|receipt| receipt.receipt).collect())
+                .collect(),
+        )
+    }
+}
+
+impl From<Receipts69> for Receipts {
+    fn from(receipts: Receipts69) -> Self {
+        receipts.into_receipts_with_bloom()
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::{message::RequestPair, GetReceipts, Receipts};
+    use crate::{message::RequestPair, GetReceipts, Receipts, Receipts69};
     use alloy_primitives::{hex, Log};
     use alloy_rlp::{Decodable, Encodable};
     use reth_primitives::{Receipt, ReceiptWithBloom, TxType};
@@ -152,4 +196,36 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    fn roundtrip_receipts69() {
+        let receipt = Receipt {
+            tx_type: TxType::Eip1559,
+            success: true,
+            cumulative_gas_used: 21000,
+            logs: vec![Log::new_unchecked(
+                hex!("0000000000000000000000000000000000000011").into(),
+                vec![
+                    hex!("000000000000000000000000000000000000000000000000000000000000dead").into()
+                ],
+                hex!("0100ff")[..].into(),
+            )],
+            ..Default::default()
+        };
+        let receipts = Receipts69(vec![vec![receipt.clone()]]);
+
+        let mut out = vec![];
+        receipts.encode(&mut out);
+        let decoded = Receipts69::decode(&mut out.as_slice()).unwrap();
+        assert_eq!(receipts, decoded);
+
+        // the receipt is encoded as a plain list without the bloom
+        let mut expected = vec![];
+        vec![vec![receipt.clone()]].encode(&mut expected);
+        assert_eq!(out, expected);
+
+        let with_bloom = decoded.into_receipts_with_bloom();
+        assert_eq!(with_bloom.0[0][0].bloom, receipt.bloom_slow());
+        assert_eq!(Receipts69::from(with_bloom), receipts);
+    }
 }
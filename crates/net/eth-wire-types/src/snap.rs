@@ -0,0 +1,499 @@
+//! Implements the `snap/1` protocol messages.
+//!
+//! Reference: <https://github.com/ethereum/devp2p/blob/master/caps/snap.md>
+
+use alloy_consensus::EMPTY_ROOT_HASH;
+use alloy_primitives::{
+    bytes::{Buf, BufMut},
+    Bytes, B256, U256,
+};
+use alloy_rlp::{Decodable, Encodable, Header, RlpDecodable, RlpEncodable, EMPTY_STRING_CODE};
+use reth_codecs_derive::add_arbitrary_tests;
+use reth_primitives::constants::KECCAK_EMPTY;
+
+/// The version of the `snap` protocol.
+pub const SNAP_VERSION: usize = 1;
+
+/// The number of message ids reserved by the `snap/1` protocol.
+pub const SNAP_MESSAGE_COUNT: u8 = 8;
+
+/// An account in the slim format of the `snap` protocol.
+///
+/// The slim format encodes an empty storage root and an empty code hash as empty strings.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(arbitrary::Arbitrary))]
+#[add_arbitrary_tests(rlp)]
+pub struct SlimAccount {
+    /// The nonce of the account.
+    pub nonce: u64,
+    /// The balance of the account.
+    pub balance: U256,
+    /// The root of the storage trie of the account.
+    pub storage_root: B256,
+    /// The hash of the bytecode of the account.
+    pub code_hash: B256,
+}
+
+impl SlimAccount {
+    /// Returns the storage root if it is not the empty root, encoded as in the slim format.
+    fn slim_storage_root(&self) -> Option<&B256> {
+        (self.storage_root != EMPTY_ROOT_HASH).then_some(&self.storage_root)
+    }
+
+    /// Returns the code hash if it is not the empty code hash, encoded as in the slim format.
+    fn slim_code_hash(&self) -> Option<&B256> {
+        (self.code_hash != KECCAK_EMPTY).then_some(&self.code_hash)
+    }
+
+    fn payload_length(&self) -> usize {
+        self.nonce.length() +
+            self.balance.length() +
+            self.slim_storage_root().map_or(1, Encodable::length) +
+            self.slim_code_hash().map_or(1, Encodable::length)
+    }
+}
+
+impl Encodable for SlimAccount {
+    fn encode(&self, out: &mut dyn BufMut) {
+        Header { list: true, payload_length: self.payload_length() }.encode(out);
+        self.nonce.encode(out);
+        self.balance.encode(out);
+        for hash in [self.slim_storage_root(), self.slim_code_hash()] {
+            match hash {
+                Some(hash) => hash.encode(out),
+                None => out.put_u8(EMPTY_STRING_CODE),
+            }
+        }
+    }
+
+    fn length(&self) -> usize {
+        let payload_length = self.payload_length();
+        payload_length + alloy_rlp::length_of_length(payload_length)
+    }
+}
+
+impl Decodable for SlimAccount {
+    fn decode(buf: &mut &[u8]) -> alloy_rlp::Result<Self> {
+        let header = Header::decode(buf)?;
+        if !header.list {
+            return Err(alloy_rlp::Error::UnexpectedString)
+        }
+        let started_len = buf.len();
+
+        let nonce = u64::decode(buf)?;
+        let balance = U256::decode(buf)?;
+        let storage_root = decode_slim_hash(buf)?.unwrap_or(EMPTY_ROOT_HASH);
+        let code_hash = decode_slim_hash(buf)?.unwrap_or(KECCAK_EMPTY);
+
+        if started_len - buf.len() != header.payload_length {
+            return Err(alloy_rlp::Error::ListLengthMismatch {
+                expected: header.payload_length,
+                got: started_len - buf.len(),
+            })
+        }
+        Ok(Self { nonce, balance, storage_root, code_hash })
+    }
+}
+
+/// Decodes a hash that is encoded as an empty string if it has its default value.
+fn decode_slim_hash(buf: &mut &[u8]) -> alloy_rlp::Result<Option<B256>> {
+    if buf.first() == Some(&EMPTY_STRING_CODE) {
+        buf.advance(1);
+        return Ok(None)
+    }
+    B256::decode(buf).map(Some)
+}
+
+/// An account and its hashed address, as returned in [`AccountRange`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(arbitrary::Arbitrary))]
+#[add_arbitrary_tests(rlp)]
+pub struct AccountData {
+    /// The hashed address of the account.
+    pub hash: B256,
+    /// The account in the slim format.
+    pub account: SlimAccount,
+}
+
+/// A storage slot and its hashed key, as returned in [`StorageRanges`].
+#[derive(Clone, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(arbitrary::Arbitrary))]
+#[add_arbitrary_tests(rlp)]
+pub struct StorageData {
+    /// The hashed key of the slot.
+    pub hash: B256,
+    /// The RLP encoded value of the slot.
+    pub data: Bytes,
+}
+
+/// Requests the accounts of the state trie with the given root, starting at `starting_hash`.
+///
+/// The peer should answer with the accounts up to `limit_hash`, or until the response reaches
+/// `response_bytes`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(arbitrary::Arbitrary))]
+#[add_arbitrary_tests(rlp)]
+pub struct GetAccountRange {
+    /// The id of the request.
+    pub request_id: u64,
+    /// The root of the state trie to serve the accounts from.
+    pub root_hash: B256,
+    /// The hashed address of the first account to return.
+    pub starting_hash: B256,
+    /// The hashed address after which no more accounts should be returned.
+    pub limit_hash: B256,
+    /// The soft limit of the size of the response.
+    pub response_bytes: u64,
+}
+
+/// The response to [`GetAccountRange`].
+///
+/// The proof contains the trie nodes on the paths to `starting_hash` and the last returned account,
+/// which allows to verify that the range is complete.
+#[derive(Clone, Debug, PartialEq, Eq, Default, RlpEncodable, RlpDecodable)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(arbitrary::Arbitrary))]
+#[add_arbitrary_tests(rlp)]
+pub struct AccountRange {
+    /// The id of the request.
+    pub request_id: u64,
+    /// The consecutive accounts, ordered by hashed address.
+    pub accounts: Vec<AccountData>,
+    /// The boundary proof of the range.
+    pub proof: Vec<Bytes>,
+}
+
+/// Requests the storage slots of the given accounts in the state trie with the given root.
+///
+/// The range given by `starting_hash` and `limit_hash` only applies to the first account.
+#[derive(Clone, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(arbitrary::Arbitrary))]
+#[add_arbitrary_tests(rlp)]
+pub struct GetStorageRanges {
+    /// The id of the request.
+    pub request_id: u64,
+    /// The root of the state trie to serve the storage from.
+    pub root_hash: B256,
+    /// The hashed addresses of the accounts.
+    pub account_hashes: Vec<B256>,
+    /// The hashed key of the first slot to return.
+    pub starting_hash: B256,
+    /// The hashed key after which no more slots should be returned.
+    pub limit_hash: B256,
+    /// The soft limit of the size of the response.
+    pub response_bytes: u64,
+}
+
+/// The response to [`GetStorageRanges`].
+///
+/// Only the storage of the last account can be incomplete, in which case the proof is its boundary
+/// proof.
+#[derive(Clone, Debug, PartialEq, Eq, Default, RlpEncodable, RlpDecodable)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(arbitrary::Arbitrary))]
+#[add_arbitrary_tests(rlp)]
+pub struct StorageRanges {
+    /// The id of the request.
+    pub request_id: u64,
+    /// The consecutive slots of every served account, ordered by hashed key.
+    pub slots: Vec<Vec<StorageData>>,
+    /// The boundary proof of the storage of the last account, if it is incomplete.
+    pub proof: Vec<Bytes>,
+}
+
+/// Requests the bytecodes with the given hashes.
+#[derive(Clone, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(arbitrary::Arbitrary))]
+#[add_arbitrary_tests(rlp)]
+pub struct GetByteCodes {
+    /// The id of the request.
+    pub request_id: u64,
+    /// The hashes of the bytecodes.
+    pub hashes: Vec<B256>,
+    /// The soft limit of the size of the response.
+    pub response_bytes: u64,
+}
+
+/// The response to [`GetByteCodes`].
+///
+/// The bytecodes are in the order of the request, unavailable bytecodes are skipped.
+#[derive(Clone, Debug, PartialEq, Eq, Default, RlpEncodable, RlpDecodable)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(arbitrary::Arbitrary))]
+#[add_arbitrary_tests(rlp)]
+pub struct ByteCodes {
+    /// The id of the request.
+    pub request_id: u64,
+    /// The requested bytecodes.
+    pub codes: Vec<Bytes>,
+}
+
+/// Requests trie nodes of the state trie with the given root.
+///
+/// Every path set starts with the compact encoded path of a node in the account trie. If the set
+/// has more than one path, the remaining ones are compact encoded paths of nodes in the storage
+/// trie of the account with the hashed address given by the first path.
+#[derive(Clone, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(arbitrary::Arbitrary))]
+#[add_arbitrary_tests(rlp)]
+pub struct GetTrieNodes {
+    /// The id of the request.
+    pub request_id: u64,
+    /// The root of the state trie to serve the nodes from.
+    pub root_hash: B256,
+    /// The sets of paths of the requested nodes.
+    pub paths: Vec<Vec<Bytes>>,
+    /// The soft limit of the size of the response.
+    pub response_bytes: u64,
+}
+
+/// The response to [`GetTrieNodes`].
+///
+/// The nodes are in the order of the request, the response stops at the first unavailable node.
+#[derive(Clone, Debug, PartialEq, Eq, Default, RlpEncodable, RlpDecodable)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(arbitrary::Arbitrary))]
+#[add_arbitrary_tests(rlp)]
+pub struct TrieNodes {
+    /// The id of the request.
+    pub request_id: u64,
+    /// The RLP encoded trie nodes.
+    pub nodes: Vec<Bytes>,
+}
+
+/// Represents message IDs for `snap/1` protocol messages, relative to the offset of the protocol.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SnapMessageId {
+    /// Requests a range of accounts.
+    GetAccountRange = 0x00,
+    /// Responds with a range of accounts.
+    AccountRange = 0x01,
+    /// Requests ranges of storage slots.
+    GetStorageRanges = 0x02,
+    /// Responds with ranges of storage slots.
+    StorageRanges = 0x03,
+    /// Requests bytecodes.
+    GetByteCodes = 0x04,
+    /// Responds with bytecodes.
+    ByteCodes = 0x05,
+    /// Requests trie nodes.
+    GetTrieNodes = 0x06,
+    /// Responds with trie nodes.
+    TrieNodes = 0x07,
+}
+
+impl Encodable for SnapMessageId {
+    fn encode(&self, out: &mut dyn BufMut) {
+        out.put_u8(*self as u8);
+    }
+
+    fn length(&self) -> usize {
+        1
+    }
+}
+
+impl Decodable for SnapMessageId {
+    fn decode(buf: &mut &[u8]) -> alloy_rlp::Result<Self> {
+        let id = match buf.first().ok_or(alloy_rlp::Error::InputTooShort)? {
+            0x00 => Self::GetAccountRange,
+            0x01 => Self::AccountRange,
+            0x02 => Self::GetStorageRanges,
+            0x03 => Self::StorageRanges,
+            0x04 => Self::GetByteCodes,
+            0x05 => Self::ByteCodes,
+            0x06 => Self::GetTrieNodes,
+            0x07 => Self::TrieNodes,
+            _ => return Err(alloy_rlp::Error::Custom("Invalid message ID")),
+        };
+        buf.advance(1);
+        Ok(id)
+    }
+}
+
+/// A `snap/1` protocol message.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SnapMessage {
+    /// Represents a `GetAccountRange` request.
+    GetAccountRange(GetAccountRange),
+    /// Represents an `AccountRange` response.
+    AccountRange(AccountRange),
+    /// Represents a `GetStorageRanges` request.
+    GetStorageRanges(GetStorageRanges),
+    /// Represents a `StorageRanges` response.
+    StorageRanges(StorageRanges),
+    /// Represents a `GetByteCodes` request.
+    GetByteCodes(GetByteCodes),
+    /// Represents a `ByteCodes` response.
+    ByteCodes(ByteCodes),
+    /// Represents a `GetTrieNodes` request.
+    GetTrieNodes(GetTrieNodes),
+    /// Represents a `TrieNodes` response.
+    TrieNodes(TrieNodes),
+}
+
+impl SnapMessage {
+    /// Returns the message's ID.
+    pub const fn message_id(&self) -> SnapMessageId {
+        match self {
+            Self::GetAccountRange(_) => SnapMessageId::GetAccountRange,
+            Self::AccountRange(_) => SnapMessageId::AccountRange,
+            Self::GetStorageRanges(_) => SnapMessageId::GetStorageRanges,
+            Self::StorageRanges(_) => SnapMessageId::StorageRanges,
+            Self::GetByteCodes(_) => SnapMessageId::GetByteCodes,
+            Self::ByteCodes(_) => SnapMessageId::ByteCodes,
+            Self::GetTrieNodes(_) => SnapMessageId::GetTrieNodes,
+            Self::TrieNodes(_) => SnapMessageId::TrieNodes,
+        }
+    }
+
+    /// Returns the id of the request this message belongs to.
+    pub const fn request_id(&self) -> u64 {
+        match self {
+            Self::GetAccountRange(msg) => msg.request_id,
+            Self::AccountRange(msg) => msg.request_id,
+            Self::GetStorageRanges(msg) => msg.request_id,
+            Self::StorageRanges(msg) => msg.request_id,
+            Self::GetByteCodes(msg) => msg.request_id,
+            Self::ByteCodes(msg) => msg.request_id,
+            Self::GetTrieNodes(msg) => msg.request_id,
+            Self::TrieNodes(msg) => msg.request_id,
+        }
+    }
+
+    /// Sets the id of the request this message belongs to.
+    pub fn set_request_id(&mut self, request_id: u64) {
+        match self {
+            Self::GetAccountRange(msg) => msg.request_id = request_id,
+            Self::AccountRange(msg) => msg.request_id = request_id,
+            Self::GetStorageRanges(msg) => msg.request_id = request_id,
+            Self::StorageRanges(msg) => msg.request_id = request_id,
+            Self::GetByteCodes(msg) => msg.request_id = request_id,
+            Self::ByteCodes(msg) => msg.request_id = request_id,
+            Self::GetTrieNodes(msg) => msg.request_id = request_id,
+            Self::TrieNodes(msg) => msg.request_id = request_id,
+        }
+    }
+
+    /// Returns the id of the response to this message, if it is a request.
+    pub const fn response_id(&self) -> Option<SnapMessageId> {
+        match self {
+            Self::GetAccountRange(_) => Some(SnapMessageId::AccountRange),
+            Self::GetStorageRanges(_) => Some(SnapMessageId::StorageRanges),
+            Self::GetByteCodes(_) => Some(SnapMessageId::ByteCodes),
+            Self::GetTrieNodes(_) => Some(SnapMessageId::TrieNodes),
+            _ => None,
+        }
+    }
+
+    /// Returns true if the message is a request.
+    pub const fn is_request(&self) -> bool {
+        matches!(
+            self,
+            Self::GetAccountRange(_) |
+                Self::GetStorageRanges(_) |
+                Self::GetByteCodes(_) |
+                Self::GetTrieNodes(_)
+        )
+    }
+
+    /// Decodes a message, prefixed with its [`SnapMessageId`].
+    pub fn decode_message(buf: &mut &[u8]) -> alloy_rlp::Result<Self> {
+        let message = match SnapMessageId::decode(buf)? {
+            SnapMessageId::GetAccountRange => Self::GetAccountRange(GetAccountRange::decode(buf)?),
+            SnapMessageId::AccountRange => Self::AccountRange(AccountRange::decode(buf)?),
+            SnapMessageId::GetStorageRanges => {
+                Self::GetStorageRanges(GetStorageRanges::decode(buf)?)
+            }
+            SnapMessageId::StorageRanges => Self::StorageRanges(StorageRanges::decode(buf)?),
+            SnapMessageId::GetByteCodes => Self::GetByteCodes(GetByteCodes::decode(buf)?),
+            SnapMessageId::ByteCodes => Self::ByteCodes(ByteCodes::decode(buf)?),
+            SnapMessageId::GetTrieNodes => Self::GetTrieNodes(GetTrieNodes::decode(buf)?),
+            SnapMessageId::TrieNodes => Self::TrieNodes(TrieNodes::decode(buf)?),
+        };
+        Ok(message)
+    }
+
+    /// Encodes the message, prefixed with its [`SnapMessageId`].
+    pub fn encode_message(&self, out: &mut dyn BufMut) {
+        self.message_id().encode(out);
+        match self {
+            Self::GetAccountRange(msg) => msg.encode(out),
+            Self::AccountRange(msg) => msg.encode(out),
+            Self::GetStorageRanges(msg) => msg.encode(out),
+            Self::StorageRanges(msg) => msg.encode(out),
+            Self::GetByteCodes(msg) => msg.encode(out),
+            Self::ByteCodes(msg) => msg.encode(out),
+            Self::GetTrieNodes(msg) => msg.encode(out),
+            Self::TrieNodes(msg) => msg.encode(out),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::hex;
+
+    #[test]
+    fn slim_account_encodes_empty_hashes_as_empty_strings() {
+        let account = SlimAccount {
+            nonce: 1,
+            balance: U256::from(2),
+            storage_root: EMPTY_ROOT_HASH,
+            code_hash: KECCAK_EMPTY,
+        };
+        let encoded = alloy_rlp::encode(account);
+        assert_eq!(encoded, hex!("c401028080"));
+        assert_eq!(SlimAccount::decode(&mut &encoded[..]).unwrap(), account);
+
+        let account = SlimAccount { storage_root: B256::repeat_byte(1), ..account };
+        let encoded = alloy_rlp::encode(account);
+        assert_eq!(encoded.len(), 1 + 2 + 33 + 1);
+        assert_eq!(SlimAccount::decode(&mut &encoded[..]).unwrap(), account);
+    }
+
+    #[test]
+    fn message_roundtrip() {
+        let messages = [
+            SnapMessage::GetAccountRange(GetAccountRange {
+                request_id: 1,
+                root_hash: B256::repeat_byte(1),
+                starting_hash: B256::ZERO,
+                limit_hash: B256::repeat_byte(0xff),
+                response_bytes: 512 * 1024,
+            }),
+            SnapMessage::StorageRanges(StorageRanges {
+                request_id: 2,
+                slots: vec![vec![StorageData {
+                    hash: B256::repeat_byte(2),
+                    data: hex!("01").into(),
+                }]],
+                proof: vec![hex!("c0").into()],
+            }),
+            SnapMessage::GetTrieNodes(GetTrieNodes {
+                request_id: 3,
+                root_hash: B256::repeat_byte(3),
+                paths: vec![vec![hex!("00").into()], vec![hex!("1f").into(), hex!("00").into()]],
+                response_bytes: 1024,
+            }),
+            SnapMessage::ByteCodes(ByteCodes { request_id: 4, codes: vec![hex!("6000").into()] }),
+        ];
+
+        for message in messages {
+            let mut encoded = Vec::new();
+            message.encode_message(&mut encoded);
+            assert_eq!(encoded[0], message.message_id() as u8);
+            assert_eq!(SnapMessage::decode_message(&mut &encoded[..]).unwrap(), message);
+        }
+    }
+}
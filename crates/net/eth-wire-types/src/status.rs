@@ -1,7 +1,7 @@
-use crate::EthVersion;
+use crate::{BlockRangeUpdate, EthVersion};
 use alloy_chains::{Chain, NamedChain};
 use alloy_primitives::{hex, B256, U256};
-use alloy_rlp::{RlpDecodable, RlpEncodable};
+use alloy_rlp::{BufMut, Encodable, RlpDecodable, RlpEncodable};
 use reth_chainspec::{EthChainSpec, Hardforks, MAINNET};
 use reth_codecs_derive::add_arbitrary_tests;
 use reth_primitives::{EthereumHardfork, ForkId, Head};
@@ -134,6 +134,209 @@ impl Default for Status {
     }
 }
 
+/// The `eth/69` status message.
+///
+/// Compared to [`Status`], the total difficulty is dropped and the peer instead announces the range
+/// of blocks it is able to serve, see also [`BlockRangeUpdate`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(arbitrary::Arbitrary))]
+#[add_arbitrary_tests(rlp)]
+pub struct StatusEth69 {
+    /// The current protocol version, 69 or higher.
+    pub version: u8,
+
+    /// The chain id, as introduced in
+    /// [EIP155](https://eips.ethereum.org/EIPS/eip-155#list-of-chain-ids).
+    pub chain: Chain,
+
+    /// The genesis hash of the peer's chain.
+    pub genesis: B256,
+
+    /// The fork identifier as defined by
+    /// [EIP-2124](https://github.com/ethereum/EIPs/blob/master/EIPS/eip-2124.md).
+    pub forkid: ForkId,
+
+    /// The earliest block the peer can serve.
+    pub earliest: u64,
+
+    /// The latest block the peer can serve.
+    pub latest: u64,
+
+    /// The hash of the latest block.
+    pub blockhash: B256,
+}
+
+impl StatusEth69 {
+    /// Returns the block range announced by the peer.
+    pub const fn block_range(&self) -> BlockRangeUpdate {
+        BlockRangeUpdate {
+            earliest: self.earliest,
+            latest: self.latest,
+            latest_hash: self.blockhash,
+        }
+    }
+}
+
+impl Display for StatusEth69 {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Status {{ version: {}, chain: {}, genesis: {}, forkid: {:X?}, earliest: {}, latest: {}, blockhash: {} }}",
+            self.version,
+            self.chain,
+            hex::encode(self.genesis),
+            self.forkid,
+            self.earliest,
+            self.latest,
+            hex::encode(self.blockhash),
+        )
+    }
+}
+
+/// The status message exchanged during the `eth` handshake, in the format of the negotiated
+/// version.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum StatusMessage {
+    /// The status message for `eth/66` to `eth/68`.
+    Legacy(Status),
+    /// The status message for `eth/69`.
+    Eth69(StatusEth69),
+}
+
+impl StatusMessage {
+    /// Creates the status message for the given version.
+    ///
+    /// For `eth/69` the head of `status` is replaced by the given block range.
+    pub fn new(version: EthVersion, mut status: Status, block_range: BlockRangeUpdate) -> Self {
+        status.set_eth_version(version);
+        if version >= EthVersion::Eth69 {
+            Self::Eth69(StatusEth69 {
+                version: status.version,
+                chain: status.chain,
+                genesis: status.genesis,
+                forkid: status.forkid,
+                earliest: block_range.earliest,
+                latest: block_range.latest,
+                blockhash: block_range.latest_hash,
+            })
+        } else {
+            Self::Legacy(status)
+        }
+    }
+
+    /// Returns the protocol version.
+    pub const fn version(&self) -> u8 {
+        match self {
+            Self::Legacy(status) => status.version,
+            Self::Eth69(status) => status.version,
+        }
+    }
+
+    /// Returns the chain id.
+    pub const fn chain(&self) -> &Chain {
+        match self {
+            Self::Legacy(status) => &status.chain,
+            Self::Eth69(status) => &status.chain,
+        }
+    }
+
+    /// Returns the genesis hash.
+    pub const fn genesis(&self) -> B256 {
+        match self {
+            Self::Legacy(status) => status.genesis,
+            Self::Eth69(status) => status.genesis,
+        }
+    }
+
+    /// Returns the fork id.
+    pub const fn forkid(&self) -> ForkId {
+        match self {
+            Self::Legacy(status) => status.forkid,
+            Self::Eth69(status) => status.forkid,
+        }
+    }
+
+    /// Returns the hash of the best block of the peer.
+    pub const fn blockhash(&self) -> B256 {
+        match self {
+            Self::Legacy(status) => status.blockhash,
+            Self::Eth69(status) => status.blockhash,
+        }
+    }
+
+    /// Returns the total difficulty, this is only announced by peers before `eth/69`.
+    pub const fn total_difficulty(&self) -> Option<U256> {
+        match self {
+            Self::Legacy(status) => Some(status.total_difficulty),
+            Self::Eth69(_) => None,
+        }
+    }
+
+    /// Returns the announced block range, this is only announced by `eth/69` peers.
+    pub const fn block_range(&self) -> Option<BlockRangeUpdate> {
+        match self {
+            Self::Legacy(_) => None,
+            Self::Eth69(status) => Some(status.block_range()),
+        }
+    }
+
+    /// Converts the message into a [`Status`].
+    ///
+    /// The total difficulty is zero for `eth/69` messages.
+    pub const fn to_legacy(&self) -> Status {
+        match self {
+            Self::Legacy(status) => *status,
+            Self::Eth69(status) => Status {
+                version: status.version,
+                chain: status.chain,
+                total_difficulty: U256::ZERO,
+                blockhash: status.blockhash,
+                genesis: status.genesis,
+                forkid: status.forkid,
+            },
+        }
+    }
+}
+
+impl From<Status> for StatusMessage {
+    fn from(status: Status) -> Self {
+        Self::Legacy(status)
+    }
+}
+
+impl From<StatusEth69> for StatusMessage {
+    fn from(status: StatusEth69) -> Self {
+        Self::Eth69(status)
+    }
+}
+
+impl Encodable for StatusMessage {
+    fn encode(&self, out: &mut dyn BufMut) {
+        match self {
+            Self::Legacy(status) => status.encode(out),
+            Self::Eth69(status) => status.encode(out),
+        }
+    }
+
+    fn length(&self) -> usize {
+        match self {
+            Self::Legacy(status) => status.length(),
+            Self::Eth69(status) => status.length(),
+        }
+    }
+}
+
+impl Display for StatusMessage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Legacy(status) => Display::fmt(status, f),
+            Self::Eth69(status) => Display::fmt(status, f),
+        }
+    }
+}
+
 /// Builder for [`Status`] messages.
 ///
 /// # Example
@@ -215,7 +418,7 @@ impl StatusBuilder {
 
 #[cfg(test)]
 mod tests {
-    use crate::{EthVersion, Status};
+    use crate::{BlockRangeUpdate, EthVersion, Status, StatusEth69, StatusMessage};
     use alloy_genesis::Genesis;
     use alloy_primitives::{hex, B256, U256};
     use alloy_rlp::{Decodable, Encodable};
@@ -392,4 +595,27 @@ mod tests {
         assert_eq!(status.blockhash, head_hash);
         assert_eq!(status.genesis, genesis_hash);
     }
+
+    #[test]
+    fn eth69_status_message() {
+        let status = Status::default();
+        let range =
+            BlockRangeUpdate { earliest: 100, latest: 200, latest_hash: B256::with_last_byte(1) };
+
+        let legacy = StatusMessage::new(EthVersion::Eth68, status, range);
+        assert_eq!(legacy, StatusMessage::Legacy(status));
+        assert_eq!(legacy.block_range(), None);
+
+        let message = StatusMessage::new(EthVersion::Eth69, status, range);
+        let StatusMessage::Eth69(eth69) = message else { panic!("expected eth/69 status") };
+        assert_eq!(eth69.version, EthVersion::Eth69 as u8);
+        assert_eq!(message.block_range(), Some(range));
+        assert_eq!(message.blockhash(), range.latest_hash);
+        assert_eq!(message.total_difficulty(), None);
+        assert_eq!(message.to_legacy().total_difficulty, U256::ZERO);
+
+        let mut encoded = vec![];
+        message.encode(&mut encoded);
+        assert_eq!(StatusEth69::decode(&mut &encoded[..]).unwrap(), eth69);
+    }
 }
@@ -100,4 +100,12 @@ pub enum EthHandshakeError {
         /// The maximum allowed bit length for the total difficulty.
         maximum: usize,
     },
+    #[error("invalid block range in status message: earliest {earliest} > latest {latest}")]
+    /// The announced block range of an `eth/69` status message is invalid.
+    InvalidBlockRange {
+        /// The announced earliest block.
+        earliest: u64,
+        /// The announced latest block.
+        latest: u64,
+    },
 }
@@ -2,7 +2,7 @@ use crate::{
     errors::{EthHandshakeError, EthStreamError},
     message::{EthBroadcastMessage, ProtocolBroadcastMessage},
     p2pstream::HANDSHAKE_TIMEOUT,
-    CanDisconnect, DisconnectReason, EthMessage, EthVersion, ProtocolMessage, Status,
+    CanDisconnect, DisconnectReason, EthMessage, EthVersion, ProtocolMessage, StatusMessage,
 };
 use alloy_primitives::bytes::{Bytes, BytesMut};
 use futures::{ready, Sink, SinkExt, StreamExt};
@@ -50,22 +50,25 @@ where
     /// Consumes the [`UnauthedEthStream`] and returns an [`EthStream`] after the `Status`
     /// handshake is completed successfully. This also returns the `Status` message sent by the
     /// remote peer.
+    ///
+    /// The version of the given status message determines the negotiated version and thereby the
+    /// format of the exchanged status messages, see [`StatusMessage`].
     pub async fn handshake(
         self,
-        status: Status,
+        status: impl Into<StatusMessage>,
         fork_filter: ForkFilter,
-    ) -> Result<(EthStream<S>, Status), EthStreamError> {
+    ) -> Result<(EthStream<S>, StatusMessage), EthStreamError> {
         self.handshake_with_timeout(status, fork_filter, HANDSHAKE_TIMEOUT).await
     }
 
     /// Wrapper around handshake which enforces a timeout.
     pub async fn handshake_with_timeout(
         self,
-        status: Status,
+        status: impl Into<StatusMessage>,
         fork_filter: ForkFilter,
         timeout_limit: Duration,
-    ) -> Result<(EthStream<S>, Status), EthStreamError> {
-        timeout(timeout_limit, Self::handshake_without_timeout(self, status, fork_filter))
+    ) -> Result<(EthStream<S>, StatusMessage), EthStreamError> {
+        timeout(timeout_limit, Self::handshake_without_timeout(self, status.into(), fork_filter))
             .await
             .map_err(|_| EthStreamError::StreamTimeout)?
     }
@@ -73,9 +76,9 @@ where
     /// Handshake with no timeout
     pub async fn handshake_without_timeout(
         mut self,
-        status: Status,
+        status: StatusMessage,
         fork_filter: ForkFilter,
-    ) -> Result<(EthStream<S>, Status), EthStreamError> {
+    ) -> Result<(EthStream<S>, StatusMessage), EthStreamError> {
         trace!(
             %status,
             "sending eth status to peer"
@@ -102,7 +105,7 @@ where
             return Err(EthStreamError::MessageTooBig(their_msg.len()))
         }
 
-        let version = EthVersion::try_from(status.version)?;
+        let version = EthVersion::try_from(status.version())?;
         let msg = match ProtocolMessage::decode_message(version, &mut their_msg.as_ref()) {
             Ok(m) => m,
             Err(err) => {
@@ -120,45 +123,58 @@ where
                     status=%resp,
                     "validating incoming eth status from peer"
                 );
-                if status.genesis != resp.genesis {
+                if status.genesis() != resp.genesis() {
                     self.inner.disconnect(DisconnectReason::ProtocolBreach).await?;
                     return Err(EthHandshakeError::MismatchedGenesis(
-                        GotExpected { expected: status.genesis, got: resp.genesis }.into(),
+                        GotExpected { expected: status.genesis(), got: resp.genesis() }.into(),
                     )
                     .into())
                 }
 
-                if status.version != resp.version {
+                if status.version() != resp.version() {
                     self.inner.disconnect(DisconnectReason::ProtocolBreach).await?;
                     return Err(EthHandshakeError::MismatchedProtocolVersion(GotExpected {
-                        got: resp.version,
-                        expected: status.version,
+                        got: resp.version(),
+                        expected: status.version(),
                     })
                     .into())
                 }
 
-                if status.chain != resp.chain {
+                if status.chain() != resp.chain() {
                     self.inner.disconnect(DisconnectReason::ProtocolBreach).await?;
                     return Err(EthHandshakeError::MismatchedChain(GotExpected {
-                        got: resp.chain,
-                        expected: status.chain,
+                        got: *resp.chain(),
+                        expected: *status.chain(),
                     })
                     .into())
                 }
 
                 // TD at mainnet block #7753254 is 76 bits. If it becomes 100 million times
                 // larger, it will still fit within 100 bits
-                if status.total_difficulty.bit_len() > 100 {
-                    self.inner.disconnect(DisconnectReason::ProtocolBreach).await?;
-                    return Err(EthHandshakeError::TotalDifficultyBitLenTooLarge {
-                        got: status.total_difficulty.bit_len(),
-                        maximum: 100,
+                if let Some(total_difficulty) = status.total_difficulty() {
+                    if total_difficulty.bit_len() > 100 {
+                        self.inner.disconnect(DisconnectReason::ProtocolBreach).await?;
+                        return Err(EthHandshakeError::TotalDifficultyBitLenTooLarge {
+                            got: total_difficulty.bit_len(),
+                            maximum: 100,
+                        }
+                        .into())
+                    }
+                }
+
+                if let Some(range) = resp.block_range() {
+                    if !range.is_valid() {
+                        self.inner.disconnect(DisconnectReason::ProtocolBreach).await?;
+                        return Err(EthHandshakeError::InvalidBlockRange {
+                            earliest: range.earliest,
+                            latest: range.latest,
+                        }
+                        .into())
                     }
-                    .into())
                 }
 
                 if let Err(err) =
-                    fork_filter.validate(resp.forkid).map_err(EthHandshakeError::InvalidFork)
+                    fork_filter.validate(resp.forkid()).map_err(EthHandshakeError::InvalidFork)
                 {
                     self.inner.disconnect(DisconnectReason::ProtocolBreach).await?;
                     return Err(err.into())
@@ -348,8 +364,8 @@ mod tests {
         errors::{EthHandshakeError, EthStreamError},
         hello::DEFAULT_TCP_PORT,
         p2pstream::UnauthedP2PStream,
-        EthMessage, EthStream, EthVersion, HelloMessageWithProtocols, PassthroughCodec,
-        ProtocolVersion, Status,
+        BlockRangeUpdate, EthMessage, EthStream, EthVersion, HelloMessageWithProtocols,
+        PassthroughCodec, ProtocolVersion, Status, StatusMessage,
     };
     use alloy_primitives::{B256, U256};
     use futures::{SinkExt, StreamExt};
@@ -392,7 +408,7 @@ mod tests {
                 .unwrap();
 
             // just make sure it equals our status (our status is a clone of their status)
-            assert_eq!(their_status, status_clone);
+            assert_eq!(their_status, StatusMessage::from(status_clone));
         });
 
         let outgoing = TcpStream::connect(local_addr).await.unwrap();
@@ -403,12 +419,91 @@ mod tests {
             UnauthedEthStream::new(sink).handshake(status, fork_filter).await.unwrap();
 
         // their status is a clone of our status, these should be equal
-        assert_eq!(their_status, status);
+        assert_eq!(their_status, StatusMessage::from(status));
 
         // wait for it to finish
         handle.await.unwrap();
     }
 
+    #[tokio::test]
+    async fn can_handshake_eth69() {
+        let genesis = B256::random();
+        let fork_filter = ForkFilter::new(Head::default(), genesis, 0, Vec::new());
+
+        let status = Status {
+            version: EthVersion::Eth69 as u8,
+            chain: NamedChain::Mainnet.into(),
+            total_difficulty: U256::ZERO,
+            blockhash: B256::random(),
+            genesis,
+            forkid: fork_filter.current(),
+        };
+        let range = BlockRangeUpdate { earliest: 10, latest: 100, latest_hash: B256::random() };
+        let status = StatusMessage::new(EthVersion::Eth69, status, range);
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let local_addr = listener.local_addr().unwrap();
+
+        let fork_filter_clone = fork_filter.clone();
+        let handle = tokio::spawn(async move {
+            let (incoming, _) = listener.accept().await.unwrap();
+            let stream = PassthroughCodec::default().framed(incoming);
+            let (stream, their_status) =
+                UnauthedEthStream::new(stream).handshake(status, fork_filter_clone).await.unwrap();
+
+            assert_eq!(stream.version(), EthVersion::Eth69);
+            assert_eq!(their_status.block_range(), Some(range));
+        });
+
+        let outgoing = TcpStream::connect(local_addr).await.unwrap();
+        let sink = PassthroughCodec::default().framed(outgoing);
+
+        let (_, their_status) =
+            UnauthedEthStream::new(sink).handshake(status, fork_filter).await.unwrap();
+        assert_eq!(their_status, status);
+
+        handle.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn fail_handshake_on_invalid_block_range() {
+        let genesis = B256::random();
+        let fork_filter = ForkFilter::new(Head::default(), genesis, 0, Vec::new());
+
+        let status = Status { genesis, forkid: fork_filter.current(), ..Default::default() };
+        let range = BlockRangeUpdate { earliest: 100, latest: 10, latest_hash: B256::random() };
+        let status = StatusMessage::new(EthVersion::Eth69, status, range);
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let local_addr = listener.local_addr().unwrap();
+
+        let fork_filter_clone = fork_filter.clone();
+        let handle = tokio::spawn(async move {
+            let (incoming, _) = listener.accept().await.unwrap();
+            let stream = PassthroughCodec::default().framed(incoming);
+            let handshake_res =
+                UnauthedEthStream::new(stream).handshake(status, fork_filter_clone).await;
+            assert!(matches!(
+                handshake_res,
+                Err(EthStreamError::EthHandshakeError(EthHandshakeError::InvalidBlockRange {
+                    earliest: 100,
+                    latest: 10
+                }))
+            ));
+        });
+
+        let outgoing = TcpStream::connect(local_addr).await.unwrap();
+        let sink = PassthroughCodec::default().framed(outgoing);
+
+        let handshake_res = UnauthedEthStream::new(sink).handshake(status, fork_filter).await;
+        assert!(matches!(
+            handshake_res,
+            Err(EthStreamError::EthHandshakeError(EthHandshakeError::InvalidBlockRange { .. }))
+        ));
+
+        handle.await.unwrap();
+    }
+
     #[tokio::test]
     async fn pass_handshake_on_low_td_bitlen() {
         let genesis = B256::random();
@@ -439,7 +534,7 @@ mod tests {
                 .unwrap();
 
             // just make sure it equals our status, and that the handshake succeeded
-            assert_eq!(their_status, status_clone);
+            assert_eq!(their_status, StatusMessage::from(status_clone));
         });
 
         let outgoing = TcpStream::connect(local_addr).await.unwrap();
@@ -450,7 +545,7 @@ mod tests {
             UnauthedEthStream::new(sink).handshake(status, fork_filter).await.unwrap();
 
         // their status is a clone of our status, these should be equal
-        assert_eq!(their_status, status);
+        assert_eq!(their_status, StatusMessage::from(status));
 
         // await the other handshake
         handle.await.unwrap();
@@ -700,7 +795,7 @@ mod tests {
                 .unwrap();
 
             // just make sure it equals our status (our status is a clone of their status)
-            assert_eq!(their_status, status_clone);
+            assert_eq!(their_status, StatusMessage::from(status_clone));
         });
 
         let outgoing = TcpStream::connect(local_addr).await.unwrap();
@@ -52,10 +52,10 @@ pub mod fuzz_rlp {
     use alloy_rlp::{RlpDecodableWrapper, RlpEncodableWrapper};
     use reth_codecs::add_arbitrary_tests;
     use reth_eth_wire::{
-        BlockBodies, BlockHeaders, DisconnectReason, GetBlockBodies, GetBlockHeaders, GetNodeData,
-        GetPooledTransactions, GetReceipts, HelloMessage, NewBlock, NewBlockHashes,
-        NewPooledTransactionHashes66, NewPooledTransactionHashes68, NodeData, P2PMessage,
-        PooledTransactions, Receipts, Status, Transactions,
+        BlockBodies, BlockHeaders, BlockRangeUpdate, DisconnectReason, GetBlockBodies,
+        GetBlockHeaders, GetNodeData, GetPooledTransactions, GetReceipts, HelloMessage, NewBlock,
+        NewBlockHashes, NewPooledTransactionHashes66, NewPooledTransactionHashes68, NodeData,
+        P2PMessage, PooledTransactions, Receipts, Receipts69, Status, Transactions,
     };
     use reth_primitives::TransactionSigned;
     use serde::{Deserialize, Serialize};
@@ -161,5 +161,7 @@ pub mod fuzz_rlp {
     fuzz_type_and_name!(NodeData, fuzz_NodeData);
     fuzz_type_and_name!(GetReceipts, fuzz_GetReceipts);
     fuzz_type_and_name!(Receipts, fuzz_Receipts);
+    fuzz_type_and_name!(Receipts69, fuzz_Receipts69);
+    fuzz_type_and_name!(BlockRangeUpdate, fuzz_BlockRangeUpdate);
     fuzz_type_and_name!(TransactionSigned, fuzz_TransactionSigned);
 }
@@ -1,4 +1,4 @@
-//! Helpers for resolving the external IP.
+//! Helpers for resolving the external IP and mapping ports on the gateway.
 //!
 //! ## Feature Flags
 //!
@@ -12,14 +12,21 @@
 #![cfg_attr(not(test), warn(unused_crate_dependencies))]
 #![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
 
+pub mod natpmp;
 pub mod net_if;
+pub mod port_mapping;
+pub mod upnp;
 
 pub use net_if::{NetInterfaceError, DEFAULT_NET_IF_NAME};
+pub use port_mapping::{
+    MappedAddrs, MappedPort, PortMapper, PortMapperHandle, PortMappingConfig, PortMappingError,
+    PortMappingRequest, Protocol,
+};
 
 use std::{
     fmt,
     future::{poll_fn, Future},
-    net::{AddrParseError, IpAddr},
+    net::{AddrParseError, IpAddr, Ipv4Addr},
     pin::Pin,
     str::FromStr,
     task::{Context, Poll},
@@ -44,8 +51,11 @@ pub enum NatResolver {
     /// Resolve with any available resolver.
     #[default]
     Any,
-    /// Resolve external IP via `UPnP`.
+    /// Resolve external IP and map ports via `UPnP`.
     Upnp,
+    /// Resolve external IP and map ports via NAT-PMP/PCP, using the given gateway or the default
+    /// gateway of the host.
+    NatPmp(Option<Ipv4Addr>),
     /// Resolve external IP via a network request.
     PublicIp,
     /// Use the given [`IpAddr`]
@@ -76,6 +86,8 @@ impl fmt::Display for NatResolver {
         match self {
             Self::Any => f.write_str("any"),
             Self::Upnp => f.write_str("upnp"),
+            Self::NatPmp(None) => f.write_str("natpmp"),
+            Self::NatPmp(Some(gateway)) => write!(f, "natpmp:{gateway}"),
             Self::PublicIp => f.write_str("publicip"),
             Self::ExternalIp(ip) => write!(f, "extip:{ip}"),
             Self::NetIf => f.write_str("netif"),
@@ -102,10 +114,14 @@ impl FromStr for NatResolver {
         let r = match s {
             "any" => Self::Any,
             "upnp" => Self::Upnp,
+            "natpmp" => Self::NatPmp(None),
             "none" => Self::None,
             "publicip" | "public-ip" => Self::PublicIp,
             "netif" => Self::NetIf,
             s => {
+                if let Some(gateway) = s.strip_prefix("natpmp:") {
+                    return Ok(Self::NatPmp(Some(gateway.parse()?)))
+                }
                 let Some(ip) = s.strip_prefix("extip:") else {
                     return Err(ParseNatResolverError::UnknownVariant(format!(
                         "Unknown Nat Resolver: {s}"
@@ -197,7 +213,15 @@ pub async fn external_ip() -> Option<IpAddr> {
 /// Given a [`NatResolver`] attempts to produce an IP address (best effort).
 pub async fn external_addr_with(resolver: NatResolver) -> Option<IpAddr> {
     match resolver {
-        NatResolver::Any | NatResolver::Upnp | NatResolver::PublicIp => resolve_external_ip().await,
+        NatResolver::Any | NatResolver::PublicIp => resolve_external_ip().await,
+        NatResolver::Upnp => match resolve_upnp_ip().await {
+            Some(ip) => Some(ip),
+            None => resolve_external_ip().await,
+        },
+        NatResolver::NatPmp(gateway) => match resolve_natpmp_ip(gateway).await {
+            Some(ip) => Some(ip),
+            None => resolve_external_ip().await,
+        },
         NatResolver::ExternalIp(ip) => Some(ip),
         NatResolver::NetIf => resolve_net_if_ip(DEFAULT_NET_IF_NAME)
             .inspect_err(|err| {
@@ -211,6 +235,22 @@ pub async fn external_addr_with(resolver: NatResolver) -> Option<IpAddr> {
     }
 }
 
+async fn resolve_upnp_ip() -> Option<IpAddr> {
+    let res = async { upnp::UpnpGateway::discover().await?.external_ip().await }.await;
+    res.inspect_err(|err| {
+        debug!(target: "net::nat", %err, "Failed to resolve external IP via UPnP");
+    })
+    .ok()
+}
+
+async fn resolve_natpmp_ip(gateway: Option<Ipv4Addr>) -> Option<IpAddr> {
+    let res = async { natpmp::NatPmpGateway::new(gateway)?.external_ip().await }.await;
+    res.inspect_err(|err| {
+        debug!(target: "net::nat", %err, "Failed to resolve external IP via NAT-PMP");
+    })
+    .ok()
+}
+
 async fn resolve_external_ip() -> Option<IpAddr> {
     let futures = EXTERNAL_IP_APIS.iter().copied().map(resolve_external_ip_url_res).map(Box::pin);
     futures_util::future::select_ok(futures)
@@ -270,5 +310,11 @@ mod tests {
         let s = "extip:0.0.0.0";
         assert_eq!(ip, s.parse().unwrap());
         assert_eq!(ip.to_string().as_str(), s);
+
+        assert_eq!(NatResolver::NatPmp(None), "natpmp".parse().unwrap());
+        let natpmp = NatResolver::NatPmp(Some(Ipv4Addr::new(192, 168, 1, 1)));
+        let s = "natpmp:192.168.1.1";
+        assert_eq!(natpmp, s.parse().unwrap());
+        assert_eq!(natpmp.to_string().as_str(), s);
     }
 }
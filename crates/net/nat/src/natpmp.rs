@@ -0,0 +1,290 @@
+//! Port mapping via NAT-PMP ([RFC 6886](https://datatracker.ietf.org/doc/html/rfc6886)) and its
+//! successor PCP ([RFC 6887](https://datatracker.ietf.org/doc/html/rfc6887)).
+//!
+//! The gateway is asked via PCP first, and via NAT-PMP if it does not support PCP.
+
+use std::{
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use tokio::net::UdpSocket;
+use tracing::trace;
+
+use crate::port_mapping::{PortMappingError, Protocol};
+
+/// Port of the NAT-PMP and PCP server on the gateway.
+const SERVER_PORT: u16 = 5351;
+
+/// Initial timeout of a request, doubled with every retransmission.
+const INITIAL_TIMEOUT: Duration = Duration::from_millis(250);
+
+/// Number of times a request is sent before giving up.
+const MAX_ATTEMPTS: usize = 5;
+
+/// NAT-PMP protocol version.
+const NATPMP_VERSION: u8 = 0;
+
+/// PCP protocol version.
+const PCP_VERSION: u8 = 2;
+
+/// PCP `MAP` opcode.
+const PCP_OPCODE_MAP: u8 = 1;
+
+/// PCP result code of a server that does not support the requested version.
+const PCP_UNSUPP_VERSION: u8 = 1;
+
+/// A mapping that was created by the gateway.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NatPmpMapping {
+    /// The external port assigned by the gateway.
+    pub external_port: u16,
+    /// The external IP, only reported by PCP gateways.
+    pub external_ip: Option<IpAddr>,
+    /// The lifetime of the mapping granted by the gateway.
+    pub lifetime: Duration,
+}
+
+/// A gateway that supports NAT-PMP or PCP.
+#[derive(Debug, Clone)]
+pub struct NatPmpGateway {
+    /// Address of the gateway.
+    gateway: Ipv4Addr,
+    /// Whether the gateway supports PCP, `None` if not yet known.
+    pcp: Option<bool>,
+    /// Nonce that identifies the PCP mappings of this node.
+    nonce: [u8; 12],
+}
+
+impl NatPmpGateway {
+    /// Creates a new instance for the given gateway, or the default gateway of the host if `None`.
+    pub fn new(gateway: Option<Ipv4Addr>) -> Result<Self, PortMappingError> {
+        let gateway = gateway.or_else(default_gateway).ok_or(PortMappingError::NoGateway)?;
+        let seed = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_nanos();
+        let mut nonce = [0u8; 12];
+        nonce.copy_from_slice(&(seed ^ std::process::id() as u128).to_be_bytes()[4..]);
+        Ok(Self { gateway, pcp: None, nonce })
+    }
+
+    /// Returns the external IP of the gateway.
+    pub async fn external_ip(&self) -> Result<IpAddr, PortMappingError> {
+        let response = self.send(&[NATPMP_VERSION, 0], 12).await?;
+        check_natpmp_response(&response, 0)?;
+        Ok(IpAddr::V4(Ipv4Addr::new(response[8], response[9], response[10], response[11])))
+    }
+
+    /// Maps the external port to the given port of this node, for the given lease duration.
+    ///
+    /// A lease of zero removes the mapping.
+    pub async fn map_port(
+        &mut self,
+        protocol: Protocol,
+        internal_port: u16,
+        external_port: u16,
+        lease: Duration,
+    ) -> Result<NatPmpMapping, PortMappingError> {
+        if self.pcp != Some(false) {
+            match self.pcp_map(protocol, internal_port, external_port, lease).await {
+                Ok(mapping) => {
+                    self.pcp = Some(true);
+                    return Ok(mapping)
+                }
+                Err(PortMappingError::Pcp(PCP_UNSUPP_VERSION)) |
+                Err(PortMappingError::InvalidResponse) |
+                Err(PortMappingError::Timeout)
+                    if self.pcp.is_none() =>
+                {
+                    trace!(target: "net::nat", gateway=%self.gateway, "PCP not supported, falling back to NAT-PMP");
+                    self.pcp = Some(false);
+                }
+                Err(err) => return Err(err),
+            }
+        }
+        self.natpmp_map(protocol, internal_port, external_port, lease).await
+    }
+
+    /// Sends a NAT-PMP mapping request.
+    async fn natpmp_map(
+        &self,
+        protocol: Protocol,
+        internal_port: u16,
+        external_port: u16,
+        lease: Duration,
+    ) -> Result<NatPmpMapping, PortMappingError> {
+        let opcode = match protocol {
+            Protocol::Udp => 1,
+            Protocol::Tcp => 2,
+        };
+        let mut request = [0u8; 12];
+        request[0] = NATPMP_VERSION;
+        request[1] = opcode;
+        request[4..6].copy_from_slice(&internal_port.to_be_bytes());
+        request[6..8].copy_from_slice(&external_port.to_be_bytes());
+        request[8..12].copy_from_slice(&lifetime_secs(lease).to_be_bytes());
+
+        let response = self.send(&request, 16).await?;
+        check_natpmp_response(&response, opcode)?;
+        Ok(NatPmpMapping {
+            external_port: u16::from_be_bytes([response[10], response[11]]),
+            external_ip: None,
+            lifetime: Duration::from_secs(
+                u32::from_be_bytes(response[12..16].try_into().unwrap()) as u64
+            ),
+        })
+    }
+
+    /// Sends a PCP `MAP` request.
+    async fn pcp_map(
+        &self,
+        protocol: Protocol,
+        internal_port: u16,
+        external_port: u16,
+        lease: Duration,
+    ) -> Result<NatPmpMapping, PortMappingError> {
+        let client_ip = match self.local_ip().await? {
+            IpAddr::V4(ip) => ip.to_ipv6_mapped(),
+            IpAddr::V6(ip) => ip,
+        };
+        let protocol_number = match protocol {
+            Protocol::Tcp => 6,
+            Protocol::Udp => 17,
+        };
+
+        let mut request = [0u8; 60];
+        request[0] = PCP_VERSION;
+        request[1] = PCP_OPCODE_MAP;
+        request[4..8].copy_from_slice(&lifetime_secs(lease).to_be_bytes());
+        request[8..24].copy_from_slice(&client_ip.octets());
+        request[24..36].copy_from_slice(&self.nonce);
+        request[36] = protocol_number;
+        request[40..42].copy_from_slice(&internal_port.to_be_bytes());
+        request[42..44].copy_from_slice(&external_port.to_be_bytes());
+        request[44..60].copy_from_slice(&Ipv4Addr::UNSPECIFIED.to_ipv6_mapped().octets());
+
+        let response = self.send(&request, 60).await?;
+        if response[0] != PCP_VERSION {
+            return Err(PortMappingError::Pcp(PCP_UNSUPP_VERSION))
+        }
+        if response[1] != 0x80 | PCP_OPCODE_MAP || response[24..36] != self.nonce {
+            return Err(PortMappingError::InvalidResponse)
+        }
+        if response[3] != 0 {
+            return Err(PortMappingError::Pcp(response[3]))
+        }
+
+        let external_ip = Ipv6Addr::from(<[u8; 16]>::try_from(&response[44..60]).unwrap());
+        Ok(NatPmpMapping {
+            external_port: u16::from_be_bytes([response[42], response[43]]),
+            external_ip: Some(
+                external_ip.to_ipv4_mapped().map(IpAddr::V4).unwrap_or(IpAddr::V6(external_ip)),
+            ),
+            lifetime: Duration::from_secs(
+                u32::from_be_bytes(response[4..8].try_into().unwrap()) as u64
+            ),
+        })
+    }
+
+    /// Returns the local IP that is used to reach the gateway.
+    async fn local_ip(&self) -> Result<IpAddr, PortMappingError> {
+        let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0)).await?;
+        socket.connect((self.gateway, SERVER_PORT)).await?;
+        Ok(socket.local_addr()?.ip())
+    }
+
+    /// Sends the request to the gateway and waits for a response of at least `min_len` bytes,
+    /// retransmitting the request with exponential backoff.
+    async fn send(&self, request: &[u8], min_len: usize) -> Result<Vec<u8>, PortMappingError> {
+        let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0)).await?;
+        let gateway = SocketAddr::from((self.gateway, SERVER_PORT));
+        socket.connect(gateway).await?;
+
+        let mut buf = [0u8; 1100];
+        let mut timeout = INITIAL_TIMEOUT;
+        for _ in 0..MAX_ATTEMPTS {
+            socket.send(request).await?;
+            if let Ok(len) = tokio::time::timeout(timeout, socket.recv(&mut buf)).await {
+                let len = len?;
+                // a server that does not understand the version may respond with a short packet
+                if len < 4 {
+                    return Err(PortMappingError::InvalidResponse)
+                }
+                if len < min_len {
+                    return if buf[0] == request[0] {
+                        Err(PortMappingError::InvalidResponse)
+                    } else {
+                        Err(PortMappingError::Pcp(PCP_UNSUPP_VERSION))
+                    }
+                }
+                return Ok(buf[..len].to_vec())
+            }
+            timeout *= 2;
+        }
+        Err(PortMappingError::Timeout)
+    }
+}
+
+/// Checks the header of a NAT-PMP response to the request with the given opcode.
+fn check_natpmp_response(response: &[u8], opcode: u8) -> Result<(), PortMappingError> {
+    if response[0] != NATPMP_VERSION || response[1] != 0x80 | opcode {
+        return Err(PortMappingError::InvalidResponse)
+    }
+    match u16::from_be_bytes([response[2], response[3]]) {
+        0 => Ok(()),
+        code => Err(PortMappingError::NatPmp(code)),
+    }
+}
+
+/// Returns the lease duration in seconds, as requested lifetime.
+fn lifetime_secs(lease: Duration) -> u32 {
+    lease.as_secs().try_into().unwrap_or(u32::MAX)
+}
+
+/// Returns the IPv4 default gateway of the host.
+///
+/// This is only supported on Linux, where the routing table is read from `/proc/net/route`.
+pub fn default_gateway() -> Option<Ipv4Addr> {
+    let routes = std::fs::read_to_string("/proc/net/route").ok()?;
+    parse_default_gateway(&routes)
+}
+
+/// Parses the default gateway from the contents of `/proc/net/route`.
+fn parse_default_gateway(routes: &str) -> Option<Ipv4Addr> {
+    routes.lines().skip(1).find_map(|line| {
+        let mut fields = line.split_whitespace();
+        let (_iface, destination, gateway) = (fields.next()?, fields.next()?, fields.next()?);
+        if destination != "00000000" {
+            return None
+        }
+        // the address is in host byte order, which is little endian on all supported platforms
+        let gateway = u32::from_str_radix(gateway, 16).ok()?;
+        Some(Ipv4Addr::from(gateway.swap_bytes()))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_proc_net_route() {
+        let routes =
+            "Iface\tDestination\tGateway \tFlags\tRefCnt\tUse\tMetric\tMask\t\tMTU\tWindow\tIRTT\n\
+                      eth0\t0000A8C0\t00000000\t0001\t0\t0\t0\t00FFFFFF\t0\t0\t0\n\
+                      eth0\t00000000\t0101A8C0\t0003\t0\t0\t0\t00000000\t0\t0\t0\n";
+        assert_eq!(parse_default_gateway(routes), Some(Ipv4Addr::new(192, 168, 1, 1)));
+        assert_eq!(parse_default_gateway("Iface\tDestination\tGateway\n"), None);
+    }
+
+    #[test]
+    fn natpmp_response_header() {
+        assert!(check_natpmp_response(&[0, 0x82, 0, 0], 2).is_ok());
+        assert!(matches!(
+            check_natpmp_response(&[0, 0x82, 0, 3], 2),
+            Err(PortMappingError::NatPmp(3))
+        ));
+        assert!(matches!(
+            check_natpmp_response(&[0, 0x81, 0, 0], 2),
+            Err(PortMappingError::InvalidResponse)
+        ));
+    }
+}
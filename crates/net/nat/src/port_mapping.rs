@@ -0,0 +1,389 @@
+//! Lifecycle of the port mappings of the node's gateway.
+//!
+//! The [`PortMapper`] maps the configured ports via `UPnP` or NAT-PMP/PCP, renews the mappings
+//! before their lease expires and removes them on shutdown. The current [`MappedAddrs`] are
+//! published via the [`PortMapperHandle`], so that the discovery services can advertise the
+//! external addresses.
+
+use std::{
+    fmt,
+    net::{IpAddr, Ipv4Addr},
+    time::Duration,
+};
+
+use tokio::sync::{oneshot, watch};
+use tracing::{debug, trace};
+
+use crate::{natpmp::NatPmpGateway, upnp::UpnpGateway, NatResolver};
+
+/// Default lease duration of a port mapping.
+pub const DEFAULT_MAPPING_LEASE: Duration = Duration::from_secs(60 * 60);
+
+/// Interval between attempts if the ports could not be mapped.
+const RETRY_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Minimum interval between renewals of the mappings.
+const MIN_RENEWAL_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Error when mapping ports.
+#[derive(Debug, thiserror::Error)]
+pub enum PortMappingError {
+    /// Failed to communicate with the gateway.
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    /// HTTP request to the `UPnP` gateway failed.
+    #[error(transparent)]
+    Http(#[from] reqwest::Error),
+    /// No `UPnP` gateway was discovered.
+    #[error("no UPnP gateway found")]
+    NoUpnpGateway,
+    /// The `UPnP` gateway returned an error.
+    #[error("UPnP error: {0}")]
+    Upnp(String),
+    /// The default gateway is unknown.
+    #[error("no default gateway found")]
+    NoGateway,
+    /// The NAT-PMP gateway returned an error result code.
+    #[error("NAT-PMP result code {0}")]
+    NatPmp(u16),
+    /// The PCP gateway returned an error result code.
+    #[error("PCP result code {0}")]
+    Pcp(u8),
+    /// The gateway sent an invalid response.
+    #[error("invalid response from gateway")]
+    InvalidResponse,
+    /// The gateway did not respond.
+    #[error("gateway did not respond")]
+    Timeout,
+}
+
+/// Transport protocol of a port mapping.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Protocol {
+    /// TCP, used by `RLPx`.
+    Tcp,
+    /// UDP, used by discovery.
+    Udp,
+}
+
+impl fmt::Display for Protocol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Tcp => f.write_str("TCP"),
+            Self::Udp => f.write_str("UDP"),
+        }
+    }
+}
+
+/// A port of the node that should be reachable from outside.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PortMappingRequest {
+    /// Transport protocol of the port.
+    pub protocol: Protocol,
+    /// The local port.
+    pub port: u16,
+}
+
+impl PortMappingRequest {
+    /// Returns a request to map the given TCP port.
+    pub const fn tcp(port: u16) -> Self {
+        Self { protocol: Protocol::Tcp, port }
+    }
+
+    /// Returns a request to map the given UDP port.
+    pub const fn udp(port: u16) -> Self {
+        Self { protocol: Protocol::Udp, port }
+    }
+}
+
+/// Configuration of the [`PortMapper`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PortMappingConfig {
+    /// The ports to map.
+    pub ports: Vec<PortMappingRequest>,
+    /// Requested lease duration of the mappings. The mappings are renewed at half the lease.
+    pub lease: Duration,
+}
+
+impl PortMappingConfig {
+    /// Returns a config that maps the given ports with the [`DEFAULT_MAPPING_LEASE`].
+    pub fn new(ports: impl IntoIterator<Item = PortMappingRequest>) -> Self {
+        let mut deduped = Vec::new();
+        for port in ports {
+            if !deduped.contains(&port) {
+                deduped.push(port);
+            }
+        }
+        Self { ports: deduped, lease: DEFAULT_MAPPING_LEASE }
+    }
+
+    /// Sets the requested lease duration of the mappings.
+    pub const fn with_lease(mut self, lease: Duration) -> Self {
+        self.lease = lease;
+        self
+    }
+}
+
+/// A port that is mapped by the gateway.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MappedPort {
+    /// Transport protocol of the port.
+    pub protocol: Protocol,
+    /// The local port.
+    pub internal_port: u16,
+    /// The external port on the gateway.
+    pub external_port: u16,
+}
+
+/// The external addresses of the node, as mapped by the gateway.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MappedAddrs {
+    /// The external IP of the gateway.
+    pub external_ip: IpAddr,
+    /// The mapped ports.
+    pub ports: Vec<MappedPort>,
+}
+
+impl MappedAddrs {
+    /// Returns the external port the given local port is mapped to, if any.
+    pub fn external_port(&self, protocol: Protocol, internal_port: u16) -> Option<u16> {
+        self.ports
+            .iter()
+            .find(|mapped| mapped.protocol == protocol && mapped.internal_port == internal_port)
+            .map(|mapped| mapped.external_port)
+    }
+}
+
+/// The gateway protocol used to map ports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Method {
+    Upnp,
+    NatPmp(Option<Ipv4Addr>),
+}
+
+/// A discovered gateway.
+#[derive(Debug)]
+enum Gateway {
+    Upnp(UpnpGateway),
+    NatPmp(NatPmpGateway),
+}
+
+/// Maps the configured ports on the gateway for as long as its [`PortMapperHandle`] is alive.
+///
+/// This type is a service that must be spawned via [`PortMapper::run`].
+#[must_use = "Does nothing unless run"]
+pub struct PortMapper {
+    method: Method,
+    config: PortMappingConfig,
+    gateway: Option<Gateway>,
+    mapped: watch::Sender<Option<MappedAddrs>>,
+    shutdown: oneshot::Receiver<()>,
+}
+
+impl fmt::Debug for PortMapper {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PortMapper")
+            .field("method", &self.method)
+            .field("config", &self.config)
+            .field("gateway", &self.gateway)
+            .finish_non_exhaustive()
+    }
+}
+
+impl PortMapper {
+    /// Creates a new port mapper for the given resolver.
+    ///
+    /// Returns `None` if the resolver does not support port mapping, which are all but
+    /// [`NatResolver::Upnp`] and [`NatResolver::NatPmp`].
+    pub fn new(
+        resolver: NatResolver,
+        config: PortMappingConfig,
+    ) -> Option<(Self, PortMapperHandle)> {
+        let method = match resolver {
+            NatResolver::Upnp => Method::Upnp,
+            NatResolver::NatPmp(gateway) => Method::NatPmp(gateway),
+            _ => return None,
+        };
+        let (mapped, mapped_rx) = watch::channel(None);
+        let (shutdown_tx, shutdown) = oneshot::channel();
+        let mapper = Self { method, config, gateway: None, mapped, shutdown };
+        Some((mapper, PortMapperHandle { mapped: mapped_rx, shutdown: Some(shutdown_tx) }))
+    }
+
+    /// Maps the ports and renews the mappings until the [`PortMapperHandle`] is dropped, then
+    /// removes the mappings.
+    pub async fn run(mut self) {
+        loop {
+            let next = match self.map_ports().await {
+                Ok((addrs, renewal)) => {
+                    self.update(Some(addrs));
+                    renewal
+                }
+                Err(err) => {
+                    debug!(target: "net::nat", %err, "Failed to map ports");
+                    // rediscover the gateway on the next attempt
+                    self.gateway = None;
+                    self.update(None);
+                    RETRY_INTERVAL
+                }
+            };
+
+            tokio::select! {
+                _ = tokio::time::sleep(next) => {}
+                _ = &mut self.shutdown => break,
+            }
+        }
+
+        self.remove_mappings().await;
+    }
+
+    /// Maps all configured ports and returns the mapped addresses and when to renew them.
+    async fn map_ports(&mut self) -> Result<(MappedAddrs, Duration), PortMappingError> {
+        let lease = self.config.lease;
+        let mut renewal = lease / 2;
+        let requests = self.config.ports.clone();
+        let mut ports = Vec::with_capacity(requests.len());
+
+        let external_ip = match self.gateway().await? {
+            Gateway::Upnp(gateway) => {
+                for request in &requests {
+                    gateway
+                        .add_port_mapping(request.protocol, request.port, request.port, lease)
+                        .await?;
+                    ports.push(MappedPort {
+                        protocol: request.protocol,
+                        internal_port: request.port,
+                        external_port: request.port,
+                    });
+                }
+                gateway.external_ip().await?
+            }
+            Gateway::NatPmp(gateway) => {
+                let mut external_ip = None;
+                for request in &requests {
+                    let mapping = gateway
+                        .map_port(request.protocol, request.port, request.port, lease)
+                        .await?;
+                    renewal = renewal.min(mapping.lifetime / 2);
+                    external_ip = external_ip.or(mapping.external_ip);
+                    ports.push(MappedPort {
+                        protocol: request.protocol,
+                        internal_port: request.port,
+                        external_port: mapping.external_port,
+                    });
+                }
+                match external_ip {
+                    Some(ip) => ip,
+                    None => gateway.external_ip().await?,
+                }
+            }
+        };
+
+        trace!(target: "net::nat", %external_ip, ?ports, "Mapped ports");
+        Ok((MappedAddrs { external_ip, ports }, renewal.max(MIN_RENEWAL_INTERVAL)))
+    }
+
+    /// Returns the gateway, discovering it if necessary.
+    async fn gateway(&mut self) -> Result<&mut Gateway, PortMappingError> {
+        if self.gateway.is_none() {
+            let gateway = match self.method {
+                Method::Upnp => Gateway::Upnp(UpnpGateway::discover().await?),
+                Method::NatPmp(gateway) => Gateway::NatPmp(NatPmpGateway::new(gateway)?),
+            };
+            debug!(target: "net::nat", ?gateway, "Discovered gateway");
+            self.gateway = Some(gateway);
+        }
+        Ok(self.gateway.as_mut().expect("is set"))
+    }
+
+    /// Removes all mappings of the current gateway.
+    async fn remove_mappings(&mut self) {
+        let Some(mapped) = self.mapped.borrow().clone() else { return };
+        let Some(gateway) = self.gateway.as_mut() else { return };
+
+        for port in mapped.ports {
+            let res = match gateway {
+                Gateway::Upnp(gateway) => {
+                    gateway.remove_port_mapping(port.protocol, port.external_port).await
+                }
+                Gateway::NatPmp(gateway) => gateway
+                    .map_port(port.protocol, port.internal_port, 0, Duration::ZERO)
+                    .await
+                    .map(drop),
+            };
+            if let Err(err) = res {
+                debug!(target: "net::nat", ?port, %err, "Failed to remove port mapping");
+            }
+        }
+        trace!(target: "net::nat", "Removed port mappings");
+        self.update(None);
+    }
+
+    /// Publishes the mapped addresses, if they changed.
+    fn update(&self, addrs: Option<MappedAddrs>) {
+        self.mapped.send_if_modified(|current| {
+            if *current == addrs {
+                return false
+            }
+            debug!(target: "net::nat", ?addrs, "Port mappings changed");
+            *current = addrs;
+            true
+        });
+    }
+}
+
+/// Handle to a spawned [`PortMapper`].
+///
+/// Dropping the handle shuts down the port mapper, which removes the mappings.
+#[derive(Debug)]
+pub struct PortMapperHandle {
+    mapped: watch::Receiver<Option<MappedAddrs>>,
+    shutdown: Option<oneshot::Sender<()>>,
+}
+
+impl PortMapperHandle {
+    /// Returns the currently mapped addresses, if any.
+    pub fn mapped_addrs(&self) -> Option<MappedAddrs> {
+        self.mapped.borrow().clone()
+    }
+
+    /// Returns a receiver that is notified whenever the mapped addresses change.
+    pub fn subscribe(&self) -> watch::Receiver<Option<MappedAddrs>> {
+        self.mapped.clone()
+    }
+}
+
+impl Drop for PortMapperHandle {
+    fn drop(&mut self) {
+        if let Some(shutdown) = self.shutdown.take() {
+            let _ = shutdown.send(());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_gateway_resolvers_map_ports() {
+        let config = PortMappingConfig::new([PortMappingRequest::tcp(30303)]);
+        assert!(PortMapper::new(NatResolver::Upnp, config.clone()).is_some());
+        assert!(PortMapper::new(NatResolver::NatPmp(None), config.clone()).is_some());
+        assert!(PortMapper::new(NatResolver::Any, config.clone()).is_none());
+        assert!(PortMapper::new(NatResolver::PublicIp, config).is_none());
+    }
+
+    #[test]
+    fn dedup_mapping_requests() {
+        let config = PortMappingConfig::new([
+            PortMappingRequest::tcp(30303),
+            PortMappingRequest::udp(30303),
+            PortMappingRequest::udp(30303),
+        ]);
+        assert_eq!(
+            config.ports,
+            vec![PortMappingRequest::tcp(30303), PortMappingRequest::udp(30303)]
+        );
+    }
+}
@@ -0,0 +1,254 @@
+//! Port mapping via `UPnP` Internet Gateway Devices.
+//!
+//! The gateway is discovered via SSDP, the mappings are managed with the SOAP actions of the
+//! `WANIPConnection` or `WANPPPConnection` service of the gateway.
+
+use std::{
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    time::Duration,
+};
+
+use reqwest::Url;
+use tokio::net::UdpSocket;
+use tracing::{debug, trace};
+
+use crate::port_mapping::{PortMappingError, Protocol};
+
+/// SSDP multicast address.
+const SSDP_ADDR: SocketAddr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(239, 255, 255, 250)), 1900);
+
+/// How long to wait for SSDP responses.
+const SSDP_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Device type of an Internet Gateway Device.
+const IGD_DEVICE_TYPE: &str = "urn:schemas-upnp-org:device:InternetGatewayDevice:1";
+
+/// Services of an Internet Gateway Device that support port mappings, in order of preference.
+const WAN_SERVICE_TYPES: &[&str] = &[
+    "urn:schemas-upnp-org:service:WANIPConnection:2",
+    "urn:schemas-upnp-org:service:WANIPConnection:1",
+    "urn:schemas-upnp-org:service:WANPPPConnection:1",
+];
+
+/// Description of the port mappings.
+const MAPPING_DESCRIPTION: &str = "reth";
+
+/// A `UPnP` Internet Gateway Device.
+#[derive(Debug, Clone)]
+pub struct UpnpGateway {
+    /// URL of the control endpoint of the WAN service.
+    control_url: Url,
+    /// Type of the WAN service.
+    service_type: &'static str,
+    /// Local IP of this node, as seen by the gateway.
+    local_ip: IpAddr,
+}
+
+impl UpnpGateway {
+    /// Discovers the gateway of the local network via SSDP.
+    pub async fn discover() -> Result<Self, PortMappingError> {
+        let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0)).await?;
+        let request = format!(
+            "M-SEARCH * HTTP/1.1\r\nHOST: {SSDP_ADDR}\r\nST: {IGD_DEVICE_TYPE}\r\nMAN: \"ssdp:discover\"\r\nMX: 2\r\n\r\n"
+        );
+        socket.send_to(request.as_bytes(), SSDP_ADDR).await?;
+
+        let mut buf = [0u8; 2048];
+        let deadline = tokio::time::Instant::now() + SSDP_TIMEOUT;
+        loop {
+            let (len, from) = tokio::time::timeout_at(deadline, socket.recv_from(&mut buf))
+                .await
+                .map_err(|_| PortMappingError::NoUpnpGateway)??;
+            let Some(location) = ssdp_location(&String::from_utf8_lossy(&buf[..len])) else {
+                continue
+            };
+            trace!(target: "net::nat", %from, %location, "discovered UPnP device");
+
+            match Self::from_location(&location).await {
+                Ok(gateway) => return Ok(gateway),
+                Err(err) => debug!(target: "net::nat", %location, %err, "unsupported UPnP device"),
+            }
+        }
+    }
+
+    /// Creates the gateway from the URL of its device description.
+    async fn from_location(location: &str) -> Result<Self, PortMappingError> {
+        let location =
+            Url::parse(location).map_err(|err| PortMappingError::Upnp(err.to_string()))?;
+        let description = reqwest::get(location.clone()).await?.error_for_status()?.text().await?;
+
+        let (service_type, control_url) = wan_service(&description)
+            .ok_or_else(|| PortMappingError::Upnp("no WAN connection service".to_string()))?;
+        let control_url =
+            location.join(&control_url).map_err(|err| PortMappingError::Upnp(err.to_string()))?;
+
+        // the local IP is the one that is used to reach the gateway
+        let host = location
+            .socket_addrs(|| None)
+            .map_err(PortMappingError::Io)?
+            .into_iter()
+            .next()
+            .ok_or_else(|| PortMappingError::Upnp("unresolvable gateway".to_string()))?;
+        let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0)).await?;
+        socket.connect(host).await?;
+        let local_ip = socket.local_addr()?.ip();
+
+        Ok(Self { control_url, service_type, local_ip })
+    }
+
+    /// Returns the external IP of the gateway.
+    pub async fn external_ip(&self) -> Result<IpAddr, PortMappingError> {
+        let response = self.request("GetExternalIPAddress", "").await?;
+        xml_value(&response, "NewExternalIPAddress")
+            .and_then(|ip| ip.parse().ok())
+            .ok_or(PortMappingError::InvalidResponse)
+    }
+
+    /// Maps the external port to the given port of this node, for the given lease duration.
+    pub async fn add_port_mapping(
+        &self,
+        protocol: Protocol,
+        internal_port: u16,
+        external_port: u16,
+        lease: Duration,
+    ) -> Result<(), PortMappingError> {
+        let args = format!(
+            "<NewRemoteHost></NewRemoteHost>\
+             <NewExternalPort>{external_port}</NewExternalPort>\
+             <NewProtocol>{protocol}</NewProtocol>\
+             <NewInternalPort>{internal_port}</NewInternalPort>\
+             <NewInternalClient>{}</NewInternalClient>\
+             <NewEnabled>1</NewEnabled>\
+             <NewPortMappingDescription>{MAPPING_DESCRIPTION}</NewPortMappingDescription>\
+             <NewLeaseDuration>{}</NewLeaseDuration>",
+            self.local_ip,
+            lease.as_secs()
+        );
+        self.request("AddPortMapping", &args).await.map(drop)
+    }
+
+    /// Removes the mapping of the external port.
+    pub async fn remove_port_mapping(
+        &self,
+        protocol: Protocol,
+        external_port: u16,
+    ) -> Result<(), PortMappingError> {
+        let args = format!(
+            "<NewRemoteHost></NewRemoteHost>\
+             <NewExternalPort>{external_port}</NewExternalPort>\
+             <NewProtocol>{protocol}</NewProtocol>"
+        );
+        self.request("DeletePortMapping", &args).await.map(drop)
+    }
+
+    /// Sends a SOAP request for the given action to the gateway and returns the response body.
+    async fn request(&self, action: &str, args: &str) -> Result<String, PortMappingError> {
+        let body = format!(
+            "<?xml version=\"1.0\"?>\
+             <s:Envelope xmlns:s=\"http://schemas.xmlsoap.org/soap/envelope/\" \
+             s:encodingStyle=\"http://schemas.xmlsoap.org/soap/encoding/\">\
+             <s:Body><u:{action} xmlns:u=\"{}\">{args}</u:{action}></s:Body></s:Envelope>",
+            self.service_type
+        );
+        let response = reqwest::Client::new()
+            .post(self.control_url.clone())
+            .header("Content-Type", "text/xml; charset=\"utf-8\"")
+            .header("SOAPAction", format!("\"{}#{action}\"", self.service_type))
+            .body(body)
+            .send()
+            .await?;
+
+        let status = response.status();
+        let text = response.text().await?;
+        if !status.is_success() {
+            let description = xml_value(&text, "errorDescription").unwrap_or_default();
+            return Err(PortMappingError::Upnp(format!("{action} failed: {status} {description}")))
+        }
+        Ok(text)
+    }
+}
+
+/// Returns the value of the `LOCATION` header of an SSDP response.
+fn ssdp_location(response: &str) -> Option<String> {
+    response.lines().find_map(|line| {
+        let (name, value) = line.split_once(':')?;
+        name.trim().eq_ignore_ascii_case("location").then(|| value.trim().to_string())
+    })
+}
+
+/// Returns the type and the control URL of the preferred WAN connection service of a device
+/// description.
+fn wan_service(description: &str) -> Option<(&'static str, String)> {
+    let services = description.split("<service>").skip(1).collect::<Vec<_>>();
+    WAN_SERVICE_TYPES.iter().find_map(|service_type| {
+        services.iter().find_map(|service| {
+            (xml_value(service, "serviceType")? == *service_type)
+                .then(|| xml_value(service, "controlURL"))
+                .flatten()
+                .map(|control_url| (*service_type, control_url.to_string()))
+        })
+    })
+}
+
+/// Returns the text of the first element with the given name, ignoring namespace prefixes.
+fn xml_value<'a>(xml: &'a str, name: &str) -> Option<&'a str> {
+    let mut rest = xml;
+    loop {
+        let start = rest.find('<')?;
+        rest = &rest[start + 1..];
+        let end = rest.find('>')?;
+        let tag = &rest[..end];
+        rest = &rest[end + 1..];
+
+        let tag_name = tag.split_whitespace().next().unwrap_or_default();
+        let local_name = tag_name.rsplit(':').next().unwrap_or_default();
+        if local_name == name && !tag.ends_with('/') {
+            return rest.find("</").map(|end| rest[..end].trim())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_ssdp_location() {
+        let response = "HTTP/1.1 200 OK\r\nCACHE-CONTROL: max-age=120\r\nLocation: http://192.168.1.1:5000/rootDesc.xml\r\nST: urn:schemas-upnp-org:device:InternetGatewayDevice:1\r\n\r\n";
+        assert_eq!(ssdp_location(response).unwrap(), "http://192.168.1.1:5000/rootDesc.xml");
+    }
+
+    #[test]
+    fn parse_wan_service() {
+        let description = r#"<?xml version="1.0"?>
+<root xmlns="urn:schemas-upnp-org:device-1-0">
+  <device>
+    <serviceList>
+      <service>
+        <serviceType>urn:schemas-upnp-org:service:Layer3Forwarding:1</serviceType>
+        <controlURL>/ctl/L3F</controlURL>
+      </service>
+      <service>
+        <serviceType>urn:schemas-upnp-org:service:WANIPConnection:1</serviceType>
+        <serviceId>urn:upnp-org:serviceId:WANIPConn1</serviceId>
+        <controlURL>/ctl/IPConn</controlURL>
+      </service>
+    </serviceList>
+  </device>
+</root>"#;
+        let (service_type, control_url) = wan_service(description).unwrap();
+        assert_eq!(service_type, "urn:schemas-upnp-org:service:WANIPConnection:1");
+        assert_eq!(control_url, "/ctl/IPConn");
+    }
+
+    #[test]
+    fn parse_soap_response() {
+        let response = r#"<?xml version="1.0"?>
+<s:Envelope xmlns:s="http://schemas.xmlsoap.org/soap/envelope/"><s:Body>
+<u:GetExternalIPAddressResponse xmlns:u="urn:schemas-upnp-org:service:WANIPConnection:1">
+<NewExternalIPAddress>203.0.113.7</NewExternalIPAddress>
+</u:GetExternalIPAddressResponse></s:Body></s:Envelope>"#;
+        assert_eq!(xml_value(response, "NewExternalIPAddress"), Some("203.0.113.7"));
+        assert_eq!(xml_value(response, "errorDescription"), None);
+    }
+}
@@ -10,7 +10,7 @@ use reth_eth_wire_types::{
 use reth_ethereum_forks::ForkId;
 use reth_network_p2p::error::{RequestError, RequestResult};
 use reth_network_peers::PeerId;
-use reth_network_types::PeerAddr;
+use reth_network_types::{PeerAddr, PeerKind};
 use reth_tokio_util::EventStream;
 use tokio::sync::{mpsc, oneshot};
 use tokio_stream::wrappers::UnboundedReceiverStream;
@@ -55,6 +55,8 @@ pub enum NetworkEvent {
         status: Arc<Status>,
         /// negotiated eth version of the session
         version: EthVersion,
+        /// The kind of the peer, e.g. whether it is trusted.
+        peer_kind: PeerKind,
     },
     /// Event emitted when a new peer is added
     PeerAdded(PeerId),
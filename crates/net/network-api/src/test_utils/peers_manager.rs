@@ -61,6 +61,14 @@ impl PeersHandle {
 
         rx.await.unwrap_or_default()
     }
+
+    /// Returns all peers in the peerset, together with their [`Peer`] info.
+    pub async fn all_peers_with_info(&self) -> Vec<(PeerId, Peer)> {
+        let (tx, rx) = oneshot::channel();
+        self.send(PeerCommand::GetPeersWithInfo(tx));
+
+        rx.await.unwrap_or_default()
+    }
 }
 
 /// Commands the `PeersManager` listens for.
@@ -78,4 +86,6 @@ pub enum PeerCommand {
     GetPeer(PeerId, oneshot::Sender<Option<Peer>>),
     /// Get node information on all peers
     GetPeers(oneshot::Sender<Vec<NodeRecord>>),
+    /// Get all peers, together with their info
+    GetPeersWithInfo(oneshot::Sender<Vec<(PeerId, Peer)>>),
 }
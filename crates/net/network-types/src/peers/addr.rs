@@ -9,10 +9,13 @@ use std::net::{IpAddr, SocketAddr};
 /// - `tcp`: A `SocketAddr` representing the peer's data transfer address.
 /// - `udp`: An optional `SocketAddr` representing the peer's discover address. `None` if the peer
 ///   is directly connecting to us or the port is the same to `tcp`'s
+/// - `fallback_tcp`: An optional `SocketAddr` of the other IP version, that the peer can also be
+///   reached at, if it runs dual-stack.
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub struct PeerAddr {
     tcp: SocketAddr,
     udp: Option<SocketAddr>,
+    fallback_tcp: Option<SocketAddr>,
 }
 
 impl PeerAddr {
@@ -26,14 +29,38 @@ impl PeerAddr {
         self.udp
     }
 
+    /// Returns the peer's TCP address of the other IP version, if it runs dual-stack.
+    pub const fn fallback_tcp(&self) -> Option<SocketAddr> {
+        self.fallback_tcp
+    }
+
     /// Returns a new `PeerAddr` with the given `tcp` and `udp` addresses.
     pub const fn new(tcp: SocketAddr, udp: Option<SocketAddr>) -> Self {
-        Self { tcp, udp }
+        Self { tcp, udp, fallback_tcp: None }
     }
 
     /// Returns a new `PeerAddr` with a `tcp` address only.
     pub const fn from_tcp(tcp: SocketAddr) -> Self {
-        Self { tcp, udp: None }
+        Self { tcp, udp: None, fallback_tcp: None }
+    }
+
+    /// Sets the TCP address of the other IP version to fall back to, if the peer is unreachable
+    /// at the `tcp` address.
+    pub const fn with_fallback_tcp(mut self, fallback_tcp: Option<SocketAddr>) -> Self {
+        self.fallback_tcp = fallback_tcp;
+        self
+    }
+
+    /// Swaps the `tcp` address with the fallback address, if any. Returns `true` if the address
+    /// was swapped.
+    ///
+    /// The previous `tcp` address becomes the new fallback, so that repeated failures alternate
+    /// between the two IP versions.
+    pub fn swap_fallback_tcp(&mut self) -> bool {
+        let Some(fallback_tcp) = self.fallback_tcp else { return false };
+        self.fallback_tcp = Some(self.tcp);
+        self.tcp = fallback_tcp;
+        true
     }
 
     /// Returns a new `PeerAddr` with the given `tcp` and `udp` ports.
@@ -43,3 +70,26 @@ impl PeerAddr {
         Self::new(tcp, udp)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn swap_fallback_tcp() {
+        let tcp: SocketAddr = "104.28.44.25:30303".parse().unwrap();
+        let fallback: SocketAddr = "[2001:db8::1]:30303".parse().unwrap();
+
+        let mut addr = PeerAddr::from_tcp(tcp);
+        assert!(!addr.swap_fallback_tcp());
+        assert_eq!(addr.tcp(), tcp);
+
+        let mut addr = addr.with_fallback_tcp(Some(fallback));
+        assert!(addr.swap_fallback_tcp());
+        assert_eq!(addr.tcp(), fallback);
+        assert_eq!(addr.fallback_tcp(), Some(tcp));
+
+        assert!(addr.swap_fallback_tcp());
+        assert_eq!(addr.tcp(), tcp);
+    }
+}
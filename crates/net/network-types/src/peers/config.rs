@@ -11,7 +11,10 @@ use reth_net_banlist::BanList;
 use reth_network_peers::{NodeRecord, TrustedPeer};
 use tracing::info;
 
-use crate::{BackoffKind, ReputationChangeWeights};
+use crate::{
+    peers::persist::{PeersFile, PersistedPeers, PERSISTED_PEERS_VERSION},
+    BackoffKind, ReputationChangeWeights,
+};
 
 /// Maximum number of available slots for outbound sessions.
 pub const DEFAULT_MAX_COUNT_PEERS_OUTBOUND: u32 = 100;
@@ -141,6 +144,9 @@ pub struct PeersConfig {
     /// Basic nodes to connect to.
     #[cfg_attr(feature = "serde", serde(skip))]
     pub basic_nodes: HashSet<NodeRecord>,
+    /// Reputation, ban and backoff state of peers, restored from the peers file.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub persisted_peers: PersistedPeers,
     /// How long to ban bad peers.
     #[cfg_attr(feature = "serde", serde(with = "humantime_serde"))]
     pub ban_duration: Duration,
@@ -170,6 +176,7 @@ impl Default for PeersConfig {
             trusted_nodes: Default::default(),
             trusted_nodes_only: false,
             basic_nodes: Default::default(),
+            persisted_peers: Default::default(),
             max_backoff_count: 5,
         }
     }
@@ -240,6 +247,15 @@ impl PeersConfig {
         self
     }
 
+    /// Peer state to restore at launch.
+    ///
+    /// The persisted peers are added to the basic nodes.
+    pub fn with_persisted_peers(mut self, persisted_peers: PersistedPeers) -> Self {
+        self.basic_nodes.extend(persisted_peers.node_records());
+        self.persisted_peers = persisted_peers;
+        self
+    }
+
     /// Configures the max allowed backoff count.
     pub const fn with_max_backoff_count(mut self, max_backoff_count: u8) -> Self {
         self.max_backoff_count = max_backoff_count;
@@ -266,7 +282,10 @@ impl PeersConfig {
         self.connection_info.max_outbound + self.connection_info.max_inbound
     }
 
-    /// Read from file nodes available at launch. Ignored if None.
+    /// Read from file nodes available at launch, and their persisted state. Ignored if None.
+    ///
+    /// Both the versioned format of [`PersistedPeers`] and the legacy format, a plain list of
+    /// [`NodeRecord`]s, are accepted.
     pub fn with_basic_nodes_from_file(
         self,
         optional_file: Option<impl AsRef<Path>>,
@@ -278,8 +297,14 @@ impl PeersConfig {
             Err(e) => Err(e)?,
         };
         info!(target: "net::peers", file = %file_path.as_ref().display(), "Loading saved peers");
-        let nodes: HashSet<NodeRecord> = serde_json::from_reader(reader)?;
-        Ok(self.with_basic_nodes(nodes))
+        let peers = serde_json::from_reader::<_, PeersFile>(reader)?.into_persisted_peers();
+        if peers.version > PERSISTED_PEERS_VERSION {
+            return Err(io::Error::new(
+                ErrorKind::InvalidData,
+                format!("unsupported peers file version {}", peers.version),
+            ))
+        }
+        Ok(self.with_persisted_peers(peers))
     }
 
     /// Returns settings for testing
@@ -1,12 +1,15 @@
 pub mod addr;
 pub mod config;
 pub mod kind;
+pub mod persist;
 pub mod reputation;
 pub mod state;
 
 pub use config::{ConnectionsConfig, PeersConfig};
 pub use reputation::{Reputation, ReputationChange, ReputationChangeKind, ReputationChangeWeights};
 
+use std::{ops::RangeInclusive, time::SystemTime};
+
 use reth_ethereum_forks::ForkId;
 use tracing::trace;
 
@@ -35,6 +38,12 @@ pub struct Peer {
     /// Counts number of times the peer was backed off due to a severe
     /// [`BackoffKind`](crate::BackoffKind).
     pub severe_backoff_counter: u8,
+    /// The range of blocks the peer announced it can serve, if known.
+    ///
+    /// This is only advertised by `eth/69` peers.
+    pub block_range: Option<RangeInclusive<u64>>,
+    /// When a session to the peer was last active, if ever.
+    pub last_seen: Option<SystemTime>,
 }
 
 // === impl Peer ===
@@ -66,6 +75,8 @@ impl Peer {
             kind: Default::default(),
             backed_off: false,
             severe_backoff_counter: 0,
+            block_range: None,
+            last_seen: None,
         }
     }
 
@@ -74,6 +85,13 @@ impl Peer {
         Self { kind, ..Self::new(addr) }
     }
 
+    /// Returns `true` if the peer announced that it can serve the given block.
+    ///
+    /// Peers that did not announce a block range are assumed to serve the full history.
+    pub fn can_serve_block(&self, block: u64) -> bool {
+        self.block_range.as_ref().map_or(true, |range| *range.start() <= block)
+    }
+
     /// Resets the reputation of the peer to the default value. This always returns
     /// [`ReputationChangeOutcome::None`].
     pub fn reset_reputation(&mut self) -> ReputationChangeOutcome {
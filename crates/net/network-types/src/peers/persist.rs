@@ -0,0 +1,201 @@
+//! Persisted state of the peer set.
+//!
+//! The peer set is written to the peers file on shutdown and loaded at startup, so that the
+//! reputation, ban and backoff state of known peers survives a restart.
+
+use std::{
+    collections::HashSet,
+    net::IpAddr,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use reth_network_peers::NodeRecord;
+use serde::{Deserialize, Serialize};
+
+use crate::DEFAULT_REPUTATION;
+
+/// Current version of the peers file format.
+pub const PERSISTED_PEERS_VERSION: u64 = 1;
+
+/// Contents of the peers file.
+///
+/// See also [`PeersFile`] for the accepted file formats.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PersistedPeers {
+    /// Version of the file format.
+    pub version: u64,
+    /// Known peers.
+    pub peers: Vec<PersistedPeer>,
+    /// Temporarily banned IPs.
+    #[serde(default)]
+    pub banned_ips: Vec<PersistedBannedIp>,
+}
+
+impl Default for PersistedPeers {
+    fn default() -> Self {
+        Self { version: PERSISTED_PEERS_VERSION, peers: Vec::new(), banned_ips: Vec::new() }
+    }
+}
+
+impl PersistedPeers {
+    /// Returns the [`NodeRecord`]s of all persisted peers.
+    pub fn node_records(&self) -> HashSet<NodeRecord> {
+        self.peers.iter().map(|peer| peer.node).collect()
+    }
+}
+
+/// Persisted state of a single peer.
+///
+/// All timestamps are unix timestamps in seconds.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PersistedPeer {
+    /// Where to reach the peer.
+    pub node: NodeRecord,
+    /// Reputation of the peer.
+    #[serde(default = "default_reputation")]
+    pub reputation: i32,
+    /// Until when the peer is banned, if it is.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub banned_until: Option<u64>,
+    /// Until when the peer is backed off, if it is.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub backed_off_until: Option<u64>,
+    /// Number of times the peer was backed off due to a severe
+    /// [`BackoffKind`](crate::BackoffKind).
+    #[serde(default)]
+    pub severe_backoff_counter: u8,
+    /// When a session to the peer was last active.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_seen: Option<u64>,
+}
+
+impl PersistedPeer {
+    /// Returns a new entry for the given [`NodeRecord`] with default reputation and no ban or
+    /// backoff.
+    pub const fn new(node: NodeRecord) -> Self {
+        Self {
+            node,
+            reputation: DEFAULT_REPUTATION,
+            banned_until: None,
+            backed_off_until: None,
+            severe_backoff_counter: 0,
+            last_seen: None,
+        }
+    }
+}
+
+/// A temporarily banned IP.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PersistedBannedIp {
+    /// The banned IP.
+    pub ip: IpAddr,
+    /// Unix timestamp in seconds until when the IP is banned.
+    pub until: u64,
+}
+
+/// All accepted formats of the peers file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum PeersFile {
+    /// Versioned format, that includes the state of the peers.
+    Versioned(PersistedPeers),
+    /// Legacy format, a plain list of peer addresses.
+    Legacy(HashSet<NodeRecord>),
+}
+
+impl PeersFile {
+    /// Converts the file contents into [`PersistedPeers`].
+    ///
+    /// Peers of the legacy format are loaded with default reputation.
+    pub fn into_persisted_peers(self) -> PersistedPeers {
+        match self {
+            Self::Versioned(peers) => peers,
+            Self::Legacy(nodes) => PersistedPeers {
+                peers: nodes.into_iter().map(PersistedPeer::new).collect(),
+                ..Default::default()
+            },
+        }
+    }
+}
+
+fn default_reputation() -> i32 {
+    DEFAULT_REPUTATION
+}
+
+/// Converts the given [`SystemTime`] to a unix timestamp in seconds.
+pub fn to_unix_timestamp(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs()
+}
+
+/// Converts the given [`Instant`] to a unix timestamp in seconds.
+pub fn instant_to_unix_timestamp(instant: Instant) -> u64 {
+    let now = Instant::now();
+    let time = if instant >= now {
+        SystemTime::now() + (instant - now)
+    } else {
+        SystemTime::now() - (now - instant)
+    };
+    to_unix_timestamp(time)
+}
+
+/// Converts the given unix timestamp in seconds to an [`Instant`].
+///
+/// Returns `None` if the timestamp is in the past.
+pub fn unix_timestamp_to_instant(timestamp: u64) -> Option<Instant> {
+    let remaining =
+        (UNIX_EPOCH + Duration::from_secs(timestamp)).duration_since(SystemTime::now()).ok()?;
+    Some(Instant::now() + remaining)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_network_peers::PeerId;
+    use std::net::Ipv4Addr;
+
+    fn node() -> NodeRecord {
+        NodeRecord::new((Ipv4Addr::new(104, 28, 44, 25), 30303).into(), PeerId::with_last_byte(1))
+    }
+
+    #[test]
+    fn load_legacy_peers_file() {
+        let node = node();
+        let file = serde_json::to_string(&vec![node]).unwrap();
+
+        let peers = serde_json::from_str::<PeersFile>(&file).unwrap().into_persisted_peers();
+        assert_eq!(peers.version, PERSISTED_PEERS_VERSION);
+        assert_eq!(peers.peers, vec![PersistedPeer::new(node)]);
+    }
+
+    #[test]
+    fn persisted_peers_roundtrip() {
+        let peers = PersistedPeers {
+            peers: vec![PersistedPeer {
+                reputation: -100,
+                banned_until: Some(1_700_000_000),
+                last_seen: Some(1_600_000_000),
+                ..PersistedPeer::new(node())
+            }],
+            banned_ips: vec![PersistedBannedIp {
+                ip: Ipv4Addr::new(1, 1, 1, 1).into(),
+                until: 1_700_000_000,
+            }],
+            ..Default::default()
+        };
+        let file = serde_json::to_string(&peers).unwrap();
+
+        let loaded = serde_json::from_str::<PeersFile>(&file).unwrap();
+        assert_eq!(loaded, PeersFile::Versioned(peers));
+    }
+
+    #[test]
+    fn unix_timestamp_conversion() {
+        let until = Instant::now() + Duration::from_secs(60);
+        let timestamp = instant_to_unix_timestamp(until);
+        let restored = unix_timestamp_to_instant(timestamp).unwrap();
+        assert!(restored <= until + Duration::from_secs(1));
+        assert!(restored + Duration::from_secs(2) >= until);
+
+        assert!(unix_timestamp_to_instant(to_unix_timestamp(SystemTime::now()) - 10).is_none());
+    }
+}
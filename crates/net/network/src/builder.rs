@@ -7,7 +7,7 @@ use tokio::sync::mpsc;
 use crate::{
     eth_requests::EthRequestHandler,
     transactions::{TransactionsManager, TransactionsManagerConfig},
-    NetworkHandle, NetworkManager,
+    NetworkHandle, NetworkManager, PeerScoringPolicy,
 };
 
 /// We set the max channel capacity of the `EthRequestHandler` to 256
@@ -53,6 +53,12 @@ impl<Tx, Eth> NetworkBuilder<Tx, Eth> {
         (handle, network, transactions, request_handler)
     }
 
+    /// Sets the [`PeerScoringPolicy`] that decides which peers occupy the connection slots.
+    pub fn peer_scoring_policy<P: PeerScoringPolicy>(mut self, policy: P) -> Self {
+        self.network.set_peer_scoring_policy(policy);
+        self
+    }
+
     /// Creates a new [`TransactionsManager`] and wires it to the network.
     pub fn transactions<Pool: TransactionPool>(
         self,
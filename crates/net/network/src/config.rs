@@ -1,12 +1,12 @@
 //! Network config support
 
-use std::{collections::HashSet, net::SocketAddr, sync::Arc};
+use std::{collections::HashSet, net::SocketAddr, path::PathBuf, sync::Arc};
 
 use reth_chainspec::{ChainSpecProvider, EthChainSpec, Hardforks};
 use reth_discv4::{Discv4Config, Discv4ConfigBuilder, NatResolver, DEFAULT_DISCOVERY_ADDRESS};
 use reth_discv5::NetworkStackId;
 use reth_dns_discovery::DnsDiscoveryConfig;
-use reth_eth_wire::{HelloMessage, HelloMessageWithProtocols, Status};
+use reth_eth_wire::{BlockRangeUpdate, HelloMessage, HelloMessageWithProtocols, Status};
 use reth_network_peers::{mainnet_nodes, pk2id, sepolia_nodes, PeerId, TrustedPeer};
 use reth_network_types::{PeersConfig, SessionsConfig};
 use reth_primitives::{ForkFilter, Head};
@@ -52,6 +52,9 @@ pub struct NetworkConfig<C> {
     pub discovery_v5_config: Option<reth_discv5::Config>,
     /// Address to listen for incoming connections
     pub listener_addr: SocketAddr,
+    /// Additional address of the other IP version to listen for incoming connections, if the node
+    /// runs dual-stack.
+    pub dual_stack_listener_addr: Option<SocketAddr>,
     /// How to instantiate peer manager.
     pub peers_config: PeersConfig,
     /// How to configure the [`SessionManager`](crate::session::SessionManager).
@@ -73,6 +76,8 @@ pub struct NetworkConfig<C> {
     pub executor: Box<dyn TaskSpawner>,
     /// The `Status` message to send to peers at the beginning.
     pub status: Status,
+    /// The range of blocks this node can serve, announced to `eth/69` peers.
+    pub block_range: BlockRangeUpdate,
     /// Sets the hello message for the p2p handshake in `RLPx`
     pub hello_message: HelloMessageWithProtocols,
     /// Additional protocols to announce and handle in `RLPx`
@@ -83,6 +88,8 @@ pub struct NetworkConfig<C> {
     pub transactions_manager_config: TransactionsManagerConfig,
     /// The NAT resolver for external IP
     pub nat: Option<NatResolver>,
+    /// If set, the messages of all sessions are recorded to this file.
+    pub session_recording: Option<PathBuf>,
 }
 
 // === impl NetworkConfig ===
@@ -179,6 +186,8 @@ pub struct NetworkConfigBuilder {
     discovery_addr: Option<SocketAddr>,
     /// Listener for incoming connections
     listener_addr: Option<SocketAddr>,
+    /// Additional listener of the other IP version for incoming connections
+    dual_stack_listener_addr: Option<SocketAddr>,
     /// How to instantiate peer manager.
     peers_config: Option<PeersConfig>,
     /// How to configure the sessions manager
@@ -193,6 +202,8 @@ pub struct NetworkConfigBuilder {
     extra_protocols: RlpxSubProtocols,
     /// Head used to start set for the fork filter and status.
     head: Option<Head>,
+    /// The earliest block this node can serve.
+    earliest_block: u64,
     /// Whether tx gossip is disabled
     tx_gossip_disabled: bool,
     /// The block importer type
@@ -201,6 +212,8 @@ pub struct NetworkConfigBuilder {
     transactions_manager_config: TransactionsManagerConfig,
     /// The NAT resolver for external IP
     nat: Option<NatResolver>,
+    /// The file to record the messages of all sessions to.
+    session_recording: Option<PathBuf>,
 }
 
 // === impl NetworkConfigBuilder ===
@@ -222,6 +235,7 @@ impl NetworkConfigBuilder {
             boot_nodes: Default::default(),
             discovery_addr: None,
             listener_addr: None,
+            dual_stack_listener_addr: None,
             peers_config: None,
             sessions_config: None,
             network_mode: Default::default(),
@@ -229,10 +243,12 @@ impl NetworkConfigBuilder {
             hello_message: None,
             extra_protocols: Default::default(),
             head: None,
+            earliest_block: 0,
             tx_gossip_disabled: false,
             block_import: None,
             transactions_manager_config: Default::default(),
             nat: None,
+            session_recording: None,
         }
     }
 
@@ -270,6 +286,17 @@ impl NetworkConfigBuilder {
         self
     }
 
+    /// Sets the earliest block this node can serve.
+    ///
+    /// This is announced to `eth/69` peers as the start of the served block range, so that pruned
+    /// nodes don't receive requests for history they no longer have.
+    ///
+    /// Defaults to the genesis block.
+    pub const fn earliest_block(mut self, earliest_block: u64) -> Self {
+        self.earliest_block = earliest_block;
+        self
+    }
+
     /// Sets the `HelloMessage` to send when connecting to peers.
     ///
     /// ```
@@ -330,6 +357,15 @@ impl NetworkConfigBuilder {
         self
     }
 
+    /// Sets an additional socket address of the other IP version the network will listen on, so
+    /// that `RLPx` is reachable over both IPv4 and IPv6.
+    ///
+    /// If discovery v5 is enabled, this socket is advertised in the local ENR as well.
+    pub const fn dual_stack_listener_addr(mut self, dual_stack_listener_addr: SocketAddr) -> Self {
+        self.dual_stack_listener_addr = Some(dual_stack_listener_addr);
+        self
+    }
+
     /// Sets the port of the address the network will listen on.
     ///
     /// By default, this is [`DEFAULT_DISCOVERY_PORT`](reth_discv4::DEFAULT_DISCOVERY_PORT)
@@ -503,6 +539,14 @@ impl NetworkConfigBuilder {
         self
     }
 
+    /// Records the `eth` messages of all sessions to the given file.
+    ///
+    /// See also [`SessionRecorder`](crate::SessionRecorder).
+    pub fn record_sessions(mut self, path: impl Into<PathBuf>) -> Self {
+        self.session_recording = Some(path.into());
+        self
+    }
+
     /// Consumes the type and creates the actual [`NetworkConfig`]
     /// for the given client type that can interact with the chain.
     ///
@@ -523,6 +567,7 @@ impl NetworkConfigBuilder {
             boot_nodes,
             discovery_addr,
             listener_addr,
+            dual_stack_listener_addr,
             peers_config,
             sessions_config,
             network_mode,
@@ -530,23 +575,32 @@ impl NetworkConfigBuilder {
             hello_message,
             extra_protocols,
             head,
+            earliest_block,
             tx_gossip_disabled,
             block_import,
             transactions_manager_config,
             nat,
+            session_recording,
         } = self;
 
+        let listener_addr = listener_addr.unwrap_or(DEFAULT_DISCOVERY_ADDRESS);
+        // only listen on a second socket if it's of the other IP version
+        let dual_stack_listener_addr =
+            dual_stack_listener_addr.filter(|addr| addr.is_ipv4() != listener_addr.is_ipv4());
+
         discovery_v5_builder = discovery_v5_builder.map(|mut builder| {
             if let Some(network_stack_id) = NetworkStackId::id(&chain_spec) {
                 let fork_id = chain_spec.latest_fork_id();
                 builder = builder.fork(network_stack_id, fork_id)
             }
 
+            if let Some(addr) = dual_stack_listener_addr {
+                builder = builder.tcp_socket_dual_stack(addr)
+            }
+
             builder
         });
 
-        let listener_addr = listener_addr.unwrap_or(DEFAULT_DISCOVERY_ADDRESS);
-
         let mut hello_message =
             hello_message.unwrap_or_else(|| HelloMessage::builder(peer_id).build());
         hello_message.port = listener_addr.port();
@@ -561,6 +615,11 @@ impl NetworkConfigBuilder {
 
         // set the status
         let status = Status::spec_builder(&chain_spec, &head).build();
+        let block_range = BlockRangeUpdate {
+            earliest: earliest_block.min(head.number),
+            latest: head.number,
+            latest_hash: head.hash,
+        };
 
         // set a fork filter based on the chain spec and head
         let fork_filter = chain_spec.fork_filter(head);
@@ -588,6 +647,7 @@ impl NetworkConfigBuilder {
             discovery_v5_config: discovery_v5_builder.map(|builder| builder.build()),
             discovery_v4_addr: discovery_addr.unwrap_or(DEFAULT_DISCOVERY_ADDRESS),
             listener_addr,
+            dual_stack_listener_addr,
             peers_config: peers_config.unwrap_or_default(),
             sessions_config: sessions_config.unwrap_or_default(),
             chain_id,
@@ -595,12 +655,14 @@ impl NetworkConfigBuilder {
             network_mode,
             executor: executor.unwrap_or_else(|| Box::<TokioTaskExecutor>::default()),
             status,
+            block_range,
             hello_message,
             extra_protocols,
             fork_filter,
             tx_gossip_disabled,
             transactions_manager_config,
             nat,
+            session_recording,
         }
     }
 }
@@ -210,12 +210,19 @@ impl Discovery {
         Ok(())
     }
 
-    /// Processes an incoming [`NodeRecord`] update from a discovery service
-    fn on_node_record_update(&mut self, record: NodeRecord, fork_id: Option<ForkId>) {
+    /// Processes an incoming [`NodeRecord`] update from a discovery service.
+    ///
+    /// The `fallback_tcp` address is the peer's `RLPx` address of the other IP version, if known.
+    fn on_node_record_update(
+        &mut self,
+        record: NodeRecord,
+        fork_id: Option<ForkId>,
+        fallback_tcp: Option<SocketAddr>,
+    ) {
         let peer_id = record.id;
         let tcp_addr = record.tcp_addr();
         let udp_addr = record.udp_addr();
-        let addr = PeerAddr::new(tcp_addr, Some(udp_addr));
+        let addr = PeerAddr::new(tcp_addr, Some(udp_addr)).with_fallback_tcp(fallback_tcp);
         _ =
             self.discovered_nodes.get_or_insert(peer_id, || {
                 self.queued_events.push_back(DiscoveryEvent::NewNode(
@@ -229,7 +236,7 @@ impl Discovery {
     fn on_discv4_update(&mut self, update: DiscoveryUpdate) {
         match update {
             DiscoveryUpdate::Added(record) | DiscoveryUpdate::DiscoveredAtCapacity(record) => {
-                self.on_node_record_update(record, None);
+                self.on_node_record_update(record, None, None);
             }
             DiscoveryUpdate::EnrForkId(node, fork_id) => {
                 self.queued_events.push_back(DiscoveryEvent::EnrForkId(node.id, fork_id))
@@ -265,10 +272,10 @@ impl Discovery {
                 self.discv5_updates.as_mut().map(|updates| updates.poll_next_unpin(cx))
             {
                 if let Some(discv5) = self.discv5.as_mut() {
-                    if let Some(DiscoveredPeer { node_record, fork_id }) =
+                    if let Some(DiscoveredPeer { node_record, fork_id, fallback_tcp }) =
                         discv5.on_discv5_update(update)
                     {
-                        self.on_node_record_update(node_record, fork_id);
+                        self.on_node_record_update(node_record, fork_id, fallback_tcp);
                     }
                 }
             }
@@ -284,7 +291,7 @@ impl Discovery {
                         "failed adding node discovered by dns to discv5"
                     );
                 }
-                self.on_node_record_update(update.node_record, update.fork_id);
+                self.on_node_record_update(update.node_record, update.fork_id, None);
             }
 
             if self.queued_events.is_empty() {
@@ -0,0 +1,89 @@
+//! Publishing of the peers known to the network as [EIP-1459](https://eips.ethereum.org/EIPS/eip-1459)
+//! node list.
+//!
+//! Only the signed [`Enr`] of a peer can be published, which are taken from the discv5 routing
+//! table. Hence, publishing requires discv5 to be enabled.
+
+use enr::Enr;
+use reth_discv5::NetworkStackId;
+use reth_dns_discovery::{tree::LinkEntry, DnsTree};
+use reth_network_api::test_utils::PeersHandleProvider;
+use reth_network_types::{Peer, DEFAULT_REPUTATION};
+use reth_primitives::{EnrForkIdEntry, ForkFilter, ForkId};
+use secp256k1::SecretKey;
+
+use crate::NetworkHandle;
+
+/// Selects which of the peers known to the `PeersManager` are published in a node list.
+#[derive(Debug, Clone)]
+pub struct DnsNodeListFilter {
+    /// If set, only peers with a [`ForkId`] that is compatible with this filter are published.
+    /// Peers without a known [`ForkId`] are skipped.
+    pub fork_filter: Option<ForkFilter>,
+    /// Minimum reputation of a peer to be published.
+    pub min_reputation: i32,
+    /// Maximum number of peers to publish, peers with higher reputation are preferred.
+    pub max_nodes: Option<usize>,
+}
+
+impl Default for DnsNodeListFilter {
+    fn default() -> Self {
+        Self { fork_filter: None, min_reputation: DEFAULT_REPUTATION, max_nodes: None }
+    }
+}
+
+impl DnsNodeListFilter {
+    /// Returns `true` if the peer, with the given [`ForkId`], should be published.
+    fn is_allowed(&self, peer: &Peer, fork_id: Option<ForkId>) -> bool {
+        if peer.is_banned() || peer.reputation < self.min_reputation {
+            return false
+        }
+        match &self.fork_filter {
+            Some(fork_filter) => fork_id.is_some_and(|id| fork_filter.validate(id).is_ok()),
+            None => true,
+        }
+    }
+}
+
+impl NetworkHandle {
+    /// Returns the [`Enr`]s of the peers known to the network that pass the given filter, highest
+    /// reputation first.
+    ///
+    /// Returns an empty list if discv5 is disabled.
+    pub async fn dns_node_list(&self, filter: &DnsNodeListFilter) -> Vec<Enr<SecretKey>> {
+        let Some(discv5) = self.discv5() else { return Vec::new() };
+
+        let mut peers = self.peers_handle().all_peers_with_info().await;
+        peers.sort_by_key(|(_, peer)| std::cmp::Reverse(peer.reputation));
+
+        peers
+            .into_iter()
+            .filter_map(|(peer_id, peer)| {
+                let enr = discv5.find_enr(peer_id)?;
+                // fall back to the fork ID advertised in the ENR
+                let fork_id = peer.fork_id.or_else(|| {
+                    enr.get_decodable::<EnrForkIdEntry>(NetworkStackId::ETH)
+                        .and_then(Result::ok)
+                        .map(Into::into)
+                });
+                filter.is_allowed(&peer, fork_id).then_some(enr)
+            })
+            .take(filter.max_nodes.unwrap_or(usize::MAX))
+            .collect()
+    }
+
+    /// Builds an EIP-1459 node list of the peers known to the network that pass the given filter,
+    /// signed with the given key.
+    ///
+    /// See also [`NetworkHandle::dns_node_list`].
+    pub async fn build_dns_tree(
+        &self,
+        filter: &DnsNodeListFilter,
+        links: Vec<LinkEntry>,
+        sequence_number: u64,
+        key: &SecretKey,
+    ) -> DnsTree {
+        let enrs = self.dns_node_list(filter).await;
+        DnsTree::new(enrs, links, sequence_number, key)
+    }
+}
@@ -1,8 +1,11 @@
 //! A client implementation that can interact with the network and download data.
 
-use std::sync::{
-    atomic::{AtomicUsize, Ordering},
-    Arc,
+use std::{
+    ops::RangeInclusive,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
 };
 
 use alloy_primitives::B256;
@@ -83,11 +86,22 @@ impl BodiesClient for FetchClient {
         &self,
         request: Vec<B256>,
         priority: Priority,
+    ) -> Self::Output {
+        self.get_block_bodies_with_priority_and_range_hint(request, priority, None)
+    }
+
+    /// Sends a `GetBlockBodies` request to an available peer that announced to serve the given
+    /// range of blocks.
+    fn get_block_bodies_with_priority_and_range_hint(
+        &self,
+        request: Vec<B256>,
+        priority: Priority,
+        range_hint: Option<RangeInclusive<u64>>,
     ) -> Self::Output {
         let (response, rx) = oneshot::channel();
         if self
             .request_tx
-            .send(DownloadRequest::GetBlockBodies { request, response, priority })
+            .send(DownloadRequest::GetBlockBodies { request, response, priority, range_hint })
             .is_ok()
         {
             Box::pin(FlattenedResponse::from(rx))
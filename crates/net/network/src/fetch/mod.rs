@@ -6,6 +6,7 @@ pub use client::FetchClient;
 
 use std::{
     collections::{HashMap, VecDeque},
+    ops::RangeInclusive,
     sync::{
         atomic::{AtomicU64, AtomicUsize, Ordering},
         Arc,
@@ -13,13 +14,14 @@ use std::{
     task::{Context, Poll},
 };
 
+use alloy_eips::BlockHashOrNumber;
 use alloy_primitives::B256;
 use futures::StreamExt;
-use reth_eth_wire::{GetBlockBodies, GetBlockHeaders};
+use reth_eth_wire::{BlockRangeUpdate, GetBlockBodies, GetBlockHeaders};
 use reth_network_api::test_utils::PeersHandle;
 use reth_network_p2p::{
     error::{EthResponseValidator, PeerRequestResult, RequestError, RequestResult},
-    headers::client::HeadersRequest,
+    headers::client::{HeadersDirection, HeadersRequest},
     priority::Priority,
 };
 use reth_network_peers::PeerId;
@@ -89,12 +91,24 @@ impl StateFetcher {
                 state: PeerState::Idle,
                 best_hash,
                 best_number,
+                earliest: 0,
                 timeout,
                 last_response_likely_bad: false,
             },
         );
     }
 
+    /// Updates the range of blocks the peer announced to serve.
+    pub(crate) fn update_peer_block_range(&mut self, peer_id: &PeerId, range: BlockRangeUpdate) {
+        if let Some(peer) = self.peers.get_mut(peer_id) {
+            peer.earliest = range.earliest;
+            if range.latest >= peer.best_number {
+                peer.best_hash = range.latest_hash;
+                peer.best_number = range.latest;
+            }
+        }
+    }
+
     /// Removes the peer from the peer list, after which it is no longer available for future
     /// requests.
     ///
@@ -135,8 +149,14 @@ impl StateFetcher {
     /// Returns the _next_ idle peer that's ready to accept a request,
     /// prioritizing those with the lowest timeout/latency and those that recently responded with
     /// adequate data.
-    fn next_best_peer(&self) -> Option<PeerId> {
-        let mut idle = self.peers.iter().filter(|(_, peer)| peer.state.is_idle());
+    ///
+    /// If a block range is given, only peers that can serve the range are considered, unless none
+    /// of the connected peers announced to serve it.
+    fn next_best_peer(&self, range: Option<&RangeInclusive<u64>>) -> Option<PeerId> {
+        let range = range.filter(|range| self.peers.values().any(|peer| peer.can_serve(range)));
+        let mut idle = self.peers.iter().filter(|(_, peer)| {
+            peer.state.is_idle() && range.map_or(true, |range| peer.can_serve(range))
+        });
 
         let mut best_peer = idle.next()?;
 
@@ -165,9 +185,20 @@ impl StateFetcher {
             return PollAction::NoRequests
         }
 
-        let Some(peer_id) = self.next_best_peer() else { return PollAction::NoPeersAvailable };
+        if !self.peers.values().any(|peer| peer.state.is_idle()) {
+            return PollAction::NoPeersAvailable
+        }
 
-        let request = self.queued_requests.pop_front().expect("not empty");
+        // requests for history only some peers can serve must not block the remaining requests
+        let Some((idx, peer_id)) =
+            self.queued_requests.iter().enumerate().find_map(|(idx, request)| {
+                self.next_best_peer(request.block_range().as_ref()).map(|peer_id| (idx, peer_id))
+            })
+        else {
+            return PollAction::NoPeersAvailable
+        };
+
+        let request = self.queued_requests.remove(idx).expect("exists");
         let request = self.prepare_block_request(peer_id, request);
 
         PollAction::Ready(FetchAction::BlockRequest { peer_id, request })
@@ -247,7 +278,13 @@ impl StateFetcher {
     ///
     /// Caution: this expects that the peer is _not_ closed.
     fn followup_request(&mut self, peer_id: PeerId) -> Option<BlockResponseOutcome> {
-        let req = self.queued_requests.pop_front()?;
+        let peer = self.peers.get(&peer_id)?;
+        let idx = self.queued_requests.iter().position(|req| {
+            req.block_range().map_or(true, |range| {
+                peer.can_serve(&range) || !self.peers.values().any(|peer| peer.can_serve(&range))
+            })
+        })?;
+        let req = self.queued_requests.remove(idx)?;
         let req = self.prepare_block_request(peer_id, req);
         Some(BlockResponseOutcome::Request(peer_id, req))
     }
@@ -340,6 +377,10 @@ struct Peer {
     best_hash: B256,
     /// Tracks the best number of the peer.
     best_number: u64,
+    /// The earliest block the peer announced to serve.
+    ///
+    /// This is only announced by `eth/69` peers, other peers are assumed to serve all blocks.
+    earliest: u64,
     /// Tracks the current timeout value we use for the peer.
     timeout: Arc<AtomicU64>,
     /// Tracks whether the peer has recently responded with a likely bad response.
@@ -355,6 +396,14 @@ impl Peer {
     fn timeout(&self) -> u64 {
         self.timeout.load(Ordering::Relaxed)
     }
+
+    /// Returns `true` if the peer announced to serve the given range of blocks.
+    ///
+    /// Only the start of the range is checked since the best block of the peer is not always
+    /// known.
+    const fn can_serve(&self, range: &RangeInclusive<u64>) -> bool {
+        self.earliest <= *range.start()
+    }
 }
 
 /// Tracks the state of an individual peer
@@ -417,6 +466,8 @@ pub(crate) enum DownloadRequest {
         request: Vec<B256>,
         response: oneshot::Sender<PeerRequestResult<Vec<BlockBody>>>,
         priority: Priority,
+        /// The range of block numbers the requested bodies belong to, if known.
+        range_hint: Option<RangeInclusive<u64>>,
     },
 }
 
@@ -444,6 +495,21 @@ impl DownloadRequest {
     const fn is_normal_priority(&self) -> bool {
         self.get_priority().is_normal()
     }
+
+    /// Returns the range of blocks that is requested, if known.
+    fn block_range(&self) -> Option<RangeInclusive<u64>> {
+        match self {
+            Self::GetBlockHeaders { request, .. } => {
+                let BlockHashOrNumber::Number(start) = request.start else { return None };
+                let len = request.limit.saturating_sub(1);
+                match request.direction {
+                    HeadersDirection::Rising => Some(start..=start.saturating_add(len)),
+                    HeadersDirection::Falling => Some(start.saturating_sub(len)..=start),
+                }
+            }
+            Self::GetBlockBodies { range_hint, .. } => range_hint.clone(),
+        }
+    }
 }
 
 /// An action the syncer can emit.
@@ -488,6 +554,7 @@ mod tests {
                 request: vec![],
                 response: tx,
                 priority: Priority::default(),
+                range_hint: None,
             });
             assert!(fetcher.poll(cx).is_pending());
 
@@ -506,17 +573,17 @@ mod tests {
         fetcher.new_active_peer(peer1, B256::random(), 1, Arc::new(AtomicU64::new(1)));
         fetcher.new_active_peer(peer2, B256::random(), 2, Arc::new(AtomicU64::new(1)));
 
-        let first_peer = fetcher.next_best_peer().unwrap();
+        let first_peer = fetcher.next_best_peer(None).unwrap();
         assert!(first_peer == peer1 || first_peer == peer2);
         // Pending disconnect for first_peer
         fetcher.on_pending_disconnect(&first_peer);
         // first_peer now isn't idle, so we should get other peer
-        let second_peer = fetcher.next_best_peer().unwrap();
+        let second_peer = fetcher.next_best_peer(None).unwrap();
         assert!(first_peer == peer1 || first_peer == peer2);
         assert_ne!(first_peer, second_peer);
         // without idle peers, returns None
         fetcher.on_pending_disconnect(&second_peer);
-        assert_eq!(fetcher.next_best_peer(), None);
+        assert_eq!(fetcher.next_best_peer(None), None);
     }
 
     #[tokio::test]
@@ -535,13 +602,63 @@ mod tests {
         fetcher.new_active_peer(peer3, B256::random(), 3, Arc::new(AtomicU64::new(50)));
 
         // Must always get peer1 (lowest timeout)
-        assert_eq!(fetcher.next_best_peer(), Some(peer1));
-        assert_eq!(fetcher.next_best_peer(), Some(peer1));
+        assert_eq!(fetcher.next_best_peer(None), Some(peer1));
+        assert_eq!(fetcher.next_best_peer(None), Some(peer1));
         // peer2's timeout changes below peer1's
         peer2_timeout.store(10, Ordering::Relaxed);
         // Then we get peer 2 always (now lowest)
-        assert_eq!(fetcher.next_best_peer(), Some(peer2));
-        assert_eq!(fetcher.next_best_peer(), Some(peer2));
+        assert_eq!(fetcher.next_best_peer(None), Some(peer2));
+        assert_eq!(fetcher.next_best_peer(None), Some(peer2));
+    }
+
+    #[tokio::test]
+    async fn test_block_range_routing() {
+        let manager = PeersManager::new(PeersConfig::default());
+        let mut fetcher = StateFetcher::new(manager.handle(), Default::default());
+        let full_peer = B512::random();
+        let pruned_peer = B512::random();
+
+        fetcher.new_active_peer(full_peer, B256::random(), 100, Arc::new(AtomicU64::new(50)));
+        fetcher.new_active_peer(pruned_peer, B256::random(), 100, Arc::new(AtomicU64::new(10)));
+        fetcher.update_peer_block_range(
+            &pruned_peer,
+            BlockRangeUpdate { earliest: 50, latest: 100, latest_hash: B256::random() },
+        );
+
+        // the pruned peer is faster and can serve recent blocks
+        assert_eq!(fetcher.next_best_peer(None), Some(pruned_peer));
+        assert_eq!(fetcher.next_best_peer(Some(&(60..=70))), Some(pruned_peer));
+        // but older history is only routed to the full peer
+        assert_eq!(fetcher.next_best_peer(Some(&(10..=20))), Some(full_peer));
+
+        // a request for history is not blocked by a queued request no idle peer can serve
+        fetcher.on_pending_disconnect(&full_peer);
+        let (tx, _rx) = oneshot::channel();
+        fetcher.queued_requests.push_back(DownloadRequest::GetBlockBodies {
+            request: vec![],
+            response: tx,
+            priority: Priority::default(),
+            range_hint: Some(10..=20),
+        });
+        let (tx, _rx) = oneshot::channel();
+        fetcher.queued_requests.push_back(DownloadRequest::GetBlockHeaders {
+            request: HeadersRequest {
+                start: 70u64.into(),
+                limit: 10,
+                direction: HeadersDirection::Falling,
+            },
+            response: tx,
+            priority: Priority::default(),
+        });
+        match fetcher.poll_action() {
+            PollAction::Ready(FetchAction::BlockRequest { peer_id, request }) => {
+                assert_eq!(peer_id, pruned_peer);
+                assert!(matches!(request, BlockRequest::GetBlockHeaders(_)));
+            }
+            _ => unreachable!(),
+        }
+        assert_eq!(fetcher.queued_requests.len(), 1);
+        assert!(matches!(fetcher.poll_action(), PollAction::NoPeersAvailable));
     }
 
     #[tokio::test]
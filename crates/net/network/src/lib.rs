@@ -116,12 +116,15 @@ pub mod test_utils;
 
 pub mod cache;
 pub mod config;
+pub mod dns_publish;
 pub mod error;
 pub mod eth_requests;
 pub mod import;
 pub mod message;
 pub mod peers;
 pub mod protocol;
+pub mod replay;
+pub mod scoring;
 pub mod transactions;
 
 mod budget;
@@ -133,6 +136,7 @@ mod listener;
 mod manager;
 mod metrics;
 mod network;
+mod port_mapping;
 mod session;
 mod state;
 mod swarm;
@@ -145,9 +149,10 @@ pub use reth_network_api::{
 pub use reth_network_p2p::sync::{NetworkSyncUpdater, SyncState};
 pub use reth_network_types::{PeersConfig, SessionsConfig};
 pub use session::{
-    ActiveSessionHandle, ActiveSessionMessage, Direction, EthRlpxConnection, PeerInfo,
-    PendingSessionEvent, PendingSessionHandle, PendingSessionHandshakeError, SessionCommand,
-    SessionEvent, SessionId, SessionManager,
+    ActiveSessionHandle, ActiveSessionMessage, Direction, EthRlpxConnection, MessageDirection,
+    PeerInfo, PendingSessionEvent, PendingSessionHandle, PendingSessionHandshakeError,
+    RecordedMessage, SessionCommand, SessionEvent, SessionId, SessionManager, SessionRecordError,
+    SessionRecordReader, SessionRecorder,
 };
 
 pub use builder::NetworkBuilder;
@@ -158,5 +163,6 @@ pub use flattened_response::FlattenedResponse;
 pub use manager::NetworkManager;
 pub use metrics::TxTypesCounter;
 pub use network::{NetworkHandle, NetworkProtocols};
+pub use scoring::PeerScoringPolicy;
 pub use swarm::NetworkConnectionState;
 pub use transactions::{FilterAnnouncement, MessageFilter, ValidateTx68};
@@ -7,9 +7,13 @@ use std::{
     task::{Context, Poll},
 };
 
-use futures::{ready, Stream};
+use futures::{ready, Stream, StreamExt};
+use socket2::{Domain, Protocol, Socket, Type};
 use tokio::net::{TcpListener, TcpStream};
 
+/// Maximum number of pending connections of a listener, as used by [`TcpListener::bind`].
+const LISTEN_BACKLOG: i32 = 1024;
+
 /// A tcp connection listener.
 ///
 /// Listens for incoming connections.
@@ -22,6 +26,8 @@ pub struct ConnectionListener {
     /// The active tcp listener for incoming connections.
     #[pin]
     incoming: TcpListenerStream,
+    /// Local address and tcp listener of the other IP version, if listening dual-stack.
+    dual_stack: Option<(SocketAddr, TcpListenerStream)>,
 }
 
 impl ConnectionListener {
@@ -32,15 +38,42 @@ impl ConnectionListener {
         Ok(Self::new(listener, local_addr))
     }
 
+    /// Creates a new [`TcpListener`] that listens for incoming connections on the given address,
+    /// and, if set, an additional one listening on the address of the other IP version.
+    ///
+    /// IPv6 listeners only accept IPv6 connections, so that the IPv4 listener can bind the same
+    /// port.
+    pub async fn bind_dual_stack(
+        addr: SocketAddr,
+        dual_stack_addr: Option<SocketAddr>,
+    ) -> io::Result<Self> {
+        let listener = bind_single_stack(addr)?;
+        let local_addr = listener.local_addr()?;
+        let mut listener = Self::new(listener, local_addr);
+        if let Some(dual_stack_addr) = dual_stack_addr {
+            let dual_stack = bind_single_stack(dual_stack_addr)?;
+            let local_addr = dual_stack.local_addr()?;
+            listener.dual_stack = Some((local_addr, TcpListenerStream { inner: dual_stack }));
+        }
+        Ok(listener)
+    }
+
     /// Creates a new connection listener stream.
     pub(crate) const fn new(listener: TcpListener, local_address: SocketAddr) -> Self {
-        Self { local_address, incoming: TcpListenerStream { inner: listener } }
+        Self { local_address, incoming: TcpListenerStream { inner: listener }, dual_stack: None }
     }
 
     /// Polls the type to make progress.
     pub fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<ListenerEvent> {
         let this = self.project();
-        match ready!(this.incoming.poll_next(cx)) {
+        let incoming = match this.incoming.poll_next(cx) {
+            Poll::Ready(incoming) => incoming,
+            Poll::Pending => match this.dual_stack.as_mut() {
+                Some((_, dual_stack)) => ready!(dual_stack.poll_next_unpin(cx)),
+                None => return Poll::Pending,
+            },
+        };
+        match incoming {
             Some(Ok((stream, remote_addr))) => {
                 if let Err(err) = stream.set_nodelay(true) {
                     tracing::warn!(target: "net", "set nodelay failed: {:?}", err);
@@ -58,6 +91,27 @@ impl ConnectionListener {
     pub const fn local_address(&self) -> SocketAddr {
         self.local_address
     }
+
+    /// Returns the socket address of the other IP version this listener listens on, if listening
+    /// dual-stack.
+    pub fn local_address_dual_stack(&self) -> Option<SocketAddr> {
+        self.dual_stack.as_ref().map(|(addr, _)| *addr)
+    }
+}
+
+/// Binds a [`TcpListener`] to the given address, which only accepts IPv6 connections if the
+/// address is an IPv6 address.
+fn bind_single_stack(addr: SocketAddr) -> io::Result<TcpListener> {
+    let socket = Socket::new(Domain::for_address(addr), Type::STREAM, Some(Protocol::TCP))?;
+    if addr.is_ipv6() {
+        socket.set_only_v6(true)?;
+    }
+    #[cfg(not(windows))]
+    socket.set_reuse_address(true)?;
+    socket.set_nonblocking(true)?;
+    socket.bind(&addr.into())?;
+    socket.listen(LISTEN_BACKLOG)?;
+    TcpListener::from_std(socket.into())
 }
 
 /// Event type produced by the [`TcpListenerStream`].
@@ -105,7 +159,7 @@ impl Stream for TcpListenerStream {
 mod tests {
     use super::*;
     use std::{
-        net::{Ipv4Addr, SocketAddrV4},
+        net::{Ipv4Addr, Ipv6Addr, SocketAddrV4},
         pin::pin,
     };
     use tokio::macros::support::poll_fn;
@@ -130,4 +184,43 @@ mod tests {
 
         let _ = TcpStream::connect(local_addr).await.unwrap();
     }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_incoming_listener_dual_stack() {
+        // an unused port, bound by both listeners
+        let port = std::net::TcpListener::bind((Ipv4Addr::UNSPECIFIED, 0))
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+
+        let listener = ConnectionListener::bind_dual_stack(
+            (Ipv6Addr::UNSPECIFIED, port).into(),
+            Some((Ipv4Addr::UNSPECIFIED, port).into()),
+        )
+        .await
+        .unwrap();
+        assert_eq!(listener.local_address(), (Ipv6Addr::UNSPECIFIED, port).into());
+        assert_eq!(listener.local_address_dual_stack(), Some((Ipv4Addr::UNSPECIFIED, port).into()));
+
+        let accepted = tokio::task::spawn(async move {
+            let mut listener = pin!(listener);
+            let mut remote_addrs = Vec::new();
+            while remote_addrs.len() < 2 {
+                match poll_fn(|cx| listener.as_mut().poll(cx)).await {
+                    ListenerEvent::Incoming { remote_addr, .. } => remote_addrs.push(remote_addr),
+                    _ => {
+                        panic!("unexpected event")
+                    }
+                }
+            }
+            remote_addrs
+        });
+
+        let _v6 = TcpStream::connect((Ipv6Addr::LOCALHOST, port)).await.unwrap();
+        let _v4 = TcpStream::connect((Ipv4Addr::LOCALHOST, port)).await.unwrap();
+        let remote_addrs = accepted.await.unwrap();
+        assert!(remote_addrs.iter().any(|addr| addr.is_ipv6()));
+        assert!(remote_addrs.iter().any(|addr| addr.is_ipv4()));
+    }
 }
@@ -32,6 +32,7 @@ use parking_lot::Mutex;
 use reth_eth_wire::{capability::CapabilityMessage, Capabilities, DisconnectReason};
 use reth_fs_util::{self as fs, FsPathError};
 use reth_metrics::common::mpsc::UnboundedMeteredSender;
+use reth_net_nat::PortMapperHandle;
 use reth_network_api::{
     test_utils::PeersHandle, EthProtocolInfo, NetworkEvent, NetworkStatus, PeerInfo, PeerRequest,
 };
@@ -58,12 +59,13 @@ use crate::{
     network::{NetworkHandle, NetworkHandleMessage},
     peers::PeersManager,
     poll_nested_stream_with_budget,
+    port_mapping::spawn_port_mapper,
     protocol::IntoRlpxSubProtocol,
-    session::SessionManager,
+    session::{SessionManager, SessionRecorder},
     state::NetworkState,
     swarm::{Swarm, SwarmEvent},
     transactions::NetworkTransactionEvent,
-    FetchClient, NetworkBuilder,
+    FetchClient, NetworkBuilder, PeerScoringPolicy,
 };
 
 #[cfg_attr(doc, aquamarine::aquamarine)]
@@ -113,6 +115,12 @@ pub struct NetworkManager {
     metrics: NetworkMetrics,
     /// Disconnect metrics for the Network
     disconnect_metrics: DisconnectMetrics,
+    /// Handle to the port mapper of the gateway, if the [`NatResolver`] maps ports.
+    ///
+    /// The mappings are removed when the manager is dropped.
+    ///
+    /// [`NatResolver`]: reth_discv4::NatResolver
+    _port_mapper: Option<PortMapperHandle>,
 }
 
 // === impl NetworkManager ===
@@ -130,6 +138,11 @@ impl NetworkManager {
         self.to_eth_request_handler = Some(tx);
     }
 
+    /// Sets the [`PeerScoringPolicy`] that ranks peers for the connection slots.
+    pub fn set_peer_scoring_policy<P: PeerScoringPolicy>(&mut self, policy: P) {
+        self.swarm.state_mut().peers_mut().set_scoring_policy(policy);
+    }
+
     /// Adds an additional protocol handler to the `RLPx` sub-protocol list.
     pub fn add_rlpx_sub_protocol(&mut self, protocol: impl IntoRlpxSubProtocol) {
         self.swarm.add_rlpx_sub_protocol(protocol)
@@ -174,6 +187,7 @@ impl NetworkManager {
             mut discovery_v4_config,
             mut discovery_v5_config,
             listener_addr,
+            dual_stack_listener_addr,
             peers_config,
             sessions_config,
             chain_id,
@@ -183,20 +197,24 @@ impl NetworkManager {
             executor,
             hello_message,
             status,
+            block_range,
             fork_filter,
             dns_discovery_config,
             extra_protocols,
             tx_gossip_disabled,
             transactions_manager_config: _,
             nat,
+            session_recording,
         } = config;
 
         let peers_manager = PeersManager::new(peers_config);
         let peers_handle = peers_manager.handle();
 
-        let incoming = ConnectionListener::bind(listener_addr).await.map_err(|err| {
-            NetworkError::from_io_error(err, ServiceKind::Listener(listener_addr))
-        })?;
+        let incoming = ConnectionListener::bind_dual_stack(listener_addr, dual_stack_listener_addr)
+            .await
+            .map_err(|err| {
+                NetworkError::from_io_error(err, ServiceKind::Listener(listener_addr))
+            })?;
 
         // retrieve the tcp address of the socket
         let listener_addr = incoming.local_address();
@@ -232,15 +250,27 @@ impl NetworkManager {
 
         let num_active_peers = Arc::new(AtomicUsize::new(0));
 
-        let sessions = SessionManager::new(
+        let port_mapper = spawn_port_mapper(
+            nat,
+            &*executor,
+            listener_addr.port(),
+            discv4.clone(),
+            discv5.clone(),
+        );
+
+        let mut sessions = SessionManager::new(
             secret_key,
             sessions_config,
             executor,
             status,
+            block_range,
             hello_message,
             fork_filter,
             extra_protocols,
         );
+        if let Some(path) = session_recording {
+            sessions.set_recorder(SessionRecorder::new(path)?);
+        }
 
         let state = NetworkState::new(
             crate::state::BlockNumReader::new(client),
@@ -282,6 +312,7 @@ impl NetworkManager {
             num_active_peers,
             metrics: Default::default(),
             disconnect_metrics: Default::default(),
+            _port_mapper: port_mapper,
         })
     }
 
@@ -357,10 +388,10 @@ impl NetworkManager {
         self.swarm.state().peers().handle()
     }
 
-    /// Collect the peers from the [`NetworkManager`] and write them to the given
-    /// `persistent_peers_file`.
+    /// Collect the peers, including their reputation, ban and backoff state, from the
+    /// [`NetworkManager`] and write them to the given `persistent_peers_file`.
     pub fn write_peers_to_file(&self, persistent_peers_file: &Path) -> Result<(), FsPathError> {
-        let known_peers = self.all_peers().collect::<Vec<_>>();
+        let known_peers = self.swarm.state().peers().persisted_peers();
         persistent_peers_file.parent().map(fs::create_dir_all).transpose()?;
         reth_fs_util::write_json_file(persistent_peers_file, &known_peers)?;
         Ok(())
@@ -532,6 +563,9 @@ impl NetworkManager {
                     msg,
                 });
             }
+            PeerMessage::BlockRangeUpdated(range) => {
+                self.swarm.state_mut().on_block_range_update(peer_id, range);
+            }
             PeerMessage::EthRequest(req) => {
                 self.on_eth_request(peer_id, req);
             }
@@ -622,6 +656,9 @@ impl NetworkManager {
                     self.swarm.state_mut().update_fork_id(transition.current);
                 }
             }
+            NetworkHandleMessage::EarliestBlockUpdate(block) => {
+                self.swarm.sessions_mut().on_earliest_block_update(block);
+            }
             NetworkHandleMessage::GetPeerInfos(tx) => {
                 let _ = tx.send(self.get_peer_infos());
             }
@@ -708,6 +745,14 @@ impl NetworkManager {
 
                 self.update_active_connection_metrics();
 
+                let peer_kind = self
+                    .swarm
+                    .state()
+                    .peers()
+                    .peer_by_id(peer_id)
+                    .map(|(_, kind)| kind)
+                    .unwrap_or_default();
+
                 self.event_sender.notify(NetworkEvent::SessionEstablished {
                     peer_id,
                     remote_addr,
@@ -716,6 +761,7 @@ impl NetworkManager {
                     version,
                     status,
                     messages,
+                    peer_kind,
                 });
             }
             SwarmEvent::PeerAdded(peer_id) => {
@@ -11,9 +11,10 @@ use std::{
 use alloy_primitives::{Bytes, B256};
 use futures::FutureExt;
 use reth_eth_wire::{
-    capability::RawCapabilityMessage, message::RequestPair, BlockBodies, BlockHeaders, EthMessage,
-    GetBlockBodies, GetBlockHeaders, NewBlock, NewBlockHashes, NewPooledTransactionHashes,
-    NodeData, PooledTransactions, Receipts, SharedTransactions, Transactions,
+    capability::RawCapabilityMessage, message::RequestPair, BlockBodies, BlockHeaders,
+    BlockRangeUpdate, EthMessage, GetBlockBodies, GetBlockHeaders, NewBlock, NewBlockHashes,
+    NewPooledTransactionHashes, NodeData, PooledTransactions, Receipts, SharedTransactions,
+    Transactions,
 };
 use reth_network_api::PeerRequest;
 use reth_network_p2p::error::{RequestError, RequestResult};
@@ -52,6 +53,8 @@ pub enum PeerMessage {
     SendTransactions(SharedTransactions),
     /// Send new pooled transactions
     PooledTransactions(NewPooledTransactionHashes),
+    /// Announces the range of blocks the node can serve, only sent to and by `eth/69` peers.
+    BlockRangeUpdated(BlockRangeUpdate),
     /// All `eth` request variants.
     EthRequest(PeerRequest),
     /// Other than eth namespace message
@@ -77,6 +77,48 @@ pub struct NetworkMetrics {
     pub(crate) acc_duration_poll_swarm: Gauge,
 }
 
+/// Metrics of a typed `RLPx` sub-protocol, labeled with the protocol's capability.
+///
+/// See [`TypedProtocolHandler`](crate::protocol::TypedProtocolHandler).
+#[derive(Clone, Metrics)]
+#[metrics(scope = "network.subprotocol")]
+pub struct SubProtocolMetrics {
+    /// Number of currently active connections
+    pub(crate) active_connections: Gauge,
+
+    /// Total number of messages received from peers
+    pub(crate) messages_received: Counter,
+
+    /// Total number of messages sent to peers
+    pub(crate) messages_sent: Counter,
+
+    /// Total number of requests sent to peers
+    pub(crate) requests_sent: Counter,
+
+    /// Total number of requests that timed out
+    pub(crate) request_timeouts: Counter,
+
+    /// Total number of responses that did not match a pending request
+    pub(crate) unsolicited_responses: Counter,
+
+    /// Total number of messages dropped because the peer exceeded the rate limit
+    pub(crate) rate_limited_messages: Counter,
+
+    /// Total number of invalid/malformed messages received from peers
+    pub(crate) invalid_messages_received: Counter,
+
+    /// Duration in seconds between sending a request and receiving its response
+    pub(crate) request_latency: Histogram,
+}
+
+/// Metrics for the [`SessionRecorder`](crate::session::SessionRecorder)
+#[derive(Clone, Metrics)]
+#[metrics(scope = "network.session_recorder")]
+pub struct SessionRecorderMetrics {
+    /// Total number of records dropped because the writer could not keep up
+    pub(crate) dropped_records: Counter,
+}
+
 /// Metrics for `SessionManager`
 #[derive(Metrics)]
 #[metrics(scope = "network")]
@@ -6,7 +6,7 @@ use std::{
     },
 };
 
-use alloy_primitives::B256;
+use alloy_primitives::{BlockNumber, B256};
 use enr::Enr;
 use parking_lot::Mutex;
 use reth_discv4::{Discv4, NatResolver};
@@ -96,6 +96,11 @@ impl NetworkHandle {
         &self.inner.to_manager_tx
     }
 
+    /// Returns the discv5 service, if enabled.
+    pub(crate) fn discv5(&self) -> Option<&Discv5> {
+        self.inner.discv5.as_ref()
+    }
+
     /// Returns the mode of the network, either pow, or pos
     pub fn mode(&self) -> &NetworkMode {
         &self.inner.network_mode
@@ -397,6 +402,11 @@ impl NetworkSyncUpdater for NetworkHandle {
     fn update_status(&self, head: Head) {
         self.send_message(NetworkHandleMessage::StatusUpdate { head });
     }
+
+    /// Update the earliest block served to peers.
+    fn update_earliest_block(&self, block: BlockNumber) {
+        self.send_message(NetworkHandleMessage::EarliestBlockUpdate(block));
+    }
 }
 
 impl BlockDownloaderProvider for NetworkHandle {
@@ -490,6 +500,8 @@ pub(crate) enum NetworkHandleMessage {
         /// The head status to apply.
         head: Head,
     },
+    /// Updates the earliest block served to peers.
+    EarliestBlockUpdate(BlockNumber),
     /// Retrieves the current status via a oneshot sender.
     GetStatus(oneshot::Sender<NetworkStatus>),
     /// Gets `PeerInfo` for the specified peer IDs.
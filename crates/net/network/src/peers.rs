@@ -5,8 +5,9 @@ use std::{
     fmt::Display,
     io::{self},
     net::{IpAddr, SocketAddr},
+    ops::RangeInclusive,
     task::{Context, Poll},
-    time::Duration,
+    time::{Duration, SystemTime, UNIX_EPOCH},
 };
 
 use futures::StreamExt;
@@ -17,6 +18,10 @@ use reth_network_peers::{NodeRecord, PeerId};
 use reth_network_types::{
     peers::{
         config::PeerBackoffDurations,
+        persist::{
+            instant_to_unix_timestamp, to_unix_timestamp, unix_timestamp_to_instant,
+            PersistedBannedIp, PersistedPeer, PersistedPeers,
+        },
         reputation::{DEFAULT_REPUTATION, MAX_TRUSTED_PEER_REPUTATION_CHANGE},
     },
     ConnectionsConfig, Peer, PeerAddr, PeerConnectionState, PeerKind, PeersConfig,
@@ -33,6 +38,7 @@ use tracing::{trace, warn};
 
 use crate::{
     error::SessionError,
+    scoring::{DefaultPeerScoringPolicy, PeerScoringPolicy},
     session::{Direction, PendingSessionHandshakeError},
     swarm::NetworkConnectionState,
 };
@@ -84,6 +90,8 @@ pub struct PeersManager {
     max_backoff_count: u8,
     /// Tracks the connection state of the node
     net_connection_state: NetworkConnectionState,
+    /// Ranks peers for the connection slots.
+    scoring_policy: Box<dyn PeerScoringPolicy>,
 }
 
 impl PeersManager {
@@ -99,6 +107,7 @@ impl PeersManager {
             trusted_nodes,
             trusted_nodes_only,
             basic_nodes,
+            persisted_peers,
             max_backoff_count,
         } = config;
         let (manager_tx, handle_rx) = mpsc::unbounded_channel();
@@ -130,6 +139,35 @@ impl PeersManager {
             });
         }
 
+        // restore the state of the peers from the previous run
+        let mut ban_list = ban_list;
+        let mut backed_off_peers = HashMap::new();
+        for persisted in persisted_peers.peers {
+            let peer_id = persisted.node.id;
+            let Some(peer) = peers.get_mut(&peer_id) else { continue };
+            peer.reputation = persisted.reputation;
+            peer.severe_backoff_counter = persisted.severe_backoff_counter;
+            peer.last_seen =
+                persisted.last_seen.map(|timestamp| UNIX_EPOCH + Duration::from_secs(timestamp));
+
+            if let Some(until) = persisted.banned_until.and_then(unix_timestamp_to_instant) {
+                ban_list.ban_peer_until(peer_id, until);
+            } else if peer.is_banned() {
+                // the ban expired while the node was offline
+                peer.unban();
+            }
+
+            if let Some(until) = persisted.backed_off_until.and_then(unix_timestamp_to_instant) {
+                peer.backed_off = true;
+                backed_off_peers.insert(peer_id, until);
+            }
+        }
+        for PersistedBannedIp { ip, until } in persisted_peers.banned_ips {
+            if let Some(until) = unix_timestamp_to_instant(until) {
+                ban_list.ban_ip_until(ip, until);
+            }
+        }
+
         Self {
             peers,
             trusted_peer_ids,
@@ -141,13 +179,26 @@ impl PeersManager {
             release_interval: tokio::time::interval_at(now + unban_interval, unban_interval),
             connection_info: ConnectionInfo::new(connection_info),
             ban_list,
-            backed_off_peers: Default::default(),
+            backed_off_peers,
             ban_duration,
             backoff_durations,
             trusted_nodes_only,
             last_tick: Instant::now(),
             max_backoff_count,
             net_connection_state: NetworkConnectionState::default(),
+            scoring_policy: Box::new(DefaultPeerScoringPolicy),
+        }
+    }
+
+    /// Sets the [`PeerScoringPolicy`] that ranks peers for the connection slots.
+    pub fn set_scoring_policy<P: PeerScoringPolicy>(&mut self, policy: P) {
+        self.scoring_policy = Box::new(policy);
+    }
+
+    /// Called when a response to a block request was received from the peer.
+    pub(crate) fn on_response(&mut self, peer_id: PeerId, latency: Duration, useful: bool) {
+        if self.peers.contains_key(&peer_id) {
+            self.scoring_policy.on_response(peer_id, latency, useful);
         }
     }
 
@@ -174,6 +225,43 @@ impl PeersManager {
         })
     }
 
+    /// Returns the state of all peers, that should be persisted across restarts.
+    pub(crate) fn persisted_peers(&self) -> PersistedPeers {
+        let now = SystemTime::now();
+        let peers = self
+            .peers
+            .iter()
+            .map(|(peer_id, peer)| PersistedPeer {
+                node: NodeRecord::new_with_ports(
+                    peer.addr.tcp().ip(),
+                    peer.addr.tcp().port(),
+                    peer.addr.udp().map(|addr| addr.port()),
+                    *peer_id,
+                ),
+                reputation: peer.reputation,
+                banned_until: self
+                    .ban_list
+                    .peer_banned_until(peer_id)
+                    .map(instant_to_unix_timestamp),
+                backed_off_until: self
+                    .backed_off_peers
+                    .get(peer_id)
+                    .copied()
+                    .map(instant_to_unix_timestamp),
+                severe_backoff_counter: peer.severe_backoff_counter,
+                last_seen: if peer.state.is_connected() { Some(now) } else { peer.last_seen }
+                    .map(to_unix_timestamp),
+            })
+            .collect();
+        let banned_ips = self
+            .ban_list
+            .temporarily_banned_ips()
+            .map(|(ip, until)| PersistedBannedIp { ip, until: instant_to_unix_timestamp(until) })
+            .collect();
+
+        PersistedPeers { peers, banned_ips, ..Default::default() }
+    }
+
     /// Returns the `NodeRecord` and `PeerKind` for the given peer id
     pub(crate) fn peer_by_id(&self, peer_id: PeerId) -> Option<(NodeRecord, PeerKind)> {
         self.peers.get(&peer_id).map(|v| {
@@ -311,6 +399,7 @@ impl PeersManager {
                 }
 
                 peer.state = PeerConnectionState::In;
+                peer.last_seen = Some(SystemTime::now());
 
                 is_trusted = is_trusted || peer.is_trusted();
             }
@@ -319,13 +408,24 @@ impl PeersManager {
                 // disconnect, because we only know the outgoing port
                 let mut peer = Peer::with_state(PeerAddr::from_tcp(addr), PeerConnectionState::In);
                 peer.remove_after_disconnect = true;
+                peer.last_seen = Some(SystemTime::now());
                 entry.insert(peer);
                 self.queued_actions.push_back(PeerAction::PeerAdded(peer_id));
             }
         }
 
-        // disconnect the peer if we don't have capacity for more inbound connections
+        // disconnect the peer if we don't have capacity for more inbound connections, unless
+        // the scoring policy prefers it over an already connected inbound peer
         if !is_trusted && !has_in_capacity {
+            let peer_id = match self.select_inbound_eviction(peer_id) {
+                Some(evicted) => {
+                    if let Some(peer) = self.peers.get_mut(&evicted) {
+                        peer.state.disconnect();
+                    }
+                    evicted
+                }
+                None => peer_id,
+            };
             self.queued_actions.push_back(PeerAction::Disconnect {
                 peer_id,
                 reason: Some(DisconnectReason::TooManyPeers),
@@ -333,6 +433,28 @@ impl PeersManager {
         }
     }
 
+    /// Asks the scoring policy for a connected inbound peer to evict in favor of the given
+    /// incoming peer.
+    fn select_inbound_eviction(&self, incoming: PeerId) -> Option<PeerId> {
+        let incoming = (&incoming, self.peers.get(&incoming)?);
+        let inbound = self
+            .peers
+            .iter()
+            .filter(|(peer_id, peer)| {
+                *peer_id != incoming.0 &&
+                    peer.state.is_incoming() &&
+                    !peer.is_trusted() &&
+                    !peer.is_static()
+            })
+            .collect::<Vec<_>>();
+        let evicted = self
+            .scoring_policy
+            .select_eviction(incoming, &inbound)
+            .filter(|evicted| inbound.iter().any(|(peer_id, _)| *peer_id == evicted))?;
+        trace!(target: "net::peers", incoming=?incoming.0, ?evicted, "evicting inbound peer");
+        Some(evicted)
+    }
+
     /// Bans the peer temporarily with the configured ban timeout
     fn ban_peer(&mut self, peer_id: PeerId) {
         let mut ban_duration = self.ban_duration;
@@ -404,6 +526,8 @@ impl PeersManager {
     /// trusted peer, it will also be less strict with the reputation slashing.
     pub(crate) fn apply_reputation_change(&mut self, peer_id: &PeerId, rep: ReputationChangeKind) {
         let outcome = if let Some(peer) = self.peers.get_mut(peer_id) {
+            self.scoring_policy.on_reputation_change(*peer_id, rep);
+
             // First check if we should reset the reputation
             if rep.is_reset() {
                 peer.reset_reputation()
@@ -470,6 +594,8 @@ impl PeersManager {
 
     /// Gracefully disconnected an active session
     pub(crate) fn on_active_session_gracefully_closed(&mut self, peer_id: PeerId) {
+        self.scoring_policy.on_disconnect(peer_id, None);
+
         match self.peers.entry(peer_id) {
             Entry::Occupied(mut entry) => {
                 self.connection_info.decr_state(entry.get().state);
@@ -484,6 +610,7 @@ impl PeersManager {
                     // session to that peer
                     entry.get_mut().severe_backoff_counter = 0;
                     entry.get_mut().state = PeerConnectionState::Idle;
+                    entry.get_mut().last_seen = Some(SystemTime::now());
                     return
                 }
             }
@@ -499,6 +626,7 @@ impl PeersManager {
             self.connection_info.decr_state(peer.state);
             self.connection_info.inc_out();
             peer.state = PeerConnectionState::Out;
+            peer.last_seen = Some(SystemTime::now());
         }
     }
 
@@ -512,6 +640,10 @@ impl PeersManager {
         peer_id: &PeerId,
         err: &EthStreamError,
     ) {
+        if let Some(peer) = self.peers.get_mut(peer_id) {
+            peer.last_seen = Some(SystemTime::now());
+        }
+        self.scoring_policy.on_disconnect(*peer_id, err.as_disconnected());
         self.on_connection_failure(remote_addr, peer_id, err, ReputationChangeKind::Dropped)
     }
 
@@ -526,11 +658,16 @@ impl PeersManager {
         // there's a race condition where we accepted an incoming connection while we were trying to
         // connect to the same peer at the same time. if the outgoing connection failed
         // after the incoming connection was accepted, we can ignore this error
-        if let Some(peer) = self.peers.get(peer_id) {
+        if let Some(peer) = self.peers.get_mut(peer_id) {
             if peer.state.is_incoming() {
                 // we already have an active connection to the peer, so we can ignore this error
                 return
             }
+
+            // if the peer runs dual-stack, try the other IP version on the next dial
+            if peer.addr.tcp() == *remote_addr && peer.addr.swap_fallback_tcp() {
+                trace!(target: "net::peers", ?peer_id, ?remote_addr, fallback=?peer.addr.tcp(), "falling back to other IP version");
+            }
         }
 
         self.on_connection_failure(remote_addr, peer_id, err, ReputationChangeKind::FailedToConnect)
@@ -645,6 +782,18 @@ impl PeersManager {
         }
     }
 
+    /// Sets the range of blocks the given peer announced it can serve.
+    pub(crate) fn on_block_range_update(
+        &mut self,
+        peer_id: &PeerId,
+        block_range: Option<RangeInclusive<u64>>,
+    ) {
+        if let Some(peer) = self.peers.get_mut(peer_id) {
+            trace!(target: "net::peers", ?peer_id, ?block_range, "set block range");
+            peer.block_range = block_range;
+        }
+    }
+
     /// Called for a newly discovered peer.
     ///
     /// If the peer already exists, then the address, kind and `fork_id` will be updated.
@@ -789,7 +938,7 @@ impl PeersManager {
         self.trusted_peer_ids.remove(&peer_id);
     }
 
-    /// Returns the idle peer with the highest reputation.
+    /// Returns the idle peer with the highest score, see [`PeerScoringPolicy`].
     ///
     /// Peers that are `trusted` or `static`, see [`PeerKind`], are prioritized as long as they're
     /// not currently marked as banned or backed off.
@@ -819,8 +968,10 @@ impl PeersManager {
                 return Some((*maybe_better.0, maybe_better.1))
             }
 
-            // otherwise we keep track of the best peer using the reputation
-            if maybe_better.1.reputation > best_peer.1.reputation {
+            // otherwise we keep track of the best peer using the scoring policy
+            if self.scoring_policy.score(maybe_better.0, maybe_better.1) >
+                self.scoring_policy.score(best_peer.0, best_peer.1)
+            {
                 best_peer = maybe_better;
             }
         }
@@ -883,6 +1034,9 @@ impl PeersManager {
         loop {
             // drain buffered actions
             if let Some(action) = self.queued_actions.pop_front() {
+                if let PeerAction::PeerRemoved(peer_id) = &action {
+                    self.scoring_policy.on_peer_removed(*peer_id);
+                }
                 return Poll::Ready(action)
             }
 
@@ -901,6 +1055,11 @@ impl PeersManager {
                     PeerCommand::GetPeers(tx) => {
                         let _ = tx.send(self.iter_peers().collect());
                     }
+                    PeerCommand::GetPeersWithInfo(tx) => {
+                        let _ = tx.send(
+                            self.peers.iter().map(|(id, peer)| (*id, peer.clone())).collect(),
+                        );
+                    }
                 }
             }
 
@@ -1097,8 +1256,9 @@ mod tests {
     use std::{
         future::{poll_fn, Future},
         io,
-        net::{IpAddr, Ipv4Addr, SocketAddr},
+        net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
         pin::Pin,
+        sync::{Arc, Mutex},
         task::{Context, Poll},
         time::Duration,
     };
@@ -1112,7 +1272,8 @@ mod tests {
     use reth_network_api::Direction;
     use reth_network_peers::{PeerId, TrustedPeer};
     use reth_network_types::{
-        peers::reputation::DEFAULT_REPUTATION, BackoffKind, ReputationChangeKind,
+        peers::reputation::{BANNED_REPUTATION, DEFAULT_REPUTATION},
+        BackoffKind, Peer, ReputationChangeKind,
     };
     use url::Host;
 
@@ -1123,6 +1284,7 @@ mod tests {
             ConnectionInfo, InboundConnectionError, PeerAction, PeerAddr, PeerBackoffDurations,
             PeerConnectionState,
         },
+        scoring::{PeerScoringPolicy, ResponseQualityScoringPolicy},
         session::PendingSessionHandshakeError,
         PeersConfig,
     };
@@ -1220,6 +1382,44 @@ mod tests {
         .await;
     }
 
+    #[tokio::test]
+    async fn test_restore_persisted_peers() {
+        let banned = PeerId::random();
+        let backed_off = PeerId::random();
+        let expired = PeerId::random();
+        let socket_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 1, 2)), 8008);
+        let mut peers = PeersManager::new(PeersConfig::default());
+        for peer_id in [banned, backed_off, expired] {
+            peers.add_peer(peer_id, PeerAddr::from_tcp(socket_addr), None);
+        }
+
+        peers.peers.get_mut(&banned).unwrap().reputation = BANNED_REPUTATION - 1;
+        peers.ban_peer(banned);
+        peers.backoff_peer_until(backed_off, std::time::Instant::now() + Duration::from_secs(60));
+        peers.peers.get_mut(&expired).unwrap().reputation = BANNED_REPUTATION - 1;
+
+        let mut persisted = peers.persisted_peers();
+        assert_eq!(persisted.peers.len(), 3);
+        for peer in &mut persisted.peers {
+            // ban expired while the node was offline
+            if peer.node.id == expired {
+                peer.banned_until = Some(0);
+            }
+        }
+
+        let peers = PeersManager::new(PeersConfig::test().with_persisted_peers(persisted));
+        assert_eq!(peers.num_known_peers(), 3);
+
+        assert!(peers.ban_list.is_banned_peer(&banned));
+        assert!(peers.peers[&banned].is_banned());
+
+        assert!(peers.backed_off_peers.contains_key(&backed_off));
+        assert!(peers.peers[&backed_off].is_backed_off());
+
+        assert!(!peers.ban_list.is_banned_peer(&expired));
+        assert_eq!(peers.peers[&expired].reputation, DEFAULT_REPUTATION);
+    }
+
     #[tokio::test]
     async fn test_unban() {
         let peer = PeerId::random();
@@ -1686,6 +1886,85 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn evict_inbound_peer_by_scoring_policy() {
+        let mut peers = PeersManager::new(PeersConfig::test().with_max_inbound(2));
+        let mut policy = ResponseQualityScoringPolicy::default();
+
+        // saturate the inbound slots
+        let mut inbound = Vec::new();
+        for i in 0..peers.connection_info.config.max_inbound {
+            let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 1, i as u8)), 8008);
+            assert!(peers.on_incoming_pending_session(addr.ip()).is_ok());
+            let peer_id = PeerId::random();
+            peers.on_incoming_session_established(peer_id, addr);
+            assert!(matches!(event!(peers), PeerAction::PeerAdded(id) if id == peer_id));
+            inbound.push(peer_id);
+        }
+
+        // the second peer only sends useless responses
+        for _ in 0..policy.min_responses_for_eviction {
+            policy.on_response(inbound[0], Duration::from_millis(50), true);
+            policy.on_response(inbound[1], Duration::from_millis(50), false);
+        }
+        peers.set_scoring_policy(policy);
+
+        let incoming = PeerId::random();
+        let socket_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 1, 99)), 8008);
+        assert!(peers.on_incoming_pending_session(socket_addr.ip()).is_ok());
+        peers.on_incoming_session_established(incoming, socket_addr);
+        assert!(matches!(event!(peers), PeerAction::PeerAdded(id) if id == incoming));
+
+        match event!(peers) {
+            PeerAction::Disconnect { peer_id, reason } => {
+                assert_eq!(peer_id, inbound[1]);
+                assert_eq!(reason, Some(DisconnectReason::TooManyPeers));
+            }
+            _ => unreachable!(),
+        }
+        assert_eq!(peers.peers[&inbound[1]].state, PeerConnectionState::DisconnectingIn);
+        assert_eq!(peers.peers[&incoming].state, PeerConnectionState::In);
+    }
+
+    #[tokio::test]
+    async fn scoring_policy_ignores_unknown_peers() {
+        #[derive(Debug, Clone, Default)]
+        struct RecordingPolicy(Arc<Mutex<Vec<PeerId>>>);
+
+        impl PeerScoringPolicy for RecordingPolicy {
+            fn on_response(&mut self, peer_id: PeerId, _latency: Duration, _useful: bool) {
+                self.0.lock().unwrap().push(peer_id);
+            }
+
+            fn on_reputation_change(&mut self, peer_id: PeerId, _kind: ReputationChangeKind) {
+                self.0.lock().unwrap().push(peer_id);
+            }
+
+            fn score(&self, _peer_id: &PeerId, peer: &Peer) -> i64 {
+                peer.reputation() as i64
+            }
+        }
+
+        let mut peers = PeersManager::default();
+        let policy = RecordingPolicy::default();
+        peers.set_scoring_policy(policy.clone());
+
+        let unknown = PeerId::random();
+        peers.apply_reputation_change(&unknown, ReputationChangeKind::BadAnnouncement);
+        peers.on_response(unknown, Duration::from_millis(50), true);
+        assert!(policy.0.lock().unwrap().is_empty());
+
+        let known = PeerId::random();
+        peers.add_peer(
+            known,
+            PeerAddr::from_tcp(SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 1, 2)), 8008)),
+            None,
+        );
+        peers.apply_reputation_change(&known, ReputationChangeKind::BadAnnouncement);
+        peers.on_response(known, Duration::from_millis(50), true);
+        assert_eq!(*policy.0.lock().unwrap(), vec![known, known]);
+    }
+
     #[tokio::test]
     async fn accept_incoming_trusted_unknown_peer_address() {
         let socket_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 1, 99)), 8008);
@@ -1995,6 +2274,44 @@ mod tests {
         assert_eq!(peers.num_outbound_connections(), 0);
     }
 
+    #[tokio::test]
+    async fn test_outgoing_connection_error_dual_stack_fallback() {
+        let peer = PeerId::random();
+        let socket_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 1, 2)), 8008);
+        let fallback_addr = SocketAddr::new(IpAddr::V6(Ipv6Addr::LOCALHOST), 8008);
+        let mut peers = PeersManager::default();
+        peers.add_peer(
+            peer,
+            PeerAddr::from_tcp(socket_addr).with_fallback_tcp(Some(fallback_addr)),
+            None,
+        );
+
+        match event!(peers) {
+            PeerAction::PeerAdded(peer_id) => {
+                assert_eq!(peer_id, peer);
+            }
+            _ => unreachable!(),
+        }
+        match event!(peers) {
+            PeerAction::Connect { peer_id, remote_addr } => {
+                assert_eq!(peer_id, peer);
+                assert_eq!(remote_addr, socket_addr);
+            }
+            _ => unreachable!(),
+        }
+
+        peers.on_outgoing_connection_failure(
+            &socket_addr,
+            &peer,
+            &io::Error::new(io::ErrorKind::NetworkUnreachable, ""),
+        );
+
+        // next dial goes over the other IP version
+        let p = peers.peers.get(&peer).unwrap();
+        assert_eq!(p.addr.tcp(), fallback_addr);
+        assert_eq!(p.addr.fallback_tcp(), Some(socket_addr));
+    }
+
     #[tokio::test]
     async fn test_outgoing_connection_gracefully_closed() {
         let peer = PeerId::random();
@@ -0,0 +1,208 @@
+//! Port mapping of the `RLPx` and discovery ports on the gateway.
+
+use std::net::{IpAddr, SocketAddr};
+
+use futures::FutureExt;
+use reth_discv4::{Discv4, NatResolver};
+use reth_discv5::Discv5;
+use reth_net_nat::{
+    MappedAddrs, PortMapper, PortMapperHandle, PortMappingConfig, PortMappingRequest, Protocol,
+};
+use reth_tasks::TaskSpawner;
+use tracing::debug;
+
+/// Spawns a [`PortMapper`] for the `RLPx` port and the discovery ports, if the [`NatResolver`]
+/// supports port mapping.
+///
+/// Whenever the mapped addresses change, the advertised addresses of discv4 and discv5 are
+/// updated. If the mappings are lost, the addresses advertised before the mappings are restored.
+/// The mappings are removed when the returned handle is dropped.
+pub(crate) fn spawn_port_mapper(
+    nat: Option<NatResolver>,
+    executor: &dyn TaskSpawner,
+    rlpx_port: u16,
+    discv4: Option<Discv4>,
+    discv5: Option<Discv5>,
+) -> Option<PortMapperHandle> {
+    let discv4 = discv4.map(|discv4| {
+        let record = discv4.node_record();
+        let local = AdvertisedAddrs {
+            ip: Some(record.address),
+            tcp_port: record.tcp_port,
+            udp_port: record.udp_port,
+        };
+        DiscoveryAddrs { udp_port: discv4.local_addr().port(), local, service: discv4 }
+    });
+    let discv5 = discv5.and_then(|discv5| {
+        let (local, udp_port) = discv5.with_discv5(|d| {
+            let enr = d.local_enr();
+            let local = AdvertisedAddrs {
+                ip: enr.ip4().map(IpAddr::V4),
+                tcp_port: enr.tcp4().unwrap_or(rlpx_port),
+                udp_port: enr.udp4()?,
+            };
+            Some((local, local.udp_port))
+        })?;
+        Some(DiscoveryAddrs { udp_port, local, service: discv5 })
+    });
+
+    let ports = std::iter::once(PortMappingRequest::tcp(rlpx_port))
+        .chain(discv4.as_ref().map(|discv4| PortMappingRequest::udp(discv4.udp_port)))
+        .chain(discv5.as_ref().map(|discv5| PortMappingRequest::udp(discv5.udp_port)));
+    let (mapper, handle) = PortMapper::new(nat?, PortMappingConfig::new(ports))?;
+
+    executor.spawn(mapper.run().boxed());
+
+    let mut mapped = handle.subscribe();
+    executor.spawn(
+        async move {
+            // completes when the port mapper terminated
+            while mapped.changed().await.is_ok() {
+                let addrs = mapped.borrow_and_update().clone();
+                if let Some(discv4) = &discv4 {
+                    discv4.update(addrs.as_ref(), rlpx_port);
+                }
+                if let Some(discv5) = &discv5 {
+                    discv5.update(addrs.as_ref(), rlpx_port);
+                }
+            }
+        }
+        .boxed(),
+    );
+
+    Some(handle)
+}
+
+/// Addresses of the node advertised via discovery.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct AdvertisedAddrs {
+    /// The advertised IP, if any.
+    ip: Option<IpAddr>,
+    /// The advertised `RLPx` port.
+    tcp_port: u16,
+    /// The advertised discovery port.
+    udp_port: u16,
+}
+
+impl AdvertisedAddrs {
+    /// Returns the addresses to advertise for the given mappings of the local `RLPx` and
+    /// discovery ports.
+    ///
+    /// If the ports are not mapped, these local addresses are advertised.
+    fn mapped(self, addrs: Option<&MappedAddrs>, rlpx_port: u16, udp_port: u16) -> Self {
+        let Some(addrs) = addrs else { return self };
+        Self {
+            ip: Some(addrs.external_ip),
+            tcp_port: addrs.external_port(Protocol::Tcp, rlpx_port).unwrap_or(self.tcp_port),
+            udp_port: addrs.external_port(Protocol::Udp, udp_port).unwrap_or(self.udp_port),
+        }
+    }
+}
+
+/// A discovery service and the addresses it advertised before the ports were mapped.
+#[derive(Debug)]
+struct DiscoveryAddrs<S> {
+    /// The local discovery port.
+    udp_port: u16,
+    /// The addresses advertised before the ports were mapped.
+    local: AdvertisedAddrs,
+    /// The discovery service.
+    service: S,
+}
+
+impl DiscoveryAddrs<Discv4> {
+    /// Advertises the mapped addresses via discv4, or the local addresses if unmapped.
+    fn update(&self, addrs: Option<&MappedAddrs>, rlpx_port: u16) {
+        let advertised = self.local.mapped(addrs, rlpx_port, self.udp_port);
+        debug!(target: "net", ?advertised, "Updating discv4 advertised addresses");
+
+        if let Some(ip) = advertised.ip {
+            self.service.set_external_ip_addr(ip);
+        }
+        self.service.set_tcp_port(advertised.tcp_port);
+        self.service.set_udp_port(advertised.udp_port);
+    }
+}
+
+impl DiscoveryAddrs<Discv5> {
+    /// Advertises the mapped addresses via discv5, or the local addresses if unmapped.
+    ///
+    /// If the local ENR did not contain an IP, only the ports are restored when the mappings are
+    /// lost, discv5 replaces the IP once it learned its external address from its peers.
+    fn update(&self, addrs: Option<&MappedAddrs>, rlpx_port: u16) {
+        let advertised = self.local.mapped(addrs, rlpx_port, self.udp_port);
+        debug!(target: "net", ?advertised, "Updating discv5 advertised addresses");
+
+        self.service.with_discv5(|d| match advertised.ip {
+            Some(ip) => {
+                d.update_local_enr_socket(SocketAddr::new(ip, advertised.tcp_port), true);
+                d.update_local_enr_socket(SocketAddr::new(ip, advertised.udp_port), false);
+            }
+            None => {
+                for (key, port) in [("tcp", advertised.tcp_port), ("udp", advertised.udp_port)] {
+                    if let Err(err) = d.enr_insert(key, &port) {
+                        debug!(target: "net", %key, %port, ?err, "Failed to update local ENR");
+                    }
+                }
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_net_nat::MappedPort;
+    use std::net::Ipv4Addr;
+
+    const LOCAL: AdvertisedAddrs = AdvertisedAddrs {
+        ip: Some(IpAddr::V4(Ipv4Addr::LOCALHOST)),
+        tcp_port: 30303,
+        udp_port: 30303,
+    };
+
+    fn mapped(tcp_port: u16, udp_port: u16) -> MappedAddrs {
+        MappedAddrs {
+            external_ip: IpAddr::V4(Ipv4Addr::new(203, 0, 113, 1)),
+            ports: vec![
+                MappedPort {
+                    protocol: Protocol::Tcp,
+                    internal_port: 30303,
+                    external_port: tcp_port,
+                },
+                MappedPort {
+                    protocol: Protocol::Udp,
+                    internal_port: 30303,
+                    external_port: udp_port,
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn advertise_assigned_external_ports() {
+        let addrs = mapped(40000, 40001);
+        assert_eq!(
+            LOCAL.mapped(Some(&addrs), 30303, 30303),
+            AdvertisedAddrs { ip: Some(addrs.external_ip), tcp_port: 40000, udp_port: 40001 }
+        );
+    }
+
+    #[test]
+    fn advertise_local_addrs_if_unmapped() {
+        let addrs = mapped(40000, 40001);
+        assert_ne!(LOCAL.mapped(Some(&addrs), 30303, 30303), LOCAL);
+
+        // mappings lost, advertise the local addresses again
+        assert_eq!(LOCAL.mapped(None, 30303, 30303), LOCAL);
+    }
+
+    #[test]
+    fn keep_local_port_if_port_unmapped() {
+        let addrs = mapped(40000, 40001);
+        assert_eq!(
+            LOCAL.mapped(Some(&addrs), 30303, 30304),
+            AdvertisedAddrs { ip: Some(addrs.external_ip), tcp_port: 40000, udp_port: 30303 }
+        );
+    }
+}
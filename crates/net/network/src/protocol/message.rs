@@ -0,0 +1,321 @@
+//! Typed messages of `RLPx` sub-protocols.
+//!
+//! Messages of a sub-protocol are declared with the [`rlpx_sub_protocol_messages`] macro, which
+//! allocates the message IDs and derives the codec from the RLP encoding of the message types.
+//!
+//! [`rlpx_sub_protocol_messages`]: crate::rlpx_sub_protocol_messages
+
+use std::fmt;
+
+use alloy_primitives::bytes::{BufMut, BytesMut};
+
+/// The kind of a [`SubProtocolMessage`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageKind {
+    /// A message that does not expect a response.
+    Notification,
+    /// A request with the given request id.
+    Request(u64),
+    /// A response to the request with the given request id.
+    Response(u64),
+}
+
+/// A message of a typed `RLPx` sub-protocol.
+///
+/// Implemented by the message enum declared with the
+/// [`rlpx_sub_protocol_messages`](crate::rlpx_sub_protocol_messages) macro.
+pub trait SubProtocolMessage: fmt::Debug + Send + Sync + Sized + 'static {
+    /// Number of message IDs reserved by the protocol.
+    const MESSAGE_COUNT: u8;
+
+    /// Returns the ID of the message, relative to the protocol's offset.
+    fn message_id(&self) -> u8;
+
+    /// Returns whether the message is a notification, request or response.
+    fn kind(&self) -> MessageKind;
+
+    /// RLP encodes the payload of the message, without the message ID.
+    fn encode_payload(&self, out: &mut dyn BufMut);
+
+    /// Decodes the payload of the message with the given ID.
+    fn decode_payload(message_id: u8, buf: &mut &[u8]) -> alloy_rlp::Result<Self>;
+
+    /// Encodes the message, prefixed with its message ID.
+    fn encoded(&self) -> BytesMut {
+        let mut buf = BytesMut::new();
+        buf.put_u8(self.message_id());
+        self.encode_payload(&mut buf);
+        buf
+    }
+
+    /// Decodes a message, prefixed with its message ID.
+    fn decode_message(buf: &[u8]) -> alloy_rlp::Result<Self> {
+        let (&message_id, mut payload) =
+            buf.split_first().ok_or(alloy_rlp::Error::InputTooShort)?;
+        let message = Self::decode_payload(message_id, &mut payload)?;
+        if !payload.is_empty() {
+            return Err(alloy_rlp::Error::UnexpectedLength)
+        }
+        Ok(message)
+    }
+}
+
+/// A request of the sub-protocol with messages `M`.
+pub trait SubProtocolRequest<M: SubProtocolMessage>: Send + 'static {
+    /// The expected response.
+    type Response: Send + 'static;
+
+    /// Wraps the request into a message with the given request id.
+    fn into_message(self, request_id: u64) -> M;
+
+    /// Extracts the response from the given message, or returns the message if it is not the
+    /// expected response.
+    fn try_into_response(message: M) -> Result<Self::Response, M>;
+}
+
+/// A response of the sub-protocol with messages `M`.
+pub trait SubProtocolResponse<M: SubProtocolMessage>: Send + 'static {
+    /// Wraps the response into a message with the given request id.
+    fn into_message(self, request_id: u64) -> M;
+}
+
+/// Declares the messages of a typed `RLPx` sub-protocol.
+///
+/// The macro generates an enum with one variant per message and implements
+/// [`SubProtocolMessage`] for it. Message IDs are allocated in declaration order: first the
+/// notifications, then every request followed by its response. All message types must implement
+/// [`Encodable`](alloy_rlp::Encodable) and [`Decodable`](alloy_rlp::Decodable), e.g. via
+/// `#[derive(RlpEncodable, RlpDecodable)]`.
+///
+/// Requests and responses are sent with a request id, which is used to correlate the response
+/// with the request. [`SubProtocolRequest`] is implemented for the request types and
+/// [`SubProtocolResponse`] for the response types.
+///
+/// Changing the order of the messages changes their IDs, which breaks compatibility with peers
+/// that use the previous order. New messages should only be appended.
+///
+/// ```
+/// use alloy_rlp::{RlpDecodable, RlpEncodable};
+///
+/// #[derive(Debug, Clone, PartialEq, Eq, RlpEncodable, RlpDecodable)]
+/// pub struct Announce {
+///     pub number: u64,
+/// }
+///
+/// #[derive(Debug, Clone, PartialEq, Eq, RlpEncodable, RlpDecodable)]
+/// pub struct GetValue {
+///     pub key: u64,
+/// }
+///
+/// #[derive(Debug, Clone, PartialEq, Eq, RlpEncodable, RlpDecodable)]
+/// pub struct Value {
+///     pub value: Vec<u8>,
+/// }
+///
+/// reth_network::rlpx_sub_protocol_messages! {
+///     /// Messages of the example protocol.
+///     #[derive(Debug, Clone, PartialEq, Eq)]
+///     pub enum ExampleMessage {
+///         notifications {
+///             /// Announces a new number.
+///             Announce(Announce),
+///         }
+///         requests {
+///             /// Requests the value of a key.
+///             GetValue(GetValue) => Value(Value),
+///         }
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! rlpx_sub_protocol_messages {
+    (
+        $(#[$meta:meta])*
+        $vis:vis enum $name:ident {
+            notifications {
+                $(
+                    $(#[$notification_meta:meta])*
+                    $notification:ident($notification_ty:ty)
+                ),* $(,)?
+            }
+            requests {
+                $(
+                    $(#[$request_meta:meta])*
+                    $request:ident($request_ty:ty) => $response:ident($response_ty:ty)
+                ),* $(,)?
+            }
+        }
+    ) => {
+        $(#[$meta])*
+        $vis enum $name {
+            $(
+                $(#[$notification_meta])*
+                $notification($notification_ty),
+            )*
+            $(
+                $(#[$request_meta])*
+                $request($crate::protocol::__private::RequestPair<$request_ty>),
+                #[doc = concat!("Response to [`", stringify!($request), "`](", stringify!($name), "::", stringify!($request), ").")]
+                $response($crate::protocol::__private::RequestPair<$response_ty>),
+            )*
+        }
+
+        const _: () = {
+            #[allow(unused_imports)]
+            use $crate::protocol::__private::{alloy_rlp, BufMut, RequestPair};
+
+            #[allow(non_camel_case_types, clippy::enum_variant_names)]
+            #[repr(u8)]
+            enum MessageId {
+                $($notification,)*
+                $($request, $response,)*
+                __MessageCount,
+            }
+
+            impl $crate::protocol::SubProtocolMessage for $name {
+                const MESSAGE_COUNT: u8 = MessageId::__MessageCount as u8;
+
+                fn message_id(&self) -> u8 {
+                    match self {
+                        $(Self::$notification(_) => MessageId::$notification as u8,)*
+                        $(
+                            Self::$request(_) => MessageId::$request as u8,
+                            Self::$response(_) => MessageId::$response as u8,
+                        )*
+                    }
+                }
+
+                fn kind(&self) -> $crate::protocol::MessageKind {
+                    match self {
+                        $(Self::$notification(_) => $crate::protocol::MessageKind::Notification,)*
+                        $(
+                            Self::$request(pair) => {
+                                $crate::protocol::MessageKind::Request(pair.request_id)
+                            }
+                            Self::$response(pair) => {
+                                $crate::protocol::MessageKind::Response(pair.request_id)
+                            }
+                        )*
+                    }
+                }
+
+                fn encode_payload(&self, out: &mut dyn BufMut) {
+                    match self {
+                        $(Self::$notification(message) => alloy_rlp::Encodable::encode(message, out),)*
+                        $(
+                            Self::$request(pair) => alloy_rlp::Encodable::encode(pair, out),
+                            Self::$response(pair) => alloy_rlp::Encodable::encode(pair, out),
+                        )*
+                    }
+                }
+
+                fn decode_payload(message_id: u8, buf: &mut &[u8]) -> alloy_rlp::Result<Self> {
+                    $(
+                        if message_id == MessageId::$notification as u8 {
+                            return alloy_rlp::Decodable::decode(buf).map(Self::$notification)
+                        }
+                    )*
+                    $(
+                        if message_id == MessageId::$request as u8 {
+                            return alloy_rlp::Decodable::decode(buf).map(Self::$request)
+                        }
+                        if message_id == MessageId::$response as u8 {
+                            return alloy_rlp::Decodable::decode(buf).map(Self::$response)
+                        }
+                    )*
+                    Err(alloy_rlp::Error::Custom("unknown message id"))
+                }
+            }
+
+            $(
+                impl From<$notification_ty> for $name {
+                    fn from(message: $notification_ty) -> Self {
+                        Self::$notification(message)
+                    }
+                }
+            )*
+
+            $(
+                impl $crate::protocol::SubProtocolRequest<$name> for $request_ty {
+                    type Response = $response_ty;
+
+                    fn into_message(self, request_id: u64) -> $name {
+                        $name::$request(RequestPair { request_id, message: self })
+                    }
+
+                    fn try_into_response(message: $name) -> Result<Self::Response, $name> {
+                        match message {
+                            $name::$response(pair) => Ok(pair.message),
+                            message => Err(message),
+                        }
+                    }
+                }
+
+                impl $crate::protocol::SubProtocolResponse<$name> for $response_ty {
+                    fn into_message(self, request_id: u64) -> $name {
+                        $name::$response(RequestPair { request_id, message: self })
+                    }
+                }
+            )*
+        };
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_rlp::{RlpDecodable, RlpEncodable};
+
+    #[derive(Debug, Clone, PartialEq, Eq, RlpEncodable, RlpDecodable)]
+    struct Ping {
+        nonce: u64,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq, RlpEncodable, RlpDecodable)]
+    struct GetData {
+        keys: Vec<u64>,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq, RlpEncodable, RlpDecodable)]
+    struct Data {
+        values: Vec<u64>,
+    }
+
+    crate::rlpx_sub_protocol_messages! {
+        #[derive(Debug, Clone, PartialEq, Eq)]
+        enum TestMessage {
+            notifications {
+                Ping(Ping),
+            }
+            requests {
+                GetData(GetData) => Data(Data),
+            }
+        }
+    }
+
+    #[test]
+    fn message_ids_and_roundtrip() {
+        assert_eq!(TestMessage::MESSAGE_COUNT, 3);
+
+        let ping = TestMessage::from(Ping { nonce: 7 });
+        assert_eq!(ping.message_id(), 0);
+        assert_eq!(ping.kind(), MessageKind::Notification);
+
+        let request = GetData { keys: vec![1, 2] }.into_message(42);
+        assert_eq!(request.message_id(), 1);
+        assert_eq!(request.kind(), MessageKind::Request(42));
+
+        let response = Data { values: vec![3] }.into_message(42);
+        assert_eq!(response.message_id(), 2);
+        assert_eq!(response.kind(), MessageKind::Response(42));
+        assert_eq!(
+            <GetData as SubProtocolRequest<TestMessage>>::try_into_response(response.clone()),
+            Ok(Data { values: vec![3] })
+        );
+
+        for message in [ping, request, response] {
+            assert_eq!(TestMessage::decode_message(&message.encoded()).unwrap(), message);
+        }
+        assert!(TestMessage::decode_message(&[3, 0xc0]).is_err());
+    }
+}
@@ -1,6 +1,31 @@
 //! Support for handling additional RLPx-based application-level protocols.
 //!
+//! [`ProtocolHandler`] and [`ConnectionHandler`] give raw access to the messages of a protocol.
+//! Protocols with typed messages, declared via [`rlpx_sub_protocol_messages`], can use the
+//! [`TypedProtocolHandler`] instead, which takes care of the message codec, request/response
+//! correlation, timeouts, rate limiting and metrics.
+//!
 //! See also <https://github.com/ethereum/devp2p/blob/master/README.md>
+//!
+//! [`rlpx_sub_protocol_messages`]: crate::rlpx_sub_protocol_messages
+
+mod message;
+mod typed;
+
+pub use message::{MessageKind, SubProtocolMessage, SubProtocolRequest, SubProtocolResponse};
+pub use typed::{
+    RateLimit, Responder, SubProtocolError, TypedConnection, TypedConnectionHandler,
+    TypedPeerHandle, TypedProtocolConfig, TypedProtocolEvent, TypedProtocolHandler,
+    DEFAULT_SUB_PROTOCOL_RATE_LIMIT, DEFAULT_SUB_PROTOCOL_REQUEST_TIMEOUT,
+};
+
+/// Re-exports used by [`rlpx_sub_protocol_messages`](crate::rlpx_sub_protocol_messages).
+#[doc(hidden)]
+pub mod __private {
+    pub use alloy_primitives::bytes::BufMut;
+    pub use alloy_rlp;
+    pub use reth_eth_wire::message::RequestPair;
+}
 
 use std::{
     fmt,
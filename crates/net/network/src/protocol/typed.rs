@@ -0,0 +1,724 @@
+//! A [`ProtocolHandler`] for typed `RLPx` sub-protocols.
+//!
+//! The [`TypedProtocolHandler`] takes care of the connection state of a sub-protocol whose
+//! messages implement [`SubProtocolMessage`]: it decodes incoming messages, correlates responses
+//! with their requests, times out requests, rate limits peers and records metrics. Everything
+//! else is reported as [`TypedProtocolEvent`], and messages are sent via the
+//! [`TypedPeerHandle`] of a connection.
+//!
+//! All channels are bounded: a connection stops reading from its peer while the event channel is
+//! full, and messages can't be queued for a peer once its command channel is full.
+
+use std::{
+    collections::HashMap,
+    fmt,
+    net::SocketAddr,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    task::{ready, Context, Poll},
+    time::{Duration, Instant},
+};
+
+use alloy_primitives::bytes::BytesMut;
+use futures::{task::AtomicWaker, Stream, StreamExt};
+use reth_eth_wire::{
+    capability::SharedCapabilities, multiplex::ProtocolConnection, protocol::Protocol, Capability,
+};
+use reth_network_api::{Direction, PeerId};
+use tokio::sync::{
+    mpsc::{self, error::TrySendError},
+    oneshot,
+};
+use tokio_stream::wrappers::ReceiverStream;
+use tokio_util::sync::PollSender;
+use tracing::trace;
+
+use crate::{
+    metrics::SubProtocolMetrics,
+    protocol::{
+        ConnectionHandler, MessageKind, OnNotSupported, ProtocolHandler, SubProtocolMessage,
+        SubProtocolRequest, SubProtocolResponse,
+    },
+};
+
+/// Default timeout of a request, see [`TypedProtocolConfig::request_timeout`].
+pub const DEFAULT_SUB_PROTOCOL_REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Default per-peer limit of incoming messages, see [`TypedProtocolConfig::rate_limit`].
+pub const DEFAULT_SUB_PROTOCOL_RATE_LIMIT: RateLimit = RateLimit::new(100, Duration::from_secs(1));
+
+/// Default size of the event channel, see [`TypedProtocolConfig::event_buffer`].
+const DEFAULT_EVENT_BUFFER: usize = 1024;
+
+/// Default size of the command channel of a connection, see
+/// [`TypedProtocolConfig::command_buffer`].
+const DEFAULT_COMMAND_BUFFER: usize = 128;
+
+/// Maximum interval between checks for timed out requests.
+const TIMEOUT_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Configuration of a [`TypedProtocolHandler`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypedProtocolConfig {
+    /// How long to wait for the response to a request.
+    pub request_timeout: Duration,
+    /// Limit for the notifications and requests received from a single peer, unlimited if `None`.
+    ///
+    /// Messages above the limit are dropped.
+    pub rate_limit: Option<RateLimit>,
+    /// Capacity of the channel of [`TypedProtocolEvent`]s shared by all connections.
+    ///
+    /// Connections stop reading from their peers while the channel is full. Every connection
+    /// reserves one slot for its [`TypedProtocolEvent::Closed`] event, so this must be larger
+    /// than the number of connections.
+    pub event_buffer: usize,
+    /// Capacity of the channel of messages queued for a single peer.
+    pub command_buffer: usize,
+    /// What to do if the peer does not support the protocol.
+    pub on_unsupported_by_peer: OnNotSupported,
+}
+
+impl Default for TypedProtocolConfig {
+    fn default() -> Self {
+        Self {
+            request_timeout: DEFAULT_SUB_PROTOCOL_REQUEST_TIMEOUT,
+            rate_limit: Some(DEFAULT_SUB_PROTOCOL_RATE_LIMIT),
+            event_buffer: DEFAULT_EVENT_BUFFER,
+            command_buffer: DEFAULT_COMMAND_BUFFER,
+            on_unsupported_by_peer: OnNotSupported::KeepAlive,
+        }
+    }
+}
+
+impl TypedProtocolConfig {
+    /// Sets the timeout of requests.
+    pub const fn with_request_timeout(mut self, request_timeout: Duration) -> Self {
+        self.request_timeout = request_timeout;
+        self
+    }
+
+    /// Sets the per-peer rate limit of incoming messages.
+    pub const fn with_rate_limit(mut self, rate_limit: RateLimit) -> Self {
+        self.rate_limit = Some(rate_limit);
+        self
+    }
+
+    /// Removes the per-peer rate limit of incoming messages.
+    pub const fn without_rate_limit(mut self) -> Self {
+        self.rate_limit = None;
+        self
+    }
+
+    /// Sets the capacity of the event channel.
+    pub const fn with_event_buffer(mut self, event_buffer: usize) -> Self {
+        self.event_buffer = event_buffer;
+        self
+    }
+
+    /// Sets the capacity of the per-peer command channel.
+    pub const fn with_command_buffer(mut self, command_buffer: usize) -> Self {
+        self.command_buffer = command_buffer;
+        self
+    }
+
+    /// Sets what to do if the peer does not support the protocol.
+    pub const fn with_on_unsupported_by_peer(mut self, on_unsupported: OnNotSupported) -> Self {
+        self.on_unsupported_by_peer = on_unsupported;
+        self
+    }
+}
+
+/// Allows at most `max_messages` messages per `interval` from a single peer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimit {
+    /// Maximum number of messages per interval.
+    pub max_messages: u32,
+    /// Length of the interval.
+    pub interval: Duration,
+}
+
+impl RateLimit {
+    /// Creates a new rate limit.
+    pub const fn new(max_messages: u32, interval: Duration) -> Self {
+        Self { max_messages, interval }
+    }
+}
+
+/// Enforces a [`RateLimit`] over fixed windows.
+#[derive(Debug)]
+struct RateLimiter {
+    limit: RateLimit,
+    remaining: u32,
+    window_end: Instant,
+}
+
+impl RateLimiter {
+    fn new(limit: RateLimit) -> Self {
+        Self { limit, remaining: limit.max_messages, window_end: Instant::now() + limit.interval }
+    }
+
+    /// Returns `true` if another message is allowed.
+    fn try_acquire(&mut self) -> bool {
+        let now = Instant::now();
+        if now >= self.window_end {
+            self.remaining = self.limit.max_messages;
+            self.window_end = now + self.limit.interval;
+        }
+        if self.remaining == 0 {
+            return false
+        }
+        self.remaining -= 1;
+        true
+    }
+}
+
+/// Error of a request or message sent via a [`TypedPeerHandle`].
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum SubProtocolError {
+    /// The connection to the peer was closed.
+    #[error("connection closed")]
+    ConnectionClosed,
+    /// Too many messages are queued for the peer.
+    #[error("too many messages queued for the peer")]
+    ChannelFull,
+    /// The peer did not respond in time.
+    #[error("request timed out")]
+    Timeout,
+    /// The peer responded with a message that is not the expected response.
+    #[error("unexpected response with message id {0}")]
+    UnexpectedResponse(u8),
+}
+
+/// Events of a [`TypedProtocolHandler`].
+#[derive(Debug)]
+pub enum TypedProtocolEvent<M> {
+    /// The protocol was established with the peer.
+    Established {
+        /// The remote peer.
+        peer_id: PeerId,
+        /// Direction of the connection.
+        direction: Direction,
+        /// Handle to send messages to the peer.
+        peer: TypedPeerHandle<M>,
+    },
+    /// A notification was received from the peer.
+    Notification {
+        /// The remote peer.
+        peer_id: PeerId,
+        /// The received message.
+        message: M,
+    },
+    /// A request was received from the peer.
+    Request {
+        /// The remote peer.
+        peer_id: PeerId,
+        /// The received request.
+        request: M,
+        /// Sends the response to the peer.
+        responder: Responder<M>,
+    },
+    /// The connection to the peer was closed.
+    Closed {
+        /// The remote peer.
+        peer_id: PeerId,
+    },
+}
+
+/// A [`ProtocolHandler`] for the sub-protocol with messages `M`.
+///
+/// All connections report to the same [`TypedProtocolEvent`] channel, which is returned by
+/// [`TypedProtocolHandler::new`].
+pub struct TypedProtocolHandler<M> {
+    capability: Capability,
+    config: TypedProtocolConfig,
+    events: mpsc::Sender<TypedProtocolEvent<M>>,
+    metrics: SubProtocolMetrics,
+}
+
+impl<M> fmt::Debug for TypedProtocolHandler<M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TypedProtocolHandler")
+            .field("capability", &self.capability)
+            .field("config", &self.config)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<M: SubProtocolMessage> TypedProtocolHandler<M> {
+    /// Creates a new handler for the protocol with the given capability and the receiver of its
+    /// events.
+    pub fn new(
+        capability: Capability,
+        config: TypedProtocolConfig,
+    ) -> (Self, mpsc::Receiver<TypedProtocolEvent<M>>) {
+        let (events, rx) = mpsc::channel(config.event_buffer);
+        let metrics = SubProtocolMetrics::new_with_labels(&[("protocol", capability.to_string())]);
+        (Self { capability, config, events, metrics }, rx)
+    }
+
+    /// Returns the protocol that is announced to peers.
+    pub fn protocol(&self) -> Protocol {
+        Protocol::new(self.capability.clone(), M::MESSAGE_COUNT)
+    }
+
+    fn connection_handler(&self) -> TypedConnectionHandler<M> {
+        TypedConnectionHandler {
+            protocol: self.protocol(),
+            config: self.config.clone(),
+            events: self.events.clone(),
+            metrics: self.metrics.clone(),
+        }
+    }
+}
+
+impl<M: SubProtocolMessage> ProtocolHandler for TypedProtocolHandler<M> {
+    type ConnectionHandler = TypedConnectionHandler<M>;
+
+    fn on_incoming(&self, _socket_addr: SocketAddr) -> Option<Self::ConnectionHandler> {
+        Some(self.connection_handler())
+    }
+
+    fn on_outgoing(
+        &self,
+        _socket_addr: SocketAddr,
+        _peer_id: PeerId,
+    ) -> Option<Self::ConnectionHandler> {
+        Some(self.connection_handler())
+    }
+}
+
+/// The [`ConnectionHandler`] of a [`TypedProtocolHandler`].
+pub struct TypedConnectionHandler<M> {
+    protocol: Protocol,
+    config: TypedProtocolConfig,
+    events: mpsc::Sender<TypedProtocolEvent<M>>,
+    metrics: SubProtocolMetrics,
+}
+
+impl<M> fmt::Debug for TypedConnectionHandler<M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TypedConnectionHandler")
+            .field("protocol", &self.protocol)
+            .field("config", &self.config)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<M: SubProtocolMessage> ConnectionHandler for TypedConnectionHandler<M> {
+    type Connection = TypedConnection<M>;
+
+    fn protocol(&self) -> Protocol {
+        self.protocol.clone()
+    }
+
+    fn on_unsupported_by_peer(
+        self,
+        _supported: &SharedCapabilities,
+        _direction: Direction,
+        _peer_id: PeerId,
+    ) -> OnNotSupported {
+        self.config.on_unsupported_by_peer
+    }
+
+    fn into_connection(
+        self,
+        direction: Direction,
+        peer_id: PeerId,
+        conn: ProtocolConnection,
+    ) -> Self::Connection {
+        let (to_connection, commands) = mpsc::channel(self.config.command_buffer);
+        let disconnect = Arc::new(DisconnectSignal::default());
+        let peer = TypedPeerHandle {
+            peer_id,
+            to_connection: to_connection.clone(),
+            disconnect: disconnect.clone(),
+        };
+        self.metrics.active_connections.increment(1);
+
+        TypedConnection {
+            peer_id,
+            conn,
+            commands: ReceiverStream::new(commands),
+            to_connection,
+            disconnect,
+            events: PollSender::new(self.events.clone()),
+            on_close: PollSender::new(self.events),
+            pending_event: Some(TypedProtocolEvent::Established { peer_id, direction, peer }),
+            pending_requests: HashMap::new(),
+            next_request_id: 0,
+            request_timeout: self.config.request_timeout,
+            timeout_check: tokio::time::interval(
+                self.config.request_timeout.min(TIMEOUT_CHECK_INTERVAL),
+            ),
+            rate_limiter: self.config.rate_limit.map(RateLimiter::new),
+            metrics: self.metrics,
+        }
+    }
+}
+
+/// Commands sent from a [`TypedPeerHandle`] to its [`TypedConnection`].
+enum PeerCommand<M> {
+    /// Send a message without tracking a response.
+    Send(M),
+    /// Send a request and track its response.
+    Request {
+        /// Builds the request message for the allocated request id.
+        message: Box<dyn FnOnce(u64) -> M + Send>,
+        /// Receives the response.
+        response: oneshot::Sender<Result<M, SubProtocolError>>,
+    },
+}
+
+/// Requests closing a [`TypedConnection`] once all queued messages were sent.
+#[derive(Debug, Default)]
+struct DisconnectSignal {
+    requested: AtomicBool,
+    waker: AtomicWaker,
+}
+
+impl DisconnectSignal {
+    fn request(&self) {
+        self.requested.store(true, Ordering::Relaxed);
+        self.waker.wake();
+    }
+
+    fn is_requested(&self) -> bool {
+        self.requested.load(Ordering::Relaxed)
+    }
+}
+
+/// A request that awaits its response.
+struct PendingRequest<M> {
+    sent_at: Instant,
+    response: oneshot::Sender<Result<M, SubProtocolError>>,
+}
+
+/// The connection of a typed sub-protocol to a single peer.
+#[must_use = "Does nothing unless polled"]
+pub struct TypedConnection<M: Send + 'static> {
+    peer_id: PeerId,
+    conn: ProtocolConnection,
+    commands: ReceiverStream<PeerCommand<M>>,
+    /// Used to create [`Responder`]s.
+    to_connection: mpsc::Sender<PeerCommand<M>>,
+    disconnect: Arc<DisconnectSignal>,
+    events: PollSender<TypedProtocolEvent<M>>,
+    /// Holds the reserved slot for the [`TypedProtocolEvent::Closed`] event.
+    on_close: PollSender<TypedProtocolEvent<M>>,
+    /// The event that waits for capacity in the event channel.
+    pending_event: Option<TypedProtocolEvent<M>>,
+    pending_requests: HashMap<u64, PendingRequest<M>>,
+    next_request_id: u64,
+    request_timeout: Duration,
+    timeout_check: tokio::time::Interval,
+    rate_limiter: Option<RateLimiter>,
+    metrics: SubProtocolMetrics,
+}
+
+impl<M: Send + 'static> fmt::Debug for TypedConnection<M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TypedConnection")
+            .field("peer_id", &self.peer_id)
+            .field("pending_requests", &self.pending_requests.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<M: SubProtocolMessage> TypedConnection<M> {
+    /// Fails all requests that exceeded the request timeout.
+    fn expire_requests(&mut self) {
+        let now = Instant::now();
+        let expired = self
+            .pending_requests
+            .iter()
+            .filter(|(_, request)| now.duration_since(request.sent_at) >= self.request_timeout)
+            .map(|(request_id, _)| *request_id)
+            .collect::<Vec<_>>();
+
+        for request_id in expired {
+            let Some(request) = self.pending_requests.remove(&request_id) else { continue };
+            trace!(target: "net::subprotocol", peer_id=%self.peer_id, %request_id, "request timed out");
+            self.metrics.request_timeouts.increment(1);
+            let _ = request.response.send(Err(SubProtocolError::Timeout));
+        }
+    }
+
+    /// Sends the pending event once the event channel has capacity.
+    ///
+    /// Before the first event, a slot for the [`TypedProtocolEvent::Closed`] event is reserved,
+    /// so it can always be reported once the [`TypedProtocolEvent::Established`] event was.
+    fn poll_pending_event(&mut self, cx: &mut Context<'_>) -> Poll<()> {
+        if self.pending_event.is_none() {
+            return Poll::Ready(())
+        }
+
+        let reserved = ready!(self.on_close.poll_reserve(cx)).is_ok() &&
+            ready!(self.events.poll_reserve(cx)).is_ok();
+        let event = self.pending_event.take().expect("pending event is set");
+        if reserved {
+            let _ = self.events.send_item(event);
+        }
+        Poll::Ready(())
+    }
+
+    /// Handles a message received from the peer.
+    ///
+    /// Messages to report are stored as the pending event.
+    ///
+    /// Returns `false` if the message was invalid and the connection should be closed.
+    fn on_message(&mut self, bytes: &[u8]) -> bool {
+        self.metrics.messages_received.increment(1);
+        let message = match M::decode_message(bytes) {
+            Ok(message) => message,
+            Err(err) => {
+                trace!(target: "net::subprotocol", peer_id=%self.peer_id, %err, "invalid message");
+                self.metrics.invalid_messages_received.increment(1);
+                return false
+            }
+        };
+
+        let peer_id = self.peer_id;
+        let kind = message.kind();
+        if !matches!(kind, MessageKind::Response(_)) &&
+            self.rate_limiter.as_mut().is_some_and(|limiter| !limiter.try_acquire())
+        {
+            trace!(target: "net::subprotocol", %peer_id, "rate limit exceeded, dropping message");
+            self.metrics.rate_limited_messages.increment(1);
+            return true
+        }
+
+        match kind {
+            MessageKind::Notification => {
+                self.pending_event = Some(TypedProtocolEvent::Notification { peer_id, message });
+            }
+            MessageKind::Request(request_id) => {
+                let responder = Responder { request_id, to_connection: self.to_connection.clone() };
+                self.pending_event =
+                    Some(TypedProtocolEvent::Request { peer_id, request: message, responder });
+            }
+            MessageKind::Response(request_id) => {
+                if let Some(request) = self.pending_requests.remove(&request_id) {
+                    self.metrics.request_latency.record(request.sent_at.elapsed().as_secs_f64());
+                    let _ = request.response.send(Ok(message));
+                } else {
+                    trace!(target: "net::subprotocol", %peer_id, %request_id, "unsolicited response");
+                    self.metrics.unsolicited_responses.increment(1);
+                }
+            }
+        }
+        true
+    }
+}
+
+impl<M: SubProtocolMessage> Stream for TypedConnection<M> {
+    type Item = BytesMut;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        this.disconnect.waker.register(cx.waker());
+
+        // the connection holds a sender itself, so the command stream never terminates
+        if let Poll::Ready(Some(command)) = this.commands.poll_next_unpin(cx) {
+            let message = match command {
+                PeerCommand::Send(message) => message,
+                PeerCommand::Request { message, response } => {
+                    let request_id = this.next_request_id;
+                    this.next_request_id = this.next_request_id.wrapping_add(1);
+                    this.pending_requests
+                        .insert(request_id, PendingRequest { sent_at: Instant::now(), response });
+                    this.metrics.requests_sent.increment(1);
+                    message(request_id)
+                }
+            };
+            this.metrics.messages_sent.increment(1);
+            return Poll::Ready(Some(message.encoded()))
+        }
+
+        // all queued messages were sent
+        if this.disconnect.is_requested() {
+            return Poll::Ready(None)
+        }
+
+        while this.timeout_check.poll_tick(cx).is_ready() {
+            this.expire_requests();
+        }
+
+        loop {
+            // don't read from the peer until the previous message was reported
+            ready!(this.poll_pending_event(cx));
+
+            let Some(bytes) = ready!(this.conn.poll_next_unpin(cx)) else {
+                return Poll::Ready(None)
+            };
+            if !this.on_message(&bytes) {
+                return Poll::Ready(None)
+            }
+        }
+    }
+}
+
+impl<M: Send + 'static> Drop for TypedConnection<M> {
+    fn drop(&mut self) {
+        self.metrics.active_connections.decrement(1);
+        if !matches!(self.pending_event, Some(TypedProtocolEvent::Established { .. })) {
+            let _ = self.on_close.send_item(TypedProtocolEvent::Closed { peer_id: self.peer_id });
+        }
+    }
+}
+
+/// Handle to send messages to a peer via an established [`TypedConnection`].
+pub struct TypedPeerHandle<M> {
+    peer_id: PeerId,
+    to_connection: mpsc::Sender<PeerCommand<M>>,
+    disconnect: Arc<DisconnectSignal>,
+}
+
+impl<M> Clone for TypedPeerHandle<M> {
+    fn clone(&self) -> Self {
+        Self {
+            peer_id: self.peer_id,
+            to_connection: self.to_connection.clone(),
+            disconnect: self.disconnect.clone(),
+        }
+    }
+}
+
+impl<M> fmt::Debug for TypedPeerHandle<M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TypedPeerHandle").field("peer_id", &self.peer_id).finish_non_exhaustive()
+    }
+}
+
+impl<M: SubProtocolMessage> TypedPeerHandle<M> {
+    /// Returns the id of the peer.
+    pub const fn peer_id(&self) -> &PeerId {
+        &self.peer_id
+    }
+
+    /// Returns `true` if the connection to the peer was closed.
+    pub fn is_closed(&self) -> bool {
+        self.to_connection.is_closed()
+    }
+
+    /// Sends a notification to the peer.
+    ///
+    /// Returns [`SubProtocolError::ChannelFull`] if too many messages are queued for the peer.
+    pub fn send(&self, message: impl Into<M>) -> Result<(), SubProtocolError> {
+        try_send_command(&self.to_connection, PeerCommand::Send(message.into()))
+    }
+
+    /// Sends a request to the peer and waits for its response.
+    ///
+    /// Waits for capacity if too many messages are queued for the peer.
+    pub async fn request<R: SubProtocolRequest<M>>(
+        &self,
+        request: R,
+    ) -> Result<R::Response, SubProtocolError> {
+        let (tx, rx) = oneshot::channel();
+        self.to_connection
+            .send(PeerCommand::Request {
+                message: Box::new(move |request_id| request.into_message(request_id)),
+                response: tx,
+            })
+            .await
+            .map_err(|_| SubProtocolError::ConnectionClosed)?;
+
+        let response = rx.await.map_err(|_| SubProtocolError::ConnectionClosed)??;
+        R::try_into_response(response)
+            .map_err(|message| SubProtocolError::UnexpectedResponse(message.message_id()))
+    }
+
+    /// Closes the connection of the protocol once all queued messages were sent.
+    pub fn disconnect(&self) {
+        self.disconnect.request();
+    }
+}
+
+/// Queues a command for the connection without waiting for capacity.
+fn try_send_command<M>(
+    to_connection: &mpsc::Sender<PeerCommand<M>>,
+    command: PeerCommand<M>,
+) -> Result<(), SubProtocolError> {
+    to_connection.try_send(command).map_err(|err| match err {
+        TrySendError::Full(_) => SubProtocolError::ChannelFull,
+        TrySendError::Closed(_) => SubProtocolError::ConnectionClosed,
+    })
+}
+
+/// Sends the response to a request received via [`TypedProtocolEvent::Request`].
+pub struct Responder<M> {
+    request_id: u64,
+    to_connection: mpsc::Sender<PeerCommand<M>>,
+}
+
+impl<M> fmt::Debug for Responder<M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Responder").field("request_id", &self.request_id).finish_non_exhaustive()
+    }
+}
+
+impl<M: SubProtocolMessage> Responder<M> {
+    /// Returns the id of the request.
+    pub const fn request_id(&self) -> u64 {
+        self.request_id
+    }
+
+    /// Sends the response to the peer.
+    ///
+    /// Returns [`SubProtocolError::ChannelFull`] if too many messages are queued for the peer.
+    pub fn respond<R: SubProtocolResponse<M>>(self, response: R) -> Result<(), SubProtocolError> {
+        try_send_command(
+            &self.to_connection,
+            PeerCommand::Send(response.into_message(self.request_id)),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rate_limiter_window() {
+        let mut limiter = RateLimiter::new(RateLimit::new(2, Duration::from_secs(60)));
+        assert!(limiter.try_acquire());
+        assert!(limiter.try_acquire());
+        assert!(!limiter.try_acquire());
+
+        limiter.window_end = Instant::now();
+        assert!(limiter.try_acquire());
+    }
+
+    #[test]
+    fn command_channel_full() {
+        let (to_connection, mut commands) = mpsc::channel::<PeerCommand<()>>(1);
+
+        assert!(try_send_command(&to_connection, PeerCommand::Send(())).is_ok());
+        assert_eq!(
+            try_send_command(&to_connection, PeerCommand::Send(())),
+            Err(SubProtocolError::ChannelFull)
+        );
+
+        assert!(commands.try_recv().is_ok());
+        assert!(try_send_command(&to_connection, PeerCommand::Send(())).is_ok());
+
+        drop(commands);
+        assert_eq!(
+            try_send_command(&to_connection, PeerCommand::Send(())),
+            Err(SubProtocolError::ConnectionClosed)
+        );
+    }
+
+    #[test]
+    fn default_rate_limit() {
+        assert_eq!(
+            TypedProtocolConfig::default().rate_limit,
+            Some(DEFAULT_SUB_PROTOCOL_RATE_LIMIT)
+        );
+        assert_eq!(TypedProtocolConfig::default().without_rate_limit().rate_limit, None);
+    }
+}
@@ -0,0 +1,220 @@
+//! Replay of recorded `eth` sessions.
+//!
+//! A session recorded with the [`SessionRecorder`](crate::SessionRecorder) can be replayed against
+//! a local [`NetworkManager`](crate::NetworkManager): the replay connects to the node as a new peer
+//! and sends the messages that were received from the recorded peer, in their original order. This
+//! allows reproducing bugs that were triggered by a peer, without connecting to the network.
+
+use std::{
+    collections::VecDeque,
+    net::SocketAddr,
+    time::{Duration, UNIX_EPOCH},
+};
+
+use futures::{SinkExt, StreamExt};
+use reth_ecies::{stream::ECIESStream, ECIESError};
+use reth_eth_wire::{
+    errors::EthStreamError, BlockRangeUpdate, EthMessage, HelloMessage, Status, StatusMessage,
+    UnauthedEthStream, UnauthedP2PStream,
+};
+use reth_network_peers::{pk2id, PeerId};
+use reth_primitives::ForkFilter;
+use secp256k1::SECP256K1;
+use tokio::{net::TcpStream, time::Instant};
+use tracing::{debug, trace};
+
+use crate::{config::rng_secret_key, session::RecordedMessage};
+
+/// How long to wait for further messages from the node after the last message was replayed.
+pub const DEFAULT_REPLAY_LINGER: Duration = Duration::from_secs(5);
+
+/// Configures how a recorded session is replayed.
+#[derive(Debug, Clone)]
+pub struct ReplayConfig {
+    /// Whether to preserve the original delays between the messages. If disabled, the messages
+    /// are sent as fast as possible.
+    pub paced: bool,
+    /// How long to wait for further messages from the node after the last message was replayed.
+    pub linger: Duration,
+}
+
+impl Default for ReplayConfig {
+    fn default() -> Self {
+        Self { paced: false, linger: DEFAULT_REPLAY_LINGER }
+    }
+}
+
+/// The local node the session is replayed against.
+#[derive(Debug, Clone)]
+pub struct ReplayTarget {
+    /// The node's peer ID.
+    pub peer_id: PeerId,
+    /// The node's `RLPx` address.
+    pub addr: SocketAddr,
+    /// The `Status` to send to the node, must be compatible with the node's chain.
+    pub status: Status,
+    /// The block range to announce to the node, if it supports `eth/69`.
+    pub block_range: BlockRangeUpdate,
+    /// The fork filter to validate the node's `Status` with.
+    pub fork_filter: ForkFilter,
+}
+
+/// Errors when replaying a session.
+#[derive(Debug, thiserror::Error)]
+pub enum ReplayError {
+    /// Failed to connect to the node.
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    /// The `RLPx` handshake failed.
+    #[error(transparent)]
+    Ecies(#[from] ECIESError),
+    /// The `p2p` or `eth` handshake failed.
+    #[error(transparent)]
+    Handshake(EthStreamError),
+    /// The recording contains no messages received from the peer.
+    #[error("no messages to replay")]
+    NoMessages,
+}
+
+/// The result of a replay.
+#[derive(Debug, Default)]
+pub struct ReplayOutcome {
+    /// The number of replayed messages.
+    pub sent: usize,
+    /// The messages the node sent during the replay.
+    pub received: Vec<EthMessage>,
+    /// The error that terminated the session, e.g. because the node disconnected.
+    pub error: Option<EthStreamError>,
+}
+
+/// Replays the messages received from a recorded peer against the given node.
+///
+/// Only messages that were received from the peer are sent, the `Status` messages of the original
+/// handshake are skipped. The session is established with the `eth` version of the recording.
+///
+/// The node's requests get new request IDs, so recorded responses are sent with the ID of the
+/// oldest unanswered request of the matching kind, if any.
+pub async fn replay_session(
+    target: ReplayTarget,
+    messages: impl IntoIterator<Item = RecordedMessage>,
+    config: ReplayConfig,
+) -> Result<ReplayOutcome, ReplayError> {
+    let mut messages = messages
+        .into_iter()
+        .filter(|msg| msg.direction.is_received() && !matches!(msg.message, EthMessage::Status(_)))
+        .collect::<VecDeque<_>>();
+    let version = messages.front().ok_or(ReplayError::NoMessages)?.version;
+
+    let secret_key = rng_secret_key();
+    let stream = TcpStream::connect(target.addr).await?;
+    let stream = ECIESStream::connect(stream, secret_key, target.peer_id).await?;
+    let hello = HelloMessage::builder(pk2id(&secret_key.public_key(SECP256K1)))
+        .protocol(version)
+        .port(0)
+        .build();
+    let (p2p_stream, _) = UnauthedP2PStream::new(stream)
+        .handshake(hello)
+        .await
+        .map_err(|err| ReplayError::Handshake(err.into()))?;
+    let status = StatusMessage::new(version, target.status, target.block_range);
+    let (mut stream, _) = UnauthedEthStream::new(p2p_stream)
+        .handshake(status, target.fork_filter)
+        .await
+        .map_err(ReplayError::Handshake)?;
+    debug!(target: "net::replay", peer_id=%target.peer_id, %version, num_messages=messages.len(), "Replaying session");
+
+    let mut outcome = ReplayOutcome::default();
+    // requests of the node that were not answered yet
+    let mut pending_requests = VecDeque::new();
+    let started = Instant::now();
+    let first_timestamp = messages.front().map(|msg| msg.timestamp).unwrap_or(UNIX_EPOCH);
+    let mut linger_until = None;
+
+    loop {
+        let deadline = match messages.front() {
+            Some(next) if config.paced => {
+                started + next.timestamp.duration_since(first_timestamp).unwrap_or_default()
+            }
+            Some(_) => Instant::now(),
+            None => *linger_until.get_or_insert_with(|| Instant::now() + config.linger),
+        };
+
+        tokio::select! {
+            biased;
+
+            msg = stream.next() => match msg {
+                Some(Ok(mut msg)) => {
+                    trace!(target: "net::replay", msg_id=?msg.message_id(), "Received message");
+                    let msg_id = msg.message_id() as u8;
+                    if is_request(&msg) {
+                        if let Some(request_id) = request_id_mut(&mut msg) {
+                            pending_requests.push_back((msg_id, *request_id));
+                        }
+                    }
+                    outcome.received.push(msg);
+                }
+                Some(Err(err)) => {
+                    outcome.error = Some(err);
+                    break
+                }
+                None => break,
+            },
+            _ = tokio::time::sleep_until(deadline) => {
+                let Some(RecordedMessage { mut message, .. }) = messages.pop_front() else {
+                    break
+                };
+                if !is_request(&message) {
+                    // responses are matched with the oldest pending request of the same kind
+                    let request_msg_id = (message.message_id() as u8).wrapping_sub(1);
+                    if let Some(idx) =
+                        pending_requests.iter().position(|(msg_id, _)| *msg_id == request_msg_id)
+                    {
+                        let (_, id) = pending_requests.remove(idx).expect("exists");
+                        if let Some(request_id) = request_id_mut(&mut message) {
+                            *request_id = id;
+                        }
+                    }
+                }
+                trace!(target: "net::replay", msg_id=?message.message_id(), "Replaying message");
+                if let Err(err) = stream.send(message).await {
+                    outcome.error = Some(err);
+                    break
+                }
+                outcome.sent += 1;
+            }
+        }
+    }
+
+    Ok(outcome)
+}
+
+/// Returns `true` if the message is a request.
+const fn is_request(msg: &EthMessage) -> bool {
+    matches!(
+        msg,
+        EthMessage::GetBlockHeaders(_) |
+            EthMessage::GetBlockBodies(_) |
+            EthMessage::GetPooledTransactions(_) |
+            EthMessage::GetNodeData(_) |
+            EthMessage::GetReceipts(_)
+    )
+}
+
+/// Returns a mutable reference to the request ID of a request or response.
+fn request_id_mut(msg: &mut EthMessage) -> Option<&mut u64> {
+    let request_id = match msg {
+        EthMessage::GetBlockHeaders(pair) => &mut pair.request_id,
+        EthMessage::BlockHeaders(pair) => &mut pair.request_id,
+        EthMessage::GetBlockBodies(pair) => &mut pair.request_id,
+        EthMessage::BlockBodies(pair) => &mut pair.request_id,
+        EthMessage::GetPooledTransactions(pair) => &mut pair.request_id,
+        EthMessage::PooledTransactions(pair) => &mut pair.request_id,
+        EthMessage::GetNodeData(pair) => &mut pair.request_id,
+        EthMessage::NodeData(pair) => &mut pair.request_id,
+        EthMessage::GetReceipts(pair) => &mut pair.request_id,
+        EthMessage::Receipts(pair) => &mut pair.request_id,
+        EthMessage::Receipts69(pair) => &mut pair.request_id,
+        _ => return None,
+    };
+    Some(request_id)
+}
@@ -0,0 +1,257 @@
+//! Pluggable scoring of peers.
+//!
+//! The [`PeersManager`](crate::peers::PeersManager) delegates the decision which peers occupy the
+//! available connection slots to a [`PeerScoringPolicy`]. The policy is notified about the
+//! observed behaviour of peers and ranks them accordingly.
+//!
+//! The [`DefaultPeerScoringPolicy`] ranks peers by reputation only, the
+//! [`ResponseQualityScoringPolicy`] additionally prefers fast peers with useful responses and good
+//! transaction announcements.
+
+use std::{collections::HashMap, fmt, time::Duration};
+
+use reth_eth_wire::DisconnectReason;
+use reth_network_peers::PeerId;
+use reth_network_types::{Peer, ReputationChangeKind};
+
+/// Decides which peers the [`PeersManager`](crate::peers::PeersManager) prefers for its
+/// connection slots.
+///
+/// Trusted and static peers are not subject to the policy: they are always dialed first and
+/// never evicted.
+pub trait PeerScoringPolicy: fmt::Debug + Send + Sync + 'static {
+    /// Called when a response to a block request was received from a peer of the peer set.
+    ///
+    /// `useful` is `false` if the request failed or the response was rejected.
+    fn on_response(&mut self, peer_id: PeerId, latency: Duration, useful: bool) {
+        let _ = (peer_id, latency, useful);
+    }
+
+    /// Called when a reputation change is applied to a peer of the peer set.
+    ///
+    /// This includes the quality of the peer's transaction announcements, e.g.
+    /// [`ReputationChangeKind::BadAnnouncement`] or [`ReputationChangeKind::BadTransactions`].
+    fn on_reputation_change(&mut self, peer_id: PeerId, kind: ReputationChangeKind) {
+        let _ = (peer_id, kind);
+    }
+
+    /// Called when an active session to the peer was closed.
+    ///
+    /// `reason` is the [`DisconnectReason`], if the session was closed due to a disconnect
+    /// message.
+    fn on_disconnect(&mut self, peer_id: PeerId, reason: Option<DisconnectReason>) {
+        let _ = (peer_id, reason);
+    }
+
+    /// Called when the peer was removed from the peer set.
+    fn on_peer_removed(&mut self, peer_id: PeerId) {
+        let _ = peer_id;
+    }
+
+    /// Returns the score of the peer. Free outbound slots are filled with the unconnected peers
+    /// with the highest score.
+    fn score(&self, peer_id: &PeerId, peer: &Peer) -> i64;
+
+    /// Called when an inbound session was established while all inbound slots are occupied.
+    ///
+    /// Returns the peer among the given `inbound` peers to disconnect in favor of the `incoming`
+    /// peer, or `None` if the incoming peer should be rejected.
+    fn select_eviction(
+        &self,
+        incoming: (&PeerId, &Peer),
+        inbound: &[(&PeerId, &Peer)],
+    ) -> Option<PeerId> {
+        let _ = (incoming, inbound);
+        None
+    }
+}
+
+/// The default [`PeerScoringPolicy`]: ranks peers by reputation and never evicts peers.
+#[derive(Debug, Clone, Copy, Default)]
+#[non_exhaustive]
+pub struct DefaultPeerScoringPolicy;
+
+impl PeerScoringPolicy for DefaultPeerScoringPolicy {
+    fn score(&self, _peer_id: &PeerId, peer: &Peer) -> i64 {
+        peer.reputation() as i64
+    }
+}
+
+/// Default weight of a fully useful response history, see [`ResponseQualityScoringPolicy`].
+pub const DEFAULT_USEFUL_RESPONSE_WEIGHT: i64 = 10_000;
+
+/// Default penalty per bad transaction announcement, see [`ResponseQualityScoringPolicy`].
+pub const DEFAULT_BAD_ANNOUNCEMENT_PENALTY: i64 = 1_000;
+
+/// Default number of responses before a peer can be evicted, see
+/// [`ResponseQualityScoringPolicy`].
+pub const DEFAULT_MIN_RESPONSES_FOR_EVICTION: u32 = 10;
+
+/// A [`PeerScoringPolicy`] that prefers fast peers with a high ratio of useful responses and good
+/// transaction announcements.
+///
+/// The score of a peer is its reputation, plus the share of useful responses weighted by
+/// `useful_response_weight`, minus its average response latency in milliseconds and a penalty for
+/// every bad transaction announcement.
+///
+/// If all inbound slots are occupied, the inbound peer with the lowest score is evicted in favor
+/// of an incoming peer with a higher score, if at least `min_responses_for_eviction` responses
+/// were received from it.
+#[derive(Debug, Clone)]
+pub struct ResponseQualityScoringPolicy {
+    /// Weight of a fully useful response history.
+    pub useful_response_weight: i64,
+    /// Penalty per bad transaction announcement.
+    pub bad_announcement_penalty: i64,
+    /// Number of responses before a peer can be evicted.
+    pub min_responses_for_eviction: u32,
+    /// Observed stats of the peers.
+    stats: HashMap<PeerId, ResponseStats>,
+}
+
+impl Default for ResponseQualityScoringPolicy {
+    fn default() -> Self {
+        Self {
+            useful_response_weight: DEFAULT_USEFUL_RESPONSE_WEIGHT,
+            bad_announcement_penalty: DEFAULT_BAD_ANNOUNCEMENT_PENALTY,
+            min_responses_for_eviction: DEFAULT_MIN_RESPONSES_FOR_EVICTION,
+            stats: Default::default(),
+        }
+    }
+}
+
+impl ResponseQualityScoringPolicy {
+    /// Returns the observed stats of the peer, if any.
+    pub fn stats(&self, peer_id: &PeerId) -> Option<&ResponseStats> {
+        self.stats.get(peer_id)
+    }
+}
+
+impl PeerScoringPolicy for ResponseQualityScoringPolicy {
+    fn on_response(&mut self, peer_id: PeerId, latency: Duration, useful: bool) {
+        self.stats.entry(peer_id).or_default().on_response(latency, useful);
+    }
+
+    fn on_reputation_change(&mut self, peer_id: PeerId, kind: ReputationChangeKind) {
+        if matches!(
+            kind,
+            ReputationChangeKind::BadAnnouncement | ReputationChangeKind::BadTransactions
+        ) {
+            self.stats.entry(peer_id).or_default().bad_announcements += 1;
+        }
+    }
+
+    fn on_peer_removed(&mut self, peer_id: PeerId) {
+        self.stats.remove(&peer_id);
+    }
+
+    fn score(&self, peer_id: &PeerId, peer: &Peer) -> i64 {
+        let mut score = peer.reputation() as i64;
+        if let Some(stats) = self.stats.get(peer_id) {
+            if stats.responses > 0 {
+                score += self.useful_response_weight * stats.useful_responses as i64 /
+                    stats.responses as i64;
+                score -= stats.avg_latency.as_millis() as i64;
+            }
+            score -= self.bad_announcement_penalty * stats.bad_announcements as i64;
+        }
+        score
+    }
+
+    fn select_eviction(
+        &self,
+        incoming: (&PeerId, &Peer),
+        inbound: &[(&PeerId, &Peer)],
+    ) -> Option<PeerId> {
+        let incoming_score = self.score(incoming.0, incoming.1);
+        inbound
+            .iter()
+            .filter(|(peer_id, _)| {
+                self.stats
+                    .get(peer_id)
+                    .is_some_and(|stats| stats.responses >= self.min_responses_for_eviction)
+            })
+            .map(|(peer_id, peer)| (**peer_id, self.score(peer_id, peer)))
+            .filter(|(_, score)| *score < incoming_score)
+            .min_by_key(|(_, score)| *score)
+            .map(|(peer_id, _)| peer_id)
+    }
+}
+
+/// Response stats of a peer, tracked by the [`ResponseQualityScoringPolicy`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ResponseStats {
+    /// Number of responses received.
+    pub responses: u32,
+    /// Number of useful responses received.
+    pub useful_responses: u32,
+    /// Exponential moving average of the response latency.
+    pub avg_latency: Duration,
+    /// Number of bad transaction announcements.
+    pub bad_announcements: u32,
+}
+
+impl ResponseStats {
+    /// Records a response.
+    fn on_response(&mut self, latency: Duration, useful: bool) {
+        self.avg_latency = if self.responses == 0 {
+            latency
+        } else {
+            // weigh the latest sample with 1/8, like the TCP RTT estimation
+            (self.avg_latency * 7 + latency) / 8
+        };
+        self.responses = self.responses.saturating_add(1);
+        if useful {
+            self.useful_responses = self.useful_responses.saturating_add(1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_network_types::PeerAddr;
+    use std::net::SocketAddr;
+
+    fn peer() -> Peer {
+        Peer::new(PeerAddr::from_tcp(SocketAddr::from(([127, 0, 0, 1], 30303))))
+    }
+
+    #[test]
+    fn prefers_fast_useful_peers() {
+        let mut policy = ResponseQualityScoringPolicy::default();
+        let (fast, slow, bad) = (PeerId::random(), PeerId::random(), PeerId::random());
+        for _ in 0..10 {
+            policy.on_response(fast, Duration::from_millis(50), true);
+            policy.on_response(slow, Duration::from_millis(2_000), true);
+            policy.on_response(bad, Duration::from_millis(50), false);
+        }
+
+        let peer = peer();
+        let score = |peer_id| policy.score(peer_id, &peer);
+        assert!(score(&fast) > score(&slow));
+        assert!(score(&slow) > score(&bad));
+
+        policy.on_reputation_change(fast, ReputationChangeKind::BadAnnouncement);
+        assert_eq!(policy.stats(&fast).unwrap().bad_announcements, 1);
+    }
+
+    #[test]
+    fn evicts_worst_inbound_peer() {
+        let mut policy = ResponseQualityScoringPolicy::default();
+        let (good, bad, unknown, incoming) =
+            (PeerId::random(), PeerId::random(), PeerId::random(), PeerId::random());
+        for _ in 0..DEFAULT_MIN_RESPONSES_FOR_EVICTION {
+            policy.on_response(good, Duration::from_millis(50), true);
+            policy.on_response(bad, Duration::from_millis(50), false);
+        }
+
+        let peer = peer();
+        let inbound = [(&good, &peer), (&bad, &peer), (&unknown, &peer)];
+        assert_eq!(policy.select_eviction((&incoming, &peer), &inbound), Some(bad));
+
+        // not enough responses to judge the peer
+        policy.on_peer_removed(bad);
+        assert_eq!(policy.select_eviction((&incoming, &peer), &inbound), None);
+    }
+}
@@ -15,7 +15,7 @@ use futures::{stream::Fuse, SinkExt, StreamExt};
 use reth_eth_wire::{
     errors::{EthHandshakeError, EthStreamError, P2PStreamError},
     message::{EthBroadcastMessage, RequestPair},
-    Capabilities, DisconnectP2P, DisconnectReason, EthMessage,
+    Capabilities, DisconnectP2P, DisconnectReason, EthMessage, EthVersion,
 };
 use reth_metrics::common::mpsc::MeteredPollSender;
 use reth_network_api::PeerRequest;
@@ -36,6 +36,7 @@ use crate::{
     session::{
         conn::EthRlpxConnection,
         handle::{ActiveSessionMessage, SessionCommand},
+        recorder::SessionRecorder,
         SessionId,
     },
 };
@@ -97,6 +98,8 @@ pub(crate) struct ActiveSession {
     pub(crate) protocol_breach_request_timeout: Duration,
     /// Used to reserve a slot to guarantee that the termination message is delivered
     pub(crate) terminate_message: Option<(PollSender<ActiveSessionMessage>, ActiveSessionMessage)>,
+    /// Records the exchanged messages, if enabled.
+    pub(crate) recorder: Option<SessionRecorder>,
 }
 
 impl ActiveSession {
@@ -112,6 +115,28 @@ impl ActiveSession {
         id
     }
 
+    /// Records a message received from the remote peer, if recording is enabled.
+    fn record_incoming(&self, msg: &EthMessage) {
+        if let Some(recorder) = &self.recorder {
+            recorder.record_received(self.remote_peer_id, self.conn.version(), msg);
+        }
+    }
+
+    /// Records a message sent to the remote peer, if recording is enabled.
+    fn record_outgoing(&self, msg: &OutgoingMessage) {
+        if let Some(recorder) = &self.recorder {
+            let version = self.conn.version();
+            match msg {
+                OutgoingMessage::Eth(msg) => {
+                    recorder.record_sent(self.remote_peer_id, version, msg)
+                }
+                OutgoingMessage::Broadcast(msg) => {
+                    recorder.record_sent_broadcast(self.remote_peer_id, version, msg)
+                }
+            }
+        }
+    }
+
     /// Shrinks the capacity of the internal buffers.
     pub fn shrink_to_fit(&mut self) {
         self.received_requests_from_remote.shrink_to_fit();
@@ -233,6 +258,21 @@ impl ActiveSession {
             EthMessage::Receipts(resp) => {
                 on_response!(resp, GetReceipts)
             }
+            EthMessage::Receipts69(resp) => {
+                // eth/69 receipts don't include the bloom, which is recomputed from the logs
+                let resp = RequestPair {
+                    request_id: resp.request_id,
+                    message: resp.message.into_receipts_with_bloom(),
+                };
+                on_response!(resp, GetReceipts)
+            }
+            EthMessage::BlockRangeUpdate(msg) => {
+                if !msg.is_valid() {
+                    self.on_bad_message();
+                    return OnIncomingMessageOutcome::Ok
+                }
+                self.try_emit_broadcast(PeerMessage::BlockRangeUpdated(msg)).into()
+            }
         }
     }
 
@@ -253,10 +293,20 @@ impl ActiveSession {
     fn on_internal_peer_message(&mut self, msg: PeerMessage) {
         match msg {
             PeerMessage::NewBlockHashes(msg) => {
-                self.queued_outgoing.push_back(EthMessage::NewBlockHashes(msg).into());
+                // block announcements were removed in eth/69
+                if self.conn.version() < EthVersion::Eth69 {
+                    self.queued_outgoing.push_back(EthMessage::NewBlockHashes(msg).into());
+                }
             }
             PeerMessage::NewBlock(msg) => {
-                self.queued_outgoing.push_back(EthBroadcastMessage::NewBlock(msg.block).into());
+                if self.conn.version() < EthVersion::Eth69 {
+                    self.queued_outgoing.push_back(EthBroadcastMessage::NewBlock(msg.block).into());
+                }
+            }
+            PeerMessage::BlockRangeUpdated(msg) => {
+                if self.conn.version() >= EthVersion::Eth69 {
+                    self.queued_outgoing.push_back(EthMessage::BlockRangeUpdate(msg).into());
+                }
             }
             PeerMessage::PooledTransactions(msg) => {
                 if msg.is_valid_for_version(self.conn.version()) {
@@ -291,6 +341,15 @@ impl ActiveSession {
     fn handle_outgoing_response(&mut self, id: u64, resp: PeerResponseResult) {
         match resp.try_into_message(id) {
             Ok(msg) => {
+                let msg = match msg {
+                    EthMessage::Receipts(resp) if self.conn.version() >= EthVersion::Eth69 => {
+                        EthMessage::Receipts69(RequestPair {
+                            request_id: resp.request_id,
+                            message: resp.message.into(),
+                        })
+                    }
+                    msg => msg,
+                };
                 self.queued_outgoing.push_back(msg.into());
             }
             Err(err) => {
@@ -553,6 +612,7 @@ impl Future for ActiveSession {
             while this.conn.poll_ready_unpin(cx).is_ready() {
                 if let Some(msg) = this.queued_outgoing.pop_front() {
                     progress = true;
+                    this.record_outgoing(&msg);
                     let res = match msg {
                         OutgoingMessage::Eth(msg) => this.conn.start_send_unpin(msg),
                         OutgoingMessage::Broadcast(msg) => this.conn.start_send_broadcast(msg),
@@ -607,6 +667,7 @@ impl Future for ActiveSession {
                         match res {
                             Ok(msg) => {
                                 trace!(target: "net::session", msg_id=?msg.message_id(), remote_peer_id=?this.remote_peer_id, "received eth message");
+                                this.record_incoming(&msg);
                                 // decode and handle message
                                 match this.on_incoming_message(msg) {
                                     OnIncomingMessageOutcome::Ok => {
@@ -846,8 +907,10 @@ mod tests {
                 self.secret_key,
                 self.hello.clone(),
                 self.status,
+                Default::default(),
                 self.fork_filter.clone(),
                 Default::default(),
+                None,
             ));
 
             let mut stream = ReceiverStream::new(pending_sessions_rx);
@@ -892,6 +955,7 @@ mod tests {
                         )),
                         protocol_breach_request_timeout: PROTOCOL_BREACH_REQUEST_TIMEOUT,
                         terminate_message: None,
+                        recorder: None,
                     }
                 }
                 ev => {
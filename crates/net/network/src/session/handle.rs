@@ -4,8 +4,8 @@ use std::{io, net::SocketAddr, sync::Arc, time::Instant};
 
 use reth_ecies::ECIESError;
 use reth_eth_wire::{
-    capability::CapabilityMessage, errors::EthStreamError, Capabilities, DisconnectReason,
-    EthVersion, Status,
+    capability::CapabilityMessage, errors::EthStreamError, BlockRangeUpdate, Capabilities,
+    DisconnectReason, EthVersion, Status,
 };
 use reth_network_api::PeerInfo;
 use reth_network_peers::{NodeRecord, PeerId};
@@ -177,6 +177,8 @@ pub enum PendingSessionEvent {
         capabilities: Arc<Capabilities>,
         /// The Status message the peer sent for the `eth` handshake
         status: Arc<Status>,
+        /// The block range the peer announced in its `eth/69` status message
+        block_range: Option<BlockRangeUpdate>,
         /// The actual connection stream which can be used to send and receive `eth` protocol
         /// messages
         conn: EthRlpxConnection,
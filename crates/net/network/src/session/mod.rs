@@ -4,12 +4,17 @@ mod active;
 mod conn;
 mod counter;
 mod handle;
+mod recorder;
 
 pub use conn::EthRlpxConnection;
 pub use handle::{
     ActiveSessionHandle, ActiveSessionMessage, PendingSessionEvent, PendingSessionHandle,
     SessionCommand,
 };
+pub use recorder::{
+    MessageDirection, RecordedMessage, SessionRecordError, SessionRecordReader, SessionRecorder,
+    RECORDING_MAGIC,
+};
 
 pub use reth_network_api::{Direction, PeerInfo};
 
@@ -27,8 +32,8 @@ use futures::{future::Either, io, FutureExt, StreamExt};
 use reth_ecies::{stream::ECIESStream, ECIESError};
 use reth_eth_wire::{
     capability::CapabilityMessage, errors::EthStreamError, multiplex::RlpxProtocolMultiplexer,
-    Capabilities, DisconnectReason, EthVersion, HelloMessageWithProtocols, Status,
-    UnauthedEthStream, UnauthedP2PStream,
+    BlockRangeUpdate, Capabilities, DisconnectReason, EthMessage, EthVersion,
+    HelloMessageWithProtocols, Status, StatusMessage, UnauthedEthStream, UnauthedP2PStream,
 };
 use reth_metrics::common::mpsc::MeteredPollSender;
 use reth_network_api::PeerRequestSender;
@@ -54,6 +59,10 @@ use crate::{
     session::active::ActiveSession,
 };
 
+/// The number of blocks after which an updated [`BlockRangeUpdate`] is announced to `eth/69`
+/// peers.
+const BLOCK_RANGE_UPDATE_INTERVAL: u64 = 32;
+
 /// Internal identifier for active sessions.
 #[derive(Debug, Clone, Copy, PartialOrd, PartialEq, Eq, Hash)]
 pub struct SessionId(usize);
@@ -78,6 +87,10 @@ pub struct SessionManager {
     secret_key: SecretKey,
     /// The `Status` message to send to peers.
     status: Status,
+    /// The range of blocks this node can serve, sent to `eth/69` peers.
+    block_range: BlockRangeUpdate,
+    /// The latest block of the last [`BlockRangeUpdate`] announced to active sessions.
+    last_announced_block: u64,
     /// The `HelloMessage` message to send to peers.
     hello_message: HelloMessageWithProtocols,
     /// The [`ForkFilter`] used to validate the peer's `Status` message.
@@ -111,6 +124,8 @@ pub struct SessionManager {
     extra_protocols: RlpxSubProtocols,
     /// Metrics for the session manager.
     metrics: SessionManagerMetrics,
+    /// Records the messages of all active sessions, if enabled.
+    recorder: Option<SessionRecorder>,
 }
 
 // === impl SessionManager ===
@@ -123,6 +138,7 @@ impl SessionManager {
         config: SessionsConfig,
         executor: Box<dyn TaskSpawner>,
         status: Status,
+        block_range: BlockRangeUpdate,
         hello_message: HelloMessageWithProtocols,
         fork_filter: ForkFilter,
         extra_protocols: RlpxSubProtocols,
@@ -139,6 +155,8 @@ impl SessionManager {
             pending_session_timeout: config.pending_session_timeout,
             secret_key,
             status,
+            block_range,
+            last_announced_block: block_range.latest,
             hello_message,
             fork_filter,
             session_command_buffer: config.session_command_buffer,
@@ -151,6 +169,7 @@ impl SessionManager {
             active_session_rx: ReceiverStream::new(active_session_rx),
             extra_protocols,
             metrics: Default::default(),
+            recorder: None,
         }
     }
 
@@ -172,6 +191,11 @@ impl SessionManager {
         self.status
     }
 
+    /// Returns the range of blocks this node can serve.
+    pub const fn block_range(&self) -> BlockRangeUpdate {
+        self.block_range
+    }
+
     /// Returns the secret key used for authenticating sessions.
     pub const fn secret_key(&self) -> SecretKey {
         self.secret_key
@@ -192,6 +216,11 @@ impl SessionManager {
         self.extra_protocols.push(protocol)
     }
 
+    /// Records the messages of all sessions that are established from now on.
+    pub(crate) fn set_recorder(&mut self, recorder: SessionRecorder) {
+        self.recorder = Some(recorder);
+    }
+
     /// Returns the number of currently pending connections.
     #[inline]
     pub(crate) fn num_pending_connections(&self) -> usize {
@@ -214,11 +243,45 @@ impl SessionManager {
     pub(crate) fn on_status_update(&mut self, head: Head) -> Option<ForkTransition> {
         self.status.blockhash = head.hash;
         self.status.total_difficulty = head.total_difficulty;
+        self.block_range.latest = head.number;
+        self.block_range.latest_hash = head.hash;
+        self.block_range.earliest = self.block_range.earliest.min(head.number);
         let transition = self.fork_filter.set_head(head);
         self.status.forkid = self.fork_filter.current();
+
+        if head.number.abs_diff(self.last_announced_block) >= BLOCK_RANGE_UPDATE_INTERVAL {
+            self.announce_block_range();
+        }
+
         transition
     }
 
+    /// Invoked when the earliest block served to peers changed, e.g. because history was expired.
+    ///
+    /// The earliest block only moves forward, and is announced to `eth/69` peers when it does.
+    pub(crate) fn on_earliest_block_update(&mut self, earliest: u64) {
+        let earliest = earliest.min(self.block_range.latest);
+        if earliest > self.block_range.earliest {
+            self.block_range.earliest = earliest;
+            self.announce_block_range();
+        }
+    }
+
+    /// Sends the current [`BlockRangeUpdate`] to all active `eth/69` sessions.
+    fn announce_block_range(&mut self) {
+        self.last_announced_block = self.block_range.latest;
+        let range = self.block_range;
+        let peers = self
+            .active_sessions
+            .iter()
+            .filter(|(_, session)| session.version >= EthVersion::Eth69)
+            .map(|(peer_id, _)| *peer_id)
+            .collect::<Vec<_>>();
+        for peer_id in peers {
+            self.send_message(&peer_id, PeerMessage::BlockRangeUpdated(range));
+        }
+    }
+
     /// An incoming TCP connection was received. This starts the authentication process to turn this
     /// stream into an active peer session.
     ///
@@ -244,8 +307,10 @@ impl SessionManager {
         let secret_key = self.secret_key;
         let hello_message = self.hello_message.clone();
         let status = self.status;
+        let block_range = self.block_range;
         let fork_filter = self.fork_filter.clone();
         let extra_handlers = self.extra_protocols.on_incoming(remote_addr);
+        let recorder = self.recorder.clone();
         self.spawn(pending_session_with_timeout(
             self.pending_session_timeout,
             session_id,
@@ -261,8 +326,10 @@ impl SessionManager {
                 secret_key,
                 hello_message,
                 status,
+                block_range,
                 fork_filter,
                 extra_handlers,
+                recorder,
             ),
         ));
 
@@ -286,7 +353,9 @@ impl SessionManager {
             let hello_message = self.hello_message.clone();
             let fork_filter = self.fork_filter.clone();
             let status = self.status;
+            let block_range = self.block_range;
             let extra_handlers = self.extra_protocols.on_outgoing(remote_addr, remote_peer_id);
+            let recorder = self.recorder.clone();
             self.spawn(pending_session_with_timeout(
                 self.pending_session_timeout,
                 session_id,
@@ -302,8 +371,10 @@ impl SessionManager {
                     secret_key,
                     hello_message,
                     status,
+                    block_range,
                     fork_filter,
                     extra_handlers,
+                    recorder,
                 ),
             ));
 
@@ -440,6 +511,7 @@ impl SessionManager {
                 capabilities,
                 conn,
                 status,
+                block_range,
                 direction,
                 client_id,
             } => {
@@ -503,6 +575,7 @@ impl SessionManager {
                     internal_request_timeout: Arc::clone(&timeout),
                     protocol_breach_request_timeout: self.protocol_breach_request_timeout,
                     terminate_message: None,
+                    recorder: self.recorder.clone(),
                 };
 
                 self.spawn(session);
@@ -536,6 +609,7 @@ impl SessionManager {
                     version,
                     capabilities,
                     status,
+                    block_range,
                     messages,
                     direction,
                     timeout,
@@ -631,6 +705,8 @@ pub enum SessionEvent {
         version: EthVersion,
         /// The Status message the peer sent during the `eth` handshake
         status: Arc<Status>,
+        /// The block range the peer announced during the `eth/69` handshake
+        block_range: Option<BlockRangeUpdate>,
         /// The channel for sending messages to the peer with the session
         messages: PeerRequestSender,
         /// The direction of the session, either `Inbound` or `Outgoing`
@@ -783,8 +859,10 @@ pub(crate) async fn start_pending_incoming_session(
     secret_key: SecretKey,
     hello: HelloMessageWithProtocols,
     status: Status,
+    block_range: BlockRangeUpdate,
     fork_filter: ForkFilter,
     extra_handlers: RlpxSubProtocolHandlers,
+    recorder: Option<SessionRecorder>,
 ) {
     authenticate(
         disconnect_rx,
@@ -796,8 +874,10 @@ pub(crate) async fn start_pending_incoming_session(
         Direction::Incoming,
         hello,
         status,
+        block_range,
         fork_filter,
         extra_handlers,
+        recorder,
     )
     .await
 }
@@ -814,8 +894,10 @@ async fn start_pending_outbound_session(
     secret_key: SecretKey,
     hello: HelloMessageWithProtocols,
     status: Status,
+    block_range: BlockRangeUpdate,
     fork_filter: ForkFilter,
     extra_handlers: RlpxSubProtocolHandlers,
+    recorder: Option<SessionRecorder>,
 ) {
     let stream = match TcpStream::connect(remote_addr).await {
         Ok(stream) => {
@@ -846,8 +928,10 @@ async fn start_pending_outbound_session(
         Direction::Outgoing(remote_peer_id),
         hello,
         status,
+        block_range,
         fork_filter,
         extra_handlers,
+        recorder,
     )
     .await
 }
@@ -864,8 +948,10 @@ async fn authenticate(
     direction: Direction,
     hello: HelloMessageWithProtocols,
     status: Status,
+    block_range: BlockRangeUpdate,
     fork_filter: ForkFilter,
     extra_handlers: RlpxSubProtocolHandlers,
+    recorder: Option<SessionRecorder>,
 ) {
     let local_addr = stream.local_addr().ok();
     let stream = match get_eciess_stream(stream, secret_key, direction).await {
@@ -893,8 +979,10 @@ async fn authenticate(
         direction,
         hello,
         status,
+        block_range,
         fork_filter,
         extra_handlers,
+        recorder,
     )
     .boxed();
 
@@ -944,9 +1032,11 @@ async fn authenticate_stream(
     local_addr: Option<SocketAddr>,
     direction: Direction,
     mut hello: HelloMessageWithProtocols,
-    mut status: Status,
+    status: Status,
+    block_range: BlockRangeUpdate,
     fork_filter: ForkFilter,
     mut extra_handlers: RlpxSubProtocolHandlers,
+    recorder: Option<SessionRecorder>,
 ) -> PendingSessionEvent {
     // Add extra protocols to the hello message
     extra_handlers.retain(|handler| hello.try_add_protocol(handler.protocol()).is_ok());
@@ -977,11 +1067,11 @@ async fn authenticate_stream(
         }
     };
 
+    // Before trying status handshake, set up the status for the negotiated shared version
+    let status = StatusMessage::new(eth_version, status, block_range);
+
     let (conn, their_status) = if p2p_stream.shared_capabilities().len() == 1 {
         // if the hello handshake was successful we can try status handshake
-        //
-        // Before trying status handshake, set up the version to negotiated shared version
-        status.set_eth_version(eth_version);
         let eth_unauthed = UnauthedEthStream::new(p2p_stream);
         let (eth_stream, their_status) = match eth_unauthed.handshake(status, fork_filter).await {
             Ok(stream_res) => stream_res,
@@ -1026,13 +1116,19 @@ async fn authenticate_stream(
         (multiplex_stream.into(), their_status)
     };
 
+    if let Some(recorder) = &recorder {
+        recorder.record_sent(their_hello.id, eth_version, &EthMessage::Status(status));
+        recorder.record_received(their_hello.id, eth_version, &EthMessage::Status(their_status));
+    }
+
     PendingSessionEvent::Established {
         session_id,
         remote_addr,
         local_addr,
         peer_id: their_hello.id,
         capabilities: Arc::new(Capabilities::from(their_hello.capabilities)),
-        status: Arc::new(their_status),
+        status: Arc::new(their_status.to_legacy()),
+        block_range: their_status.block_range(),
         conn,
         direction,
         client_id: their_hello.client_version,
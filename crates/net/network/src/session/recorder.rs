@@ -0,0 +1,343 @@
+//! Recording of the `eth` messages exchanged with peers.
+//!
+//! The recorder writes every `eth` message that is sent to or received from a peer to a log file,
+//! including the `Status` messages of the handshake, together with the peer's ID, the direction
+//! and a timestamp. Recorded sessions can be read with
+//! [`SessionRecordReader`] and replayed against a local node, see
+//! [`replay_session`](crate::replay::replay_session).
+//!
+//! The log starts with the magic bytes [`RECORDING_MAGIC`] followed by the format version. Each
+//! record is prefixed with its length as big endian `u32`, and consists of:
+//!
+//! - the timestamp in milliseconds since the UNIX epoch, as big endian `u64`
+//! - the direction, `0` for received and `1` for sent messages
+//! - the negotiated `eth` version
+//! - the 64 byte peer ID
+//! - the message ID followed by the RLP encoded message, as sent on the wire
+
+use std::{
+    fs::File,
+    io::{self, BufReader, BufWriter, Read, Write},
+    path::Path,
+    sync::mpsc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use alloy_rlp::Encodable;
+use reth_eth_wire::{
+    message::{EthBroadcastMessage, MessageError, ProtocolMessage},
+    EthMessage, EthVersion,
+};
+use reth_network_peers::PeerId;
+use tracing::{debug, trace};
+
+use crate::metrics::SessionRecorderMetrics;
+
+/// Magic bytes at the start of a session recording.
+pub const RECORDING_MAGIC: &[u8; 8] = b"rethwire";
+
+/// Version of the recording format.
+const RECORDING_VERSION: u8 = 1;
+
+/// Size of the fixed part of a record: timestamp, direction, `eth` version and peer ID.
+const RECORD_HEADER_LEN: usize = 8 + 1 + 1 + 64;
+
+/// Upper bound of the size of a single record.
+const MAX_RECORD_LEN: usize = 64 * 1024 * 1024;
+
+/// Number of records that can be queued for the writer thread.
+const RECORDER_CHANNEL_SIZE: usize = 4096;
+
+/// Whether a recorded message was sent or received.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageDirection {
+    /// The message was received from the peer.
+    Received,
+    /// The message was sent to the peer.
+    Sent,
+}
+
+impl MessageDirection {
+    /// Returns `true` if the message was received from the peer.
+    pub const fn is_received(&self) -> bool {
+        matches!(self, Self::Received)
+    }
+}
+
+/// Records the `eth` messages of all sessions to a file.
+///
+/// The records are written by a dedicated thread, so that sessions never block on disk IO. If the
+/// thread can't keep up, records are dropped. The file is flushed whenever there are no more
+/// pending records, and the thread exits once all clones of the recorder have been dropped.
+#[derive(Debug, Clone)]
+pub struct SessionRecorder {
+    to_writer: mpsc::SyncSender<Vec<u8>>,
+    metrics: SessionRecorderMetrics,
+}
+
+impl SessionRecorder {
+    /// Creates a new recording at the given path, truncating any existing file.
+    pub fn new(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref();
+        let mut writer = BufWriter::new(File::create(path)?);
+        writer.write_all(RECORDING_MAGIC)?;
+        writer.write_all(&[RECORDING_VERSION])?;
+        writer.flush()?;
+
+        let (to_writer, records) = mpsc::sync_channel::<Vec<u8>>(RECORDER_CHANNEL_SIZE);
+        std::thread::Builder::new().name("session-recorder".to_string()).spawn(move || {
+            let mut write_records = || -> io::Result<()> {
+                while let Ok(record) = records.recv() {
+                    writer.write_all(&record)?;
+                    while let Ok(record) = records.try_recv() {
+                        writer.write_all(&record)?;
+                    }
+                    writer.flush()?;
+                }
+                Ok(())
+            };
+            if let Err(err) = write_records() {
+                debug!(target: "net::session", %err, "Failed to write session recording");
+            }
+        })?;
+
+        debug!(target: "net::session", path=%path.display(), "Recording sessions");
+        Ok(Self { to_writer, metrics: SessionRecorderMetrics::default() })
+    }
+
+    /// Records a message received from the peer.
+    pub(crate) fn record_received(&self, peer_id: PeerId, version: EthVersion, msg: &EthMessage) {
+        self.record(peer_id, MessageDirection::Received, version, |out| {
+            msg.message_id().encode(out);
+            msg.encode(out);
+        })
+    }
+
+    /// Records a message sent to the peer.
+    pub(crate) fn record_sent(&self, peer_id: PeerId, version: EthVersion, msg: &EthMessage) {
+        self.record(peer_id, MessageDirection::Sent, version, |out| {
+            msg.message_id().encode(out);
+            msg.encode(out);
+        })
+    }
+
+    /// Records a broadcast sent to the peer.
+    pub(crate) fn record_sent_broadcast(
+        &self,
+        peer_id: PeerId,
+        version: EthVersion,
+        msg: &EthBroadcastMessage,
+    ) {
+        self.record(peer_id, MessageDirection::Sent, version, |out| {
+            msg.message_id().encode(out);
+            msg.encode(out);
+        })
+    }
+
+    /// Encodes the record and sends it to the writer thread.
+    fn record(
+        &self,
+        peer_id: PeerId,
+        direction: MessageDirection,
+        version: EthVersion,
+        encode_message: impl FnOnce(&mut Vec<u8>),
+    ) {
+        let timestamp =
+            SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64;
+
+        let mut record = Vec::with_capacity(4 + RECORD_HEADER_LEN);
+        record.extend_from_slice(&[0; 4]);
+        record.extend_from_slice(&timestamp.to_be_bytes());
+        record.push(match direction {
+            MessageDirection::Received => 0,
+            MessageDirection::Sent => 1,
+        });
+        record.push(version as u8);
+        record.extend_from_slice(peer_id.as_slice());
+        encode_message(&mut record);
+
+        let len = (record.len() - 4) as u32;
+        record[..4].copy_from_slice(&len.to_be_bytes());
+
+        match self.to_writer.try_send(record) {
+            Ok(()) => {}
+            Err(mpsc::TrySendError::Full(_)) => {
+                trace!(target: "net::session", "Session recorder is lagging, dropping record");
+                self.metrics.dropped_records.increment(1);
+            }
+            Err(mpsc::TrySendError::Disconnected(_)) => {
+                trace!(target: "net::session", "Session recorder terminated, dropping record");
+            }
+        }
+    }
+}
+
+/// A message read from a session recording.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordedMessage {
+    /// When the message was sent or received.
+    pub timestamp: SystemTime,
+    /// The peer the message was exchanged with.
+    pub peer_id: PeerId,
+    /// Whether the message was sent or received.
+    pub direction: MessageDirection,
+    /// The negotiated `eth` version of the session.
+    pub version: EthVersion,
+    /// The message.
+    pub message: EthMessage,
+}
+
+/// Errors when reading a session recording.
+#[derive(Debug, thiserror::Error)]
+pub enum SessionRecordError {
+    /// Failed to read the recording.
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    /// The file is not a session recording.
+    #[error("not a session recording")]
+    InvalidMagic,
+    /// The recording was written with an unsupported format version.
+    #[error("unsupported recording version {0}")]
+    UnsupportedVersion(u8),
+    /// A record is malformed.
+    #[error("malformed record: {0}")]
+    InvalidRecord(&'static str),
+    /// Failed to decode a recorded message.
+    #[error("failed to decode recorded message: {0}")]
+    Decode(#[from] MessageError),
+}
+
+/// Reads the messages of a session recording, in the order they were recorded.
+#[derive(Debug)]
+pub struct SessionRecordReader<R = BufReader<File>> {
+    reader: R,
+}
+
+impl SessionRecordReader {
+    /// Opens the recording at the given path.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, SessionRecordError> {
+        Self::new(BufReader::new(File::open(path)?))
+    }
+}
+
+impl<R: Read> SessionRecordReader<R> {
+    /// Creates a new reader and checks the header of the recording.
+    pub fn new(mut reader: R) -> Result<Self, SessionRecordError> {
+        let mut header = [0u8; RECORDING_MAGIC.len() + 1];
+        reader.read_exact(&mut header)?;
+        if &header[..RECORDING_MAGIC.len()] != RECORDING_MAGIC {
+            return Err(SessionRecordError::InvalidMagic)
+        }
+        let version = header[RECORDING_MAGIC.len()];
+        if version != RECORDING_VERSION {
+            return Err(SessionRecordError::UnsupportedVersion(version))
+        }
+        Ok(Self { reader })
+    }
+
+    /// Reads the next message, returns `None` at the end of the recording.
+    ///
+    /// A record that was cut off, e.g. because the node was killed while writing it, is treated as
+    /// the end of the recording.
+    pub fn next_message(&mut self) -> Result<Option<RecordedMessage>, SessionRecordError> {
+        let mut len = [0u8; 4];
+        match self.reader.read_exact(&mut len) {
+            Ok(()) => {}
+            Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+            Err(err) => return Err(err.into()),
+        }
+        let len = u32::from_be_bytes(len) as usize;
+        if !(RECORD_HEADER_LEN..=MAX_RECORD_LEN).contains(&len) {
+            return Err(SessionRecordError::InvalidRecord("invalid length"))
+        }
+
+        let mut record = vec![0u8; len];
+        match self.reader.read_exact(&mut record) {
+            Ok(()) => {}
+            Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+            Err(err) => return Err(err.into()),
+        }
+
+        let timestamp = u64::from_be_bytes(record[..8].try_into().unwrap());
+        let direction = match record[8] {
+            0 => MessageDirection::Received,
+            1 => MessageDirection::Sent,
+            _ => return Err(SessionRecordError::InvalidRecord("invalid direction")),
+        };
+        let version = EthVersion::try_from(record[9])
+            .map_err(|_| SessionRecordError::InvalidRecord("invalid eth version"))?;
+        let peer_id = PeerId::from_slice(&record[10..RECORD_HEADER_LEN]);
+        let message =
+            ProtocolMessage::decode_message(version, &mut &record[RECORD_HEADER_LEN..])?.message;
+
+        Ok(Some(RecordedMessage {
+            timestamp: UNIX_EPOCH + Duration::from_millis(timestamp),
+            peer_id,
+            direction,
+            version,
+            message,
+        }))
+    }
+}
+
+impl<R: Read> Iterator for SessionRecordReader<R> {
+    type Item = Result<RecordedMessage, SessionRecordError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_message().transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::B256;
+    use reth_eth_wire::{GetBlockHeaders, HeadersDirection, RequestPair};
+
+    #[test]
+    fn record_and_read() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("sessions.rec");
+        let recorder = SessionRecorder::new(&path).unwrap();
+
+        let peer_id = PeerId::random();
+        let request = EthMessage::GetBlockHeaders(RequestPair {
+            request_id: 1,
+            message: GetBlockHeaders {
+                start_block: 1u64.into(),
+                limit: 10,
+                skip: 0,
+                direction: HeadersDirection::Rising,
+            },
+        });
+        let hashes = EthMessage::NewPooledTransactionHashes66(vec![B256::random()].into());
+        recorder.record_sent(peer_id, EthVersion::Eth68, &request);
+        recorder.record_received(peer_id, EthVersion::Eth68, &hashes);
+        drop(recorder);
+
+        // wait for the writer thread to flush the records
+        let mut messages = Vec::new();
+        for _ in 0..100 {
+            messages =
+                SessionRecordReader::open(&path).unwrap().collect::<Result<Vec<_>, _>>().unwrap();
+            if messages.len() == 2 {
+                break
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0].peer_id, peer_id);
+        assert_eq!(messages[0].direction, MessageDirection::Sent);
+        assert_eq!(messages[0].version, EthVersion::Eth68);
+        assert_eq!(messages[0].message, request);
+        assert_eq!(messages[1].direction, MessageDirection::Received);
+        assert_eq!(messages[1].message, hashes);
+    }
+
+    #[test]
+    fn reject_invalid_header() {
+        let err = SessionRecordReader::new(&b"notarecording"[..]).unwrap_err();
+        assert!(matches!(err, SessionRecordError::InvalidMagic));
+    }
+}
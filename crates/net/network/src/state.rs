@@ -10,11 +10,14 @@ use std::{
         Arc,
     },
     task::{Context, Poll},
+    time::{Duration, Instant},
 };
 
 use alloy_primitives::B256;
 use rand::seq::SliceRandom;
-use reth_eth_wire::{BlockHashNumber, Capabilities, DisconnectReason, NewBlockHashes, Status};
+use reth_eth_wire::{
+    BlockHashNumber, BlockRangeUpdate, Capabilities, DisconnectReason, NewBlockHashes, Status,
+};
 use reth_network_api::{DiscoveredEvent, DiscoveryEvent, PeerRequest, PeerRequestSender};
 use reth_network_peers::PeerId;
 use reth_network_types::{PeerAddr, PeerKind};
@@ -144,15 +147,22 @@ impl NetworkState {
         peer: PeerId,
         capabilities: Arc<Capabilities>,
         status: Arc<Status>,
+        block_range: Option<BlockRangeUpdate>,
         request_tx: PeerRequestSender,
         timeout: Arc<AtomicU64>,
     ) {
         debug_assert!(!self.active_peers.contains_key(&peer), "Already connected; not possible");
 
-        // find the corresponding block number
-        let block_number =
-            self.client.block_number(status.blockhash).ok().flatten().unwrap_or_default();
+        // find the corresponding block number, `eth/69` peers announce it directly
+        let block_number = match block_range {
+            Some(range) => range.latest,
+            None => self.client.block_number(status.blockhash).ok().flatten().unwrap_or_default(),
+        };
         self.state_fetcher.new_active_peer(peer, status.blockhash, block_number, timeout);
+        if let Some(range) = block_range {
+            self.state_fetcher.update_peer_block_range(&peer, range);
+        }
+        self.peers_manager.on_block_range_update(&peer, block_range.map(|r| r.earliest..=r.latest));
 
         self.active_peers.insert(
             peer,
@@ -161,11 +171,21 @@ impl NetworkState {
                 capabilities,
                 request_tx,
                 pending_response: None,
+                pending_response_since: None,
                 blocks: LruCache::new(PEER_BLOCK_CACHE_LIMIT),
             },
         );
     }
 
+    /// Invoked when an `eth/69` peer announced a new range of blocks it can serve.
+    pub(crate) fn on_block_range_update(&mut self, peer_id: PeerId, range: BlockRangeUpdate) {
+        if let Some(peer) = self.active_peers.get_mut(&peer_id) {
+            peer.best_hash = range.latest_hash;
+        }
+        self.state_fetcher.update_peer_block_range(&peer_id, range);
+        self.peers_manager.on_block_range_update(&peer_id, Some(range.earliest..=range.latest));
+    }
+
     /// Event hook for a disconnected session for the given peer.
     ///
     /// This will remove the peer from the available set of peers and close all inflight requests.
@@ -378,6 +398,7 @@ impl NetworkState {
             };
             let _ = peer.request_tx.to_session_tx.try_send(request);
             peer.pending_response = Some(response);
+            peer.pending_response_since = Some(Instant::now());
         }
     }
 
@@ -399,18 +420,27 @@ impl NetworkState {
     /// Delegates the response result to the fetcher which may return an outcome specific
     /// instruction that needs to be handled in [`Self::on_block_response_outcome`]. This could be
     /// a follow-up request or an instruction to slash the peer's reputation.
-    fn on_eth_response(&mut self, peer: PeerId, resp: PeerResponseResult) -> Option<StateAction> {
-        match resp {
+    ///
+    /// The latency and usefulness of the response are reported to the [`PeersManager`].
+    fn on_eth_response(
+        &mut self,
+        peer: PeerId,
+        resp: PeerResponseResult,
+        latency: Duration,
+    ) -> Option<StateAction> {
+        let (is_ok, outcome) = match resp {
             PeerResponseResult::BlockHeaders(res) => {
-                let outcome = self.state_fetcher.on_block_headers_response(peer, res)?;
-                self.on_block_response_outcome(outcome)
+                (res.is_ok(), self.state_fetcher.on_block_headers_response(peer, res))
             }
             PeerResponseResult::BlockBodies(res) => {
-                let outcome = self.state_fetcher.on_block_bodies_response(peer, res)?;
-                self.on_block_response_outcome(outcome)
+                (res.is_ok(), self.state_fetcher.on_block_bodies_response(peer, res))
             }
-            _ => None,
-        }
+            _ => return None,
+        };
+        let useful = is_ok && !matches!(outcome, Some(BlockResponseOutcome::BadResponse(..)));
+        self.peers_manager.on_response(peer, latency, useful);
+
+        self.on_block_response_outcome(outcome?)
     }
 
     /// Advances the state
@@ -455,7 +485,12 @@ impl NetworkState {
                                 // connection dropped error
                                 closed_sessions.push(*id);
                             } else {
-                                received_responses.push((*id, res));
+                                let latency = peer
+                                    .pending_response_since
+                                    .take()
+                                    .map(|since| since.elapsed())
+                                    .unwrap_or_default();
+                                received_responses.push((*id, res, latency));
                             }
                         }
                         Poll::Pending => {
@@ -470,8 +505,8 @@ impl NetworkState {
                 self.on_session_closed(peer)
             }
 
-            for (peer_id, resp) in received_responses {
-                if let Some(action) = self.on_eth_response(peer_id, resp) {
+            for (peer_id, resp, latency) in received_responses {
+                if let Some(action) = self.on_eth_response(peer_id, resp, latency) {
                     self.queued_messages.push_back(action);
                 }
             }
@@ -502,6 +537,8 @@ pub(crate) struct ActivePeer {
     pub(crate) request_tx: PeerRequestSender,
     /// The response receiver for a currently active request to that peer.
     pub(crate) pending_response: Option<PeerResponse>,
+    /// When the currently active request to that peer was sent.
+    pub(crate) pending_response_since: Option<Instant>,
     /// Blocks we know the peer has.
     pub(crate) blocks: LruCache<B256>,
 }
@@ -602,6 +639,7 @@ mod tests {
             peer_id,
             capabilities(),
             Arc::default(),
+            None,
             peer_tx,
             Arc::new(AtomicU64::new(1)),
         );
@@ -1,4 +1,9 @@
+use std::collections::{HashMap, HashSet};
+
 use derive_more::Constructor;
+use reth_network_peers::PeerId;
+use reth_network_types::PeerKind;
+use reth_transaction_pool::TransactionOrigin;
 
 use super::{
     DEFAULT_MAX_COUNT_TRANSACTIONS_SEEN_BY_PEER,
@@ -21,6 +26,9 @@ pub struct TransactionsManagerConfig {
     /// How new pending transactions are propagated.
     #[cfg_attr(feature = "serde", serde(default))]
     pub propagation_mode: TransactionPropagationMode,
+    /// Which peers transactions are shared with.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub gossip_policy: TransactionGossipPolicy,
 }
 
 impl Default for TransactionsManagerConfig {
@@ -29,6 +37,7 @@ impl Default for TransactionsManagerConfig {
             transaction_fetcher_config: TransactionFetcherConfig::default(),
             max_transactions_seen_by_peer_history: DEFAULT_MAX_COUNT_TRANSACTIONS_SEEN_BY_PEER,
             propagation_mode: TransactionPropagationMode::default(),
+            gossip_policy: TransactionGossipPolicy::default(),
         }
     }
 }
@@ -57,6 +66,90 @@ impl TransactionPropagationMode {
     }
 }
 
+/// Determines which peers transactions are shared with, based on the peer and the
+/// [`TransactionOrigin`] of the transaction.
+///
+/// This applies to broadcasts, announcements and responses to
+/// [`GetPooledTransactions`](reth_eth_wire::GetPooledTransactions) requests. By default,
+/// transactions are shared with all peers.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct TransactionGossipPolicy {
+    /// Only share transactions with trusted peers.
+    pub trusted_peers_only: bool,
+    /// Per-peer filters by transaction origin.
+    pub peer_rules: HashMap<PeerId, OriginFilter>,
+    /// If set, locally submitted transactions are only shared with these peers.
+    pub private_peers: Option<HashSet<PeerId>>,
+}
+
+impl TransactionGossipPolicy {
+    /// Only share transactions with trusted peers.
+    pub const fn with_trusted_peers_only(mut self, trusted_peers_only: bool) -> Self {
+        self.trusted_peers_only = trusted_peers_only;
+        self
+    }
+
+    /// Sets the origin filter for the given peer.
+    pub fn with_peer_rule(mut self, peer_id: PeerId, filter: OriginFilter) -> Self {
+        self.peer_rules.insert(peer_id, filter);
+        self
+    }
+
+    /// Only share locally submitted transactions with the given peers.
+    pub fn with_private_peers(mut self, peers: impl IntoIterator<Item = PeerId>) -> Self {
+        self.private_peers = Some(peers.into_iter().collect());
+        self
+    }
+
+    /// Returns `true` if transactions are shared with all peers, regardless of their origin.
+    pub fn is_permissive(&self) -> bool {
+        !self.trusted_peers_only && self.peer_rules.is_empty() && self.private_peers.is_none()
+    }
+
+    /// Returns `true` if a transaction of the given origin may be shared with the peer.
+    ///
+    /// Transactions with [`TransactionOrigin::Private`] are never shared.
+    pub fn allows(&self, peer_id: &PeerId, peer_kind: PeerKind, origin: TransactionOrigin) -> bool {
+        if origin.is_private() {
+            return false
+        }
+        if self.trusted_peers_only && !peer_kind.is_trusted() {
+            return false
+        }
+        if origin.is_local() {
+            if let Some(private_peers) = &self.private_peers {
+                if !private_peers.contains(peer_id) {
+                    return false
+                }
+            }
+        }
+        self.peer_rules.get(peer_id).map_or(true, |filter| filter.allows(origin))
+    }
+}
+
+/// Filters the transactions shared with a peer by their [`TransactionOrigin`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum OriginFilter {
+    /// Only share transactions of these origins.
+    Allow(Vec<TransactionOrigin>),
+    /// Share transactions of all origins except these.
+    Deny(Vec<TransactionOrigin>),
+}
+
+impl OriginFilter {
+    /// Returns `true` if transactions of the given origin pass the filter.
+    pub fn allows(&self, origin: TransactionOrigin) -> bool {
+        match self {
+            Self::Allow(origins) => origins.contains(&origin),
+            Self::Deny(origins) => !origins.contains(&origin),
+        }
+    }
+}
+
 /// Configuration for fetching transactions.
 #[derive(Debug, Constructor, Clone)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -12,7 +12,10 @@ pub use self::constants::{
     tx_fetcher::DEFAULT_SOFT_LIMIT_BYTE_SIZE_POOLED_TRANSACTIONS_RESP_ON_PACK_GET_POOLED_TRANSACTIONS_REQ,
     SOFT_LIMIT_BYTE_SIZE_POOLED_TRANSACTIONS_RESPONSE,
 };
-pub use config::{TransactionFetcherConfig, TransactionPropagationMode, TransactionsManagerConfig};
+pub use config::{
+    OriginFilter, TransactionFetcherConfig, TransactionGossipPolicy, TransactionPropagationMode,
+    TransactionsManagerConfig,
+};
 pub use validation::*;
 
 pub(crate) use fetcher::{FetchEvent, TransactionFetcher};
@@ -47,13 +50,13 @@ use reth_network_p2p::{
     sync::SyncStateProvider,
 };
 use reth_network_peers::PeerId;
-use reth_network_types::ReputationChangeKind;
+use reth_network_types::{PeerKind, ReputationChangeKind};
 use reth_primitives::{PooledTransactionsElement, TransactionSigned, TransactionSignedEcRecovered};
 use reth_tokio_util::EventStream;
 use reth_transaction_pool::{
     error::{PoolError, PoolResult},
     GetPooledTransactionLimit, PoolTransaction, PropagateKind, PropagatedTransactions,
-    TransactionPool, ValidPoolTransaction,
+    TransactionOrigin, TransactionPool, ValidPoolTransaction,
 };
 use tokio::sync::{mpsc, oneshot, oneshot::error::RecvError};
 use tokio_stream::wrappers::{ReceiverStream, UnboundedReceiverStream};
@@ -366,8 +369,20 @@ where
                 let _ = response.send(Ok(PooledTransactions::default()));
                 return
             }
+            let mut hashes = request.0;
+            let policy = &self.config.gossip_policy;
+            if !policy.is_permissive() {
+                // only serve transactions the peer is allowed to receive
+                hashes = self
+                    .pool
+                    .get_all(hashes)
+                    .into_iter()
+                    .filter(|tx| policy.allows(&peer_id, peer.peer_kind, tx.origin))
+                    .map(|tx| *tx.hash())
+                    .collect();
+            }
             let transactions = self.pool.get_pooled_transaction_elements(
-                request.0,
+                hashes,
                 GetPooledTransactionLimit::ResponseSizeSoftLimit(
                     self.transaction_fetcher.info.soft_limit_byte_size_pooled_transactions_response,
                 ),
@@ -454,7 +469,10 @@ where
             // peer.
             for tx in &to_propagate {
                 // Only proceed if the transaction is not in the peer's list of seen transactions
-                if !peer.seen_transactions.contains(&tx.hash()) {
+                // and the peer is allowed to receive it
+                if !peer.seen_transactions.contains(&tx.hash()) &&
+                    self.config.gossip_policy.allows(peer_id, peer.peer_kind, tx.origin)
+                {
                     // add transaction to the list of hashes to propagate
                     builder.push(tx);
                 }
@@ -527,7 +545,9 @@ where
 
         // Iterate through the transactions to propagate and fill the hashes and full transaction
         for tx in to_propagate {
-            if !peer.seen_transactions.contains(&tx.hash()) {
+            if !peer.seen_transactions.contains(&tx.hash()) &&
+                self.config.gossip_policy.allows(&peer_id, peer.peer_kind, tx.origin)
+            {
                 full_transactions.push(&tx);
             }
         }
@@ -590,6 +610,9 @@ where
             let mut hashes = PooledTransactionsHashesBuilder::new(peer.version);
 
             for tx in to_propagate {
+                if !self.config.gossip_policy.allows(&peer_id, peer.peer_kind, tx.origin) {
+                    continue
+                }
                 if !peer.seen_transactions.insert(tx.hash()) {
                     hashes.push(&tx);
                 }
@@ -920,13 +943,19 @@ where
                 self.transaction_fetcher.remove_peer(&peer_id);
             }
             NetworkEvent::SessionEstablished {
-                peer_id, client_version, messages, version, ..
+                peer_id,
+                client_version,
+                messages,
+                version,
+                peer_kind,
+                ..
             } => {
                 // Insert a new peer into the peerset.
                 let peer = PeerMetadata::new(
                     messages,
                     version,
                     client_version,
+                    peer_kind,
                     self.config.max_transactions_seen_by_peer_history,
                 );
                 let peer = match self.peers.entry(peer_id) {
@@ -954,6 +983,9 @@ where
 
                 let mut msg_builder = PooledTransactionsHashesBuilder::new(version);
                 for pooled_tx in pooled_txs {
+                    if !self.config.gossip_policy.allows(&peer_id, peer_kind, pooled_tx.origin) {
+                        continue
+                    }
                     peer.seen_transactions.insert(*pooled_tx.hash());
                     msg_builder.push_pooled(pooled_tx);
                 }
@@ -1400,6 +1432,7 @@ where
 struct PropagateTransaction {
     size: usize,
     transaction: Arc<TransactionSigned>,
+    origin: TransactionOrigin,
 }
 
 // === impl PropagateTransaction ===
@@ -1418,7 +1451,7 @@ impl PropagateTransaction {
         let recovered: TransactionSignedEcRecovered =
             tx.transaction.clone().into_consensus().into();
         let transaction = Arc::new(recovered.into_signed());
-        Self { size, transaction }
+        Self { size, transaction, origin: tx.origin }
     }
 }
 
@@ -1596,7 +1629,7 @@ impl PooledTransactionsHashesBuilder {
     fn new(version: EthVersion) -> Self {
         match version {
             EthVersion::Eth66 | EthVersion::Eth67 => Self::Eth66(Default::default()),
-            EthVersion::Eth68 => Self::Eth68(Default::default()),
+            EthVersion::Eth68 | EthVersion::Eth69 => Self::Eth68(Default::default()),
         }
     }
 
@@ -1638,6 +1671,8 @@ pub struct PeerMetadata {
     version: EthVersion,
     /// The peer's client version.
     client_version: Arc<str>,
+    /// The kind of the peer, used to apply the [`TransactionGossipPolicy`].
+    peer_kind: PeerKind,
 }
 
 impl PeerMetadata {
@@ -1646,6 +1681,7 @@ impl PeerMetadata {
         request_tx: PeerRequestSender,
         version: EthVersion,
         client_version: Arc<str>,
+        peer_kind: PeerKind,
         max_transactions_seen_by_peer: u32,
     ) -> Self {
         Self {
@@ -1653,6 +1689,7 @@ impl PeerMetadata {
             request_tx,
             version,
             client_version,
+            peer_kind,
         }
     }
 }
@@ -1818,6 +1855,7 @@ mod tests {
                 PeerRequestSender::new(peer_id, to_mock_session_tx),
                 version,
                 Arc::from(""),
+                PeerKind::Basic,
                 DEFAULT_MAX_COUNT_TRANSACTIONS_SEEN_BY_PEER,
             ),
             to_mock_session_rx,
@@ -1873,6 +1911,7 @@ mod tests {
                     messages,
                     status,
                     version,
+                    peer_kind,
                 } => {
                     // to insert a new peer in transactions peerset
                     transactions.on_network_event(NetworkEvent::SessionEstablished {
@@ -1883,6 +1922,7 @@ mod tests {
                         messages,
                         status,
                         version,
+                        peer_kind,
                     })
                 }
                 NetworkEvent::PeerAdded(_peer_id) => continue,
@@ -1959,6 +1999,7 @@ mod tests {
                     messages,
                     status,
                     version,
+                    peer_kind,
                 } => {
                     // to insert a new peer in transactions peerset
                     transactions.on_network_event(NetworkEvent::SessionEstablished {
@@ -1969,6 +2010,7 @@ mod tests {
                         messages,
                         status,
                         version,
+                        peer_kind,
                     })
                 }
                 NetworkEvent::PeerAdded(_peer_id) => continue,
@@ -2043,6 +2085,7 @@ mod tests {
                     messages,
                     status,
                     version,
+                    peer_kind,
                 } => {
                     // to insert a new peer in transactions peerset
                     transactions.on_network_event(NetworkEvent::SessionEstablished {
@@ -2053,6 +2096,7 @@ mod tests {
                         messages,
                         status,
                         version,
+                        peer_kind,
                     })
                 }
                 NetworkEvent::PeerAdded(_peer_id) => continue,
@@ -2134,6 +2178,7 @@ mod tests {
                     messages,
                     status,
                     version,
+                    peer_kind,
                 } => transactions.on_network_event(NetworkEvent::SessionEstablished {
                     peer_id,
                     remote_addr,
@@ -2142,6 +2187,7 @@ mod tests {
                     messages,
                     status,
                     version,
+                    peer_kind,
                 }),
                 NetworkEvent::PeerAdded(_peer_id) => continue,
                 ev => {
@@ -2379,6 +2425,7 @@ mod tests {
             messages: PeerRequestSender::new(peer_id, tx),
             status: Arc::new(Default::default()),
             version: EthVersion::Eth68,
+            peer_kind: Default::default(),
         });
 
         let mut propagate = vec![];
@@ -2407,4 +2454,66 @@ mod tests {
         let propagated = tx_manager.propagate_transactions(propagate);
         assert!(propagated.0.is_empty());
     }
+
+    #[tokio::test]
+    async fn test_propagate_gossip_policy() {
+        reth_tracing::init_test_tracing();
+
+        let (mut tx_manager, network) = new_tx_manager().await;
+        network.handle().update_sync_state(SyncState::Idle);
+
+        let private_peer = PeerId::random();
+        let trusted_peer = PeerId::random();
+        let basic_peer = PeerId::random();
+        tx_manager.config.gossip_policy = TransactionGossipPolicy::default()
+            .with_private_peers([private_peer])
+            .with_peer_rule(trusted_peer, OriginFilter::Deny(vec![TransactionOrigin::External]));
+
+        for (peer_id, peer_kind) in [
+            (private_peer, PeerKind::Basic),
+            (trusted_peer, PeerKind::Trusted),
+            (basic_peer, PeerKind::Basic),
+        ] {
+            let (tx, _rx) = mpsc::channel(1);
+            tx_manager.on_network_event(NetworkEvent::SessionEstablished {
+                peer_id,
+                remote_addr: SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 0),
+                client_version: Arc::from(""),
+                capabilities: Arc::new(vec![].into()),
+                messages: PeerRequestSender::new(peer_id, tx),
+                status: Arc::new(Default::default()),
+                version: EthVersion::Eth68,
+                peer_kind,
+            });
+        }
+
+        let mut factory = MockTransactionFactory::default();
+        let local_tx = Arc::new(
+            factory.validated_with_origin(TransactionOrigin::Local, MockTransaction::eip1559()),
+        );
+        let external_tx = Arc::new(factory.create_eip1559());
+        let propagated = tx_manager.propagate_transactions(vec![
+            PropagateTransaction::new(local_tx.clone()),
+            PropagateTransaction::new(external_tx.clone()),
+        ]);
+
+        let peers = |hash| {
+            propagated.0.get(hash).unwrap().iter().map(|kind| *kind.peer()).collect::<HashSet<_>>()
+        };
+        assert_eq!(peers(local_tx.transaction.hash()), HashSet::from([private_peer]));
+        assert_eq!(
+            peers(external_tx.transaction.hash()),
+            HashSet::from([private_peer, basic_peer])
+        );
+
+        // only trusted peers
+        tx_manager.config.gossip_policy =
+            TransactionGossipPolicy::default().with_trusted_peers_only(true);
+        let tx = Arc::new(factory.create_eip1559());
+        let propagated =
+            tx_manager.propagate_transactions(vec![PropagateTransaction::new(tx.clone())]);
+        let prop_txs = propagated.0.get(tx.transaction.hash()).unwrap();
+        assert_eq!(prop_txs.len(), 1);
+        assert_eq!(*prop_txs[0].peer(), trusted_peer);
+    }
 }
@@ -325,3 +325,97 @@ async fn test_proto_multiplex() {
     let response = rx.await.unwrap();
     assert_eq!(response, "hello from peer1!");
 }
+
+/// A typed sub-protocol, declared via [`reth_network::rlpx_sub_protocol_messages`].
+mod typed_proto {
+    use alloy_rlp::{RlpDecodable, RlpEncodable};
+
+    #[derive(Clone, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable)]
+    pub struct Announce {
+        pub number: u64,
+    }
+
+    #[derive(Clone, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable)]
+    pub struct Echo {
+        pub msg: String,
+    }
+
+    #[derive(Clone, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable)]
+    pub struct EchoReply {
+        pub msg: String,
+    }
+
+    reth_network::rlpx_sub_protocol_messages! {
+        /// Messages of the typed echo protocol.
+        #[derive(Clone, Debug, PartialEq, Eq)]
+        pub enum EchoMessage {
+            notifications {
+                Announce(Announce),
+            }
+            requests {
+                Echo(Echo) => EchoReply(EchoReply),
+            }
+        }
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_typed_proto_request_response() {
+    use reth_eth_wire::Capability;
+    use reth_network::protocol::{
+        SubProtocolMessage, TypedPeerHandle, TypedProtocolConfig, TypedProtocolEvent,
+        TypedProtocolHandler,
+    };
+    use typed_proto::*;
+
+    reth_tracing::init_test_tracing();
+    let provider = MockEthProvider::default();
+    let mut net = Testnet::create_with(2, provider.clone()).await;
+
+    let capability = Capability::new_static("echo", 1);
+    let (handler, mut from_peer0) = TypedProtocolHandler::<EchoMessage>::new(
+        capability.clone(),
+        TypedProtocolConfig::default(),
+    );
+    assert_eq!(handler.protocol().messages(), EchoMessage::MESSAGE_COUNT);
+    net.peers_mut()[0].add_rlpx_sub_protocol(handler);
+    let (handler, mut from_peer1) =
+        TypedProtocolHandler::<EchoMessage>::new(capability, TypedProtocolConfig::default());
+    net.peers_mut()[1].add_rlpx_sub_protocol(handler);
+
+    let handle = net.spawn();
+    handle.connect_peers().await;
+
+    let peer0_conn: TypedPeerHandle<EchoMessage> = match from_peer0.recv().await.unwrap() {
+        TypedProtocolEvent::Established { peer_id, peer, .. } => {
+            assert_eq!(peer_id, *handle.peers()[1].peer_id());
+            peer
+        }
+        ev => panic!("unexpected event: {ev:?}"),
+    };
+    assert!(matches!(from_peer1.recv().await.unwrap(), TypedProtocolEvent::Established { .. }));
+
+    // peer1 answers echo requests
+    tokio::spawn(async move {
+        while let Some(event) = from_peer1.recv().await {
+            match event {
+                TypedProtocolEvent::Request {
+                    request: EchoMessage::Echo(req), responder, ..
+                } => {
+                    responder.respond(EchoReply { msg: req.message.msg }).unwrap();
+                }
+                TypedProtocolEvent::Notification { message, .. } => {
+                    assert_eq!(message, EchoMessage::Announce(Announce { number: 1 }));
+                }
+                _ => {}
+            }
+        }
+    });
+
+    peer0_conn.send(Announce { number: 1 }).unwrap();
+    let reply = peer0_conn.request(Echo { msg: "hello!".to_string() }).await.unwrap();
+    assert_eq!(reply, EchoReply { msg: "hello!".to_string() });
+
+    let reply = peer0_conn.request(Echo { msg: "again".to_string() }).await.unwrap();
+    assert_eq!(reply.msg, "again");
+}
@@ -1,12 +1,22 @@
 //! Session tests
 
+use std::{
+    net::{Ipv4Addr, SocketAddr},
+    time::{Duration, SystemTime},
+};
+
+use alloy_primitives::B256;
 use futures::StreamExt;
-use reth_eth_wire::EthVersion;
+use reth_chainspec::MAINNET;
+use reth_eth_wire::{EthMessage, EthVersion, GetBlockHeaders, HeadersDirection, RequestPair};
 use reth_network::{
+    replay::{replay_session, ReplayConfig, ReplayTarget},
     test_utils::{PeerConfig, Testnet},
-    NetworkEvent, NetworkEventListenerProvider,
+    MessageDirection, NetworkConfigBuilder, NetworkEvent, NetworkEventListenerProvider,
+    RecordedMessage, SessionRecordReader,
 };
 use reth_network_api::{NetworkInfo, Peers};
+use reth_network_peers::PeerId;
 use reth_provider::test_utils::NoopProvider;
 
 #[tokio::test(flavor = "multi_thread")]
@@ -81,3 +91,79 @@ async fn test_session_established_with_different_capability() {
 
     handle.terminate().await;
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_record_and_replay_session() {
+    reth_tracing::init_test_tracing();
+
+    let dir = tempfile::tempdir().unwrap();
+    let recording = dir.path().join("sessions.rec");
+
+    let config = NetworkConfigBuilder::with_rng_secret_key()
+        .listener_addr(SocketAddr::from((Ipv4Addr::LOCALHOST, 0)))
+        .disable_discovery()
+        .record_sessions(&recording)
+        .build_with_noop_provider(MAINNET.clone());
+    let target_status = config.status;
+    let target_block_range = config.block_range;
+    let fork_filter = config.fork_filter.clone();
+    let network = config.manager().await.unwrap();
+    let handle = network.handle().clone();
+    tokio::task::spawn(network);
+
+    let messages = vec![
+        EthMessage::GetBlockHeaders(RequestPair {
+            request_id: 0,
+            message: GetBlockHeaders {
+                start_block: 1u64.into(),
+                limit: 1,
+                skip: 0,
+                direction: HeadersDirection::Rising,
+            },
+        }),
+        EthMessage::NewPooledTransactionHashes66(vec![B256::random()].into()),
+    ];
+    let recorded = messages.iter().cloned().map(|message| RecordedMessage {
+        timestamp: SystemTime::now(),
+        peer_id: PeerId::random(),
+        direction: MessageDirection::Received,
+        version: EthVersion::Eth68,
+        message,
+    });
+
+    let target = ReplayTarget {
+        peer_id: *handle.peer_id(),
+        addr: handle.local_addr(),
+        status: target_status,
+        block_range: target_block_range,
+        fork_filter,
+    };
+    let config = ReplayConfig { paced: false, linger: Duration::from_millis(500) };
+    let outcome = replay_session(target, recorded, config).await.unwrap();
+    assert_eq!(outcome.sent, 2);
+    assert!(outcome.error.is_none());
+
+    // the node recorded the handshake and the replayed messages
+    let mut received = Vec::new();
+    for _ in 0..50 {
+        received = SessionRecordReader::open(&recording)
+            .unwrap()
+            .map(Result::unwrap)
+            .filter(|msg| msg.direction.is_received())
+            .map(|msg| msg.message)
+            .collect();
+        if received.len() == messages.len() + 1 {
+            break
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    assert!(matches!(received[0], EthMessage::Status(_)));
+    assert_eq!(received[1..], messages);
+
+    let sent_status = SessionRecordReader::open(&recording)
+        .unwrap()
+        .map(Result::unwrap)
+        .find(|msg| !msg.direction.is_received())
+        .unwrap();
+    assert!(matches!(sent_status.message, EthMessage::Status(_)));
+}
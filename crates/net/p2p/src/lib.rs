@@ -38,6 +38,9 @@ pub mod error;
 /// Priority enum for `BlockHeader` and `BlockBody` requests
 pub mod priority;
 
+/// Traits for implementing `snap` protocol clients.
+pub mod snap;
+
 /// Syncing related traits.
 pub mod sync;
 
@@ -47,6 +50,7 @@ pub mod test_utils;
 
 pub use bodies::client::BodiesClient;
 pub use headers::client::HeadersClient;
+pub use snap::client::SnapClient;
 
 /// Helper trait that unifies network behaviour needed for fetching blocks.
 pub trait BlockClient: HeadersClient + BodiesClient + Unpin + Clone {}
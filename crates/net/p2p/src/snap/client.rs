@@ -0,0 +1,22 @@
+use crate::{download::DownloadClient, error::PeerRequestResult};
+use futures::Future;
+use reth_eth_wire_types::snap::SnapMessage;
+use std::pin::Pin;
+
+/// The snap future type
+pub type SnapFut = Pin<Box<dyn Future<Output = PeerRequestResult<SnapMessage>> + Send + Sync>>;
+
+/// A client capable of downloading state with the `snap/1` protocol.
+#[auto_impl::auto_impl(&, Arc, Box)]
+pub trait SnapClient: DownloadClient {
+    /// The output of the request future for querying state.
+    type Output: Future<Output = PeerRequestResult<SnapMessage>> + Sync + Send + Unpin;
+
+    /// Sends the request to a peer that supports `snap/1` and resolves with its response.
+    ///
+    /// The request id of the message is replaced with a unique id of the connection.
+    fn get_snap_data(&self, request: SnapMessage) -> Self::Output;
+
+    /// Returns how many connected peers support `snap/1`.
+    fn num_snap_peers(&self) -> usize;
+}
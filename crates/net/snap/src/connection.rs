@@ -0,0 +1,271 @@
+//! A `snap/1` connection to a single peer.
+
+use crate::{peers::SnapPeers, server::SnapRequestHandler};
+use alloy_primitives::bytes::BytesMut;
+use futures::{stream::FuturesUnordered, Stream, StreamExt};
+use reth_eth_wire::multiplex::ProtocolConnection;
+use reth_eth_wire_types::snap::{SnapMessage, SnapMessageId};
+use reth_network_p2p::error::RequestError;
+use reth_network_peers::PeerId;
+use reth_provider::{
+    DatabaseProviderFactory, HashedStateRangeReader, HeaderProvider, StageCheckpointReader,
+};
+use std::{
+    collections::HashMap,
+    pin::Pin,
+    task::{Context, Poll},
+    time::{Duration, Instant},
+};
+use tokio::{sync::oneshot, task::JoinHandle};
+use tokio_stream::wrappers::UnboundedReceiverStream;
+use tracing::trace;
+
+/// The maximum number of requests of a peer that are served concurrently.
+///
+/// A peer that sends more requests before the previous ones are answered is disconnected.
+const MAX_CONCURRENT_REQUESTS_PER_PEER: usize = 8;
+
+/// The maximum number of requests a peer can send per [`REQUEST_RATE_INTERVAL`].
+///
+/// A peer that sends more requests is disconnected.
+const MAX_REQUESTS_PER_INTERVAL: usize = 64;
+
+/// The interval of [`MAX_REQUESTS_PER_INTERVAL`].
+const REQUEST_RATE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// A request to send to the peer.
+#[derive(Debug)]
+pub(crate) struct SnapCommand {
+    /// The request, the request id is replaced by the connection.
+    pub(crate) request: SnapMessage,
+    /// The response will be sent to this channel.
+    pub(crate) response: oneshot::Sender<Result<SnapMessage, RequestError>>,
+}
+
+/// A request that was sent to the peer and awaits its response.
+#[derive(Debug)]
+struct InflightRequest {
+    /// The id of the expected response.
+    response_id: SnapMessageId,
+    /// The channel to send the response to.
+    response: oneshot::Sender<Result<SnapMessage, RequestError>>,
+}
+
+/// The connection to a peer that supports `snap/1`.
+///
+/// Sends the requests of [`SnapPeers`] to the peer and answers its requests, if serving is
+/// enabled.
+#[derive(Debug)]
+pub struct SnapConnection<F> {
+    conn: ProtocolConnection,
+    peer_id: PeerId,
+    connection_id: u64,
+    peers: SnapPeers,
+    commands: UnboundedReceiverStream<SnapCommand>,
+    inflight: HashMap<u64, InflightRequest>,
+    next_request_id: u64,
+    server: Option<SnapRequestHandler<F>>,
+    serving: FuturesUnordered<JoinHandle<Option<SnapMessage>>>,
+    rate_limit: RequestRateLimit,
+}
+
+impl<F> SnapConnection<F> {
+    /// Creates a new connection and registers it with the peer set.
+    pub(crate) fn new(
+        conn: ProtocolConnection,
+        peer_id: PeerId,
+        peers: SnapPeers,
+        server: Option<SnapRequestHandler<F>>,
+    ) -> Self {
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        let connection_id = peers.insert(peer_id, tx);
+        Self {
+            conn,
+            peer_id,
+            connection_id,
+            peers,
+            commands: UnboundedReceiverStream::new(rx),
+            inflight: HashMap::new(),
+            next_request_id: 0,
+            server,
+            serving: FuturesUnordered::new(),
+            rate_limit: RequestRateLimit::new(Instant::now()),
+        }
+    }
+
+    /// Handles a message received from the peer.
+    ///
+    /// Returns an error if the peer violated the protocol or exceeded the request limits.
+    fn on_message(&mut self, message: SnapMessage) -> Result<(), RequestError>
+    where
+        F: DatabaseProviderFactory<
+                Provider: StageCheckpointReader + HeaderProvider + HashedStateRangeReader,
+            > + Clone
+            + Send
+            + 'static,
+    {
+        if message.is_request() {
+            if self.serving.len() >= MAX_CONCURRENT_REQUESTS_PER_PEER ||
+                !self.rate_limit.try_acquire(Instant::now())
+            {
+                trace!(target: "net::snap", peer_id=?self.peer_id, "Peer exceeded the request limits");
+                return Err(RequestError::BadResponse)
+            }
+
+            // requests are answered with an empty response if serving is disabled or all
+            // connections together already serve the maximum number of requests
+            let Some((server, permit)) = self
+                .server
+                .as_ref()
+                .and_then(|server| Some((server.clone(), server.try_acquire()?)))
+            else {
+                let response = empty_response(&message);
+                self.serving.push(tokio::task::spawn(async move { response }));
+                return Ok(())
+            };
+            self.serving.push(tokio::task::spawn_blocking(move || {
+                let _permit = permit;
+                server.on_request(message)
+            }));
+            return Ok(())
+        }
+
+        let Some(inflight) = self.inflight.remove(&message.request_id()) else {
+            trace!(target: "net::snap", peer_id=?self.peer_id, "Received unsolicited response");
+            return Err(RequestError::BadResponse)
+        };
+        if message.message_id() != inflight.response_id {
+            let _ = inflight.response.send(Err(RequestError::BadResponse));
+            return Err(RequestError::BadResponse)
+        }
+        let _ = inflight.response.send(Ok(message));
+        Ok(())
+    }
+}
+
+impl<F> Stream for SnapConnection<F>
+where
+    F: DatabaseProviderFactory<
+            Provider: StageCheckpointReader + HeaderProvider + HashedStateRangeReader,
+        > + Clone
+        + Send
+        + Unpin
+        + 'static,
+{
+    type Item = BytesMut;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        loop {
+            // answer requests of the peer
+            if let Poll::Ready(Some(response)) = this.serving.poll_next_unpin(cx) {
+                if let Ok(Some(response)) = response {
+                    return Poll::Ready(Some(encoded(&response)))
+                }
+                continue
+            }
+
+            // send our requests
+            match this.commands.poll_next_unpin(cx) {
+                Poll::Ready(Some(SnapCommand { mut request, response })) => {
+                    let Some(response_id) = request.response_id() else {
+                        let _ = response.send(Err(RequestError::BadResponse));
+                        continue
+                    };
+                    // requests that were abandoned will never be answered
+                    this.inflight.retain(|_, inflight| !inflight.response.is_closed());
+
+                    let request_id = this.next_request_id;
+                    this.next_request_id += 1;
+                    request.set_request_id(request_id);
+                    this.inflight.insert(request_id, InflightRequest { response_id, response });
+                    return Poll::Ready(Some(encoded(&request)))
+                }
+                // the peer was removed from the peer set
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => {}
+            }
+
+            let Poll::Ready(message) = this.conn.poll_next_unpin(cx) else { return Poll::Pending };
+            let Some(message) = message else { return Poll::Ready(None) };
+            let Ok(message) = SnapMessage::decode_message(&mut &message[..]) else {
+                trace!(target: "net::snap", peer_id=?this.peer_id, "Failed to decode message");
+                return Poll::Ready(None)
+            };
+            if this.on_message(message).is_err() {
+                return Poll::Ready(None)
+            }
+        }
+    }
+}
+
+impl<F> Drop for SnapConnection<F> {
+    fn drop(&mut self) {
+        self.peers.remove(&self.peer_id, self.connection_id);
+        for (_, inflight) in self.inflight.drain() {
+            let _ = inflight.response.send(Err(RequestError::ConnectionDropped));
+        }
+    }
+}
+
+/// Limits the number of requests of a peer per [`REQUEST_RATE_INTERVAL`].
+#[derive(Debug)]
+struct RequestRateLimit {
+    /// The start of the current interval.
+    interval_start: Instant,
+    /// The number of requests in the current interval.
+    requests: usize,
+}
+
+impl RequestRateLimit {
+    const fn new(now: Instant) -> Self {
+        Self { interval_start: now, requests: 0 }
+    }
+
+    /// Counts a request and returns `false` if the peer exceeded [`MAX_REQUESTS_PER_INTERVAL`].
+    fn try_acquire(&mut self, now: Instant) -> bool {
+        if now.saturating_duration_since(self.interval_start) >= REQUEST_RATE_INTERVAL {
+            self.interval_start = now;
+            self.requests = 0;
+        }
+        self.requests += 1;
+        self.requests <= MAX_REQUESTS_PER_INTERVAL
+    }
+}
+
+/// Returns the empty response to the given request.
+fn empty_response(request: &SnapMessage) -> Option<SnapMessage> {
+    let mut response = match request.response_id()? {
+        SnapMessageId::AccountRange => SnapMessage::AccountRange(Default::default()),
+        SnapMessageId::StorageRanges => SnapMessage::StorageRanges(Default::default()),
+        SnapMessageId::ByteCodes => SnapMessage::ByteCodes(Default::default()),
+        _ => SnapMessage::TrieNodes(Default::default()),
+    };
+    response.set_request_id(request.request_id());
+    Some(response)
+}
+
+fn encoded(message: &SnapMessage) -> BytesMut {
+    let mut buf = BytesMut::new();
+    message.encode_message(&mut buf);
+    buf
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn request_rate_limit() {
+        let start = Instant::now();
+        let mut limit = RequestRateLimit::new(start);
+        for _ in 0..MAX_REQUESTS_PER_INTERVAL {
+            assert!(limit.try_acquire(start));
+        }
+        assert!(!limit.try_acquire(start + REQUEST_RATE_INTERVAL / 2));
+
+        // the limit is reset in the next interval
+        assert!(limit.try_acquire(start + REQUEST_RATE_INTERVAL));
+    }
+}
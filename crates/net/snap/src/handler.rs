@@ -0,0 +1,110 @@
+//! The `snap/1` protocol handler that is installed into the network.
+
+use crate::{connection::SnapConnection, peers::SnapPeers, server::SnapRequestHandler};
+use reth_eth_wire::{
+    capability::SharedCapabilities, multiplex::ProtocolConnection, protocol::Protocol, Capability,
+};
+use reth_eth_wire_types::snap::{SNAP_MESSAGE_COUNT, SNAP_VERSION};
+use reth_network::protocol::{ConnectionHandler, OnNotSupported, ProtocolHandler};
+use reth_network_api::Direction;
+use reth_network_peers::PeerId;
+use reth_provider::{
+    DatabaseProviderFactory, HashedStateRangeReader, HeaderProvider, StageCheckpointReader,
+};
+use std::net::SocketAddr;
+
+/// Returns the `snap/1` protocol.
+pub fn snap_protocol() -> Protocol {
+    Protocol::new(Capability::new_static("snap", SNAP_VERSION as usize), SNAP_MESSAGE_COUNT)
+}
+
+/// The [`ProtocolHandler`] of `snap/1`.
+///
+/// Every connection with a peer that supports `snap/1` is registered with [`SnapPeers`], which can
+/// be used to request state from the peers. Requests of the peers are answered by the
+/// [`SnapRequestHandler`], if serving is enabled, and with empty responses otherwise.
+#[derive(Debug, Clone)]
+pub struct SnapProtocolHandler<F> {
+    server: Option<SnapRequestHandler<F>>,
+    peers: SnapPeers,
+}
+
+impl<F> SnapProtocolHandler<F> {
+    /// Creates a new protocol handler that serves state with the given request handler, if any.
+    pub fn new(server: Option<SnapRequestHandler<F>>) -> Self {
+        Self { server, peers: SnapPeers::default() }
+    }
+
+    /// Returns the set of connected peers that support `snap/1`.
+    pub fn peers(&self) -> SnapPeers {
+        self.peers.clone()
+    }
+}
+
+impl<F> ProtocolHandler for SnapProtocolHandler<F>
+where
+    F: DatabaseProviderFactory<
+            Provider: StageCheckpointReader + HeaderProvider + HashedStateRangeReader,
+        > + Clone
+        + std::fmt::Debug
+        + Send
+        + Sync
+        + Unpin
+        + 'static,
+{
+    type ConnectionHandler = SnapConnectionHandler<F>;
+
+    fn on_incoming(&self, _socket_addr: SocketAddr) -> Option<Self::ConnectionHandler> {
+        Some(SnapConnectionHandler { server: self.server.clone(), peers: self.peers.clone() })
+    }
+
+    fn on_outgoing(
+        &self,
+        _socket_addr: SocketAddr,
+        _peer_id: PeerId,
+    ) -> Option<Self::ConnectionHandler> {
+        Some(SnapConnectionHandler { server: self.server.clone(), peers: self.peers.clone() })
+    }
+}
+
+/// The [`ConnectionHandler`] of `snap/1`.
+#[derive(Debug)]
+pub struct SnapConnectionHandler<F> {
+    server: Option<SnapRequestHandler<F>>,
+    peers: SnapPeers,
+}
+
+impl<F> ConnectionHandler for SnapConnectionHandler<F>
+where
+    F: DatabaseProviderFactory<
+            Provider: StageCheckpointReader + HeaderProvider + HashedStateRangeReader,
+        > + Clone
+        + Send
+        + Sync
+        + Unpin
+        + 'static,
+{
+    type Connection = SnapConnection<F>;
+
+    fn protocol(&self) -> Protocol {
+        snap_protocol()
+    }
+
+    fn on_unsupported_by_peer(
+        self,
+        _supported: &SharedCapabilities,
+        _direction: Direction,
+        _peer_id: PeerId,
+    ) -> OnNotSupported {
+        OnNotSupported::KeepAlive
+    }
+
+    fn into_connection(
+        self,
+        _direction: Direction,
+        peer_id: PeerId,
+        conn: ProtocolConnection,
+    ) -> Self::Connection {
+        SnapConnection::new(conn, peer_id, self.peers, self.server)
+    }
+}
@@ -0,0 +1,25 @@
+//! Implementation of the [`snap/1`](https://github.com/ethereum/devp2p/blob/master/caps/snap.md)
+//! protocol.
+//!
+//! The protocol is installed into the network as an additional `RLPx` sub-protocol with the
+//! [`SnapProtocolHandler`]. It answers the requests of peers from the hashed state and the trie
+//! tables with the [`SnapRequestHandler`] and exposes the connected peers that support `snap/1`
+//! as a [`SnapClient`](reth_network_p2p::snap::client::SnapClient) with [`SnapPeers`].
+
+#![doc(
+    html_logo_url = "https://raw.githubusercontent.com/paradigmxyz/reth/main/assets/reth-docs.png",
+    html_favicon_url = "https://avatars0.githubusercontent.com/u/97369466?s=256",
+    issue_tracker_base_url = "https://github.com/paradigmxyz/reth/issues/"
+)]
+#![cfg_attr(not(test), warn(unused_crate_dependencies))]
+#![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
+
+mod connection;
+mod handler;
+mod peers;
+pub mod server;
+
+pub use connection::SnapConnection;
+pub use handler::{snap_protocol, SnapConnectionHandler, SnapProtocolHandler};
+pub use peers::{SnapPeers, SnapResponseFuture};
+pub use server::SnapRequestHandler;
@@ -0,0 +1,141 @@
+//! The set of connected peers that support `snap/1`.
+
+use crate::connection::SnapCommand;
+use futures::{Future, FutureExt};
+use parking_lot::RwLock;
+use reth_eth_wire_types::snap::SnapMessage;
+use reth_network_p2p::{
+    download::DownloadClient,
+    error::{PeerRequestResult, RequestError},
+    snap::client::SnapClient,
+};
+use reth_network_peers::{PeerId, WithPeerId};
+use std::{
+    collections::HashMap,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicU64, AtomicUsize, Ordering},
+        Arc,
+    },
+    task::{ready, Context, Poll},
+};
+use tokio::sync::{mpsc, oneshot};
+use tracing::debug;
+
+/// Tracks the connected peers that support `snap/1` and dispatches requests to them.
+///
+/// Requests are distributed over the peers in a round-robin fashion.
+#[derive(Debug, Clone, Default)]
+pub struct SnapPeers {
+    inner: Arc<SnapPeersInner>,
+}
+
+#[derive(Debug, Default)]
+struct SnapPeersInner {
+    /// The ids and command channels of the active connections.
+    peers: RwLock<HashMap<PeerId, (u64, mpsc::UnboundedSender<SnapCommand>)>>,
+    /// Counter used to select the next peer.
+    next_peer: AtomicUsize,
+    /// Counter used to identify connections.
+    next_connection_id: AtomicU64,
+}
+
+impl SnapPeers {
+    /// Registers a new connection and returns its id.
+    ///
+    /// The connection is closed once the sender is dropped.
+    pub(crate) fn insert(
+        &self,
+        peer_id: PeerId,
+        commands: mpsc::UnboundedSender<SnapCommand>,
+    ) -> u64 {
+        let id = self.inner.next_connection_id.fetch_add(1, Ordering::Relaxed);
+        self.inner.peers.write().insert(peer_id, (id, commands));
+        id
+    }
+
+    /// Removes the connection of the peer, if it is still the one with the given id.
+    pub(crate) fn remove(&self, peer_id: &PeerId, connection_id: u64) {
+        let mut peers = self.inner.peers.write();
+        if peers.get(peer_id).is_some_and(|(id, _)| *id == connection_id) {
+            peers.remove(peer_id);
+        }
+    }
+
+    /// Returns the ids of all peers that support `snap/1`.
+    pub fn peer_ids(&self) -> Vec<PeerId> {
+        self.inner.peers.read().keys().copied().collect()
+    }
+
+    /// Returns the next peer to send a request to.
+    fn next_peer(&self) -> Option<(PeerId, mpsc::UnboundedSender<SnapCommand>)> {
+        let peers = self.inner.peers.read();
+        if peers.is_empty() {
+            return None
+        }
+        let index = self.inner.next_peer.fetch_add(1, Ordering::Relaxed) % peers.len();
+        peers.iter().nth(index).map(|(peer_id, (_, commands))| (*peer_id, commands.clone()))
+    }
+}
+
+impl DownloadClient for SnapPeers {
+    /// Closes the `snap/1` connection of the peer, so that no further requests are sent to it.
+    fn report_bad_message(&self, peer_id: PeerId) {
+        debug!(target: "net::snap", ?peer_id, "Dropping snap peer after bad response");
+        self.inner.peers.write().remove(&peer_id);
+    }
+
+    fn num_connected_peers(&self) -> usize {
+        self.num_snap_peers()
+    }
+}
+
+impl SnapClient for SnapPeers {
+    type Output = SnapResponseFuture;
+
+    fn get_snap_data(&self, request: SnapMessage) -> Self::Output {
+        let Some((peer_id, commands)) = self.next_peer() else {
+            return SnapResponseFuture::Failed(Some(RequestError::UnsupportedCapability))
+        };
+        let (response, rx) = oneshot::channel();
+        if commands.send(SnapCommand { request, response }).is_err() {
+            return SnapResponseFuture::Failed(Some(RequestError::ConnectionDropped))
+        }
+        SnapResponseFuture::Pending { peer_id, rx }
+    }
+
+    fn num_snap_peers(&self) -> usize {
+        self.inner.peers.read().len()
+    }
+}
+
+/// The future returned by [`SnapPeers::get_snap_data`].
+#[derive(Debug)]
+pub enum SnapResponseFuture {
+    /// The request could not be sent.
+    Failed(Option<RequestError>),
+    /// The request was sent to the peer.
+    Pending {
+        /// The peer the request was sent to.
+        peer_id: PeerId,
+        /// Receives the response of the peer.
+        rx: oneshot::Receiver<Result<SnapMessage, RequestError>>,
+    },
+}
+
+impl Future for SnapResponseFuture {
+    type Output = PeerRequestResult<SnapMessage>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        match self.get_mut() {
+            Self::Failed(err) => {
+                Poll::Ready(Err(err.take().unwrap_or(RequestError::ChannelClosed)))
+            }
+            Self::Pending { peer_id, rx } => {
+                let response =
+                    ready!(rx.poll_unpin(cx)).unwrap_or(Err(RequestError::ChannelClosed));
+                Poll::Ready(response.map(|message| WithPeerId::new(*peer_id, message)))
+            }
+        }
+    }
+}
@@ -0,0 +1,829 @@
+//! Serves `snap/1` requests from the hashed state and the trie tables.
+
+use alloy_primitives::{
+    map::{HashMap, HashSet},
+    BlockNumber, Bytes, B256,
+};
+use parking_lot::Mutex;
+use reth_db::tables;
+use reth_db_api::transaction::DbTx;
+use reth_eth_wire_types::snap::{
+    AccountData, AccountRange, ByteCodes, GetAccountRange, GetByteCodes, GetStorageRanges,
+    GetTrieNodes, SlimAccount, SnapMessage, StorageData, StorageRanges, TrieNodes,
+};
+use reth_primitives::constants::KECCAK_EMPTY;
+use reth_provider::{
+    DBProvider, DatabaseProviderFactory, HashedStateRangeReader, HeaderProvider,
+    StageCheckpointReader,
+};
+use reth_stages_types::StageId;
+use reth_storage_errors::provider::{ProviderError, ProviderResult};
+use reth_trie::{
+    hashed_cursor::{HashedCursor, HashedCursorFactory, HashedPostStateCursorFactory},
+    prefix_set::{PrefixSetMut, TriePrefixSetsMut},
+    proof::{Proof, StorageProof},
+    HashedPostState, HashedPostStateSorted, Nibbles, StorageRoot,
+};
+use reth_trie_db::{DatabaseHashedCursorFactory, DatabaseStorageRoot, DatabaseTrieCursorFactory};
+use std::{collections::BTreeMap, sync::Arc};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tracing::trace;
+
+/// The upper limit of the size of a response, regardless of the requested size.
+///
+/// Same as the soft response limit of geth.
+pub const MAX_RESPONSE_BYTES: u64 = 2 * 1024 * 1024;
+
+/// The maximum number of bytecodes served in a single response.
+pub const MAX_BYTECODES_SERVE: usize = 1024;
+
+/// The maximum number of accounts whose storage is served in a single response.
+pub const MAX_STORAGE_ACCOUNTS_SERVE: usize = 1024;
+
+/// The maximum number of trie nodes served in a single response.
+pub const MAX_TRIE_NODES_SERVE: usize = 1024;
+
+/// The number of most recent blocks whose state is served.
+///
+/// Same as the number of state layers geth keeps in memory.
+pub const MAX_SERVED_BLOCKS: u64 = 128;
+
+/// The maximum number of requests that are served concurrently, over all connections.
+pub const MAX_CONCURRENT_REQUESTS: usize = 32;
+
+/// The maximum number of served states whose reverts are kept in memory.
+const MAX_CACHED_STATES: usize = 4;
+
+/// Answers `snap/1` requests from the database.
+///
+/// The state of the [`MAX_SERVED_BLOCKS`] most recent fully synced blocks is served. The state
+/// of older blocks is read by applying the reverts of the changesets on top of the latest state.
+/// Requests for any other state root are answered with empty responses, which the protocol allows
+/// for state that is not available.
+#[derive(Debug, Clone)]
+pub struct SnapRequestHandler<F> {
+    factory: F,
+    /// The recently requested states, shared by all connections.
+    cache: Arc<Mutex<ServedStates>>,
+    /// Limits the number of requests that are served concurrently by all connections.
+    permits: Arc<Semaphore>,
+}
+
+impl<F> SnapRequestHandler<F> {
+    /// Creates a new request handler that serves the state of the given provider factory.
+    pub fn new(factory: F) -> Self {
+        Self {
+            factory,
+            cache: Default::default(),
+            permits: Arc::new(Semaphore::new(MAX_CONCURRENT_REQUESTS)),
+        }
+    }
+
+    /// Returns a permit to serve a request, if less than [`MAX_CONCURRENT_REQUESTS`] requests are
+    /// served.
+    pub(crate) fn try_acquire(&self) -> Option<OwnedSemaphorePermit> {
+        self.permits.clone().try_acquire_owned().ok()
+    }
+}
+
+impl<F> SnapRequestHandler<F>
+where
+    F: DatabaseProviderFactory<
+        Provider: StageCheckpointReader + HeaderProvider + HashedStateRangeReader,
+    >,
+{
+    /// Answers the given request.
+    ///
+    /// Returns `None` if the message is not a request.
+    pub fn on_request(&self, request: SnapMessage) -> Option<SnapMessage> {
+        let request_id = request.request_id();
+        let response = match request {
+            SnapMessage::GetAccountRange(request) => {
+                self.account_range(request).map(SnapMessage::AccountRange).unwrap_or_else(|err| {
+                    trace!(target: "net::snap", %err, "Failed to serve account range");
+                    SnapMessage::AccountRange(AccountRange { request_id, ..Default::default() })
+                })
+            }
+            SnapMessage::GetStorageRanges(request) => {
+                self.storage_ranges(request).map(SnapMessage::StorageRanges).unwrap_or_else(|err| {
+                    trace!(target: "net::snap", %err, "Failed to serve storage ranges");
+                    SnapMessage::StorageRanges(StorageRanges { request_id, ..Default::default() })
+                })
+            }
+            SnapMessage::GetByteCodes(request) => {
+                self.bytecodes(request).map(SnapMessage::ByteCodes).unwrap_or_else(|err| {
+                    trace!(target: "net::snap", %err, "Failed to serve bytecodes");
+                    SnapMessage::ByteCodes(ByteCodes { request_id, ..Default::default() })
+                })
+            }
+            SnapMessage::GetTrieNodes(request) => {
+                self.trie_nodes(request).map(SnapMessage::TrieNodes).unwrap_or_else(|err| {
+                    trace!(target: "net::snap", %err, "Failed to serve trie nodes");
+                    SnapMessage::TrieNodes(TrieNodes { request_id, ..Default::default() })
+                })
+            }
+            _ => return None,
+        };
+        Some(response)
+    }
+
+    /// Returns a read-only provider and the served state if the given root is the state root of
+    /// one of the [`MAX_SERVED_BLOCKS`] most recent blocks.
+    fn provider_at(&self, root: B256) -> ProviderResult<Option<(F::Provider, Arc<ServedState>)>> {
+        let provider = self.factory.database_provider_ro()?;
+        let tip = provider.get_stage_checkpoint(StageId::Finish)?.unwrap_or_default().block_number;
+
+        let (blocks, state) = {
+            let cache = self.cache.lock();
+            if cache.tip == tip && !cache.blocks.is_empty() {
+                let state = cache.states.iter().find(|state| state.root == root).cloned();
+                (Some(cache.blocks.clone()), state)
+            } else {
+                (None, None)
+            }
+        };
+        if let Some(state) = state {
+            return Ok(Some((provider, state)))
+        }
+
+        let blocks = match blocks {
+            Some(blocks) => blocks,
+            None => {
+                let start = tip.saturating_sub(MAX_SERVED_BLOCKS - 1);
+                Arc::new(
+                    provider
+                        .headers_range(start..=tip)?
+                        .into_iter()
+                        .map(|header| (header.state_root, header.number))
+                        .collect(),
+                )
+            }
+        };
+        let Some(&block) = blocks.get(&root) else {
+            self.cache.lock().insert(tip, blocks, None);
+            return Ok(None)
+        };
+
+        let reverts = if block == tip {
+            HashedPostState::default()
+        } else {
+            provider.hashed_state_reverts(block)?
+        };
+        let state = Arc::new(ServedState {
+            root,
+            prefix_sets: reverts.construct_prefix_sets(),
+            reverts: reverts.into_sorted(),
+        });
+        self.cache.lock().insert(tip, blocks, Some(state.clone()));
+        Ok(Some((provider, state)))
+    }
+
+    /// Answers a [`GetAccountRange`] request.
+    pub fn account_range(&self, request: GetAccountRange) -> ProviderResult<AccountRange> {
+        let mut response = AccountRange { request_id: request.request_id, ..Default::default() };
+        let Some((provider, state)) = self.provider_at(request.root_hash)? else {
+            return Ok(response)
+        };
+        let tx = provider.tx_ref();
+        let limit = request.response_bytes.min(MAX_RESPONSE_BYTES) as usize;
+
+        let mut size = 0;
+        let mut cursor = state.hashed_cursor_factory(tx).hashed_account_cursor()?;
+        let mut entry = cursor.seek(request.starting_hash)?;
+        while let Some((hash, account)) = entry {
+            let account = SlimAccount {
+                nonce: account.nonce,
+                balance: account.balance,
+                storage_root: state.storage_root(tx, hash)?,
+                code_hash: account.bytecode_hash.unwrap_or(KECCAK_EMPTY),
+            };
+            let data = AccountData { hash, account };
+            size += alloy_rlp::Encodable::length(&data);
+            response.accounts.push(data);
+
+            if hash >= request.limit_hash || size >= limit {
+                break
+            }
+            entry = cursor.next()?;
+        }
+
+        let last = response.accounts.last().map_or(request.starting_hash, |data| data.hash);
+        let targets = HashMap::from_iter(
+            [request.starting_hash, last].into_iter().map(|key| (key, HashSet::default())),
+        );
+        let multiproof = state.proof(tx).multiproof(targets)?;
+        response.proof = boundary_proof([request.starting_hash, last], |key| {
+            multiproof
+                .account_subtree
+                .matching_nodes_iter(key)
+                .map(|(path, node)| (path.clone(), node.clone()))
+                .collect()
+        });
+
+        Ok(response)
+    }
+
+    /// Answers a [`GetStorageRanges`] request.
+    pub fn storage_ranges(&self, request: GetStorageRanges) -> ProviderResult<StorageRanges> {
+        let mut response = StorageRanges { request_id: request.request_id, ..Default::default() };
+        let Some((provider, state)) = self.provider_at(request.root_hash)? else {
+            return Ok(response)
+        };
+        let tx = provider.tx_ref();
+        let hashed_cursor_factory = state.hashed_cursor_factory(tx);
+        let limit = request.response_bytes.min(MAX_RESPONSE_BYTES) as usize;
+
+        let mut size = 0;
+        for (index, hashed_address) in
+            request.account_hashes.iter().copied().take(MAX_STORAGE_ACCOUNTS_SERVE).enumerate()
+        {
+            // the requested range only applies to the first account
+            let (start, end) = if index == 0 {
+                (request.starting_hash, request.limit_hash)
+            } else {
+                (B256::ZERO, B256::repeat_byte(0xff))
+            };
+
+            let mut slots = Vec::new();
+            let mut slots_size = 0;
+            let mut complete = true;
+            let mut cursor = hashed_cursor_factory.hashed_storage_cursor(hashed_address)?;
+            let mut entry = cursor.seek(start)?;
+            while let Some((key, value)) = entry {
+                let data = StorageData {
+                    hash: key,
+                    data: alloy_rlp::encode_fixed_size(&value).to_vec().into(),
+                };
+                slots_size += alloy_rlp::Encodable::length(&data);
+                slots.push(data);
+
+                if key >= end {
+                    complete = false;
+                    break
+                }
+                if size + slots_size >= limit {
+                    complete = false;
+                    break
+                }
+                entry = cursor.next()?;
+            }
+            // the list of every account counts towards the response size, even if it's empty
+            size += alloy_rlp::length_of_length(slots_size) + slots_size;
+
+            // a proof is required if the range does not start at the beginning of the storage or
+            // the storage could not be served completely
+            if start != B256::ZERO || !complete {
+                let last = slots.last().map_or(start, |data| data.hash);
+                let multiproof = state
+                    .storage_proof(tx, hashed_address)
+                    .storage_multiproof(HashSet::from_iter([start, last]))?;
+                response.proof = boundary_proof([start, last], |key| {
+                    multiproof
+                        .subtree
+                        .matching_nodes_iter(key)
+                        .map(|(path, node)| (path.clone(), node.clone()))
+                        .collect()
+                });
+                response.slots.push(slots);
+                break
+            }
+
+            response.slots.push(slots);
+            if size >= limit {
+                break
+            }
+        }
+
+        Ok(response)
+    }
+
+    /// Answers a [`GetByteCodes`] request.
+    pub fn bytecodes(&self, request: GetByteCodes) -> ProviderResult<ByteCodes> {
+        let mut response = ByteCodes { request_id: request.request_id, codes: Vec::new() };
+        let provider = self.factory.database_provider_ro()?;
+        let limit = request.response_bytes.min(MAX_RESPONSE_BYTES) as usize;
+
+        let mut size = 0;
+        for hash in request.hashes.into_iter().take(MAX_BYTECODES_SERVE) {
+            let Some(code) = provider.tx_ref().get::<tables::Bytecodes>(hash)? else { continue };
+            let code = code.original_bytes();
+            size += code.len();
+            response.codes.push(code);
+            if size >= limit {
+                break
+            }
+        }
+
+        Ok(response)
+    }
+
+    /// Answers a [`GetTrieNodes`] request.
+    ///
+    /// All requested nodes are looked up with a single multiproof.
+    pub fn trie_nodes(&self, request: GetTrieNodes) -> ProviderResult<TrieNodes> {
+        let mut response = TrieNodes { request_id: request.request_id, nodes: Vec::new() };
+        let Some((provider, state)) = self.provider_at(request.root_hash)? else {
+            return Ok(response)
+        };
+        let tx = provider.tx_ref();
+        let limit = request.response_bytes.min(MAX_RESPONSE_BYTES) as usize;
+
+        // the requested nodes in the order of the request, up to the first invalid path
+        let mut paths = Vec::new();
+        'sets: for set in request.paths {
+            match set.as_slice() {
+                [] => break,
+                [path] => {
+                    let Some(path) = decode_compact_path(path) else { break };
+                    paths.push((None, path));
+                }
+                [account, storage_paths @ ..] => {
+                    if account.len() != 32 {
+                        break
+                    }
+                    let hashed_address = B256::from_slice(account);
+                    for path in storage_paths {
+                        let Some(path) = decode_compact_path(path) else { break 'sets };
+                        paths.push((Some(hashed_address), path));
+                    }
+                }
+            }
+            if paths.len() >= MAX_TRIE_NODES_SERVE {
+                paths.truncate(MAX_TRIE_NODES_SERVE);
+                break
+            }
+        }
+
+        let mut targets = HashMap::<B256, HashSet<B256>>::default();
+        for (hashed_address, path) in &paths {
+            match hashed_address {
+                Some(hashed_address) => {
+                    targets.entry(*hashed_address).or_default().insert(padded_key(path));
+                }
+                None => {
+                    targets.entry(padded_key(path)).or_default();
+                }
+            }
+        }
+        let multiproof = state.proof(tx).multiproof(targets)?;
+
+        let mut size = 0;
+        for (hashed_address, path) in paths {
+            let subtree = match hashed_address {
+                Some(hashed_address) => match multiproof.storages.get(&hashed_address) {
+                    Some(storage) => &storage.subtree,
+                    None => break,
+                },
+                None => &multiproof.account_subtree,
+            };
+            // the response ends at the first unavailable node
+            let Some(node) = subtree
+                .matching_nodes_iter(&Nibbles::unpack(padded_key(&path)))
+                .find(|(node_path, _)| **node_path == path)
+                .map(|(_, node)| node.clone())
+            else {
+                break
+            };
+            size += node.len();
+            response.nodes.push(node);
+            if size >= limit {
+                break
+            }
+        }
+
+        Ok(response)
+    }
+}
+
+/// The recently requested states of a [`SnapRequestHandler`].
+#[derive(Debug, Default)]
+struct ServedStates {
+    /// The latest block, the cache is only valid for this block.
+    tip: BlockNumber,
+    /// The state roots of the served blocks and their block numbers.
+    blocks: Arc<HashMap<B256, BlockNumber>>,
+    /// The most recently requested states, up to [`MAX_CACHED_STATES`].
+    states: Vec<Arc<ServedState>>,
+}
+
+impl ServedStates {
+    /// Caches the state roots of the served blocks and a requested state for the given tip.
+    fn insert(
+        &mut self,
+        tip: BlockNumber,
+        blocks: Arc<HashMap<B256, BlockNumber>>,
+        state: Option<Arc<ServedState>>,
+    ) {
+        if tip < self.tip {
+            return
+        }
+        if tip > self.tip || self.blocks.is_empty() {
+            *self = Self { tip, blocks, states: Vec::new() };
+        }
+        if let Some(state) = state {
+            if self.states.len() >= MAX_CACHED_STATES {
+                self.states.remove(0);
+            }
+            self.states.push(state);
+        }
+    }
+}
+
+/// The state of a recent block, as reverts on top of the latest state.
+#[derive(Debug)]
+struct ServedState {
+    /// The state root of the block.
+    root: B256,
+    /// The reverts from the latest state to the state of the block, empty for the latest block.
+    reverts: HashedPostStateSorted,
+    /// The prefix sets of the reverts, the trie nodes under these prefixes are recomputed.
+    prefix_sets: TriePrefixSetsMut,
+}
+
+impl ServedState {
+    /// Returns a hashed cursor factory over the state.
+    fn hashed_cursor_factory<'a, TX: DbTx>(
+        &'a self,
+        tx: &'a TX,
+    ) -> HashedPostStateCursorFactory<'a, DatabaseHashedCursorFactory<'a, TX>> {
+        HashedPostStateCursorFactory::new(DatabaseHashedCursorFactory::new(tx), &self.reverts)
+    }
+
+    /// Returns the storage root of the given account.
+    fn storage_root<TX: DbTx>(&self, tx: &TX, hashed_address: B256) -> ProviderResult<B256> {
+        StorageRoot::from_tx_hashed(tx, hashed_address)
+            .with_hashed_cursor_factory(self.hashed_cursor_factory(tx))
+            .with_prefix_set(self.storage_prefix_set(hashed_address).freeze())
+            .root()
+            .map_err(|err| ProviderError::Database(err.into()))
+    }
+
+    /// Returns the proof generator of the account trie.
+    fn proof<'a, TX: DbTx>(
+        &'a self,
+        tx: &'a TX,
+    ) -> Proof<
+        DatabaseTrieCursorFactory<'a, TX>,
+        HashedPostStateCursorFactory<'a, DatabaseHashedCursorFactory<'a, TX>>,
+    > {
+        Proof::new(DatabaseTrieCursorFactory::new(tx), self.hashed_cursor_factory(tx))
+            .with_prefix_sets_mut(self.prefix_sets.clone())
+    }
+
+    /// Returns the proof generator of the storage trie of the given account.
+    fn storage_proof<'a, TX: DbTx>(
+        &'a self,
+        tx: &'a TX,
+        hashed_address: B256,
+    ) -> StorageProof<
+        DatabaseTrieCursorFactory<'a, TX>,
+        HashedPostStateCursorFactory<'a, DatabaseHashedCursorFactory<'a, TX>>,
+    > {
+        StorageProof::new_hashed(
+            DatabaseTrieCursorFactory::new(tx),
+            self.hashed_cursor_factory(tx),
+            hashed_address,
+        )
+        .with_prefix_set_mut(self.storage_prefix_set(hashed_address))
+    }
+
+    /// Returns the prefix set of the storage trie of the given account.
+    fn storage_prefix_set(&self, hashed_address: B256) -> PrefixSetMut {
+        self.prefix_sets.storage_prefix_sets.get(&hashed_address).cloned().unwrap_or_default()
+    }
+}
+
+/// Collects the proof nodes on the paths to the given keys, without duplicates.
+fn boundary_proof(
+    keys: [B256; 2],
+    matching_nodes: impl Fn(&Nibbles) -> Vec<(Nibbles, Bytes)>,
+) -> Vec<Bytes> {
+    keys.iter()
+        .flat_map(|key| matching_nodes(&Nibbles::unpack(key)))
+        .collect::<BTreeMap<_, _>>()
+        .into_values()
+        .collect()
+}
+
+/// Returns the key that starts with the given path and is padded with zeros.
+fn padded_key(path: &Nibbles) -> B256 {
+    let mut nibbles = path.to_vec();
+    nibbles.resize(64, 0);
+    B256::from_slice(&Nibbles::from_nibbles_unchecked(nibbles).pack())
+}
+
+/// Decodes a path in the compact (hex-prefix) encoding.
+fn decode_compact_path(encoded: &[u8]) -> Option<Nibbles> {
+    let (first, rest) = encoded.split_first()?;
+    let mut nibbles = Vec::with_capacity(rest.len() * 2 + 1);
+    if first & 0x10 != 0 {
+        nibbles.push(first & 0x0f);
+    }
+    for byte in rest {
+        nibbles.push(byte >> 4);
+        nibbles.push(byte & 0x0f);
+    }
+    (nibbles.len() <= 64).then(|| Nibbles::from_nibbles_unchecked(nibbles))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::{keccak256, Address, U256};
+    use reth_db_api::{
+        cursor::{DbCursorRW, DbDupCursorRO},
+        models::{AccountBeforeTx, BlockNumberAddress},
+        transaction::DbTxMut,
+    };
+    use reth_primitives::{Account, Header, StaticFileSegment, StorageEntry};
+    use reth_provider::{
+        test_utils::create_test_provider_factory, StageCheckpointWriter, StaticFileProviderFactory,
+        StaticFileWriter, TrieWriter,
+    };
+    use reth_stages_types::StageCheckpoint;
+    use reth_trie::StateRoot;
+    use reth_trie_common::{range_proof::verify_range_proof, TrieAccount};
+    use reth_trie_db::DatabaseStateRoot;
+
+    #[test]
+    fn compact_path_roundtrip() {
+        for len in 0..=64 {
+            let path = Nibbles::from_nibbles_unchecked(
+                (0..len).map(|i| (i % 16) as u8).collect::<Vec<_>>(),
+            );
+            assert_eq!(decode_compact_path(&path.encode_path_leaf(false)), Some(path));
+        }
+    }
+
+    #[test]
+    fn serve_verifiable_ranges() {
+        let factory = create_test_provider_factory();
+        let provider = factory.provider_rw().unwrap();
+        let tx = provider.tx_ref();
+
+        let contract = keccak256(B256::repeat_byte(0xee));
+        for i in 0..200u64 {
+            let account = Account { nonce: i, balance: U256::from(i), bytecode_hash: None };
+            tx.put::<tables::HashedAccounts>(keccak256(U256::from(i).to_be_bytes::<32>()), account)
+                .unwrap();
+        }
+        tx.put::<tables::HashedAccounts>(contract, Account::default()).unwrap();
+        for i in 1..=100u64 {
+            let entry = StorageEntry {
+                key: keccak256(U256::from(i).to_be_bytes::<32>()),
+                value: U256::from(i),
+            };
+            tx.put::<tables::HashedStorages>(contract, entry).unwrap();
+        }
+        let (root, updates) = StateRoot::from_tx(tx).root_with_updates().unwrap();
+        provider.write_trie_updates(&updates).unwrap();
+
+        let header = Header { state_root: root, ..Default::default() };
+        let static_file_provider = factory.static_file_provider();
+        let mut writer = static_file_provider.latest_writer(StaticFileSegment::Headers).unwrap();
+        writer.append_header(&header, U256::ZERO, &header.hash_slow()).unwrap();
+        writer.commit().unwrap();
+        drop(writer);
+        provider.save_stage_checkpoint(StageId::Finish, StageCheckpoint::new(0)).unwrap();
+        provider.commit().unwrap();
+
+        let handler = SnapRequestHandler::new(factory);
+
+        // accounts, in two pages
+        let request = GetAccountRange {
+            request_id: 1,
+            root_hash: root,
+            starting_hash: B256::ZERO,
+            limit_hash: B256::repeat_byte(0xff),
+            response_bytes: 100 * 80,
+        };
+        let first = handler.account_range(request).unwrap();
+        assert!(!first.accounts.is_empty() && first.accounts.len() < 201);
+        let leaves = account_leaves(&first);
+        assert_eq!(verify_range_proof(root, B256::ZERO, &leaves, &first.proof), Ok(true));
+
+        let starting_hash =
+            B256::from(U256::from_be_bytes(first.accounts.last().unwrap().hash.0) + U256::from(1));
+        let second = handler
+            .account_range(GetAccountRange { starting_hash, response_bytes: u64::MAX, ..request })
+            .unwrap();
+        assert_eq!(first.accounts.len() + second.accounts.len(), 201);
+        let leaves = account_leaves(&second);
+        assert_eq!(verify_range_proof(root, starting_hash, &leaves, &second.proof), Ok(false));
+
+        // storage of the contract, served completely without a proof
+        let storage_root = second
+            .accounts
+            .iter()
+            .chain(&first.accounts)
+            .find(|data| data.hash == contract)
+            .unwrap()
+            .account
+            .storage_root;
+        let ranges = handler
+            .storage_ranges(GetStorageRanges {
+                request_id: 2,
+                root_hash: root,
+                account_hashes: vec![contract],
+                starting_hash: B256::ZERO,
+                limit_hash: B256::repeat_byte(0xff),
+                response_bytes: u64::MAX,
+            })
+            .unwrap();
+        assert_eq!(ranges.slots.len(), 1);
+        assert_eq!(ranges.slots[0].len(), 100);
+        assert!(ranges.proof.is_empty());
+        let leaves =
+            ranges.slots[0].iter().map(|slot| (slot.hash, slot.data.to_vec())).collect::<Vec<_>>();
+        assert_eq!(verify_range_proof(storage_root, B256::ZERO, &leaves, &[]), Ok(false));
+
+        // the number of accounts is capped, and their empty storages count towards the size
+        let request = GetStorageRanges {
+            request_id: 2,
+            root_hash: root,
+            account_hashes: vec![B256::repeat_byte(1); MAX_STORAGE_ACCOUNTS_SERVE + 1],
+            starting_hash: B256::ZERO,
+            limit_hash: B256::repeat_byte(0xff),
+            response_bytes: u64::MAX,
+        };
+        let ranges = handler.storage_ranges(request.clone()).unwrap();
+        assert_eq!(ranges.slots.len(), MAX_STORAGE_ACCOUNTS_SERVE);
+        let ranges =
+            handler.storage_ranges(GetStorageRanges { response_bytes: 10, ..request }).unwrap();
+        assert_eq!(ranges.slots.len(), 10);
+
+        // unknown roots are not served
+        let response = handler
+            .account_range(GetAccountRange { root_hash: B256::repeat_byte(1), ..request })
+            .unwrap();
+        assert!(response.accounts.is_empty() && response.proof.is_empty());
+
+        // the root node
+        let nodes = handler
+            .trie_nodes(GetTrieNodes {
+                request_id: 3,
+                root_hash: root,
+                paths: vec![vec![Nibbles::default().encode_path_leaf(false).to_vec().into()]],
+                response_bytes: u64::MAX,
+            })
+            .unwrap();
+        assert_eq!(nodes.nodes.len(), 1);
+        assert_eq!(keccak256(&nodes.nodes[0]), root);
+    }
+
+    #[test]
+    fn serve_recent_state() {
+        let factory = create_test_provider_factory();
+        let provider = factory.provider_rw().unwrap();
+        let tx = provider.tx_ref();
+
+        // the state at the end of block 0
+        let contract = Address::repeat_byte(0xee);
+        for i in 0..20u8 {
+            let account = Account { nonce: 1, balance: U256::from(i), bytecode_hash: None };
+            tx.put::<tables::HashedAccounts>(keccak256(Address::with_last_byte(i)), account)
+                .unwrap();
+        }
+        tx.put::<tables::HashedAccounts>(keccak256(contract), Account::default()).unwrap();
+        for i in 1..=10u64 {
+            let slot = B256::from(U256::from(i));
+            set_storage(tx, keccak256(contract), keccak256(slot), U256::from(i));
+        }
+        let root = StateRoot::from_tx(tx).root().unwrap();
+
+        // block 1 changes an account, creates another one and changes the storage of the contract
+        let changed = Address::with_last_byte(1);
+        let created = Address::with_last_byte(50);
+        let before = Account { nonce: 1, balance: U256::from(1), bytecode_hash: None };
+        tx.put::<tables::AccountChangeSets>(
+            1,
+            AccountBeforeTx { address: changed, info: Some(before) },
+        )
+        .unwrap();
+        tx.put::<tables::AccountChangeSets>(1, AccountBeforeTx { address: created, info: None })
+            .unwrap();
+        let after = Account { nonce: 2, balance: U256::from(100), bytecode_hash: None };
+        tx.put::<tables::HashedAccounts>(keccak256(changed), after).unwrap();
+        tx.put::<tables::HashedAccounts>(keccak256(created), Account::default()).unwrap();
+        for (slot, before, after) in [(1u64, 1u64, 7u64), (200, 0, 5)] {
+            let slot = B256::from(U256::from(slot));
+            tx.put::<tables::StorageChangeSets>(
+                BlockNumberAddress((1, contract)),
+                StorageEntry { key: slot, value: U256::from(before) },
+            )
+            .unwrap();
+            set_storage(tx, keccak256(contract), keccak256(slot), U256::from(after));
+        }
+        let (latest_root, updates) = StateRoot::from_tx(tx).root_with_updates().unwrap();
+        provider.write_trie_updates(&updates).unwrap();
+        assert_ne!(root, latest_root);
+
+        let static_file_provider = factory.static_file_provider();
+        let mut writer = static_file_provider.latest_writer(StaticFileSegment::Headers).unwrap();
+        for (number, state_root) in [(0, root), (1, latest_root)] {
+            let header = Header { number, state_root, ..Default::default() };
+            writer.append_header(&header, U256::ZERO, &header.hash_slow()).unwrap();
+        }
+        writer.commit().unwrap();
+        drop(writer);
+        provider.save_stage_checkpoint(StageId::Finish, StageCheckpoint::new(1)).unwrap();
+        provider.commit().unwrap();
+
+        let handler = SnapRequestHandler::new(factory);
+
+        // the state of the previous block is served from the reverts
+        let accounts_request = GetAccountRange {
+            request_id: 1,
+            root_hash: root,
+            starting_hash: B256::ZERO,
+            limit_hash: B256::repeat_byte(0xff),
+            response_bytes: u64::MAX,
+        };
+        let range = handler.account_range(accounts_request).unwrap();
+        assert_eq!(range.accounts.len(), 21);
+        assert_eq!(
+            verify_range_proof(root, B256::ZERO, &account_leaves(&range), &range.proof),
+            Ok(false)
+        );
+        let account = |hash| range.accounts.iter().find(|data| data.hash == hash).unwrap().account;
+        assert_eq!(account(keccak256(changed)).balance, U256::from(1));
+        let storage_root = account(keccak256(contract)).storage_root;
+
+        let request = GetStorageRanges {
+            request_id: 2,
+            root_hash: root,
+            account_hashes: vec![keccak256(contract)],
+            starting_hash: B256::ZERO,
+            limit_hash: B256::repeat_byte(0xff),
+            response_bytes: u64::MAX,
+        };
+        let ranges = handler.storage_ranges(request.clone()).unwrap();
+        assert_eq!(ranges.slots[0].len(), 10);
+        let leaves =
+            ranges.slots[0].iter().map(|slot| (slot.hash, slot.data.to_vec())).collect::<Vec<_>>();
+        assert_eq!(verify_range_proof(storage_root, B256::ZERO, &leaves, &[]), Ok(false));
+
+        // a partial range is proven against the storage root of the previous block
+        let ranges =
+            handler.storage_ranges(GetStorageRanges { response_bytes: 1, ..request }).unwrap();
+        assert_eq!(ranges.slots[0].len(), 1);
+        let leaves =
+            ranges.slots[0].iter().map(|slot| (slot.hash, slot.data.to_vec())).collect::<Vec<_>>();
+        assert_eq!(verify_range_proof(storage_root, B256::ZERO, &leaves, &ranges.proof), Ok(true));
+
+        // account and storage trie nodes in a single request
+        let root_path = Bytes::from(Nibbles::default().encode_path_leaf(false).to_vec());
+        let nodes = handler
+            .trie_nodes(GetTrieNodes {
+                request_id: 3,
+                root_hash: root,
+                paths: vec![
+                    vec![root_path.clone()],
+                    vec![Bytes::copy_from_slice(keccak256(contract).as_slice()), root_path],
+                ],
+                response_bytes: u64::MAX,
+            })
+            .unwrap();
+        assert_eq!(nodes.nodes.len(), 2);
+        assert_eq!(keccak256(&nodes.nodes[0]), root);
+        assert_eq!(keccak256(&nodes.nodes[1]), storage_root);
+
+        // the latest state is served as well
+        let range = handler
+            .account_range(GetAccountRange { root_hash: latest_root, ..accounts_request })
+            .unwrap();
+        assert_eq!(range.accounts.len(), 22);
+        assert_eq!(
+            verify_range_proof(latest_root, B256::ZERO, &account_leaves(&range), &range.proof),
+            Ok(false)
+        );
+    }
+
+    fn set_storage<TX: DbTxMut + DbTx>(tx: &TX, hashed_address: B256, key: B256, value: U256) {
+        let mut cursor = tx.cursor_dup_write::<tables::HashedStorages>().unwrap();
+        if cursor
+            .seek_by_key_subkey(hashed_address, key)
+            .unwrap()
+            .is_some_and(|entry| entry.key == key)
+        {
+            cursor.delete_current().unwrap();
+        }
+        cursor.upsert(hashed_address, StorageEntry { key, value }).unwrap();
+    }
+
+    fn account_leaves(range: &AccountRange) -> Vec<(B256, Vec<u8>)> {
+        range
+            .accounts
+            .iter()
+            .map(|data| {
+                let account = TrieAccount {
+                    nonce: data.account.nonce,
+                    balance: data.account.balance,
+                    storage_root: data.account.storage_root,
+                    code_hash: data.account.code_hash,
+                };
+                (data.hash, alloy_rlp::encode(account))
+            })
+            .collect()
+    }
+}
@@ -20,13 +20,13 @@ use futures::Future;
 use reth_chainspec::{EthChainSpec, EthereumHardforks, Hardforks};
 use reth_cli_util::get_secret_key;
 use reth_db_api::{
-    database::Database,
+    database::{Database, DatabaseCopy},
     database_metrics::{DatabaseMetadata, DatabaseMetrics},
 };
 use reth_exex::ExExContext;
 use reth_network::{
-    transactions::TransactionsManagerConfig, NetworkBuilder, NetworkConfig, NetworkConfigBuilder,
-    NetworkHandle, NetworkManager,
+    protocol::RlpxSubProtocol, transactions::TransactionsManagerConfig, NetworkBuilder,
+    NetworkConfig, NetworkConfigBuilder, NetworkHandle, NetworkManager,
 };
 use reth_node_api::{
     FullNodeTypes, FullNodeTypesAdapter, NodeAddOns, NodeTypes, NodeTypesWithDBAdapter,
@@ -39,7 +39,10 @@ use reth_node_core::{
     primitives::Head,
 };
 use reth_primitives::revm_primitives::EnvKzgSettings;
-use reth_provider::{providers::BlockchainProvider, ChainSpecProvider, FullProvider};
+use reth_provider::{
+    providers::BlockchainProvider, ChainSpecProvider, FullProvider, StaticFileProviderFactory,
+};
+use reth_prune::PruneMode;
 use reth_tasks::TaskExecutor;
 use reth_transaction_pool::{PoolConfig, TransactionPool};
 use secp256k1::SecretKey;
@@ -235,7 +238,7 @@ impl<DB, ChainSpec: EthChainSpec> NodeBuilder<DB, ChainSpec> {
 
 impl<DB, ChainSpec> NodeBuilder<DB, ChainSpec>
 where
-    DB: Database + DatabaseMetrics + DatabaseMetadata + Clone + Unpin + 'static,
+    DB: Database + DatabaseCopy + DatabaseMetrics + DatabaseMetadata + Clone + Unpin + 'static,
     ChainSpec: EthChainSpec + EthereumHardforks,
 {
     /// Configures the types of the node.
@@ -296,7 +299,7 @@ impl<DB, ChainSpec> WithLaunchContext<NodeBuilder<DB, ChainSpec>> {
 
 impl<DB, ChainSpec> WithLaunchContext<NodeBuilder<DB, ChainSpec>>
 where
-    DB: Database + DatabaseMetrics + DatabaseMetadata + Clone + Unpin + 'static,
+    DB: Database + DatabaseCopy + DatabaseMetrics + DatabaseMetadata + Clone + Unpin + 'static,
     ChainSpec: EthChainSpec + EthereumHardforks,
 {
     /// Configures the types of the node.
@@ -549,7 +552,7 @@ where
 
 impl<T, DB, CB, AO> WithLaunchContext<NodeBuilderWithComponents<RethFullAdapter<DB, T>, CB, AO>>
 where
-    DB: Database + DatabaseMetrics + DatabaseMetadata + Clone + Unpin + 'static,
+    DB: Database + DatabaseCopy + DatabaseMetrics + DatabaseMetadata + Clone + Unpin + 'static,
     T: NodeTypesWithEngine<ChainSpec: EthereumHardforks + EthChainSpec>,
     CB: NodeComponentsBuilder<RethFullAdapter<DB, T>>,
     AO: RethRpcAddOns<NodeAdapter<RethFullAdapter<DB, T>, CB::Components>>,
@@ -575,6 +578,8 @@ pub struct BuilderContext<Node: FullNodeTypes> {
     pub(crate) executor: TaskExecutor,
     /// Config container
     pub(crate) config_container: WithConfigs<<Node::Types as NodeTypes>::ChainSpec>,
+    /// Creates the `snap/1` protocol handler that is installed into the network, if enabled.
+    pub(crate) snap_protocol: Option<Arc<dyn Fn() -> RlpxSubProtocol + Send + Sync>>,
 }
 
 impl<Node: FullNodeTypes> BuilderContext<Node> {
@@ -585,7 +590,17 @@ impl<Node: FullNodeTypes> BuilderContext<Node> {
         executor: TaskExecutor,
         config_container: WithConfigs<<Node::Types as NodeTypes>::ChainSpec>,
     ) -> Self {
-        Self { head, provider, executor, config_container }
+        Self { head, provider, executor, config_container, snap_protocol: None }
+    }
+
+    /// Sets the function that creates the `snap/1` protocol handler that is installed into the
+    /// network.
+    pub fn with_snap_protocol(
+        mut self,
+        snap_protocol: Option<Arc<dyn Fn() -> RlpxSubProtocol + Send + Sync>>,
+    ) -> Self {
+        self.snap_protocol = snap_protocol;
+        self
     }
 
     /// Returns the configured provider to interact with the blockchain.
@@ -747,10 +762,52 @@ impl<Node: FullNodeTypes<Types: NodeTypes<ChainSpec: Hardforks>>> BuilderContext
                 default_peers_path,
             )
             .with_task_executor(Box::new(self.executor.clone()))
-            .set_head(self.head);
+            .set_head(self.head)
+            .earliest_block(self.earliest_served_block());
+
+        let builder = match &self.snap_protocol {
+            Some(snap_protocol) => builder.add_rlpx_sub_protocol(snap_protocol()),
+            None => builder,
+        };
 
         Ok(builder)
     }
+
+    /// Returns the earliest block for which this node still serves receipts at the current head.
+    ///
+    /// See [`earliest_served_block`].
+    fn earliest_served_block(&self) -> u64 {
+        let receipts = match self.config().prune_config() {
+            Some(mut config) => {
+                config.merge(self.reth_config().prune.clone());
+                config.segments.receipts
+            }
+            None => self.reth_config().prune.as_ref().and_then(|config| config.segments.receipts),
+        };
+        earliest_served_block(
+            receipts,
+            self.provider().static_file_provider().earliest_history_block(),
+            self.head.number,
+        )
+    }
+}
+
+/// Returns the earliest block for which this node still serves receipts at the given tip,
+/// according to the receipts [`PruneMode`] and the history expired from static files.
+///
+/// This is advertised to `eth/69` peers so that they don't request history we can't serve.
+pub(crate) fn earliest_served_block(
+    receipts: Option<PruneMode>,
+    earliest_history_block: Option<u64>,
+    tip: u64,
+) -> u64 {
+    let receipts = match receipts {
+        Some(PruneMode::Full) => tip,
+        Some(PruneMode::Distance(distance)) => tip.saturating_sub(distance),
+        Some(PruneMode::Before(block)) => block.min(tip),
+        None => 0,
+    };
+    receipts.max(earliest_history_block.unwrap_or_default().min(tip))
 }
 
 impl<Node: FullNodeTypes> std::fmt::Debug for BuilderContext<Node> {
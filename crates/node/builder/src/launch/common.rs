@@ -17,9 +17,12 @@ use reth_db_api::database::Database;
 use reth_db_common::init::{init_genesis, InitDatabaseError};
 use reth_downloaders::{bodies::noop::NoopBodiesDownloader, headers::noop::NoopHeaderDownloader};
 use reth_engine_tree::tree::{InvalidBlockHook, InvalidBlockHooks, NoopInvalidBlockHook};
+use reth_era::Era1History;
 use reth_evm::noop::NoopBlockExecutorProvider;
 use reth_fs_util as fs;
 use reth_invalid_block_hooks::InvalidBlockWitnessHook;
+use reth_net_snap::{SnapPeers, SnapProtocolHandler, SnapRequestHandler};
+use reth_network::protocol::{IntoRlpxSubProtocol, RlpxSubProtocol};
 use reth_network_p2p::headers::client::HeadersClient;
 use reth_node_api::{FullNodeTypes, NodeTypes, NodeTypesWithDB};
 use reth_node_core::{
@@ -126,6 +129,8 @@ impl LaunchContext {
         &self,
         config: &NodeConfig<ChainSpec>,
     ) -> eyre::Result<reth_config::Config> {
+        config.pruning.validate(&config.chain)?;
+
         let config_path = config.config.clone().unwrap_or_else(|| self.data_dir.config());
 
         let mut toml_config = reth_config::Config::from_path(&config_path)
@@ -408,13 +413,19 @@ where
     pub async fn create_provider_factory<N: NodeTypesWithDB<DB = DB, ChainSpec = ChainSpec>>(
         &self,
     ) -> eyre::Result<ProviderFactory<N>> {
-        let factory = ProviderFactory::new(
-            self.right().clone(),
-            self.chain_spec(),
-            StaticFileProvider::read_write(self.data_dir().static_files())?,
-        )
-        .with_prune_modes(self.prune_modes())
-        .with_static_files_metrics();
+        let mut static_file_provider =
+            StaticFileProvider::read_write(self.data_dir().static_files())?
+                .with_segment_configs(self.toml_config().static_files.segments());
+        if let Some(era1_dir) = &self.node_config().pruning.history_era1_dir {
+            let era1_history = Era1History::open(era1_dir)?;
+            info!(target: "reth::cli", dir = ?era1_dir, range = ?era1_history.block_range(), "Serving expired history from Era1 files");
+            static_file_provider = static_file_provider.with_era1_history(era1_history);
+        }
+
+        let factory =
+            ProviderFactory::new(self.right().clone(), self.chain_spec(), static_file_provider)
+                .with_prune_modes(self.prune_modes())
+                .with_static_files_metrics();
 
         let has_receipt_pruning =
             self.toml_config().prune.as_ref().map_or(false, |a| a.has_receipts_pruning());
@@ -680,12 +691,27 @@ where
         // fetch the head block from the database
         let head = self.lookup_head()?;
 
+        // the snap protocol is installed into the network by the components builder
+        let network_args = &self.node_config().network;
+        let snap = network_args.snap_enabled().then(|| {
+            SnapProtocolHandler::new(
+                network_args
+                    .snap_serve
+                    .then(|| SnapRequestHandler::new(self.provider_factory().clone())),
+            )
+        });
+        let snap_peers = snap.as_ref().filter(|_| network_args.snap_sync).map(|snap| snap.peers());
+
         let builder_ctx = BuilderContext::new(
             head,
             self.blockchain_db().clone(),
             self.task_executor().clone(),
             self.configs().clone(),
-        );
+        )
+        .with_snap_protocol(snap.map(|snap| {
+            Arc::new(move || snap.clone().into_rlpx_sub_protocol())
+                as Arc<dyn Fn() -> RlpxSubProtocol + Send + Sync>
+        }));
 
         debug!(target: "reth::cli", "creating components");
         let components = components_builder.build_components(&builder_ctx).await?;
@@ -730,6 +756,7 @@ where
             node_adapter,
             head,
             consensus,
+            snap_peers,
         };
 
         let ctx = LaunchContextWith {
@@ -781,6 +808,11 @@ where
         self.right().head
     }
 
+    /// Returns the peers that support `snap/1`, if snap sync is enabled.
+    pub fn snap_peers(&self) -> Option<SnapPeers> {
+        self.right().snap_peers.clone()
+    }
+
     /// Returns the configured `NodeAdapter`.
     pub const fn node_adapter(&self) -> &NodeAdapter<T, CB::Components> {
         &self.right().node_adapter
@@ -835,12 +867,21 @@ where
             .unwrap_or_default()
             .block_number;
 
+        // The transaction appearances index is only part of the pipeline if it's enabled, and has
+        // to be built up to the other stages when it's enabled on an existing node.
+        let optional_stages = self
+            .toml_config()
+            .stages
+            .index_transaction_appearances
+            .enabled
+            .then_some(StageId::IndexTransactionAppearances);
+
         // Skip the first stage as we've already retrieved it and comparing all other checkpoints
         // against it.
-        for stage_id in StageId::ALL.iter().skip(1) {
+        for stage_id in StageId::ALL.into_iter().skip(1).chain(optional_stages) {
             let stage_checkpoint = self
                 .blockchain_db()
-                .get_stage_checkpoint(*stage_id)?
+                .get_stage_checkpoint(stage_id)?
                 .unwrap_or_default()
                 .block_number;
 
@@ -1048,6 +1089,7 @@ where
     node_adapter: NodeAdapter<T, CB::Components>,
     head: Head,
     consensus: Arc<dyn Consensus>,
+    snap_peers: Option<SnapPeers>,
 }
 
 #[cfg(test)]
@@ -1088,6 +1130,10 @@ mod tests {
                     storage_history_full: false,
                     storage_history_distance: None,
                     storage_history_before: None,
+                    history_pre_merge: false,
+                    history_distance: None,
+                    history_before: None,
+                    history_era1_dir: None,
                     receipts_log_filter: vec![],
                 },
                 ..NodeConfig::test()
@@ -29,7 +29,10 @@ use reth_node_core::{
 use reth_node_events::{cl::ConsensusLayerHealthEvents, node};
 use reth_payload_primitives::PayloadBuilder;
 use reth_primitives::EthereumHardforks;
-use reth_provider::providers::{BlockchainProvider2, ProviderNodeTypes};
+use reth_provider::{
+    providers::{BlockchainProvider2, ProviderNodeTypes},
+    StaticFileProviderFactory,
+};
 use reth_tasks::TaskExecutor;
 use reth_tokio_util::EventSender;
 use reth_tracing::tracing::{debug, error, info};
@@ -40,6 +43,7 @@ use tokio_stream::wrappers::UnboundedReceiverStream;
 use crate::{
     common::{Attached, LaunchContextWith, WithConfigs},
     hooks::NodeHooks,
+    launch::announce_earliest_block_on_prune,
     rpc::{RethRpcAddOns, RpcHandle},
     setup::build_networked_pipeline,
     AddOns, AddOnsContext, ExExLauncher, FullNode, LaunchContext, LaunchNode, NodeAdapter,
@@ -190,6 +194,7 @@ where
             static_file_producer,
             ctx.components().block_executor().clone(),
             pipeline_exex_handle,
+            ctx.snap_peers(),
         )?;
 
         // The new engine writes directly to static files. This ensures that they're up to the tip.
@@ -207,6 +212,14 @@ where
         let pruner_events = pruner.events();
         info!(target: "reth::cli", prune_config=?ctx.prune_config().unwrap_or_default(), "Pruner initialized");
 
+        // announce history that was pruned or expired by the pruner
+        ctx.task_executor().spawn(Box::pin(announce_earliest_block_on_prune(
+            ctx.components().network().clone(),
+            pruner.events(),
+            ctx.provider_factory().static_file_provider(),
+            ctx.prune_modes().receipts,
+        )));
+
         let mut engine_service = if ctx.is_dev() {
             let mining_mode = if let Some(block_time) = ctx.node_config().dev.block_time {
                 MiningMode::interval(block_time)
@@ -22,7 +22,7 @@ use reth_chainspec::{EthChainSpec, EthereumHardforks};
 use reth_consensus_debug_client::{DebugConsensusClient, EtherscanBlockProvider, RpcBlockProvider};
 use reth_engine_util::EngineMessageStreamExt;
 use reth_exex::ExExManagerHandle;
-use reth_network::{BlockDownloaderProvider, NetworkEventListenerProvider};
+use reth_network::{BlockDownloaderProvider, NetworkEventListenerProvider, NetworkSyncUpdater};
 use reth_node_api::{
     AddOnsContext, FullNodeComponents, FullNodeTypes, NodeTypesWithDB, NodeTypesWithEngine,
 };
@@ -31,15 +31,20 @@ use reth_node_core::{
     exit::NodeExitFuture,
 };
 use reth_node_events::{cl::ConsensusLayerHealthEvents, node};
-use reth_provider::providers::BlockchainProvider;
+use reth_provider::{
+    providers::{BlockchainProvider, StaticFileProvider},
+    StaticFileProviderFactory,
+};
+use reth_prune::{PruneMode, PrunerEvent};
 use reth_tasks::TaskExecutor;
+use reth_tokio_util::EventStream;
 use reth_tracing::tracing::{debug, info};
 use reth_transaction_pool::TransactionPool;
 use tokio::sync::{mpsc::unbounded_channel, oneshot};
 use tokio_stream::wrappers::UnboundedReceiverStream;
 
 use crate::{
-    builder::{NodeAdapter, NodeTypesAdapter},
+    builder::{earliest_served_block, NodeAdapter, NodeTypesAdapter},
     components::{NodeComponents, NodeComponentsBuilder},
     hooks::NodeHooks,
     node::FullNode,
@@ -47,6 +52,24 @@ use crate::{
     AddOns, NodeBuilderWithComponents, NodeHandle,
 };
 
+/// Announces the earliest block this node serves to the network whenever the pruner finished a
+/// run, which may have pruned receipts or expired history.
+pub(crate) async fn announce_earliest_block_on_prune<N: NetworkSyncUpdater>(
+    network: N,
+    mut pruner_events: EventStream<PrunerEvent>,
+    static_file_provider: StaticFileProvider,
+    receipts: Option<PruneMode>,
+) {
+    while let Some(event) = pruner_events.next().await {
+        let PrunerEvent::Finished { tip_block_number, .. } = event else { continue };
+        network.update_earliest_block(earliest_served_block(
+            receipts,
+            static_file_provider.earliest_history_block(),
+            tip_block_number,
+        ));
+    }
+}
+
 /// Alias for [`reth_rpc_eth_types::EthApiBuilderCtx`], adapter for [`FullNodeComponents`].
 pub type EthApiBuilderCtx<N> = reth_rpc_eth_types::EthApiBuilderCtx<
     <N as FullNodeTypes>::Provider,
@@ -244,6 +267,7 @@ where
                 static_file_producer,
                 ctx.components().block_executor().clone(),
                 pipeline_exex_handle,
+                ctx.snap_peers(),
             )?;
 
             let pipeline_events = pipeline.events();
@@ -265,6 +289,7 @@ where
                 static_file_producer,
                 ctx.components().block_executor().clone(),
                 pipeline_exex_handle,
+                ctx.snap_peers(),
             )?;
 
             (pipeline, Either::Right(network_client.clone()))
@@ -283,6 +308,14 @@ where
 
         let pruner_events = pruner.events();
         info!(target: "reth::cli", prune_config=?ctx.prune_config().unwrap_or_default(), "Pruner initialized");
+
+        // announce history that was pruned or expired by the pruner
+        ctx.task_executor().spawn(Box::pin(announce_earliest_block_on_prune(
+            ctx.components().network().clone(),
+            pruner.events(),
+            ctx.provider_factory().static_file_provider(),
+            ctx.prune_modes().receipts,
+        )));
         hooks.add(PruneHook::new(pruner, Box::new(ctx.task_executor().clone())));
 
         // Configure the consensus engine
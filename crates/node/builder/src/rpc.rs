@@ -4,12 +4,15 @@ use std::{
     fmt::{self, Debug},
     marker::PhantomData,
     ops::{Deref, DerefMut},
+    sync::Arc,
 };
 
 use alloy_rpc_types::engine::ClientVersionV1;
-use futures::TryFutureExt;
+use futures::{StreamExt, TryFutureExt};
+use reth_beacon_consensus::BeaconConsensusEngineHandle;
+use reth_errors::RethResult;
 use reth_node_api::{
-    AddOnsContext, FullNodeComponents, NodeAddOns, NodeTypes, NodeTypesWithEngine,
+    AddOnsContext, EngineTypes, FullNodeComponents, NodeAddOns, NodeTypes, NodeTypesWithEngine,
 };
 use reth_node_core::{
     node_config::NodeConfig,
@@ -17,9 +20,10 @@ use reth_node_core::{
     version::{CARGO_PKG_VERSION, CLIENT_CODE, NAME_CLIENT, VERGEN_GIT_SHA},
 };
 use reth_payload_builder::PayloadBuilderHandle;
+use reth_payload_primitives::{PayloadBuilder, PayloadBuilderAttributes};
 use reth_provider::providers::ProviderNodeTypes;
-use reth_rpc::EthApi;
-use reth_rpc_api::eth::helpers::AddDevSigners;
+use reth_rpc::{eth::KeystoreSigner, EthApi, SetHeadHandle};
+use reth_rpc_api::eth::helpers::{AddDevSigners, EthApiSpec};
 use reth_rpc_builder::{
     auth::{AuthRpcModule, AuthServerHandle},
     config::RethRpcServerConfig,
@@ -419,7 +423,7 @@ where
         let module_config = config.rpc.transport_rpc_module_config();
         debug!(target: "reth::cli", http=?module_config.http(), ws=?module_config.ws(), "Using RPC module config");
 
-        let (mut modules, mut auth_module, registry) = RpcModuleBuilder::default()
+        let mut registry = RpcModuleBuilder::default()
             .with_provider(node.provider().clone())
             .with_pool(node.pool().clone())
             .with_network(node.network().clone())
@@ -427,13 +431,43 @@ where
             .with_executor(node.task_executor().clone())
             .with_evm_config(node.evm_config().clone())
             .with_block_executor(node.block_executor().clone())
-            .build_with_auth_server(module_config, engine_api, self.eth_api_builder);
+            .into_registry(
+                module_config.config().cloned().unwrap_or_default(),
+                self.eth_api_builder,
+            )
+            .with_set_head_handle(Arc::new(EngineSetHeadHandle(beacon_engine_handle.clone())));
+        let mut modules = registry.create_transport_rpc_modules(module_config);
+        let mut auth_module = registry.create_auth_module(engine_api);
 
         // in dev mode we generate 20 random dev-signer accounts
         if config.dev.dev {
             registry.eth_api().with_dev_accounts();
         }
 
+        // unlock the accounts of the configured keystore
+        if let (Some(keystore), Some(password_file)) =
+            (&config.rpc.rpc_keystore, &config.rpc.rpc_keystore_password_file)
+        {
+            let signer = KeystoreSigner::from_password_file(keystore, password_file)?;
+            info!(target: "reth::cli", keystore = %keystore.display(), "Unlocked keystore accounts");
+            registry.eth_api().add_signer(Box::new(signer));
+        }
+
+        // serve the fee recipient the CL last suggested to the payload builder as `eth_coinbase`
+        let mut payload_attributes = node
+            .payload_builder()
+            .subscribe()
+            .await
+            .map_err(|e| eyre::eyre!("Failed to subscribe to payload builder events: {:?}", e))?
+            .into_attributes_stream();
+        let eth_api = registry.eth_api().clone();
+        node.task_executor().spawn(Box::pin(async move {
+            while let Some(attributes) = payload_attributes.next().await {
+                *eth_api.suggested_fee_recipient().write() =
+                    Some(attributes.suggested_fee_recipient());
+            }
+        }));
+
         let mut registry = RpcRegistry { registry };
         let ctx = RpcContext {
             node: node.clone(),
@@ -525,3 +559,14 @@ impl<N: FullNodeComponents> EthApiBuilder<N> for EthApi<N::Provider, N::Pool, N:
         Self::with_spawner(ctx)
     }
 }
+
+/// Rewinds the canonical chain through the [`BeaconConsensusEngineHandle`] for `debug_setHead`.
+#[derive(Debug, Clone)]
+struct EngineSetHeadHandle<Engine: EngineTypes>(BeaconConsensusEngineHandle<Engine>);
+
+#[async_trait::async_trait]
+impl<Engine: EngineTypes> SetHeadHandle for EngineSetHeadHandle<Engine> {
+    async fn set_head(&self, block_number: u64) -> RethResult<()> {
+        self.0.set_head(block_number).await
+    }
+}
@@ -11,11 +11,16 @@ use reth_downloaders::{
 };
 use reth_evm::execute::BlockExecutorProvider;
 use reth_exex::ExExManagerHandle;
+use reth_net_snap::SnapPeers;
 use reth_network_p2p::{
     bodies::downloader::BodyDownloader, headers::downloader::HeaderDownloader, BlockClient,
 };
 use reth_provider::{providers::ProviderNodeTypes, ProviderFactory};
-use reth_stages::{prelude::DefaultStages, stages::ExecutionStage, Pipeline, StageSet};
+use reth_stages::{
+    prelude::DefaultStages,
+    stages::{ExecutionStage, SnapSyncStage},
+    Pipeline, StageId, StageSet,
+};
 use reth_static_file::StaticFileProducer;
 use reth_tasks::TaskExecutor;
 use reth_tracing::tracing::debug;
@@ -35,6 +40,7 @@ pub fn build_networked_pipeline<N, Client, Executor>(
     static_file_producer: StaticFileProducer<ProviderFactory<N>>,
     executor: Executor,
     exex_manager_handle: ExExManagerHandle,
+    snap_peers: Option<SnapPeers>,
 ) -> eyre::Result<Pipeline<N>>
 where
     N: ProviderNodeTypes,
@@ -62,6 +68,7 @@ where
         static_file_producer,
         executor,
         exex_manager_handle,
+        snap_peers,
     )?;
 
     Ok(pipeline)
@@ -81,6 +88,7 @@ pub fn build_pipeline<N, H, B, Executor>(
     static_file_producer: StaticFileProducer<ProviderFactory<N>>,
     executor: Executor,
     exex_manager_handle: ExExManagerHandle,
+    snap_peers: Option<SnapPeers>,
 ) -> eyre::Result<Pipeline<N>>
 where
     N: ProviderNodeTypes,
@@ -99,28 +107,33 @@ where
 
     let prune_modes = prune_config.map(|prune| prune.segments).unwrap_or_default();
 
+    let mut stages = DefaultStages::new(
+        provider_factory.clone(),
+        tip_rx,
+        Arc::clone(&consensus),
+        header_downloader,
+        body_downloader,
+        executor.clone(),
+        stage_config.clone(),
+        prune_modes.clone(),
+    )
+    .set(ExecutionStage::new(
+        executor,
+        stage_config.execution.into(),
+        stage_config.execution_external_clean_threshold(),
+        prune_modes,
+        exex_manager_handle,
+    ));
+
+    if let Some(snap_peers) = snap_peers {
+        debug!(target: "reth::cli", "Configuring pipeline to snap sync");
+        stages = stages.add_before(SnapSyncStage::new(snap_peers), StageId::Execution);
+    }
+
     let pipeline = builder
         .with_tip_sender(tip_tx)
         .with_metrics_tx(metrics_tx)
-        .add_stages(
-            DefaultStages::new(
-                provider_factory.clone(),
-                tip_rx,
-                Arc::clone(&consensus),
-                header_downloader,
-                body_downloader,
-                executor.clone(),
-                stage_config.clone(),
-                prune_modes.clone(),
-            )
-            .set(ExecutionStage::new(
-                executor,
-                stage_config.execution.into(),
-                stage_config.execution_external_clean_threshold(),
-                prune_modes,
-                exex_manager_handle,
-            )),
-        )
+        .add_stages(stages)
         .build(provider_factory, static_file_producer);
 
     Ok(pipeline)
@@ -1,6 +1,7 @@
 //! clap [Args](clap::Args) for network related arguments.
 
 use std::{
+    borrow::Cow,
     net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6},
     ops::Not,
     path::PathBuf,
@@ -26,13 +27,13 @@ use reth_network::{
                 DEFAULT_MAX_COUNT_PENDING_POOL_IMPORTS, DEFAULT_MAX_COUNT_TRANSACTIONS_SEEN_BY_PEER,
             },
         },
-        TransactionFetcherConfig, TransactionsManagerConfig,
+        TransactionFetcherConfig, TransactionGossipPolicy, TransactionsManagerConfig,
         DEFAULT_SOFT_LIMIT_BYTE_SIZE_POOLED_TRANSACTIONS_RESP_ON_PACK_GET_POOLED_TRANSACTIONS_REQ,
         SOFT_LIMIT_BYTE_SIZE_POOLED_TRANSACTIONS_RESPONSE,
     },
     HelloMessageWithProtocols, NetworkConfigBuilder, SessionsConfig,
 };
-use reth_network_peers::{mainnet_nodes, TrustedPeer};
+use reth_network_peers::{mainnet_nodes, PeerId, TrustedPeer};
 use secp256k1::SecretKey;
 use tracing::error;
 
@@ -87,7 +88,9 @@ pub struct NetworkArgs {
     #[arg(long, verbatim_doc_comment)]
     pub no_persist_peers: bool,
 
-    /// NAT resolution method (any|none|upnp|publicip|extip:\<IP\>)
+    /// NAT resolution method (any|none|upnp|natpmp[:\<GATEWAY\>]|publicip|extip:\<IP\>)
+    ///
+    /// With `upnp` and `natpmp`, the `RLPx` and discovery ports are also mapped on the gateway.
     #[arg(long, default_value = "any")]
     pub nat: NatResolver,
 
@@ -99,6 +102,11 @@ pub struct NetworkArgs {
     #[arg(long = "port", value_name = "PORT", default_value_t = DEFAULT_DISCOVERY_PORT)]
     pub port: u16,
 
+    /// Additional network listening address of the other IP version than `--addr`, on the same
+    /// port. Runs `RLPx` dual-stack, so that peers can connect over both IPv4 and IPv6.
+    #[arg(long = "addr.dual-stack", value_name = "ADDR")]
+    pub addr_dual_stack: Option<IpAddr>,
+
     /// Maximum number of outbound requests. default: 100
     #[arg(long)]
     pub max_outbound_peers: Option<usize>,
@@ -149,11 +157,38 @@ pub struct NetworkArgs {
     #[arg(long = "max-tx-pending-fetch", value_name = "COUNT", default_value_t = DEFAULT_MAX_CAPACITY_CACHE_PENDING_FETCH, verbatim_doc_comment)]
     pub max_capacity_cache_txns_pending_fetch: u32,
 
+    /// Only share transactions with trusted peers.
+    #[arg(long = "tx-gossip.trusted-only")]
+    pub tx_gossip_trusted_only: bool,
+
+    /// Comma separated peer IDs that locally submitted transactions are exclusively shared with.
+    ///
+    /// Transactions received from the network are still shared with all peers.
+    #[arg(long = "tx-gossip.private-peers", value_name = "PEER_ID", value_delimiter = ',')]
+    pub tx_gossip_private_peers: Option<Vec<PeerId>>,
+
+    /// Records all `eth` messages exchanged with peers to this file.
+    ///
+    /// The recording can be replayed against a local node with `reth p2p replay`.
+    #[arg(long = "record-sessions", value_name = "FILE")]
+    pub record_sessions: Option<PathBuf>,
+
     /// Name of network interface used to communicate with peers.
     ///
     /// If flag is set, but no value is passed, the default interface for docker `eth0` is tried.
     #[arg(long = "net-if.experimental", conflicts_with = "addr", value_name = "IF_NAME")]
     pub net_if: Option<String>,
+
+    /// Serve the state to peers with the `snap/1` protocol.
+    #[arg(long = "snap.serve")]
+    pub snap_serve: bool,
+
+    /// Download the state of a recent block from peers with the `snap/1` protocol instead of
+    /// executing all blocks, if the node has not executed any blocks yet.
+    ///
+    /// There is no state history and there are no receipts before that block.
+    #[arg(long = "snap.sync", verbatim_doc_comment)]
+    pub snap_sync: bool,
 }
 
 impl NetworkArgs {
@@ -178,6 +213,16 @@ impl NetworkArgs {
         self.addr
     }
 
+    /// Returns the [`TransactionGossipPolicy`] configured by the `--tx-gossip.*` flags.
+    pub fn tx_gossip_policy(&self) -> TransactionGossipPolicy {
+        let mut policy =
+            TransactionGossipPolicy::default().with_trusted_peers_only(self.tx_gossip_trusted_only);
+        if let Some(peers) = &self.tx_gossip_private_peers {
+            policy = policy.with_private_peers(peers.iter().copied());
+        }
+        policy
+    }
+
     /// Returns the resolved bootnodes if any are provided.
     pub fn resolved_bootnodes(&self) -> Option<Vec<NodeRecord>> {
         self.bootnodes.clone().map(|bootnodes| {
@@ -227,6 +272,7 @@ impl NetworkArgs {
             ),
             max_transactions_seen_by_peer_history: self.max_seen_tx_history,
             propagation_mode: Default::default(),
+            gossip_policy: self.tx_gossip_policy(),
         };
 
         // Configure basic network stack
@@ -253,17 +299,45 @@ impl NetworkArgs {
             // apply discovery settings
             .apply(|builder| {
                 let rlpx_socket = (addr, self.port).into();
-                self.discovery.apply_to_builder(builder, rlpx_socket, chain_bootnodes)
+                self.discovery_args_dual_stack().apply_to_builder(
+                    builder,
+                    rlpx_socket,
+                    chain_bootnodes,
+                )
             })
             .listener_addr(SocketAddr::new(
                 addr, // set discovery port based on instance number
                 self.port,
             ))
+            .apply(|builder| match self.addr_dual_stack {
+                Some(addr_dual_stack) => {
+                    builder.dual_stack_listener_addr(SocketAddr::new(addr_dual_stack, self.port))
+                }
+                None => builder,
+            })
             .discovery_addr(SocketAddr::new(
                 self.discovery.addr,
                 // set discovery port based on instance number
                 self.discovery.port,
             ))
+            .apply(|builder| match &self.record_sessions {
+                Some(path) => builder.record_sessions(path),
+                None => builder,
+            })
+    }
+
+    /// Returns the [`DiscoveryArgs`], with the discv5 address of the IP version of
+    /// `--addr.dual-stack` set to it, if not configured otherwise.
+    fn discovery_args_dual_stack(&self) -> Cow<'_, DiscoveryArgs> {
+        match self.addr_dual_stack {
+            Some(IpAddr::V4(addr)) if self.discovery.discv5_addr.is_none() => {
+                Cow::Owned(DiscoveryArgs { discv5_addr: Some(addr), ..self.discovery.clone() })
+            }
+            Some(IpAddr::V6(addr)) if self.discovery.discv5_addr_ipv6.is_none() => {
+                Cow::Owned(DiscoveryArgs { discv5_addr_ipv6: Some(addr), ..self.discovery.clone() })
+            }
+            _ => Cow::Borrowed(&self.discovery),
+        }
     }
 
     /// If `no_persist_peers` is false then this returns the path to the persistent peers file path.
@@ -271,6 +345,11 @@ impl NetworkArgs {
         self.no_persist_peers.not().then_some(peers_file)
     }
 
+    /// Returns true if the `snap/1` protocol is enabled.
+    pub const fn snap_enabled(&self) -> bool {
+        self.snap_serve || self.snap_sync
+    }
+
     /// Sets the p2p port to zero, to allow the OS to assign a random unused port when
     /// the network components bind to a socket.
     pub const fn with_unused_p2p_port(mut self) -> Self {
@@ -321,6 +400,7 @@ impl Default for NetworkArgs {
             nat: NatResolver::Any,
             addr: DEFAULT_DISCOVERY_ADDR,
             port: DEFAULT_DISCOVERY_PORT,
+            addr_dual_stack: None,
             max_outbound_peers: None,
             max_inbound_peers: None,
             max_concurrent_tx_requests: DEFAULT_MAX_COUNT_CONCURRENT_REQUESTS,
@@ -331,7 +411,12 @@ impl Default for NetworkArgs {
             max_pending_pool_imports: DEFAULT_MAX_COUNT_PENDING_POOL_IMPORTS,
             max_seen_tx_history: DEFAULT_MAX_COUNT_TRANSACTIONS_SEEN_BY_PEER,
             max_capacity_cache_txns_pending_fetch: DEFAULT_MAX_CAPACITY_CACHE_PENDING_FETCH,
+            tx_gossip_trusted_only: false,
+            tx_gossip_private_peers: None,
+            record_sessions: None,
             net_if: None,
+            snap_serve: false,
+            snap_sync: false,
         }
     }
 }
@@ -591,6 +676,22 @@ mod tests {
         }
     }
 
+    #[test]
+    fn parse_tx_gossip_args() {
+        let peer_id = PeerId::repeat_byte(0x11);
+        let args = CommandParser::<NetworkArgs>::parse_from([
+            "reth",
+            "--tx-gossip.trusted-only",
+            "--tx-gossip.private-peers",
+            &peer_id.to_string(),
+        ])
+        .args;
+
+        let policy = args.tx_gossip_policy();
+        assert!(policy.trusted_peers_only);
+        assert_eq!(policy.private_peers, Some([peer_id].into()));
+    }
+
     #[cfg(not(feature = "optimism"))]
     #[test]
     fn network_args_default_sanity_test() {
@@ -6,7 +6,7 @@ use clap::{builder::RangedU64ValueParser, Args};
 use reth_chainspec::EthChainSpec;
 use reth_config::config::PruneConfig;
 use reth_prune_types::{PruneMode, PruneModes, ReceiptsLogPruneConfig, MINIMUM_PRUNING_DISTANCE};
-use std::collections::BTreeMap;
+use std::{collections::BTreeMap, path::PathBuf};
 
 /// Parameters for pruning and full node
 #[derive(Debug, Clone, Args, PartialEq, Eq, Default)]
@@ -82,6 +82,23 @@ pub struct PruningArgs {
     #[arg(long = "prune.storagehistory.before", value_name = "BLOCK_NUMBER", conflicts_with_all = &["storage_history_full", "storage_history_distance"])]
     pub storage_history_before: Option<BlockNumber>,
 
+    // History
+    /// Expires all pre-merge block history (headers, transactions and receipts) as described in
+    /// EIP-4444.
+    #[arg(long = "prune.history.pre-merge", conflicts_with_all = &["history_distance", "history_before"])]
+    pub history_pre_merge: bool,
+    /// Expire block history before the `head-N` block number. In other words, keep last N + 1
+    /// blocks.
+    #[arg(long = "prune.history.distance", value_name = "BLOCKS", conflicts_with_all = &["history_pre_merge", "history_before"])]
+    pub history_distance: Option<u64>,
+    /// Expire block history before the specified block number. The specified block number is not
+    /// expired.
+    #[arg(long = "prune.history.before", value_name = "BLOCK_NUMBER", conflicts_with_all = &["history_pre_merge", "history_distance"])]
+    pub history_before: Option<BlockNumber>,
+    /// Directory with Era1 files to serve expired block history from.
+    #[arg(long = "prune.history.era1-dir", value_name = "PATH")]
+    pub history_era1_dir: Option<PathBuf>,
+
     // Receipts Log Filter
     /// Configure receipts log filter. Format:
     /// <`address`>:<`prune_mode`>[,<`address`>:<`prune_mode`>...] Where <`prune_mode`> can be
@@ -91,6 +108,17 @@ pub struct PruningArgs {
 }
 
 impl PruningArgs {
+    /// Returns an error if the arguments can't be applied to the given chain.
+    pub fn validate(&self, chain_spec: &impl EthChainSpec) -> eyre::Result<()> {
+        if self.history_pre_merge && chain_spec.paris_block().is_none() {
+            eyre::bail!(
+                "--prune.history.pre-merge requires a chain with a known merge block, chain {} has none",
+                chain_spec.chain()
+            )
+        }
+        Ok(())
+    }
+
     /// Returns pruning configuration.
     pub fn prune_config(&self, chain_spec: &impl EthChainSpec) -> Option<PruneConfig> {
         // Initialise with a default prune configuration.
@@ -111,6 +139,7 @@ impl PruningArgs {
                         .or(Some(PruneMode::Distance(MINIMUM_PRUNING_DISTANCE))),
                     account_history: Some(PruneMode::Distance(MINIMUM_PRUNING_DISTANCE)),
                     storage_history: Some(PruneMode::Distance(MINIMUM_PRUNING_DISTANCE)),
+                    history: None,
                     receipts_log_filter: ReceiptsLogPruneConfig(
                         chain_spec
                             .deposit_contract()
@@ -141,6 +170,9 @@ impl PruningArgs {
         if let Some(mode) = self.storage_history_prune_mode() {
             config.segments.storage_history = Some(mode);
         }
+        if let Some(mode) = self.history_prune_mode(chain_spec) {
+            config.segments.history = Some(mode);
+        }
 
         Some(config)
     }
@@ -203,6 +235,16 @@ impl PruningArgs {
             None
         }
     }
+
+    fn history_prune_mode(&self, chain_spec: &impl EthChainSpec) -> Option<PruneMode> {
+        if self.history_pre_merge {
+            chain_spec.paris_block().map(PruneMode::Before)
+        } else if let Some(distance) = self.history_distance {
+            Some(PruneMode::Distance(distance))
+        } else {
+            self.history_before.map(PruneMode::Before)
+        }
+    }
 }
 
 pub(crate) fn parse_receipts_log_filter(
@@ -267,6 +309,20 @@ mod tests {
         assert_eq!(args, default_args);
     }
 
+    #[test]
+    fn history_pre_merge_requires_merge_block() {
+        let args =
+            CommandParser::<PruningArgs>::parse_from(["reth", "--prune.history.pre-merge"]).args;
+
+        assert!(args.validate(&*reth_chainspec::MAINNET).is_ok());
+        assert_eq!(
+            args.prune_config(&*reth_chainspec::MAINNET).and_then(|config| config.segments.history),
+            Some(PruneMode::Before(15537394))
+        );
+
+        assert!(args.validate(&reth_chainspec::ChainSpec::default()).is_err());
+    }
+
     #[test]
     fn test_parse_receipts_log_filter() {
         let filter1 = "0x0000000000000000000000000000000000000001:full";
@@ -4,6 +4,7 @@ use std::{
     ffi::OsStr,
     net::{IpAddr, Ipv4Addr},
     path::PathBuf,
+    time::Duration,
 };
 
 use alloy_rpc_types_engine::JwtSecret;
@@ -11,6 +12,7 @@ use clap::{
     builder::{PossibleValue, RangedU64ValueParser, TypedValueParser},
     Arg, Args, Command,
 };
+use humantime::parse_duration;
 use rand::Rng;
 use reth_rpc_server_types::{constants, RethRpcModule, RpcModuleSelection};
 
@@ -169,6 +171,14 @@ pub struct RpcServerArgs {
     )]
     pub rpc_max_simulate_blocks: u64,
 
+    /// Maximum number of blocks for `debug_traceChain` and `debug_subscribeTraceChain` calls.
+    #[arg(
+        long = "rpc.max-trace-chain-blocks",
+        value_name = "BLOCKS_COUNT",
+        default_value_t = constants::DEFAULT_MAX_TRACE_CHAIN_BLOCKS
+    )]
+    pub rpc_max_trace_chain_blocks: u64,
+
     /// The maximum proof window for historical proof generation.
     /// This value allows for generating historical proofs up to
     /// configured number of blocks from current tip (up to `tip - window`).
@@ -183,6 +193,53 @@ pub struct RpcServerArgs {
     #[arg(long = "rpc.proof-permits", alias = "rpc-proof-permits", value_name = "COUNT", default_value_t = constants::DEFAULT_PROOF_PERMITS)]
     pub rpc_proof_permits: usize,
 
+    /// Maximum time a custom JS tracer may spend on a single transaction.
+    ///
+    /// Parses strings using [`humantime::parse_duration`]
+    /// --rpc.js-tracer-timeout 10s
+    #[arg(
+        long = "rpc.js-tracer-timeout",
+        value_name = "DURATION",
+        value_parser = parse_duration,
+        default_value = "5s",
+        verbatim_doc_comment
+    )]
+    pub rpc_js_tracer_timeout: Duration,
+
+    /// Maximum memory in bytes a custom JS tracer may work with.
+    ///
+    /// Bounds the heap growth of the tracing thread and the size of the result.
+    #[arg(long = "rpc.js-tracer-memory-limit", value_name = "BYTES", default_value_t = constants::DEFAULT_JS_TRACER_MEMORY_LIMIT)]
+    pub rpc_js_tracer_memory_limit: usize,
+
+    /// Maximum number of loop iterations a single call of a custom JS tracer hook may execute.
+    #[arg(long = "rpc.js-tracer-loop-iteration-limit", value_name = "COUNT", default_value_t = constants::DEFAULT_JS_TRACER_LOOP_ITERATION_LIMIT)]
+    pub rpc_js_tracer_loop_iteration_limit: u64,
+
+    /// Maximum recursion depth of a custom JS tracer.
+    #[arg(long = "rpc.js-tracer-recursion-limit", value_name = "DEPTH", default_value_t = constants::DEFAULT_JS_TRACER_RECURSION_LIMIT)]
+    pub rpc_js_tracer_recursion_limit: usize,
+
+    /// Maintain an index of the addresses appearing in transaction traces.
+    ///
+    /// Allows `trace_filter` requests with address filters to only trace the blocks the
+    /// addresses appeared in. The index is built in the background, starting from genesis.
+    #[arg(long = "rpc.trace-index", default_value_t = false)]
+    pub rpc_trace_index: bool,
+
+    /// Path to a geth-style keystore directory whose accounts are available for signing.
+    ///
+    /// The accounts are returned by `eth_accounts` and can be used with `eth_sign`,
+    /// `eth_signTransaction`, `eth_signTypedData` and `eth_sendTransaction`.
+    #[arg(long = "rpc.keystore", value_name = "PATH", requires = "rpc_keystore_password_file")]
+    pub rpc_keystore: Option<PathBuf>,
+
+    /// Path to the file with the passwords of the keystore files, one per line.
+    ///
+    /// Every key file is decrypted with the first password that matches.
+    #[arg(long = "rpc.keystore-password-file", value_name = "PATH", requires = "rpc_keystore")]
+    pub rpc_keystore_password_file: Option<PathBuf>,
+
     /// State cache configuration.
     #[command(flatten)]
     pub rpc_state_cache: RpcStateCacheArgs,
@@ -314,10 +371,18 @@ impl Default for RpcServerArgs {
             rpc_max_logs_per_response: (constants::DEFAULT_MAX_LOGS_PER_RESPONSE as u64).into(),
             rpc_gas_cap: constants::gas_oracle::RPC_DEFAULT_GAS_CAP,
             rpc_max_simulate_blocks: constants::DEFAULT_MAX_SIMULATE_BLOCKS,
+            rpc_max_trace_chain_blocks: constants::DEFAULT_MAX_TRACE_CHAIN_BLOCKS,
             rpc_eth_proof_window: constants::DEFAULT_ETH_PROOF_WINDOW,
             gas_price_oracle: GasPriceOracleArgs::default(),
             rpc_state_cache: RpcStateCacheArgs::default(),
             rpc_proof_permits: constants::DEFAULT_PROOF_PERMITS,
+            rpc_js_tracer_timeout: constants::DEFAULT_JS_TRACER_TIMEOUT,
+            rpc_js_tracer_memory_limit: constants::DEFAULT_JS_TRACER_MEMORY_LIMIT,
+            rpc_js_tracer_loop_iteration_limit: constants::DEFAULT_JS_TRACER_LOOP_ITERATION_LIMIT,
+            rpc_js_tracer_recursion_limit: constants::DEFAULT_JS_TRACER_RECURSION_LIMIT,
+            rpc_trace_index: false,
+            rpc_keystore: None,
+            rpc_keystore_password_file: None,
         }
     }
 }
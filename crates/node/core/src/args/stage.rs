@@ -50,4 +50,8 @@ pub enum StageEnum {
     ///
     /// Manages historical data related to storage.
     StorageHistory,
+    /// The transaction appearances stage within the pipeline.
+    ///
+    /// Indexes the blocks in which an address sent or received a transaction.
+    TransactionAppearances,
 }
@@ -13,7 +13,7 @@ use std::marker::PhantomData;
 use reth_chainspec::EthChainSpec;
 use reth_db_api::{
     database_metrics::{DatabaseMetadata, DatabaseMetrics},
-    Database,
+    Database, DatabaseCopy,
 };
 use reth_engine_primitives::EngineTypes;
 
@@ -48,7 +48,7 @@ pub trait NodeTypesWithEngine: NodeTypes {
 /// Its types are configured by node internally and are not intended to be user configurable.
 pub trait NodeTypesWithDB: NodeTypes {
     /// Underlying database type used by the node to store and retrieve data.
-    type DB: Database + DatabaseMetrics + DatabaseMetadata + Clone + Unpin + 'static;
+    type DB: Database + DatabaseCopy + DatabaseMetrics + DatabaseMetadata + Clone + Unpin + 'static;
 }
 
 /// An adapter type combining [`NodeTypes`] and db into [`NodeTypesWithDB`].
@@ -97,7 +97,7 @@ where
 impl<Types, DB> NodeTypesWithDB for NodeTypesWithDBAdapter<Types, DB>
 where
     Types: NodeTypes,
-    DB: Database + DatabaseMetrics + DatabaseMetadata + Clone + Unpin + 'static,
+    DB: Database + DatabaseCopy + DatabaseMetrics + DatabaseMetadata + Clone + Unpin + 'static,
 {
     type DB = DB;
 }
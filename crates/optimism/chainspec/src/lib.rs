@@ -229,6 +229,10 @@ impl EthChainSpec for OpChainSpec {
         self.inner.bootnodes()
     }
 
+    fn paris_block(&self) -> Option<u64> {
+        self.inner.paris_block()
+    }
+
     fn is_optimism(&self) -> bool {
         true
     }
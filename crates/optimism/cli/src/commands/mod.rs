@@ -6,7 +6,7 @@ use reth_cli::chainspec::ChainSpecParser;
 use reth_cli_commands::{
     config_cmd, db, dump_genesis, init_cmd,
     node::{self, NoArgs},
-    p2p, prune, recover, stage,
+    p2p, prune, recover, stage, static_file,
 };
 use std::fmt;
 
@@ -55,4 +55,7 @@ pub enum Commands<Spec: ChainSpecParser = OpChainSpecParser, Ext: clap::Args + f
     /// Prune according to the configuration without any limits
     #[command(name = "prune")]
     Prune(prune::PruneCommand<Spec>),
+    /// Static file utilities
+    #[command(name = "static-file")]
+    StaticFile(static_file::Command<Spec>),
 }
@@ -165,6 +165,9 @@ where
                 runner.run_command_until_exit(|ctx| command.execute::<OptimismNode>(ctx))
             }
             Commands::Prune(command) => runner.run_until_ctrl_c(command.execute::<OptimismNode>()),
+            Commands::StaticFile(command) => {
+                runner.run_blocking_until_ctrl_c(command.execute::<OptimismNode>())
+            }
         }
     }
 
@@ -11,7 +11,7 @@ pub use receipt::{OpReceiptBuilder, OpReceiptFieldsBuilder};
 
 use std::{fmt, sync::Arc};
 
-use alloy_primitives::U256;
+use alloy_primitives::{Address, U256};
 use derive_more::Deref;
 use op_alloy_network::Optimism;
 use reth_chainspec::EthereumHardforks;
@@ -132,6 +132,11 @@ where
     fn signers(&self) -> &parking_lot::RwLock<Vec<Box<dyn EthSigner>>> {
         self.inner.signers()
     }
+
+    #[inline]
+    fn suggested_fee_recipient(&self) -> &parking_lot::RwLock<Option<Address>> {
+        self.inner.suggested_fee_recipient()
+    }
 }
 
 impl<N> SpawnBlocking for OpEthApi<N>
@@ -242,6 +247,10 @@ where
     fn with_dev_accounts(&self) {
         *self.signers().write() = DevSigner::random_signers(20)
     }
+
+    fn add_signer(&self, signer: Box<dyn EthSigner>) {
+        self.signers().write().push(signer)
+    }
 }
 
 impl<N: FullNodeComponents> fmt::Debug for OpEthApi<N> {
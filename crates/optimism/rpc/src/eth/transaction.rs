@@ -119,6 +119,10 @@ impl TransactionCompat for OpTxBuilder {
         tx.inner.input = tx.inner.input.slice(..4);
     }
 
+    fn otterscan_api_transaction(tx: Self::Transaction) -> alloy_rpc_types::Transaction {
+        tx.inner
+    }
+
     fn tx_type(tx: &Self::Transaction) -> u8 {
         tx.inner.transaction_type.unwrap_or_default()
     }
@@ -330,9 +330,29 @@ where
 
 #[cfg(test)]
 mod tests {
-    use crate::Pruner;
+    use crate::{
+        segments::{PruneInput, ReceiptsByLogs, Segment},
+        Pruner, PrunerBuilder,
+    };
+    use alloy_primitives::B256;
+    use reth_chainspec::MAINNET;
+    use reth_db::{
+        tables,
+        test_utils::{create_test_rw_db, create_test_static_files_dir},
+    };
     use reth_exex_types::FinishedExExHeight;
-    use reth_provider::test_utils::create_test_provider_factory;
+    use reth_provider::{
+        providers::StaticFileProvider, test_utils::create_test_provider_factory,
+        DatabaseProviderFactory, ProviderFactory, PruneCheckpointReader, StaticFileProviderFactory,
+    };
+    use reth_prune_types::{
+        PruneLimiter, PruneMode, PruneModes, PruneSegment, ReceiptsLogPruneConfig,
+    };
+    use reth_stages::test_utils::{StorageKind, TestStageDB};
+    use reth_testing_utils::generators::{
+        self, random_block_range, random_receipt, BlockRangeParams,
+    };
+    use std::collections::BTreeMap;
 
     #[test]
     fn is_pruning_needed() {
@@ -373,4 +393,106 @@ mod tests {
         finished_exex_height_tx.send(FinishedExExHeight::Height(third_block_number)).unwrap();
         assert!(pruner.is_pruning_needed(third_block_number));
     }
+
+    #[test]
+    fn history_expiry_with_transaction_lookup_and_receipts_by_logs() {
+        let (temp_static_files_dir, static_dir_path) = create_test_static_files_dir();
+        let db = TestStageDB {
+            factory: ProviderFactory::new(
+                create_test_rw_db(),
+                MAINNET.clone(),
+                StaticFileProvider::read_write(static_dir_path)
+                    .unwrap()
+                    .with_custom_blocks_per_file(5_000),
+            ),
+            temp_static_files_dir,
+        };
+        let mut rng = generators::rng();
+
+        let tip = 25_000;
+        let blocks = random_block_range(
+            &mut rng,
+            0..=tip,
+            BlockRangeParams { parent: Some(B256::ZERO), tx_count: 1..2, ..Default::default() },
+        );
+        db.insert_blocks(blocks.iter(), StorageKind::Static).expect("insert blocks");
+
+        let transactions = blocks.iter().flat_map(|block| &block.body.transactions);
+        db.insert_tx_hash_numbers(
+            transactions.clone().zip(0..).map(|(tx, number)| (tx.hash, number)),
+        )
+        .expect("insert tx hash numbers");
+        db.insert_receipts(
+            transactions
+                .zip(0..)
+                .map(|(tx, number)| (number, random_receipt(&mut rng, tx, Some(0)))),
+        )
+        .expect("insert receipts");
+
+        let run_pruner = |segments: PruneModes| {
+            let mut pruner = PrunerBuilder::default()
+                .segments(segments)
+                .delete_limit(100_000)
+                .build_with_provider_factory(db.factory.clone());
+            while !pruner.run(tip).expect("prune").progress.is_finished() {}
+        };
+
+        // History was expired before the other segments were enabled
+        run_pruner(PruneModes { history: Some(PruneMode::Before(12_000)), ..PruneModes::none() });
+        assert_eq!(db.factory.static_file_provider().earliest_history_block(), Some(10_000));
+
+        // Blocks below the earliest history block are skipped instead of failing to read their
+        // transactions
+        let receipts_log_filter = ReceiptsLogPruneConfig(BTreeMap::from([(
+            blocks[0].beneficiary,
+            PruneMode::Before(tip - 10_064),
+        )]));
+        run_pruner(PruneModes {
+            history: Some(PruneMode::Before(12_000)),
+            transaction_lookup: Some(PruneMode::Before(15_000)),
+            receipts_log_filter: receipts_log_filter.clone(),
+            ..PruneModes::none()
+        });
+
+        let provider = db.factory.provider().unwrap();
+        assert_eq!(
+            provider
+                .get_prune_checkpoint(PruneSegment::TransactionLookup)
+                .unwrap()
+                .and_then(|checkpoint| checkpoint.block_number),
+            Some(14_999)
+        );
+        // One transaction per block, the entries of the expired blocks are left untouched
+        assert_eq!(db.table::<tables::TransactionHashNumbers>().unwrap().len(), 10_000 + 10_001);
+        drop(provider);
+
+        // The pruner doesn't run segments without a prune mode, so the receipts by logs segment
+        // is run directly
+        let provider = db.factory.database_provider_rw().unwrap();
+        let output = ReceiptsByLogs::new(receipts_log_filter, db.factory.static_file_provider())
+            .prune(
+                &provider,
+                PruneInput {
+                    previous_checkpoint: None,
+                    to_block: tip,
+                    limiter: PruneLimiter::default(),
+                },
+            )
+            .expect("prune receipts by logs");
+        provider.commit().unwrap();
+        assert!(output.progress.is_finished());
+
+        // Receipts of the expired blocks are skipped, the ones up to the segment target are pruned
+        let to_block = tip - 10_064;
+        assert_eq!(
+            db.factory
+                .provider()
+                .unwrap()
+                .get_prune_checkpoint(PruneSegment::ContractLogs)
+                .unwrap()
+                .and_then(|checkpoint| checkpoint.block_number),
+            Some(to_block)
+        );
+        assert_eq!(db.table::<tables::Receipts>().unwrap().len() as u64, 10_000 + (tip - to_block));
+    }
 }
@@ -11,14 +11,15 @@ use reth_prune_types::{
 };
 pub use set::SegmentSet;
 pub use static_file::{
-    Headers as StaticFileHeaders, Receipts as StaticFileReceipts,
+    AccountChangeSets as StaticFileAccountChangeSets, Headers as StaticFileHeaders,
+    Receipts as StaticFileReceipts, StorageChangeSets as StaticFileStorageChangeSets,
     Transactions as StaticFileTransactions,
 };
 use std::{fmt::Debug, ops::RangeInclusive};
 use tracing::error;
 pub use user::{
-    AccountHistory, Receipts as UserReceipts, ReceiptsByLogs, SenderRecovery, StorageHistory,
-    TransactionLookup,
+    AccountHistory, HistoryExpiry, Receipts as UserReceipts, ReceiptsByLogs, SenderRecovery,
+    StorageHistory, TransactionAppearances, TransactionLookup,
 };
 
 /// A segment represents a pruning of some portion of the data.
@@ -130,6 +131,36 @@ impl PruneInput {
         Some(range)
     }
 
+    /// Moves the previous checkpoint up to the block before `earliest_history_block`, capped at
+    /// `to_block`, so that segments which read headers, transactions or receipts skip the blocks
+    /// whose history has expired, see [`HistoryExpiry`].
+    ///
+    /// Returns `true` if the checkpoint was moved.
+    pub(crate) fn skip_expired_history<Provider: BlockReader>(
+        &mut self,
+        provider: &Provider,
+        earliest_history_block: Option<BlockNumber>,
+    ) -> ProviderResult<bool> {
+        let Some(last_expired_block) =
+            earliest_history_block.map(|block| block.saturating_sub(1).min(self.to_block))
+        else {
+            return Ok(false)
+        };
+        if self.get_start_next_block_range() > last_expired_block {
+            return Ok(false)
+        }
+
+        let tx_number =
+            provider.block_body_indices(last_expired_block)?.map(|body| body.last_tx_num());
+        self.previous_checkpoint = Some(PruneCheckpoint {
+            block_number: Some(last_expired_block),
+            tx_number,
+            prune_mode: PruneMode::Before(last_expired_block + 1),
+        });
+
+        Ok(true)
+    }
+
     /// Returns the start of the next block range.
     ///
     /// 1. If checkpoint exists, use next block.
@@ -1,6 +1,6 @@
 use crate::segments::{
-    AccountHistory, ReceiptsByLogs, Segment, SenderRecovery, StorageHistory, TransactionLookup,
-    UserReceipts,
+    AccountHistory, HistoryExpiry, ReceiptsByLogs, Segment, SenderRecovery, StorageHistory,
+    TransactionAppearances, TransactionLookup, UserReceipts,
 };
 use reth_db::transaction::DbTxMut;
 use reth_provider::{
@@ -9,7 +9,10 @@ use reth_provider::{
 };
 use reth_prune_types::PruneModes;
 
-use super::{StaticFileHeaders, StaticFileReceipts, StaticFileTransactions};
+use super::{
+    StaticFileAccountChangeSets, StaticFileHeaders, StaticFileReceipts,
+    StaticFileStorageChangeSets, StaticFileTransactions,
+};
 
 /// Collection of [Segment]. Thread-safe, allocated on the heap.
 #[derive(Debug)]
@@ -59,6 +62,7 @@ where
             receipts,
             account_history,
             storage_history,
+            history,
             receipts_log_filter,
         } = prune_modes;
 
@@ -68,22 +72,37 @@ where
             // Static file transactions
             .segment(StaticFileTransactions::new(static_file_provider.clone()))
             // Static file receipts
-            .segment(StaticFileReceipts::new(static_file_provider))
+            .segment(StaticFileReceipts::new(static_file_provider.clone()))
+            // Static file account changesets
+            .segment(StaticFileAccountChangeSets::new(static_file_provider.clone()))
+            // Static file storage changesets
+            .segment(StaticFileStorageChangeSets::new(static_file_provider.clone()))
             // Account history
             .segment_opt(account_history.map(AccountHistory::new))
+            // Transaction appearances, pruned with the account history since both back the
+            // search for the transactions of an address
+            .segment_opt(
+                account_history
+                    .map(|mode| TransactionAppearances::new(mode, static_file_provider.clone())),
+            )
             // Storage history
             .segment_opt(storage_history.map(StorageHistory::new))
             // User receipts
             .segment_opt(receipts.map(UserReceipts::new))
             // Receipts by logs
+            .segment_opt((!receipts_log_filter.is_empty()).then(|| {
+                ReceiptsByLogs::new(receipts_log_filter.clone(), static_file_provider.clone())
+            }))
+            // Transaction lookup
             .segment_opt(
-                (!receipts_log_filter.is_empty())
-                    .then(|| ReceiptsByLogs::new(receipts_log_filter.clone())),
+                transaction_lookup
+                    .map(|mode| TransactionLookup::new(mode, static_file_provider.clone())),
             )
-            // Transaction lookup
-            .segment_opt(transaction_lookup.map(TransactionLookup::new))
             // Sender recovery
             .segment_opt(sender_recovery.map(SenderRecovery::new))
+            // History expiry, last so that the segments above can still read the history they
+            // prune in the same run
+            .segment_opt(history.map(|mode| HistoryExpiry::new(mode, static_file_provider)))
     }
 }
 
@@ -0,0 +1,183 @@
+use crate::{
+    db_ext::DbTxPruneExt,
+    segments::{PruneInput, Segment},
+    PrunerError,
+};
+use reth_db::{tables, transaction::DbTxMut};
+use reth_db_api::models::BlockNumberAddress;
+use reth_provider::{providers::StaticFileProvider, DBProvider};
+use reth_prune_types::{
+    PruneMode, PruneProgress, PrunePurpose, PruneSegment, SegmentOutput, SegmentOutputCheckpoint,
+};
+use reth_static_file_types::StaticFileSegment;
+use tracing::trace;
+
+#[derive(Debug)]
+pub struct StorageChangeSets {
+    static_file_provider: StaticFileProvider,
+}
+
+impl StorageChangeSets {
+    pub const fn new(static_file_provider: StaticFileProvider) -> Self {
+        Self { static_file_provider }
+    }
+}
+
+impl<Provider: DBProvider<Tx: DbTxMut>> Segment<Provider> for StorageChangeSets {
+    fn segment(&self) -> PruneSegment {
+        PruneSegment::StorageChangeSets
+    }
+
+    fn mode(&self) -> Option<PruneMode> {
+        self.static_file_provider
+            .get_highest_static_file_block(StaticFileSegment::StorageChangeSets)
+            .map(PruneMode::before_inclusive)
+    }
+
+    fn purpose(&self) -> PrunePurpose {
+        PrunePurpose::StaticFile
+    }
+
+    fn prune(&self, provider: &Provider, input: PruneInput) -> Result<SegmentOutput, PrunerError> {
+        let range = match input.get_next_block_range() {
+            Some(range) => range,
+            None => {
+                trace!(target: "pruner", "No storage changesets to prune");
+                return Ok(SegmentOutput::done())
+            }
+        };
+        let range_end = *range.end();
+
+        let mut limiter = input.limiter;
+
+        let mut last_pruned_block = None;
+        let (pruned, done) =
+            provider.tx_ref().prune_table_with_range::<tables::StorageChangeSets>(
+                BlockNumberAddress::range(range),
+                &mut limiter,
+                |_| false,
+                |(BlockNumberAddress((block_number, _)), _)| last_pruned_block = Some(block_number),
+            )?;
+        trace!(target: "pruner", %pruned, %done, "Pruned storage changesets");
+
+        let last_pruned_block = last_pruned_block
+            // If there's more storage changesets to prune, set the checkpoint block number to
+            // previous, so we could finish pruning its storage changesets on the next run.
+            .map(|block_number| if done { block_number } else { block_number.saturating_sub(1) })
+            .unwrap_or(range_end);
+
+        let progress = PruneProgress::new(done, &limiter);
+
+        Ok(SegmentOutput {
+            progress,
+            pruned,
+            checkpoint: Some(SegmentOutputCheckpoint {
+                block_number: Some(last_pruned_block),
+                tx_number: None,
+            }),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::segments::{PruneInput, Segment, SegmentOutput};
+    use alloy_primitives::B256;
+    use assert_matches::assert_matches;
+    use reth_db::tables;
+    use reth_provider::{
+        DatabaseProviderFactory, PruneCheckpointReader, StaticFileProviderFactory,
+    };
+    use reth_prune_types::{
+        PruneInterruptReason, PruneLimiter, PruneMode, PruneProgress, PruneSegment,
+    };
+    use reth_stages::test_utils::{StorageKind, TestStageDB};
+    use reth_static_file_types::StaticFileSegment;
+    use reth_testing_utils::generators::{
+        self, random_block_range, random_changeset_range, random_eoa_accounts, BlockRangeParams,
+    };
+
+    #[test]
+    fn prune() {
+        let db = TestStageDB::default();
+        let mut rng = generators::rng();
+
+        let blocks = random_block_range(
+            &mut rng,
+            0..=100,
+            BlockRangeParams { parent: Some(B256::ZERO), tx_count: 0..1, ..Default::default() },
+        );
+        db.insert_blocks(blocks.iter(), StorageKind::Database(None)).expect("insert blocks");
+
+        let accounts = random_eoa_accounts(&mut rng, 4);
+        let (changesets, _) = random_changeset_range(
+            &mut rng,
+            blocks.iter(),
+            accounts.into_iter().map(|(address, account)| (address, (account, Vec::new()))),
+            1..3,
+            0..20,
+        );
+        db.insert_changesets(changesets, None).expect("insert changesets");
+        let original = db.table::<tables::StorageChangeSets>().unwrap();
+
+        // Changesets of blocks `0..=60` were moved to static files.
+        let static_file_provider = db.factory.static_file_provider();
+        let mut writer =
+            static_file_provider.latest_writer(StaticFileSegment::StorageChangeSets).unwrap();
+        for block in 0..=60 {
+            writer.increment_block(block).unwrap();
+        }
+        writer.commit().unwrap();
+        drop(writer);
+        let prune_mode = PruneMode::before_inclusive(60);
+
+        let test_prune = |limiter: PruneLimiter| {
+            let segment = super::StorageChangeSets::new(db.factory.static_file_provider());
+            let provider = db.factory.database_provider_rw().unwrap();
+            let input = PruneInput {
+                previous_checkpoint: provider
+                    .get_prune_checkpoint(PruneSegment::StorageChangeSets)
+                    .unwrap(),
+                to_block: 60,
+                limiter,
+            };
+            let result = segment.prune(&provider, input).unwrap();
+            segment
+                .save_checkpoint(
+                    &provider,
+                    result.checkpoint.unwrap().as_prune_checkpoint(prune_mode),
+                )
+                .unwrap();
+            provider.commit().expect("commit");
+            result
+        };
+
+        // The limit is reached within a block, so its remaining changes are pruned on the next
+        // run.
+        let result = test_prune(PruneLimiter::default().set_deleted_entries_limit(10));
+        assert_matches!(
+            result,
+            SegmentOutput {
+                progress: PruneProgress::HasMoreData(
+                    PruneInterruptReason::DeletedEntriesLimitReached
+                ),
+                pruned: 10,
+                checkpoint: Some(_),
+            }
+        );
+        let checkpoint = result.checkpoint.unwrap().block_number.unwrap();
+        assert_eq!(db.table::<tables::StorageChangeSets>().unwrap(), original[10..].to_vec());
+        assert!(original[..10].iter().all(|(key, _)| key.block_number() <= checkpoint + 1));
+
+        let result = test_prune(PruneLimiter::default());
+        assert_matches!(
+            result,
+            SegmentOutput { progress: PruneProgress::Finished, checkpoint: Some(_), .. }
+        );
+        assert_eq!(result.checkpoint.unwrap().block_number, Some(60));
+        assert_eq!(
+            db.table::<tables::StorageChangeSets>().unwrap(),
+            original.into_iter().filter(|(key, _)| key.block_number() > 60).collect::<Vec<_>>()
+        );
+    }
+}
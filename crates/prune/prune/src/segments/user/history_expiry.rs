@@ -0,0 +1,106 @@
+use crate::{
+    segments::{PruneInput, Segment, SegmentOutput},
+    PrunerError,
+};
+use reth_provider::providers::StaticFileProvider;
+use reth_prune_types::{
+    PruneMode, PruneProgress, PrunePurpose, PruneSegment, SegmentOutputCheckpoint,
+};
+use tracing::{instrument, trace};
+
+/// Expires headers, transactions and receipts from static files, as described in
+/// [EIP-4444](https://eips.ethereum.org/EIPS/eip-4444).
+///
+/// Only whole static files are deleted, see [`StaticFileProvider::expire_history`].
+#[derive(Debug)]
+pub struct HistoryExpiry {
+    mode: PruneMode,
+    static_file_provider: StaticFileProvider,
+}
+
+impl HistoryExpiry {
+    pub const fn new(mode: PruneMode, static_file_provider: StaticFileProvider) -> Self {
+        Self { mode, static_file_provider }
+    }
+}
+
+impl<Provider> Segment<Provider> for HistoryExpiry {
+    fn segment(&self) -> PruneSegment {
+        PruneSegment::History
+    }
+
+    fn mode(&self) -> Option<PruneMode> {
+        Some(self.mode)
+    }
+
+    fn purpose(&self) -> PrunePurpose {
+        PrunePurpose::User
+    }
+
+    #[instrument(level = "trace", target = "pruner", skip(self, _provider), ret)]
+    fn prune(&self, _provider: &Provider, input: PruneInput) -> Result<SegmentOutput, PrunerError> {
+        let expired = self.static_file_provider.expire_history(input.to_block + 1)?;
+        trace!(target: "pruner", ?expired, "Expired history");
+
+        let Some(earliest_block) = self.static_file_provider.earliest_history_block() else {
+            return Ok(SegmentOutput::done())
+        };
+
+        Ok(SegmentOutput {
+            progress: PruneProgress::Finished,
+            pruned: expired.len(),
+            checkpoint: Some(SegmentOutputCheckpoint {
+                block_number: Some(earliest_block - 1),
+                tx_number: None,
+            }),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::segments::{user::HistoryExpiry, PruneInput, Segment};
+    use alloy_primitives::{B256, U256};
+    use reth_db::test_utils::create_test_static_files_dir;
+    use reth_provider::{
+        providers::{StaticFileProvider, StaticFileWriter},
+        HeaderProvider, ProviderError,
+    };
+    use reth_prune_types::{PruneLimiter, PruneMode};
+    use reth_static_file_types::StaticFileSegment;
+    use reth_testing_utils::generators::{self, random_header_range};
+
+    #[test]
+    fn prune() {
+        let (static_dir, _) = create_test_static_files_dir();
+        let static_file_provider =
+            StaticFileProvider::read_write(&static_dir).unwrap().with_custom_blocks_per_file(10);
+
+        let headers = random_header_range(&mut generators::rng(), 0..30, B256::ZERO);
+        let mut writer = static_file_provider.latest_writer(StaticFileSegment::Headers).unwrap();
+        for header in headers {
+            let hash = header.hash();
+            writer.append_header(&header.unseal(), U256::ZERO, &hash).unwrap();
+        }
+        writer.commit().unwrap();
+        drop(writer);
+
+        let segment = HistoryExpiry::new(PruneMode::Before(15), static_file_provider.clone());
+        let input = PruneInput {
+            previous_checkpoint: None,
+            to_block: 14,
+            limiter: PruneLimiter::default(),
+        };
+
+        let output = Segment::<()>::prune(&segment, &(), input).unwrap();
+        assert!(output.progress.is_finished());
+        assert_eq!(output.pruned, 1);
+        assert_eq!(output.checkpoint.and_then(|checkpoint| checkpoint.block_number), Some(9));
+
+        assert!(matches!(
+            static_file_provider.header_by_number(9),
+            Err(ProviderError::HistoryExpired(10))
+        ));
+        assert!(static_file_provider.header_by_number(10).unwrap().is_some());
+    }
+}
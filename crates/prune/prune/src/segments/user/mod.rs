@@ -1,14 +1,18 @@
 mod account_history;
 mod history;
+mod history_expiry;
 mod receipts;
 mod receipts_by_logs;
 mod sender_recovery;
 mod storage_history;
+mod transaction_appearances;
 mod transaction_lookup;
 
 pub use account_history::AccountHistory;
+pub use history_expiry::HistoryExpiry;
 pub use receipts::Receipts;
 pub use receipts_by_logs::ReceiptsByLogs;
 pub use sender_recovery::SenderRecovery;
 pub use storage_history::StorageHistory;
+pub use transaction_appearances::TransactionAppearances;
 pub use transaction_lookup::TransactionLookup;
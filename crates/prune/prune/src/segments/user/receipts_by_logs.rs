@@ -4,7 +4,10 @@ use crate::{
     PrunerError,
 };
 use reth_db::{tables, transaction::DbTxMut};
-use reth_provider::{BlockReader, DBProvider, PruneCheckpointWriter, TransactionsProvider};
+use reth_provider::{
+    providers::StaticFileProvider, BlockReader, DBProvider, PruneCheckpointWriter,
+    TransactionsProvider,
+};
 use reth_prune_types::{
     PruneCheckpoint, PruneMode, PruneProgress, PrunePurpose, PruneSegment, ReceiptsLogPruneConfig,
     SegmentOutput, MINIMUM_PRUNING_DISTANCE,
@@ -14,11 +17,15 @@ use tracing::{instrument, trace};
 #[derive(Debug)]
 pub struct ReceiptsByLogs {
     config: ReceiptsLogPruneConfig,
+    static_file_provider: StaticFileProvider,
 }
 
 impl ReceiptsByLogs {
-    pub const fn new(config: ReceiptsLogPruneConfig) -> Self {
-        Self { config }
+    pub const fn new(
+        config: ReceiptsLogPruneConfig,
+        static_file_provider: StaticFileProvider,
+    ) -> Self {
+        Self { config, static_file_provider }
     }
 }
 
@@ -39,7 +46,11 @@ where
     }
 
     #[instrument(level = "trace", target = "pruner", skip(self, provider), ret)]
-    fn prune(&self, provider: &Provider, input: PruneInput) -> Result<SegmentOutput, PrunerError> {
+    fn prune(
+        &self,
+        provider: &Provider,
+        mut input: PruneInput,
+    ) -> Result<SegmentOutput, PrunerError> {
         // Contract log filtering removes every receipt possible except the ones in the list. So,
         // for the other receipts it's as if they had a `PruneMode::Distance()` of
         // `MINIMUM_PRUNING_DISTANCE`.
@@ -48,6 +59,11 @@ where
             .map(|(bn, _)| bn)
             .unwrap_or_default();
 
+        // Blocks whose history has expired are skipped, up to `to_block` of this segment
+        let earliest_history_block =
+            self.static_file_provider.earliest_history_block().map(|block| block.min(to_block + 1));
+        input.skip_expired_history(provider, earliest_history_block)?;
+
         // Get status checkpoint from latest run
         let mut last_pruned_block =
             input.previous_checkpoint.and_then(|checkpoint| checkpoint.block_number);
@@ -223,7 +239,10 @@ mod tests {
     use assert_matches::assert_matches;
     use reth_db::tables;
     use reth_db_api::{cursor::DbCursorRO, transaction::DbTx};
-    use reth_provider::{DatabaseProviderFactory, PruneCheckpointReader, TransactionsProvider};
+    use reth_provider::{
+        DatabaseProviderFactory, PruneCheckpointReader, StaticFileProviderFactory,
+        TransactionsProvider,
+    };
     use reth_prune_types::{PruneLimiter, PruneMode, PruneSegment, ReceiptsLogPruneConfig};
     use reth_stages::test_utils::{StorageKind, TestStageDB};
     use reth_testing_utils::generators::{
@@ -294,19 +313,20 @@ mod tests {
 
             let limiter = PruneLimiter::default().set_deleted_entries_limit(10);
 
-            let result = ReceiptsByLogs::new(receipts_log_filter).prune(
-                &provider,
-                PruneInput {
-                    previous_checkpoint: db
-                        .factory
-                        .provider()
-                        .unwrap()
-                        .get_prune_checkpoint(PruneSegment::ContractLogs)
-                        .unwrap(),
-                    to_block: tip,
-                    limiter,
-                },
-            );
+            let result =
+                ReceiptsByLogs::new(receipts_log_filter, db.factory.static_file_provider()).prune(
+                    &provider,
+                    PruneInput {
+                        previous_checkpoint: db
+                            .factory
+                            .provider()
+                            .unwrap()
+                            .get_prune_checkpoint(PruneSegment::ContractLogs)
+                            .unwrap(),
+                        to_block: tip,
+                        limiter,
+                    },
+                );
             provider.commit().expect("commit");
 
             assert_matches!(result, Ok(_));
@@ -0,0 +1,229 @@
+use crate::{
+    segments::{user::history::prune_history_indices, PruneInput, Segment},
+    PrunerError,
+};
+use itertools::Itertools;
+use reth_db::{
+    tables,
+    transaction::{DbTx, DbTxMut},
+};
+use reth_db_api::models::ShardedKey;
+use reth_provider::{providers::StaticFileProvider, BlockReader, DBProvider, TransactionsProvider};
+use reth_prune_types::{
+    PruneInterruptReason, PruneMode, PruneProgress, PrunePurpose, PruneSegment, SegmentOutput,
+    SegmentOutputCheckpoint,
+};
+use rustc_hash::FxHashMap;
+use tracing::{instrument, trace};
+
+/// Prunes the [`tables::TransactionAppearances`] index.
+///
+/// The index has no changesets, so the addresses to prune are collected from the senders and
+/// recipients of the transactions in the pruned blocks. Every transaction counts as one deleted
+/// entry towards the limit. Nothing is pruned if the optional index is not maintained.
+#[derive(Debug)]
+pub struct TransactionAppearances {
+    mode: PruneMode,
+    static_file_provider: StaticFileProvider,
+}
+
+impl TransactionAppearances {
+    pub const fn new(mode: PruneMode, static_file_provider: StaticFileProvider) -> Self {
+        Self { mode, static_file_provider }
+    }
+}
+
+impl<Provider> Segment<Provider> for TransactionAppearances
+where
+    Provider: DBProvider<Tx: DbTxMut> + TransactionsProvider + BlockReader,
+{
+    fn segment(&self) -> PruneSegment {
+        PruneSegment::TransactionAppearances
+    }
+
+    fn mode(&self) -> Option<PruneMode> {
+        Some(self.mode)
+    }
+
+    fn purpose(&self) -> PrunePurpose {
+        PrunePurpose::User
+    }
+
+    #[instrument(level = "trace", target = "pruner", skip(self, provider), ret)]
+    fn prune(
+        &self,
+        provider: &Provider,
+        mut input: PruneInput,
+    ) -> Result<SegmentOutput, PrunerError> {
+        // The index is optional, and empty if it's not maintained
+        if provider.tx_ref().entries::<tables::TransactionAppearances>()? == 0 {
+            trace!(target: "pruner", "Transaction appearances are not indexed");
+            return Ok(SegmentOutput::done())
+        }
+
+        // Transactions of expired blocks can't be read anymore, so their appearances are skipped
+        let skipped = input
+            .skip_expired_history(provider, self.static_file_provider.earliest_history_block())?;
+
+        let range = match input.get_next_block_range() {
+            Some(range) => range,
+            None => {
+                trace!(target: "pruner", "No transaction appearances to prune");
+                return Ok(SegmentOutput {
+                    checkpoint: input
+                        .previous_checkpoint
+                        .filter(|_| skipped)
+                        .map(SegmentOutputCheckpoint::from_prune_checkpoint),
+                    ..SegmentOutput::done()
+                })
+            }
+        };
+        let range_end = *range.end();
+
+        let mut limiter = input.limiter;
+        if limiter.is_limit_reached() {
+            return Ok(SegmentOutput::not_done(
+                PruneInterruptReason::new(&limiter),
+                input.previous_checkpoint.map(SegmentOutputCheckpoint::from_prune_checkpoint),
+            ))
+        }
+
+        // Addresses that appeared in the pruned blocks with the highest block number they
+        // appeared in.
+        let mut highest_appearances = FxHashMap::default();
+        let mut last_pruned_block = range_end;
+        for block_number in range {
+            if limiter.is_limit_reached() {
+                last_pruned_block = block_number.saturating_sub(1);
+                break
+            }
+
+            let Some(body) = provider.block_body_indices(block_number)? else { continue };
+            let tx_num_range = body.tx_num_range();
+            if tx_num_range.is_empty() {
+                continue
+            }
+
+            let transactions = provider.transactions_by_tx_range(tx_num_range.clone())?;
+            let mut senders = provider.senders_by_tx_range(tx_num_range)?;
+            // Senders might be pruned, in which case we need to recover them.
+            if senders.len() != transactions.len() {
+                senders = transactions
+                    .iter()
+                    .map(|transaction| transaction.recover_signer())
+                    .collect::<Option<Vec<_>>>()
+                    .ok_or(PrunerError::InconsistentData("Failed to recover transaction sender"))?;
+            }
+
+            for (transaction, sender) in transactions.iter().zip(senders) {
+                for address in std::iter::once(sender).chain(transaction.to()) {
+                    highest_appearances.insert(address, block_number);
+                }
+            }
+            limiter.increment_deleted_entries_count_by(transactions.len());
+        }
+        let done = last_pruned_block == range_end;
+        trace!(target: "pruner", addresses = %highest_appearances.len(), %last_pruned_block, %done, "Collected transaction appearances");
+
+        // Sort highest block numbers by address and turn them into sharded keys.
+        let highest_sharded_keys = highest_appearances
+            .into_iter()
+            .sorted_unstable() // Unstable is fine because no equal keys exist in the map
+            .map(|(address, block_number)| ShardedKey::new(address, block_number));
+        let outcomes = prune_history_indices::<Provider, tables::TransactionAppearances, _>(
+            provider,
+            highest_sharded_keys,
+            |a, b| a.key == b.key,
+        )?;
+        trace!(target: "pruner", ?outcomes, %done, "Pruned transaction appearances");
+
+        let progress = PruneProgress::new(done, &limiter);
+
+        Ok(SegmentOutput {
+            progress,
+            pruned: outcomes.deleted,
+            checkpoint: Some(SegmentOutputCheckpoint {
+                block_number: Some(last_pruned_block),
+                tx_number: None,
+            }),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::segments::{PruneInput, Segment, SegmentOutput, TransactionAppearances};
+    use alloy_primitives::{Address, BlockNumber, B256};
+    use assert_matches::assert_matches;
+    use reth_db::{tables, BlockNumberList};
+    use reth_provider::{
+        AccountExtReader, DatabaseProviderFactory, HistoryWriter, PruneCheckpointReader,
+        StaticFileProviderFactory,
+    };
+    use reth_prune_types::{PruneLimiter, PruneMode, PruneProgress, PruneSegment};
+    use reth_stages::test_utils::{StorageKind, TestStageDB};
+    use reth_testing_utils::generators::{self, random_block_range, BlockRangeParams};
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn prune() {
+        let db = TestStageDB::default();
+        let mut rng = generators::rng();
+
+        let blocks = random_block_range(
+            &mut rng,
+            1..=100,
+            BlockRangeParams { parent: Some(B256::ZERO), tx_count: 1..3, ..Default::default() },
+        );
+        db.insert_blocks(blocks.iter(), StorageKind::Database(None)).expect("insert blocks");
+
+        let provider = db.factory.database_provider_rw().unwrap();
+        let appearances = provider.transaction_appearances_with_range(1..=100).unwrap();
+        provider.insert_transaction_appearance_index(appearances.clone()).unwrap();
+        provider.commit().expect("commit");
+
+        let to_block: BlockNumber = 50;
+        let prune_mode = PruneMode::Before(to_block + 1);
+        let input =
+            PruneInput { previous_checkpoint: None, to_block, limiter: PruneLimiter::default() };
+        let segment = TransactionAppearances::new(prune_mode, db.factory.static_file_provider());
+
+        let provider = db.factory.database_provider_rw().unwrap();
+        let result = segment.prune(&provider, input).unwrap();
+        assert_matches!(
+            result,
+            SegmentOutput { progress: PruneProgress::Finished, checkpoint: Some(_), .. }
+        );
+        segment
+            .save_checkpoint(&provider, result.checkpoint.unwrap().as_prune_checkpoint(prune_mode))
+            .unwrap();
+        provider.commit().expect("commit");
+
+        let expected = appearances
+            .into_iter()
+            .filter_map(|(address, blocks)| {
+                let blocks =
+                    blocks.into_iter().filter(|block| *block > to_block).collect::<Vec<_>>();
+                (!blocks.is_empty()).then_some((address, blocks))
+            })
+            .collect::<BTreeMap<_, _>>();
+        let actual = db.table::<tables::TransactionAppearances>().unwrap().into_iter().fold(
+            BTreeMap::<Address, Vec<u64>>::new(),
+            |mut acc, (key, list): (_, BlockNumberList)| {
+                acc.entry(key.key).or_default().extend(list.iter());
+                acc
+            },
+        );
+        assert_eq!(actual, expected);
+
+        assert_eq!(
+            db.factory
+                .provider()
+                .unwrap()
+                .get_prune_checkpoint(PruneSegment::TransactionAppearances)
+                .unwrap()
+                .and_then(|checkpoint| checkpoint.block_number),
+            Some(to_block)
+        );
+    }
+}
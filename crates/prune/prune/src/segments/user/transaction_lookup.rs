@@ -5,7 +5,7 @@ use crate::{
 };
 use rayon::prelude::*;
 use reth_db::{tables, transaction::DbTxMut};
-use reth_provider::{BlockReader, DBProvider, TransactionsProvider};
+use reth_provider::{providers::StaticFileProvider, BlockReader, DBProvider, TransactionsProvider};
 use reth_prune_types::{
     PruneMode, PruneProgress, PrunePurpose, PruneSegment, SegmentOutputCheckpoint,
 };
@@ -14,11 +14,12 @@ use tracing::{instrument, trace};
 #[derive(Debug)]
 pub struct TransactionLookup {
     mode: PruneMode,
+    static_file_provider: StaticFileProvider,
 }
 
 impl TransactionLookup {
-    pub const fn new(mode: PruneMode) -> Self {
-        Self { mode }
+    pub const fn new(mode: PruneMode, static_file_provider: StaticFileProvider) -> Self {
+        Self { mode, static_file_provider }
     }
 }
 
@@ -39,12 +40,27 @@ where
     }
 
     #[instrument(level = "trace", target = "pruner", skip(self, provider), ret)]
-    fn prune(&self, provider: &Provider, input: PruneInput) -> Result<SegmentOutput, PrunerError> {
+    fn prune(
+        &self,
+        provider: &Provider,
+        mut input: PruneInput,
+    ) -> Result<SegmentOutput, PrunerError> {
+        // Transactions of expired blocks can't be hashed anymore, so their lookup entries are
+        // skipped
+        let skipped = input
+            .skip_expired_history(provider, self.static_file_provider.earliest_history_block())?;
+
         let (start, end) = match input.get_next_tx_num_range(provider)? {
             Some(range) => range,
             None => {
                 trace!(target: "pruner", "No transaction lookup entries to prune");
-                return Ok(SegmentOutput::done())
+                return Ok(SegmentOutput {
+                    checkpoint: input
+                        .previous_checkpoint
+                        .filter(|_| skipped)
+                        .map(SegmentOutputCheckpoint::from_prune_checkpoint),
+                    ..SegmentOutput::done()
+                })
             }
         }
         .into_inner();
@@ -118,7 +134,9 @@ mod tests {
         Itertools,
     };
     use reth_db::tables;
-    use reth_provider::{DatabaseProviderFactory, PruneCheckpointReader};
+    use reth_provider::{
+        DatabaseProviderFactory, PruneCheckpointReader, StaticFileProviderFactory,
+    };
     use reth_prune_types::{
         PruneCheckpoint, PruneInterruptReason, PruneLimiter, PruneMode, PruneProgress, PruneSegment,
     };
@@ -157,7 +175,7 @@ mod tests {
 
         let test_prune = |to_block: BlockNumber, expected_result: (PruneProgress, usize)| {
             let prune_mode = PruneMode::Before(to_block);
-            let segment = TransactionLookup::new(prune_mode);
+            let segment = TransactionLookup::new(prune_mode, db.factory.static_file_provider());
             let mut limiter = PruneLimiter::default().set_deleted_entries_limit(10);
             let input = PruneInput {
                 previous_checkpoint: db
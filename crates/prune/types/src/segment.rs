@@ -39,19 +39,35 @@ pub enum PruneSegment {
     Headers,
     /// Prune segment responsible for the `Transactions` table.
     Transactions,
+    /// Prune segment responsible for the `AccountChangeSets` table, after its rows were moved to
+    /// static files.
+    AccountChangeSets,
+    /// Prune segment responsible for the `StorageChangeSets` table, after its rows were moved to
+    /// static files.
+    StorageChangeSets,
+    /// Prune segment responsible for expiring headers, transactions and receipts from static
+    /// files, as described in [EIP-4444](https://eips.ethereum.org/EIPS/eip-4444).
+    History,
+    /// Prune segment responsible for the `TransactionAppearances` table.
+    TransactionAppearances,
 }
 
 impl PruneSegment {
     /// Returns minimum number of blocks to left in the database for this segment.
     pub const fn min_blocks(&self, purpose: PrunePurpose) -> u64 {
         match self {
-            Self::SenderRecovery | Self::TransactionLookup | Self::Headers | Self::Transactions => {
-                0
-            }
+            Self::SenderRecovery |
+            Self::TransactionLookup |
+            Self::Headers |
+            Self::Transactions |
+            Self::AccountChangeSets |
+            Self::StorageChangeSets => 0,
             Self::Receipts if purpose.is_static_file() => 0,
-            Self::ContractLogs | Self::AccountHistory | Self::StorageHistory => {
-                MINIMUM_PRUNING_DISTANCE
-            }
+            Self::ContractLogs |
+            Self::AccountHistory |
+            Self::StorageHistory |
+            Self::History |
+            Self::TransactionAppearances => MINIMUM_PRUNING_DISTANCE,
             Self::Receipts => MINIMUM_PRUNING_DISTANCE,
         }
     }
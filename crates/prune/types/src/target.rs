@@ -37,6 +37,13 @@ pub struct PruneModes {
         deserialize_with = "deserialize_opt_prune_mode_with_min_blocks::<MINIMUM_PRUNING_DISTANCE, _>"
     )]
     pub storage_history: Option<PruneMode>,
+    /// History expiry configuration. Expires headers, transactions and receipts from static
+    /// files, see [EIP-4444](https://eips.ethereum.org/EIPS/eip-4444).
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_opt_prune_mode_with_min_blocks::<MINIMUM_PRUNING_DISTANCE, _>"
+    )]
+    pub history: Option<PruneMode>,
     /// Receipts pruning configuration by retaining only those receipts that contain logs emitted
     /// by the specified addresses, discarding others. This setting is overridden by `receipts`.
     ///
@@ -59,6 +66,8 @@ impl PruneModes {
             receipts: Some(PruneMode::Full),
             account_history: Some(PruneMode::Full),
             storage_history: Some(PruneMode::Full),
+            // history expiry deletes static files and is never enabled implicitly
+            history: None,
             receipts_log_filter: Default::default(),
         }
     }
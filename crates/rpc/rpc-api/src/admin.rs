@@ -1,6 +1,36 @@
+use alloy_primitives::BlockNumber;
 use alloy_rpc_types_admin::{NodeInfo, PeerInfo};
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
 use reth_network_peers::{AnyNode, NodeRecord};
+use serde::{Deserialize, Serialize};
+
+/// Options of `admin_snapshot`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AdminSnapshotOptions {
+    /// Omit free pages from the database copy.
+    #[serde(default)]
+    pub compact: bool,
+    /// Copy static files below the finalized block instead of hard-linking them.
+    #[serde(default)]
+    pub copy_static_files: bool,
+}
+
+/// A snapshot written by `admin_snapshot`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AdminSnapshot {
+    /// Directory the snapshot was written to.
+    pub dir: String,
+    /// Highest block fully persisted in the snapshot.
+    pub block_number: BlockNumber,
+    /// Whether free pages were omitted from the database copy.
+    pub compacted: bool,
+    /// Number of files in the snapshot, excluding the manifest.
+    pub files: usize,
+    /// Total size of the files in bytes.
+    pub size: u64,
+}
 
 /// Admin namespace rpc interface that gives access to several non-standard RPC methods.
 #[cfg_attr(not(feature = "client"), rpc(server, namespace = "admin"))]
@@ -45,4 +75,15 @@ pub trait AdminApi {
     /// Returns the ENR of the node.
     #[method(name = "nodeInfo")]
     async fn node_info(&self) -> RpcResult<NodeInfo>;
+
+    /// Writes a consistent snapshot of the database and the static files of the running node to
+    /// the given directory, which must be empty or not exist yet.
+    ///
+    /// The snapshot can be restored with `reth db restore-snapshot`.
+    #[method(name = "snapshot")]
+    async fn snapshot(
+        &self,
+        dir: String,
+        options: Option<AdminSnapshotOptions>,
+    ) -> RpcResult<AdminSnapshot>;
 }
@@ -7,6 +7,78 @@ use alloy_rpc_types_trace::geth::{
 };
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
 use reth_primitives::{BlockId, BlockNumberOrTag};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// A block that was rejected by the node, as returned by `debug_getBadBlocks`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BadBlock {
+    /// The hash of the block.
+    pub hash: B256,
+    /// The block.
+    pub block: Block,
+    /// The RLP encoded block.
+    pub rlp: Bytes,
+    /// The error the block was rejected with.
+    pub error: String,
+}
+
+/// A page of the state at a block, as returned by `debug_dumpBlock` and `debug_accountRange`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StateDump {
+    /// The state root of the block.
+    pub root: B256,
+    /// The accounts, keyed by `pre(<hashed address>)` since address preimages are not stored.
+    pub accounts: BTreeMap<String, DumpAccount>,
+    /// The hashed address to continue from, if there are more accounts.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next: Option<B256>,
+}
+
+/// An account of a [`StateDump`].
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DumpAccount {
+    /// The balance of the account, as a decimal string.
+    pub balance: String,
+    /// The nonce of the account.
+    pub nonce: u64,
+    /// The storage root of the account, only set if the storage was dumped.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub root: Option<B256>,
+    /// The hash of the code of the account.
+    pub code_hash: B256,
+    /// The code of the account, unless empty or excluded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<Bytes>,
+    /// The storage of the account keyed by hashed slot, unless empty or excluded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub storage: Option<BTreeMap<B256, B256>>,
+    /// The address of the account, if its preimage is known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub address: Option<Address>,
+    /// The hashed address of the account.
+    pub key: B256,
+}
+
+/// A page of the storage of an account, as returned by `debug_storageRangeAt`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageRangeResult {
+    /// The storage slots, keyed by hashed slot.
+    pub storage: BTreeMap<B256, StorageRangeEntry>,
+    /// The hashed slot to continue from, if there are more slots.
+    pub next_key: Option<B256>,
+}
+
+/// A storage slot of a [`StorageRangeResult`].
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StorageRangeEntry {
+    /// The slot, if its preimage is known.
+    pub key: Option<B256>,
+    /// The value of the slot.
+    pub value: B256,
+}
 
 /// Debug rpc interface.
 #[cfg_attr(not(feature = "client"), rpc(server, namespace = "debug"))]
@@ -34,9 +106,10 @@ pub trait DebugApi {
     #[method(name = "getRawReceipts")]
     async fn raw_receipts(&self, block_id: BlockId) -> RpcResult<Vec<Bytes>>;
 
-    /// Returns an array of recent bad blocks that the client has seen on the network.
+    /// Returns an array of recent bad blocks that the client has seen on the network, most recent
+    /// first.
     #[method(name = "getBadBlocks")]
-    async fn bad_blocks(&self) -> RpcResult<Vec<Block>>;
+    async fn bad_blocks(&self) -> RpcResult<Vec<BadBlock>>;
 
     /// Returns the structured logs created during the execution of EVM between two blocks
     /// (excluding start) as a JSON object.
@@ -45,8 +118,25 @@ pub trait DebugApi {
         &self,
         start_exclusive: BlockNumberOrTag,
         end_inclusive: BlockNumberOrTag,
+        opts: Option<GethDebugTracingOptions>,
     ) -> RpcResult<Vec<BlockTraceResult>>;
 
+    /// Streams the structured logs created during the execution of EVM between two blocks
+    /// (excluding start), one [`BlockTraceResult`] per block in ascending order.
+    ///
+    /// The subscription is closed once the last block has been traced.
+    #[subscription(
+        name = "subscribeTraceChain" => "traceChainSubscription",
+        unsubscribe = "unsubscribeTraceChain",
+        item = BlockTraceResult
+    )]
+    async fn debug_subscribe_trace_chain(
+        &self,
+        start_exclusive: BlockNumberOrTag,
+        end_inclusive: BlockNumberOrTag,
+        opts: Option<GethDebugTracingOptions>,
+    ) -> jsonrpsee::core::SubscriptionResult;
+
     /// The `debug_traceBlock` method will return a full stack trace of all invoked opcodes of all
     /// transaction that were included in this block.
     ///
@@ -154,6 +244,8 @@ pub trait DebugApi {
     ///
     /// If incompletes is false, then accounts for which the key preimage (i.e: the address) doesn't
     /// exist in db are skipped. NB: geth by default does not store preimages.
+    ///
+    /// Reth does not store preimages, so all accounts are returned regardless of incompletes.
     #[method(name = "accountRange")]
     async fn debug_account_range(
         &self,
@@ -163,7 +255,7 @@ pub trait DebugApi {
         nocode: bool,
         nostorage: bool,
         incompletes: bool,
-    ) -> RpcResult<()>;
+    ) -> RpcResult<StateDump>;
 
     /// Turns on block profiling for the given duration and writes profile data to disk. It uses a
     /// profile rate of 1 for most accurate information. If a different rate is desired, set the
@@ -171,14 +263,11 @@ pub trait DebugApi {
     #[method(name = "blockProfile")]
     async fn debug_block_profile(&self, file: String, seconds: u64) -> RpcResult<()>;
 
-    /// Flattens the entire key-value database into a single level, removing all unused slots and
-    /// merging all keys.
-    #[method(name = "chaindbCompact")]
-    async fn debug_chaindb_compact(&self) -> RpcResult<()>;
-
-    /// Returns leveldb properties of the key-value database.
+    /// Returns properties of the key-value database and the static files.
+    ///
+    /// Supported properties are `mdbx.stats` (the default), `mdbx.freelist` and `static_files`.
     #[method(name = "chaindbProperty")]
-    async fn debug_chaindb_property(&self, property: String) -> RpcResult<()>;
+    async fn debug_chaindb_property(&self, property: String) -> RpcResult<String>;
 
     /// Turns on CPU profiling for the given duration and writes profile data to disk.
     #[method(name = "cpuProfile")]
@@ -187,21 +276,35 @@ pub trait DebugApi {
     /// Retrieves an ancient binary blob from the freezer. The freezer is a collection of
     /// append-only immutable files. The first argument `kind` specifies which table to look up data
     /// from. The list of all table kinds are as follows:
+    ///
+    /// - `headers`: RLP encoded header
+    /// - `hashes`: block hash
+    /// - `bodies`: RLP encoded block body
+    /// - `receipts`: RLP encoded list of the receipts of the block
+    /// - `diffs`: RLP encoded total difficulty
+    ///
+    /// Only blocks that were moved to the static files can be retrieved.
     #[method(name = "dbAncient")]
-    async fn debug_db_ancient(&self, kind: String, number: u64) -> RpcResult<()>;
+    async fn debug_db_ancient(&self, kind: String, number: u64) -> RpcResult<Bytes>;
 
     /// Returns the number of ancient items in the ancient store.
+    ///
+    /// This is the number of blocks that were moved to the static files.
     #[method(name = "dbAncients")]
-    async fn debug_db_ancients(&self) -> RpcResult<()>;
+    async fn debug_db_ancients(&self) -> RpcResult<u64>;
 
     /// Returns the raw value of a key stored in the database.
+    ///
+    /// The key has the format `<table>:<hex encoded raw key>`.
     #[method(name = "dbGet")]
-    async fn debug_db_get(&self, key: String) -> RpcResult<()>;
+    async fn debug_db_get(&self, key: String) -> RpcResult<Bytes>;
 
-    /// Retrieves the state that corresponds to the block number and returns a list of accounts
-    /// (including storage and code).
+    /// Retrieves the state that corresponds to the block number and returns a page of its
+    /// accounts (including storage and code), starting at the hashed address `start`.
+    ///
+    /// If there are more accounts, [`StateDump::next`] is the `start` of the next page.
     #[method(name = "dumpBlock")]
-    async fn debug_dump_block(&self, number: BlockId) -> RpcResult<()>;
+    async fn debug_dump_block(&self, number: BlockId, start: Option<B256>) -> RpcResult<StateDump>;
 
     /// Forces garbage collection.
     #[method(name = "freeOSMemory")]
@@ -354,7 +457,7 @@ pub trait DebugApi {
         contract_address: Address,
         key_start: B256,
         max_result: u64,
-    ) -> RpcResult<()>;
+    ) -> RpcResult<StorageRangeResult>;
 
     /// Returns the structured logs created during the execution of EVM against a block pulled
     /// from the pool of bad ones and returns them as a JSON object. For the second parameter see
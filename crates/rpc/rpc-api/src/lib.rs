@@ -30,6 +30,9 @@ mod txpool;
 mod validation;
 mod web3;
 
+pub use admin::{AdminSnapshot, AdminSnapshotOptions};
+pub use debug::{BadBlock, DumpAccount, StateDump, StorageRangeEntry, StorageRangeResult};
+
 /// re-export of all server traits
 pub use servers::*;
 
@@ -64,6 +64,11 @@ pub trait Otterscan<T: RpcObject> {
     ) -> RpcResult<OtsBlockTransactions<T>>;
 
     /// Gets paginated inbound/outbound transaction calls for a certain address.
+    ///
+    /// Only the blocks in which the account of the address changed are searched, plus the blocks
+    /// of the transaction appearances index and of the trace index (`--rpc.trace-index`) if the
+    /// node maintains them. Without these indices, calls that leave the account unchanged are not
+    /// found.
     #[method(name = "searchTransactionsBefore")]
     async fn search_transactions_before(
         &self,
@@ -73,6 +78,11 @@ pub trait Otterscan<T: RpcObject> {
     ) -> RpcResult<TransactionsWithReceipts>;
 
     /// Gets paginated inbound/outbound transaction calls for a certain address.
+    ///
+    /// Only the blocks in which the account of the address changed are searched, plus the blocks
+    /// of the transaction appearances index and of the trace index (`--rpc.trace-index`) if the
+    /// node maintains them. Without these indices, calls that leave the account unchanged are not
+    /// found.
     #[method(name = "searchTransactionsAfter")]
     async fn search_transactions_after(
         &self,
@@ -2,7 +2,7 @@ use std::{net::SocketAddr, path::PathBuf};
 
 use jsonrpsee::server::ServerBuilder;
 use reth_node_core::{args::RpcServerArgs, utils::get_or_create_jwt_secret_from_path};
-use reth_rpc_eth_types::{EthConfig, EthStateCacheConfig, GasPriceOracleConfig};
+use reth_rpc_eth_types::{EthConfig, EthStateCacheConfig, GasPriceOracleConfig, JsTracerConfig};
 use reth_rpc_layer::{JwtError, JwtSecret};
 use reth_rpc_server_types::RpcModuleSelection;
 use tower::layer::util::Identity;
@@ -96,9 +96,17 @@ impl RethRpcServerConfig for RpcServerArgs {
             .eth_proof_window(self.rpc_eth_proof_window)
             .rpc_gas_cap(self.rpc_gas_cap)
             .rpc_max_simulate_blocks(self.rpc_max_simulate_blocks)
+            .rpc_max_trace_chain_blocks(self.rpc_max_trace_chain_blocks)
             .state_cache(self.state_cache_config())
             .gpo_config(self.gas_price_oracle_config())
             .proof_permits(self.rpc_proof_permits)
+            .js_tracer(
+                JsTracerConfig::default()
+                    .timeout(self.rpc_js_tracer_timeout)
+                    .memory_limit(self.rpc_js_tracer_memory_limit)
+                    .loop_iteration_limit(self.rpc_js_tracer_loop_iteration_limit)
+                    .recursion_limit(self.rpc_js_tracer_recursion_limit),
+            )
     }
 
     fn state_cache_config(&self) -> EthStateCacheConfig {
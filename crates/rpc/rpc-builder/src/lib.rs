@@ -154,6 +154,7 @@ use std::{
     collections::HashMap,
     fmt::Debug,
     net::{Ipv4Addr, SocketAddr, SocketAddrV4},
+    sync::Arc,
     time::{Duration, SystemTime, UNIX_EPOCH},
 };
 
@@ -175,18 +176,18 @@ use reth_network_api::{noop::NoopNetwork, NetworkInfo, Peers};
 use reth_primitives::Header;
 use reth_provider::{
     AccountReader, BlockReader, CanonStateSubscriptions, ChainSpecProvider, ChangeSetReader,
-    EvmEnvProvider, FullRpcProvider, StateProviderFactory,
+    EvmEnvProvider, FullRpcProvider, StateProviderFactory, StorageSnapshotProvider,
 };
 use reth_rpc::{
-    AdminApi, DebugApi, EngineEthApi, EthBundle, NetApi, OtterscanApi, RPCApi, RethApi, TraceApi,
-    TxPoolApi, Web3Api,
+    AdminApi, DebugApi, EngineEthApi, EthBundle, EthSimBundle, NetApi, OtterscanApi, RPCApi,
+    RethApi, SetHeadHandle, TraceApi, TxPoolApi, Web3Api,
 };
 use reth_rpc_api::servers::*;
 use reth_rpc_eth_api::{
     helpers::{Call, EthApiSpec, EthTransactions, LoadPendingBlock, TraceExt},
     EthApiServer, EthApiTypes, FullEthApiServer, RpcBlock, RpcReceipt, RpcTransaction,
 };
-use reth_rpc_eth_types::{EthConfig, EthStateCache, EthSubscriptionIdProvider};
+use reth_rpc_eth_types::{EthConfig, EthStateCache, EthSubscriptionIdProvider, JsTracerConfig};
 use reth_rpc_layer::{AuthLayer, Claims, JwtAuthValidator, JwtSecret};
 use reth_tasks::{pool::BlockingTaskGuard, TaskSpawner, TokioTaskExecutor};
 use reth_transaction_pool::{noop::NoopTransactionPool, TransactionPool};
@@ -712,6 +713,12 @@ pub struct RpcRegistryInner<
     eth: EthHandlers<Provider, Pool, Network, Events, EthApi>,
     /// to put trace calls behind semaphore
     blocking_pool_guard: BlockingTaskGuard,
+    /// Limits for custom JS tracers
+    js_tracer_config: JsTracerConfig,
+    /// Maximum number of blocks for `debug_traceChain`
+    max_trace_chain_blocks: u64,
+    /// Handle to rewind the canonical chain via `debug_setHead`
+    set_head_handle: Option<Arc<dyn SetHeadHandle>>,
     /// Contains the [Methods] of a module
     modules: HashMap<RethRpcModule, Methods>,
 }
@@ -754,6 +761,8 @@ where
         EvmConfig: ConfigureEvm<Header = Header>,
     {
         let blocking_pool_guard = BlockingTaskGuard::new(config.eth.max_tracing_requests);
+        let js_tracer_config = config.eth.js_tracer;
+        let max_trace_chain_blocks = config.eth.rpc_max_trace_chain_blocks;
 
         let eth = EthHandlers::bootstrap(
             provider.clone(),
@@ -774,6 +783,9 @@ where
             executor,
             modules: Default::default(),
             blocking_pool_guard,
+            js_tracer_config,
+            max_trace_chain_blocks,
+            set_head_handle: None,
             events,
             block_executor,
         }
@@ -823,6 +835,14 @@ where
         &self.provider
     }
 
+    /// Configures the handle `debug_setHead` uses to rewind the canonical chain.
+    ///
+    /// This must be set before the `debug` module is created.
+    pub fn with_set_head_handle(mut self, handle: Arc<dyn SetHeadHandle>) -> Self {
+        self.set_head_handle = Some(handle);
+        self
+    }
+
     /// Returns all installed methods
     pub fn methods(&self) -> Vec<Methods> {
         self.modules.values().cloned().collect()
@@ -847,11 +867,12 @@ where
     BlockExecutor: BlockExecutorProvider,
 {
     /// Instantiates `AdminApi`
-    pub fn admin_api(&self) -> AdminApi<Network, Provider::ChainSpec>
+    pub fn admin_api(&self) -> AdminApi<Network, Provider::ChainSpec, Provider>
     where
         Network: Peers,
+        Provider: Clone,
     {
-        AdminApi::new(self.network.clone(), self.provider.chain_spec())
+        AdminApi::new(self.network.clone(), self.provider.chain_spec(), self.provider.clone())
     }
 
     /// Instantiates `Web3Api`
@@ -863,6 +884,7 @@ where
     pub fn register_admin(&mut self) -> &mut Self
     where
         Network: Peers,
+        Provider: StorageSnapshotProvider + Clone + 'static,
     {
         let adminapi = self.admin_api();
         self.modules.insert(RethRpcModule::Admin, adminapi.into_rpc().into());
@@ -984,9 +1006,9 @@ where
     /// # Panics
     ///
     /// If called outside of the tokio runtime. See also [`Self::eth_api`]
-    pub fn otterscan_api(&self) -> OtterscanApi<EthApi> {
+    pub fn otterscan_api(&self) -> OtterscanApi<Provider, EthApi> {
         let eth_api = self.eth_api().clone();
-        OtterscanApi::new(eth_api)
+        OtterscanApi::new(self.provider.clone(), eth_api)
     }
 }
 
@@ -1028,6 +1050,19 @@ where
         EthBundle::new(eth_api, self.blocking_pool_guard.clone())
     }
 
+    /// Instantiates [`EthSimBundle`] Api
+    ///
+    /// # Panics
+    ///
+    /// If called outside of the tokio runtime. See also [`Self::eth_api`]
+    pub fn sim_bundle_api(&self) -> EthSimBundle<EthApi>
+    where
+        EthApi: EthTransactions + LoadPendingBlock + Call,
+    {
+        let eth_api = self.eth_api().clone();
+        EthSimBundle::new(eth_api, self.blocking_pool_guard.clone())
+    }
+
     /// Instantiates `DebugApi`
     ///
     /// # Panics
@@ -1043,6 +1078,9 @@ where
             self.eth_api().clone(),
             self.blocking_pool_guard.clone(),
             self.block_executor.clone(),
+            self.js_tracer_config,
+            self.max_trace_chain_blocks,
+            self.set_head_handle.clone(),
         )
     }
 
@@ -1158,16 +1196,21 @@ where
                 self.modules
                     .entry(namespace)
                     .or_insert_with(|| match namespace {
-                        RethRpcModule::Admin => {
-                            AdminApi::new(self.network.clone(), self.provider.chain_spec())
-                                .into_rpc()
-                                .into()
-                        }
+                        RethRpcModule::Admin => AdminApi::new(
+                            self.network.clone(),
+                            self.provider.chain_spec(),
+                            self.provider.clone(),
+                        )
+                        .into_rpc()
+                        .into(),
                         RethRpcModule::Debug => DebugApi::new(
                             self.provider.clone(),
                             eth_api.clone(),
                             self.blocking_pool_guard.clone(),
                             self.block_executor.clone(),
+                            self.js_tracer_config,
+                            self.max_trace_chain_blocks,
+                            self.set_head_handle.clone(),
                         )
                         .into_rpc()
                         .into(),
@@ -1185,6 +1228,15 @@ where
                                     .into_rpc(),
                                 )
                                 .expect("No conflicts");
+                            module
+                                .merge(
+                                    EthSimBundle::new(
+                                        eth_api.clone(),
+                                        self.blocking_pool_guard.clone(),
+                                    )
+                                    .into_rpc(),
+                                )
+                                .expect("No conflicts");
 
                             module.into()
                         }
@@ -1210,7 +1262,11 @@ where
                         )
                         .into_rpc()
                         .into(),
-                        RethRpcModule::Ots => OtterscanApi::new(eth_api.clone()).into_rpc().into(),
+                        RethRpcModule::Ots => {
+                            OtterscanApi::new(self.provider.clone(), eth_api.clone())
+                                .into_rpc()
+                                .into()
+                        }
                         RethRpcModule::Reth => {
                             RethApi::new(self.provider.clone(), Box::new(self.executor.clone()))
                                 .into_rpc()
@@ -336,7 +336,7 @@ where
     DebugApiClient::raw_block(client, block_id).await.unwrap_err();
     DebugApiClient::raw_transaction(client, B256::default()).await.unwrap();
     DebugApiClient::raw_receipts(client, block_id).await.unwrap();
-    assert!(is_unimplemented(DebugApiClient::bad_blocks(client).await.err().unwrap()));
+    assert!(DebugApiClient::bad_blocks(client).await.unwrap().is_empty());
 }
 
 async fn test_basic_net_calls<C>(client: &C)
@@ -890,15 +890,18 @@ async fn test_eth_coinbase_rpc_call() {
     let handle = launch_http(vec![RethRpcModule::Eth]).await;
     let client = handle.http_client().unwrap();
 
-    // Requesting coinbase address without any parameter should return Unimplemented
+    // Requesting coinbase address before the payload builder received a fee recipient should
+    // return an error
     match client.request::<Address, _>("eth_coinbase", rpc_params![]).await {
         Ok(_) => {
             // If there's a response, it's unexpected, panic
-            panic!("Expected Unimplemented error, got successful response");
+            panic!("Expected error, got successful response");
         }
-        Err(err) => {
-            assert!(is_unimplemented(err));
+        Err(jsonrpsee::core::client::Error::Call(error_obj)) => {
+            assert_eq!(error_obj.code(), ErrorCode::InternalError.code());
+            assert_eq!(error_obj.message(), "etherbase must be explicitly specified");
         }
+        Err(err) => panic!("Expected call error, got {err:?}"),
     };
 }
 
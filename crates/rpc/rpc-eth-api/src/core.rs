@@ -386,7 +386,10 @@ where
 
     /// Handler for: `eth_coinbase`
     async fn author(&self) -> RpcResult<Address> {
-        Err(internal_rpc_err("unimplemented"))
+        trace!(target: "rpc::eth", "Serving eth_coinbase");
+        EthApiSpec::suggested_fee_recipient(self)
+            .read()
+            .ok_or_else(|| internal_rpc_err("etherbase must be explicitly specified"))
     }
 
     /// Handler for: `eth_accounts`
@@ -780,8 +783,9 @@ where
     }
 
     /// Handler for: `eth_signTransaction`
-    async fn sign_transaction(&self, _transaction: TransactionRequest) -> RpcResult<Bytes> {
-        Err(internal_rpc_err("unimplemented"))
+    async fn sign_transaction(&self, transaction: TransactionRequest) -> RpcResult<Bytes> {
+        trace!(target: "rpc::eth", ?transaction, "Serving eth_signTransaction");
+        Ok(EthTransactions::sign_transaction(self, transaction).await?)
     }
 
     /// Handler for: `eth_signTypedData`
@@ -44,4 +44,7 @@ pub trait AddDevSigners {
     /// Generates 20 random developer accounts.
     /// Used in DEV mode.
     fn with_dev_accounts(&self);
+
+    /// Adds the given signer to the signers available via the API, e.g. one backed by a keystore.
+    fn add_signer(&self, signer: Box<dyn EthSigner>);
 }
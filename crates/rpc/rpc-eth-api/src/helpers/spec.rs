@@ -29,6 +29,10 @@ pub trait EthApiSpec: Send + Sync {
     /// Returns a handle to the signers owned by provider.
     fn signers(&self) -> &parking_lot::RwLock<Vec<Box<dyn EthSigner>>>;
 
+    /// Returns a handle to the latest fee recipient the consensus layer suggested to the payload
+    /// builder, which is served as the coinbase.
+    fn suggested_fee_recipient(&self) -> &parking_lot::RwLock<Option<Address>>;
+
     /// Returns the current ethereum protocol version.
     fn protocol_version(&self) -> impl Future<Output = RethResult<U64>> + Send {
         async move {
@@ -5,7 +5,7 @@ use alloy_consensus::Transaction;
 use alloy_dyn_abi::TypedData;
 use alloy_eips::eip2718::Encodable2718;
 use alloy_network::TransactionBuilder;
-use alloy_primitives::{Address, Bytes, TxHash, B256};
+use alloy_primitives::{Address, Bytes, TxHash, B256, U256};
 use alloy_rpc_types::{BlockNumberOrTag, TransactionInfo};
 use alloy_rpc_types_eth::transaction::TransactionRequest;
 use futures::Future;
@@ -24,7 +24,8 @@ use std::sync::Arc;
 use crate::{FromEthApiError, FullEthApiTypes, IntoEthApiError, RpcReceipt, RpcTransaction};
 
 use super::{
-    Call, EthApiSpec, EthSigner, LoadBlock, LoadPendingBlock, LoadReceipt, LoadState, SpawnBlocking,
+    Call, EthApiSpec, EthSigner, LoadBlock, LoadFee, LoadPendingBlock, LoadReceipt, LoadState,
+    SpawnBlocking,
 };
 
 /// Transaction related functions for the [`EthApiServer`](crate::EthApiServer) trait in
@@ -348,10 +349,10 @@ pub trait EthTransactions: LoadTransaction {
     /// Returns the hash of the signed transaction.
     fn send_transaction(
         &self,
-        mut request: TransactionRequest,
+        request: TransactionRequest,
     ) -> impl Future<Output = Result<B256, Self::Error>> + Send
     where
-        Self: EthApiSpec + LoadBlock + LoadPendingBlock + Call,
+        Self: EthApiSpec + LoadBlock + LoadPendingBlock + LoadFee + Call,
     {
         async move {
             let from = match request.from {
@@ -363,20 +364,7 @@ pub trait EthTransactions: LoadTransaction {
                 return Err(SignError::NoAccount.into_eth_err())
             }
 
-            // set nonce if not already set before
-            if request.nonce.is_none() {
-                let nonce = self.transaction_count(from, Some(BlockId::pending())).await?;
-                // note: `.to()` can't panic because the nonce is constructed from a `u64`
-                request.nonce = Some(nonce.to());
-            }
-
-            let chain_id = self.chain_id();
-            request.chain_id = Some(chain_id.to());
-
-            let estimated_gas =
-                self.estimate_gas_at(request.clone(), BlockId::pending(), None).await?;
-            let gas_limit = estimated_gas;
-            request.set_gas_limit(gas_limit.to());
+            let request = self.fill_transaction_request(from, request).await?;
 
             let transaction = self.sign_request(&from, request).await?.with_signer(from);
 
@@ -412,6 +400,76 @@ pub trait EthTransactions: LoadTransaction {
         }
     }
 
+    /// Fills in the fields of a transaction request sent by `from` that are required for
+    /// signing: a missing nonce, gas limit or fees and the chain id.
+    ///
+    /// Missing fees are filled as EIP-1559 fees with the suggested priority fee and a max fee
+    /// that covers twice the pending base fee.
+    fn fill_transaction_request(
+        &self,
+        from: Address,
+        mut request: TransactionRequest,
+    ) -> impl Future<Output = Result<TransactionRequest, Self::Error>> + Send
+    where
+        Self: EthApiSpec + LoadBlock + LoadPendingBlock + LoadFee + Call,
+    {
+        async move {
+            // set nonce if not already set before
+            if request.nonce.is_none() {
+                let nonce = self.transaction_count(from, Some(BlockId::pending())).await?;
+                // note: `.to()` can't panic because the nonce is constructed from a `u64`
+                request.nonce = Some(nonce.to());
+            }
+
+            let chain_id = self.chain_id();
+            request.chain_id = Some(chain_id.to());
+
+            if request.gas.is_none() {
+                let estimated_gas =
+                    self.estimate_gas_at(request.clone(), BlockId::pending(), None).await?;
+                request.set_gas_limit(estimated_gas.to());
+            }
+
+            if request.gas_price.is_none() && request.max_fee_per_gas.is_none() {
+                let (base_fee, priority_fee) = self
+                    .eip1559_fees(None, request.max_priority_fee_per_gas.map(U256::from))
+                    .await?;
+                let max_fee = base_fee.saturating_mul(U256::from(2)).saturating_add(priority_fee);
+                request.max_fee_per_gas = Some(max_fee.saturating_to());
+                request.max_priority_fee_per_gas = Some(priority_fee.saturating_to());
+            }
+
+            Ok(request)
+        }
+    }
+
+    /// Signs a transaction with a matching signer without submitting it to the pool.
+    ///
+    /// Missing fields are filled in, see [`EthTransactions::fill_transaction_request`]. Returns
+    /// the EIP-2718 encoded signed transaction.
+    fn sign_transaction(
+        &self,
+        request: TransactionRequest,
+    ) -> impl Future<Output = Result<Bytes, Self::Error>> + Send
+    where
+        Self: EthApiSpec + LoadBlock + LoadPendingBlock + LoadFee + Call,
+    {
+        async move {
+            let from = match request.from {
+                Some(from) => from,
+                None => return Err(SignError::NoAccount.into_eth_err()),
+            };
+
+            if self.find_signer(&from).is_err() {
+                return Err(SignError::NoAccount.into_eth_err())
+            }
+
+            let request = self.fill_transaction_request(from, request).await?;
+            let transaction = self.sign_request(&from, request).await?;
+            Ok(transaction.encoded_2718().into())
+        }
+    }
+
     /// Signs given message. Returns the signature.
     fn sign(
         &self,
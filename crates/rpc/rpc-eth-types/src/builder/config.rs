@@ -6,8 +6,10 @@ use crate::{
     EthStateCacheConfig, FeeHistoryCacheConfig, GasPriceOracleConfig, RPC_DEFAULT_GAS_CAP,
 };
 use reth_rpc_server_types::constants::{
-    default_max_tracing_requests, DEFAULT_ETH_PROOF_WINDOW, DEFAULT_MAX_BLOCKS_PER_FILTER,
-    DEFAULT_MAX_LOGS_PER_RESPONSE, DEFAULT_MAX_SIMULATE_BLOCKS, DEFAULT_PROOF_PERMITS,
+    default_max_tracing_requests, DEFAULT_ETH_PROOF_WINDOW, DEFAULT_JS_TRACER_LOOP_ITERATION_LIMIT,
+    DEFAULT_JS_TRACER_MEMORY_LIMIT, DEFAULT_JS_TRACER_RECURSION_LIMIT, DEFAULT_JS_TRACER_TIMEOUT,
+    DEFAULT_MAX_BLOCKS_PER_FILTER, DEFAULT_MAX_LOGS_PER_RESPONSE, DEFAULT_MAX_SIMULATE_BLOCKS,
+    DEFAULT_MAX_TRACE_CHAIN_BLOCKS, DEFAULT_PROOF_PERMITS,
 };
 use serde::{Deserialize, Serialize};
 
@@ -35,6 +37,8 @@ pub struct EthConfig {
     pub rpc_gas_cap: u64,
     /// Max number of blocks for `eth_simulateV1`.
     pub rpc_max_simulate_blocks: u64,
+    /// Max number of blocks for `debug_traceChain` and `debug_subscribeTraceChain`.
+    pub rpc_max_trace_chain_blocks: u64,
     ///
     /// Sets TTL for stale filters
     pub stale_filter_ttl: Duration,
@@ -42,6 +46,8 @@ pub struct EthConfig {
     pub fee_history_cache: FeeHistoryCacheConfig,
     /// The maximum number of getproof calls that can be executed concurrently.
     pub proof_permits: usize,
+    /// Limits for custom JS tracers.
+    pub js_tracer: JsTracerConfig,
 }
 
 impl EthConfig {
@@ -65,9 +71,11 @@ impl Default for EthConfig {
             max_logs_per_response: DEFAULT_MAX_LOGS_PER_RESPONSE,
             rpc_gas_cap: RPC_DEFAULT_GAS_CAP.into(),
             rpc_max_simulate_blocks: DEFAULT_MAX_SIMULATE_BLOCKS,
+            rpc_max_trace_chain_blocks: DEFAULT_MAX_TRACE_CHAIN_BLOCKS,
             stale_filter_ttl: DEFAULT_STALE_FILTER_TTL,
             fee_history_cache: FeeHistoryCacheConfig::default(),
             proof_permits: DEFAULT_PROOF_PERMITS,
+            js_tracer: JsTracerConfig::default(),
         }
     }
 }
@@ -115,6 +123,13 @@ impl EthConfig {
         self
     }
 
+    /// Configures the maximum number of blocks for `debug_traceChain` and
+    /// `debug_subscribeTraceChain`
+    pub const fn rpc_max_trace_chain_blocks(mut self, max_blocks: u64) -> Self {
+        self.rpc_max_trace_chain_blocks = max_blocks;
+        self
+    }
+
     /// Configures the maximum proof window for historical proof generation.
     pub const fn eth_proof_window(mut self, window: u64) -> Self {
         self.eth_proof_window = window;
@@ -126,6 +141,69 @@ impl EthConfig {
         self.proof_permits = permits;
         self
     }
+
+    /// Configures the limits for custom JS tracers
+    pub const fn js_tracer(mut self, js_tracer: JsTracerConfig) -> Self {
+        self.js_tracer = js_tracer;
+        self
+    }
+}
+
+/// Limits applied to custom JS tracers in the `debug` namespace.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JsTracerConfig {
+    /// Maximum time a JS tracer may spend on a single transaction.
+    ///
+    /// Requests can ask for a shorter timeout via the `timeout` tracing option.
+    pub timeout: Duration,
+    /// Maximum amount of memory in bytes a JS tracer may work with.
+    ///
+    /// This bounds the heap growth of the tracing thread, or the EVM memory exposed to the tracer
+    /// if heap statistics are unavailable, and the size of its result.
+    pub memory_limit: usize,
+    /// Maximum number of loop iterations a single call of a JS tracer hook may execute.
+    ///
+    /// Enforced by the JS engine, so hooks that never return are aborted.
+    pub loop_iteration_limit: u64,
+    /// Maximum recursion depth of a JS tracer, enforced by the JS engine.
+    pub recursion_limit: usize,
+}
+
+impl JsTracerConfig {
+    /// Configures the maximum time a JS tracer may spend on a single transaction
+    pub const fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Configures the memory limit of a JS tracer
+    pub const fn memory_limit(mut self, memory_limit: usize) -> Self {
+        self.memory_limit = memory_limit;
+        self
+    }
+
+    /// Configures the loop iteration limit of a single call of a JS tracer hook
+    pub const fn loop_iteration_limit(mut self, loop_iteration_limit: u64) -> Self {
+        self.loop_iteration_limit = loop_iteration_limit;
+        self
+    }
+
+    /// Configures the recursion limit of a JS tracer
+    pub const fn recursion_limit(mut self, recursion_limit: usize) -> Self {
+        self.recursion_limit = recursion_limit;
+        self
+    }
+}
+
+impl Default for JsTracerConfig {
+    fn default() -> Self {
+        Self {
+            timeout: DEFAULT_JS_TRACER_TIMEOUT,
+            memory_limit: DEFAULT_JS_TRACER_MEMORY_LIMIT,
+            loop_iteration_limit: DEFAULT_JS_TRACER_LOOP_ITERATION_LIMIT,
+            recursion_limit: DEFAULT_JS_TRACER_RECURSION_LIMIT,
+        }
+    }
 }
 
 /// Config for the filter
@@ -2,7 +2,7 @@
 
 use std::time::Duration;
 
-use alloy_primitives::{Address, Bytes, U256};
+use alloy_primitives::{Address, BlockNumber, Bytes, U256};
 use alloy_rpc_types::{error::EthRpcErrorCode, request::TransactionInputError, BlockError};
 use alloy_sol_types::decode_revert_reason;
 use reth_errors::RethError;
@@ -18,6 +18,9 @@ use revm::primitives::{EVMError, ExecutionResult, HaltReason, InvalidTransaction
 use revm_inspectors::tracing::MuxError;
 use tracing::error;
 
+/// Error code returned when the requested history has been expired, the same as used by geth.
+pub const PRUNED_HISTORY_UNAVAILABLE_CODE: i32 = 4444;
+
 /// A trait to convert an error to an RPC error.
 pub trait ToRpcError: core::error::Error + Send + Sync + 'static {
     /// Converts the error to a JSON-RPC error object.
@@ -57,6 +60,10 @@ pub enum EthApiError {
     /// Receipts not found for block hash/number/tag
     #[error("receipts not found")]
     ReceiptsNotFound(BlockId),
+    /// The requested history has been expired and is only available from the given block, see
+    /// [EIP-4444](https://eips.ethereum.org/EIPS/eip-4444).
+    #[error("pruned history unavailable")]
+    PrunedHistoryUnavailable(BlockNumber),
     /// Thrown when an unknown block or transaction index is encountered
     #[error("unknown block or tx index")]
     UnknownBlockOrTxIndex,
@@ -192,6 +199,9 @@ impl From<EthApiError> for jsonrpsee_types::error::ErrorObject<'static> {
                 EthRpcErrorCode::ResourceNotFound.code(),
                 format!("block not found: {}", block_id_to_str(id)),
             ),
+            EthApiError::PrunedHistoryUnavailable(_) => {
+                rpc_error_with_code(PRUNED_HISTORY_UNAVAILABLE_CODE, error.to_string())
+            }
             EthApiError::ReceiptsNotFound(id) => rpc_error_with_code(
                 EthRpcErrorCode::ResourceNotFound.code(),
                 format!("{error}: {}", block_id_to_str(id)),
@@ -255,6 +265,7 @@ impl From<reth_errors::ProviderError> for EthApiError {
             ProviderError::TotalDifficultyNotFound(num) => Self::HeaderNotFound(num.into()),
             ProviderError::FinalizedBlockNotFound => Self::HeaderNotFound(BlockId::finalized()),
             ProviderError::SafeBlockNotFound => Self::HeaderNotFound(BlockId::safe()),
+            ProviderError::HistoryExpired(block) => Self::PrunedHistoryUnavailable(block),
             err => Self::Internal(err.into()),
         }
     }
@@ -795,4 +806,12 @@ mod tests {
             EthApiError::HeaderNotFound(BlockId::finalized()).into();
         assert_eq!(err.message(), "block not found: finalized");
     }
+
+    #[test]
+    fn pruned_history_unavailable_error() {
+        let err: EthApiError = reth_errors::ProviderError::HistoryExpired(15537394).into();
+        let err: jsonrpsee_types::error::ErrorObject<'static> = err.into();
+        assert_eq!(err.code(), PRUNED_HISTORY_UNAVAILABLE_CODE);
+        assert_eq!(err.message(), "pruned history unavailable");
+    }
 }
@@ -23,7 +23,7 @@ pub mod transaction;
 pub mod utils;
 
 pub use builder::{
-    config::{EthConfig, EthFilterConfig},
+    config::{EthConfig, EthFilterConfig, JsTracerConfig},
     ctx::EthApiBuilderCtx,
 };
 pub use cache::{
@@ -1,4 +1,4 @@
-use std::cmp::max;
+use std::{cmp::max, time::Duration};
 
 /// The default port for the http server
 pub const DEFAULT_HTTP_RPC_PORT: u16 = 8545;
@@ -48,6 +48,21 @@ pub const DEFAULT_ENGINE_API_IPC_ENDPOINT: &str = "/tmp/reth_engine_api.ipc";
 /// The default limit for blocks count in `eth_simulateV1`.
 pub const DEFAULT_MAX_SIMULATE_BLOCKS: u64 = 256;
 
+/// The default limit for blocks count in `debug_traceChain` and `debug_subscribeTraceChain`.
+pub const DEFAULT_MAX_TRACE_CHAIN_BLOCKS: u64 = 1000;
+
+/// The default time a JS tracer may run for a single transaction.
+pub const DEFAULT_JS_TRACER_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The default memory limit of a JS tracer, 64 MiB.
+pub const DEFAULT_JS_TRACER_MEMORY_LIMIT: usize = 64 * 1024 * 1024;
+
+/// The default number of loop iterations a single call of a JS tracer hook may execute.
+pub const DEFAULT_JS_TRACER_LOOP_ITERATION_LIMIT: u64 = 200_000;
+
+/// The default recursion depth of a JS tracer.
+pub const DEFAULT_JS_TRACER_RECURSION_LIMIT: usize = 1_000;
+
 /// The default eth historical proof window.
 pub const DEFAULT_ETH_PROOF_WINDOW: u64 = 0;
 
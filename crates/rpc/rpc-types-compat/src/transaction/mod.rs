@@ -72,6 +72,10 @@ pub trait TransactionCompat: Send + Sync + Unpin + Clone + fmt::Debug {
     // <https://github.com/alloy-rs/alloy/issues/1315>.
     fn otterscan_api_truncate_input(tx: &mut Self::Transaction);
 
+    /// Returns the plain transaction object of a transaction, as returned by the
+    /// `ots_searchTransactions*` endpoints.
+    fn otterscan_api_transaction(tx: Self::Transaction) -> Transaction;
+
     /// Returns the transaction type.
     // todo: remove when alloy TransactionResponse trait it updated.
     fn tx_type(tx: &Self::Transaction) -> u8;
@@ -88,6 +92,10 @@ impl TransactionCompat for () {
 
     fn otterscan_api_truncate_input(_tx: &mut Self::Transaction) {}
 
+    fn otterscan_api_transaction(tx: Self::Transaction) -> Transaction {
+        tx.inner
+    }
+
     fn tx_type(_tx: &Self::Transaction) -> u8 {
         0
     }
@@ -1,4 +1,4 @@
-use std::sync::Arc;
+use std::{path::PathBuf, sync::Arc};
 
 use alloy_genesis::ChainConfig;
 use alloy_rpc_types_admin::{
@@ -12,31 +12,36 @@ use reth_network_api::{NetworkInfo, Peers};
 use reth_network_peers::{id2pk, AnyNode, NodeRecord};
 use reth_network_types::PeerKind;
 use reth_primitives::EthereumHardfork;
-use reth_rpc_api::AdminApiServer;
-use reth_rpc_server_types::ToRpcResult;
+use reth_provider::{SnapshotOptions, StorageSnapshotProvider};
+use reth_rpc_api::{AdminApiServer, AdminSnapshot, AdminSnapshotOptions};
+use reth_rpc_server_types::{result::internal_rpc_err, ToRpcResult};
+use tokio::sync::oneshot;
 
 /// `admin` API implementation.
 ///
 /// This type provides the functionality for handling `admin` related requests.
-pub struct AdminApi<N, ChainSpec> {
+pub struct AdminApi<N, ChainSpec, Provider> {
     /// An interface to interact with the network
     network: N,
     /// The specification of the blockchain's configuration.
     chain_spec: Arc<ChainSpec>,
+    /// The provider used to snapshot the storage of the node.
+    provider: Provider,
 }
 
-impl<N, ChainSpec> AdminApi<N, ChainSpec> {
+impl<N, ChainSpec, Provider> AdminApi<N, ChainSpec, Provider> {
     /// Creates a new instance of `AdminApi`.
-    pub const fn new(network: N, chain_spec: Arc<ChainSpec>) -> Self {
-        Self { network, chain_spec }
+    pub const fn new(network: N, chain_spec: Arc<ChainSpec>, provider: Provider) -> Self {
+        Self { network, chain_spec, provider }
     }
 }
 
 #[async_trait]
-impl<N, ChainSpec> AdminApiServer for AdminApi<N, ChainSpec>
+impl<N, ChainSpec, Provider> AdminApiServer for AdminApi<N, ChainSpec, Provider>
 where
     N: NetworkInfo + Peers + 'static,
     ChainSpec: EthChainSpec + EthereumHardforks + Send + Sync + 'static,
+    Provider: StorageSnapshotProvider + Clone + 'static,
 {
     /// Handler for `admin_addPeer`
     fn add_peer(&self, record: NodeRecord) -> RpcResult<bool> {
@@ -178,6 +183,42 @@ where
         })
     }
 
+    /// Handler for `admin_snapshot`
+    async fn snapshot(
+        &self,
+        dir: String,
+        options: Option<AdminSnapshotOptions>,
+    ) -> RpcResult<AdminSnapshot> {
+        let options = options.unwrap_or_default();
+        let options = SnapshotOptions {
+            compact: options.compact,
+            copy_static_files: options.copy_static_files,
+        };
+
+        // copying the database can take a long time, so it runs on a dedicated thread
+        let (tx, rx) = oneshot::channel();
+        let provider = self.provider.clone();
+        let path = PathBuf::from(&dir);
+        std::thread::Builder::new()
+            .name("admin-snapshot".to_string())
+            .spawn(move || {
+                let _ = tx.send(provider.create_snapshot(&path, options));
+            })
+            .map_err(|err| internal_rpc_err(err.to_string()))?;
+        let manifest = rx
+            .await
+            .map_err(|_| internal_rpc_err("snapshot task was dropped".to_string()))?
+            .to_rpc_result()?;
+
+        Ok(AdminSnapshot {
+            dir,
+            block_number: manifest.block_number,
+            compacted: manifest.compacted,
+            files: manifest.files.len(),
+            size: manifest.files.iter().map(|file| file.size).sum(),
+        })
+    }
+
     /// Handler for `admin_peerEvents`
     async fn subscribe_peer_events(
         &self,
@@ -187,7 +228,7 @@ where
     }
 }
 
-impl<N, ChainSpec> std::fmt::Debug for AdminApi<N, ChainSpec> {
+impl<N, ChainSpec, Provider> std::fmt::Debug for AdminApi<N, ChainSpec, Provider> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("AdminApi").finish_non_exhaustive()
     }
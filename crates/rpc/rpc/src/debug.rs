@@ -1,8 +1,10 @@
+use crate::eth::EthTxBuilder;
 use alloy_eips::eip2718::Encodable2718;
-use alloy_primitives::{Address, Bytes, B256, U256};
+use alloy_primitives::{Address, BlockNumber, Bytes, B256, U256};
 use alloy_rlp::{Decodable, Encodable};
 use alloy_rpc_types::{
-    state::EvmOverrides, Block as RpcBlock, BlockError, Bundle, StateContext, TransactionInfo,
+    state::EvmOverrides, Block as RpcBlock, BlockError, BlockTransactions, Bundle, StateContext,
+    TransactionInfo,
 };
 use alloy_rpc_types_debug::ExecutionWitness;
 use alloy_rpc_types_eth::transaction::TransactionRequest;
@@ -12,27 +14,43 @@ use alloy_rpc_types_trace::geth::{
     NoopFrame, TraceResult,
 };
 use async_trait::async_trait;
-use jsonrpsee::core::RpcResult;
+use futures::{Stream, StreamExt, TryStreamExt};
+use jsonrpsee::{
+    core::RpcResult, server::SubscriptionMessage, types::ErrorObjectOwned, PendingSubscriptionSink,
+};
 use reth_chainspec::EthereumHardforks;
+use reth_db::{database_metrics::DatabaseMetadataValue, Tables};
+use reth_errors::RethResult;
 use reth_evm::{
     execute::{BlockExecutorProvider, Executor},
     ConfigureEvmEnv,
 };
-use reth_primitives::{Block, BlockId, BlockNumberOrTag, TransactionSignedEcRecovered};
+use reth_primitives::{
+    Block, BlockId, BlockNumberOrTag, BlockWithSenders, Receipt, SealedBlock, StaticFileSegment,
+    TransactionSignedEcRecovered,
+};
 use reth_provider::{
-    BlockReaderIdExt, ChainSpecProvider, EvmEnvProvider, HeaderProvider, StateProofProvider,
-    StateProviderFactory, TransactionVariant,
+    providers::StaticFileProvider, BadBlockReader, BlockHashReader, BlockReader, BlockReaderIdExt,
+    ChainSpecProvider, EvmEnvProvider, HashedAccountEntry, HashedStateRangeReader, HeaderProvider,
+    RawDatabaseReader, ReceiptProvider, StateProofProvider, StateProviderFactory,
+    StaticFileProviderFactory, TransactionVariant,
 };
 use reth_revm::database::StateProviderDatabase;
-use reth_rpc_api::DebugApiServer;
+use reth_rpc_api::{
+    BadBlock, DebugApiServer, DumpAccount, StateDump, StorageRangeEntry, StorageRangeResult,
+};
 use reth_rpc_eth_api::{
-    helpers::{Call, EthApiSpec, EthTransactions, TraceExt},
+    helpers::{Call, EthApiSpec, EthTransactions, SpawnBlocking, TraceExt},
     EthApiTypes, FromEthApiError,
 };
-use reth_rpc_eth_types::{EthApiError, StateCacheDb};
-use reth_rpc_server_types::{result::internal_rpc_err, ToRpcResult};
+use reth_rpc_eth_types::{EthApiError, JsTracerConfig, StateCacheDb};
+use reth_rpc_server_types::{
+    result::{internal_rpc_err, invalid_params_rpc_err},
+    ToRpcResult,
+};
+use reth_rpc_types_compat::block::{from_block_full, from_block_with_tx_hashes};
 use reth_tasks::pool::BlockingTaskGuard;
-use reth_trie::{HashedPostState, HashedStorage};
+use reth_trie::{root::storage_root, HashedPostState, HashedStorage};
 use revm::{
     db::{CacheDB, State},
     primitives::{db::DatabaseCommit, BlockEnv, CfgEnvWithHandlerCfg, Env, EnvWithHandlerCfg},
@@ -40,10 +58,25 @@ use revm::{
 use revm_inspectors::tracing::{
     FourByteInspector, MuxInspector, TracingInspector, TracingInspectorConfig, TransactionContext,
 };
-use revm_primitives::{keccak256, HashMap};
-use std::sync::Arc;
+use revm_primitives::{keccak256, HashMap, KECCAK_EMPTY};
+use std::{collections::BTreeMap, fmt::Write, future::Future, ops::RangeInclusive, sync::Arc};
 use tokio::sync::{AcquireError, OwnedSemaphorePermit};
 
+/// The maximum number of blocks that are traced in parallel by `debug_traceChain`.
+const TRACE_CHAIN_CONCURRENCY: usize = 8;
+
+/// The maximum number of accounts returned by `debug_accountRange`, same as geth.
+const ACCOUNT_RANGE_MAX_RESULTS: u64 = 256;
+
+/// The maximum number of storage slots returned by `debug_storageRangeAt`.
+const STORAGE_RANGE_MAX_RESULTS: u64 = 1024;
+
+/// The maximum number of accounts returned by a single `debug_dumpBlock` call.
+const DUMP_BLOCK_MAX_RESULTS: usize = 256;
+
+/// The properties supported by `debug_chaindbProperty`.
+const CHAINDB_PROPERTIES: [&str; 3] = ["mdbx.stats", "mdbx.freelist", "static_files"];
+
 /// `debug` API implementation.
 ///
 /// This type provides the functionality for handling `debug` related requests.
@@ -60,9 +93,19 @@ impl<Provider, Eth, BlockExecutor> DebugApi<Provider, Eth, BlockExecutor> {
         eth: Eth,
         blocking_task_guard: BlockingTaskGuard,
         block_executor: BlockExecutor,
+        js_tracer_config: JsTracerConfig,
+        max_trace_chain_blocks: u64,
+        set_head_handle: Option<Arc<dyn SetHeadHandle>>,
     ) -> Self {
-        let inner =
-            Arc::new(DebugApiInner { provider, eth_api: eth, blocking_task_guard, block_executor });
+        let inner = Arc::new(DebugApiInner {
+            provider,
+            eth_api: eth,
+            blocking_task_guard,
+            block_executor,
+            js_tracer_config,
+            max_trace_chain_blocks,
+            set_head_handle,
+        });
         Self { inner }
     }
 
@@ -81,6 +124,9 @@ where
         + ChainSpecProvider<ChainSpec: EthereumHardforks>
         + StateProviderFactory
         + EvmEnvProvider
+        + HashedStateRangeReader
+        + RawDatabaseReader
+        + StaticFileProviderFactory
         + 'static,
     Eth: EthApiTypes + TraceExt + 'static,
     BlockExecutor: BlockExecutorProvider,
@@ -90,6 +136,19 @@ where
         self.inner.blocking_task_guard.clone().acquire_owned().await
     }
 
+    /// Returns the limits for a JS tracer, honoring the `timeout` requested in the tracing
+    /// options as long as it does not exceed the configured one.
+    #[cfg(feature = "js-tracer")]
+    fn js_tracer_limits(&self, timeout: Option<String>) -> Result<JsTracerConfig, EthApiError> {
+        let mut limits = self.inner.js_tracer_config;
+        if let Some(timeout) = timeout {
+            let timeout = humantime::parse_duration(&timeout)
+                .map_err(|err| EthApiError::InvalidParams(format!("invalid timeout: {err}")))?;
+            limits.timeout = limits.timeout.min(timeout);
+        }
+        Ok(limits)
+    }
+
     /// Trace the entire block asynchronously
     async fn trace_block(
         &self,
@@ -227,6 +286,52 @@ where
         .await
     }
 
+    /// Traces all blocks between `start_exclusive` and `end_inclusive` with the given options.
+    ///
+    /// Returns a stream that yields one [`BlockTraceResult`] per block in ascending order. Up to
+    /// [`TRACE_CHAIN_CONCURRENCY`] blocks are traced in parallel, each of them holding a tracing
+    /// permit while it is being traced.
+    ///
+    /// Fails if the range covers more than the configured maximum number of blocks.
+    pub fn debug_trace_chain_stream(
+        &self,
+        start_exclusive: BlockNumberOrTag,
+        end_inclusive: BlockNumberOrTag,
+        opts: GethDebugTracingOptions,
+    ) -> Result<impl Stream<Item = Result<BlockTraceResult, Eth::Error>> + Send, Eth::Error> {
+        let start = self
+            .inner
+            .provider
+            .convert_block_number(start_exclusive)
+            .map_err(Eth::Error::from_eth_err)?
+            .ok_or(EthApiError::HeaderNotFound(start_exclusive.into()))?;
+        let end = self
+            .inner
+            .provider
+            .convert_block_number(end_inclusive)
+            .map_err(Eth::Error::from_eth_err)?
+            .ok_or(EthApiError::HeaderNotFound(end_inclusive.into()))?;
+
+        let blocks = trace_chain_blocks(start, end, self.inner.max_trace_chain_blocks)?;
+
+        let this = self.clone();
+        Ok(trace_chain_stream(blocks, move |number| {
+            let this = this.clone();
+            let opts = opts.clone();
+            async move {
+                let _permit = this.acquire_trace_permit().await;
+                let hash = this
+                    .inner
+                    .provider
+                    .block_hash(number)
+                    .map_err(Eth::Error::from_eth_err)?
+                    .ok_or(EthApiError::HeaderNotFound(number.into()))?;
+                let traces = this.debug_trace_block(hash.into(), opts).await?;
+                Ok(BlockTraceResult { block: number.into(), hash, traces })
+            }
+        }))
+    }
+
     /// Trace the transaction according to the provided options.
     ///
     /// Ref: <https://geth.ethereum.org/docs/developers/evm-tracing/built-in-tracers>
@@ -303,7 +408,14 @@ where
         let GethDebugTracingCallOptions { tracing_options, state_overrides, block_overrides } =
             opts;
         let overrides = EvmOverrides::new(state_overrides, block_overrides.map(Box::new));
-        let GethDebugTracingOptions { config, tracer, tracer_config, .. } = tracing_options;
+        let GethDebugTracingOptions {
+            config,
+            tracer,
+            tracer_config,
+            #[cfg(feature = "js-tracer")]
+            timeout,
+            ..
+        } = tracing_options;
 
         let this = self.clone();
         if let Some(tracer) = tracer {
@@ -430,6 +542,7 @@ where
                 #[cfg(feature = "js-tracer")]
                 GethDebugTracerType::JsTracer(code) => {
                     let config = tracer_config.into_json();
+                    let limits = self.js_tracer_limits(timeout)?;
 
                     let (_, _, at) = self.eth_api().evm_env_at(at).await?;
 
@@ -441,11 +554,16 @@ where
                             let db = db.0;
 
                             let mut inspector =
-                                revm_inspectors::tracing::js::JsInspector::new(code, config)
+                                js::new_inspector(code, config, Default::default(), limits)
                                     .map_err(Eth::Error::from_eth_err)?;
                             let (res, _) =
                                 this.eth_api().inspect(&mut *db, env.clone(), &mut inspector)?;
-                            inspector.json_result(res, &env, db).map_err(Eth::Error::from_eth_err)
+                            let result = inspector
+                                .into_inner()?
+                                .json_result(res, &env, db)
+                                .map_err(Eth::Error::from_eth_err)?;
+                            js::check_result_size(&result, &limits)?;
+                            Ok(result)
                         })
                         .await?;
 
@@ -669,6 +787,159 @@ where
             .await
     }
 
+    /// Returns up to `limit` accounts of the state at the end of the given block, starting at the
+    /// hashed address `start`.
+    ///
+    /// Accounts are keyed by their hashed address, since address preimages are not stored.
+    pub async fn debug_state_dump(
+        &self,
+        block_number: BlockNumber,
+        start: B256,
+        limit: usize,
+        with_code: bool,
+        with_storage: bool,
+    ) -> Result<StateDump, Eth::Error> {
+        let this = self.clone();
+        self.eth_api()
+            .spawn_blocking_io(move |_| {
+                let provider = &this.inner.provider;
+                let root = provider
+                    .header_by_number(block_number)
+                    .map_err(Eth::Error::from_eth_err)?
+                    .ok_or(EthApiError::HeaderNotFound(block_number.into()))?
+                    .state_root;
+                let range = provider
+                    .hashed_account_range(
+                        block_number,
+                        HashedPostState::default(),
+                        start,
+                        limit,
+                        with_storage,
+                    )
+                    .map_err(Eth::Error::from_eth_err)?;
+                // bytecodes are never removed, so the latest state has the code of all accounts
+                let state = provider.latest().map_err(Eth::Error::from_eth_err)?;
+
+                let mut accounts = BTreeMap::new();
+                for HashedAccountEntry { hashed_address, account, storage } in range.accounts {
+                    let code_hash = account.get_bytecode_hash();
+                    let code = if with_code && code_hash != KECCAK_EMPTY {
+                        state
+                            .bytecode_by_hash(code_hash)
+                            .map_err(Eth::Error::from_eth_err)?
+                            .map(|code| code.original_bytes())
+                    } else {
+                        None
+                    };
+
+                    let root =
+                        storage.as_ref().map(|storage| storage_root(storage.iter().copied()));
+                    let storage = storage.filter(|storage| !storage.is_empty()).map(|storage| {
+                        storage.into_iter().map(|(slot, value)| (slot, value.into())).collect()
+                    });
+
+                    accounts.insert(
+                        format!("pre({hashed_address})"),
+                        DumpAccount {
+                            balance: account.balance.to_string(),
+                            nonce: account.nonce,
+                            root,
+                            code_hash,
+                            code,
+                            storage,
+                            address: None,
+                            key: hashed_address,
+                        },
+                    );
+                }
+
+                Ok(StateDump { root, accounts, next: range.next_key })
+            })
+            .await
+    }
+
+    /// Returns up to `limit` storage slots of the account, starting at the hashed slot `start`, as
+    /// they were right before the transaction at `tx_index` of the given block was executed.
+    pub async fn debug_storage_range_at(
+        &self,
+        block_hash: B256,
+        tx_index: usize,
+        address: Address,
+        start: B256,
+        limit: usize,
+    ) -> Result<StorageRangeResult, Eth::Error> {
+        let block = self
+            .eth_api()
+            .block_with_senders(block_hash.into())
+            .await?
+            .ok_or(EthApiError::HeaderNotFound(block_hash.into()))?;
+        if tx_index > 0 && tx_index >= block.body.transactions.len() {
+            return Err(EthApiError::InvalidParams(format!(
+                "transaction index {tx_index} out of range"
+            ))
+            .into())
+        }
+        let (cfg, block_env, _) = self.eth_api().evm_env_at(block_hash.into()).await?;
+
+        // the genesis block has no parent and no transactions, its storage is the genesis state
+        let (state_block_hash, state_block_number) = match block.number.checked_sub(1) {
+            Some(parent_number) => (block.parent_hash, parent_number),
+            None => (block_hash, block.number),
+        };
+
+        let this = self.clone();
+        self.eth_api()
+            .spawn_with_state_at_block(state_block_hash.into(), move |state| {
+                let mut db = CacheDB::new(StateProviderDatabase::new(state));
+
+                // replay all transactions prior to the targeted transaction
+                if let Some(tx) = block.body.transactions.get(tx_index) {
+                    this.eth_api().replay_transactions_until(
+                        &mut db,
+                        cfg,
+                        block_env,
+                        block.transactions_with_sender(),
+                        tx.hash(),
+                    )?;
+                }
+
+                // the storage changes of the replayed transactions are applied on top of the state
+                // the block is executed on
+                let overlay = db
+                    .accounts
+                    .get(&address)
+                    .map(|account| {
+                        let storage = HashedStorage::from_iter(
+                            account.account_state.is_storage_cleared(),
+                            account
+                                .storage
+                                .iter()
+                                .map(|(slot, value)| (keccak256(B256::from(*slot)), *value)),
+                        );
+                        HashedPostState::from_hashed_storage(keccak256(address), storage)
+                    })
+                    .unwrap_or_default();
+
+                let range = this
+                    .inner
+                    .provider
+                    .hashed_storage_range(state_block_number, overlay, address, start, limit)
+                    .map_err(Eth::Error::from_eth_err)?;
+
+                Ok(StorageRangeResult {
+                    storage: range
+                        .storage
+                        .into_iter()
+                        .map(|(hashed_slot, value)| {
+                            (hashed_slot, StorageRangeEntry { key: None, value: value.into() })
+                        })
+                        .collect(),
+                    next_key: range.next_key,
+                })
+            })
+            .await
+    }
+
     /// Executes the configured transaction with the environment on the given database.
     ///
     /// Returns the trace frame and the state that got updated after executing the transaction.
@@ -683,7 +954,14 @@ where
         db: &mut StateCacheDb<'_>,
         transaction_context: Option<TransactionContext>,
     ) -> Result<(GethTrace, revm_primitives::EvmState), Eth::Error> {
-        let GethDebugTracingOptions { config, tracer, tracer_config, .. } = opts;
+        let GethDebugTracingOptions {
+            config,
+            tracer,
+            tracer_config,
+            #[cfg(feature = "js-tracer")]
+            timeout,
+            ..
+        } = opts;
 
         if let Some(tracer) = tracer {
             return match tracer {
@@ -779,18 +1057,22 @@ where
                 #[cfg(feature = "js-tracer")]
                 GethDebugTracerType::JsTracer(code) => {
                     let config = tracer_config.into_json();
-                    let mut inspector =
-                        revm_inspectors::tracing::js::JsInspector::with_transaction_context(
-                            code,
-                            config,
-                            transaction_context.unwrap_or_default(),
-                        )
-                        .map_err(Eth::Error::from_eth_err)?;
+                    let limits = self.js_tracer_limits(timeout)?;
+                    let mut inspector = js::new_inspector(
+                        code,
+                        config,
+                        transaction_context.unwrap_or_default(),
+                        limits,
+                    )
+                    .map_err(Eth::Error::from_eth_err)?;
                     let (res, env) = self.eth_api().inspect(&mut *db, env, &mut inspector)?;
 
                     let state = res.state.clone();
-                    let result =
-                        inspector.json_result(res, &env, db).map_err(Eth::Error::from_eth_err)?;
+                    let result = inspector
+                        .into_inner()?
+                        .json_result(res, &env, db)
+                        .map_err(Eth::Error::from_eth_err)?;
+                    js::check_result_size(&result, &limits)?;
                     Ok((GethTrace::JS(result), state))
                 }
             }
@@ -811,6 +1093,126 @@ where
 
         Ok((frame.into(), res.state))
     }
+
+    /// Returns the given property of the database or the static files, see
+    /// `debug_chaindbProperty`.
+    ///
+    /// An empty property is treated as `mdbx.stats`.
+    pub async fn debug_chaindb_property(&self, property: String) -> Result<String, Eth::Error> {
+        let this = self.clone();
+        self.eth_api()
+            .spawn_blocking_io(move |_| {
+                let provider = &this.inner.provider;
+                match property.as_str() {
+                    "" | "mdbx.stats" => {
+                        let metadata =
+                            provider.database_metadata().map_err(Eth::Error::from_eth_err)?;
+                        Ok(format_table_stats(&metadata))
+                    }
+                    "mdbx.freelist" => {
+                        let metadata =
+                            provider.database_metadata().map_err(Eth::Error::from_eth_err)?;
+                        Ok(format_freelist(&metadata))
+                    }
+                    "static_files" => Ok(format_static_files(&provider.static_file_provider())),
+                    _ => Err(Eth::Error::from_eth_err(EthApiError::InvalidParams(format!(
+                        "unknown property {property:?}, supported properties: {}",
+                        CHAINDB_PROPERTIES.join(", ")
+                    )))),
+                }
+            })
+            .await
+    }
+
+    /// Returns the raw value stored under the given key, see `debug_dbGet`.
+    ///
+    /// The key has the format `<table>:<hex encoded raw key>`, e.g. `CanonicalHeaders:0x00..01`.
+    pub async fn debug_db_get(&self, key: String) -> Result<Bytes, Eth::Error> {
+        let (table, key) = key.split_once(':').ok_or_else(|| {
+            Eth::Error::from_eth_err(EthApiError::InvalidParams(
+                "key must have the format <table>:<hex encoded key>".to_string(),
+            ))
+        })?;
+        let table = table
+            .parse::<Tables>()
+            .map_err(|err| Eth::Error::from_eth_err(EthApiError::InvalidParams(err)))?;
+        let key = key
+            .parse::<Bytes>()
+            .map_err(|err| Eth::Error::from_eth_err(EthApiError::InvalidParams(err.to_string())))?;
+
+        let this = self.clone();
+        self.eth_api()
+            .spawn_blocking_io(move |_| {
+                this.inner
+                    .provider
+                    .raw_table_value(table, key.to_vec())
+                    .map_err(Eth::Error::from_eth_err)?
+                    .map(Bytes::from)
+                    .ok_or_else(|| {
+                        Eth::Error::from_eth_err(EthApiError::InvalidParams(format!(
+                            "key not found in table {table}"
+                        )))
+                    })
+            })
+            .await
+    }
+
+    /// Returns an item of a block that was moved to the static files, see
+    /// [`DebugApiServer::debug_db_ancient`] for the supported kinds.
+    pub async fn debug_db_ancient(&self, kind: String, number: u64) -> Result<Bytes, Eth::Error> {
+        let this = self.clone();
+        self.eth_api()
+            .spawn_blocking_io(move |_| {
+                let provider = &this.inner.provider;
+                let ancients = provider
+                    .static_file_provider()
+                    .get_highest_static_file_block(StaticFileSegment::Headers)
+                    .map_or(0, |block| block + 1);
+                if number >= ancients {
+                    return Err(Eth::Error::from_eth_err(EthApiError::InvalidParams(format!(
+                        "block {number} is not in the static files"
+                    ))))
+                }
+
+                let item: Option<Bytes> = match kind.as_str() {
+                    "headers" => provider
+                        .header_by_number(number)
+                        .map_err(Eth::Error::from_eth_err)?
+                        .map(|header| alloy_rlp::encode(header).into()),
+                    "hashes" => provider
+                        .block_hash(number)
+                        .map_err(Eth::Error::from_eth_err)?
+                        .map(|hash| Bytes::copy_from_slice(hash.as_slice())),
+                    "bodies" => provider
+                        .block_by_number(number)
+                        .map_err(Eth::Error::from_eth_err)?
+                        .map(|block| alloy_rlp::encode(block.body).into()),
+                    "receipts" => provider
+                        .receipts_by_block(number.into())
+                        .map_err(Eth::Error::from_eth_err)?
+                        .map(|receipts| {
+                            let receipts =
+                                receipts.into_iter().map(Receipt::with_bloom).collect::<Vec<_>>();
+                            alloy_rlp::encode(receipts).into()
+                        }),
+                    "diffs" => provider
+                        .header_td_by_number(number)
+                        .map_err(Eth::Error::from_eth_err)?
+                        .map(|td| alloy_rlp::encode(td).into()),
+                    _ => {
+                        return Err(Eth::Error::from_eth_err(EthApiError::InvalidParams(format!(
+                            "unknown ancient kind {kind}"
+                        ))))
+                    }
+                };
+                item.ok_or_else(|| {
+                    Eth::Error::from_eth_err(EthApiError::InvalidParams(format!(
+                        "{kind} of block {number} not found"
+                    )))
+                })
+            })
+            .await
+    }
 }
 
 #[async_trait]
@@ -821,6 +1223,10 @@ where
         + ChainSpecProvider<ChainSpec: EthereumHardforks>
         + StateProviderFactory
         + EvmEnvProvider
+        + BadBlockReader
+        + HashedStateRangeReader
+        + RawDatabaseReader
+        + StaticFileProviderFactory
         + 'static,
     Eth: EthApiSpec + EthTransactions + TraceExt + 'static,
     BlockExecutor: BlockExecutorProvider,
@@ -896,17 +1302,89 @@ where
     }
 
     /// Handler for `debug_getBadBlocks`
-    async fn bad_blocks(&self) -> RpcResult<Vec<RpcBlock>> {
-        Err(internal_rpc_err("unimplemented"))
+    async fn bad_blocks(&self) -> RpcResult<Vec<BadBlock>> {
+        let bad_blocks = self.inner.provider.bad_blocks().to_rpc_result()?;
+
+        let mut blocks = Vec::with_capacity(bad_blocks.len());
+        for bad_block in bad_blocks.into_iter().rev() {
+            let block = bad_block.block;
+            let total_difficulty = self
+                .inner
+                .provider
+                .header_td(&block.parent_hash)
+                .to_rpc_result()?
+                .unwrap_or_default() +
+                block.difficulty;
+
+            let mut rlp = Vec::with_capacity(block.length());
+            block.encode(&mut rlp);
+
+            blocks.push(BadBlock {
+                hash: block.hash(),
+                block: bad_block_to_rpc(block, total_difficulty),
+                rlp: rlp.into(),
+                error: bad_block.error,
+            });
+        }
+
+        Ok(blocks)
     }
 
     /// Handler for `debug_traceChain`
     async fn debug_trace_chain(
         &self,
-        _start_exclusive: BlockNumberOrTag,
-        _end_inclusive: BlockNumberOrTag,
+        start_exclusive: BlockNumberOrTag,
+        end_inclusive: BlockNumberOrTag,
+        opts: Option<GethDebugTracingOptions>,
     ) -> RpcResult<Vec<BlockTraceResult>> {
-        Err(internal_rpc_err("unimplemented"))
+        Self::debug_trace_chain_stream(
+            self,
+            start_exclusive,
+            end_inclusive,
+            opts.unwrap_or_default(),
+        )
+        .map_err(Into::into)?
+        .try_collect()
+        .await
+        .map_err(Into::into)
+    }
+
+    /// Handler for `debug_subscribeTraceChain`
+    async fn debug_subscribe_trace_chain(
+        &self,
+        pending: PendingSubscriptionSink,
+        start_exclusive: BlockNumberOrTag,
+        end_inclusive: BlockNumberOrTag,
+        opts: Option<GethDebugTracingOptions>,
+    ) -> jsonrpsee::core::SubscriptionResult {
+        let stream = match Self::debug_trace_chain_stream(
+            self,
+            start_exclusive,
+            end_inclusive,
+            opts.unwrap_or_default(),
+        ) {
+            Ok(stream) => stream,
+            Err(err) => {
+                pending.reject(err).await;
+                return Ok(())
+            }
+        };
+
+        let sink = pending.accept().await?;
+        let mut stream = std::pin::pin!(stream);
+        while let Some(result) = stream.next().await {
+            let trace = result.map_err(|err| {
+                let err: ErrorObjectOwned = err.into();
+                err.message().to_string()
+            })?;
+            let msg = SubscriptionMessage::from_json(&trace)?;
+            if sink.send(msg).await.is_err() {
+                // the subscriber went away
+                break
+            }
+        }
+
+        Ok(())
     }
 
     /// Handler for `debug_traceBlock`
@@ -993,48 +1471,100 @@ where
         Ok(())
     }
 
+    /// Handler for `debug_accountRange`
     async fn debug_account_range(
         &self,
-        _block_number: BlockNumberOrTag,
-        _start: Bytes,
-        _max_results: u64,
-        _nocode: bool,
-        _nostorage: bool,
+        block_number: BlockNumberOrTag,
+        start: Bytes,
+        max_results: u64,
+        nocode: bool,
+        nostorage: bool,
         _incompletes: bool,
-    ) -> RpcResult<()> {
-        Ok(())
-    }
+    ) -> RpcResult<StateDump> {
+        let number = self
+            .inner
+            .provider
+            .convert_block_number(block_number)
+            .to_rpc_result()?
+            .ok_or_else(|| internal_rpc_err("Pending block not supported".to_string()))?;
 
-    async fn debug_block_profile(&self, _file: String, _seconds: u64) -> RpcResult<()> {
-        Ok(())
+        // the start key may be a prefix of a hashed address
+        if start.len() > B256::len_bytes() {
+            return Err(invalid_params_rpc_err("start key longer than 32 bytes"))
+        }
+        let mut start_key = B256::ZERO;
+        start_key[..start.len()].copy_from_slice(&start);
+
+        let limit = if max_results == 0 || max_results > ACCOUNT_RANGE_MAX_RESULTS {
+            ACCOUNT_RANGE_MAX_RESULTS
+        } else {
+            max_results
+        };
+
+        let _permit = self.acquire_trace_permit().await;
+        Self::debug_state_dump(self, number, start_key, limit as usize, !nocode, !nostorage)
+            .await
+            .map_err(Into::into)
     }
 
-    async fn debug_chaindb_compact(&self) -> RpcResult<()> {
+    async fn debug_block_profile(&self, _file: String, _seconds: u64) -> RpcResult<()> {
         Ok(())
     }
 
-    async fn debug_chaindb_property(&self, _property: String) -> RpcResult<()> {
-        Ok(())
+    /// Handler for `debug_chaindbProperty`
+    async fn debug_chaindb_property(&self, property: String) -> RpcResult<String> {
+        Self::debug_chaindb_property(self, property).await.map_err(Into::into)
     }
 
     async fn debug_cpu_profile(&self, _file: String, _seconds: u64) -> RpcResult<()> {
         Ok(())
     }
 
-    async fn debug_db_ancient(&self, _kind: String, _number: u64) -> RpcResult<()> {
-        Ok(())
+    /// Handler for `debug_dbAncient`
+    async fn debug_db_ancient(&self, kind: String, number: u64) -> RpcResult<Bytes> {
+        Self::debug_db_ancient(self, kind, number).await.map_err(Into::into)
     }
 
-    async fn debug_db_ancients(&self) -> RpcResult<()> {
-        Ok(())
+    /// Handler for `debug_dbAncients`
+    ///
+    /// Returns the number of blocks that were moved to the static files.
+    async fn debug_db_ancients(&self) -> RpcResult<u64> {
+        Ok(self
+            .inner
+            .provider
+            .static_file_provider()
+            .get_highest_static_file_block(StaticFileSegment::Headers)
+            .map_or(0, |block| block + 1))
     }
 
-    async fn debug_db_get(&self, _key: String) -> RpcResult<()> {
-        Ok(())
+    /// Handler for `debug_dbGet`
+    async fn debug_db_get(&self, key: String) -> RpcResult<Bytes> {
+        Self::debug_db_get(self, key).await.map_err(Into::into)
     }
 
-    async fn debug_dump_block(&self, _number: BlockId) -> RpcResult<()> {
-        Ok(())
+    /// Handler for `debug_dumpBlock`
+    ///
+    /// Returns at most [`DUMP_BLOCK_MAX_RESULTS`] accounts, the remaining accounts are fetched by
+    /// passing [`StateDump::next`] as `start`.
+    async fn debug_dump_block(&self, number: BlockId, start: Option<B256>) -> RpcResult<StateDump> {
+        let number = self
+            .inner
+            .provider
+            .block_number_for_id(number)
+            .to_rpc_result()?
+            .ok_or(EthApiError::HeaderNotFound(number))?;
+
+        let _permit = self.acquire_trace_permit().await;
+        Self::debug_state_dump(
+            self,
+            number,
+            start.unwrap_or_default(),
+            DUMP_BLOCK_MAX_RESULTS,
+            true,
+            true,
+        )
+        .await
+        .map_err(Into::into)
     }
 
     async fn debug_free_os_memory(&self) -> RpcResult<()> {
@@ -1113,8 +1643,12 @@ where
         Ok(())
     }
 
-    async fn debug_set_head(&self, _number: u64) -> RpcResult<()> {
-        Ok(())
+    /// Handler for `debug_setHead`
+    async fn debug_set_head(&self, number: u64) -> RpcResult<()> {
+        let Some(handle) = &self.inner.set_head_handle else {
+            return Err(internal_rpc_err("debug_setHead is not supported by this node"))
+        };
+        handle.set_head(number).await.map_err(|err| internal_rpc_err(err.to_string()))
     }
 
     async fn debug_set_mutex_profile_fraction(&self, _rate: i32) -> RpcResult<()> {
@@ -1161,15 +1695,31 @@ where
         Ok(())
     }
 
+    /// Handler for `debug_storageRangeAt`
     async fn debug_storage_range_at(
         &self,
-        _block_hash: B256,
-        _tx_idx: usize,
-        _contract_address: Address,
-        _key_start: B256,
-        _max_result: u64,
-    ) -> RpcResult<()> {
-        Ok(())
+        block_hash: B256,
+        tx_idx: usize,
+        contract_address: Address,
+        key_start: B256,
+        max_result: u64,
+    ) -> RpcResult<StorageRangeResult> {
+        let limit = if max_result == 0 || max_result > STORAGE_RANGE_MAX_RESULTS {
+            STORAGE_RANGE_MAX_RESULTS
+        } else {
+            max_result
+        };
+        let _permit = self.acquire_trace_permit().await;
+        Self::debug_storage_range_at(
+            self,
+            block_hash,
+            tx_idx,
+            contract_address,
+            key_start,
+            limit as usize,
+        )
+        .await
+        .map_err(Into::into)
     }
 
     async fn debug_trace_bad_block(
@@ -1201,6 +1751,66 @@ where
     }
 }
 
+/// Formats the per-table statistics of the database followed by the freelist, for
+/// `debug_chaindbProperty`.
+fn format_table_stats(metadata: &DatabaseMetadataValue) -> String {
+    let mut out = format!(
+        "{:<32} {:>14} {:>12} {:>12} {:>14} {:>16}\n",
+        "Table", "Entries", "Branch pages", "Leaf pages", "Overflow pages", "Size (bytes)"
+    );
+    let mut total_size = 0;
+    for stats in metadata.table_stats() {
+        total_size += stats.size();
+        let _ = writeln!(
+            out,
+            "{:<32} {:>14} {:>12} {:>12} {:>14} {:>16}",
+            stats.name,
+            stats.entries,
+            stats.branch_pages,
+            stats.leaf_pages,
+            stats.overflow_pages,
+            stats.size()
+        );
+    }
+    let _ = writeln!(out, "{:<32} {:>72}", "Total", total_size);
+    out.push_str(&format_freelist(metadata));
+    out
+}
+
+/// Formats the size of the freelist of the database, for `debug_chaindbProperty`.
+fn format_freelist(metadata: &DatabaseMetadataValue) -> String {
+    match (metadata.freelist_size(), metadata.page_size()) {
+        (Some(pages), Some(page_size)) => {
+            format!("Freelist: {pages} pages ({} bytes)\n", pages * page_size)
+        }
+        (Some(pages), None) => format!("Freelist: {pages} pages\n"),
+        _ => "Freelist: unavailable\n".to_string(),
+    }
+}
+
+/// Formats the highest block and transaction of every static file segment, for
+/// `debug_chaindbProperty`.
+fn format_static_files(provider: &StaticFileProvider) -> String {
+    let mut out = format!("Static files: {}\n", provider.directory().display());
+    for segment in [
+        StaticFileSegment::Headers,
+        StaticFileSegment::Transactions,
+        StaticFileSegment::Receipts,
+        StaticFileSegment::AccountChangeSets,
+        StaticFileSegment::StorageChangeSets,
+    ] {
+        let blocks = provider
+            .get_highest_static_file_block(segment)
+            .map_or_else(|| "empty".to_string(), |block| format!("0..={block}"));
+        let rows = provider
+            .get_highest_static_file_tx(segment)
+            .map_or_else(|| "none".to_string(), |tx| format!("0..={tx}"));
+        let kind = if segment.is_change_based() { "changes" } else { "transactions" };
+        let _ = writeln!(out, "{segment}: blocks {blocks}, {kind} {rows}");
+    }
+    out
+}
+
 impl<Provider, Eth, BlockExecutor> std::fmt::Debug for DebugApi<Provider, Eth, BlockExecutor> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("DebugApi").finish_non_exhaustive()
@@ -1213,6 +1823,40 @@ impl<Provider, Eth, BlockExecutor> Clone for DebugApi<Provider, Eth, BlockExecut
     }
 }
 
+/// Returns the blocks traced by `debug_traceChain` between `start_exclusive` and `end_inclusive`.
+///
+/// Fails if the range is empty or covers more than `max_blocks` blocks.
+fn trace_chain_blocks(
+    start_exclusive: u64,
+    end_inclusive: u64,
+    max_blocks: u64,
+) -> Result<RangeInclusive<u64>, EthApiError> {
+    if start_exclusive >= end_inclusive {
+        return Err(EthApiError::InvalidParams(
+            "invalid parameters: start block must be lower than end block".to_string(),
+        ))
+    }
+    if end_inclusive - start_exclusive > max_blocks {
+        return Err(EthApiError::InvalidParams(format!(
+            "Block range too large; currently limited to {max_blocks} blocks"
+        )))
+    }
+    Ok(start_exclusive + 1..=end_inclusive)
+}
+
+/// Traces the given blocks with `trace_block`, up to [`TRACE_CHAIN_CONCURRENCY`] of them in
+/// parallel, and yields the results in ascending block order.
+fn trace_chain_stream<F, Fut>(
+    blocks: RangeInclusive<u64>,
+    trace_block: F,
+) -> impl Stream<Item = Fut::Output> + Send
+where
+    F: FnMut(u64) -> Fut + Send,
+    Fut: Future<Output: Send> + Send,
+{
+    futures::stream::iter(blocks).map(trace_block).buffered(TRACE_CHAIN_CONCURRENCY)
+}
+
 struct DebugApiInner<Provider, Eth, BlockExecutor> {
     /// The provider that can interact with the chain.
     provider: Provider,
@@ -1222,4 +1866,439 @@ struct DebugApiInner<Provider, Eth, BlockExecutor> {
     blocking_task_guard: BlockingTaskGuard,
     /// block executor for debug & trace apis
     block_executor: BlockExecutor,
+    /// Limits for custom JS tracers
+    #[cfg_attr(not(feature = "js-tracer"), allow(dead_code))]
+    js_tracer_config: JsTracerConfig,
+    /// Maximum number of blocks traced by a single `debug_traceChain` call
+    max_trace_chain_blocks: u64,
+    /// Handle to rewind the canonical chain, if supported by the node
+    set_head_handle: Option<Arc<dyn SetHeadHandle>>,
+}
+
+/// A handle to rewind the canonical chain of a running node, used by `debug_setHead`.
+#[async_trait]
+pub trait SetHeadHandle: Send + Sync {
+    /// Rewinds the canonical chain to the given block, dropping all blocks above it.
+    async fn set_head(&self, block_number: u64) -> RethResult<()>;
+}
+
+/// Converts a rejected block into its RPC representation.
+///
+/// Transactions are included in full if their senders can be recovered, otherwise only their
+/// hashes are returned.
+fn bad_block_to_rpc(block: SealedBlock, total_difficulty: U256) -> RpcBlock {
+    let hash = block.hash();
+    let block = block.unseal();
+
+    let full = block.clone().with_recovered_senders().and_then(|block| {
+        from_block_full::<EthTxBuilder>(block, total_difficulty, Some(hash)).ok()
+    });
+    let Some(full) = full else {
+        let block = BlockWithSenders { block, senders: Vec::new() };
+        return from_block_with_tx_hashes(block, total_difficulty, Some(hash))
+    };
+
+    let RpcBlock { header, uncles, transactions, size, withdrawals } = full;
+    let transactions = match transactions {
+        BlockTransactions::Full(transactions) => {
+            BlockTransactions::Full(transactions.into_iter().map(|tx| tx.inner).collect())
+        }
+        BlockTransactions::Hashes(hashes) => BlockTransactions::Hashes(hashes),
+        BlockTransactions::Uncle => BlockTransactions::Uncle,
+    };
+    RpcBlock { header, uncles, transactions, size, withdrawals }
+}
+
+/// Enforcement of the [`JsTracerConfig`] limits.
+#[cfg(feature = "js-tracer")]
+mod js {
+    use reth_rpc_eth_types::{EthApiError, JsTracerConfig};
+    use revm::{
+        interpreter::{
+            CallInputs, CallOutcome, CreateInputs, CreateOutcome, EOFCreateInputs,
+            InstructionResult, Interpreter,
+        },
+        primitives::{Address, Log, U256},
+        Database, EvmContext, Inspector,
+    };
+    use revm_inspectors::tracing::{
+        js::{JsInspector, JsInspectorError, RuntimeLimits},
+        TransactionContext,
+    };
+    use std::time::Instant;
+
+    /// The limit that was exceeded while tracing.
+    #[derive(Debug, Clone, Copy)]
+    enum ExceededLimit {
+        Timeout,
+        Memory,
+        /// A hook of the JS tracer threw, e.g. because the JS engine aborted it after it exceeded
+        /// the loop iteration or recursion limit.
+        HookFailed,
+    }
+
+    /// Creates a JS tracer whose JS engine enforces the loop iteration and recursion limits.
+    pub(super) fn new_inspector(
+        code: String,
+        config: serde_json::Value,
+        transaction_context: TransactionContext,
+        limits: JsTracerConfig,
+    ) -> Result<LimitedInspector<JsInspector>, JsInspectorError> {
+        let mut inspector =
+            JsInspector::with_transaction_context(code, config, transaction_context)?;
+        let mut runtime_limits = RuntimeLimits::default();
+        runtime_limits.set_loop_iteration_limit(limits.loop_iteration_limit);
+        runtime_limits.set_recursion_limit(limits.recursion_limit);
+        inspector.set_runtime_limits(runtime_limits);
+        Ok(LimitedInspector::new(inspector, limits))
+    }
+
+    /// An [`Inspector`] that wraps a JS tracer and halts execution once a limit is exceeded.
+    ///
+    /// The JS engine aborts a hook once it exceeds the loop iteration or recursion limit, which
+    /// halts the trace. The timeout and the memory limit are checked before every step.
+    pub(super) struct LimitedInspector<I> {
+        inner: I,
+        limits: JsTracerConfig,
+        deadline: Instant,
+        #[cfg(feature = "jemalloc")]
+        heap: Option<heap::ThreadHeap>,
+        exceeded: Option<ExceededLimit>,
+    }
+
+    impl<I> LimitedInspector<I> {
+        /// Wraps the given inspector, the timeout starts now.
+        ///
+        /// Must be created on the thread that executes the trace, since the heap growth of that
+        /// thread is attributed to the tracer.
+        pub(super) fn new(inner: I, limits: JsTracerConfig) -> Self {
+            Self {
+                inner,
+                limits,
+                deadline: Instant::now() + limits.timeout,
+                #[cfg(feature = "jemalloc")]
+                heap: heap::ThreadHeap::new(),
+                exceeded: None,
+            }
+        }
+
+        /// Returns the wrapped inspector if no limit was exceeded.
+        pub(super) fn into_inner(self) -> Result<I, EthApiError> {
+            match self.exceeded {
+                None => Ok(self.inner),
+                Some(ExceededLimit::Timeout) => {
+                    Err(EthApiError::ExecutionTimedOut(self.limits.timeout))
+                }
+                Some(ExceededLimit::Memory) => Err(memory_limit_exceeded(&self.limits)),
+                Some(ExceededLimit::HookFailed) => {
+                    Err(EthApiError::InternalJsTracerError(format!(
+                        "JS tracer step failed, it may have exceeded the loop iteration limit of \
+                         {} or the recursion limit of {}",
+                        self.limits.loop_iteration_limit, self.limits.recursion_limit
+                    )))
+                }
+            }
+        }
+
+        /// Returns true if the tracer uses more memory than allowed.
+        fn exceeds_memory_limit(&self, interp: &Interpreter) -> bool {
+            #[cfg(feature = "jemalloc")]
+            if self.heap.as_ref().is_some_and(|heap| heap.growth() > self.limits.memory_limit) {
+                return true
+            }
+            interp.shared_memory.len() > self.limits.memory_limit
+        }
+    }
+
+    impl<DB, I> Inspector<DB> for LimitedInspector<I>
+    where
+        DB: Database,
+        I: Inspector<DB>,
+    {
+        fn initialize_interp(&mut self, interp: &mut Interpreter, context: &mut EvmContext<DB>) {
+            self.inner.initialize_interp(interp, context)
+        }
+
+        fn step(&mut self, interp: &mut Interpreter, context: &mut EvmContext<DB>) {
+            if self.exceeded.is_none() {
+                if Instant::now() > self.deadline {
+                    self.exceeded = Some(ExceededLimit::Timeout);
+                } else if self.exceeds_memory_limit(interp) {
+                    self.exceeded = Some(ExceededLimit::Memory);
+                }
+            }
+            if self.exceeded.is_some() {
+                // halt the current frame, parent frames are halted on their next step
+                interp.instruction_result = InstructionResult::OutOfGas;
+                return
+            }
+
+            self.inner.step(interp, context);
+
+            // the JS tracer reverts the frame if its step hook threw
+            if interp.instruction_result == InstructionResult::Revert {
+                self.exceeded = Some(if Instant::now() > self.deadline {
+                    ExceededLimit::Timeout
+                } else {
+                    ExceededLimit::HookFailed
+                });
+            }
+        }
+
+        fn step_end(&mut self, interp: &mut Interpreter, context: &mut EvmContext<DB>) {
+            self.inner.step_end(interp, context)
+        }
+
+        fn log(&mut self, interp: &mut Interpreter, context: &mut EvmContext<DB>, log: &Log) {
+            self.inner.log(interp, context, log)
+        }
+
+        fn call(
+            &mut self,
+            context: &mut EvmContext<DB>,
+            inputs: &mut CallInputs,
+        ) -> Option<CallOutcome> {
+            self.inner.call(context, inputs)
+        }
+
+        fn call_end(
+            &mut self,
+            context: &mut EvmContext<DB>,
+            inputs: &CallInputs,
+            outcome: CallOutcome,
+        ) -> CallOutcome {
+            self.inner.call_end(context, inputs, outcome)
+        }
+
+        fn create(
+            &mut self,
+            context: &mut EvmContext<DB>,
+            inputs: &mut CreateInputs,
+        ) -> Option<CreateOutcome> {
+            self.inner.create(context, inputs)
+        }
+
+        fn create_end(
+            &mut self,
+            context: &mut EvmContext<DB>,
+            inputs: &CreateInputs,
+            outcome: CreateOutcome,
+        ) -> CreateOutcome {
+            self.inner.create_end(context, inputs, outcome)
+        }
+
+        fn eofcreate(
+            &mut self,
+            context: &mut EvmContext<DB>,
+            inputs: &mut EOFCreateInputs,
+        ) -> Option<CreateOutcome> {
+            self.inner.eofcreate(context, inputs)
+        }
+
+        fn eofcreate_end(
+            &mut self,
+            context: &mut EvmContext<DB>,
+            inputs: &EOFCreateInputs,
+            outcome: CreateOutcome,
+        ) -> CreateOutcome {
+            self.inner.eofcreate_end(context, inputs, outcome)
+        }
+
+        fn selfdestruct(&mut self, contract: Address, target: Address, value: U256) {
+            self.inner.selfdestruct(contract, target, value)
+        }
+    }
+
+    /// Ensures the result of a JS tracer does not exceed the memory limit.
+    pub(super) fn check_result_size(
+        result: &serde_json::Value,
+        limits: &JsTracerConfig,
+    ) -> Result<(), EthApiError> {
+        let size = serde_json::to_vec(result)
+            .map_err(|err| EthApiError::InternalJsTracerError(err.to_string()))?
+            .len();
+        if size > limits.memory_limit {
+            return Err(memory_limit_exceeded(limits))
+        }
+        Ok(())
+    }
+
+    fn memory_limit_exceeded(limits: &JsTracerConfig) -> EthApiError {
+        EthApiError::InternalJsTracerError(format!(
+            "memory limit of {} bytes exceeded",
+            limits.memory_limit
+        ))
+    }
+
+    /// Heap statistics of the tracing thread.
+    #[cfg(feature = "jemalloc")]
+    mod heap {
+        use tikv_jemalloc_ctl::thread::{allocatedp, deallocatedp, ThreadLocal};
+
+        /// Tracks the heap growth of the current thread, which runs the JS engine of the tracer.
+        pub(super) struct ThreadHeap {
+            allocated: ThreadLocal<u64>,
+            deallocated: ThreadLocal<u64>,
+            baseline: u64,
+        }
+
+        impl ThreadHeap {
+            /// Starts tracking the heap of the current thread, returns `None` if jemalloc does not
+            /// provide thread statistics.
+            pub(super) fn new() -> Option<Self> {
+                let allocated = allocatedp::read().ok()?;
+                let deallocated = deallocatedp::read().ok()?;
+                let baseline = allocated.get().saturating_sub(deallocated.get());
+                Some(Self { allocated, deallocated, baseline })
+            }
+
+            /// Returns the number of bytes the heap of the thread grew since tracking started.
+            pub(super) fn growth(&self) -> usize {
+                let live = self.allocated.get().saturating_sub(self.deallocated.get());
+                live.saturating_sub(self.baseline) as usize
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{trace_chain_blocks, trace_chain_stream};
+    use futures::{channel::oneshot, FutureExt, StreamExt};
+    use reth_rpc_eth_types::EthApiError;
+
+    #[test]
+    fn trace_chain_range_is_capped() {
+        assert_eq!(trace_chain_blocks(10, 20, 10).unwrap(), 11..=20);
+        assert_eq!(trace_chain_blocks(0, 1, 1).unwrap(), 1..=1);
+
+        let err = trace_chain_blocks(10, 21, 10).unwrap_err();
+        assert!(matches!(err, EthApiError::InvalidParams(_)), "{err:?}");
+        let err = trace_chain_blocks(10, 10, 10).unwrap_err();
+        assert!(matches!(err, EthApiError::InvalidParams(_)), "{err:?}");
+    }
+
+    #[test]
+    fn trace_chain_streams_in_block_order() {
+        let (first_tx, first) = oneshot::channel::<u64>();
+        let (second_tx, second) = oneshot::channel();
+        let (third_tx, third) = oneshot::channel();
+        let mut pending = vec![first, second, third].into_iter();
+        let mut stream = Box::pin(trace_chain_stream(1..=3, move |_| {
+            pending.next().unwrap().map(Result::unwrap)
+        }));
+
+        // later blocks finishing first are held back until the earlier ones are done
+        third_tx.send(3).unwrap();
+        second_tx.send(2).unwrap();
+        assert_eq!(stream.next().now_or_never(), None);
+
+        first_tx.send(1).unwrap();
+        assert_eq!(stream.next().now_or_never(), Some(Some(1)));
+        assert_eq!(stream.next().now_or_never(), Some(Some(2)));
+        assert_eq!(stream.next().now_or_never(), Some(Some(3)));
+        assert_eq!(stream.next().now_or_never(), Some(None));
+    }
+}
+
+#[cfg(all(test, feature = "js-tracer"))]
+mod js_tests {
+    use super::js;
+    use alloy_primitives::{address, bytes, TxKind};
+    use reth_rpc_eth_types::{EthApiError, JsTracerConfig};
+    use revm::{
+        db::{CacheDB, EmptyDB},
+        inspector_handle_register,
+        primitives::{AccountInfo, Bytecode},
+        Evm,
+    };
+    use std::time::Duration;
+
+    /// Traces a call to a contract that loops until it runs out of gas with the given JS tracer.
+    fn trace_loop(code: &str, limits: JsTracerConfig) -> Result<(), EthApiError> {
+        let contract = address!("00000000000000000000000000000000000000aa");
+        let mut db = CacheDB::new(EmptyDB::default());
+        // JUMPDEST PUSH1 0 JUMP
+        db.insert_account_info(
+            contract,
+            AccountInfo { code: Some(Bytecode::new_raw(bytes!("5b600056"))), ..Default::default() },
+        );
+
+        let inspector = js::new_inspector(
+            code.to_string(),
+            serde_json::Value::Null,
+            Default::default(),
+            limits,
+        )?;
+        let mut evm = Evm::builder()
+            .with_db(db)
+            .with_external_context(inspector)
+            .modify_tx_env(|tx| {
+                tx.transact_to = TxKind::Call(contract);
+                tx.gas_limit = 10_000_000;
+            })
+            .append_handler_register(inspector_handle_register)
+            .build();
+        evm.transact().map_err(|err| EthApiError::InternalJsTracerError(err.to_string()))?;
+
+        evm.into_context().external.into_inner().map(drop)
+    }
+
+    #[test]
+    fn infinite_loop_times_out() {
+        let code = r#"{
+            step: function() { while (true) {} },
+            fault: function() {},
+            result: function() { return null; }
+        }"#;
+        let limits = JsTracerConfig::default().timeout(Duration::from_millis(1));
+
+        let err = trace_loop(code, limits).unwrap_err();
+        assert!(matches!(err, EthApiError::ExecutionTimedOut(_)), "{err:?}");
+    }
+
+    #[test]
+    fn infinite_loop_is_aborted_by_the_js_engine() {
+        let code = r#"{
+            step: function() { for (;;) {} },
+            fault: function() {},
+            result: function() { return null; }
+        }"#;
+        let limits = JsTracerConfig::default().loop_iteration_limit(1_000);
+
+        let err = trace_loop(code, limits).unwrap_err();
+        assert!(matches!(err, EthApiError::InternalJsTracerError(_)), "{err:?}");
+    }
+
+    #[test]
+    fn unbounded_recursion_is_aborted_by_the_js_engine() {
+        let code = r#"{
+            recurse: function(depth) { return this.recurse(depth + 1); },
+            step: function() { this.recurse(0); },
+            fault: function() {},
+            result: function() { return null; }
+        }"#;
+        let limits = JsTracerConfig::default().recursion_limit(100);
+
+        let err = trace_loop(code, limits).unwrap_err();
+        assert!(matches!(err, EthApiError::InternalJsTracerError(_)), "{err:?}");
+    }
+
+    #[test]
+    #[cfg(all(unix, feature = "jemalloc"))]
+    fn allocations_hit_the_memory_limit() {
+        let code = r#"{
+            chunks: [],
+            step: function() { this.chunks.push("x".repeat(1024 * 1024)); },
+            fault: function() {},
+            result: function() { return this.chunks.length; }
+        }"#;
+        let limits = JsTracerConfig::default().memory_limit(16 * 1024 * 1024);
+
+        let err = trace_loop(code, limits).unwrap_err();
+        assert!(
+            matches!(&err, EthApiError::InternalJsTracerError(msg) if msg.contains("memory limit")),
+            "{err:?}"
+        );
+    }
 }
@@ -4,7 +4,7 @@
 use std::sync::Arc;
 
 use alloy_network::AnyNetwork;
-use alloy_primitives::U256;
+use alloy_primitives::{Address, U256};
 use derive_more::Deref;
 use reth_primitives::BlockNumberOrTag;
 use reth_provider::{BlockReaderIdExt, CanonStateSubscriptions, ChainSpecProvider};
@@ -173,6 +173,8 @@ pub struct EthApiInner<Provider, Pool, Network, EvmConfig> {
     network: Network,
     /// All configured Signers
     signers: parking_lot::RwLock<Vec<Box<dyn EthSigner>>>,
+    /// The latest fee recipient suggested to the payload builder
+    suggested_fee_recipient: parking_lot::RwLock<Option<Address>>,
     /// The async cache frontend for eth related data
     eth_cache: EthStateCache,
     /// The async gas oracle frontend for gas price suggestions
@@ -237,6 +239,7 @@ where
             pool,
             network,
             signers,
+            suggested_fee_recipient: Default::default(),
             eth_cache,
             gas_oracle,
             gas_cap: gas_cap.into().into(),
@@ -326,6 +329,12 @@ impl<Provider, Pool, Network, EvmConfig> EthApiInner<Provider, Pool, Network, Ev
         &self.signers
     }
 
+    /// Returns a handle to the latest fee recipient suggested to the payload builder.
+    #[inline]
+    pub const fn suggested_fee_recipient(&self) -> &parking_lot::RwLock<Option<Address>> {
+        &self.suggested_fee_recipient
+    }
+
     /// Returns the starting block.
     #[inline]
     pub const fn starting_block(&self) -> U256 {
@@ -353,7 +362,7 @@ impl<Provider, Pool, Network, EvmConfig> EthApiInner<Provider, Pool, Network, Ev
 
 #[cfg(test)]
 mod tests {
-    use alloy_primitives::{B256, U64};
+    use alloy_primitives::{Address, B256, U64};
     use alloy_rpc_types::FeeHistory;
     use jsonrpsee_types::error::INVALID_PARAMS_CODE;
     use reth_chainspec::{BaseFeeParams, ChainSpec, EthChainSpec};
@@ -654,4 +663,18 @@ mod tests {
             "all: no percentiles were requested, so there should be no rewards result"
         );
     }
+
+    #[tokio::test]
+    /// Invocation of `eth_coinbase` returns the fee recipient suggested to the payload builder
+    async fn test_coinbase_is_suggested_fee_recipient() {
+        let eth_api = crate::eth::helpers::test_utils::build_test_eth_api();
+        assert!(<EthApi<_, _, _, _> as EthApiServer<_, _, _>>::author(&eth_api).await.is_err());
+
+        let fee_recipient = Address::random();
+        *eth_api.inner.suggested_fee_recipient().write() = Some(fee_recipient);
+        assert_eq!(
+            <EthApi<_, _, _, _> as EthApiServer<_, _, _>>::author(&eth_api).await.unwrap(),
+            fee_recipient
+        );
+    }
 }
@@ -13,3 +13,6 @@ mod spec;
 mod state;
 mod trace;
 mod transaction;
+
+#[cfg(test)]
+pub(crate) mod test_utils;
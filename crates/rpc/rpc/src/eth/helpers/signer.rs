@@ -1,6 +1,10 @@
 //! An abstraction over ethereum signers.
 
-use std::collections::HashMap;
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    result,
+};
 
 use crate::EthApi;
 use alloy_dyn_abi::TypedData;
@@ -20,6 +24,10 @@ impl<Provider, Pool, Network, EvmConfig> AddDevSigners
     fn with_dev_accounts(&self) {
         *self.inner.signers().write() = DevSigner::random_signers(20)
     }
+
+    fn add_signer(&self, signer: Box<dyn EthSigner>) {
+        self.inner.signers().write().push(signer)
+    }
 }
 
 /// Holds developer keys
@@ -107,6 +115,116 @@ impl EthSigner for DevSigner {
     }
 }
 
+/// Holds the keys of a geth-style keystore directory.
+///
+/// The encrypted JSON key files are decrypted once, when the signer is created.
+#[derive(Debug, Clone)]
+pub struct KeystoreSigner {
+    inner: DevSigner,
+}
+
+impl KeystoreSigner {
+    /// Decrypts all key files in the keystore directory, each with the first of the given
+    /// passwords that matches.
+    ///
+    /// Like geth, hidden files and editor backups ending in `~` are skipped.
+    pub fn decrypt<P: AsRef<[u8]>>(
+        keystore: &Path,
+        passwords: &[P],
+    ) -> result::Result<Self, KeystoreError> {
+        let io_err = |source| KeystoreError::Io { path: keystore.to_path_buf(), source };
+
+        let mut paths = Vec::new();
+        for entry in std::fs::read_dir(keystore).map_err(io_err)? {
+            let entry = entry.map_err(io_err)?;
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            if name.starts_with('.') || name.ends_with('~') || !entry.path().is_file() {
+                continue
+            }
+            paths.push(entry.path());
+        }
+        // sort the key files so the accounts are listed in a stable order
+        paths.sort();
+
+        let mut addresses = Vec::with_capacity(paths.len());
+        let mut accounts = HashMap::with_capacity(paths.len());
+        for path in paths {
+            let signer = passwords
+                .iter()
+                .find_map(|password| PrivateKeySigner::decrypt_keystore(&path, password).ok())
+                .ok_or(KeystoreError::Decrypt(path))?;
+            addresses.push(signer.address());
+            accounts.insert(signer.address(), signer);
+        }
+
+        if addresses.is_empty() {
+            return Err(KeystoreError::Empty(keystore.to_path_buf()))
+        }
+
+        Ok(Self { inner: DevSigner { addresses, accounts } })
+    }
+
+    /// Decrypts all key files in the keystore directory with the passwords listed in the password
+    /// file, one per line.
+    pub fn from_password_file(
+        keystore: &Path,
+        password_file: &Path,
+    ) -> result::Result<Self, KeystoreError> {
+        let passwords = std::fs::read_to_string(password_file)
+            .map_err(|source| KeystoreError::Io { path: password_file.to_path_buf(), source })?;
+        let passwords =
+            passwords.lines().map(|line| line.trim_end_matches('\r')).collect::<Vec<_>>();
+        Self::decrypt(keystore, &passwords)
+    }
+}
+
+#[async_trait::async_trait]
+impl EthSigner for KeystoreSigner {
+    fn accounts(&self) -> Vec<Address> {
+        self.inner.accounts()
+    }
+
+    fn is_signer_for(&self, addr: &Address) -> bool {
+        self.inner.is_signer_for(addr)
+    }
+
+    async fn sign(&self, address: Address, message: &[u8]) -> Result<Signature> {
+        self.inner.sign(address, message).await
+    }
+
+    async fn sign_transaction(
+        &self,
+        request: TransactionRequest,
+        address: &Address,
+    ) -> Result<TransactionSigned> {
+        self.inner.sign_transaction(request, address).await
+    }
+
+    fn sign_typed_data(&self, address: Address, payload: &TypedData) -> Result<Signature> {
+        self.inner.sign_typed_data(address, payload)
+    }
+}
+
+/// Errors that can occur when loading a [`KeystoreSigner`].
+#[derive(Debug, thiserror::Error)]
+pub enum KeystoreError {
+    /// Failed to read the keystore directory or the password file.
+    #[error("failed to read {path}: {source}")]
+    Io {
+        /// The path that could not be read.
+        path: PathBuf,
+        /// The underlying error.
+        source: std::io::Error,
+    },
+    /// None of the passwords decrypts the key file.
+    #[error("could not decrypt key file {0}")]
+    Decrypt(PathBuf),
+    /// The keystore directory contains no key files.
+    #[error("no key files in keystore {0}")]
+    Empty(PathBuf),
+}
+
 #[cfg(test)]
 mod tests {
     use alloy_primitives::{Bytes, Parity, U256};
@@ -256,4 +374,29 @@ mod tests {
 
         assert_eq!(Bytes::from(message.to_vec()), txn_signed.unwrap().input().0);
     }
+
+    #[test]
+    fn test_keystore_signer() {
+        let keystore = tempfile::tempdir().unwrap();
+        let (signer, _) = PrivateKeySigner::encrypt_keystore(
+            keystore.path(),
+            &mut rand::thread_rng(),
+            B256::repeat_byte(0x46),
+            "secret",
+            Some("key"),
+        )
+        .unwrap();
+        std::fs::write(keystore.path().join(".hidden"), "not a key").unwrap();
+
+        let password_file = keystore.path().join(".password");
+        std::fs::write(&password_file, "wrong\nsecret\n").unwrap();
+        let keystore_signer =
+            KeystoreSigner::from_password_file(keystore.path(), &password_file).unwrap();
+        assert_eq!(keystore_signer.accounts(), vec![signer.address()]);
+
+        assert!(matches!(
+            KeystoreSigner::decrypt(keystore.path(), &["wrong"]),
+            Err(KeystoreError::Decrypt(_))
+        ));
+    }
 }
@@ -1,4 +1,4 @@
-use alloy_primitives::U256;
+use alloy_primitives::{Address, U256};
 use reth_chainspec::EthereumHardforks;
 use reth_network_api::NetworkInfo;
 use reth_provider::{BlockNumReader, ChainSpecProvider, StageCheckpointReader};
@@ -35,4 +35,8 @@ where
     fn signers(&self) -> &parking_lot::RwLock<Vec<Box<dyn reth_rpc_eth_api::helpers::EthSigner>>> {
         self.inner.signers()
     }
+
+    fn suggested_fee_recipient(&self) -> &parking_lot::RwLock<Option<Address>> {
+        self.inner.suggested_fee_recipient()
+    }
 }
@@ -0,0 +1,41 @@
+//! Helpers for testing the `eth` namespace.
+
+use reth_chainspec::ChainSpecProvider;
+use reth_evm_ethereum::EthEvmConfig;
+use reth_network_api::noop::NoopNetwork;
+use reth_primitives::constants::ETHEREUM_BLOCK_GAS_LIMIT;
+use reth_provider::test_utils::NoopProvider;
+use reth_rpc_eth_types::{EthStateCache, FeeHistoryCache, FeeHistoryCacheConfig, GasPriceOracle};
+use reth_rpc_server_types::constants::{
+    DEFAULT_ETH_PROOF_WINDOW, DEFAULT_MAX_SIMULATE_BLOCKS, DEFAULT_PROOF_PERMITS,
+};
+use reth_tasks::pool::BlockingTaskPool;
+use reth_transaction_pool::test_utils::{testing_pool, TestPool};
+
+use crate::EthApi;
+
+/// An [`EthApi`] without chain data and network.
+pub(crate) type TestEthApi = EthApi<NoopProvider, TestPool, NoopNetwork, EthEvmConfig>;
+
+/// Returns a [`TestEthApi`] with an empty testing pool and the default settings.
+pub(crate) fn build_test_eth_api() -> TestEthApi {
+    let provider = NoopProvider::default();
+    let evm_config = EthEvmConfig::new(provider.chain_spec());
+    let cache = EthStateCache::spawn(provider, Default::default(), evm_config.clone());
+    let fee_history_cache = FeeHistoryCache::new(cache.clone(), FeeHistoryCacheConfig::default());
+
+    EthApi::new(
+        provider,
+        testing_pool(),
+        NoopNetwork::default(),
+        cache.clone(),
+        GasPriceOracle::new(provider, Default::default(), cache),
+        ETHEREUM_BLOCK_GAS_LIMIT,
+        DEFAULT_MAX_SIMULATE_BLOCKS,
+        DEFAULT_ETH_PROOF_WINDOW,
+        BlockingTaskPool::build().expect("failed to build tracing pool"),
+        fee_history_cache,
+        evm_config,
+        DEFAULT_PROOF_PERMITS,
+    )
+}
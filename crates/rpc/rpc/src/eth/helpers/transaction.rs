@@ -55,13 +55,14 @@ where
 
 #[cfg(test)]
 mod tests {
-    use alloy_primitives::{hex_literal::hex, Bytes};
+    use alloy_primitives::{hex_literal::hex, Address, Bytes};
+    use alloy_rpc_types_eth::TransactionRequest;
     use reth_chainspec::ChainSpecProvider;
     use reth_evm_ethereum::EthEvmConfig;
     use reth_network_api::noop::NoopNetwork;
     use reth_primitives::constants::ETHEREUM_BLOCK_GAS_LIMIT;
     use reth_provider::test_utils::NoopProvider;
-    use reth_rpc_eth_api::helpers::EthTransactions;
+    use reth_rpc_eth_api::helpers::{EthApiSpec, EthTransactions};
     use reth_rpc_eth_types::{
         EthStateCache, FeeHistoryCache, FeeHistoryCacheConfig, GasPriceOracle,
     };
@@ -72,6 +73,29 @@ mod tests {
     use reth_transaction_pool::{test_utils::testing_pool, TransactionPool};
 
     use super::*;
+    use crate::eth::helpers::test_utils::build_test_eth_api;
+
+    #[tokio::test]
+    async fn fill_transaction_request_keeps_set_fields() {
+        let eth_api = build_test_eth_api();
+        let from = Address::random();
+        let request = TransactionRequest::default()
+            .from(from)
+            .to(Address::random())
+            .nonce(7)
+            .gas_limit(21_000)
+            .max_fee_per_gas(100)
+            .max_priority_fee_per_gas(2);
+
+        let filled = eth_api.fill_transaction_request(from, request.clone()).await.unwrap();
+        assert_eq!(filled.chain_id, Some(eth_api.chain_id().to()));
+        assert_eq!(filled.nonce, Some(7));
+        assert_eq!(filled.gas, Some(21_000));
+        assert_eq!(filled.max_fee_per_gas, Some(100));
+        assert_eq!(filled.max_priority_fee_per_gas, Some(2));
+        assert_eq!(filled.gas_price, None);
+        assert_eq!(TransactionRequest { chain_id: None, ..filled }, request);
+    }
 
     #[tokio::test]
     async fn send_raw_transaction() {
@@ -82,6 +82,10 @@ where
         tx.inner.input = tx.inner.input.slice(..4);
     }
 
+    fn otterscan_api_transaction(tx: Self::Transaction) -> Transaction {
+        tx.inner
+    }
+
     fn tx_type(tx: &Self::Transaction) -> u8 {
         tx.inner.transaction_type.unwrap_or(0)
     }
@@ -12,7 +12,11 @@ pub use bundle::EthBundle;
 pub use core::EthApi;
 pub use filter::EthFilter;
 pub use pubsub::EthPubSub;
+pub use sim_bundle::EthSimBundle;
 
-pub use helpers::{signer::DevSigner, types::EthTxBuilder};
+pub use helpers::{
+    signer::{DevSigner, KeystoreError, KeystoreSigner},
+    types::EthTxBuilder,
+};
 
 pub use reth_rpc_eth_api::EthApiServer;
@@ -1,14 +1,52 @@
 //! `Eth` Sim bundle implementation and helpers.
 
-use std::sync::Arc;
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
-use alloy_rpc_types_mev::{SendBundleRequest, SimBundleOverrides, SimBundleResponse};
+use alloy_primitives::{Address, U256};
+use alloy_rpc_types::BlockId;
+use alloy_rpc_types_mev::{
+    BundleItem, Inclusion, SendBundleRequest, SimBundleLogs, SimBundleOverrides, SimBundleResponse,
+};
 use jsonrpsee::core::RpcResult;
+use reth_chainspec::EthChainSpec;
+use reth_evm::{ConfigureEvm, ConfigureEvmEnv};
+use reth_primitives::TransactionSigned;
+use reth_provider::{ChainSpecProvider, HeaderProvider};
+use reth_revm::database::StateProviderDatabase;
 use reth_rpc_api::MevSimApiServer;
-use reth_rpc_eth_api::helpers::{Call, EthTransactions, LoadPendingBlock};
-use reth_rpc_eth_types::EthApiError;
+use reth_rpc_eth_api::{
+    helpers::{Call, EthTransactions, LoadPendingBlock},
+    FromEthApiError, FromEvmError,
+};
+use reth_rpc_eth_types::{utils::recover_raw_transaction, EthApiError};
 use reth_tasks::pool::BlockingTaskGuard;
-use tracing::info;
+use revm::{
+    db::CacheDB,
+    primitives::{EVMError, ResultAndState, TxEnv},
+    Database, DatabaseCommit, Evm,
+};
+use revm_primitives::{EnvWithHandlerCfg, SpecId};
+
+/// Maximum depth of nested bundles.
+const MAX_NESTED_BUNDLE_DEPTH: usize = 5;
+
+/// Maximum number of items in the body of a single bundle.
+const MAX_BUNDLE_BODY_SIZE: usize = 50;
+
+/// Default simulation timeout, used if the request does not specify one.
+const DEFAULT_SIM_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Maximum simulation timeout a request can ask for.
+const MAX_SIM_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Upper bound for the gas cost of a single refund payout transaction.
+///
+/// Payouts are not part of the bundle, but the builder has to pay for them out of the refundable
+/// value, so they are accounted for in the simulation result.
+const SBUNDLE_PAYOUT_MAX_COST: u64 = 30_000;
 
 /// `Eth` sim bundle implementation.
 pub struct EthSimBundle<Eth> {
@@ -21,20 +59,131 @@ impl<Eth> EthSimBundle<Eth> {
     pub fn new(eth_api: Eth, blocking_task_guard: BlockingTaskGuard) -> Self {
         Self { inner: Arc::new(EthSimBundleInner { eth_api, blocking_task_guard }) }
     }
+
+    /// Access the underlying `Eth` API.
+    pub fn eth_api(&self) -> &Eth {
+        &self.inner.eth_api
+    }
 }
 
 impl<Eth> EthSimBundle<Eth>
 where
     Eth: EthTransactions + LoadPendingBlock + Call + 'static,
 {
-    /// Simulates a bundle of transactions.
+    /// Simulates a MEV-Share bundle on top of the configured parent block.
+    ///
+    /// Invalid requests are rejected with an error. If the bundle itself fails to execute, e.g.
+    /// because a transaction that is not allowed to revert reverted, the returned response has
+    /// `success` set to `false` and carries the reason in `error`.
     pub async fn sim_bundle(
         &self,
         request: SendBundleRequest,
         overrides: SimBundleOverrides,
-    ) -> RpcResult<SimBundleResponse> {
-        info!("mev_simBundle called, request: {:?}, overrides: {:?}", request, overrides);
-        Err(EthApiError::Unsupported("mev_simBundle is not supported").into())
+    ) -> Result<SimBundleResponse, Eth::Error> {
+        let bundle = parse_bundle(request, None, 0)?;
+
+        let SimBundleOverrides {
+            parent_block,
+            block_number,
+            coinbase,
+            timestamp,
+            gas_limit,
+            base_fee,
+            timeout,
+        } = overrides;
+
+        let timeout = timeout.map(Duration::from_secs).unwrap_or(DEFAULT_SIM_TIMEOUT);
+        if timeout > MAX_SIM_TIMEOUT {
+            return Err(EthApiError::InvalidParams(
+                EthSimBundleError::TimeoutTooLarge(MAX_SIM_TIMEOUT.as_secs()).to_string(),
+            )
+            .into())
+        }
+
+        let block_id = parent_block.unwrap_or_else(BlockId::latest);
+        let (cfg, mut block_env, at) = self.eth_api().evm_env_at(block_id).await?;
+
+        // the environment is that of the parent block, adjust it for the simulated block
+        let parent_number = block_env.number.saturating_to::<u64>();
+        block_env.number = U256::from(block_number.unwrap_or(parent_number + 1));
+
+        if let Some(timestamp) = timestamp {
+            block_env.timestamp = U256::from(timestamp);
+        } else {
+            block_env.timestamp += U256::from(12);
+        }
+
+        if let Some(coinbase) = coinbase {
+            block_env.coinbase = coinbase;
+        }
+
+        if let Some(gas_limit) = gas_limit {
+            block_env.gas_limit = U256::from(gas_limit);
+        }
+
+        if let Some(base_fee) = base_fee {
+            block_env.basefee = U256::from(base_fee);
+        } else if cfg.handler_cfg.spec_id.is_enabled_in(SpecId::LONDON) {
+            let parent = LoadPendingBlock::provider(self.eth_api())
+                .header_by_number(parent_number)
+                .map_err(Eth::Error::from_eth_err)?
+                .ok_or(EthApiError::HeaderNotFound(parent_number.into()))?;
+            if let Some(base_fee) = parent.next_block_base_fee(
+                LoadPendingBlock::provider(self.eth_api())
+                    .chain_spec()
+                    .base_fee_params_at_block(parent_number),
+            ) {
+                block_env.basefee = U256::from(base_fee);
+            }
+        }
+
+        let sim_env = SimEnv {
+            block_number: block_env.number.saturating_to(),
+            coinbase: block_env.coinbase,
+            basefee: block_env.basefee,
+            deadline: Instant::now() + timeout,
+        };
+
+        let eth_api = self.eth_api().clone();
+        let _permit = self.inner.blocking_task_guard.clone().acquire_owned().await;
+
+        self.eth_api()
+            .spawn_with_state_at_block(at, move |state| {
+                let env = EnvWithHandlerCfg::new_with_cfg_env(cfg, block_env, TxEnv::default());
+                let db = CacheDB::new(StateProviderDatabase::new(state));
+                let mut evm = Call::evm_config(&eth_api).evm_with_env(db, env);
+
+                let response = match simulate_bundle(&eth_api, &mut evm, &bundle, &sim_env) {
+                    Ok(res) => {
+                        let mev_gas_price =
+                            res.profit.checked_div(U256::from(res.gas_used)).unwrap_or_default();
+                        SimBundleResponse {
+                            success: true,
+                            error: None,
+                            state_block: parent_number,
+                            mev_gas_price: mev_gas_price.saturating_to(),
+                            profit: res.profit.saturating_to(),
+                            refundable_value: res.refundable_value.saturating_to(),
+                            gas_used: res.gas_used,
+                            logs: Some(res.logs),
+                        }
+                    }
+                    Err(SimError::Bundle(err)) => SimBundleResponse {
+                        success: false,
+                        error: Some(err.to_string()),
+                        state_block: parent_number,
+                        mev_gas_price: 0,
+                        profit: 0,
+                        refundable_value: 0,
+                        gas_used: 0,
+                        logs: None,
+                    },
+                    Err(SimError::Eth(err)) => return Err(err),
+                };
+
+                Ok(response)
+            })
+            .await
     }
 }
 
@@ -48,7 +197,7 @@ where
         request: SendBundleRequest,
         overrides: SimBundleOverrides,
     ) -> RpcResult<SimBundleResponse> {
-        Self::sim_bundle(self, request, overrides).await
+        Self::sim_bundle(self, request, overrides).await.map_err(Into::into)
     }
 }
 
@@ -56,10 +205,8 @@ where
 #[derive(Debug)]
 struct EthSimBundleInner<Eth> {
     /// Access to commonly used code of the `eth` namespace
-    #[allow(dead_code)]
     eth_api: Eth,
-    // restrict the number of concurrent tracing calls.
-    #[allow(dead_code)]
+    // restrict the number of concurrent simulations.
     blocking_task_guard: BlockingTaskGuard,
 }
 
@@ -74,3 +221,521 @@ impl<Eth> Clone for EthSimBundle<Eth> {
         Self { inner: Arc::clone(&self.inner) }
     }
 }
+
+/// A validated bundle with all transactions decoded and signers recovered.
+#[derive(Debug)]
+struct SimBundle {
+    /// Blocks the bundle may be included in.
+    inclusion: Inclusion,
+    /// The bundle body.
+    body: Vec<SimBundleItem>,
+    /// Refund percentage for each body item, if the item is refunded.
+    refund_percents: Vec<Option<u64>>,
+    /// Number of addresses the refund is split between.
+    refund_recipients: u64,
+}
+
+/// A single item of a [`SimBundle`] body.
+#[derive(Debug)]
+enum SimBundleItem {
+    /// A signed transaction.
+    Tx { tx: TransactionSigned, signer: Address, can_revert: bool },
+    /// A nested bundle.
+    Bundle(Box<SimBundle>),
+}
+
+/// Environment shared by all (nested) bundles of a simulation.
+#[derive(Debug)]
+struct SimEnv {
+    /// Number of the simulated block.
+    block_number: u64,
+    /// Beneficiary of the simulated block.
+    coinbase: Address,
+    /// Base fee of the simulated block.
+    basefee: U256,
+    /// Point in time at which the simulation is aborted.
+    deadline: Instant,
+}
+
+/// Outcome of a successful bundle simulation.
+#[derive(Debug, Default)]
+struct SimBundleResult {
+    /// Total gas used, including the estimated cost of refund payouts.
+    gas_used: u64,
+    /// Coinbase profit left after refunds.
+    profit: U256,
+    /// Coinbase profit generated by the items that are not refunded.
+    refundable_value: U256,
+    /// Logs of every body item.
+    logs: Vec<SimBundleLogs>,
+}
+
+/// Errors that can occur during a bundle simulation.
+#[derive(Debug)]
+enum SimError<E> {
+    /// The bundle failed to execute.
+    Bundle(EthSimBundleError),
+    /// Simulation could not be carried out.
+    Eth(E),
+}
+
+/// Validates the request and recovers the signers of all transactions, recursing into nested
+/// bundles.
+fn parse_bundle(
+    request: SendBundleRequest,
+    parent_inclusion: Option<&Inclusion>,
+    depth: usize,
+) -> Result<SimBundle, EthApiError> {
+    let invalid = |err: EthSimBundleError| EthApiError::InvalidParams(err.to_string());
+
+    if depth > MAX_NESTED_BUNDLE_DEPTH {
+        return Err(invalid(EthSimBundleError::MaxDepth(MAX_NESTED_BUNDLE_DEPTH)))
+    }
+    if request.bundle_body.is_empty() {
+        return Err(invalid(EthSimBundleError::EmptyBundleBody))
+    }
+    if request.bundle_body.len() > MAX_BUNDLE_BODY_SIZE {
+        return Err(invalid(EthSimBundleError::BundleTooLarge(MAX_BUNDLE_BODY_SIZE)))
+    }
+
+    let inclusion = request.inclusion;
+    let max_block = inclusion.max_block.unwrap_or(inclusion.block);
+    if max_block < inclusion.block {
+        return Err(invalid(EthSimBundleError::InvalidInclusion))
+    }
+    // a nested bundle can not be valid outside of the range of its parent
+    if let Some(parent) = parent_inclusion {
+        if inclusion.block < parent.block || max_block > parent.max_block.unwrap_or(parent.block) {
+            return Err(invalid(EthSimBundleError::InvalidInclusion))
+        }
+    }
+
+    let mut refund_percents = vec![None; request.bundle_body.len()];
+    let mut refund_recipients = 1;
+    if let Some(validity) = request.validity {
+        let mut total_percent = 0;
+        for refund in validity.refund.unwrap_or_default() {
+            let slot = usize::try_from(refund.body_idx)
+                .ok()
+                .and_then(|idx| refund_percents.get_mut(idx))
+                .ok_or_else(|| invalid(EthSimBundleError::InvalidValidity))?;
+            total_percent = refund
+                .percent
+                .checked_add(total_percent)
+                .ok_or_else(|| invalid(EthSimBundleError::InvalidValidity))?;
+            *slot = Some(refund.percent);
+        }
+        if total_percent > 100 {
+            return Err(invalid(EthSimBundleError::InvalidValidity))
+        }
+
+        if let Some(configs) = validity.refund_config.filter(|configs| !configs.is_empty()) {
+            let total_percent =
+                configs.iter().try_fold(0u64, |total, config| total.checked_add(config.percent));
+            if total_percent != Some(100) {
+                return Err(invalid(EthSimBundleError::InvalidValidity))
+            }
+            refund_recipients = configs.len() as u64;
+        }
+    }
+
+    let mut body = Vec::with_capacity(request.bundle_body.len());
+    for item in request.bundle_body {
+        let item = match item {
+            BundleItem::Tx { tx, can_revert } => {
+                let (tx, signer) = recover_raw_transaction(tx)?.into_components();
+                SimBundleItem::Tx { tx: tx.into_transaction(), signer, can_revert }
+            }
+            BundleItem::Bundle { bundle } => {
+                SimBundleItem::Bundle(Box::new(parse_bundle(bundle, Some(&inclusion), depth + 1)?))
+            }
+            BundleItem::Hash { .. } => return Err(invalid(EthSimBundleError::UnmatchedBundle)),
+        };
+        body.push(item);
+    }
+
+    Ok(SimBundle { inclusion, body, refund_percents, refund_recipients })
+}
+
+/// Executes the bundle on top of the given EVM, committing the state changes of every
+/// transaction.
+///
+/// Nested bundles are executed in place. The profit of a transaction is the balance change of the
+/// coinbase, the profit of a nested bundle is its profit after its own refunds. Refunds are then
+/// paid out of the profit of the items that are not refunded.
+fn simulate_bundle<Eth, EXT, DB>(
+    eth_api: &Eth,
+    evm: &mut Evm<'_, EXT, DB>,
+    bundle: &SimBundle,
+    env: &SimEnv,
+) -> Result<SimBundleResult, SimError<Eth::Error>>
+where
+    Eth: Call,
+    DB: Database + DatabaseCommit,
+    EthApiError: From<DB::Error>,
+{
+    let max_block = bundle.inclusion.max_block.unwrap_or(bundle.inclusion.block);
+    if env.block_number < bundle.inclusion.block || env.block_number > max_block {
+        return Err(SimError::Bundle(EthSimBundleError::InvalidInclusion))
+    }
+
+    let mut res = SimBundleResult::default();
+
+    for (item, refund_percent) in bundle.body.iter().zip(&bundle.refund_percents) {
+        if Instant::now() > env.deadline {
+            return Err(SimError::Bundle(EthSimBundleError::SimulationTimeout))
+        }
+
+        let profit = match item {
+            SimBundleItem::Tx { tx, signer, can_revert } => {
+                let coinbase_before = coinbase_balance::<Eth, _, _>(evm, env.coinbase)?;
+
+                Call::evm_config(eth_api).fill_tx_env(evm.tx_mut(), tx, *signer);
+                let ResultAndState { result, state } = match evm.transact() {
+                    Ok(res) => res,
+                    Err(EVMError::Transaction(err)) => {
+                        return Err(SimError::Bundle(EthSimBundleError::InvalidTransaction(
+                            err.to_string(),
+                        )))
+                    }
+                    Err(err) => return Err(SimError::Eth(Eth::Error::from_evm_err(err))),
+                };
+
+                if !result.is_success() && !can_revert {
+                    return Err(SimError::Bundle(EthSimBundleError::TransactionReverted))
+                }
+
+                res.gas_used += result.gas_used();
+                res.logs.push(SimBundleLogs {
+                    tx_logs: Some(result.logs().to_vec()),
+                    bundle_logs: None,
+                });
+
+                evm.context.evm.db.commit(state);
+
+                let coinbase_after = coinbase_balance::<Eth, _, _>(evm, env.coinbase)?;
+                coinbase_after.saturating_sub(coinbase_before)
+            }
+            SimBundleItem::Bundle(inner) => {
+                // the refunds of the nested bundle are deducted from its profit, but not paid
+                // out, so the balance change of the coinbase would overstate its profit
+                let inner = simulate_bundle(eth_api, evm, inner, env)?;
+                res.gas_used += inner.gas_used;
+                res.logs.push(SimBundleLogs { tx_logs: None, bundle_logs: Some(inner.logs) });
+                inner.profit
+            }
+        };
+
+        res.profit += profit;
+        if refund_percent.is_none() {
+            res.refundable_value += profit;
+        }
+    }
+
+    // the refunds are paid out in separate transactions, the cost of which is covered by the
+    // refund itself
+    let payout_gas = SBUNDLE_PAYOUT_MAX_COST * bundle.refund_recipients;
+    let payout_tx_fee = env.basefee * U256::from(payout_gas);
+    for refund_percent in bundle.refund_percents.iter().flatten() {
+        res.gas_used += payout_gas;
+
+        let payout_value = res.refundable_value * U256::from(*refund_percent) / U256::from(100);
+        if payout_tx_fee > payout_value {
+            return Err(SimError::Bundle(EthSimBundleError::NegativeProfit))
+        }
+        res.profit = res
+            .profit
+            .checked_sub(payout_value)
+            .ok_or(SimError::Bundle(EthSimBundleError::NegativeProfit))?;
+    }
+
+    Ok(res)
+}
+
+/// Returns the current balance of the coinbase account.
+fn coinbase_balance<Eth, EXT, DB>(
+    evm: &mut Evm<'_, EXT, DB>,
+    coinbase: Address,
+) -> Result<U256, SimError<Eth::Error>>
+where
+    Eth: Call,
+    DB: Database,
+    EthApiError: From<DB::Error>,
+{
+    let account = evm
+        .db_mut()
+        .basic(coinbase)
+        .map_err(|err| SimError::Eth(Eth::Error::from_eth_err(EthApiError::from(err))))?;
+    Ok(account.map(|acc| acc.balance).unwrap_or_default())
+}
+
+/// [`EthSimBundle`] specific errors.
+#[derive(Debug, thiserror::Error)]
+pub enum EthSimBundleError {
+    /// Thrown if the bundle body is empty.
+    #[error("bundle body is empty")]
+    EmptyBundleBody,
+    /// Thrown if the bundle body has more items than allowed.
+    #[error("bundle body exceeds the limit of {0} items")]
+    BundleTooLarge(usize),
+    /// Thrown if bundles are nested deeper than allowed.
+    #[error("bundle nesting exceeds the maximum depth of {0}")]
+    MaxDepth(usize),
+    /// Thrown if the bundle contains a transaction hash instead of a signed transaction.
+    #[error("only fully matched bundles can be simulated")]
+    UnmatchedBundle,
+    /// Thrown if the inclusion range is invalid or does not contain the simulated block.
+    #[error("invalid inclusion")]
+    InvalidInclusion,
+    /// Thrown if the refund settings are invalid.
+    #[error("invalid validity")]
+    InvalidValidity,
+    /// Thrown if the requested timeout exceeds the limit.
+    #[error("timeout exceeds the limit of {0} seconds")]
+    TimeoutTooLarge(u64),
+    /// Thrown if the simulation did not finish in time.
+    #[error("simulation timed out")]
+    SimulationTimeout,
+    /// Thrown if a transaction of the bundle is invalid.
+    #[error("invalid transaction: {0}")]
+    InvalidTransaction(String),
+    /// Thrown if a transaction that is not allowed to revert reverted.
+    #[error("transaction reverted")]
+    TransactionReverted,
+    /// Thrown if the refunds exceed the profit of the bundle.
+    #[error("negative profit")]
+    NegativeProfit,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eth::helpers::test_utils::{build_test_eth_api, TestEthApi};
+    use alloy_consensus::TxLegacy;
+    use alloy_eips::eip2718::Encodable2718;
+    use alloy_primitives::{Bytes, TxKind, B256};
+    use reth_primitives::{constants::GWEI_TO_WEI, Transaction};
+    use reth_provider::ProviderError;
+    use reth_testing_utils::generators::{self, sign_tx_with_key_pair};
+    use revm::db::EmptyDBTyped;
+    use revm_primitives::{AccountInfo, Env};
+    use serde_json::json;
+
+    /// Returns the error message of a request that fails to parse.
+    fn parse_error(request: serde_json::Value) -> String {
+        match parse_bundle(serde_json::from_value(request).unwrap(), None, 0) {
+            Err(EthApiError::InvalidParams(msg)) => msg,
+            res => panic!("unexpected result: {res:?}"),
+        }
+    }
+
+    fn hash_item() -> serde_json::Value {
+        json!({ "hash": B256::ZERO })
+    }
+
+    #[test]
+    fn parse_bundle_limits() {
+        // the outermost bundle has depth 0
+        let mut request =
+            json!({ "version": "v0.1", "inclusion": { "block": "0x1" }, "body": [hash_item()] });
+        for _ in 0..=MAX_NESTED_BUNDLE_DEPTH {
+            request = json!({
+                "version": "v0.1",
+                "inclusion": { "block": "0x1" },
+                "body": [{ "bundle": request }]
+            });
+        }
+        assert_eq!(
+            parse_error(request),
+            EthSimBundleError::MaxDepth(MAX_NESTED_BUNDLE_DEPTH).to_string()
+        );
+
+        let request = json!({
+            "version": "v0.1",
+            "inclusion": { "block": "0x1" },
+            "body": vec![hash_item(); MAX_BUNDLE_BODY_SIZE + 1]
+        });
+        assert_eq!(
+            parse_error(request),
+            EthSimBundleError::BundleTooLarge(MAX_BUNDLE_BODY_SIZE).to_string()
+        );
+
+        let request = json!({ "version": "v0.1", "inclusion": { "block": "0x1" }, "body": [] });
+        assert_eq!(parse_error(request), EthSimBundleError::EmptyBundleBody.to_string());
+
+        let request =
+            json!({ "version": "v0.1", "inclusion": { "block": "0x1" }, "body": [hash_item()] });
+        assert_eq!(parse_error(request), EthSimBundleError::UnmatchedBundle.to_string());
+    }
+
+    #[test]
+    fn parse_bundle_rejects_malformed_inclusion() {
+        let request = json!({
+            "version": "v0.1",
+            "inclusion": { "block": "0x2", "maxBlock": "0x1" },
+            "body": [hash_item()]
+        });
+        assert_eq!(parse_error(request), EthSimBundleError::InvalidInclusion.to_string());
+
+        // nested bundles must be valid within the range of their parent
+        let request = json!({
+            "version": "v0.1",
+            "inclusion": { "block": "0x1", "maxBlock": "0x2" },
+            "body": [{ "bundle": {
+                "version": "v0.1",
+                "inclusion": { "block": "0x2", "maxBlock": "0x3" },
+                "body": [hash_item()]
+            }}]
+        });
+        assert_eq!(parse_error(request), EthSimBundleError::InvalidInclusion.to_string());
+    }
+
+    #[test]
+    fn parse_bundle_rejects_invalid_refunds() {
+        for validity in [
+            json!({ "refund": [{ "bodyIdx": 1, "percent": 10 }] }),
+            json!({ "refund": [{ "bodyIdx": 0, "percent": 60 }, { "bodyIdx": 0, "percent": 50 }] }),
+            json!({ "refundConfig": [{ "address": Address::ZERO, "percent": 90 }] }),
+            // percents that overflow must not wrap around the checks
+            json!({ "refund": [
+                { "bodyIdx": 0, "percent": u64::MAX },
+                { "bodyIdx": 0, "percent": 2 }
+            ] }),
+            json!({ "refundConfig": [
+                { "address": Address::ZERO, "percent": u64::MAX },
+                { "address": Address::ZERO, "percent": 101 }
+            ] }),
+        ] {
+            let request = json!({
+                "version": "v0.1",
+                "inclusion": { "block": "0x1" },
+                "body": [hash_item()],
+                "validity": validity
+            });
+            assert_eq!(parse_error(request), EthSimBundleError::InvalidValidity.to_string());
+        }
+    }
+
+    /// Returns the signer of the first transaction of the bundle.
+    fn first_signer(bundle: &SimBundle) -> Address {
+        match &bundle.body[0] {
+            SimBundleItem::Tx { signer, .. } => *signer,
+            SimBundleItem::Bundle(inner) => first_signer(inner),
+        }
+    }
+
+    /// Simulates the request at block 1 with a funded sender.
+    fn simulate_request(
+        eth_api: &TestEthApi,
+        request: serde_json::Value,
+    ) -> Result<SimBundleResult, SimError<EthApiError>> {
+        let bundle = parse_bundle(serde_json::from_value(request).unwrap(), None, 0).unwrap();
+
+        let mut db = CacheDB::new(EmptyDBTyped::<ProviderError>::new());
+        db.insert_account_info(
+            first_signer(&bundle),
+            AccountInfo { balance: U256::from(10).pow(U256::from(18)), ..Default::default() },
+        );
+        let mut env = Env::default();
+        env.block.number = U256::from(1);
+        env.block.coinbase = COINBASE;
+        env.block.basefee = BASEFEE;
+        env.block.gas_limit = U256::from(30_000_000);
+        let env = EnvWithHandlerCfg::new_with_spec_id(Box::new(env), SpecId::CANCUN);
+        let mut evm = Call::evm_config(eth_api).evm_with_env(db, env);
+
+        let sim_env = SimEnv {
+            block_number: 1,
+            coinbase: COINBASE,
+            basefee: BASEFEE,
+            deadline: Instant::now() + DEFAULT_SIM_TIMEOUT,
+        };
+        simulate_bundle(eth_api, &mut evm, &bundle, &sim_env)
+    }
+
+    const COINBASE: Address = Address::with_last_byte(0xcb);
+    const BASEFEE: U256 = U256::from_limbs([GWEI_TO_WEI, 0, 0, 0]);
+    /// The priority fee each transfer pays to the coinbase.
+    const PRIORITY_FEE: U256 = U256::from_limbs([21_000 * 10 * GWEI_TO_WEI, 0, 0, 0]);
+
+    /// Returns a signer of transfers that each pay [`PRIORITY_FEE`] to the coinbase.
+    fn transfers() -> impl Fn(u64) -> Bytes {
+        let key_pair = generators::generate_keys(&mut generators::rng(), 1)[0];
+        move |nonce| {
+            sign_tx_with_key_pair(
+                key_pair,
+                Transaction::Legacy(TxLegacy {
+                    chain_id: Some(1),
+                    nonce,
+                    gas_price: (11 * GWEI_TO_WEI).into(),
+                    gas_limit: 21_000,
+                    to: TxKind::Call(Address::with_last_byte(0x42)),
+                    value: U256::from(1),
+                    input: Bytes::new(),
+                }),
+            )
+            .encoded_2718()
+            .into()
+        }
+    }
+
+    #[tokio::test]
+    async fn simulate_bundle_with_refund() {
+        let eth_api = build_test_eth_api();
+        let transfer = transfers();
+
+        let simulate = |refund_percent: u64| {
+            let request = json!({
+                "version": "v0.1",
+                "inclusion": { "block": "0x1" },
+                "body": [
+                    { "tx": transfer(0), "canRevert": false },
+                    { "tx": transfer(1), "canRevert": false }
+                ],
+                "validity": { "refund": [{ "bodyIdx": 1, "percent": refund_percent }] }
+            });
+            simulate_request(&eth_api, request)
+        };
+
+        // half of the value of the first transfer is refunded to the sender of the second one
+        let res = simulate(50).unwrap();
+        assert_eq!(res.gas_used, 2 * 21_000 + SBUNDLE_PAYOUT_MAX_COST);
+        assert_eq!(res.refundable_value, PRIORITY_FEE);
+        assert_eq!(res.profit, PRIORITY_FEE * U256::from(2) - PRIORITY_FEE / U256::from(2));
+        assert_eq!(res.logs.len(), 2);
+
+        // a refund that doesn't cover the cost of its payout is rejected
+        assert!(matches!(simulate(1), Err(SimError::Bundle(EthSimBundleError::NegativeProfit))));
+    }
+
+    #[tokio::test]
+    async fn simulate_nested_bundle_with_refund() {
+        let eth_api = build_test_eth_api();
+        let transfer = transfers();
+
+        let request = json!({
+            "version": "v0.1",
+            "inclusion": { "block": "0x1" },
+            "body": [
+                { "tx": transfer(0), "canRevert": false },
+                { "bundle": {
+                    "version": "v0.1",
+                    "inclusion": { "block": "0x1" },
+                    "body": [
+                        { "tx": transfer(1), "canRevert": false },
+                        { "tx": transfer(2), "canRevert": false }
+                    ],
+                    "validity": { "refund": [{ "bodyIdx": 1, "percent": 50 }] }
+                }}
+            ]
+        });
+        let res = simulate_request(&eth_api, request).unwrap();
+
+        // the nested bundle refunds half of the value of its first transfer
+        let nested_profit = PRIORITY_FEE * U256::from(2) - PRIORITY_FEE / U256::from(2);
+        assert_eq!(res.gas_used, 3 * 21_000 + SBUNDLE_PAYOUT_MAX_COST);
+        assert_eq!(res.profit, PRIORITY_FEE + nested_profit);
+        assert_eq!(res.refundable_value, PRIORITY_FEE + nested_profit);
+        assert_eq!(res.logs.len(), 2);
+    }
+}
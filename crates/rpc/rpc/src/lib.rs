@@ -30,8 +30,15 @@ use http_body as _;
 use hyper as _;
 use jsonwebtoken as _;
 use pin_project as _;
+#[cfg(all(feature = "jemalloc", not(feature = "js-tracer")))]
+use tikv_jemalloc_ctl as _;
 use tower as _;
 
+/// Lets the JS tracer tests measure the heap growth of the tracing thread.
+#[cfg(all(test, unix, feature = "jemalloc"))]
+#[global_allocator]
+static ALLOC: tikv_jemallocator::Jemalloc = tikv_jemallocator::Jemalloc;
+
 mod admin;
 mod debug;
 mod engine;
@@ -44,9 +51,9 @@ mod trace;
 mod txpool;
 mod web3;
 pub use admin::AdminApi;
-pub use debug::DebugApi;
+pub use debug::{DebugApi, SetHeadHandle};
 pub use engine::{EngineApi, EngineEthApi};
-pub use eth::{EthApi, EthBundle, EthFilter, EthPubSub};
+pub use eth::{EthApi, EthBundle, EthFilter, EthPubSub, EthSimBundle};
 pub use net::NetApi;
 pub use otterscan::OtterscanApi;
 pub use reth::RethApi;
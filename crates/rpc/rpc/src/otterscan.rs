@@ -1,7 +1,7 @@
-use alloy_consensus::Transaction;
+use alloy_consensus::Transaction as _;
 use alloy_network::{ReceiptResponse, TransactionResponse};
 use alloy_primitives::{Address, Bytes, TxHash, B256, U256};
-use alloy_rpc_types::{BlockTransactions, Header, TransactionReceipt};
+use alloy_rpc_types::{BlockTransactions, Header, Transaction, TransactionReceipt};
 use alloy_rpc_types_trace::{
     otterscan::{
         BlockDetails, ContractCreator, InternalOperation, OperationType, OtsBlockTransactions,
@@ -12,6 +12,7 @@ use alloy_rpc_types_trace::{
 use async_trait::async_trait;
 use jsonrpsee::{core::RpcResult, types::ErrorObjectOwned};
 use reth_primitives::{BlockId, BlockNumberOrTag};
+use reth_provider::{AccountHistoryReader, TraceIndexReader};
 use reth_rpc_api::{EthApiServer, OtterscanServer};
 use reth_rpc_eth_api::{
     helpers::{EthTransactions, TraceExt},
@@ -24,23 +25,29 @@ use revm_inspectors::{
     transfer::{TransferInspector, TransferKind},
 };
 use revm_primitives::{ExecutionResult, SignedAuthorization};
+use std::ops::RangeInclusive;
 
 const API_LEVEL: u64 = 8;
 
+/// The maximum number of blocks covered by a single history index lookup when searching for the
+/// transactions of an address.
+const SEARCH_WINDOW_SIZE: u64 = 100_000;
+
 /// Otterscan API.
 #[derive(Debug)]
-pub struct OtterscanApi<Eth> {
+pub struct OtterscanApi<Provider, Eth> {
+    provider: Provider,
     eth: Eth,
 }
 
-impl<Eth> OtterscanApi<Eth> {
+impl<Provider, Eth> OtterscanApi<Provider, Eth> {
     /// Creates a new instance of `Otterscan`.
-    pub const fn new(eth: Eth) -> Self {
-        Self { eth }
+    pub const fn new(provider: Provider, eth: Eth) -> Self {
+        Self { provider, eth }
     }
 }
 
-impl<Eth> OtterscanApi<Eth>
+impl<Provider, Eth> OtterscanApi<Provider, Eth>
 where
     Eth: FullEthApiTypes,
 {
@@ -58,11 +65,155 @@ where
 
         Ok(BlockDetails::new(block, Default::default(), U256::from(total_fees)))
     }
+
+    /// Constructs an `OtsTransactionReceipt` from a receipt, dropping its logs and bloom.
+    fn ots_receipt(
+        receipt: RpcReceipt<Eth::NetworkTypes>,
+        tx_ty: u8,
+        timestamp: Option<u64>,
+    ) -> OtsTransactionReceipt {
+        let inner = OtsReceipt {
+            status: receipt.status(),
+            cumulative_gas_used: receipt.cumulative_gas_used() as u64,
+            logs: None,
+            logs_bloom: None,
+            r#type: tx_ty,
+        };
+
+        let receipt = TransactionReceipt {
+            inner,
+            transaction_hash: receipt.transaction_hash(),
+            transaction_index: receipt.transaction_index(),
+            block_hash: receipt.block_hash(),
+            block_number: receipt.block_number(),
+            gas_used: receipt.gas_used(),
+            effective_gas_price: receipt.effective_gas_price(),
+            blob_gas_used: receipt.blob_gas_used(),
+            blob_gas_price: receipt.blob_gas_price(),
+            from: receipt.from(),
+            to: receipt.to(),
+            contract_address: receipt.contract_address(),
+            state_root: receipt.state_root(),
+            authorization_list: receipt.authorization_list().map(<[SignedAuthorization]>::to_vec),
+        };
+
+        OtsTransactionReceipt { receipt, timestamp }
+    }
+}
+
+impl<Provider, Eth> OtterscanApi<Provider, Eth>
+where
+    Provider: AccountHistoryReader + TraceIndexReader,
+{
+    /// Returns the numbers of the blocks in the given range in which `address` may be involved in
+    /// a transaction, in ascending order.
+    ///
+    /// These are the blocks of the [`AccountHistoryReader`], in which the account changed or, if
+    /// the transaction appearances index is enabled, the address sent or received a
+    /// transaction. If the trace index is maintained (`--rpc.trace-index`), the blocks it
+    /// covers in which the address appeared in any call are added, which includes internal
+    /// calls that leave the account unchanged.
+    fn address_blocks(&self, address: Address, range: RangeInclusive<u64>) -> RpcResult<Vec<u64>> {
+        let mut blocks = self
+            .provider
+            .account_history_blocks(address, range.clone())
+            .map_err(EthApiError::from)?;
+
+        if let Some(tip) = self.provider.trace_index_tip().map_err(EthApiError::from)? {
+            if *range.start() <= tip {
+                blocks.extend(
+                    self.provider
+                        .trace_index_blocks(&[address], *range.start()..=tip.min(*range.end()))
+                        .map_err(EthApiError::from)?,
+                );
+                blocks.sort_unstable();
+                blocks.dedup();
+            }
+        }
+
+        Ok(blocks)
+    }
+}
+
+impl<Provider, Eth> OtterscanApi<Provider, Eth>
+where
+    Eth: EthApiServer<
+            RpcTransaction<Eth::NetworkTypes>,
+            RpcBlock<Eth::NetworkTypes>,
+            RpcReceipt<Eth::NetworkTypes>,
+        > + EthTransactions
+        + TraceExt
+        + 'static,
+{
+    /// Returns the transactions of the given block that `address` was involved in, together with
+    /// their receipts, in the order of execution.
+    ///
+    /// An address is involved in a transaction if it is the caller or the callee of any of its
+    /// calls. This covers the sender and recipient of the transaction, created contracts and
+    /// internal calls and transfers.
+    ///
+    /// Only the blocks returned by [`Self::address_blocks`] are searched.
+    async fn address_transactions_in_block(
+        &self,
+        address: Address,
+        block_number: u64,
+    ) -> RpcResult<(Vec<Transaction>, Vec<OtsTransactionReceipt>)> {
+        let involved =
+            self.eth
+                .trace_block_with(
+                    block_number.into(),
+                    None,
+                    TracingInspectorConfig::default_parity(),
+                    move |_tx_info, inspector, _, _, _| {
+                        Ok(inspector.into_traces().into_nodes().iter().any(|node| {
+                            node.trace.caller == address || node.trace.address == address
+                        }))
+                    },
+                )
+                .await
+                .map_err(Into::into)?
+                .unwrap_or_default();
+
+        // the account was only touched outside of transactions, e.g. by a withdrawal
+        if !involved.contains(&true) {
+            return Ok(Default::default())
+        }
+
+        let block_id = block_number.into();
+        let block = self.eth.block_by_number(block_id, true);
+        let block_id = block_id.into();
+        let receipts = self.eth.block_receipts(block_id);
+        let (block, receipts) = futures::try_join!(block, receipts)?;
+
+        let block = block.ok_or(EthApiError::HeaderNotFound(block_id))?;
+        let receipts = receipts.ok_or(EthApiError::ReceiptsNotFound(block_id))?;
+        let timestamp = Some(block.header.timestamp);
+
+        let BlockTransactions::Full(transactions) = block.transactions else {
+            return Err(internal_rpc_err("block is not full"));
+        };
+
+        Ok(transactions
+            .into_iter()
+            .zip(receipts)
+            .zip(involved)
+            .filter(|(_, involved)| *involved)
+            .map(|((tx, receipt), _)| {
+                let tx_ty = Eth::TransactionCompat::tx_type(&tx);
+                (
+                    Eth::TransactionCompat::otterscan_api_transaction(tx),
+                    Self::ots_receipt(receipt, tx_ty, timestamp),
+                )
+            })
+            .unzip())
+    }
 }
 
 #[async_trait]
-impl<Eth> OtterscanServer<RpcTransaction<Eth::NetworkTypes>> for OtterscanApi<Eth>
+impl<Provider, Eth> OtterscanServer<RpcTransaction<Eth::NetworkTypes>>
+    for OtterscanApi<Provider, Eth>
 where
+    Provider: AccountHistoryReader + TraceIndexReader + 'static,
     Eth: EthApiServer<
             RpcTransaction<Eth::NetworkTypes>,
             RpcBlock<Eth::NetworkTypes>,
@@ -239,36 +390,7 @@ where
         let receipts = receipts
             .drain(page_start..page_end)
             .zip(transactions.iter().map(Eth::TransactionCompat::tx_type))
-            .map(|(receipt, tx_ty)| {
-                let inner = OtsReceipt {
-                    status: receipt.status(),
-                    cumulative_gas_used: receipt.cumulative_gas_used() as u64,
-                    logs: None,
-                    logs_bloom: None,
-                    r#type: tx_ty,
-                };
-
-                let receipt = TransactionReceipt {
-                    inner,
-                    transaction_hash: receipt.transaction_hash(),
-                    transaction_index: receipt.transaction_index(),
-                    block_hash: receipt.block_hash(),
-                    block_number: receipt.block_number(),
-                    gas_used: receipt.gas_used(),
-                    effective_gas_price: receipt.effective_gas_price(),
-                    blob_gas_used: receipt.blob_gas_used(),
-                    blob_gas_price: receipt.blob_gas_price(),
-                    from: receipt.from(),
-                    to: receipt.to(),
-                    contract_address: receipt.contract_address(),
-                    state_root: receipt.state_root(),
-                    authorization_list: receipt
-                        .authorization_list()
-                        .map(<[SignedAuthorization]>::to_vec),
-                };
-
-                OtsTransactionReceipt { receipt, timestamp }
-            })
+            .map(|(receipt, tx_ty)| Self::ots_receipt(receipt, tx_ty, timestamp))
             .collect();
 
         // use `transaction_count` to indicate the paginate information
@@ -278,23 +400,104 @@ where
     }
 
     /// Handler for `searchTransactionsBefore`
+    ///
+    /// See `address_transactions_in_block` for which transactions are found.
     async fn search_transactions_before(
         &self,
-        _address: Address,
-        _block_number: u64,
-        _page_size: usize,
+        address: Address,
+        block_number: u64,
+        page_size: usize,
     ) -> RpcResult<TransactionsWithReceipts> {
-        Err(internal_rpc_err("unimplemented"))
+        // `0` means that the search starts at the latest block
+        let is_first_page = block_number == 0;
+        // exclusive upper bound of the next window to search
+        let mut to = if is_first_page {
+            self.eth.block_number()?.saturating_to::<u64>().saturating_add(1)
+        } else {
+            block_number
+        };
+
+        let mut txs = Vec::new();
+        let mut receipts = Vec::new();
+        let mut has_more = false;
+
+        'search: while to > 0 {
+            let from = to.saturating_sub(SEARCH_WINDOW_SIZE);
+            let blocks = self.address_blocks(address, from..=to - 1)?;
+
+            for block in blocks.into_iter().rev() {
+                // blocks are never split across pages
+                if txs.len() >= page_size {
+                    has_more = true;
+                    break 'search
+                }
+
+                let (block_txs, block_receipts) =
+                    self.address_transactions_in_block(address, block).await?;
+                txs.extend(block_txs.into_iter().rev());
+                receipts.extend(block_receipts.into_iter().rev());
+            }
+
+            to = from;
+        }
+
+        Ok(TransactionsWithReceipts {
+            txs,
+            receipts,
+            first_page: is_first_page,
+            last_page: !has_more,
+        })
     }
 
     /// Handler for `searchTransactionsAfter`
+    ///
+    /// See `address_transactions_in_block` for which transactions are found.
     async fn search_transactions_after(
         &self,
-        _address: Address,
-        _block_number: u64,
-        _page_size: usize,
+        address: Address,
+        block_number: u64,
+        page_size: usize,
     ) -> RpcResult<TransactionsWithReceipts> {
-        Err(internal_rpc_err("unimplemented"))
+        // `0` means that the search starts at genesis
+        let is_last_page = block_number == 0;
+        let latest = self.eth.block_number()?.saturating_to::<u64>();
+        // inclusive lower bound of the next window to search
+        let mut from = if is_last_page { 0 } else { block_number.saturating_add(1) };
+
+        let mut txs = Vec::new();
+        let mut receipts = Vec::new();
+        let mut has_more = false;
+
+        'search: while from <= latest {
+            let to = from.saturating_add(SEARCH_WINDOW_SIZE - 1).min(latest);
+            let blocks = self.address_blocks(address, from..=to)?;
+
+            for block in blocks {
+                // blocks are never split across pages
+                if txs.len() >= page_size {
+                    has_more = true;
+                    break 'search
+                }
+
+                let (block_txs, block_receipts) =
+                    self.address_transactions_in_block(address, block).await?;
+                txs.extend(block_txs);
+                receipts.extend(block_receipts);
+            }
+
+            from = to + 1;
+        }
+
+        // results are always returned from the newest to the oldest transaction
+        txs.reverse();
+        receipts.reverse();
+
+        Ok(TransactionsWithReceipts {
+            txs,
+            receipts,
+            first_page: !has_more,
+            last_page: is_last_page,
+        })
     }
 
     /// Handler for `getTransactionBySenderAndNonce`
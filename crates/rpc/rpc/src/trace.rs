@@ -18,7 +18,10 @@ use reth_consensus_common::calc::{
 };
 use reth_evm::ConfigureEvmEnv;
 use reth_primitives::{BlockId, Header};
-use reth_provider::{BlockReader, ChainSpecProvider, EvmEnvProvider, StateProviderFactory};
+use reth_provider::{
+    BlockReader, ChainSpecProvider, EvmEnvProvider, StateProviderFactory, TraceIndexReader,
+    TransactionVariant,
+};
 use reth_revm::database::StateProviderDatabase;
 use reth_rpc_api::TraceApiServer;
 use reth_rpc_eth_api::{
@@ -80,6 +83,7 @@ where
         + StateProviderFactory
         + EvmEnvProvider
         + ChainSpecProvider<ChainSpec: EthereumHardforks>
+        + TraceIndexReader
         + 'static,
     Eth: TraceExt + 'static,
 {
@@ -252,7 +256,8 @@ where
     ) -> Result<Vec<LocalizedTransactionTrace>, Eth::Error> {
         // We'll reuse the matcher across multiple blocks that are traced in parallel
         let matcher = Arc::new(filter.matcher());
-        let TraceFilter { from_block, to_block, after, count, .. } = filter;
+        let TraceFilter { from_block, to_block, from_address, to_address, after, count, .. } =
+            filter;
         let start = from_block.unwrap_or(0);
         let end = if let Some(to_block) = to_block {
             to_block
@@ -267,23 +272,55 @@ where
             .into())
         }
 
+        // if the trace index is maintained, only the blocks in which one of the filtered addresses
+        // appeared need to be traced, all blocks above the tip of the index are traced in full
+        let mut indexed_blocks = Vec::new();
+        let mut unindexed_start = start;
+        if !from_address.is_empty() || !to_address.is_empty() {
+            if let Some(tip) =
+                self.provider().trace_index_tip().map_err(Eth::Error::from_eth_err)?
+            {
+                if start <= tip {
+                    let addresses =
+                        from_address.iter().chain(&to_address).copied().collect::<Vec<_>>();
+                    indexed_blocks = self
+                        .provider()
+                        .trace_index_blocks(&addresses, start..=tip.min(end))
+                        .map_err(Eth::Error::from_eth_err)?;
+                }
+                unindexed_start = start.max(tip + 1);
+            }
+        }
+
         // ensure that the range is not too large, since we need to fetch all blocks in the range
-        let distance = end.saturating_sub(start);
-        if distance > 100 {
+        let distance = end.saturating_sub(unindexed_start);
+        if unindexed_start <= end && distance > 100 {
             return Err(EthApiError::InvalidParams(
                 "Block range too large; currently limited to 100 blocks".to_string(),
             )
             .into())
         }
 
-        // fetch all blocks in that range
-        let blocks = self
-            .provider()
-            .sealed_block_with_senders_range(start..=end)
-            .map_err(Eth::Error::from_eth_err)?
-            .into_iter()
-            .map(Arc::new)
-            .collect::<Vec<_>>();
+        // fetch all blocks that need to be traced
+        let mut blocks = Vec::with_capacity(indexed_blocks.len());
+        for number in indexed_blocks {
+            if let Some(block) = self
+                .provider()
+                .sealed_block_with_senders(number.into(), TransactionVariant::WithHash)
+                .map_err(Eth::Error::from_eth_err)?
+            {
+                blocks.push(Arc::new(block));
+            }
+        }
+        if unindexed_start <= end {
+            blocks.extend(
+                self.provider()
+                    .sealed_block_with_senders_range(unindexed_start..=end)
+                    .map_err(Eth::Error::from_eth_err)?
+                    .into_iter()
+                    .map(Arc::new),
+            );
+        }
 
         // trace all blocks
         let mut block_traces = Vec::with_capacity(blocks.len());
@@ -569,6 +606,7 @@ where
         + StateProviderFactory
         + EvmEnvProvider
         + ChainSpecProvider<ChainSpec: EthereumHardforks>
+        + TraceIndexReader
         + 'static,
     Eth: TraceExt + 'static,
 {
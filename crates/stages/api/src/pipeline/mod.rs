@@ -31,6 +31,10 @@ use progress::*;
 use reth_errors::RethResult;
 pub use set::*;
 
+/// The maximum number of blocks whose changesets are moved from static files back into the
+/// database within a single transaction, before unwinding.
+const CHANGESET_RESTORE_BATCH_BLOCKS: u64 = 1_000;
+
 /// A container for a queued stage.
 pub(crate) type BoxedStage<DB> = Box<dyn Stage<DB>>;
 
@@ -278,6 +282,19 @@ impl<N: ProviderNodeTypes> Pipeline<N> {
 
         let mut provider_rw = self.provider_factory.database_provider_rw()?;
 
+        // Changesets of blocks which were moved to static files need to be in the database, so
+        // the stages can unwind them. They're restored in batches that are committed one by one,
+        // while the last batch is committed alongside the first unwound stage.
+        while !provider_rw
+            .restore_static_file_changesets_batch(to + 1, CHANGESET_RESTORE_BATCH_BLOCKS)?
+        {
+            UnifiedStorageWriter::commit_unwind(
+                provider_rw,
+                self.provider_factory.static_file_provider(),
+            )?;
+            provider_rw = self.provider_factory.database_provider_rw()?;
+        }
+
         for stage in unwind_pipeline {
             let stage_id = stage.id();
             let span = info_span!("Unwinding", stage = %stage_id);
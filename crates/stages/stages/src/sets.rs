@@ -36,8 +36,9 @@
 use crate::{
     stages::{
         AccountHashingStage, BodyStage, ExecutionStage, FinishStage, HeaderStage,
-        IndexAccountHistoryStage, IndexStorageHistoryStage, MerkleStage, PruneSenderRecoveryStage,
-        PruneStage, SenderRecoveryStage, StorageHashingStage, TransactionLookupStage,
+        IndexAccountHistoryStage, IndexStorageHistoryStage, IndexTransactionAppearancesStage,
+        MerkleStage, PruneSenderRecoveryStage, PruneStage, SenderRecoveryStage,
+        StorageHashingStage, TransactionLookupStage,
     },
     StageSet, StageSetBuilder,
 };
@@ -73,6 +74,7 @@ use tokio::sync::watch;
 /// - [`TransactionLookupStage`]
 /// - [`IndexStorageHistoryStage`]
 /// - [`IndexAccountHistoryStage`]
+/// - [`IndexTransactionAppearancesStage`] (if enabled)
 /// - [`PruneStage`] (execute)
 /// - [`FinishStage`]
 #[derive(Debug)]
@@ -407,6 +409,7 @@ where
     TransactionLookupStage: Stage<Provider>,
     IndexStorageHistoryStage: Stage<Provider>,
     IndexAccountHistoryStage: Stage<Provider>,
+    IndexTransactionAppearancesStage: Stage<Provider>,
 {
     fn builder(self) -> StageSetBuilder<Provider> {
         StageSetBuilder::default()
@@ -425,5 +428,10 @@ where
                 self.stages_config.etl.clone(),
                 self.prune_modes.storage_history,
             ))
+            .add_stage_opt(self.stages_config.index_transaction_appearances.enabled.then(|| {
+                IndexTransactionAppearancesStage::new(
+                    self.stages_config.index_transaction_appearances,
+                )
+            }))
     }
 }
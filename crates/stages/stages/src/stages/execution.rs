@@ -13,7 +13,9 @@ use reth_exex::{ExExManagerHandle, ExExNotification, ExExNotificationSource};
 use reth_primitives::{Header, SealedHeader, StaticFileSegment};
 use reth_primitives_traits::format_gas_throughput;
 use reth_provider::{
-    providers::{StaticFileProvider, StaticFileProviderRWRefMut, StaticFileWriter},
+    providers::{
+        snap_sync_pivot, StaticFileProvider, StaticFileProviderRWRefMut, StaticFileWriter,
+    },
     writer::UnifiedStorageWriter,
     BlockReader, DBProvider, HeaderProvider, LatestStateProviderRef, OriginalValuesKnown,
     ProviderError, StateChangeWriter, StateWriter, StaticFileProviderFactory, StatsReader,
@@ -204,9 +206,14 @@ where
         let prune_modes = self.adjust_prune_modes(provider, start_block, max_block)?;
         let static_file_provider = provider.static_file_provider();
 
+        // After snap sync, the hashed state is the only complete copy of the state, so it is read
+        // from and kept up to date by this stage. There are no receipts before the pivot block.
+        let snap_synced = snap_sync_pivot(provider.tx_ref())?.is_some();
+
         // We only use static files for Receipts, if there is no receipt pruning of any kind.
         let static_file_producer = if self.prune_modes.receipts.is_none() &&
-            self.prune_modes.receipts_log_filter.is_empty()
+            self.prune_modes.receipts_log_filter.is_empty() &&
+            !snap_synced
         {
             debug!(target: "sync::stages::execution", start = start_block, "Preparing static file producer");
             let mut producer =
@@ -219,10 +226,10 @@ where
             None
         };
 
-        let db = StateProviderDatabase(LatestStateProviderRef::new(
-            provider.tx_ref(),
-            provider.static_file_provider(),
-        ));
+        let db = StateProviderDatabase(
+            LatestStateProviderRef::new(provider.tx_ref(), provider.static_file_provider())
+                .with_hashed_state(snap_synced),
+        );
         let mut executor = self.executor_provider.batch_executor(db);
         executor.set_tip(max_block);
         executor.set_prune_modes(prune_modes);
@@ -363,8 +370,12 @@ where
         let time = Instant::now();
 
         // write output
+        let hashed_state = snap_synced.then(|| state.hash_state_slow().into_sorted());
         let mut writer = UnifiedStorageWriter::new(provider, static_file_producer);
         writer.write_to_storage(state, OriginalValuesKnown::Yes)?;
+        if let Some(hashed_state) = hashed_state {
+            provider.write_hashed_state(&hashed_state)?;
+        }
 
         let db_write_duration = time.elapsed();
         debug!(
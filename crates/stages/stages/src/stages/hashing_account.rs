@@ -8,7 +8,9 @@ use reth_db_api::{
 };
 use reth_etl::Collector;
 use reth_primitives::Account;
-use reth_provider::{AccountExtReader, DBProvider, HashingWriter, StatsReader};
+use reth_provider::{
+    providers::snap_sync_pivot, AccountExtReader, DBProvider, HashingWriter, StatsReader,
+};
 use reth_stages_api::{
     AccountHashingCheckpoint, EntitiesCheckpoint, ExecInput, ExecOutput, Stage, StageCheckpoint,
     StageError, StageId, UnwindInput, UnwindOutput,
@@ -143,6 +145,12 @@ where
             return Ok(ExecOutput::done(input.checkpoint()))
         }
 
+        // After snap sync, the hashed state is kept up to date by the execution stage, and can't be
+        // rebuilt from the plain state.
+        if snap_sync_pivot(provider.tx_ref())?.is_some() {
+            return Ok(ExecOutput::done(StageCheckpoint::new(input.target())))
+        }
+
         let (from_block, to_block) = input.next_block_range().into_inner();
 
         // if there are more blocks then threshold it is faster to go over Plain state and hash all
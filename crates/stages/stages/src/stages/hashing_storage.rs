@@ -10,7 +10,9 @@ use reth_db_api::{
 };
 use reth_etl::Collector;
 use reth_primitives::StorageEntry;
-use reth_provider::{DBProvider, HashingWriter, StatsReader, StorageReader};
+use reth_provider::{
+    providers::snap_sync_pivot, DBProvider, HashingWriter, StatsReader, StorageReader,
+};
 use reth_stages_api::{
     EntitiesCheckpoint, ExecInput, ExecOutput, Stage, StageCheckpoint, StageError, StageId,
     StorageHashingCheckpoint, UnwindInput, UnwindOutput,
@@ -78,6 +80,12 @@ where
             return Ok(ExecOutput::done(input.checkpoint()))
         }
 
+        // After snap sync, the hashed state is kept up to date by the execution stage, and can't be
+        // rebuilt from the plain state.
+        if snap_sync_pivot(provider.tx_ref())?.is_some() {
+            return Ok(ExecOutput::done(StageCheckpoint::new(input.target())))
+        }
+
         let (from_block, to_block) = input.next_block_range().into_inner();
 
         // if there are more blocks then threshold it is faster to go over Plain state and hash all
@@ -0,0 +1,154 @@
+use reth_config::config::IndexTransactionAppearancesConfig;
+use reth_db_api::transaction::DbTxMut;
+use reth_provider::{AccountExtReader, DBProvider, HistoryWriter};
+use reth_stages_api::{
+    ExecInput, ExecOutput, Stage, StageCheckpoint, StageError, StageId, UnwindInput, UnwindOutput,
+};
+use tracing::info;
+
+/// Stage is indexing the blocks in which an address appeared as the sender or the recipient of a
+/// transaction. For more information on index sharding take a look at
+/// [`tables::TransactionAppearances`](reth_db::tables::TransactionAppearances).
+///
+/// The index is optional, so the stage is only part of the pipeline if it's enabled by
+/// [`IndexTransactionAppearancesConfig::enabled`]. Once the stage has a checkpoint, the index is
+/// also maintained for the blocks that are persisted outside of the pipeline.
+#[derive(Debug)]
+pub struct IndexTransactionAppearancesStage {
+    /// Number of blocks after which the control
+    /// flow will be returned to the pipeline for commit.
+    pub commit_threshold: u64,
+}
+
+impl IndexTransactionAppearancesStage {
+    /// Create new instance of [`IndexTransactionAppearancesStage`].
+    pub const fn new(config: IndexTransactionAppearancesConfig) -> Self {
+        Self { commit_threshold: config.commit_threshold }
+    }
+}
+
+impl Default for IndexTransactionAppearancesStage {
+    fn default() -> Self {
+        Self { commit_threshold: 100_000 }
+    }
+}
+
+impl<Provider> Stage<Provider> for IndexTransactionAppearancesStage
+where
+    Provider: DBProvider<Tx: DbTxMut> + AccountExtReader + HistoryWriter,
+{
+    /// Return the id of the stage
+    fn id(&self) -> StageId {
+        StageId::IndexTransactionAppearances
+    }
+
+    /// Execute the stage.
+    fn execute(&mut self, provider: &Provider, input: ExecInput) -> Result<ExecOutput, StageError> {
+        if input.target_reached() {
+            return Ok(ExecOutput::done(input.checkpoint()))
+        }
+
+        let (range, is_final_range) = input.next_block_range_with_threshold(self.commit_threshold);
+
+        info!(target: "sync::stages::index_transaction_appearances::exec", ?range, "Collecting transaction appearances");
+        let appearances = provider.transaction_appearances_with_range(range.clone())?;
+
+        info!(target: "sync::stages::index_transaction_appearances::exec", addresses = appearances.len(), "Loading indices into database");
+        provider.insert_transaction_appearance_index(appearances)?;
+
+        Ok(ExecOutput { checkpoint: StageCheckpoint::new(*range.end()), done: is_final_range })
+    }
+
+    /// Unwind the stage.
+    fn unwind(
+        &mut self,
+        provider: &Provider,
+        input: UnwindInput,
+    ) -> Result<UnwindOutput, StageError> {
+        let (range, unwind_progress, _) =
+            input.unwind_block_range_with_threshold(self.commit_threshold);
+
+        provider.unwind_transaction_appearance_indices(range)?;
+
+        Ok(UnwindOutput { checkpoint: StageCheckpoint::new(unwind_progress) })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{StorageKind, TestStageDB};
+    use alloy_primitives::{Address, B256};
+    use reth_db::{tables, BlockNumberList};
+    use reth_db_api::models::ShardedKey;
+    use reth_provider::DatabaseProviderFactory;
+    use reth_testing_utils::generators::{self, random_block_range, BlockRangeParams};
+    use std::collections::BTreeMap;
+
+    fn cast(table: Vec<(ShardedKey<Address>, BlockNumberList)>) -> BTreeMap<Address, Vec<u64>> {
+        table.into_iter().fold(BTreeMap::new(), |mut acc, (key, list)| {
+            acc.entry(key.key).or_insert_with(Vec::new).extend(list.iter());
+            acc
+        })
+    }
+
+    #[tokio::test]
+    async fn execute_and_unwind_transaction_appearances() {
+        let db = TestStageDB::default();
+        let mut rng = generators::rng();
+
+        let blocks = random_block_range(
+            &mut rng,
+            0..=20,
+            BlockRangeParams { parent: Some(B256::ZERO), tx_count: 1..3, ..Default::default() },
+        );
+        db.insert_blocks(blocks.iter(), StorageKind::Static).unwrap();
+
+        // Build the expected index from the inserted blocks. The genesis block is not indexed by
+        // the stage.
+        let mut expected = BTreeMap::<Address, Vec<u64>>::new();
+        for block in blocks.iter().skip(1) {
+            for transaction in &block.body.transactions {
+                let sender = transaction.recover_signer().unwrap();
+                for address in std::iter::once(sender).chain(transaction.to()) {
+                    let entry = expected.entry(address).or_default();
+                    if entry.last() != Some(&block.number) {
+                        entry.push(block.number);
+                    }
+                }
+            }
+        }
+
+        // run
+        let mut stage = IndexTransactionAppearancesStage::default();
+        let provider = db.factory.database_provider_rw().unwrap();
+        let input = ExecInput { target: Some(20), checkpoint: None };
+        let out = stage.execute(&provider, input).unwrap();
+        assert_eq!(out, ExecOutput { checkpoint: StageCheckpoint::new(20), done: true });
+        provider.commit().unwrap();
+
+        let table = cast(db.table::<tables::TransactionAppearances>().unwrap());
+        assert_eq!(table, expected);
+
+        // unwind
+        let provider = db.factory.database_provider_rw().unwrap();
+        let input = UnwindInput {
+            checkpoint: StageCheckpoint::new(20),
+            unwind_to: 10,
+            ..Default::default()
+        };
+        let out = stage.unwind(&provider, input).unwrap();
+        assert_eq!(out, UnwindOutput { checkpoint: StageCheckpoint::new(10) });
+        provider.commit().unwrap();
+
+        let expected = expected
+            .into_iter()
+            .filter_map(|(address, blocks)| {
+                let blocks = blocks.into_iter().filter(|block| *block <= 10).collect::<Vec<_>>();
+                (!blocks.is_empty()).then_some((address, blocks))
+            })
+            .collect::<BTreeMap<_, _>>();
+        let table = cast(db.table::<tables::TransactionAppearances>().unwrap());
+        assert_eq!(table, expected);
+    }
+}
@@ -14,11 +14,15 @@ mod headers;
 mod index_account_history;
 /// Index history of storage changes
 mod index_storage_history;
+/// Index of transaction senders and recipients
+mod index_transaction_appearances;
 /// Stage for computing state root.
 mod merkle;
 mod prune;
 /// The sender recovery stage.
 mod sender_recovery;
+/// The snap sync stage.
+mod snap_sync;
 /// The transaction lookup stage
 mod tx_lookup;
 
@@ -30,9 +34,11 @@ pub use hashing_storage::*;
 pub use headers::*;
 pub use index_account_history::*;
 pub use index_storage_history::*;
+pub use index_transaction_appearances::*;
 pub use merkle::*;
 pub use prune::*;
 pub use sender_recovery::*;
+pub use snap_sync::*;
 pub use tx_lookup::*;
 
 mod utils;
@@ -42,7 +48,9 @@ use utils::*;
 mod tests {
     use super::*;
     use crate::test_utils::{StorageKind, TestStageDB};
-    use alloy_primitives::{address, hex_literal::hex, keccak256, BlockNumber, B256, U256};
+    use alloy_primitives::{
+        address, hex_literal::hex, keccak256, Address, BlockNumber, B256, U256,
+    };
     use alloy_rlp::Decodable;
     use reth_chainspec::ChainSpecBuilder;
     use reth_db::{
@@ -51,12 +59,13 @@ mod tests {
     };
     use reth_db_api::{
         cursor::{DbCursorRO, DbCursorRW},
+        models::AccountBeforeTx,
         table::Table,
         transaction::{DbTx, DbTxMut},
     };
     use reth_evm_ethereum::execute::EthExecutorProvider;
     use reth_exex::ExExManagerHandle;
-    use reth_primitives::{Account, Bytecode, SealedBlock, StaticFileSegment};
+    use reth_primitives::{Account, Bytecode, SealedBlock, StaticFileSegment, StorageEntry};
     use reth_provider::{
         providers::{StaticFileProvider, StaticFileWriter},
         test_utils::MockNodeTypesWithDB,
@@ -505,4 +514,66 @@ mod tests {
         // Fill the gap, and ensure no unwind is necessary.
         update_db_and_check::<tables::Receipts>(&db, current + 1, None);
     }
+
+    #[test]
+    fn test_consistency_changesets() {
+        let db = seed_data(90).unwrap();
+        let static_file_provider = db.factory.static_file_provider();
+        let address = |block: u64| Address::with_last_byte(block as u8);
+
+        // One account and one storage change per block.
+        let mut account_writer =
+            static_file_provider.latest_writer(StaticFileSegment::AccountChangeSets).unwrap();
+        let mut storage_writer =
+            static_file_provider.latest_writer(StaticFileSegment::StorageChangeSets).unwrap();
+        for block in 0..90 {
+            account_writer.increment_block(block).unwrap();
+            account_writer
+                .append_account_changeset(
+                    block,
+                    [AccountBeforeTx { address: address(block), info: None }],
+                )
+                .unwrap();
+            storage_writer.increment_block(block).unwrap();
+            storage_writer
+                .append_storage_changeset(
+                    block,
+                    [(address(block), StorageEntry { key: B256::ZERO, value: U256::from(block) })],
+                )
+                .unwrap();
+        }
+        account_writer.commit().unwrap();
+        storage_writer.commit().unwrap();
+        drop(account_writer);
+        drop(storage_writer);
+
+        // Changesets which are not ahead of the execution checkpoint are left untouched.
+        save_checkpoint_and_check(&db, StageId::Execution, 89, None);
+        for segment in [StaticFileSegment::AccountChangeSets, StaticFileSegment::StorageChangeSets]
+        {
+            assert_eq!(static_file_provider.get_highest_static_file_block(segment), Some(89));
+        }
+
+        // An unwind committed to the database only leaves changesets ahead of the execution
+        // checkpoint, which are pruned without requesting a pipeline unwind.
+        let block = 80;
+        save_checkpoint_and_check(&db, StageId::Execution, block, None);
+        for segment in [StaticFileSegment::AccountChangeSets, StaticFileSegment::StorageChangeSets]
+        {
+            assert_eq!(static_file_provider.get_highest_static_file_block(segment), Some(block));
+            assert_eq!(static_file_provider.get_highest_static_file_tx(segment), Some(block));
+        }
+        assert_eq!(
+            static_file_provider.account_changesets_range(0..=89).unwrap().last(),
+            Some(&(block, AccountBeforeTx { address: address(block), info: None }))
+        );
+        assert_eq!(
+            static_file_provider
+                .storage_changesets_range(0..=89)
+                .unwrap()
+                .last()
+                .map(|(key, entry)| (key.block_number(), entry.value)),
+            Some((block, U256::from(block)))
+        );
+    }
 }
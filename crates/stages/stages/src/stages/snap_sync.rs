@@ -0,0 +1,1197 @@
+use alloy_primitives::{
+    keccak256,
+    map::{HashMap, HashSet},
+    Bytes, B256, U256,
+};
+use alloy_rlp::Decodable;
+use futures_util::Future;
+use reth_db::tables;
+use reth_db_api::{
+    cursor::{DbCursorRO, DbCursorRW, DbDupCursorRO, DbDupCursorRW},
+    transaction::{DbTx, DbTxMut},
+};
+use reth_eth_wire_types::snap::{
+    AccountData, GetAccountRange, GetByteCodes, GetStorageRanges, GetTrieNodes, SlimAccount,
+    SnapMessage,
+};
+use reth_network_p2p::{download::DownloadClient, error::RequestError, snap::client::SnapClient};
+use reth_primitives::{constants::KECCAK_EMPTY, Account, Bytecode, StorageEntry};
+use reth_provider::{
+    providers::snap_sync_pivot, DBProvider, HeaderProvider, ProviderError, StageCheckpointReader,
+    StageCheckpointWriter, TrieWriter,
+};
+use reth_stages_api::{
+    ExecInput, ExecOutput, Stage, StageCheckpoint, StageError, StageId, UnwindInput, UnwindOutput,
+};
+use reth_storage_errors::db::DatabaseError;
+use reth_trie::{
+    proof::Proof, range_proof::verify_range_proof, Nibbles, StateRoot, StorageRoot, TrieAccount,
+    TrieNode, EMPTY_ROOT_HASH,
+};
+use reth_trie_db::{
+    DatabaseHashedCursorFactory, DatabaseStateRoot, DatabaseStorageRoot, DatabaseTrieCursorFactory,
+};
+use std::{
+    collections::VecDeque,
+    fmt,
+    ops::RangeInclusive,
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+use tracing::*;
+
+/// The default distance of the pivot block from the target of the pipeline.
+///
+/// Peers only keep the state of the most recent blocks, the pivot must be close to the tip.
+pub const SNAP_SYNC_DEFAULT_PIVOT_DISTANCE: u64 = 64;
+
+/// The soft limit of the size of a response that is requested from peers.
+const RESPONSE_BYTES: u64 = 512 * 1024;
+
+/// The time after which a request is considered failed.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The maximum number of attempts for a single request, including attempts that failed because
+/// no peer was available.
+const MAX_REQUEST_ATTEMPTS: usize = 64;
+
+/// The number of empty responses after which the state of the pivot is considered unavailable.
+const MAX_EMPTY_RESPONSES: usize = 16;
+
+/// The maximum number of bytecodes requested at once.
+const MAX_BYTECODES_PER_REQUEST: usize = 64;
+
+/// The maximum number of accounts whose storage is requested at once.
+const MAX_STORAGES_PER_REQUEST: usize = 128;
+
+/// The number of storage slots after which a download is written to the database.
+///
+/// The remaining storage is downloaded by the next download, so that a download never holds large
+/// storages in memory.
+const MAX_STORAGE_SLOTS_PER_DOWNLOAD: usize = 64 * 1024;
+
+/// The size of bytecodes after which a download is written to the database.
+const MAX_BYTECODE_BYTES_PER_DOWNLOAD: usize = 16 * 1024 * 1024;
+
+/// The maximum number of trie nodes requested in a single batch.
+const MAX_TRIE_NODES_PER_BATCH: usize = 256;
+
+/// The snap sync stage downloads the state of a recent block from peers that support `snap/1`,
+/// instead of executing all blocks since genesis.
+///
+/// The stage only runs on an empty node, i.e. if no block has been executed yet. It picks a pivot
+/// block [`SNAP_SYNC_DEFAULT_PIVOT_DISTANCE`] blocks below the target of the pipeline and:
+///
+/// 1. downloads all accounts, their storage and bytecode with verified range proofs,
+/// 2. builds the trie and compares its root with the state root of the pivot,
+/// 3. if the roots differ, because the state was downloaded over several pivots, heals the account
+///    trie by walking it top-down and downloading the nodes that differ, and re-downloads the
+///    storage of accounts with a different storage root.
+///
+/// Once the state matches the pivot, the checkpoints of the execution, hashing and merkle stages
+/// are set to the pivot, and the pipeline continues by executing the blocks after it.
+///
+/// If the peers no longer serve the state of the pivot, the stage finishes the pipeline run
+/// without progress and continues with a new pivot in the next run. The downloaded state is kept.
+///
+/// # Limitations
+///
+/// - The downloaded state is only written to the hashed state tables. The plain state tables only
+///   hold the accounts and storage changed after the pivot, state providers read from the hashed
+///   state instead (see [`snap_sync_pivot`]).
+/// - There is no state history and there are no receipts before the pivot. The chain can't be
+///   unwound below the pivot.
+///
+/// # Tables
+///
+/// - [`HashedAccounts`][reth_db::tables::HashedAccounts]
+/// - [`HashedStorages`][reth_db::tables::HashedStorages]
+/// - [`Bytecodes`][reth_db::tables::Bytecodes]
+/// - [`AccountsTrie`][reth_db::tables::AccountsTrie]
+/// - [`StoragesTrie`][reth_db::tables::StoragesTrie]
+pub struct SnapSyncStage<C> {
+    /// The client to download state with.
+    client: C,
+    /// The distance of the pivot block from the target.
+    pivot_distance: u64,
+    /// The state root of the pivot block the state is currently synced to.
+    root: B256,
+    /// The current phase, if the stage was started.
+    phase: Option<SnapSyncPhase>,
+    /// Account trie paths and the expected hashes of the nodes at those paths, to be healed.
+    heal_paths: Vec<(Nibbles, B256)>,
+    /// The storages that need to be downloaded.
+    pending_storages: Vec<PendingStorage>,
+    /// Hashes of bytecodes that need to be downloaded.
+    pending_bytecodes: Vec<B256>,
+    /// The requests that are sent once the stage is polled.
+    requests: Option<SnapRequests>,
+    /// The download in progress.
+    download: Option<SnapDownload>,
+    /// The result of the last download, waiting to be written.
+    buffer: Option<Result<SnapResponses, SnapSyncError>>,
+}
+
+type SnapDownload =
+    Pin<Box<dyn Future<Output = Result<SnapResponses, SnapSyncError>> + Send + Sync>>;
+
+impl<C> SnapSyncStage<C> {
+    /// Creates a new snap sync stage that downloads state with the given client.
+    pub const fn new(client: C) -> Self {
+        Self {
+            client,
+            pivot_distance: SNAP_SYNC_DEFAULT_PIVOT_DISTANCE,
+            root: B256::ZERO,
+            phase: None,
+            heal_paths: Vec::new(),
+            pending_storages: Vec::new(),
+            pending_bytecodes: Vec::new(),
+            requests: None,
+            download: None,
+            buffer: None,
+        }
+    }
+
+    /// Sets the distance of the pivot block from the target of the pipeline.
+    pub const fn with_pivot_distance(mut self, pivot_distance: u64) -> Self {
+        self.pivot_distance = pivot_distance;
+        self
+    }
+}
+
+impl<C> fmt::Debug for SnapSyncStage<C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SnapSyncStage")
+            .field("pivot_distance", &self.pivot_distance)
+            .field("root", &self.root)
+            .field("phase", &self.phase)
+            .field("heal_paths", &self.heal_paths.len())
+            .field("pending_storages", &self.pending_storages.len())
+            .field("pending_bytecodes", &self.pending_bytecodes.len())
+            .field("downloading", &self.download.is_some())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<Provider, C> Stage<Provider> for SnapSyncStage<C>
+where
+    Provider: DBProvider<Tx: DbTxMut>
+        + HeaderProvider
+        + StageCheckpointReader
+        + StageCheckpointWriter
+        + TrieWriter,
+    C: SnapClient + Clone + Send + Sync + 'static,
+{
+    fn id(&self) -> StageId {
+        StageId::SnapSync
+    }
+
+    fn poll_execute_ready(
+        &mut self,
+        cx: &mut Context<'_>,
+        _input: ExecInput,
+    ) -> Poll<Result<(), StageError>> {
+        if self.buffer.is_some() {
+            return Poll::Ready(Ok(()))
+        }
+
+        if let Some(requests) = self.requests.take() {
+            self.download = Some(Box::pin(download(self.client.clone(), requests)));
+        }
+        let Some(download) = self.download.as_mut() else { return Poll::Ready(Ok(())) };
+        let result = std::task::ready!(download.as_mut().poll(cx));
+        self.download = None;
+        self.buffer = Some(result);
+        Poll::Ready(Ok(()))
+    }
+
+    fn execute(&mut self, provider: &Provider, input: ExecInput) -> Result<ExecOutput, StageError> {
+        if input.target_reached() || snap_sync_pivot(provider.tx_ref())?.is_some() {
+            return Ok(ExecOutput::done(StageCheckpoint::new(input.target())))
+        }
+
+        let pivot = input.target().saturating_sub(self.pivot_distance);
+        if self.phase.is_none() {
+            let progress = provider.get_stage_checkpoint_progress(StageId::SnapSync)?;
+            let executed =
+                provider.get_stage_checkpoint(StageId::Execution)?.unwrap_or_default().block_number;
+
+            // snap sync only starts on an empty node, and the chain must be long enough to have a
+            // pivot
+            if progress.is_none() && (executed > 0 || pivot == 0) {
+                return Ok(ExecOutput::done(StageCheckpoint::new(input.target())))
+            }
+            self.phase = Some(SnapSyncPhase::from_progress(progress.as_deref()));
+        }
+
+        let root = provider
+            .header_by_number(pivot)?
+            .ok_or_else(|| ProviderError::HeaderNotFound(pivot.into()))?
+            .state_root;
+        if root != self.root {
+            debug!(target: "sync::stages::snap_sync", pivot, ?root, "Syncing to new pivot");
+            self.root = root;
+            // the expected node hashes of the trie that is healed no longer apply
+            if self.phase == Some(SnapSyncPhase::Heal) {
+                self.phase = Some(SnapSyncPhase::Rebuild);
+            }
+            self.heal_paths.clear();
+            // the storage roots of the accounts are found again by the rebuild
+            self.pending_storages.clear();
+        }
+
+        if let Some(result) = self.buffer.take() {
+            match result {
+                Ok(responses) => self.write_responses(provider, responses)?,
+                Err(SnapSyncError::Unavailable) => {
+                    info!(target: "sync::stages::snap_sync", pivot, "State of the pivot is no longer served, continuing in the next pipeline run");
+                    return Ok(ExecOutput::done(input.checkpoint()))
+                }
+                Err(SnapSyncError::Request(err)) => {
+                    return Err(StageError::Recoverable(Box::new(err)))
+                }
+            }
+        }
+
+        loop {
+            match self.phase.unwrap_or_default() {
+                SnapSyncPhase::Accounts(start) => {
+                    // the storage and bytecodes of the previous account ranges are downloaded
+                    // first, which bounds the size of a download
+                    let pending = self.pending_requests();
+                    self.requests =
+                        Some(if pending.storages.is_empty() && pending.bytecodes.is_empty() {
+                            SnapRequests { accounts_from: Some(start), ..pending }
+                        } else {
+                            pending
+                        });
+                    return Ok(ExecOutput { checkpoint: input.checkpoint(), done: false })
+                }
+                SnapSyncPhase::Rebuild => {
+                    let pending = self.pending_requests();
+                    if !pending.storages.is_empty() || !pending.bytecodes.is_empty() {
+                        self.requests = Some(pending);
+                        return Ok(ExecOutput { checkpoint: input.checkpoint(), done: false })
+                    }
+
+                    debug!(target: "sync::stages::snap_sync", pivot, "Building state trie");
+                    let tx = provider.tx_ref();
+                    tx.clear::<tables::AccountsTrie>()?;
+                    tx.clear::<tables::StoragesTrie>()?;
+                    let (trie_root, updates) = StateRoot::from_tx(tx)
+                        .root_with_updates()
+                        .map_err(|err| StageError::Fatal(Box::new(err)))?;
+                    provider.write_trie_updates(&updates)?;
+
+                    if trie_root == root {
+                        info!(target: "sync::stages::snap_sync", pivot, ?root, "Snap sync finished");
+                        provider.save_stage_checkpoint_progress(
+                            StageId::SnapSync,
+                            pivot.to_be_bytes().to_vec(),
+                        )?;
+                        for stage_id in [
+                            StageId::Execution,
+                            StageId::AccountHashing,
+                            StageId::StorageHashing,
+                            StageId::MerkleExecute,
+                        ] {
+                            provider
+                                .save_stage_checkpoint(stage_id, StageCheckpoint::new(pivot))?;
+                        }
+                        self.phase = None;
+                        return Ok(ExecOutput::done(StageCheckpoint::new(input.target())))
+                    }
+
+                    debug!(target: "sync::stages::snap_sync", pivot, ?trie_root, ?root, "Healing state trie");
+                    self.set_phase(provider, SnapSyncPhase::Heal)?;
+                    self.heal_paths = vec![(Nibbles::default(), root)];
+                }
+                SnapSyncPhase::Heal => {
+                    let requests = self.heal_requests(provider)?;
+                    if requests.trie_nodes.is_empty() &&
+                        requests.storages.is_empty() &&
+                        requests.bytecodes.is_empty()
+                    {
+                        self.set_phase(provider, SnapSyncPhase::Rebuild)?;
+                        continue
+                    }
+                    self.requests = Some(requests);
+                    return Ok(ExecOutput { checkpoint: input.checkpoint(), done: false })
+                }
+            }
+        }
+    }
+
+    fn unwind(
+        &mut self,
+        provider: &Provider,
+        input: UnwindInput,
+    ) -> Result<UnwindOutput, StageError> {
+        self.phase = None;
+        self.requests = None;
+        self.download = None;
+        self.buffer = None;
+        self.pending_storages.clear();
+        self.pending_bytecodes.clear();
+
+        if let Some(pivot) = snap_sync_pivot(provider.tx_ref())? {
+            if input.unwind_to < pivot {
+                return Err(StageError::Fatal(
+                    format!(
+                        "can't unwind to block {} below the snap sync pivot {pivot}",
+                        input.unwind_to
+                    )
+                    .into(),
+                ))
+            }
+        }
+        Ok(UnwindOutput { checkpoint: StageCheckpoint::new(input.unwind_to) })
+    }
+}
+
+impl<C> SnapSyncStage<C> {
+    fn set_phase<Provider: StageCheckpointWriter>(
+        &mut self,
+        provider: &Provider,
+        phase: SnapSyncPhase,
+    ) -> Result<(), StageError> {
+        provider.save_stage_checkpoint_progress(StageId::SnapSync, phase.progress())?;
+        self.phase = Some(phase);
+        Ok(())
+    }
+
+    /// Returns the requests for the pending storages and bytecodes.
+    fn pending_requests(&self) -> SnapRequests {
+        SnapRequests {
+            root: self.root,
+            storages: self.pending_storages.clone(),
+            bytecodes: self.pending_bytecodes.clone(),
+            ..Default::default()
+        }
+    }
+
+    /// Writes the downloaded state.
+    fn write_responses<Provider>(
+        &mut self,
+        provider: &Provider,
+        responses: SnapResponses,
+    ) -> Result<(), StageError>
+    where
+        Provider: DBProvider<Tx: DbTxMut> + StageCheckpointWriter,
+    {
+        let tx = provider.tx_ref();
+
+        // responses of an older pivot are still valid state, which is healed later, but the trie
+        // nodes no longer match the paths that are healed
+        let trie_nodes =
+            if responses.root == self.root { responses.trie_nodes } else { Vec::new() };
+
+        for (hashed_address, account) in &responses.accounts {
+            tx.put::<tables::HashedAccounts>(*hashed_address, *account)?;
+        }
+        let mut storage_cursor = tx.cursor_dup_write::<tables::HashedStorages>()?;
+        for chunk in responses.storages {
+            // the first chunk of a storage replaces the storage that was downloaded before
+            if chunk.start == B256::ZERO &&
+                storage_cursor.seek_exact(chunk.hashed_address)?.is_some()
+            {
+                storage_cursor.delete_current_duplicates()?;
+            }
+            for (key, value) in chunk.slots {
+                storage_cursor.append_dup(chunk.hashed_address, StorageEntry { key, value })?;
+            }
+        }
+        for (hash, code) in responses.bytecodes {
+            tx.put::<tables::Bytecodes>(hash, Bytecode::new_raw(code))?;
+        }
+        // the remaining storages of an older pivot are found again by the rebuild
+        if responses.root == self.root {
+            self.pending_storages = responses.pending_storages;
+        }
+        self.pending_bytecodes = responses.pending_bytecodes;
+
+        for (path, node) in trie_nodes {
+            self.heal_node(tx, path, &node)?;
+        }
+
+        if let (Some(SnapSyncPhase::Accounts(_)), true) = (self.phase, responses.accounts_requested)
+        {
+            let phase =
+                responses.next_account.map_or(SnapSyncPhase::Rebuild, SnapSyncPhase::Accounts);
+            self.set_phase(provider, phase)?;
+        }
+
+        Ok(())
+    }
+
+    /// Compares the node of the remote account trie at the given path with the local state and
+    /// applies the differences.
+    fn heal_node<TX: DbTxMut + DbTx>(
+        &mut self,
+        tx: &TX,
+        path: Nibbles,
+        node: &Bytes,
+    ) -> Result<(), StageError> {
+        let node =
+            TrieNode::decode(&mut &node[..]).map_err(|err| StageError::Fatal(Box::new(err)))?;
+        match node {
+            TrieNode::EmptyRoot => delete_hashed_accounts(tx, subtrie_range(&path), None)?,
+            TrieNode::Branch(branch) => {
+                let mut children = branch.stack.into_iter();
+                for nibble in 0..16u8 {
+                    let mut child_path = path.clone();
+                    child_path.push(nibble);
+                    if !branch.state_mask.is_bit_set(nibble) {
+                        delete_hashed_accounts(tx, subtrie_range(&child_path), None)?;
+                        continue
+                    }
+                    let child = children.next().ok_or_else(|| invalid_node("invalid branch"))?;
+                    let hash = child.as_hash().ok_or_else(|| invalid_node("embedded node"))?;
+                    self.heal_paths.push((child_path, hash));
+                }
+            }
+            TrieNode::Extension(extension) => {
+                let mut child_path = path.clone();
+                child_path.extend_from_slice(&extension.key);
+                let (start, end) = subtrie_range(&path).into_inner();
+                let (child_start, child_end) = subtrie_range(&child_path).into_inner();
+                if child_start > start {
+                    delete_hashed_accounts(tx, start..=decrement(child_start), None)?;
+                }
+                if child_end < end {
+                    delete_hashed_accounts(tx, increment(child_end)..=end, None)?;
+                }
+                let hash =
+                    extension.child.as_hash().ok_or_else(|| invalid_node("embedded node"))?;
+                self.heal_paths.push((child_path, hash));
+            }
+            TrieNode::Leaf(leaf) => {
+                let mut key = path.clone();
+                key.extend_from_slice(&leaf.key);
+                if key.len() != 64 {
+                    return Err(invalid_node("invalid leaf key"))
+                }
+                let hashed_address = B256::from_slice(&key.pack());
+                delete_hashed_accounts(tx, subtrie_range(&path), Some(hashed_address))?;
+
+                let account = TrieAccount::decode(&mut &leaf.value[..])
+                    .map_err(|err| StageError::Fatal(Box::new(err)))?;
+                tx.put::<tables::HashedAccounts>(hashed_address, to_account(&account))?;
+
+                let storage_root = StorageRoot::from_tx_hashed(tx, hashed_address)
+                    .root()
+                    .map_err(|err| StageError::Fatal(Box::new(err)))?;
+                if storage_root != account.storage_root {
+                    if account.storage_root == EMPTY_ROOT_HASH {
+                        let mut cursor = tx.cursor_dup_write::<tables::HashedStorages>()?;
+                        if cursor.seek_exact(hashed_address)?.is_some() {
+                            cursor.delete_current_duplicates()?;
+                        }
+                    } else {
+                        self.pending_storages.push(PendingStorage {
+                            hashed_address,
+                            storage_root: account.storage_root,
+                            start: B256::ZERO,
+                        });
+                    }
+                }
+                if account.code_hash != KECCAK_EMPTY &&
+                    tx.get::<tables::Bytecodes>(account.code_hash)?.is_none()
+                {
+                    self.pending_bytecodes.push(account.code_hash);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the requests for the next batch of healing.
+    ///
+    /// Paths at which the local trie already has the expected node are skipped.
+    fn heal_requests<Provider: DBProvider>(
+        &mut self,
+        provider: &Provider,
+    ) -> Result<SnapRequests, StageError> {
+        let tx = provider.tx_ref();
+        let mut requests = self.pending_requests();
+        while requests.trie_nodes.len() < MAX_TRIE_NODES_PER_BATCH {
+            let Some((path, hash)) = self.heal_paths.pop() else { break };
+            if local_node_hash(tx, &path)? != Some(hash) {
+                requests.trie_nodes.push((path, hash));
+            }
+        }
+        Ok(requests)
+    }
+}
+
+/// The phase of snap sync.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum SnapSyncPhase {
+    /// Downloading the accounts, starting at the given hashed address.
+    Accounts(B256),
+    /// Building the trie of the downloaded state.
+    Rebuild,
+    /// Healing the trie.
+    Heal,
+}
+
+impl Default for SnapSyncPhase {
+    fn default() -> Self {
+        Self::Accounts(B256::ZERO)
+    }
+}
+
+impl SnapSyncPhase {
+    /// Restores the phase from the stage progress.
+    ///
+    /// The progress of healing is not persisted, after a restart the trie is rebuilt and healing
+    /// starts again at the root.
+    fn from_progress(progress: Option<&[u8]>) -> Self {
+        match progress {
+            Some(start) if start.len() == 32 => Self::Accounts(B256::from_slice(start)),
+            Some([]) => Self::Rebuild,
+            _ => Self::default(),
+        }
+    }
+
+    /// Returns the stage progress of the phase.
+    ///
+    /// The progress of a finished snap sync is the pivot block, see [`snap_sync_pivot`].
+    fn progress(&self) -> Vec<u8> {
+        match self {
+            Self::Accounts(start) => start.to_vec(),
+            Self::Rebuild | Self::Heal => Vec::new(),
+        }
+    }
+}
+
+/// The storage of an account that needs to be downloaded.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct PendingStorage {
+    /// The hashed address of the account.
+    hashed_address: B256,
+    /// The storage root the downloaded storage is verified against.
+    storage_root: B256,
+    /// The hashed slot the download continues at, zero if nothing was downloaded yet.
+    start: B256,
+}
+
+/// The requests of a single download.
+#[derive(Debug, Default)]
+struct SnapRequests {
+    /// The state root to download from.
+    root: B256,
+    /// The start of the account range to download.
+    accounts_from: Option<B256>,
+    /// The storages to download.
+    storages: Vec<PendingStorage>,
+    /// Hashes of the bytecodes to download.
+    bytecodes: Vec<B256>,
+    /// Paths and hashes of the account trie nodes to download.
+    trie_nodes: Vec<(Nibbles, B256)>,
+}
+
+/// The verified responses of a single download.
+#[derive(Debug, Default)]
+struct SnapResponses {
+    /// The state root the responses belong to.
+    root: B256,
+    /// Whether an account range was downloaded.
+    accounts_requested: bool,
+    /// The downloaded accounts.
+    accounts: Vec<(B256, Account)>,
+    /// The start of the next account range, if the downloaded range was not the last one.
+    next_account: Option<B256>,
+    /// The downloaded storage slots.
+    storages: Vec<StorageChunk>,
+    /// The storages that were not downloaded completely.
+    pending_storages: Vec<PendingStorage>,
+    /// The downloaded bytecodes.
+    bytecodes: Vec<(B256, Bytes)>,
+    /// The hashes of the bytecodes that were not downloaded.
+    pending_bytecodes: Vec<B256>,
+    /// The downloaded account trie nodes and their paths.
+    trie_nodes: Vec<(Nibbles, Bytes)>,
+}
+
+/// A consecutive range of the storage slots of an account.
+#[derive(Debug)]
+struct StorageChunk {
+    /// The hashed address of the account.
+    hashed_address: B256,
+    /// The hashed slot the range starts at, zero for the beginning of the storage.
+    start: B256,
+    /// The storage slots and their values.
+    slots: Vec<(B256, U256)>,
+}
+
+/// Errors of a download.
+#[derive(Debug, thiserror::Error)]
+enum SnapSyncError {
+    /// Peers no longer serve the state of the pivot.
+    #[error("state of the pivot is unavailable")]
+    Unavailable,
+    /// Requests kept failing.
+    #[error(transparent)]
+    Request(#[from] RequestError),
+}
+
+/// Downloads and verifies the requested state.
+///
+/// Storages and bytecodes are downloaded until [`MAX_STORAGE_SLOTS_PER_DOWNLOAD`] and
+/// [`MAX_BYTECODE_BYTES_PER_DOWNLOAD`] are reached, the rest is returned as pending and
+/// downloaded after the responses were written.
+async fn download<C: SnapClient>(
+    client: C,
+    requests: SnapRequests,
+) -> Result<SnapResponses, SnapSyncError> {
+    let SnapRequests { root, accounts_from, storages, mut bytecodes, trie_nodes } = requests;
+    let mut responses =
+        SnapResponses { root, accounts_requested: accounts_from.is_some(), ..Default::default() };
+    let mut storages = VecDeque::from(storages);
+
+    if let Some(start) = accounts_from {
+        let request = SnapMessage::GetAccountRange(GetAccountRange {
+            request_id: 0,
+            root_hash: root,
+            starting_hash: start,
+            limit_hash: B256::repeat_byte(0xff),
+            response_bytes: RESPONSE_BYTES,
+        });
+        let (accounts, more) = fetch(&client, request, |response| {
+            let SnapMessage::AccountRange(range) = response else { return Err(()) };
+            if range.accounts.is_empty() && range.proof.is_empty() {
+                return Ok(None)
+            }
+            let leaves = range
+                .accounts
+                .iter()
+                .map(|data| (data.hash, alloy_rlp::encode(to_trie_account(&data.account))))
+                .collect::<Vec<_>>();
+            let more = verify_range_proof(root, start, &leaves, &range.proof).map_err(|_| ())?;
+            Ok(Some((range.accounts, more)))
+        })
+        .await?;
+
+        responses.next_account =
+            if more { accounts.last().and_then(|data| increment_checked(data.hash)) } else { None };
+        for AccountData { hash, account } in accounts {
+            if account.storage_root != EMPTY_ROOT_HASH {
+                storages.push_back(PendingStorage {
+                    hashed_address: hash,
+                    storage_root: account.storage_root,
+                    start: B256::ZERO,
+                });
+            }
+            if account.code_hash != KECCAK_EMPTY {
+                bytecodes.push(account.code_hash);
+            }
+            responses.accounts.push((hash, to_account(&to_trie_account(&account))));
+        }
+        bytecodes.sort_unstable();
+        bytecodes.dedup();
+    }
+
+    let mut slots = 0;
+    while slots < MAX_STORAGE_SLOTS_PER_DOWNLOAD {
+        // storages are requested in batches, a storage that is continued is requested on its own
+        let batch = match storages.front() {
+            None => break,
+            Some(storage) if storage.start != B256::ZERO => vec![*storage],
+            Some(_) => storages
+                .iter()
+                .take(MAX_STORAGES_PER_REQUEST)
+                .take_while(|storage| storage.start == B256::ZERO)
+                .copied()
+                .collect(),
+        };
+        let ranges = fetch_storages(&client, root, &batch).await?;
+        storages.drain(..ranges.len());
+        for (storage, (range, next)) in batch.into_iter().zip(ranges) {
+            slots += range.len();
+            responses.storages.push(StorageChunk {
+                hashed_address: storage.hashed_address,
+                start: storage.start,
+                slots: range,
+            });
+            if let Some(next) = next {
+                storages.push_front(PendingStorage { start: next, ..storage });
+            }
+        }
+    }
+    responses.pending_storages = storages.into();
+
+    let mut size = 0;
+    while !bytecodes.is_empty() && size < MAX_BYTECODE_BYTES_PER_DOWNLOAD {
+        let hashes = bytecodes.iter().take(MAX_BYTECODES_PER_REQUEST).copied().collect::<Vec<_>>();
+        let request = SnapMessage::GetByteCodes(GetByteCodes {
+            request_id: 0,
+            hashes: hashes.clone(),
+            response_bytes: RESPONSE_BYTES,
+        });
+        let codes = fetch(&client, request, |response| {
+            let SnapMessage::ByteCodes(response) = response else { return Err(()) };
+            if response.codes.is_empty() {
+                return Ok(None)
+            }
+            response
+                .codes
+                .into_iter()
+                .map(|code| {
+                    let hash = keccak256(&code);
+                    hashes.contains(&hash).then_some((hash, code)).ok_or(())
+                })
+                .collect::<Result<Vec<_>, _>>()
+                .map(Some)
+        })
+        .await?;
+        bytecodes.retain(|hash| !codes.iter().any(|(code_hash, _)| code_hash == hash));
+        size += codes.iter().map(|(_, code)| code.len()).sum::<usize>();
+        responses.bytecodes.extend(codes);
+    }
+    responses.pending_bytecodes = bytecodes;
+
+    let mut trie_nodes = trie_nodes.as_slice();
+    while !trie_nodes.is_empty() {
+        let request = SnapMessage::GetTrieNodes(GetTrieNodes {
+            request_id: 0,
+            root_hash: root,
+            paths: trie_nodes
+                .iter()
+                .map(|(path, _)| vec![Bytes::from(path.encode_path_leaf(false).to_vec())])
+                .collect(),
+            response_bytes: RESPONSE_BYTES,
+        });
+        let nodes = fetch(&client, request, |response| {
+            let SnapMessage::TrieNodes(response) = response else { return Err(()) };
+            if response.nodes.is_empty() {
+                return Ok(None)
+            }
+            if response.nodes.len() > trie_nodes.len() ||
+                response
+                    .nodes
+                    .iter()
+                    .zip(trie_nodes)
+                    .any(|(node, (_, hash))| keccak256(node) != *hash)
+            {
+                return Err(())
+            }
+            Ok(Some(response.nodes))
+        })
+        .await?;
+        let (fetched, remaining) = trie_nodes.split_at(nodes.len());
+        responses.trie_nodes.extend(fetched.iter().map(|(path, _)| path.clone()).zip(nodes));
+        trie_nodes = remaining;
+    }
+
+    Ok(responses)
+}
+
+/// Downloads the storage of the given accounts with a single request.
+///
+/// The range of the first account starts at its `start`, the ranges of the others at the
+/// beginning of their storage. Returns the slots of the accounts that were served, in order, and
+/// the hashed slot at which the last one continues if it was not served completely.
+async fn fetch_storages<C: SnapClient>(
+    client: &C,
+    root: B256,
+    storages: &[PendingStorage],
+) -> Result<Vec<(Vec<(B256, U256)>, Option<B256>)>, SnapSyncError> {
+    let start = storages[0].start;
+    let request = SnapMessage::GetStorageRanges(GetStorageRanges {
+        request_id: 0,
+        root_hash: root,
+        account_hashes: storages.iter().map(|storage| storage.hashed_address).collect(),
+        starting_hash: start,
+        limit_hash: B256::repeat_byte(0xff),
+        response_bytes: RESPONSE_BYTES,
+    });
+    fetch(client, request, |response| {
+        let SnapMessage::StorageRanges(response) = response else { return Err(()) };
+        if response.slots.iter().all(Vec::is_empty) && response.proof.is_empty() {
+            return Ok(None)
+        }
+        if response.slots.is_empty() || response.slots.len() > storages.len() {
+            return Err(())
+        }
+
+        // only the last range can be incomplete and have a proof
+        let last = response.slots.len() - 1;
+        response
+            .slots
+            .into_iter()
+            .zip(storages)
+            .enumerate()
+            .map(|(index, (slots, storage))| {
+                let leaves =
+                    slots.iter().map(|slot| (slot.hash, slot.data.to_vec())).collect::<Vec<_>>();
+                let origin = if index == 0 { start } else { B256::ZERO };
+                let proof = if index == last { response.proof.as_slice() } else { &[] };
+                let more = verify_range_proof(storage.storage_root, origin, &leaves, proof)
+                    .map_err(|_| ())?;
+                let slots = slots
+                    .into_iter()
+                    .map(|slot| Ok((slot.hash, U256::decode(&mut &slot.data[..]).map_err(|_| ())?)))
+                    .collect::<Result<Vec<_>, ()>>()?;
+                let next = if more {
+                    slots.last().and_then(|(hash, _)| increment_checked(*hash))
+                } else {
+                    None
+                };
+                Ok((slots, next))
+            })
+            .collect::<Result<Vec<_>, ()>>()
+            .map(Some)
+    })
+    .await
+}
+
+/// Sends the request to peers until one of them responds with a valid response.
+///
+/// The closure verifies the response and returns `None` if the response is empty, which means
+/// that the peer doesn't have the requested state, and an error if it is invalid.
+async fn fetch<C, T>(
+    client: &C,
+    request: SnapMessage,
+    mut verify: impl FnMut(SnapMessage) -> Result<Option<T>, ()>,
+) -> Result<T, SnapSyncError>
+where
+    C: SnapClient,
+{
+    let mut empty_responses = 0;
+    let mut last_error = RequestError::UnsupportedCapability;
+    for _ in 0..MAX_REQUEST_ATTEMPTS {
+        let response = match tokio::time::timeout(
+            REQUEST_TIMEOUT,
+            client.get_snap_data(request.clone()),
+        )
+        .await
+        {
+            Ok(Ok(response)) => response,
+            Ok(Err(err)) => {
+                trace!(target: "sync::stages::snap_sync", %err, "Snap request failed");
+                if err == RequestError::UnsupportedCapability {
+                    // no peer supports snap, wait for one to connect
+                    tokio::time::sleep(Duration::from_secs(1)).await;
+                }
+                last_error = err;
+                continue
+            }
+            Err(_) => {
+                last_error = RequestError::Timeout;
+                continue
+            }
+        };
+
+        let (peer_id, response) = response.split();
+        match verify(response) {
+            Ok(Some(response)) => return Ok(response),
+            Ok(None) => {
+                empty_responses += 1;
+                if empty_responses >= MAX_EMPTY_RESPONSES {
+                    return Err(SnapSyncError::Unavailable)
+                }
+            }
+            Err(()) => {
+                debug!(target: "sync::stages::snap_sync", ?peer_id, "Invalid snap response");
+                client.report_bad_message(peer_id);
+            }
+        }
+    }
+    Err(last_error.into())
+}
+
+/// Returns the hash of the node of the local account trie at the given path, if there is one.
+fn local_node_hash<TX: DbTx>(tx: &TX, path: &Nibbles) -> Result<Option<B256>, StageError> {
+    let target = *subtrie_range(path).start();
+    let multiproof =
+        Proof::new(DatabaseTrieCursorFactory::new(tx), DatabaseHashedCursorFactory::new(tx))
+            .multiproof(HashMap::from_iter([(target, HashSet::default())]))
+            .map_err(ProviderError::from)?;
+    Ok(multiproof
+        .account_subtree
+        .matching_nodes_iter(&Nibbles::unpack(target))
+        .find(|(node_path, _)| *node_path == path)
+        .map(|(_, node)| keccak256(node)))
+}
+
+/// Deletes the hashed accounts in the given range and their storage, except for `keep`.
+fn delete_hashed_accounts<TX: DbTxMut + DbTx>(
+    tx: &TX,
+    range: RangeInclusive<B256>,
+    keep: Option<B256>,
+) -> Result<(), DatabaseError> {
+    let mut accounts = tx.cursor_write::<tables::HashedAccounts>()?;
+    let mut storages = tx.cursor_dup_write::<tables::HashedStorages>()?;
+    let mut walker = accounts.walk_range(range)?;
+    while let Some((hashed_address, _)) = walker.next().transpose()? {
+        if Some(hashed_address) == keep {
+            continue
+        }
+        walker.delete_current()?;
+        if storages.seek_exact(hashed_address)?.is_some() {
+            storages.delete_current_duplicates()?;
+        }
+    }
+    Ok(())
+}
+
+/// Returns the range of keys in the subtrie at the given path.
+fn subtrie_range(path: &Nibbles) -> RangeInclusive<B256> {
+    let padded = |nibble| {
+        let mut nibbles = path.to_vec();
+        nibbles.resize(64, nibble);
+        B256::from_slice(&Nibbles::from_nibbles_unchecked(nibbles).pack())
+    };
+    padded(0)..=padded(0xf)
+}
+
+fn increment_checked(key: B256) -> Option<B256> {
+    U256::from_be_bytes(key.0).checked_add(U256::from(1)).map(|key| B256::from(key.to_be_bytes()))
+}
+
+fn increment(key: B256) -> B256 {
+    B256::from(U256::from_be_bytes(key.0).saturating_add(U256::from(1)).to_be_bytes())
+}
+
+fn decrement(key: B256) -> B256 {
+    B256::from(U256::from_be_bytes(key.0).saturating_sub(U256::from(1)).to_be_bytes())
+}
+
+fn invalid_node(reason: &'static str) -> StageError {
+    StageError::Fatal(Box::new(alloy_rlp::Error::Custom(reason)))
+}
+
+const fn to_trie_account(account: &SlimAccount) -> TrieAccount {
+    TrieAccount {
+        nonce: account.nonce,
+        balance: account.balance,
+        storage_root: account.storage_root,
+        code_hash: account.code_hash,
+    }
+}
+
+fn to_account(account: &TrieAccount) -> Account {
+    Account {
+        nonce: account.nonce,
+        balance: account.balance,
+        bytecode_hash: (account.code_hash != KECCAK_EMPTY).then_some(account.code_hash),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::{b256, Address};
+    use futures_util::future::{poll_fn, ready, Ready};
+    use reth_db_api::models::{AccountBeforeTx, BlockNumberAddress};
+    use reth_net_snap::SnapRequestHandler;
+    use reth_network_p2p::error::PeerRequestResult;
+    use reth_network_peers::{PeerId, WithPeerId};
+    use reth_primitives::{Header, StaticFileSegment};
+    use reth_provider::{
+        providers::StaticFileProvider,
+        test_utils::{create_test_provider_factory, MockNodeTypesWithDB},
+        ProviderFactory, StaticFileProviderFactory, StaticFileWriter,
+    };
+
+    /// A snap client that answers requests with a [`SnapRequestHandler`] of another node.
+    #[derive(Clone, Debug)]
+    struct LocalSnapClient(SnapRequestHandler<ProviderFactory<MockNodeTypesWithDB>>);
+
+    impl DownloadClient for LocalSnapClient {
+        fn report_bad_message(&self, peer_id: PeerId) {
+            panic!("invalid response of {peer_id}")
+        }
+
+        fn num_connected_peers(&self) -> usize {
+            1
+        }
+    }
+
+    impl SnapClient for LocalSnapClient {
+        type Output = Ready<PeerRequestResult<SnapMessage>>;
+
+        fn get_snap_data(&self, request: SnapMessage) -> Self::Output {
+            let response = self.0.on_request(request).ok_or(RequestError::BadResponse);
+            ready(response.map(|response| WithPeerId::new(PeerId::ZERO, response)))
+        }
+
+        fn num_snap_peers(&self) -> usize {
+            1
+        }
+    }
+
+    #[tokio::test]
+    async fn sync_from_snap_server() {
+        let source = create_test_provider_factory();
+        let provider = source.provider_rw().unwrap();
+        let tx = provider.tx_ref();
+
+        // accounts, contracts with storage and code, and a contract whose storage is larger
+        // than a single download
+        let address = |i: u64| Address::from_word(B256::from(U256::from(i)));
+        let large = address(1_000_000);
+        for i in 0..300 {
+            let account = Account { nonce: i, balance: U256::from(i), bytecode_hash: None };
+            set_account(tx, address(i), Some(account), None);
+        }
+        for i in 0..200 {
+            let code = Bytes::from(vec![0x60, i as u8, 0x60, (i >> 8) as u8]);
+            let code_hash = keccak256(&code);
+            tx.put::<tables::Bytecodes>(code_hash, Bytecode::new_raw(code)).unwrap();
+            let contract = address(10_000 + i);
+            let account = Account { nonce: 1, balance: U256::ZERO, bytecode_hash: Some(code_hash) };
+            set_account(tx, contract, Some(account), None);
+            for slot in 1..=3 {
+                set_slot(tx, contract, slot, U256::from(slot + i), None);
+            }
+        }
+        set_account(tx, large, Some(Account { nonce: 1, ..Default::default() }), None);
+        for slot in 1..=MAX_STORAGE_SLOTS_PER_DOWNLOAD as u64 + 1000 {
+            set_slot(tx, large, slot, U256::from(slot), None);
+        }
+        let genesis_root = StateRoot::from_tx(tx).root().unwrap();
+
+        // block 37 is the first block after the pivot, the state changes again at the tip
+        let account = Account { nonce: 5, balance: U256::from(5), bytecode_hash: None };
+        set_account(tx, address(1), Some(account), Some(37));
+        set_account(tx, address(300), Some(account), Some(37));
+        set_slot(tx, large, 1, U256::from(7), Some(37));
+        set_slot(tx, address(10_000), 2, U256::ZERO, Some(37));
+        let pivot_root = StateRoot::from_tx(tx).root().unwrap();
+
+        set_account(tx, address(2), Some(account), Some(100));
+        set_slot(tx, large, 5, U256::from(7), Some(100));
+        let (tip_root, updates) = StateRoot::from_tx(tx).root_with_updates().unwrap();
+        provider.write_trie_updates(&updates).unwrap();
+        provider.save_stage_checkpoint(StageId::Finish, StageCheckpoint::new(100)).unwrap();
+        provider.commit().unwrap();
+
+        let state_root = |number| match number {
+            ..=36 => genesis_root,
+            37..=99 => pivot_root,
+            _ => tip_root,
+        };
+        write_headers(&source.static_file_provider(), state_root);
+        let destination = create_test_provider_factory();
+        write_headers(&destination.static_file_provider(), state_root);
+
+        let mut stage = SnapSyncStage::new(LocalSnapClient(SnapRequestHandler::new(source)));
+        let input = ExecInput { target: Some(100), checkpoint: None };
+        loop {
+            let provider = destination.provider_rw().unwrap();
+            let output = execute(&mut stage, &provider, input).await;
+            provider.commit().unwrap();
+            if output.done {
+                break
+            }
+        }
+
+        // the destination has the state of the pivot, 64 blocks below the tip
+        let provider = destination.provider().unwrap();
+        assert_eq!(snap_sync_pivot(provider.tx_ref()).unwrap(), Some(36));
+        assert_eq!(StateRoot::from_tx(provider.tx_ref()).root().unwrap(), state_root(36));
+        assert_eq!(provider.tx_ref().entries::<tables::Bytecodes>().unwrap(), 200);
+    }
+
+    async fn execute<Provider, S: Stage<Provider>>(
+        stage: &mut S,
+        provider: &Provider,
+        input: ExecInput,
+    ) -> ExecOutput {
+        poll_fn(|cx| stage.poll_execute_ready(cx, input)).await.unwrap();
+        stage.execute(provider, input).unwrap()
+    }
+
+    fn write_headers(static_file_provider: &StaticFileProvider, state_root: impl Fn(u64) -> B256) {
+        let mut writer = static_file_provider.latest_writer(StaticFileSegment::Headers).unwrap();
+        for number in 0..=100 {
+            let header = Header { number, state_root: state_root(number), ..Default::default() };
+            writer.append_header(&header, U256::ZERO, &header.hash_slow()).unwrap();
+        }
+        writer.commit().unwrap();
+    }
+
+    /// Sets the hashed account and records the change in the changeset of the given block.
+    fn set_account<TX: DbTxMut + DbTx>(
+        tx: &TX,
+        address: Address,
+        account: Option<Account>,
+        block: Option<u64>,
+    ) {
+        let hashed_address = keccak256(address);
+        if let Some(block) = block {
+            let info = tx.get::<tables::HashedAccounts>(hashed_address).unwrap();
+            tx.put::<tables::AccountChangeSets>(block, AccountBeforeTx { address, info }).unwrap();
+        }
+        match account {
+            Some(account) => tx.put::<tables::HashedAccounts>(hashed_address, account).unwrap(),
+            None => {
+                tx.delete::<tables::HashedAccounts>(hashed_address, None).unwrap();
+            }
+        }
+    }
+
+    /// Sets the hashed storage slot and records the change in the changeset of the given block.
+    fn set_slot<TX: DbTxMut + DbTx>(
+        tx: &TX,
+        address: Address,
+        slot: u64,
+        value: U256,
+        block: Option<u64>,
+    ) {
+        let (hashed_address, slot) = (keccak256(address), B256::from(U256::from(slot)));
+        let key = keccak256(slot);
+        let mut cursor = tx.cursor_dup_write::<tables::HashedStorages>().unwrap();
+        let before = cursor
+            .seek_by_key_subkey(hashed_address, key)
+            .unwrap()
+            .filter(|entry| entry.key == key);
+        if let Some(block) = block {
+            let value = before.map(|entry| entry.value).unwrap_or_default();
+            tx.put::<tables::StorageChangeSets>(
+                BlockNumberAddress((block, address)),
+                StorageEntry { key: slot, value },
+            )
+            .unwrap();
+        }
+        if before.is_some() {
+            cursor.delete_current().unwrap();
+        }
+        if !value.is_zero() {
+            cursor.upsert(hashed_address, StorageEntry { key, value }).unwrap();
+        }
+    }
+
+    #[test]
+    fn phase_progress_roundtrip() {
+        for phase in [
+            SnapSyncPhase::Accounts(B256::ZERO),
+            SnapSyncPhase::Accounts(B256::repeat_byte(0xab)),
+            SnapSyncPhase::Rebuild,
+        ] {
+            assert_eq!(SnapSyncPhase::from_progress(Some(&phase.progress())), phase);
+        }
+        // healing restarts with a rebuild of the trie
+        assert_eq!(
+            SnapSyncPhase::from_progress(Some(&SnapSyncPhase::Heal.progress())),
+            SnapSyncPhase::Rebuild
+        );
+        assert_eq!(SnapSyncPhase::from_progress(None), SnapSyncPhase::default());
+    }
+
+    #[test]
+    fn subtrie_ranges() {
+        assert_eq!(subtrie_range(&Nibbles::default()), B256::ZERO..=B256::repeat_byte(0xff));
+        assert_eq!(
+            subtrie_range(&Nibbles::from_nibbles([0x1, 0x2, 0x3])),
+            b256!("1230000000000000000000000000000000000000000000000000000000000000")..=
+                b256!("123fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff")
+        );
+        assert_eq!(increment_checked(B256::repeat_byte(0xff)), None);
+        assert_eq!(increment(B256::ZERO), B256::with_last_byte(1));
+        assert_eq!(decrement(B256::with_last_byte(1)), B256::ZERO);
+    }
+}
@@ -11,6 +11,7 @@ pub enum StageId {
     Headers,
     Bodies,
     SenderRecovery,
+    SnapSync,
     Execution,
     PruneSenderRecovery,
     MerkleUnwind,
@@ -20,6 +21,7 @@ pub enum StageId {
     TransactionLookup,
     IndexStorageHistory,
     IndexAccountHistory,
+    IndexTransactionAppearances,
     Prune,
     Finish,
     /// Other custom stage with a provided string identifier.
@@ -28,6 +30,9 @@ pub enum StageId {
 
 impl StageId {
     /// All supported Stages
+    ///
+    /// [`StageId::SnapSync`] and [`StageId::IndexTransactionAppearances`] are not included, as they
+    /// are only part of the pipeline if snap sync or the transaction appearances index is enabled.
     pub const ALL: [Self; 14] = [
         Self::Headers,
         Self::Bodies,
@@ -66,6 +71,7 @@ impl StageId {
             Self::Headers => "Headers",
             Self::Bodies => "Bodies",
             Self::SenderRecovery => "SenderRecovery",
+            Self::SnapSync => "SnapSync",
             Self::Execution => "Execution",
             Self::PruneSenderRecovery => "PruneSenderRecovery",
             Self::MerkleUnwind => "MerkleUnwind",
@@ -75,6 +81,7 @@ impl StageId {
             Self::TransactionLookup => "TransactionLookup",
             Self::IndexAccountHistory => "IndexAccountHistory",
             Self::IndexStorageHistory => "IndexStorageHistory",
+            Self::IndexTransactionAppearances => "IndexTransactionAppearances",
             Self::Prune => "Prune",
             Self::Finish => "Finish",
             Self::Other(s) => s,
@@ -112,6 +119,7 @@ mod tests {
         assert_eq!(StageId::Headers.to_string(), "Headers");
         assert_eq!(StageId::Bodies.to_string(), "Bodies");
         assert_eq!(StageId::SenderRecovery.to_string(), "SenderRecovery");
+        assert_eq!(StageId::SnapSync.to_string(), "SnapSync");
         assert_eq!(StageId::Execution.to_string(), "Execution");
         assert_eq!(StageId::MerkleUnwind.to_string(), "MerkleUnwind");
         assert_eq!(StageId::AccountHashing.to_string(), "AccountHashing");
@@ -119,6 +127,7 @@ mod tests {
         assert_eq!(StageId::MerkleExecute.to_string(), "MerkleExecute");
         assert_eq!(StageId::IndexAccountHistory.to_string(), "IndexAccountHistory");
         assert_eq!(StageId::IndexStorageHistory.to_string(), "IndexStorageHistory");
+        assert_eq!(StageId::IndexTransactionAppearances.to_string(), "IndexTransactionAppearances");
         assert_eq!(StageId::TransactionLookup.to_string(), "TransactionLookup");
         assert_eq!(StageId::Finish.to_string(), "Finish");
 
@@ -0,0 +1,47 @@
+use crate::segments::Segment;
+use alloy_primitives::BlockNumber;
+use reth_db::tables;
+use reth_db_api::{cursor::DbCursorRO, models::BlockNumberAddress, transaction::DbTx};
+use reth_provider::{
+    providers::{StaticFileProvider, StaticFileWriter},
+    DBProvider,
+};
+use reth_static_file_types::StaticFileSegment;
+use reth_storage_errors::provider::ProviderResult;
+use std::ops::RangeInclusive;
+
+/// Static File segment responsible for [`StaticFileSegment::StorageChangeSets`] part of data.
+#[derive(Debug, Default)]
+pub struct StorageChangeSets;
+
+impl<Provider: DBProvider> Segment<Provider> for StorageChangeSets {
+    fn segment(&self) -> StaticFileSegment {
+        StaticFileSegment::StorageChangeSets
+    }
+
+    fn copy_to_static_files(
+        &self,
+        provider: Provider,
+        static_file_provider: StaticFileProvider,
+        block_range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<()> {
+        let mut static_file_writer = static_file_provider
+            .get_writer(*block_range.start(), StaticFileSegment::StorageChangeSets)?;
+
+        let mut changesets_cursor = provider.tx_ref().cursor_read::<tables::StorageChangeSets>()?;
+
+        for block in block_range {
+            let _static_file_block = static_file_writer.increment_block(block)?;
+            debug_assert_eq!(_static_file_block, block);
+
+            let changes = changesets_cursor
+                .walk_range(BlockNumberAddress::range(block..=block))?
+                .map(|result| result.map(|(key, entry)| (key.address(), entry)))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            static_file_writer.append_storage_changeset(block, changes)?;
+        }
+
+        Ok(())
+    }
+}
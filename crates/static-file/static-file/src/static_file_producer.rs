@@ -72,12 +72,18 @@ pub struct StaticFileTargets {
     headers: Option<RangeInclusive<BlockNumber>>,
     receipts: Option<RangeInclusive<BlockNumber>>,
     transactions: Option<RangeInclusive<BlockNumber>>,
+    account_changesets: Option<RangeInclusive<BlockNumber>>,
+    storage_changesets: Option<RangeInclusive<BlockNumber>>,
 }
 
 impl StaticFileTargets {
     /// Returns `true` if any of the targets are [Some].
     pub const fn any(&self) -> bool {
-        self.headers.is_some() || self.receipts.is_some() || self.transactions.is_some()
+        self.headers.is_some() ||
+            self.receipts.is_some() ||
+            self.transactions.is_some() ||
+            self.account_changesets.is_some() ||
+            self.storage_changesets.is_some()
     }
 
     // Returns `true` if all targets are either [`None`] or has beginning of the range equal to the
@@ -87,6 +93,8 @@ impl StaticFileTargets {
             (self.headers.as_ref(), static_files.headers),
             (self.receipts.as_ref(), static_files.receipts),
             (self.transactions.as_ref(), static_files.transactions),
+            (self.account_changesets.as_ref(), static_files.account_changesets),
+            (self.storage_changesets.as_ref(), static_files.storage_changesets),
         ]
         .iter()
         .all(|(target_block_range, highest_static_fileted_block)| {
@@ -152,6 +160,12 @@ where
         if let Some(block_range) = targets.receipts.clone() {
             segments.push((Box::new(segments::Receipts), block_range));
         }
+        if let Some(block_range) = targets.account_changesets.clone() {
+            segments.push((Box::new(segments::AccountChangeSets), block_range));
+        }
+        if let Some(block_range) = targets.storage_changesets.clone() {
+            segments.push((Box::new(segments::StorageChangeSets), block_range));
+        }
 
         segments.par_iter().try_for_each(|(segment, block_range)| -> ProviderResult<()> {
             debug!(target: "static_file", segment = %segment.segment(), ?block_range, "StaticFileProducer segment");
@@ -190,15 +204,23 @@ where
     /// Returns highest block numbers for all static file segments.
     pub fn copy_to_static_files(&self) -> ProviderResult<HighestStaticFiles> {
         let provider = self.provider.database_provider_ro()?;
-        let stages_checkpoints = [StageId::Headers, StageId::Execution, StageId::Bodies]
-            .into_iter()
-            .map(|stage| provider.get_stage_checkpoint(stage).map(|c| c.map(|c| c.block_number)))
-            .collect::<Result<Vec<_>, _>>()?;
+        let stages_checkpoints = [
+            StageId::Headers,
+            StageId::Execution,
+            StageId::Bodies,
+            StageId::IndexAccountHistory,
+            StageId::IndexStorageHistory,
+        ]
+        .into_iter()
+        .map(|stage| provider.get_stage_checkpoint(stage).map(|c| c.map(|c| c.block_number)))
+        .collect::<Result<Vec<_>, _>>()?;
 
         let highest_static_files = HighestStaticFiles {
             headers: stages_checkpoints[0],
             receipts: stages_checkpoints[1],
             transactions: stages_checkpoints[2],
+            account_changesets: stages_checkpoints[3],
+            storage_changesets: stages_checkpoints[4],
         };
         let targets = self.get_static_file_targets(highest_static_files)?;
         self.run(targets)?;
@@ -215,6 +237,19 @@ where
     ) -> ProviderResult<StaticFileTargets> {
         let highest_static_files = self.provider.static_file_provider().get_highest_static_files();
 
+        // Changesets are read by the history index stages from the database, so they can only be
+        // moved once indexed.
+        let provider = self.provider.database_provider_ro()?;
+        let account_history_checkpoint = provider
+            .get_stage_checkpoint(StageId::IndexAccountHistory)?
+            .unwrap_or_default()
+            .block_number;
+        let storage_history_checkpoint = provider
+            .get_stage_checkpoint(StageId::IndexStorageHistory)?
+            .unwrap_or_default()
+            .block_number;
+        drop(provider);
+
         let targets = StaticFileTargets {
             headers: finalized_block_numbers.headers.and_then(|finalized_block_number| {
                 self.get_static_file_target(highest_static_files.headers, finalized_block_number)
@@ -238,6 +273,27 @@ where
                     finalized_block_number,
                 )
             }),
+            // StaticFile changesets only if they're not pruned according to the user configuration
+            account_changesets: if self.prune_modes.account_history.is_none() {
+                finalized_block_numbers.account_changesets.and_then(|finalized_block_number| {
+                    self.get_static_file_target(
+                        highest_static_files.account_changesets,
+                        finalized_block_number.min(account_history_checkpoint),
+                    )
+                })
+            } else {
+                None
+            },
+            storage_changesets: if self.prune_modes.storage_history.is_none() {
+                finalized_block_numbers.storage_changesets.and_then(|finalized_block_number| {
+                    self.get_static_file_target(
+                        highest_static_files.storage_changesets,
+                        finalized_block_number.min(storage_history_checkpoint),
+                    )
+                })
+            } else {
+                None
+            },
         };
 
         trace!(
@@ -269,16 +325,24 @@ mod tests {
     };
     use alloy_primitives::{B256, U256};
     use assert_matches::assert_matches;
-    use reth_db_api::{database::Database, transaction::DbTx};
+    use reth_db::tables;
+    use reth_db_api::{
+        cursor::DbCursorRO,
+        database::Database,
+        models::{AccountBeforeTx, BlockNumberAddress},
+        transaction::{DbTx, DbTxMut},
+    };
     use reth_provider::{
-        providers::StaticFileWriter, test_utils::MockNodeTypesWithDB, ProviderError,
-        ProviderFactory, StaticFileProviderFactory,
+        providers::StaticFileWriter, test_utils::MockNodeTypesWithDB, DBProvider, ProviderError,
+        ProviderFactory, StageCheckpointWriter, StaticFileProviderFactory,
     };
-    use reth_prune_types::PruneModes;
+    use reth_prune_types::{PruneMode, PruneModes};
     use reth_stages::test_utils::{StorageKind, TestStageDB};
+    use reth_stages_types::{StageCheckpoint, StageId};
     use reth_static_file_types::{HighestStaticFiles, StaticFileSegment};
     use reth_testing_utils::generators::{
-        self, random_block_range, random_receipt, BlockRangeParams,
+        self, random_block_range, random_changeset_range, random_eoa_accounts, random_receipt,
+        BlockRangeParams,
     };
     use std::{sync::mpsc::channel, time::Duration};
     use tempfile::TempDir;
@@ -334,6 +398,7 @@ mod tests {
                 headers: Some(1),
                 receipts: Some(1),
                 transactions: Some(1),
+                ..Default::default()
             })
             .expect("get static file targets");
         assert_eq!(
@@ -341,13 +406,20 @@ mod tests {
             StaticFileTargets {
                 headers: Some(0..=1),
                 receipts: Some(0..=1),
-                transactions: Some(0..=1)
+                transactions: Some(0..=1),
+                account_changesets: None,
+                storage_changesets: None,
             }
         );
         assert_matches!(static_file_producer.run(targets), Ok(_));
         assert_eq!(
             provider_factory.static_file_provider().get_highest_static_files(),
-            HighestStaticFiles { headers: Some(1), receipts: Some(1), transactions: Some(1) }
+            HighestStaticFiles {
+                headers: Some(1),
+                receipts: Some(1),
+                transactions: Some(1),
+                ..Default::default()
+            }
         );
 
         let targets = static_file_producer
@@ -355,6 +427,7 @@ mod tests {
                 headers: Some(3),
                 receipts: Some(3),
                 transactions: Some(3),
+                ..Default::default()
             })
             .expect("get static file targets");
         assert_eq!(
@@ -362,13 +435,20 @@ mod tests {
             StaticFileTargets {
                 headers: Some(2..=3),
                 receipts: Some(2..=3),
-                transactions: Some(2..=3)
+                transactions: Some(2..=3),
+                account_changesets: None,
+                storage_changesets: None,
             }
         );
         assert_matches!(static_file_producer.run(targets), Ok(_));
         assert_eq!(
             provider_factory.static_file_provider().get_highest_static_files(),
-            HighestStaticFiles { headers: Some(3), receipts: Some(3), transactions: Some(3) }
+            HighestStaticFiles {
+                headers: Some(3),
+                receipts: Some(3),
+                transactions: Some(3),
+                ..Default::default()
+            }
         );
 
         let targets = static_file_producer
@@ -376,6 +456,7 @@ mod tests {
                 headers: Some(4),
                 receipts: Some(4),
                 transactions: Some(4),
+                ..Default::default()
             })
             .expect("get static file targets");
         assert_eq!(
@@ -383,7 +464,9 @@ mod tests {
             StaticFileTargets {
                 headers: Some(4..=4),
                 receipts: Some(4..=4),
-                transactions: Some(4..=4)
+                transactions: Some(4..=4),
+                account_changesets: None,
+                storage_changesets: None,
             }
         );
         assert_matches!(
@@ -392,10 +475,142 @@ mod tests {
         );
         assert_eq!(
             provider_factory.static_file_provider().get_highest_static_files(),
-            HighestStaticFiles { headers: Some(3), receipts: Some(3), transactions: Some(3) }
+            HighestStaticFiles {
+                headers: Some(3),
+                receipts: Some(3),
+                transactions: Some(3),
+                ..Default::default()
+            }
         );
     }
 
+    #[test]
+    fn changeset_targets() {
+        let (provider_factory, _temp_static_files_dir) = setup();
+        let mut rng = generators::rng();
+
+        let blocks = random_block_range(
+            &mut rng,
+            0..=3,
+            BlockRangeParams { parent: Some(B256::ZERO), ..Default::default() },
+        );
+        let (changesets, _) = random_changeset_range(
+            &mut rng,
+            blocks.iter(),
+            random_eoa_accounts(&mut rng, 2)
+                .into_iter()
+                .map(|(address, account)| (address, (account, Vec::new()))),
+            1..3,
+            0..20,
+        );
+
+        // Account changesets are indexed up to block 2, storage changesets up to block 1.
+        let provider_rw = provider_factory.provider_rw().unwrap();
+        for (block, changeset) in changesets.into_iter().enumerate() {
+            let block = block as u64;
+            for (address, account, storage) in changeset {
+                provider_rw
+                    .tx_ref()
+                    .put::<tables::AccountChangeSets>(
+                        block,
+                        AccountBeforeTx { address, info: Some(account) },
+                    )
+                    .unwrap();
+                for entry in storage {
+                    provider_rw
+                        .tx_ref()
+                        .put::<tables::StorageChangeSets>(
+                            BlockNumberAddress((block, address)),
+                            entry,
+                        )
+                        .unwrap();
+                }
+            }
+        }
+        provider_rw
+            .save_stage_checkpoint(StageId::IndexAccountHistory, StageCheckpoint::new(2))
+            .unwrap();
+        provider_rw
+            .save_stage_checkpoint(StageId::IndexStorageHistory, StageCheckpoint::new(1))
+            .unwrap();
+        provider_rw.commit().unwrap();
+
+        let finalized = HighestStaticFiles {
+            account_changesets: Some(3),
+            storage_changesets: Some(3),
+            ..Default::default()
+        };
+        let no_targets = StaticFileTargets {
+            headers: None,
+            receipts: None,
+            transactions: None,
+            account_changesets: None,
+            storage_changesets: None,
+        };
+
+        // Changesets which are pruned are never moved to static files.
+        let static_file_producer = StaticFileProducerInner::new(
+            provider_factory.clone(),
+            PruneModes {
+                account_history: Some(PruneMode::Distance(10_064)),
+                storage_history: Some(PruneMode::Distance(10_064)),
+                ..Default::default()
+            },
+        );
+        assert_eq!(static_file_producer.get_static_file_targets(finalized).unwrap(), no_targets);
+
+        // Changesets are only moved once the history stages indexed them.
+        let static_file_producer =
+            StaticFileProducerInner::new(provider_factory.clone(), PruneModes::default());
+        let targets = static_file_producer.get_static_file_targets(finalized).unwrap();
+        assert_eq!(
+            targets,
+            StaticFileTargets {
+                account_changesets: Some(0..=2),
+                storage_changesets: Some(0..=1),
+                ..no_targets.clone()
+            }
+        );
+        assert_matches!(static_file_producer.run(targets), Ok(_));
+
+        let static_file_provider = provider_factory.static_file_provider();
+        assert_eq!(
+            static_file_provider.get_highest_static_files(),
+            HighestStaticFiles {
+                account_changesets: Some(2),
+                storage_changesets: Some(1),
+                ..Default::default()
+            }
+        );
+
+        let provider = provider_factory.provider().unwrap();
+        assert_eq!(
+            static_file_provider.account_changesets_range(0..=2).unwrap(),
+            provider
+                .tx_ref()
+                .cursor_read::<tables::AccountChangeSets>()
+                .unwrap()
+                .walk_range(0..=2)
+                .unwrap()
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap()
+        );
+        assert_eq!(
+            static_file_provider.storage_changesets_range(0..=1).unwrap(),
+            provider
+                .tx_ref()
+                .cursor_read::<tables::StorageChangeSets>()
+                .unwrap()
+                .walk_range(BlockNumberAddress::range(0..=1))
+                .unwrap()
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap()
+        );
+
+        // Nothing is left to move until the history stages make progress.
+        assert_eq!(static_file_producer.get_static_file_targets(finalized).unwrap(), no_targets);
+    }
+
     /// Tests that a cloneable [`StaticFileProducer`] type is not susceptible to any race condition.
     #[test]
     fn only_one() {
@@ -420,6 +635,7 @@ mod tests {
                         headers: Some(1),
                         receipts: Some(1),
                         transactions: Some(1),
+                        ..Default::default()
                     })
                     .expect("get static file targets");
                 assert_matches!(locked_producer.run(targets.clone()), Ok(_));
@@ -30,6 +30,12 @@ pub struct HighestStaticFiles {
     /// Highest static file block of transactions, inclusive.
     /// If [`None`], no static file is available.
     pub transactions: Option<BlockNumber>,
+    /// Highest static file block of account changesets, inclusive.
+    /// If [`None`], no static file is available.
+    pub account_changesets: Option<BlockNumber>,
+    /// Highest static file block of storage changesets, inclusive.
+    /// If [`None`], no static file is available.
+    pub storage_changesets: Option<BlockNumber>,
 }
 
 impl HighestStaticFiles {
@@ -39,6 +45,8 @@ impl HighestStaticFiles {
             StaticFileSegment::Headers => self.headers,
             StaticFileSegment::Transactions => self.transactions,
             StaticFileSegment::Receipts => self.receipts,
+            StaticFileSegment::AccountChangeSets => self.account_changesets,
+            StaticFileSegment::StorageChangeSets => self.storage_changesets,
         }
     }
 
@@ -48,17 +56,32 @@ impl HighestStaticFiles {
             StaticFileSegment::Headers => &mut self.headers,
             StaticFileSegment::Transactions => &mut self.transactions,
             StaticFileSegment::Receipts => &mut self.receipts,
+            StaticFileSegment::AccountChangeSets => &mut self.account_changesets,
+            StaticFileSegment::StorageChangeSets => &mut self.storage_changesets,
         }
     }
 
     /// Returns the minimum block of all segments.
     pub fn min(&self) -> Option<u64> {
-        [self.headers, self.transactions, self.receipts].iter().filter_map(|&option| option).min()
+        self.iter().min()
     }
 
     /// Returns the maximum block of all segments.
     pub fn max(&self) -> Option<u64> {
-        [self.headers, self.transactions, self.receipts].iter().filter_map(|&option| option).max()
+        self.iter().max()
+    }
+
+    /// Returns an iterator over the highest blocks of all segments that have static files.
+    fn iter(&self) -> impl Iterator<Item = BlockNumber> {
+        [
+            self.headers,
+            self.transactions,
+            self.receipts,
+            self.account_changesets,
+            self.storage_changesets,
+        ]
+        .into_iter()
+        .flatten()
     }
 }
 
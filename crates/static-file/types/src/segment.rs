@@ -34,6 +34,12 @@ pub enum StaticFileSegment {
     #[strum(serialize = "receipts")]
     /// Static File segment responsible for the `Receipts` table.
     Receipts,
+    #[strum(serialize = "account-change-sets")]
+    /// Static File segment responsible for the `AccountChangeSets` table.
+    AccountChangeSets,
+    #[strum(serialize = "storage-change-sets")]
+    /// Static File segment responsible for the `StorageChangeSets` table.
+    StorageChangeSets,
 }
 
 impl StaticFileSegment {
@@ -43,18 +49,27 @@ impl StaticFileSegment {
             Self::Headers => "headers",
             Self::Transactions => "transactions",
             Self::Receipts => "receipts",
+            Self::AccountChangeSets => "account-change-sets",
+            Self::StorageChangeSets => "storage-change-sets",
         }
     }
 
     /// Returns the default configuration of the segment.
     pub const fn config(&self) -> SegmentConfig {
-        SegmentConfig { compression: Compression::Lz4 }
+        match self {
+            // Transactions and receipts already use zstd dictionaries natively in their encoding.
+            Self::Transactions | Self::Receipts => SegmentConfig::new(Compression::Uncompressed),
+            Self::Headers | Self::AccountChangeSets | Self::StorageChangeSets => {
+                SegmentConfig::new(Compression::Lz4)
+            }
+        }
     }
 
     /// Returns the number of columns for the segment
     pub const fn columns(&self) -> usize {
         match self {
-            Self::Headers => 3,
+            Self::Headers | Self::StorageChangeSets => 3,
+            Self::AccountChangeSets => 2,
             Self::Transactions | Self::Receipts => 1,
         }
     }
@@ -128,6 +143,23 @@ impl StaticFileSegment {
     pub const fn is_tx_based(&self) -> bool {
         matches!(self, Self::Receipts | Self::Transactions)
     }
+
+    /// Returns `true` if the segment is `StaticFileSegment::AccountChangeSets` or
+    /// `StaticFileSegment::StorageChangeSets`.
+    ///
+    /// Rows of change based segments are numbered by a change number, which increases by one for
+    /// each change, across all static files of the segment. It's stored as the transaction range
+    /// of the [`SegmentHeader`].
+    pub const fn is_change_based(&self) -> bool {
+        matches!(self, Self::AccountChangeSets | Self::StorageChangeSets)
+    }
+
+    /// Returns `true` if the segment holds block history that can be expired, as described in
+    /// [EIP-4444](https://eips.ethereum.org/EIPS/eip-4444): `StaticFileSegment::Headers`,
+    /// `StaticFileSegment::Transactions` or `StaticFileSegment::Receipts`.
+    pub const fn is_history(&self) -> bool {
+        matches!(self, Self::Headers | Self::Transactions | Self::Receipts)
+    }
 }
 
 /// A segment header that contains information common to all segments. Used for storage.
@@ -140,7 +172,8 @@ pub struct SegmentHeader {
     expected_block_range: SegmentRangeInclusive,
     /// Block range of data on the static file segment
     block_range: Option<SegmentRangeInclusive>,
-    /// Transaction range of data of the static file segment
+    /// Transaction range of data of the static file segment. For change based segments, this is
+    /// the range of change numbers.
     tx_range: Option<SegmentRangeInclusive>,
     /// Segment type
     segment: StaticFileSegment,
@@ -230,7 +263,10 @@ impl SegmentHeader {
     pub fn increment_tx(&mut self) {
         match self.segment {
             StaticFileSegment::Headers => (),
-            StaticFileSegment::Transactions | StaticFileSegment::Receipts => {
+            StaticFileSegment::Transactions |
+            StaticFileSegment::Receipts |
+            StaticFileSegment::AccountChangeSets |
+            StaticFileSegment::StorageChangeSets => {
                 if let Some(tx_range) = &mut self.tx_range {
                     tx_range.end += 1;
                 } else {
@@ -252,7 +288,10 @@ impl SegmentHeader {
                     }
                 };
             }
-            StaticFileSegment::Transactions | StaticFileSegment::Receipts => {
+            StaticFileSegment::Transactions |
+            StaticFileSegment::Receipts |
+            StaticFileSegment::AccountChangeSets |
+            StaticFileSegment::StorageChangeSets => {
                 if let Some(range) = &mut self.tx_range {
                     if num > range.end - range.start {
                         self.tx_range = None;
@@ -284,20 +323,60 @@ impl SegmentHeader {
         }
     }
 
-    /// Returns the row offset which depends on whether the segment is block or transaction based.
+    /// Returns the row offset which depends on whether the segment is block, transaction or change
+    /// based.
     pub fn start(&self) -> Option<u64> {
         match self.segment {
             StaticFileSegment::Headers => self.block_start(),
-            StaticFileSegment::Transactions | StaticFileSegment::Receipts => self.tx_start(),
+            StaticFileSegment::Transactions |
+            StaticFileSegment::Receipts |
+            StaticFileSegment::AccountChangeSets |
+            StaticFileSegment::StorageChangeSets => self.tx_start(),
         }
     }
 }
 
 /// Configuration used on the segment.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
 pub struct SegmentConfig {
     /// Compression used on the segment
     pub compression: Compression,
+    /// Zstd compression level. A level of `0` uses zstd's default (currently `3`).
+    ///
+    /// Only used by [`Compression::Zstd`] and [`Compression::ZstdWithDictionary`].
+    #[serde(default)]
+    pub zstd_level: i32,
+    /// Maximum size in bytes of the dictionary trained for each column.
+    ///
+    /// Only used by [`Compression::ZstdWithDictionary`].
+    #[serde(default = "SegmentConfig::default_max_dict_size")]
+    pub max_dict_size: usize,
+}
+
+impl SegmentConfig {
+    /// Default maximum size in bytes of a trained column dictionary.
+    pub const DEFAULT_MAX_DICT_SIZE: usize = 100_000;
+
+    /// Creates a new [`SegmentConfig`] with the given compression and default zstd settings.
+    pub const fn new(compression: Compression) -> Self {
+        Self { compression, zstd_level: 0, max_dict_size: Self::DEFAULT_MAX_DICT_SIZE }
+    }
+
+    /// Sets the zstd compression level.
+    pub const fn with_zstd_level(mut self, zstd_level: i32) -> Self {
+        self.zstd_level = zstd_level;
+        self
+    }
+
+    /// Sets the maximum size in bytes of a trained column dictionary.
+    pub const fn with_max_dict_size(mut self, max_dict_size: usize) -> Self {
+        self.max_dict_size = max_dict_size;
+        self
+    }
+
+    const fn default_max_dict_size() -> usize {
+        Self::DEFAULT_MAX_DICT_SIZE
+    }
 }
 
 /// Helper type to handle segment transaction and block INCLUSIVE ranges.
@@ -359,6 +438,18 @@ mod tests {
         let test_vectors = [
             (StaticFileSegment::Headers, 2..=30, "static_file_headers_2_30", None),
             (StaticFileSegment::Receipts, 30..=300, "static_file_receipts_30_300", None),
+            (
+                StaticFileSegment::AccountChangeSets,
+                0..=499_999,
+                "static_file_account-change-sets_0_499999",
+                None,
+            ),
+            (
+                StaticFileSegment::StorageChangeSets,
+                0..=499_999,
+                "static_file_storage-change-sets_0_499999",
+                None,
+            ),
             (
                 StaticFileSegment::Transactions,
                 1_123_233..=11_223_233,
@@ -3,7 +3,7 @@ use crate::{
     transaction::{DbTx, DbTxMut},
     DatabaseError,
 };
-use std::{fmt::Debug, sync::Arc};
+use std::{fmt::Debug, path::Path, sync::Arc};
 
 /// Main Database trait that can open read-only and read-write transactions.
 ///
@@ -76,3 +76,23 @@ impl<DB: Database> Database for &DB {
         <DB as Database>::tx_mut(self)
     }
 }
+
+/// A database that can copy a consistent view of itself to a file, e.g. to create snapshots.
+pub trait DatabaseCopy {
+    /// Copies a consistent view of the database to the given file, which must not exist yet.
+    ///
+    /// If `compact` is set, free space is omitted from the copy.
+    fn copy_to(&self, dest: &Path, compact: bool) -> Result<(), DatabaseError>;
+}
+
+impl<DB: DatabaseCopy> DatabaseCopy for Arc<DB> {
+    fn copy_to(&self, dest: &Path, compact: bool) -> Result<(), DatabaseError> {
+        <DB as DatabaseCopy>::copy_to(self, dest, compact)
+    }
+}
+
+impl<DB: DatabaseCopy> DatabaseCopy for &DB {
+    fn copy_to(&self, dest: &Path, compact: bool) -> Result<(), DatabaseError> {
+        <DB as DatabaseCopy>::copy_to(self, dest, compact)
+    }
+}
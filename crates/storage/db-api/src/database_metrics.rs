@@ -41,23 +41,73 @@ impl<DB: DatabaseMetrics> DatabaseMetrics for Arc<DB> {
     }
 }
 
+/// The statistics of a single table of the database.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TableStats {
+    /// The name of the table.
+    pub name: &'static str,
+    /// The number of entries in the table.
+    pub entries: usize,
+    /// The number of branch pages of the table.
+    pub branch_pages: usize,
+    /// The number of leaf pages of the table.
+    pub leaf_pages: usize,
+    /// The number of overflow pages of the table.
+    pub overflow_pages: usize,
+    /// The size of a page in bytes.
+    pub page_size: usize,
+}
+
+impl TableStats {
+    /// Returns the size of the table in bytes.
+    pub const fn size(&self) -> usize {
+        (self.branch_pages + self.leaf_pages + self.overflow_pages) * self.page_size
+    }
+}
+
 /// The type used to store metadata about the database.
 #[derive(Debug, Default)]
 pub struct DatabaseMetadataValue {
     /// The freelist size
     freelist_size: Option<usize>,
+    /// The page size
+    page_size: Option<usize>,
+    /// The statistics of all tables
+    table_stats: Vec<TableStats>,
 }
 
 impl DatabaseMetadataValue {
     /// Creates a new [`DatabaseMetadataValue`] with the given freelist size.
     pub const fn new(freelist_size: Option<usize>) -> Self {
-        Self { freelist_size }
+        Self { freelist_size, page_size: None, table_stats: Vec::new() }
+    }
+
+    /// Sets the page size of the database.
+    pub const fn with_page_size(mut self, page_size: Option<usize>) -> Self {
+        self.page_size = page_size;
+        self
+    }
+
+    /// Sets the statistics of the tables of the database.
+    pub fn with_table_stats(mut self, table_stats: Vec<TableStats>) -> Self {
+        self.table_stats = table_stats;
+        self
     }
 
     /// Returns the freelist size, if available.
     pub const fn freelist_size(&self) -> Option<usize> {
         self.freelist_size
     }
+
+    /// Returns the page size, if available.
+    pub const fn page_size(&self) -> Option<usize> {
+        self.page_size
+    }
+
+    /// Returns the statistics of the tables of the database.
+    pub fn table_stats(&self) -> &[TableStats] {
+        &self.table_stats
+    }
 }
 
 /// Includes a method to return a [`DatabaseMetadataValue`] type, which can be used to dynamically
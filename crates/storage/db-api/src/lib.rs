@@ -80,7 +80,7 @@ mod scale;
 
 mod utils;
 
-pub use database::Database;
+pub use database::{Database, DatabaseCopy};
 
 mod unwind;
 pub use unwind::DbTxUnwindExt;
@@ -6,13 +6,13 @@ use crate::{
         DbCursorRO, DbCursorRW, DbDupCursorRO, DbDupCursorRW, DupWalker, RangeWalker,
         ReverseWalker, Walker,
     },
-    database::Database,
+    database::{Database, DatabaseCopy},
     table::{DupSort, Table, TableImporter},
     transaction::{DbTx, DbTxMut},
     DatabaseError,
 };
 use core::ops::Bound;
-use std::{collections::BTreeMap, ops::RangeBounds};
+use std::{collections::BTreeMap, ops::RangeBounds, path::Path};
 
 /// Mock database used for testing with inner `BTreeMap` structure
 // TODO
@@ -34,6 +34,12 @@ impl Database for DatabaseMock {
     }
 }
 
+impl DatabaseCopy for DatabaseMock {
+    fn copy_to(&self, _dest: &Path, _compact: bool) -> Result<(), DatabaseError> {
+        Err(DatabaseError::Other("the mock database can't be copied".to_string()))
+    }
+}
+
 /// Mock read only tx
 #[derive(Debug, Clone, Default)]
 pub struct TxMock {
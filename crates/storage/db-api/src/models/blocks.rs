@@ -1,8 +1,13 @@
 //! Block related models and types.
 
+use crate::{
+    table::{Compress, Decompress},
+    DatabaseError,
+};
 use alloy_primitives::B256;
+use alloy_rlp::{Decodable, Encodable};
 use reth_codecs::{add_arbitrary_tests, Compact};
-use reth_primitives::Header;
+use reth_primitives::{Header, SealedBlock};
 use serde::{Deserialize, Serialize};
 
 /// The storage representation of a block's ommers.
@@ -19,10 +24,43 @@ pub struct StoredBlockOmmers {
 /// Hash of the block header.
 pub type HeaderHash = B256;
 
+/// The storage representation of a block that was rejected during validation.
+///
+/// The block is stored RLP encoded, followed by the validation error.
+#[derive(Debug, Default, Eq, PartialEq, Clone, Serialize, Deserialize)]
+pub struct StoredBadBlock {
+    /// The rejected block.
+    pub block: SealedBlock,
+    /// The error the block was rejected with.
+    pub error: String,
+}
+
+impl Compress for StoredBadBlock {
+    type Compressed = Vec<u8>;
+
+    fn compress_to_buf<B: bytes::BufMut + AsMut<[u8]>>(self, buf: &mut B) {
+        let mut rlp = Vec::with_capacity(self.block.length());
+        self.block.encode(&mut rlp);
+
+        rlp.to_compact(buf);
+        self.error.as_bytes().to_compact(buf);
+    }
+}
+
+impl Decompress for StoredBadBlock {
+    fn decompress(value: &[u8]) -> Result<Self, DatabaseError> {
+        let (rlp, buf) = Vec::<u8>::from_compact(value, value.len());
+        let (error, _) = Vec::<u8>::from_compact(buf, buf.len());
+        Ok(Self {
+            block: SealedBlock::decode(&mut rlp.as_slice()).map_err(|_| DatabaseError::Decode)?,
+            error: String::from_utf8_lossy(&error).into_owned(),
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::table::{Compress, Decompress};
 
     #[test]
     fn test_ommer() {
@@ -31,4 +69,21 @@ mod tests {
         ommer.ommers.push(Header::default());
         assert_eq!(ommer.clone(), StoredBlockOmmers::decompress(&ommer.compress()).unwrap());
     }
+
+    #[test]
+    fn test_bad_block() {
+        let bad_block = StoredBadBlock {
+            block: reth_primitives::Block::default().seal_slow(),
+            error: "mismatched block state root".to_string(),
+        };
+        assert_eq!(bad_block.clone(), StoredBadBlock::decompress(&bad_block.compress()).unwrap());
+    }
+
+    #[test]
+    fn test_bad_block_invalid_rlp() {
+        let mut buf = Vec::new();
+        vec![0xc0u8, 0x01].to_compact(&mut buf);
+        b"error".to_compact(&mut buf);
+        assert_eq!(StoredBadBlock::decompress(&buf), Err(DatabaseError::Decode));
+    }
 }
@@ -227,6 +227,7 @@ impl_compression_for_compact!(
     AccountBeforeTx,
     TransactionSignedNoHash,
     CompactU256,
+    CompactU64,
     StageCheckpoint,
     PruneCheckpoint,
     ClientVersion,
@@ -6,7 +6,10 @@ use reth_chainspec::EthChainSpec;
 use reth_codecs::Compact;
 use reth_config::config::EtlConfig;
 use reth_db::tables;
-use reth_db_api::{transaction::DbTxMut, DatabaseError};
+use reth_db_api::{
+    transaction::{DbTx, DbTxMut},
+    DatabaseError,
+};
 use reth_etl::Collector;
 use reth_primitives::{Account, Bytecode, GotExpected, Receipts, StaticFileSegment, StorageEntry};
 use reth_provider::{
@@ -98,6 +101,14 @@ where
                 database_hash: block_hash,
             })
         }
+        // The genesis header was expired from static files, but its hash is still indexed.
+        Err(ProviderError::HistoryExpired(_))
+            if factory.database_provider_ro()?.tx_ref().get::<tables::HeaderNumbers>(hash)? ==
+                Some(0) =>
+        {
+            debug!("Genesis already written and expired, skipping.");
+            return Ok(hash)
+        }
         Err(e) => return Err(dbg!(e).into()),
     }
 
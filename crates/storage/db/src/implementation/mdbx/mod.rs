@@ -7,12 +7,11 @@ use crate::{
     utils::default_page_size,
     DatabaseError,
 };
-use eyre::Context;
 use metrics::{gauge, Label};
 use reth_db_api::{
     cursor::{DbCursorRO, DbCursorRW},
-    database::Database,
-    database_metrics::{DatabaseMetadata, DatabaseMetadataValue, DatabaseMetrics},
+    database::{Database, DatabaseCopy},
+    database_metrics::{DatabaseMetadata, DatabaseMetadataValue, DatabaseMetrics, TableStats},
     models::ClientVersion,
     transaction::{DbTx, DbTxMut},
 };
@@ -161,6 +160,12 @@ impl Database for DatabaseEnv {
     }
 }
 
+impl DatabaseCopy for DatabaseEnv {
+    fn copy_to(&self, dest: &Path, compact: bool) -> Result<(), DatabaseError> {
+        self.inner.copy(dest, compact).map_err(|e| DatabaseError::Copy(e.into()))
+    }
+}
+
 impl DatabaseMetrics for DatabaseEnv {
     fn report_metrics(&self) {
         for (name, value, labels) in self.gauge_metrics() {
@@ -171,54 +176,38 @@ impl DatabaseMetrics for DatabaseEnv {
     fn gauge_metrics(&self) -> Vec<(&'static str, f64, Vec<Label>)> {
         let mut metrics = Vec::new();
 
-        let _ = self
-            .view(|tx| {
-                for table in Tables::ALL.iter().map(Tables::name) {
-                    let table_db = tx.inner.open_db(Some(table)).wrap_err("Could not open db.")?;
-
-                    let stats = tx
-                        .inner
-                        .db_stat(&table_db)
-                        .wrap_err(format!("Could not find table: {table}"))?;
-
-                    let page_size = stats.page_size() as usize;
-                    let leaf_pages = stats.leaf_pages();
-                    let branch_pages = stats.branch_pages();
-                    let overflow_pages = stats.overflow_pages();
-                    let num_pages = leaf_pages + branch_pages + overflow_pages;
-                    let table_size = page_size * num_pages;
-                    let entries = stats.entries();
-
-                    metrics.push((
-                        "db.table_size",
-                        table_size as f64,
-                        vec![Label::new("table", table)],
-                    ));
-                    metrics.push((
-                        "db.table_pages",
-                        leaf_pages as f64,
-                        vec![Label::new("table", table), Label::new("type", "leaf")],
-                    ));
-                    metrics.push((
-                        "db.table_pages",
-                        branch_pages as f64,
-                        vec![Label::new("table", table), Label::new("type", "branch")],
-                    ));
-                    metrics.push((
-                        "db.table_pages",
-                        overflow_pages as f64,
-                        vec![Label::new("table", table), Label::new("type", "overflow")],
-                    ));
-                    metrics.push((
-                        "db.table_entries",
-                        entries as f64,
-                        vec![Label::new("table", table)],
-                    ));
-                }
-
-                Ok::<(), eyre::Report>(())
-            })
-            .map_err(|error| error!(%error, "Failed to read db table stats"));
+        if let Ok(table_stats) =
+            self.table_stats().map_err(|error| error!(%error, "Failed to read db table stats"))
+        {
+            for stats in table_stats {
+                let table = stats.name;
+                metrics.push((
+                    "db.table_size",
+                    stats.size() as f64,
+                    vec![Label::new("table", table)],
+                ));
+                metrics.push((
+                    "db.table_pages",
+                    stats.leaf_pages as f64,
+                    vec![Label::new("table", table), Label::new("type", "leaf")],
+                ));
+                metrics.push((
+                    "db.table_pages",
+                    stats.branch_pages as f64,
+                    vec![Label::new("table", table), Label::new("type", "branch")],
+                ));
+                metrics.push((
+                    "db.table_pages",
+                    stats.overflow_pages as f64,
+                    vec![Label::new("table", table), Label::new("type", "overflow")],
+                ));
+                metrics.push((
+                    "db.table_entries",
+                    stats.entries as f64,
+                    vec![Label::new("table", table)],
+                ));
+            }
+        }
 
         if let Ok(freelist) =
             self.freelist().map_err(|error| error!(%error, "Failed to read db.freelist"))
@@ -243,6 +232,8 @@ impl DatabaseMetrics for DatabaseEnv {
 impl DatabaseMetadata for DatabaseEnv {
     fn metadata(&self) -> DatabaseMetadataValue {
         DatabaseMetadataValue::new(self.freelist().ok())
+            .with_page_size(self.stat().ok().map(|stat| stat.page_size() as usize))
+            .with_table_stats(self.table_stats().unwrap_or_default())
     }
 }
 
@@ -435,6 +426,32 @@ impl DatabaseEnv {
         Ok(())
     }
 
+    /// Returns the statistics of all tables.
+    pub fn table_stats(&self) -> Result<Vec<TableStats>, DatabaseError> {
+        self.view(|tx| {
+            Tables::ALL
+                .iter()
+                .map(|table| {
+                    let table_db = tx
+                        .inner
+                        .open_db(Some(table.name()))
+                        .map_err(|e| DatabaseError::Open(e.into()))?;
+                    let stats =
+                        tx.inner.db_stat(&table_db).map_err(|e| DatabaseError::Stats(e.into()))?;
+
+                    Ok(TableStats {
+                        name: table.name(),
+                        entries: stats.entries(),
+                        branch_pages: stats.branch_pages(),
+                        leaf_pages: stats.leaf_pages(),
+                        overflow_pages: stats.overflow_pages(),
+                        page_size: stats.page_size() as usize,
+                    })
+                })
+                .collect()
+        })?
+    }
+
     /// Records version that accesses the database with write privileges.
     pub fn record_client_version(&self, version: ClientVersion) -> Result<(), DatabaseError> {
         if version.is_empty() {
@@ -46,7 +46,7 @@ pub mod test_utils {
     use crate::mdbx::DatabaseArguments;
     use parking_lot::RwLock;
     use reth_db_api::{
-        database::Database,
+        database::{Database, DatabaseCopy},
         database_metrics::{DatabaseMetadata, DatabaseMetadataValue, DatabaseMetrics},
         models::ClientVersion,
     };
@@ -149,6 +149,12 @@ pub mod test_utils {
         }
     }
 
+    impl<DB: DatabaseCopy> DatabaseCopy for TempDatabase<DB> {
+        fn copy_to(&self, dest: &Path, compact: bool) -> Result<(), DatabaseError> {
+            self.db().copy_to(dest, compact)
+        }
+    }
+
     impl<DB: DatabaseMetrics> DatabaseMetrics for TempDatabase<DB> {
         fn report_metrics(&self) {
             self.db().report_metrics()
@@ -1,12 +1,15 @@
-use super::{ReceiptMask, TransactionMask};
+use super::{AccountChangeSetMask, ReceiptMask, StorageChangeSetMask, TransactionMask};
 use crate::{
     add_static_file_mask,
-    static_file::mask::{ColumnSelectorOne, ColumnSelectorTwo, HeaderMask},
+    static_file::mask::{ColumnSelectorOne, ColumnSelectorThree, ColumnSelectorTwo, HeaderMask},
     HeaderTerminalDifficulties, RawValue, Receipts, Transactions,
 };
-use alloy_primitives::BlockHash;
-use reth_db_api::table::Table;
-use reth_primitives::Header;
+use alloy_primitives::{Address, BlockHash};
+use reth_db_api::{
+    models::{AccountBeforeTx, CompactU64},
+    table::Table,
+};
+use reth_primitives::{Header, StorageEntry};
 
 // HEADER MASKS
 add_static_file_mask!(HeaderMask, Header, 0b001);
@@ -21,3 +24,13 @@ add_static_file_mask!(ReceiptMask, <Receipts as Table>::Value, 0b1);
 // TRANSACTION MASKS
 add_static_file_mask!(TransactionMask, <Transactions as Table>::Value, 0b1);
 add_static_file_mask!(TransactionMask, RawValue<<Transactions as Table>::Value>, 0b1);
+
+// ACCOUNT CHANGESET MASKS
+add_static_file_mask!(AccountChangeSetMask, CompactU64, 0b01);
+add_static_file_mask!(AccountChangeSetMask, AccountBeforeTx, 0b10);
+add_static_file_mask!(AccountChangeSetMask, CompactU64, AccountBeforeTx, 0b11);
+
+// STORAGE CHANGESET MASKS
+add_static_file_mask!(StorageChangeSetMask, CompactU64, 0b001);
+add_static_file_mask!(StorageChangeSetMask, Address, StorageEntry, 0b110);
+add_static_file_mask!(StorageChangeSetMask, CompactU64, Address, StorageEntry, 0b111);
@@ -25,8 +25,8 @@ use reth_db_api::{
         accounts::BlockNumberAddress,
         blocks::{HeaderHash, StoredBlockOmmers},
         storage_sharded_key::StorageShardedKey,
-        AccountBeforeTx, ClientVersion, CompactU256, ShardedKey, StoredBlockBodyIndices,
-        StoredBlockWithdrawals,
+        AccountBeforeTx, ClientVersion, CompactU256, ShardedKey, StoredBadBlock,
+        StoredBlockBodyIndices, StoredBlockWithdrawals,
     },
     table::{Decode, DupSort, Encode, Table},
 };
@@ -359,6 +359,23 @@ tables! {
     /// Code example can be found in `reth_provider::HistoricalStateProviderRef`
     table StoragesHistory<Key = StorageShardedKey, Value = BlockNumberList>;
 
+    /// Stores pointers to the blocks in which an address appeared as the sender or the recipient
+    /// of a transaction.
+    ///
+    /// Sharded the same way as [`AccountsHistory`]: the last shard of an address is keyed with
+    /// `u64::MAX` as its highest block number. Together with [`AccountsHistory`] this allows
+    /// finding all blocks an address was involved in without re-executing the chain.
+    table TransactionAppearances<Key = ShardedKey<Address>, Value = BlockNumberList>;
+
+    /// Stores pointers to the blocks in which an address appeared in a transaction trace, as the
+    /// sender or the recipient of a call, the creator or the address of a created contract, a
+    /// selfdestructed contract or its refund address, or as a block reward beneficiary.
+    ///
+    /// Sharded the same way as [`AccountsHistory`]. This index is optional, it is only maintained
+    /// if the node runs the trace index `ExEx`, which keeps track of its progress in
+    /// [`StageCheckpoints`].
+    table TraceAppearances<Key = ShardedKey<Address>, Value = BlockNumberList>;
+
     /// Stores the state of an account before a certain transaction changed it.
     /// Change on state can be: account is created, selfdestructed, touched while empty
     /// or changed balance,nonce.
@@ -409,6 +426,10 @@ tables! {
 
     /// Stores generic chain state info, like the last finalized block.
     table ChainState<Key = ChainStateKey, Value = BlockNumber>;
+
+    /// Stores the most recent blocks that were rejected during validation, keyed by an
+    /// increasing sequence number.
+    table BadBlocks<Key = u64, Value = StoredBadBlock>;
 }
 
 /// Keys for the `ChainState` table.
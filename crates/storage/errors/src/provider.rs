@@ -127,6 +127,15 @@ pub enum ProviderError {
     /// Static File is not found for requested transaction.
     #[display("unable to find {_0} static file for transaction id {_1}")]
     MissingStaticFileTx(StaticFileSegment, TxNumber),
+    /// History before the given block has been expired from static files.
+    #[display("history before block #{_0} has expired")]
+    HistoryExpired(BlockNumber),
+    /// A file of a snapshot does not match the checksum in its manifest.
+    #[display("snapshot file {_0} does not match its checksum")]
+    SnapshotChecksumMismatch(String),
+    /// Another snapshot is being created.
+    #[display("a snapshot is already being created")]
+    SnapshotInProgress,
     /// Static File is finalized and cannot be written to.
     #[display("unable to write block #{_1} to finalized static file {_0}")]
     FinalizedStaticFile(StaticFileSegment, BlockNumber),
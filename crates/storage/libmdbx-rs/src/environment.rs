@@ -149,6 +149,18 @@ impl Environment {
         f(self.env_ptr())
     }
 
+    /// Copies the environment to the given file, see [`ffi::mdbx_env_copy`].
+    ///
+    /// The copy is taken from a read transaction, so it is consistent even if there are parallel
+    /// write transactions. The file must not exist yet. If `compact` is set, free pages are
+    /// omitted and all pages are renumbered sequentially.
+    pub fn copy(&self, dest: &Path, compact: bool) -> Result<()> {
+        let dest = CString::new(path_to_bytes(dest)).map_err(|_| Error::Invalid)?;
+        let flags = if compact { ffi::MDBX_CP_COMPACT } else { ffi::MDBX_CP_DEFAULTS };
+        mdbx_result(unsafe { ffi::mdbx_env_copy(self.env_ptr(), dest.as_ptr(), flags) })?;
+        Ok(())
+    }
+
     /// Flush the environment data buffers to disk.
     pub fn sync(&self, force: bool) -> Result<bool> {
         mdbx_result(unsafe { ffi::mdbx_env_sync_ex(self.env_ptr(), force, false) })
@@ -227,6 +239,20 @@ impl Environment {
     }
 }
 
+#[cfg(unix)]
+fn path_to_bytes<P: AsRef<Path>>(path: P) -> Vec<u8> {
+    use std::os::unix::ffi::OsStrExt;
+    path.as_ref().as_os_str().as_bytes().to_vec()
+}
+
+#[cfg(windows)]
+fn path_to_bytes<P: AsRef<Path>>(path: P) -> Vec<u8> {
+    // On Windows, could use std::os::windows::ffi::OsStrExt to encode_wide(),
+    // but we end up with a Vec<u16> instead of a Vec<u8>, so that doesn't
+    // really help.
+    path.as_ref().to_string_lossy().to_string().into_bytes()
+}
+
 /// Container type for Environment internals.
 ///
 /// This holds the raw pointer to the MDBX environment and the transaction manager.
@@ -671,20 +697,6 @@ impl EnvironmentBuilder {
                     ))?;
                 }
 
-                #[cfg(unix)]
-                fn path_to_bytes<P: AsRef<Path>>(path: P) -> Vec<u8> {
-                    use std::os::unix::ffi::OsStrExt;
-                    path.as_ref().as_os_str().as_bytes().to_vec()
-                }
-
-                #[cfg(windows)]
-                fn path_to_bytes<P: AsRef<Path>>(path: P) -> Vec<u8> {
-                    // On Windows, could use std::os::windows::ffi::OsStrExt to encode_wide(),
-                    // but we end up with a Vec<u16> instead of a Vec<u8>, so that doesn't
-                    // really help.
-                    path.as_ref().to_string_lossy().to_string().into_bytes()
-                }
-
                 let path = match CString::new(path_to_bytes(path)) {
                     Ok(path) => path,
                     Err(_) => return Err(Error::Invalid),
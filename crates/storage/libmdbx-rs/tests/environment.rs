@@ -114,6 +114,29 @@ fn test_stat() {
     assert_eq!(stat.entries(), 64);
 }
 
+#[test]
+fn test_copy() {
+    let dir = tempdir().unwrap();
+    let env = Environment::builder().open(dir.path()).unwrap();
+
+    let tx = env.begin_rw_txn().expect("begin_rw_txn");
+    tx.put(tx.open_db(None).unwrap().dbi(), b"key", b"value", WriteFlags::default())
+        .expect("tx.put");
+    tx.commit().expect("tx.commit");
+
+    for compact in [false, true] {
+        let copy_dir = tempdir().unwrap();
+        env.copy(&copy_dir.path().join("mdbx.dat"), compact).unwrap();
+
+        // the destination file must not exist yet
+        assert!(env.copy(&copy_dir.path().join("mdbx.dat"), compact).is_err());
+
+        let copy = Environment::builder().open(copy_dir.path()).unwrap();
+        let tx = copy.begin_ro_txn().unwrap();
+        assert_eq!(tx.get(tx.open_db(None).unwrap().dbi(), b"key").unwrap(), Some(*b"value"));
+    }
+}
+
 #[test]
 fn test_info() {
     let map_size = 1024 * 1024;
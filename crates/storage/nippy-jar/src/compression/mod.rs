@@ -30,7 +30,6 @@ pub trait Compression: Serialize + for<'a> Deserialize<'a> {
         true
     }
 
-    #[cfg(test)]
     /// If required, prepares compression algorithm with an early pass on the data.
     fn prepare_compression(
         &mut self,
@@ -96,7 +95,6 @@ impl Compression for Compressors {
         }
     }
 
-    #[cfg(test)]
     fn prepare_compression(
         &mut self,
         columns: Vec<impl IntoIterator<Item = Vec<u8>>>,
@@ -2,7 +2,6 @@ use crate::{compression::Compression, NippyJarError};
 use derive_more::Deref;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::{
-    fs::File,
     io::{Read, Write},
     sync::Arc,
 };
@@ -67,7 +66,7 @@ impl Zstd {
     }
 
     /// If using dictionaries, creates a list of [`Compressor`].
-    pub fn compressors(&self) -> Result<Option<Vec<Compressor<'_>>>, NippyJarError> {
+    pub fn compressors(&self) -> Result<Option<Vec<Compressor<'static>>>, NippyJarError> {
         match self.state {
             ZstdState::PendingDictionary => Err(NippyJarError::CompressorNotReady),
             ZstdState::Ready => {
@@ -77,7 +76,7 @@ impl Zstd {
 
                 if let Some(dictionaries) = &self.dictionaries {
                     debug!(target: "nippy-jar", count=?dictionaries.len(), "Generating ZSTD compressor dictionaries.");
-                    return Ok(Some(dictionaries.compressors()?))
+                    return Ok(Some(dictionaries.compressors(self.level)?))
                 }
                 Ok(None)
             }
@@ -86,12 +85,14 @@ impl Zstd {
 
     /// Compresses a value using a dictionary. Reserves additional capacity for `buffer` if
     /// necessary.
+    ///
+    /// Returns the number of bytes written to `handle`.
     pub fn compress_with_dictionary(
         column_value: &[u8],
         buffer: &mut Vec<u8>,
-        handle: &mut File,
+        handle: &mut impl Write,
         compressor: Option<&mut Compressor<'_>>,
-    ) -> Result<(), NippyJarError> {
+    ) -> Result<usize, NippyJarError> {
         if let Some(compressor) = compressor {
             // Compressor requires the destination buffer to be big enough to write, otherwise it
             // fails. However, we don't know how big it will be. If data is small
@@ -107,12 +108,13 @@ impl Zstd {
             }
 
             handle.write_all(buffer)?;
+            let written = buffer.len();
             buffer.clear();
+            Ok(written)
         } else {
             handle.write_all(column_value)?;
+            Ok(column_value.len())
         }
-
-        Ok(())
     }
 
     /// Appends a decompressed value using a dictionary to a user provided buffer.
@@ -185,7 +187,6 @@ impl Compression for Zstd {
         matches!(self.state, ZstdState::Ready)
     }
 
-    #[cfg(test)]
     /// If using it with dictionaries, prepares a dictionary for each column.
     fn prepare_compression(
         &mut self,
@@ -227,7 +228,7 @@ impl Compression for Zstd {
 
         debug_assert_eq!(dictionaries.len(), self.columns);
 
-        self.dictionaries = Some(Arc::new(ZstdDictionaries::new(dictionaries)));
+        self.dictionaries = Some(Arc::new(ZstdDictionaries::load(dictionaries)));
         self.state = ZstdState::Ready;
 
         Ok(())
@@ -273,69 +274,54 @@ impl std::fmt::Debug for ZstdDictionaries<'_> {
 }
 
 impl ZstdDictionaries<'_> {
-    #[cfg(test)]
-    /// Creates [`ZstdDictionaries`].
-    pub(crate) fn new(raw: Vec<RawDictionary>) -> Self {
-        Self(raw.into_iter().map(ZstdDictionary::Raw).collect())
-    }
-
-    /// Loads a list [`RawDictionary`] into a list of [`ZstdDictionary::Loaded`].
+    /// Loads a list [`RawDictionary`] into a list of [`ZstdDictionary`].
     pub(crate) fn load(raw: Vec<RawDictionary>) -> Self {
-        Self(
-            raw.into_iter()
-                .map(|dict| ZstdDictionary::Loaded(DecoderDictionary::copy(&dict)))
-                .collect(),
-        )
+        Self(raw.into_iter().map(ZstdDictionary::new).collect())
     }
 
-    /// Creates a list of decompressors from a list of [`ZstdDictionary::Loaded`].
+    /// Creates a list of decompressors from a list of [`ZstdDictionary`].
     pub(crate) fn decompressors(&self) -> Result<Vec<Decompressor<'_>>, NippyJarError> {
         Ok(self
             .iter()
-            .flat_map(|dict| {
-                dict.loaded()
-                    .ok_or(NippyJarError::DictionaryNotLoaded)
-                    .map(Decompressor::with_prepared_dictionary)
-            })
+            .map(|dict| Decompressor::with_prepared_dictionary(dict.loaded()))
             .collect::<Result<Vec<_>, _>>()?)
     }
 
-    /// Creates a list of compressors from a list of [`ZstdDictionary::Raw`].
-    pub(crate) fn compressors(&self) -> Result<Vec<Compressor<'_>>, NippyJarError> {
+    /// Creates a list of compressors with the given compression level from a list of
+    /// [`ZstdDictionary`].
+    pub(crate) fn compressors(
+        &self,
+        level: i32,
+    ) -> Result<Vec<Compressor<'static>>, NippyJarError> {
         Ok(self
             .iter()
-            .flat_map(|dict| {
-                dict.raw()
-                    .ok_or(NippyJarError::CompressorNotAllowed)
-                    .map(|dict| Compressor::with_dictionary(0, dict))
-            })
+            .map(|dict| Compressor::with_dictionary(level, dict.raw()))
             .collect::<Result<Vec<_>, _>>()?)
     }
 }
 
-/// A Zstd dictionary. It's created and serialized with [`ZstdDictionary::Raw`], and deserialized as
-/// [`ZstdDictionary::Loaded`].
-pub(crate) enum ZstdDictionary<'a> {
-    #[allow(dead_code)]
-    Raw(RawDictionary),
-    Loaded(DecoderDictionary<'a>),
+/// A Zstd dictionary. It's serialized as its [`RawDictionary`], and keeps both the raw and the
+/// loaded [`DecoderDictionary`] around, so a reopened jar can be both read and appended to.
+pub(crate) struct ZstdDictionary<'a> {
+    raw: RawDictionary,
+    loaded: DecoderDictionary<'a>,
 }
 
 impl ZstdDictionary<'_> {
-    /// Returns a reference to the expected `RawDictionary`
-    pub(crate) const fn raw(&self) -> Option<&RawDictionary> {
-        match self {
-            ZstdDictionary::Raw(dict) => Some(dict),
-            ZstdDictionary::Loaded(_) => None,
-        }
+    /// Creates a [`ZstdDictionary`] from a [`RawDictionary`].
+    pub(crate) fn new(raw: RawDictionary) -> Self {
+        let loaded = DecoderDictionary::copy(&raw);
+        Self { raw, loaded }
     }
 
-    /// Returns a reference to the expected `DecoderDictionary`
-    pub(crate) const fn loaded(&self) -> Option<&DecoderDictionary<'_>> {
-        match self {
-            ZstdDictionary::Raw(_) => None,
-            ZstdDictionary::Loaded(dict) => Some(dict),
-        }
+    /// Returns a reference to the `RawDictionary`
+    pub(crate) const fn raw(&self) -> &RawDictionary {
+        &self.raw
+    }
+
+    /// Returns a reference to the `DecoderDictionary`
+    pub(crate) const fn loaded(&self) -> &DecoderDictionary<'_> {
+        &self.loaded
     }
 }
 
@@ -344,8 +330,7 @@ impl<'de> Deserialize<'de> for ZstdDictionary<'_> {
     where
         D: Deserializer<'de>,
     {
-        let dict = RawDictionary::deserialize(deserializer)?;
-        Ok(Self::Loaded(DecoderDictionary::copy(&dict)))
+        Ok(Self::new(RawDictionary::deserialize(deserializer)?))
     }
 }
 
@@ -354,19 +339,14 @@ impl Serialize for ZstdDictionary<'_> {
     where
         S: Serializer,
     {
-        match self {
-            ZstdDictionary::Raw(r) => r.serialize(serializer),
-            ZstdDictionary::Loaded(_) => unreachable!(),
-        }
+        self.raw.serialize(serializer)
     }
 }
 
 #[cfg(test)]
 impl PartialEq for ZstdDictionary<'_> {
     fn eq(&self, other: &Self) -> bool {
-        if let (Self::Raw(a), Self::Raw(b)) = (self, &other) {
-            return a == b
-        }
-        unimplemented!("`DecoderDictionary` can't be compared. So comparison should be done after decompressing a value.");
+        // `DecoderDictionary` can't be compared, but it's derived from the raw dictionary.
+        self.raw == other.raw
     }
 }
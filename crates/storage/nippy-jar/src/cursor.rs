@@ -163,12 +163,10 @@ impl<'a, H: NippyJarHeader> NippyJarCursor<'a, H> {
             match compression {
                 Compressors::Zstd(z) if z.use_dict => {
                     // If we are here, then for sure we have the necessary dictionaries and they're
-                    // loaded (happens during deserialization). Otherwise, there's an issue
-                    // somewhere else and we can't recover here anyway.
-                    let dictionaries = z.dictionaries.as_ref().expect("dictionaries to exist")
-                        [column]
-                        .loaded()
-                        .expect("dictionary to be loaded");
+                    // loaded (happens during training or deserialization). Otherwise, there's an
+                    // issue somewhere else and we can't recover here anyway.
+                    let dictionaries =
+                        z.dictionaries.as_ref().expect("dictionaries to exist")[column].loaded();
                     let mut decompressor = Decompressor::with_prepared_dictionary(dictionaries)?;
                     Zstd::decompress_with_dictionary(
                         self.reader.data(column_offset_range),
@@ -29,9 +29,7 @@ use std::os::windows::prelude::OpenOptionsExt;
 use tracing::*;
 
 pub mod compression;
-#[cfg(test)]
-use compression::Compression;
-use compression::Compressors;
+use compression::{Compression, Compressors};
 
 /// empty enum for backwards compatibility
 #[derive(Debug, Serialize, Deserialize)]
@@ -163,6 +161,16 @@ impl<H: NippyJarHeader> NippyJar<H> {
         self
     }
 
+    /// Sets the compression level of the [`compression::Zstd`] compressor, if one is set.
+    ///
+    /// A level of `0` uses zstd's default.
+    pub fn with_zstd_level(mut self, level: i32) -> Self {
+        if let Some(Compressors::Zstd(zstd)) = &mut self.compressor {
+            zstd.level = level;
+        }
+        self
+    }
+
     /// Adds [`compression::Lz4`] compression.
     pub fn with_lz4(mut self) -> Self {
         self.compressor = Some(Compressors::Lz4(compression::Lz4::default()));
@@ -254,11 +262,10 @@ impl<H: NippyJarHeader> NippyJar<H> {
             bincode::serialize_into(file, &self)
         })?)
     }
-}
 
-#[cfg(test)]
-impl<H: NippyJarHeader> NippyJar<H> {
     /// If required, prepares any compression algorithm to an early pass of the data.
+    ///
+    /// Example: with `zstd` dictionaries set, it trains one dictionary per column from `columns`.
     pub fn prepare_compression(
         &mut self,
         columns: Vec<impl IntoIterator<Item = Vec<u8>>>,
@@ -270,7 +277,10 @@ impl<H: NippyJarHeader> NippyJar<H> {
         }
         Ok(())
     }
+}
 
+#[cfg(test)]
+impl<H: NippyJarHeader> NippyJar<H> {
     /// Writes all data and configuration to a file and the offset index to another.
     pub fn freeze(
         self,
@@ -548,6 +558,52 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_zstd_with_dictionaries_append_after_load() {
+        let (col1, col2) = test_data(None);
+        let num_columns = 2;
+        let half = col1.len() / 2;
+        let file_path = tempfile::NamedTempFile::new().unwrap();
+
+        let mut nippy =
+            NippyJar::new_without_header(num_columns, file_path.path()).with_zstd(true, 5000);
+        nippy.prepare_compression(vec![col1.clone(), col2.clone()]).unwrap();
+        nippy
+            .freeze(
+                vec![
+                    clone_with_result(&col1[..half].to_vec()),
+                    clone_with_result(&col2[..half].to_vec()),
+                ],
+                half as u64,
+            )
+            .unwrap();
+
+        // A reloaded jar keeps its dictionaries around, so it can be appended to.
+        let loaded_nippy = NippyJar::load_without_header(file_path.path()).unwrap();
+        let mut writer = NippyJarWriter::new(loaded_nippy).unwrap();
+        writer
+            .append_rows(
+                vec![
+                    clone_with_result(&col1[half..].to_vec()),
+                    clone_with_result(&col2[half..].to_vec()),
+                ],
+                (col1.len() - half) as u64,
+            )
+            .unwrap();
+        writer.commit().unwrap();
+
+        let loaded_nippy = NippyJar::load_without_header(file_path.path()).unwrap();
+        assert_eq!(loaded_nippy.rows, col1.len());
+
+        let mut cursor = NippyJarCursor::new(&loaded_nippy).unwrap();
+        let mut row_index = 0usize;
+        while let Some(row) = cursor.next_row().unwrap() {
+            assert_eq!((row[0], row[1]), (col1[row_index].as_slice(), col2[row_index].as_slice()));
+            row_index += 1;
+        }
+        assert_eq!(row_index, col1.len());
+    }
+
     #[test]
     fn test_lz4() {
         let (col1, col2) = test_data(None);
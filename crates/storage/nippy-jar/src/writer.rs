@@ -1,12 +1,13 @@
 use crate::{
-    compression::Compression, ColumnResult, NippyJar, NippyJarChecker, NippyJarError,
-    NippyJarHeader,
+    compression::{Compression, Compressors, Zstd},
+    ColumnResult, NippyJar, NippyJarChecker, NippyJarError, NippyJarHeader,
 };
 use std::{
     fs::{File, OpenOptions},
     io::{BufWriter, Read, Seek, SeekFrom, Write},
     path::Path,
 };
+use zstd::bulk::Compressor;
 
 /// Size of one offset in bytes.
 pub(crate) const OFFSET_SIZE_BYTES: u8 = 8;
@@ -35,6 +36,8 @@ pub struct NippyJarWriter<H: NippyJarHeader = ()> {
     offsets_file: BufWriter<File>,
     /// Temporary buffer to reuse when compressing data.
     tmp_buf: Vec<u8>,
+    /// Column compressors, if the jar uses `zstd` dictionaries.
+    dictionary_compressors: Option<DictionaryCompressors>,
     /// Used to find the maximum uncompressed size of a row in a jar.
     uncompressed_row_size: usize,
     /// Partial offset list which hasn't been flushed to disk.
@@ -70,11 +73,17 @@ impl<H: NippyJarHeader> NippyJarWriter<H> {
             (jar, data_file.expect("qed"), offsets_file.expect("qed"))
         };
 
+        let dictionary_compressors = match jar.compressor() {
+            Some(Compressors::Zstd(zstd)) => zstd.compressors()?.map(DictionaryCompressors),
+            _ => None,
+        };
+
         let mut writer = Self {
             jar,
             data_file,
             offsets_file,
             tmp_buf: Vec::with_capacity(1_000_000),
+            dictionary_compressors,
             uncompressed_row_size: 0,
             offsets: Vec::with_capacity(1_000_000),
             column: 0,
@@ -227,7 +236,14 @@ impl<H: NippyJarHeader> NippyJarWriter<H> {
     /// Writes column to data file. If it's the last column of the row, call `finalize_row()`
     fn write_column(&mut self, value: &[u8]) -> Result<usize, NippyJarError> {
         self.uncompressed_row_size += value.len();
-        let len = if let Some(compression) = &self.jar.compressor {
+        let len = if let Some(compressors) = &mut self.dictionary_compressors {
+            Zstd::compress_with_dictionary(
+                value,
+                &mut self.tmp_buf,
+                &mut self.data_file,
+                compressors.0.get_mut(self.column),
+            )?
+        } else if let Some(compression) = &self.jar.compressor {
             let before = self.tmp_buf.len();
             let len = compression.compress_to(value, &mut self.tmp_buf)?;
             self.data_file.write_all(&self.tmp_buf[before..before + len])?;
@@ -452,3 +468,12 @@ impl<H: NippyJarHeader> NippyJarWriter<H> {
         &self.jar
     }
 }
+
+/// Per column `zstd` [`Compressor`]s, loaded with the jar dictionaries.
+struct DictionaryCompressors(Vec<Compressor<'static>>);
+
+impl std::fmt::Debug for DictionaryCompressors {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DictionaryCompressors").field("num", &self.0.len()).finish_non_exhaustive()
+    }
+}
@@ -1,11 +1,14 @@
 use crate::{
-    providers::StaticFileProvider, AccountReader, BlockHashReader, BlockIdReader, BlockNumReader,
-    BlockReader, BlockReaderIdExt, BlockSource, CanonChainTracker, CanonStateNotifications,
-    CanonStateSubscriptions, ChainSpecProvider, ChainStateBlockReader, ChangeSetReader,
-    DatabaseProviderFactory, DatabaseProviderRO, EvmEnvProvider, HeaderProvider, ProviderError,
-    ProviderFactory, PruneCheckpointReader, ReceiptProvider, ReceiptProviderIdExt,
-    RequestsProvider, StageCheckpointReader, StateProviderBox, StateProviderFactory, StateReader,
-    StaticFileProviderFactory, TransactionVariant, TransactionsProvider, WithdrawalsProvider,
+    providers::{SnapshotManifest, SnapshotOptions, StaticFileProvider},
+    AccountHistoryReader, AccountReader, BadBlockReader, BlockHashReader, BlockIdReader,
+    BlockNumReader, BlockReader, BlockReaderIdExt, BlockSource, CanonChainTracker,
+    CanonStateNotifications, CanonStateSubscriptions, ChainSpecProvider, ChainStateBlockReader,
+    ChangeSetReader, DatabaseProviderFactory, DatabaseProviderRO, EvmEnvProvider,
+    HashedAccountRange, HashedStateRangeReader, HashedStorageRange, HeaderProvider, ProviderError,
+    ProviderFactory, PruneCheckpointReader, RawDatabaseReader, ReceiptProvider,
+    ReceiptProviderIdExt, RequestsProvider, StageCheckpointReader, StateProviderBox,
+    StateProviderFactory, StateReader, StaticFileProviderFactory, StorageSnapshotProvider,
+    TraceIndexReader, TransactionVariant, TransactionsProvider, WithdrawalsProvider,
 };
 use alloy_eips::{BlockHashOrNumber, BlockId, BlockNumHash, BlockNumberOrTag, HashOrNumber};
 use alloy_primitives::{Address, BlockHash, BlockNumber, Sealable, TxHash, TxNumber, B256, U256};
@@ -15,8 +18,11 @@ use reth_chain_state::{
     MemoryOverlayStateProvider,
 };
 use reth_chainspec::{ChainInfo, EthereumHardforks};
-use reth_db::models::BlockNumberAddress;
-use reth_db_api::models::{AccountBeforeTx, StoredBlockBodyIndices};
+use reth_db::{models::BlockNumberAddress, Tables};
+use reth_db_api::{
+    database_metrics::DatabaseMetadataValue,
+    models::{AccountBeforeTx, StoredBadBlock, StoredBlockBodyIndices},
+};
 use reth_evm::ConfigureEvmEnv;
 use reth_execution_types::{BundleStateInit, ExecutionOutcome, RevertsInit};
 use reth_node_types::NodeTypesWithDB;
@@ -29,6 +35,7 @@ use reth_prune_types::{PruneCheckpoint, PruneSegment};
 use reth_stages_types::{StageCheckpoint, StageId};
 use reth_storage_api::StorageChangeSetReader;
 use reth_storage_errors::provider::ProviderResult;
+use reth_trie::HashedPostState;
 use revm::{
     db::states::PlainStorageRevert,
     primitives::{BlockEnv, CfgEnvWithHandlerCfg},
@@ -36,6 +43,7 @@ use revm::{
 use std::{
     collections::{hash_map, HashMap},
     ops::{Add, Bound, RangeBounds, RangeInclusive, Sub},
+    path::Path,
     sync::Arc,
     time::Instant,
 };
@@ -405,6 +413,28 @@ impl<N: ProviderNodeTypes> BlockchainProvider2<N> {
         Ok(state.state_provider(latest_historical))
     }
 
+    /// Returns the last persisted block the given canonical block builds on and the overlay
+    /// prefixed with the changes of the in-memory blocks up to and including the given block.
+    ///
+    /// If the block is not in memory, the block number and the overlay are returned as is.
+    fn in_memory_state_overlay(
+        &self,
+        block_number: BlockNumber,
+        overlay: HashedPostState,
+    ) -> (BlockNumber, HashedPostState) {
+        let Some(block_state) = self.canonical_in_memory_state.state_by_number(block_number) else {
+            return (block_number, overlay)
+        };
+
+        let mut state = HashedPostState::default();
+        for block_state in block_state.chain().into_iter().rev() {
+            state.extend_ref(block_state.block_ref().hashed_state());
+        }
+        state.extend(overlay);
+
+        (block_state.anchor().number, state)
+    }
+
     /// Fetches data from either in-memory state or persistent storage for a range of transactions.
     ///
     /// * `fetch_from_db`: has a [`DatabaseProviderRO`] and the storage specific range.
@@ -1309,6 +1339,76 @@ impl<N: ProviderNodeTypes> PruneCheckpointReader for BlockchainProvider2<N> {
     }
 }
 
+impl<N: ProviderNodeTypes> BadBlockReader for BlockchainProvider2<N> {
+    fn bad_blocks(&self) -> ProviderResult<Vec<StoredBadBlock>> {
+        self.database.bad_blocks()
+    }
+}
+
+impl<N: ProviderNodeTypes> TraceIndexReader for BlockchainProvider2<N> {
+    fn trace_index_tip(&self) -> ProviderResult<Option<BlockNumber>> {
+        self.database.trace_index_tip()
+    }
+
+    fn trace_index_blocks(
+        &self,
+        addresses: &[Address],
+        range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<Vec<BlockNumber>> {
+        self.database.trace_index_blocks(addresses, range)
+    }
+}
+
+impl<N: ProviderNodeTypes> HashedStateRangeReader for BlockchainProvider2<N> {
+    fn hashed_account_range(
+        &self,
+        block_number: BlockNumber,
+        overlay: HashedPostState,
+        start: B256,
+        limit: usize,
+        with_storage: bool,
+    ) -> ProviderResult<HashedAccountRange> {
+        let (block_number, overlay) = self.in_memory_state_overlay(block_number, overlay);
+        self.database.hashed_account_range(block_number, overlay, start, limit, with_storage)
+    }
+
+    fn hashed_storage_range(
+        &self,
+        block_number: BlockNumber,
+        overlay: HashedPostState,
+        address: Address,
+        start: B256,
+        limit: usize,
+    ) -> ProviderResult<HashedStorageRange> {
+        let (block_number, overlay) = self.in_memory_state_overlay(block_number, overlay);
+        self.database.hashed_storage_range(block_number, overlay, address, start, limit)
+    }
+
+    fn hashed_state_reverts(&self, block_number: BlockNumber) -> ProviderResult<HashedPostState> {
+        self.database.hashed_state_reverts(block_number)
+    }
+}
+
+impl<N: ProviderNodeTypes> RawDatabaseReader for BlockchainProvider2<N> {
+    fn database_metadata(&self) -> ProviderResult<DatabaseMetadataValue> {
+        self.database.database_metadata()
+    }
+
+    fn raw_table_value(&self, table: Tables, key: Vec<u8>) -> ProviderResult<Option<Vec<u8>>> {
+        self.database.raw_table_value(table, key)
+    }
+}
+
+impl<N: ProviderNodeTypes> StorageSnapshotProvider for BlockchainProvider2<N> {
+    fn create_snapshot(
+        &self,
+        dir: &Path,
+        options: SnapshotOptions,
+    ) -> ProviderResult<SnapshotManifest> {
+        self.database.create_snapshot(dir, options)
+    }
+}
+
 impl<N: NodeTypesWithDB> ChainSpecProvider for BlockchainProvider2<N> {
     type ChainSpec = N::ChainSpec;
 
@@ -1633,6 +1733,40 @@ impl<N: ProviderNodeTypes> StorageChangeSetReader for BlockchainProvider2<N> {
     }
 }
 
+impl<N: ProviderNodeTypes> AccountHistoryReader for BlockchainProvider2<N> {
+    fn account_history_blocks(
+        &self,
+        address: Address,
+        range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<Vec<BlockNumber>> {
+        let provider = self.database.provider()?;
+        let last_database_block = provider.last_block_number()?;
+
+        let mut blocks = if *range.start() <= last_database_block {
+            provider.account_history_blocks(
+                address,
+                *range.start()..=last_database_block.min(*range.end()),
+            )?
+        } else {
+            Vec::new()
+        };
+
+        // Blocks that are not persisted yet are not indexed, so we need to look at them directly.
+        for number in (*range.start()).max(last_database_block + 1)..=*range.end() {
+            let Some(state) = self.canonical_in_memory_state.state_by_number(number) else { break };
+            let executed = state.block_ref();
+            let appeared = executed.senders().contains(&address) ||
+                executed.block().body.transactions().any(|tx| tx.to() == Some(address)) ||
+                executed.execution_outcome().bundle.account(&address).is_some();
+            if appeared {
+                blocks.push(number);
+            }
+        }
+
+        Ok(blocks)
+    }
+}
+
 impl<N: ProviderNodeTypes> ChangeSetReader for BlockchainProvider2<N> {
     fn account_block_changeset(
         &self,
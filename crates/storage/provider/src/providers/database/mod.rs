@@ -1,18 +1,32 @@
 use crate::{
-    providers::{state::latest::LatestStateProvider, StaticFileProvider},
+    providers::{
+        create_snapshot, snap_sync_pivot, state::latest::LatestStateProvider, SnapshotManifest,
+        SnapshotOptions, StaticFileProvider,
+    },
     to_range,
     traits::{BlockSource, ReceiptProvider},
-    BlockHashReader, BlockNumReader, BlockReader, ChainSpecProvider, DatabaseProviderFactory,
-    EvmEnvProvider, HeaderProvider, HeaderSyncGap, HeaderSyncGapProvider, ProviderError,
-    PruneCheckpointReader, RequestsProvider, StageCheckpointReader, StateProviderBox,
-    StaticFileProviderFactory, TransactionVariant, TransactionsProvider, WithdrawalsProvider,
+    BadBlockReader, BlockHashReader, BlockNumReader, BlockReader, ChainSpecProvider,
+    DatabaseProviderFactory, EvmEnvProvider, HashedAccountRange, HashedStateRangeReader,
+    HashedStorageRange, HeaderProvider, HeaderSyncGap, HeaderSyncGapProvider, ProviderError,
+    PruneCheckpointReader, RawDatabaseReader, RequestsProvider, StageCheckpointReader,
+    StateProviderBox, StaticFileProviderFactory, StorageSnapshotProvider, TraceIndexReader,
+    TransactionVariant, TransactionsProvider, WithdrawalsProvider,
 };
 use alloy_eips::BlockHashOrNumber;
 use alloy_primitives::{Address, BlockHash, BlockNumber, TxHash, TxNumber, B256, U256};
 use core::fmt;
 use reth_chainspec::{ChainInfo, EthereumHardforks};
-use reth_db::{init_db, mdbx::DatabaseArguments, DatabaseEnv};
-use reth_db_api::{database::Database, models::StoredBlockBodyIndices};
+use reth_db::{
+    init_db, mdbx::DatabaseArguments, DatabaseEnv, RawKey, RawTable, RawValue, TableViewer, Tables,
+};
+use reth_db_api::{
+    database::Database,
+    database_metrics::{DatabaseMetadata, DatabaseMetadataValue},
+    models::{StoredBadBlock, StoredBlockBodyIndices},
+    table::Table,
+    transaction::DbTx,
+    DatabaseError,
+};
 use reth_errors::{RethError, RethResult};
 use reth_evm::ConfigureEvmEnv;
 use reth_node_types::NodeTypesWithDB;
@@ -25,6 +39,7 @@ use reth_prune_types::{PruneCheckpoint, PruneModes, PruneSegment};
 use reth_stages_types::{StageCheckpoint, StageId};
 use reth_storage_api::TryIntoHistoricalStateProvider;
 use reth_storage_errors::provider::ProviderResult;
+use reth_trie::HashedPostState;
 use revm::primitives::{BlockEnv, CfgEnvWithHandlerCfg};
 use std::{
     ops::{RangeBounds, RangeInclusive},
@@ -157,7 +172,12 @@ impl<N: ProviderNodeTypes> ProviderFactory<N> {
     #[track_caller]
     pub fn latest(&self) -> ProviderResult<StateProviderBox> {
         trace!(target: "providers::db", "Returning latest state provider");
-        Ok(Box::new(LatestStateProvider::new(self.db.tx()?, self.static_file_provider())))
+        let tx = self.db.tx()?;
+        let hashed_state = snap_sync_pivot(&tx)?.is_some();
+        Ok(Box::new(
+            LatestStateProvider::new(tx, self.static_file_provider())
+                .with_hashed_state(hashed_state),
+        ))
     }
 
     /// Storage provider for state at that given block
@@ -615,6 +635,92 @@ impl<N: ProviderNodeTypes> PruneCheckpointReader for ProviderFactory<N> {
     }
 }
 
+impl<N: ProviderNodeTypes> BadBlockReader for ProviderFactory<N> {
+    fn bad_blocks(&self) -> ProviderResult<Vec<StoredBadBlock>> {
+        self.provider()?.bad_blocks()
+    }
+}
+
+impl<N: ProviderNodeTypes> RawDatabaseReader for ProviderFactory<N> {
+    fn database_metadata(&self) -> ProviderResult<DatabaseMetadataValue> {
+        Ok(self.db.metadata())
+    }
+
+    fn raw_table_value(&self, table: Tables, key: Vec<u8>) -> ProviderResult<Option<Vec<u8>>> {
+        let provider = self.provider()?;
+        Ok(table.view(&RawValueViewer { tx: provider.tx_ref(), key })?)
+    }
+}
+
+impl<N: ProviderNodeTypes> StorageSnapshotProvider for ProviderFactory<N> {
+    fn create_snapshot(
+        &self,
+        dir: &Path,
+        options: SnapshotOptions,
+    ) -> ProviderResult<SnapshotManifest> {
+        create_snapshot(&self.db, &self.static_file_provider, dir, options)
+    }
+}
+
+/// Looks up the encoded value of an encoded key in any table.
+struct RawValueViewer<'a, TX> {
+    tx: &'a TX,
+    key: Vec<u8>,
+}
+
+impl<TX: DbTx> TableViewer<Option<Vec<u8>>> for RawValueViewer<'_, TX> {
+    type Error = DatabaseError;
+
+    fn view<T: Table>(&self) -> Result<Option<Vec<u8>>, Self::Error> {
+        Ok(self
+            .tx
+            .get::<RawTable<T>>(RawKey::from_vec(self.key.clone()))?
+            .map(RawValue::into_value))
+    }
+}
+
+impl<N: ProviderNodeTypes> TraceIndexReader for ProviderFactory<N> {
+    fn trace_index_tip(&self) -> ProviderResult<Option<BlockNumber>> {
+        self.provider()?.trace_index_tip()
+    }
+
+    fn trace_index_blocks(
+        &self,
+        addresses: &[Address],
+        range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<Vec<BlockNumber>> {
+        self.provider()?.trace_index_blocks(addresses, range)
+    }
+}
+
+impl<N: ProviderNodeTypes> HashedStateRangeReader for ProviderFactory<N> {
+    fn hashed_account_range(
+        &self,
+        block_number: BlockNumber,
+        overlay: HashedPostState,
+        start: B256,
+        limit: usize,
+        with_storage: bool,
+    ) -> ProviderResult<HashedAccountRange> {
+        self.provider()?.hashed_account_range(block_number, overlay, start, limit, with_storage)
+    }
+
+    fn hashed_storage_range(
+        &self,
+        block_number: BlockNumber,
+        overlay: HashedPostState,
+        address: Address,
+        start: B256,
+        limit: usize,
+    ) -> ProviderResult<HashedStorageRange> {
+        self.provider()?.hashed_storage_range(block_number, overlay, address, start, limit)
+    }
+
+    fn hashed_state_reverts(&self, block_number: BlockNumber) -> ProviderResult<HashedPostState> {
+        self.provider()?.hashed_state_reverts(block_number)
+    }
+}
+
 impl<N: NodeTypesWithDB> Clone for ProviderFactory<N> {
     fn clone(&self) -> Self {
         Self {
@@ -632,7 +738,10 @@ mod tests {
     use crate::{
         providers::{StaticFileProvider, StaticFileWriter},
         test_utils::{blocks::TEST_BLOCK, create_test_provider_factory, MockNodeTypesWithDB},
-        BlockHashReader, BlockNumReader, BlockWriter, HeaderSyncGapProvider, TransactionsProvider,
+        writer::UnifiedStorageWriter,
+        BadBlockWriter, BlockHashReader, BlockNumReader, BlockWriter, ChangeSetReader, DBProvider,
+        HeaderSyncGapProvider, StorageChangeSetReader, TraceIndexWriter, TransactionsProvider,
+        MAX_BAD_BLOCKS,
     };
     use alloy_primitives::{TxNumber, B256, U256};
     use assert_matches::assert_matches;
@@ -643,7 +752,12 @@ mod tests {
         tables,
         test_utils::{create_test_static_files_dir, ERROR_TEMPDIR},
     };
-    use reth_primitives::StaticFileSegment;
+    use reth_db_api::{
+        models::{AccountBeforeTx, BlockNumberAddress},
+        table::Encode,
+        transaction::DbTxMut,
+    };
+    use reth_primitives::{Account, StaticFileSegment, StorageEntry};
     use reth_prune_types::{PruneMode, PruneModes};
     use reth_storage_errors::provider::ProviderError;
     use reth_testing_utils::generators::{self, random_block, random_header, BlockParams};
@@ -778,6 +892,176 @@ mod tests {
         }
     }
 
+    #[test]
+    fn save_bad_blocks() {
+        let factory = create_test_provider_factory();
+
+        let mut rng = generators::rng();
+        let blocks = (0..MAX_BAD_BLOCKS as u64 + 2)
+            .map(|number| {
+                random_block(
+                    &mut rng,
+                    number,
+                    BlockParams { tx_count: Some(1), ..Default::default() },
+                )
+            })
+            .collect::<Vec<_>>();
+
+        for block in &blocks {
+            let provider = factory.provider_rw().unwrap();
+            provider
+                .save_bad_block(block.clone(), format!("invalid block {}", block.number))
+                .unwrap();
+            // saving the same block twice is a no-op
+            provider.save_bad_block(block.clone(), "duplicate".to_string()).unwrap();
+            provider.commit().unwrap();
+        }
+
+        // only the most recent bad blocks are kept
+        let bad_blocks = factory.bad_blocks().unwrap();
+        assert_eq!(bad_blocks.len(), MAX_BAD_BLOCKS);
+        for (bad_block, block) in bad_blocks.iter().zip(&blocks[2..]) {
+            assert_eq!(&bad_block.block, block);
+            assert_eq!(bad_block.error, format!("invalid block {}", block.number));
+        }
+    }
+
+    #[test]
+    fn insert_and_unwind_trace_index() {
+        let factory = create_test_provider_factory();
+        let (alice, bob) = (Address::with_last_byte(1), Address::with_last_byte(2));
+
+        // the index is not maintained until something was inserted
+        assert_eq!(factory.trace_index_tip().unwrap(), None);
+
+        let provider = factory.provider_rw().unwrap();
+        provider
+            .insert_trace_index([(alice, vec![1, 3]), (bob, vec![2, 3])].into_iter().collect(), 3)
+            .unwrap();
+        provider.insert_trace_index([(alice, vec![5])].into_iter().collect(), 5).unwrap();
+        provider.commit().unwrap();
+
+        assert_eq!(factory.trace_index_tip().unwrap(), Some(5));
+        assert_eq!(factory.trace_index_blocks(&[alice], 0..=5).unwrap(), vec![1, 3, 5]);
+        assert_eq!(factory.trace_index_blocks(&[alice, bob], 2..=4).unwrap(), vec![2, 3]);
+
+        let provider = factory.provider_rw().unwrap();
+        provider.unwind_trace_index([alice, bob], 2).unwrap();
+        provider.commit().unwrap();
+
+        assert_eq!(factory.trace_index_tip().unwrap(), Some(2));
+        assert_eq!(factory.trace_index_blocks(&[alice, bob], 0..=5).unwrap(), vec![1, 2]);
+    }
+
+    #[test]
+    fn raw_table_value_and_metadata() {
+        let factory = create_test_provider_factory();
+        let hash = B256::with_last_byte(1);
+
+        let provider = factory.provider_rw().unwrap();
+        provider.tx_ref().put::<tables::CanonicalHeaders>(1, hash).unwrap();
+        provider.commit().unwrap();
+
+        assert_eq!(
+            factory.raw_table_value(Tables::CanonicalHeaders, 1u64.encode().to_vec()).unwrap(),
+            Some(hash.to_vec())
+        );
+        assert_eq!(
+            factory.raw_table_value(Tables::CanonicalHeaders, 2u64.encode().to_vec()).unwrap(),
+            None
+        );
+
+        let metadata = factory.database_metadata().unwrap();
+        assert!(metadata.page_size().is_some());
+        let stats = metadata
+            .table_stats()
+            .iter()
+            .find(|stats| stats.name == tables::CanonicalHeaders::NAME)
+            .unwrap();
+        assert_eq!(stats.entries, 1);
+    }
+
+    #[test]
+    fn restore_static_file_changesets() {
+        let factory = create_test_provider_factory();
+        let static_file_provider = factory.static_file_provider();
+        let account_changes = |block: u64| {
+            [1, 2].map(|index| AccountBeforeTx {
+                address: Address::with_last_byte(index),
+                info: Some(Account { nonce: block, ..Default::default() }),
+            })
+        };
+        let storage_change = |block: u64| {
+            (
+                BlockNumberAddress((block, Address::with_last_byte(1))),
+                StorageEntry { key: B256::with_last_byte(1), value: U256::from(block) },
+            )
+        };
+
+        // Changesets of blocks `0..=29` are in static files, the ones of blocks `30..=34` in the
+        // database.
+        let mut account_writer =
+            static_file_provider.latest_writer(StaticFileSegment::AccountChangeSets).unwrap();
+        let mut storage_writer =
+            static_file_provider.latest_writer(StaticFileSegment::StorageChangeSets).unwrap();
+        for block in 0..30 {
+            account_writer.increment_block(block).unwrap();
+            account_writer.append_account_changeset(block, account_changes(block)).unwrap();
+
+            let (key, entry) = storage_change(block);
+            storage_writer.increment_block(block).unwrap();
+            storage_writer.append_storage_changeset(block, [(key.address(), entry)]).unwrap();
+        }
+        account_writer.commit().unwrap();
+        storage_writer.commit().unwrap();
+        drop(account_writer);
+        drop(storage_writer);
+
+        let provider = factory.provider_rw().unwrap();
+        for block in 30..35 {
+            for change in account_changes(block) {
+                provider.tx_ref().put::<tables::AccountChangeSets>(block, change).unwrap();
+            }
+            let (key, entry) = storage_change(block);
+            provider.tx_ref().put::<tables::StorageChangeSets>(key, entry).unwrap();
+        }
+        provider.commit().unwrap();
+
+        // Batches are restored starting from the highest static file block, and each of them is
+        // committed on its own.
+        let assert_highest_static_file_block = |expected| {
+            for segment in
+                [StaticFileSegment::AccountChangeSets, StaticFileSegment::StorageChangeSets]
+            {
+                assert_eq!(static_file_provider.get_highest_static_file_block(segment), expected);
+            }
+        };
+        for (done, highest_static_file_block) in [(false, 19), (true, 11), (true, 11)] {
+            let provider = factory.provider_rw().unwrap();
+            assert_eq!(provider.restore_static_file_changesets_batch(12, 10).unwrap(), done);
+            UnifiedStorageWriter::commit_unwind(provider, static_file_provider.clone()).unwrap();
+            assert_highest_static_file_block(Some(highest_static_file_block));
+        }
+
+        let provider = factory.provider().unwrap();
+        assert_eq!(provider.tx_ref().entries::<tables::AccountChangeSets>().unwrap(), 2 * 23);
+        assert_eq!(provider.tx_ref().entries::<tables::StorageChangeSets>().unwrap(), 23);
+        for block in 0..35 {
+            assert_eq!(provider.account_block_changeset(block).unwrap(), account_changes(block));
+            assert_eq!(provider.storage_changeset(block).unwrap(), vec![storage_change(block)]);
+        }
+
+        // Without a batch limit, all remaining blocks are restored at once.
+        let provider = factory.provider_rw().unwrap();
+        provider.restore_static_file_changesets(5).unwrap();
+        UnifiedStorageWriter::commit_unwind(provider, static_file_provider.clone()).unwrap();
+        assert_highest_static_file_block(Some(4));
+        assert_eq!(
+            factory.provider().unwrap().tx_ref().entries::<tables::AccountChangeSets>().unwrap(),
+            2 * 30
+        );
+    }
+
     #[test]
     fn header_sync_gap_lookup() {
         let factory = create_test_provider_factory();
@@ -1,20 +1,25 @@
 use crate::{
     bundle_state::StorageRevertsIter,
-    providers::{database::metrics, static_file::StaticFileWriter, StaticFileProvider},
+    providers::{
+        database::metrics, snap_sync_pivot, static_file::StaticFileWriter, StaticFileProvider,
+    },
     to_range,
     traits::{
-        AccountExtReader, BlockSource, ChangeSetReader, ReceiptProvider, StageCheckpointWriter,
+        AccountExtReader, AccountHistoryReader, BlockSource, ChangeSetReader, HashedAccountRange,
+        HashedStateRangeReader, HashedStorageRange, ReceiptProvider, StageCheckpointWriter,
+        TraceIndexReader, TraceIndexWriter, TRACE_INDEX_ID,
     },
     writer::UnifiedStorageWriter,
-    AccountReader, BlockExecutionReader, BlockExecutionWriter, BlockHashReader, BlockNumReader,
-    BlockReader, BlockWriter, BundleStateInit, ChainStateBlockReader, ChainStateBlockWriter,
-    DBProvider, EvmEnvProvider, HashingWriter, HeaderProvider, HeaderSyncGap,
-    HeaderSyncGapProvider, HistoricalStateProvider, HistoricalStateProviderRef, HistoryWriter,
-    LatestStateProvider, LatestStateProviderRef, OriginalValuesKnown, ProviderError,
-    PruneCheckpointReader, PruneCheckpointWriter, RequestsProvider, RevertsInit,
-    StageCheckpointReader, StateChangeWriter, StateProviderBox, StateReader, StateWriter,
-    StaticFileProviderFactory, StatsReader, StorageReader, StorageTrieWriter, TransactionVariant,
-    TransactionsProvider, TransactionsProviderExt, TrieWriter, WithdrawalsProvider,
+    AccountReader, BadBlockReader, BadBlockWriter, BlockExecutionReader, BlockExecutionWriter,
+    BlockHashReader, BlockNumReader, BlockReader, BlockWriter, BundleStateInit,
+    ChainStateBlockReader, ChainStateBlockWriter, DBProvider, EvmEnvProvider, HashingWriter,
+    HeaderProvider, HeaderSyncGap, HeaderSyncGapProvider, HistoricalStateProvider,
+    HistoricalStateProviderRef, HistoryWriter, LatestStateProvider, LatestStateProviderRef,
+    OriginalValuesKnown, ProviderError, PruneCheckpointReader, PruneCheckpointWriter,
+    RequestsProvider, RevertsInit, StageCheckpointReader, StateChangeWriter, StateProviderBox,
+    StateReader, StateWriter, StaticFileProviderFactory, StatsReader, StorageReader,
+    StorageTrieWriter, TransactionVariant, TransactionsProvider, TransactionsProviderExt,
+    TrieWriter, WithdrawalsProvider, MAX_BAD_BLOCKS,
 };
 use alloy_eips::BlockHashOrNumber;
 use alloy_primitives::{keccak256, Address, BlockHash, BlockNumber, TxHash, TxNumber, B256, U256};
@@ -22,7 +27,7 @@ use itertools::{izip, Itertools};
 use rayon::slice::ParallelSliceMut;
 use reth_chainspec::{ChainInfo, ChainSpecProvider, EthChainSpec, EthereumHardforks};
 use reth_db::{
-    cursor::DbDupCursorRW, tables, BlockNumberList, PlainAccountState, PlainStorageState,
+    cursor::DbDupCursorRW, tables, BlockNumberList, PlainAccountState, PlainStorageState, RawTable,
 };
 use reth_db_api::{
     common::KeyValue,
@@ -30,12 +35,14 @@ use reth_db_api::{
     database::Database,
     models::{
         sharded_key, storage_sharded_key::StorageShardedKey, AccountBeforeTx, BlockNumberAddress,
-        ShardedKey, StoredBlockBodyIndices, StoredBlockOmmers, StoredBlockWithdrawals,
+        ShardedKey, StoredBadBlock, StoredBlockBodyIndices, StoredBlockOmmers,
+        StoredBlockWithdrawals,
     },
     table::Table,
     transaction::{DbTx, DbTxMut},
     DatabaseError, DbTxUnwindExt,
 };
+use reth_era::Era1Block;
 use reth_evm::ConfigureEvmEnv;
 use reth_execution_types::{Chain, ExecutionOutcome};
 use reth_network_p2p::headers::downloader::SyncTarget;
@@ -52,7 +59,7 @@ use reth_storage_errors::provider::{ProviderResult, RootMismatch};
 use reth_trie::{
     prefix_set::{PrefixSet, PrefixSetMut, TriePrefixSets},
     updates::{StorageTrieUpdates, TrieUpdates},
-    HashedPostStateSorted, Nibbles, StateRoot, StoredNibbles,
+    HashedPostState, HashedPostStateSorted, Nibbles, StateRoot, StoredNibbles,
 };
 use reth_trie_db::{DatabaseStateRoot, DatabaseStorageTrieCursor};
 use revm::{
@@ -149,7 +156,10 @@ impl<TX: DbTx, Spec: Send + Sync> DatabaseProvider<TX, Spec> {
     /// State provider for latest block
     pub fn latest<'a>(&'a self) -> ProviderResult<Box<dyn StateProvider + 'a>> {
         trace!(target: "providers::db", "Returning latest state provider");
-        Ok(Box::new(LatestStateProviderRef::new(&self.tx, self.static_file_provider.clone())))
+        Ok(Box::new(
+            LatestStateProviderRef::new(&self.tx, self.static_file_provider.clone())
+                .with_hashed_state(snap_sync_pivot(&self.tx)?.is_some()),
+        ))
     }
 
     /// Storage provider for state at that given block hash
@@ -162,44 +172,59 @@ impl<TX: DbTx, Spec: Send + Sync> DatabaseProvider<TX, Spec> {
         if block_number == self.best_block_number().unwrap_or_default() &&
             block_number == self.last_block_number().unwrap_or_default()
         {
-            return Ok(Box::new(LatestStateProviderRef::new(
-                &self.tx,
-                self.static_file_provider.clone(),
-            )))
+            return Ok(Box::new(
+                LatestStateProviderRef::new(&self.tx, self.static_file_provider.clone())
+                    .with_hashed_state(snap_sync_pivot(&self.tx)?.is_some()),
+            ))
         }
 
         // +1 as the changeset that we want is the one that was applied after this block.
         block_number += 1;
 
+        Ok(Box::new(self.history_ref_at(block_number)?))
+    }
+
+    /// Returns a [`HistoricalStateProviderRef`] for the state at the start of the given block,
+    /// capped at the prune checkpoints of the history segments.
+    fn history_ref_at(
+        &self,
+        block_number: BlockNumber,
+    ) -> ProviderResult<HistoricalStateProviderRef<'_, TX>> {
         let account_history_prune_checkpoint =
             self.get_prune_checkpoint(PruneSegment::AccountHistory)?;
         let storage_history_prune_checkpoint =
             self.get_prune_checkpoint(PruneSegment::StorageHistory)?;
 
+        let snap_sync_pivot = snap_sync_pivot(&self.tx)?;
+
         let mut state_provider = HistoricalStateProviderRef::new(
             &self.tx,
             block_number,
             self.static_file_provider.clone(),
-        );
+        )
+        .with_hashed_state(snap_sync_pivot.is_some());
 
         // If we pruned account or storage history, we can't return state on every historical block.
         // Instead, we should cap it at the latest prune checkpoint for corresponding prune segment.
-        if let Some(prune_checkpoint_block_number) =
-            account_history_prune_checkpoint.and_then(|checkpoint| checkpoint.block_number)
+        // The same applies to the pivot block of snap sync, as there is no history before it.
+        if let Some(prune_checkpoint_block_number) = account_history_prune_checkpoint
+            .and_then(|checkpoint| checkpoint.block_number)
+            .max(snap_sync_pivot)
         {
             state_provider = state_provider.with_lowest_available_account_history_block_number(
                 prune_checkpoint_block_number + 1,
             );
         }
-        if let Some(prune_checkpoint_block_number) =
-            storage_history_prune_checkpoint.and_then(|checkpoint| checkpoint.block_number)
+        if let Some(prune_checkpoint_block_number) = storage_history_prune_checkpoint
+            .and_then(|checkpoint| checkpoint.block_number)
+            .max(snap_sync_pivot)
         {
             state_provider = state_provider.with_lowest_available_storage_history_block_number(
                 prune_checkpoint_block_number + 1,
             );
         }
 
-        Ok(Box::new(state_provider))
+        Ok(state_provider)
     }
 }
 
@@ -248,7 +273,11 @@ impl<TX: DbTx + 'static, Spec: Send + Sync> TryIntoHistoricalStateProvider
         if block_number == self.best_block_number().unwrap_or_default() &&
             block_number == self.last_block_number().unwrap_or_default()
         {
-            return Ok(Box::new(LatestStateProvider::new(self.tx, self.static_file_provider)))
+            let hashed_state = snap_sync_pivot(&self.tx)?.is_some();
+            return Ok(Box::new(
+                LatestStateProvider::new(self.tx, self.static_file_provider)
+                    .with_hashed_state(hashed_state),
+            ))
         }
 
         // +1 as the changeset that we want is the one that was applied after this block.
@@ -259,20 +288,26 @@ impl<TX: DbTx + 'static, Spec: Send + Sync> TryIntoHistoricalStateProvider
         let storage_history_prune_checkpoint =
             self.get_prune_checkpoint(PruneSegment::StorageHistory)?;
 
+        let snap_sync_pivot = snap_sync_pivot(&self.tx)?;
+
         let mut state_provider =
-            HistoricalStateProvider::new(self.tx, block_number, self.static_file_provider);
+            HistoricalStateProvider::new(self.tx, block_number, self.static_file_provider)
+                .with_hashed_state(snap_sync_pivot.is_some());
 
         // If we pruned account or storage history, we can't return state on every historical block.
         // Instead, we should cap it at the latest prune checkpoint for corresponding prune segment.
-        if let Some(prune_checkpoint_block_number) =
-            account_history_prune_checkpoint.and_then(|checkpoint| checkpoint.block_number)
+        // The same applies to the pivot block of snap sync, as there is no history before it.
+        if let Some(prune_checkpoint_block_number) = account_history_prune_checkpoint
+            .and_then(|checkpoint| checkpoint.block_number)
+            .max(snap_sync_pivot)
         {
             state_provider = state_provider.with_lowest_available_account_history_block_number(
                 prune_checkpoint_block_number + 1,
             );
         }
-        if let Some(prune_checkpoint_block_number) =
-            storage_history_prune_checkpoint.and_then(|checkpoint| checkpoint.block_number)
+        if let Some(prune_checkpoint_block_number) = storage_history_prune_checkpoint
+            .and_then(|checkpoint| checkpoint.block_number)
+            .max(snap_sync_pivot)
         {
             state_provider = state_provider.with_lowest_available_storage_history_block_number(
                 prune_checkpoint_block_number + 1,
@@ -397,6 +432,40 @@ impl<TX: DbTx, Spec: Send + Sync> DatabaseProvider<TX, Spec> {
         &self.chain_spec
     }
 
+    /// Returns `true` if the optional transaction appearances index is maintained, which is the
+    /// case once the [`StageId::IndexTransactionAppearances`] stage has a checkpoint.
+    fn is_transaction_appearances_indexed(&self) -> ProviderResult<bool> {
+        Ok(self.get_stage_checkpoint(StageId::IndexTransactionAppearances)?.is_some())
+    }
+
+    /// Serves an expired block from the configured Era1 history, if any.
+    ///
+    /// Returns the original error unless it is [`ProviderError::HistoryExpired`] and the Era1
+    /// block matches the canonical hash known to the database.
+    fn era1_fallback<T>(
+        &self,
+        number: BlockNumber,
+        err: ProviderError,
+        f: impl FnOnce(Era1Block) -> ProviderResult<Option<T>>,
+    ) -> ProviderResult<Option<T>> {
+        let ProviderError::HistoryExpired(_) = err else { return Err(err) };
+        let Some(era1_history) = self.static_file_provider.era1_history() else { return Err(err) };
+
+        let block = match era1_history.block(number) {
+            Ok(Some(block)) => block,
+            Ok(None) => return Err(err),
+            Err(error) => {
+                warn!(target: "providers::db", %number, %error, "Failed to read expired block from Era1 file");
+                return Err(err)
+            }
+        };
+        if self.tx.get::<tables::HeaderNumbers>(block.block.hash())? != Some(number) {
+            return Err(err)
+        }
+
+        f(block)
+    }
+
     /// Disables long-lived read transaction safety guarantees for leaks prevention and
     /// observability improvements.
     ///
@@ -443,6 +512,38 @@ impl<TX: DbTx, Spec: Send + Sync> DatabaseProvider<TX, Spec> {
         self.cursor_collect_with_capacity(&mut cursor, range, capacity)
     }
 
+    /// Collects the block numbers in the given range from the history shards of an address.
+    fn history_blocks_in_range<T>(
+        &self,
+        address: Address,
+        range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<Vec<BlockNumber>>
+    where
+        T: Table<Key = ShardedKey<Address>, Value = BlockNumberList>,
+    {
+        let mut blocks = Vec::new();
+        // The first shard that can contain the start of the range is the first one with a highest
+        // block number greater than or equal to it.
+        let mut cursor = self.tx.cursor_read::<T>()?;
+        for entry in cursor.walk(Some(ShardedKey::new(address, *range.start())))? {
+            let (sharded_key, list) = entry?;
+            if sharded_key.key != address {
+                break
+            }
+
+            blocks.extend(
+                list.iter()
+                    .skip_while(|block| block < range.start())
+                    .take_while(|block| block <= range.end()),
+            );
+
+            if sharded_key.highest_block_number >= *range.end() {
+                break
+            }
+        }
+        Ok(blocks)
+    }
+
     /// Iterates over read only values in the given table and collects them into a vector.
     fn cursor_collect<T: Table<Key = u64>>(
         &self,
@@ -524,7 +625,27 @@ impl<TX: DbTx, Spec: Send + Sync> DatabaseProvider<TX, Spec> {
         let (transactions, senders) = if tx_range.is_empty() {
             (vec![], vec![])
         } else {
-            (self.transactions_by_tx_range(tx_range.clone())?, self.senders_by_tx_range(tx_range)?)
+            match self
+                .transactions_by_tx_range(tx_range.clone())
+                .and_then(|transactions| Ok((transactions, self.senders_by_tx_range(tx_range)?)))
+            {
+                Ok(transactions_and_senders) => transactions_and_senders,
+                Err(err) => {
+                    // Senders of expired blocks are not stored, so they are recovered from the
+                    // Era1 block instead.
+                    let Some((transactions, senders)) =
+                        self.era1_fallback(block_number, err, |block| {
+                            let body = block.block.body;
+                            let senders =
+                                body.recover_signers().ok_or(ProviderError::SenderRecoveryError)?;
+                            Ok(Some((body.transactions, senders)))
+                        })?
+                    else {
+                        return Ok(None)
+                    };
+                    (transactions, senders)
+                }
+            }
         };
 
         let body = transactions
@@ -867,6 +988,94 @@ impl<TX: DbTx, Spec: Send + Sync> DatabaseProvider<TX, Spec> {
         Ok(blocks)
     }
 
+    /// Returns an iterator over the account changesets of a block range. Blocks which were moved
+    /// to static files are read from there, while the rest is read from the database.
+    fn account_changesets_in_range(
+        &self,
+        range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<impl Iterator<Item = ProviderResult<(BlockNumber, AccountBeforeTx)>> + '_>
+    {
+        let (start, end) = range.into_inner();
+        let mut static_file_changesets = None;
+        let mut db_start = start;
+
+        if let Some(highest_static_file_block) = self
+            .static_file_provider
+            .get_highest_static_file_block(StaticFileSegment::AccountChangeSets)
+            .filter(|highest| start <= *highest)
+        {
+            static_file_changesets = Some(
+                self.static_file_provider
+                    .account_changesets_iter(start..=end.min(highest_static_file_block))?,
+            );
+            db_start = highest_static_file_block + 1;
+        }
+
+        let db_changesets = (db_start <= end)
+            .then(|| {
+                self.walk_from::<tables::AccountChangeSets>(db_start, move |block| *block <= end)
+            })
+            .transpose()?;
+
+        Ok(static_file_changesets.into_iter().flatten().chain(db_changesets.into_iter().flatten()))
+    }
+
+    /// Returns an iterator over the storage changesets of a block range. Blocks which were moved
+    /// to static files are read from there, while the rest is read from the database.
+    fn storage_changesets_in_range(
+        &self,
+        range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<impl Iterator<Item = ProviderResult<(BlockNumberAddress, StorageEntry)>> + '_>
+    {
+        let (start, end) = range.into_inner();
+        let mut static_file_changesets = None;
+        let mut db_start = start;
+
+        if let Some(highest_static_file_block) = self
+            .static_file_provider
+            .get_highest_static_file_block(StaticFileSegment::StorageChangeSets)
+            .filter(|highest| start <= *highest)
+        {
+            static_file_changesets = Some(
+                self.static_file_provider
+                    .storage_changesets_iter(start..=end.min(highest_static_file_block))?,
+            );
+            db_start = highest_static_file_block + 1;
+        }
+
+        let db_changesets = (db_start <= end)
+            .then(|| {
+                self.walk_from::<tables::StorageChangeSets>(
+                    BlockNumberAddress((db_start, Address::ZERO)),
+                    move |key| key.block_number() <= end,
+                )
+            })
+            .transpose()?;
+
+        Ok(static_file_changesets.into_iter().flatten().chain(db_changesets.into_iter().flatten()))
+    }
+
+    /// Returns an iterator over the entries of a table, starting at `start` and for as long as
+    /// `in_range` holds for their keys. Unlike a [`Walker`](reth_db_api::cursor::Walker), the
+    /// iterator owns its cursor.
+    fn walk_from<T: Table>(
+        &self,
+        start: T::Key,
+        in_range: impl Fn(&T::Key) -> bool,
+    ) -> ProviderResult<impl Iterator<Item = ProviderResult<(T::Key, T::Value)>>> {
+        let mut cursor = self.tx.cursor_read::<T>()?;
+        let mut next = cursor.seek(start).transpose();
+        Ok(std::iter::from_fn(move || {
+            let entry = match next.take()? {
+                Ok((key, _)) if !in_range(&key) => return None,
+                Ok(entry) => entry,
+                Err(err) => return Some(Err(err.into())),
+            };
+            next = cursor.next().transpose();
+            Some(Ok(entry))
+        }))
+    }
+
     /// Return the last N blocks of state, recreating the [`ExecutionOutcome`].
     ///
     /// 1. Iterate over the [`BlockBodyIndices`][tables::BlockBodyIndices] table to get all the
@@ -910,10 +1119,10 @@ impl<TX: DbTx, Spec: Send + Sync> DatabaseProvider<TX, Spec> {
             return Ok(None)
         };
 
-        let storage_range = BlockNumberAddress::range(range.clone());
-
-        let storage_changeset = self.get::<tables::StorageChangeSets>(storage_range)?;
-        let account_changeset = self.get::<tables::AccountChangeSets>(range)?;
+        let storage_changeset =
+            self.storage_changesets_in_range(range.clone())?.collect::<ProviderResult<_>>()?;
+        let account_changeset =
+            self.account_changesets_in_range(range)?.collect::<ProviderResult<_>>()?;
 
         // This is not working for blocks that are not at tip. as plain state is not the last
         // state of end range. We should rename the functions or add support to access
@@ -1075,6 +1284,127 @@ impl<TX: DbTxMut + DbTx, Spec: Send + Sync> DatabaseProvider<TX, Spec> {
         Ok(items)
     }
 
+    /// Moves the account and storage changesets of all blocks starting at `from_block` from
+    /// static files back into the database, so they can be unwound like any other changeset.
+    ///
+    /// The static file rows are only pruned once the static file provider is committed.
+    pub fn restore_static_file_changesets(&self, from_block: BlockNumber) -> ProviderResult<()> {
+        self.restore_static_file_changesets_batch(from_block, u64::MAX).map(drop)
+    }
+
+    /// Moves the account and storage changesets of at most `max_blocks` blocks from static files
+    /// back into the database, starting from the highest static file block and going down to
+    /// `from_block`.
+    ///
+    /// Returns `true` once static files no longer hold changesets of `from_block` or later. Since
+    /// the static file rows are only pruned once the static file provider is committed, both the
+    /// database and the static files have to be committed before restoring the next batch.
+    pub fn restore_static_file_changesets_batch(
+        &self,
+        from_block: BlockNumber,
+        max_blocks: u64,
+    ) -> ProviderResult<bool> {
+        /// Returns the highest block that has to be restored, if any.
+        fn restore_to(
+            first_db_block: Option<BlockNumber>,
+            highest_static_file_block: BlockNumber,
+        ) -> Option<BlockNumber> {
+            match first_db_block {
+                Some(first_db_block) => {
+                    first_db_block.checked_sub(1).map(|block| block.min(highest_static_file_block))
+                }
+                None => Some(highest_static_file_block),
+            }
+        }
+
+        /// Returns the lowest block of the batch ending at `highest_static_file_block`.
+        fn batch_from(
+            from_block: BlockNumber,
+            highest_static_file_block: BlockNumber,
+            max_blocks: u64,
+        ) -> BlockNumber {
+            from_block.max(highest_static_file_block.saturating_sub(max_blocks.saturating_sub(1)))
+        }
+
+        let mut done = true;
+
+        let segment = StaticFileSegment::AccountChangeSets;
+        if let Some(highest_static_file_block) = self
+            .static_file_provider
+            .get_highest_static_file_block(segment)
+            .filter(|highest| *highest >= from_block)
+        {
+            let batch_from = batch_from(from_block, highest_static_file_block, max_blocks);
+            done &= batch_from == from_block;
+
+            // The database already holds every block starting from its first changeset block.
+            let first_db_block = self
+                .tx
+                .cursor_read::<tables::AccountChangeSets>()?
+                .first()?
+                .map(|(block, _)| block);
+            if let Some(restore_to) = restore_to(first_db_block, highest_static_file_block) {
+                for change in
+                    self.static_file_provider.account_changesets_iter(batch_from..=restore_to)?
+                {
+                    let (block, change) = change?;
+                    self.tx.put::<tables::AccountChangeSets>(block, change)?;
+                }
+            }
+
+            let to_delete = self.static_file_changes_from_block(segment, batch_from)?;
+            self.static_file_provider
+                .latest_writer(segment)?
+                .prune_changesets(to_delete, batch_from.saturating_sub(1))?;
+        }
+
+        let segment = StaticFileSegment::StorageChangeSets;
+        if let Some(highest_static_file_block) = self
+            .static_file_provider
+            .get_highest_static_file_block(segment)
+            .filter(|highest| *highest >= from_block)
+        {
+            let batch_from = batch_from(from_block, highest_static_file_block, max_blocks);
+            done &= batch_from == from_block;
+
+            // The database already holds every block starting from its first changeset block.
+            let first_db_block = self
+                .tx
+                .cursor_read::<tables::StorageChangeSets>()?
+                .first()?
+                .map(|(key, _)| key.block_number());
+            if let Some(restore_to) = restore_to(first_db_block, highest_static_file_block) {
+                for change in
+                    self.static_file_provider.storage_changesets_iter(batch_from..=restore_to)?
+                {
+                    let (key, entry) = change?;
+                    self.tx.put::<tables::StorageChangeSets>(key, entry)?;
+                }
+            }
+
+            let to_delete = self.static_file_changes_from_block(segment, batch_from)?;
+            self.static_file_provider
+                .latest_writer(segment)?
+                .prune_changesets(to_delete, batch_from.saturating_sub(1))?;
+        }
+
+        Ok(done)
+    }
+
+    /// Returns the number of changes stored in static files for `from_block` and all blocks
+    /// after it.
+    fn static_file_changes_from_block(
+        &self,
+        segment: StaticFileSegment,
+        from_block: BlockNumber,
+    ) -> ProviderResult<u64> {
+        let next_change = self
+            .static_file_provider
+            .get_highest_static_file_tx(segment)
+            .map_or(0, |change| change + 1);
+        Ok(next_change - self.static_file_provider.changes_before_block(segment, from_block)?)
+    }
+
     /// Remove requested block transactions, without returning them.
     ///
     /// This will remove block data for the given range from the following tables:
@@ -1434,12 +1764,13 @@ impl<TX: DbTx, Spec: Send + Sync> AccountExtReader for DatabaseProvider<TX, Spec
         &self,
         range: impl RangeBounds<BlockNumber>,
     ) -> ProviderResult<BTreeSet<Address>> {
-        self.tx
-            .cursor_read::<tables::AccountChangeSets>()?
-            .walk_range(range)?
-            .map(|entry| {
-                entry.map(|(_, account_before)| account_before.address).map_err(Into::into)
-            })
+        let range = to_range(range);
+        if range.is_empty() {
+            return Ok(BTreeSet::new())
+        }
+
+        self.account_changesets_in_range(range.start..=range.end - 1)?
+            .map(|change| change.map(|(_, account_before)| account_before.address))
             .collect()
     }
 
@@ -1458,19 +1789,52 @@ impl<TX: DbTx, Spec: Send + Sync> AccountExtReader for DatabaseProvider<TX, Spec
         &self,
         range: RangeInclusive<BlockNumber>,
     ) -> ProviderResult<BTreeMap<Address, Vec<u64>>> {
-        let mut changeset_cursor = self.tx.cursor_read::<tables::AccountChangeSets>()?;
-
-        let account_transitions = changeset_cursor.walk_range(range)?.try_fold(
-            BTreeMap::new(),
-            |mut accounts: BTreeMap<Address, Vec<u64>>, entry| -> ProviderResult<_> {
-                let (index, account) = entry?;
-                accounts.entry(account.address).or_default().push(index);
-                Ok(accounts)
-            },
-        )?;
+        let mut account_transitions = BTreeMap::<Address, Vec<u64>>::new();
+        for change in self.account_changesets_in_range(range)? {
+            let (index, account) = change?;
+            account_transitions.entry(account.address).or_default().push(index);
+        }
 
         Ok(account_transitions)
     }
+
+    fn transaction_appearances_with_range(
+        &self,
+        range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<BTreeMap<Address, Vec<u64>>> {
+        let mut appearances = BTreeMap::<Address, Vec<u64>>::new();
+        let mut body_cursor = self.tx.cursor_read::<tables::BlockBodyIndices>()?;
+        let mut tx_cursor = self.tx.cursor_read::<tables::Transactions>()?;
+
+        for entry in body_cursor.walk_range(range)? {
+            let (block_number, body) = entry?;
+            let tx_num_range = body.tx_num_range();
+            if tx_num_range.is_empty() {
+                continue
+            }
+
+            let transactions =
+                self.transactions_by_tx_range_with_cursor(tx_num_range.clone(), &mut tx_cursor)?;
+            let mut senders = self.senders_by_tx_range(tx_num_range)?;
+            // Senders might be pruned, in which case we need to recover them.
+            if senders.len() != transactions.len() {
+                senders =
+                    TransactionSignedNoHash::recover_signers(&transactions, transactions.len())
+                        .ok_or(ProviderError::SenderRecoveryError)?;
+            }
+
+            for (transaction, sender) in transactions.iter().zip(senders) {
+                for address in std::iter::once(sender).chain(transaction.to()) {
+                    let blocks = appearances.entry(address).or_default();
+                    if blocks.last() != Some(&block_number) {
+                        blocks.push(block_number);
+                    }
+                }
+            }
+        }
+
+        Ok(appearances)
+    }
 }
 
 impl<TX: DbTx, Spec: Send + Sync> StorageChangeSetReader for DatabaseProvider<TX, Spec> {
@@ -1478,13 +1842,94 @@ impl<TX: DbTx, Spec: Send + Sync> StorageChangeSetReader for DatabaseProvider<TX
         &self,
         block_number: BlockNumber,
     ) -> ProviderResult<Vec<(BlockNumberAddress, StorageEntry)>> {
-        let range = block_number..=block_number;
-        let storage_range = BlockNumberAddress::range(range);
-        self.tx
-            .cursor_dup_read::<tables::StorageChangeSets>()?
-            .walk_range(storage_range)?
-            .map(|result| -> ProviderResult<_> { Ok(result?) })
-            .collect()
+        self.storage_changesets_in_range(block_number..=block_number)?.collect()
+    }
+}
+
+impl<TX: DbTx, Spec: Send + Sync> AccountHistoryReader for DatabaseProvider<TX, Spec> {
+    fn account_history_blocks(
+        &self,
+        address: Address,
+        range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<Vec<BlockNumber>> {
+        let mut blocks =
+            self.history_blocks_in_range::<tables::AccountsHistory>(address, range.clone())?;
+        blocks.extend(
+            self.history_blocks_in_range::<tables::TransactionAppearances>(address, range)?,
+        );
+        blocks.sort_unstable();
+        blocks.dedup();
+        Ok(blocks)
+    }
+}
+
+impl<TX: DbTx, Spec: Send + Sync> TraceIndexReader for DatabaseProvider<TX, Spec> {
+    fn trace_index_tip(&self) -> ProviderResult<Option<BlockNumber>> {
+        Ok(self.get_stage_checkpoint(TRACE_INDEX_ID)?.map(|checkpoint| checkpoint.block_number))
+    }
+
+    fn trace_index_blocks(
+        &self,
+        addresses: &[Address],
+        range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<Vec<BlockNumber>> {
+        let mut blocks = Vec::new();
+        for address in addresses {
+            blocks.extend(
+                self.history_blocks_in_range::<tables::TraceAppearances>(*address, range.clone())?,
+            );
+        }
+        blocks.sort_unstable();
+        blocks.dedup();
+        Ok(blocks)
+    }
+}
+
+impl<TX: DbTx, Spec: Send + Sync> HashedStateRangeReader for DatabaseProvider<TX, Spec> {
+    fn hashed_account_range(
+        &self,
+        block_number: BlockNumber,
+        overlay: HashedPostState,
+        start: B256,
+        limit: usize,
+        with_storage: bool,
+    ) -> ProviderResult<HashedAccountRange> {
+        if block_number > self.best_block_number()? {
+            return Err(ProviderError::StateForNumberNotFound(block_number))
+        }
+
+        // +1 as the changeset that we want is the one that was applied after this block.
+        self.history_ref_at(block_number + 1)?.hashed_account_range(
+            overlay,
+            start,
+            limit,
+            with_storage,
+        )
+    }
+
+    fn hashed_storage_range(
+        &self,
+        block_number: BlockNumber,
+        overlay: HashedPostState,
+        address: Address,
+        start: B256,
+        limit: usize,
+    ) -> ProviderResult<HashedStorageRange> {
+        if block_number > self.best_block_number()? {
+            return Err(ProviderError::StateForNumberNotFound(block_number))
+        }
+
+        // +1 as the changeset that we want is the one that was applied after this block.
+        self.history_ref_at(block_number + 1)?.hashed_storage_range(overlay, address, start, limit)
+    }
+
+    fn hashed_state_reverts(&self, block_number: BlockNumber) -> ProviderResult<HashedPostState> {
+        if block_number > self.best_block_number()? {
+            return Err(ProviderError::StateForNumberNotFound(block_number))
+        }
+
+        // +1 as the changeset that we want is the one that was applied after this block.
+        self.history_ref_at(block_number + 1)?.revert_state()
     }
 }
 
@@ -1493,14 +1938,8 @@ impl<TX: DbTx, Spec: Send + Sync> ChangeSetReader for DatabaseProvider<TX, Spec>
         &self,
         block_number: BlockNumber,
     ) -> ProviderResult<Vec<AccountBeforeTx>> {
-        let range = block_number..=block_number;
-        self.tx
-            .cursor_read::<tables::AccountChangeSets>()?
-            .walk_range(range)?
-            .map(|result| -> ProviderResult<_> {
-                let (_, account_before) = result?;
-                Ok(account_before)
-            })
+        self.account_changesets_in_range(block_number..=block_number)?
+            .map(|change| change.map(|(_, account_before)| account_before))
             .collect()
     }
 }
@@ -1561,12 +2000,16 @@ impl<TX: DbTx, Spec: Send + Sync + EthereumHardforks> HeaderProvider
     }
 
     fn header_by_number(&self, num: BlockNumber) -> ProviderResult<Option<Header>> {
-        self.static_file_provider.get_with_static_file_or_database(
-            StaticFileSegment::Headers,
-            num,
-            |static_file| static_file.header_by_number(num),
-            || Ok(self.tx.get::<tables::Headers>(num)?),
-        )
+        self.static_file_provider
+            .get_with_static_file_or_database(
+                StaticFileSegment::Headers,
+                num,
+                |static_file| static_file.header_by_number(num),
+                || Ok(self.tx.get::<tables::Headers>(num)?),
+            )
+            .or_else(|err| {
+                self.era1_fallback(num, err, |block| Ok(Some(block.block.header.unseal())))
+            })
     }
 
     fn header_td(&self, block_hash: &BlockHash) -> ProviderResult<Option<U256>> {
@@ -1584,12 +2027,16 @@ impl<TX: DbTx, Spec: Send + Sync + EthereumHardforks> HeaderProvider
             return Ok(Some(td))
         }
 
-        self.static_file_provider.get_with_static_file_or_database(
-            StaticFileSegment::Headers,
-            number,
-            |static_file| static_file.header_td_by_number(number),
-            || Ok(self.tx.get::<tables::HeaderTerminalDifficulties>(number)?.map(|td| td.0)),
-        )
+        self.static_file_provider
+            .get_with_static_file_or_database(
+                StaticFileSegment::Headers,
+                number,
+                |static_file| static_file.header_td_by_number(number),
+                || Ok(self.tx.get::<tables::HeaderTerminalDifficulties>(number)?.map(|td| td.0)),
+            )
+            .or_else(|err| {
+                self.era1_fallback(number, err, |block| Ok(Some(block.total_difficulty)))
+            })
     }
 
     fn headers_range(&self, range: impl RangeBounds<BlockNumber>) -> ProviderResult<Vec<Header>> {
@@ -1603,21 +2050,23 @@ impl<TX: DbTx, Spec: Send + Sync + EthereumHardforks> HeaderProvider
     }
 
     fn sealed_header(&self, number: BlockNumber) -> ProviderResult<Option<SealedHeader>> {
-        self.static_file_provider.get_with_static_file_or_database(
-            StaticFileSegment::Headers,
-            number,
-            |static_file| static_file.sealed_header(number),
-            || {
-                if let Some(header) = self.header_by_number(number)? {
-                    let hash = self
-                        .block_hash(number)?
-                        .ok_or_else(|| ProviderError::HeaderNotFound(number.into()))?;
-                    Ok(Some(SealedHeader::new(header, hash)))
-                } else {
-                    Ok(None)
-                }
-            },
-        )
+        self.static_file_provider
+            .get_with_static_file_or_database(
+                StaticFileSegment::Headers,
+                number,
+                |static_file| static_file.sealed_header(number),
+                || {
+                    if let Some(header) = self.header_by_number(number)? {
+                        let hash = self
+                            .block_hash(number)?
+                            .ok_or_else(|| ProviderError::HeaderNotFound(number.into()))?;
+                        Ok(Some(SealedHeader::new(header, hash)))
+                    } else {
+                        Ok(None)
+                    }
+                },
+            )
+            .or_else(|err| self.era1_fallback(number, err, |block| Ok(Some(block.block.header))))
     }
 
     fn sealed_headers_while(
@@ -1651,12 +2100,14 @@ impl<TX: DbTx, Spec: Send + Sync + EthereumHardforks> HeaderProvider
 
 impl<TX: DbTx, Spec: Send + Sync> BlockHashReader for DatabaseProvider<TX, Spec> {
     fn block_hash(&self, number: u64) -> ProviderResult<Option<B256>> {
-        self.static_file_provider.get_with_static_file_or_database(
-            StaticFileSegment::Headers,
-            number,
-            |static_file| static_file.block_hash(number),
-            || Ok(self.tx.get::<tables::CanonicalHeaders>(number)?),
-        )
+        self.static_file_provider
+            .get_with_static_file_or_database(
+                StaticFileSegment::Headers,
+                number,
+                |static_file| static_file.block_hash(number),
+                || Ok(self.tx.get::<tables::CanonicalHeaders>(number)?),
+            )
+            .or_else(|err| self.era1_fallback(number, err, |block| Ok(Some(block.block.hash()))))
     }
 
     fn canonical_hashes_range(
@@ -1731,9 +2182,13 @@ impl<TX: DbTx, Spec: Send + Sync + EthereumHardforks> BlockReader for DatabasePr
                 // exist in the database yet, or they do exit but are not indexed.
                 // If they exist but are not indexed, we don't have enough
                 // information to return the block anyways, so we return `None`.
-                let transactions = match self.transactions_by_block(number.into())? {
-                    Some(transactions) => transactions,
-                    None => return Ok(None),
+                let transactions = match self.transactions_by_block(number.into()) {
+                    Ok(Some(transactions)) => transactions,
+                    Ok(None) => return Ok(None),
+                    Err(err) => {
+                        return self
+                            .era1_fallback(number, err, |block| Ok(Some(block.block.unseal())))
+                    }
                 };
 
                 return Ok(Some(Block {
@@ -2149,7 +2604,14 @@ impl<TX: DbTx, Spec: Send + Sync + EthereumHardforks> ReceiptProvider
                 return if tx_range.is_empty() {
                     Ok(Some(Vec::new()))
                 } else {
-                    self.receipts_by_tx_range(tx_range).map(Some)
+                    self.receipts_by_tx_range(tx_range).map(Some).or_else(|err| {
+                        self.era1_fallback(number, err.clone(), |block| {
+                            // Receipts that can't be decoded, e.g. pre-Byzantium ones, are not
+                            // served.
+                            let receipts = block.decode_receipts().map_err(|_| err)?;
+                            Ok(Some(receipts.into_iter().map(|receipt| receipt.receipt).collect()))
+                        })
+                    })
                 }
             }
         }
@@ -2329,7 +2791,11 @@ impl<TX: DbTxMut, Spec: Send + Sync> StageCheckpointWriter for DatabaseProvider<
     ) -> ProviderResult<()> {
         // iterate over all existing stages in the table and update its progress.
         let mut cursor = self.tx.cursor_write::<tables::StageCheckpoints>()?;
-        for stage_id in StageId::ALL {
+        // the optional transaction appearances index is only updated if it is maintained
+        let optional_stages = cursor
+            .seek_exact(StageId::IndexTransactionAppearances.to_string())?
+            .map(|_| StageId::IndexTransactionAppearances);
+        for stage_id in StageId::ALL.into_iter().chain(optional_stages) {
             let (_, checkpoint) = cursor.seek_exact(stage_id.to_string())?.unwrap_or_default();
             cursor.upsert(
                 stage_id.to_string(),
@@ -2372,36 +2838,29 @@ impl<TX: DbTx, Spec: Send + Sync> StorageReader for DatabaseProvider<TX, Spec> {
         &self,
         range: RangeInclusive<BlockNumber>,
     ) -> ProviderResult<BTreeMap<Address, BTreeSet<B256>>> {
-        self.tx
-            .cursor_read::<tables::StorageChangeSets>()?
-            .walk_range(BlockNumberAddress::range(range))?
-            // fold all storages and save its old state so we can remove it from HashedStorage
-            // it is needed as it is dup table.
-            .try_fold(BTreeMap::new(), |mut accounts: BTreeMap<Address, BTreeSet<B256>>, entry| {
-                let (BlockNumberAddress((_, address)), storage_entry) = entry?;
-                accounts.entry(address).or_default().insert(storage_entry.key);
-                Ok(accounts)
-            })
+        // fold all storages and save its old state so we can remove it from HashedStorage
+        // it is needed as it is dup table.
+        let mut accounts = BTreeMap::<Address, BTreeSet<B256>>::new();
+        for entry in self.storage_changesets_in_range(range)? {
+            let (BlockNumberAddress((_, address)), storage_entry) = entry?;
+            accounts.entry(address).or_default().insert(storage_entry.key);
+        }
+
+        Ok(accounts)
     }
 
     fn changed_storages_and_blocks_with_range(
         &self,
         range: RangeInclusive<BlockNumber>,
     ) -> ProviderResult<BTreeMap<(Address, B256), Vec<u64>>> {
-        let mut changeset_cursor = self.tx.cursor_read::<tables::StorageChangeSets>()?;
-
-        let storage_changeset_lists =
-            changeset_cursor.walk_range(BlockNumberAddress::range(range))?.try_fold(
-                BTreeMap::new(),
-                |mut storages: BTreeMap<(Address, B256), Vec<u64>>, entry| -> ProviderResult<_> {
-                    let (index, storage) = entry?;
-                    storages
-                        .entry((index.address(), storage.key))
-                        .or_default()
-                        .push(index.block_number());
-                    Ok(storages)
-                },
-            )?;
+        let mut storage_changeset_lists = BTreeMap::<(Address, B256), Vec<u64>>::new();
+        for change in self.storage_changesets_in_range(range)? {
+            let (index, storage) = change?;
+            storage_changeset_lists
+                .entry((index.address(), storage.key))
+                .or_default()
+                .push(index.block_number());
+        }
 
         Ok(storage_changeset_lists)
     }
@@ -3176,6 +3635,42 @@ impl<TX: DbTxMut + DbTx, Spec: Send + Sync> HistoryWriter for DatabaseProvider<T
         )
     }
 
+    fn unwind_transaction_appearance_indices(
+        &self,
+        range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<usize> {
+        let rem_index = *range.start();
+        let appearances = self.transaction_appearances_with_range(range)?;
+
+        let mut cursor = self.tx.cursor_write::<tables::TransactionAppearances>()?;
+        for &address in appearances.keys() {
+            let partial_shard = unwind_history_shards::<_, tables::TransactionAppearances, _>(
+                &mut cursor,
+                ShardedKey::last(address),
+                rem_index,
+                |sharded_key| sharded_key.key == address,
+            )?;
+
+            // Check the last returned partial shard.
+            // If it's not empty, the shard needs to be reinserted.
+            if !partial_shard.is_empty() {
+                cursor.insert(
+                    ShardedKey::last(address),
+                    BlockNumberList::new_pre_sorted(partial_shard),
+                )?;
+            }
+        }
+
+        Ok(appearances.len())
+    }
+
+    fn insert_transaction_appearance_index(
+        &self,
+        appearances: impl IntoIterator<Item = (Address, impl IntoIterator<Item = u64>)>,
+    ) -> ProviderResult<()> {
+        self.append_history_index::<_, tables::TransactionAppearances>(appearances, ShardedKey::new)
+    }
+
     fn update_history_indices(&self, range: RangeInclusive<BlockNumber>) -> ProviderResult<()> {
         // account history stage
         {
@@ -3185,14 +3680,58 @@ impl<TX: DbTxMut + DbTx, Spec: Send + Sync> HistoryWriter for DatabaseProvider<T
 
         // storage history stage
         {
-            let indices = self.changed_storages_and_blocks_with_range(range)?;
+            let indices = self.changed_storages_and_blocks_with_range(range.clone())?;
             self.insert_storage_history_index(indices)?;
         }
 
+        // transaction appearances stage, if enabled
+        if self.is_transaction_appearances_indexed()? {
+            let indices = self.transaction_appearances_with_range(range)?;
+            self.insert_transaction_appearance_index(indices)?;
+        }
+
         Ok(())
     }
 }
 
+impl<TX: DbTxMut + DbTx, Spec: Send + Sync> TraceIndexWriter for DatabaseProvider<TX, Spec> {
+    fn insert_trace_index(
+        &self,
+        appearances: BTreeMap<Address, Vec<BlockNumber>>,
+        tip: BlockNumber,
+    ) -> ProviderResult<()> {
+        self.append_history_index::<_, tables::TraceAppearances>(appearances, ShardedKey::new)?;
+        self.save_stage_checkpoint(TRACE_INDEX_ID, StageCheckpoint::new(tip))
+    }
+
+    fn unwind_trace_index(
+        &self,
+        addresses: impl IntoIterator<Item = Address>,
+        tip: BlockNumber,
+    ) -> ProviderResult<()> {
+        let mut cursor = self.tx.cursor_write::<tables::TraceAppearances>()?;
+        for address in addresses {
+            let partial_shard = unwind_history_shards::<_, tables::TraceAppearances, _>(
+                &mut cursor,
+                ShardedKey::last(address),
+                tip + 1,
+                |sharded_key| sharded_key.key == address,
+            )?;
+
+            // Check the last returned partial shard.
+            // If it's not empty, the shard needs to be reinserted.
+            if !partial_shard.is_empty() {
+                cursor.insert(
+                    ShardedKey::last(address),
+                    BlockNumberList::new_pre_sorted(partial_shard),
+                )?;
+            }
+        }
+
+        self.save_stage_checkpoint(TRACE_INDEX_ID, StageCheckpoint::new(tip))
+    }
+}
+
 impl<TX: DbTx, Spec: Send + Sync + EthereumHardforks> BlockExecutionReader
     for DatabaseProvider<TX, Spec>
 {
@@ -3223,6 +3762,10 @@ impl<TX: DbTxMut + DbTx + 'static, Spec: Send + Sync + EthereumHardforks + 'stat
         &self,
         range: RangeInclusive<BlockNumber>,
     ) -> ProviderResult<Chain> {
+        // Changesets of blocks which were moved to static files need to be in the database, so
+        // they can be unwound.
+        self.restore_static_file_changesets(*range.start())?;
+
         let storage_range = BlockNumberAddress::range(range.clone());
 
         // Unwind account hashes. Add changed accounts to account prefix set.
@@ -3236,8 +3779,11 @@ impl<TX: DbTxMut + DbTx + 'static, Spec: Send + Sync + EthereumHardforks + 'stat
             }
         }
 
-        // Unwind account history indices.
+        // Unwind account history and transaction appearance indices.
         self.unwind_account_history_indices(range.clone())?;
+        if self.is_transaction_appearances_indexed()? {
+            self.unwind_transaction_appearance_indices(range.clone())?;
+        }
 
         // Unwind storage hashes. Add changed account and storage keys to corresponding prefix
         // sets.
@@ -3311,6 +3857,10 @@ impl<TX: DbTxMut + DbTx + 'static, Spec: Send + Sync + EthereumHardforks + 'stat
         &self,
         range: RangeInclusive<BlockNumber>,
     ) -> ProviderResult<()> {
+        // Changesets of blocks which were moved to static files need to be in the database, so
+        // they can be unwound.
+        self.restore_static_file_changesets(*range.start())?;
+
         let storage_range = BlockNumberAddress::range(range.clone());
 
         // Unwind account hashes. Add changed accounts to account prefix set.
@@ -3324,8 +3874,11 @@ impl<TX: DbTxMut + DbTx + 'static, Spec: Send + Sync + EthereumHardforks + 'stat
             }
         }
 
-        // Unwind account history indices.
+        // Unwind account history and transaction appearance indices.
         self.unwind_account_history_indices(range.clone())?;
+        if self.is_transaction_appearances_indexed()? {
+            self.unwind_transaction_appearance_indices(range.clone())?;
+        }
 
         // Unwind storage hashes. Add changed account and storage keys to corresponding prefix
         // sets.
@@ -3695,6 +4248,49 @@ impl<TX: DbTxMut, Spec: Send + Sync> ChainStateBlockWriter for DatabaseProvider<
     }
 }
 
+impl<TX: DbTx, Spec: Send + Sync> BadBlockReader for DatabaseProvider<TX, Spec> {
+    fn bad_blocks(&self) -> ProviderResult<Vec<StoredBadBlock>> {
+        let mut bad_blocks = Vec::new();
+        for entry in self.tx.cursor_read::<RawTable<tables::BadBlocks>>()?.walk(None)? {
+            let (key, value) = entry?;
+            match value.value() {
+                Ok(bad_block) => bad_blocks.push(bad_block),
+                Err(err) => {
+                    warn!(target: "providers::db", key = ?key.key().ok(), %err, "Skipping undecodable bad block");
+                }
+            }
+        }
+        Ok(bad_blocks)
+    }
+}
+
+impl<TX: DbTxMut, Spec: Send + Sync> BadBlockWriter for DatabaseProvider<TX, Spec> {
+    fn save_bad_block(&self, block: SealedBlock, error: String) -> ProviderResult<()> {
+        // undecodable entries are kept, so that they are evicted eventually
+        let mut entries = Vec::new();
+        for entry in self.tx.cursor_read::<RawTable<tables::BadBlocks>>()?.walk(None)? {
+            let (key, value) = entry?;
+            if value.value().is_ok_and(|bad_block| bad_block.block.hash() == block.hash()) {
+                return Ok(())
+            }
+            entries.push(key.key()?);
+        }
+
+        let mut cursor = self.tx.cursor_write::<tables::BadBlocks>()?;
+
+        let next_key = entries.last().map_or(0, |key| key + 1);
+        cursor.append(next_key, StoredBadBlock { block, error })?;
+
+        // evict the oldest bad blocks
+        let evicted = (entries.len() + 1).saturating_sub(MAX_BAD_BLOCKS);
+        for key in entries.into_iter().take(evicted) {
+            self.tx.delete::<tables::BadBlocks>(key, None)?;
+        }
+
+        Ok(())
+    }
+}
+
 impl<TX: DbTx + 'static, Spec: Send + Sync + 'static> DBProvider for DatabaseProvider<TX, Spec> {
     type Tx = TX;
 
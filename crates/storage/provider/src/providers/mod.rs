@@ -1,10 +1,12 @@
 use crate::{
-    AccountReader, BlockHashReader, BlockIdReader, BlockNumReader, BlockReader, BlockReaderIdExt,
-    BlockSource, BlockchainTreePendingStateProvider, CanonChainTracker, CanonStateNotifications,
-    CanonStateSubscriptions, ChainSpecProvider, ChainStateBlockReader, ChangeSetReader,
-    DatabaseProviderFactory, EvmEnvProvider, FullExecutionDataProvider, HeaderProvider,
-    ProviderError, PruneCheckpointReader, ReceiptProvider, ReceiptProviderIdExt, RequestsProvider,
-    StageCheckpointReader, StateProviderBox, StateProviderFactory, StaticFileProviderFactory,
+    AccountHistoryReader, AccountReader, BadBlockReader, BlockHashReader, BlockIdReader,
+    BlockNumReader, BlockReader, BlockReaderIdExt, BlockSource, BlockchainTreePendingStateProvider,
+    CanonChainTracker, CanonStateNotifications, CanonStateSubscriptions, ChainSpecProvider,
+    ChainStateBlockReader, ChangeSetReader, DatabaseProviderFactory, EvmEnvProvider,
+    FullExecutionDataProvider, HashedAccountRange, HashedStateRangeReader, HashedStorageRange,
+    HeaderProvider, ProviderError, PruneCheckpointReader, RawDatabaseReader, ReceiptProvider,
+    ReceiptProviderIdExt, RequestsProvider, StageCheckpointReader, StateProviderBox,
+    StateProviderFactory, StaticFileProviderFactory, StorageSnapshotProvider, TraceIndexReader,
     TransactionVariant, TransactionsProvider, TreeViewer, WithdrawalsProvider,
 };
 use alloy_eips::{BlockHashOrNumber, BlockId, BlockNumHash, BlockNumberOrTag};
@@ -16,7 +18,11 @@ use reth_blockchain_tree_api::{
 };
 use reth_chain_state::{ChainInfoTracker, ForkChoiceNotifications, ForkChoiceSubscriptions};
 use reth_chainspec::{ChainInfo, EthereumHardforks};
-use reth_db_api::models::{AccountBeforeTx, StoredBlockBodyIndices};
+use reth_db::Tables;
+use reth_db_api::{
+    database_metrics::DatabaseMetadataValue,
+    models::{AccountBeforeTx, StoredBadBlock, StoredBlockBodyIndices},
+};
 use reth_evm::ConfigureEvmEnv;
 use reth_node_types::NodeTypesWithDB;
 use reth_primitives::{
@@ -27,10 +33,12 @@ use reth_primitives::{
 use reth_prune_types::{PruneCheckpoint, PruneSegment};
 use reth_stages_types::{StageCheckpoint, StageId};
 use reth_storage_errors::provider::ProviderResult;
+use reth_trie::HashedPostState;
 use revm::primitives::{BlockEnv, CfgEnvWithHandlerCfg};
 use std::{
     collections::BTreeMap,
     ops::{RangeBounds, RangeInclusive},
+    path::Path,
     sync::Arc,
     time::Instant,
 };
@@ -48,7 +56,7 @@ pub use static_file::{
 mod state;
 pub use state::{
     historical::{HistoricalStateProvider, HistoricalStateProviderRef},
-    latest::{LatestStateProvider, LatestStateProviderRef},
+    latest::{snap_sync_pivot, LatestStateProvider, LatestStateProviderRef},
 };
 
 mod bundle_state_provider;
@@ -61,6 +69,12 @@ pub use consistent_view::{ConsistentDbView, ConsistentViewError};
 mod blockchain_provider;
 pub use blockchain_provider::BlockchainProvider2;
 
+mod snapshot;
+pub(crate) use snapshot::create_snapshot;
+pub use snapshot::{
+    restore_snapshot, SnapshotFile, SnapshotManifest, SnapshotOptions, SNAPSHOT_MANIFEST_FILE_NAME,
+};
+
 /// Helper trait keeping common requirements of providers for [`NodeTypesWithDB`].
 pub trait ProviderNodeTypes: NodeTypesWithDB<ChainSpec: EthereumHardforks> {}
 
@@ -593,6 +607,12 @@ impl<N: ProviderNodeTypes> PruneCheckpointReader for BlockchainProvider<N> {
     }
 }
 
+impl<N: ProviderNodeTypes> BadBlockReader for BlockchainProvider<N> {
+    fn bad_blocks(&self) -> ProviderResult<Vec<StoredBadBlock>> {
+        self.database.bad_blocks()
+    }
+}
+
 impl<N: ProviderNodeTypes> ChainSpecProvider for BlockchainProvider<N> {
     type ChainSpec = N::ChainSpec;
 
@@ -929,6 +949,78 @@ impl<N: ProviderNodeTypes> ForkChoiceSubscriptions for BlockchainProvider<N> {
     }
 }
 
+impl<N: ProviderNodeTypes> TraceIndexReader for BlockchainProvider<N> {
+    fn trace_index_tip(&self) -> ProviderResult<Option<BlockNumber>> {
+        self.database.trace_index_tip()
+    }
+
+    fn trace_index_blocks(
+        &self,
+        addresses: &[Address],
+        range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<Vec<BlockNumber>> {
+        self.database.trace_index_blocks(addresses, range)
+    }
+}
+
+impl<N: ProviderNodeTypes> HashedStateRangeReader for BlockchainProvider<N> {
+    fn hashed_account_range(
+        &self,
+        block_number: BlockNumber,
+        overlay: HashedPostState,
+        start: B256,
+        limit: usize,
+        with_storage: bool,
+    ) -> ProviderResult<HashedAccountRange> {
+        self.database.hashed_account_range(block_number, overlay, start, limit, with_storage)
+    }
+
+    fn hashed_storage_range(
+        &self,
+        block_number: BlockNumber,
+        overlay: HashedPostState,
+        address: Address,
+        start: B256,
+        limit: usize,
+    ) -> ProviderResult<HashedStorageRange> {
+        self.database.hashed_storage_range(block_number, overlay, address, start, limit)
+    }
+
+    fn hashed_state_reverts(&self, block_number: BlockNumber) -> ProviderResult<HashedPostState> {
+        self.database.hashed_state_reverts(block_number)
+    }
+}
+
+impl<N: ProviderNodeTypes> RawDatabaseReader for BlockchainProvider<N> {
+    fn database_metadata(&self) -> ProviderResult<DatabaseMetadataValue> {
+        self.database.database_metadata()
+    }
+
+    fn raw_table_value(&self, table: Tables, key: Vec<u8>) -> ProviderResult<Option<Vec<u8>>> {
+        self.database.raw_table_value(table, key)
+    }
+}
+
+impl<N: ProviderNodeTypes> StorageSnapshotProvider for BlockchainProvider<N> {
+    fn create_snapshot(
+        &self,
+        dir: &Path,
+        options: SnapshotOptions,
+    ) -> ProviderResult<SnapshotManifest> {
+        self.database.create_snapshot(dir, options)
+    }
+}
+
+impl<N: ProviderNodeTypes> AccountHistoryReader for BlockchainProvider<N> {
+    fn account_history_blocks(
+        &self,
+        address: Address,
+        range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<Vec<BlockNumber>> {
+        self.database.provider()?.account_history_blocks(address, range)
+    }
+}
+
 impl<N: ProviderNodeTypes> ChangeSetReader for BlockchainProvider<N> {
     fn account_block_changeset(
         &self,
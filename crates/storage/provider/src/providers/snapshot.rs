@@ -0,0 +1,528 @@
+use crate::providers::StaticFileProvider;
+use alloy_primitives::{BlockNumber, Keccak256, B256};
+use reth_db::{
+    mdbx::DatabaseArguments, tables, version::create_db_version_file, ClientVersion, DatabaseEnv,
+    DatabaseEnvKind,
+};
+use reth_db_api::{
+    database::{Database, DatabaseCopy},
+    transaction::DbTx,
+};
+use reth_fs_util::{self as fs, FsPathError};
+use reth_primitives::StaticFileSegment;
+use reth_stages_types::StageId;
+use reth_storage_errors::provider::{ProviderError, ProviderResult};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::File,
+    io::{self, Read},
+    path::{Path, PathBuf},
+};
+use tracing::{debug, info};
+
+/// Name of the manifest file of a snapshot.
+pub const SNAPSHOT_MANIFEST_FILE_NAME: &str = "snapshot.json";
+
+/// Version of the snapshot manifest format.
+const SNAPSHOT_MANIFEST_VERSION: u64 = 1;
+
+/// Directory of the database in a snapshot.
+const SNAPSHOT_DB_DIR: &str = "db";
+
+/// Directory of the static files in a snapshot.
+const SNAPSHOT_STATIC_FILES_DIR: &str = "static_files";
+
+/// Name of the MDBX data file.
+const MDBX_DATA_FILE_NAME: &str = "mdbx.dat";
+
+/// Name of the MDBX lock file, which is recreated on demand.
+const MDBX_LOCK_FILE_NAME: &str = "mdbx.lck";
+
+/// Extensions of the files of a static file jar, in the order they are copied.
+///
+/// The configuration of a jar is committed last, so copying it first guarantees that the copied
+/// offsets and data files contain at least the rows it references. Any rows written in between
+/// are truncated by the consistency check when the snapshot is restored.
+const JAR_FILE_EXTENSIONS: [Option<&str>; 4] = [Some("conf"), Some("off"), None, Some("idx")];
+
+/// Options of a snapshot.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotOptions {
+    /// Omit free pages from the database copy.
+    #[serde(default)]
+    pub compact: bool,
+    /// Copy static files below the finalized block instead of hard-linking them.
+    #[serde(default)]
+    pub copy_static_files: bool,
+}
+
+/// A file of a snapshot.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnapshotFile {
+    /// Path of the file relative to the snapshot directory, with `/` as separator.
+    pub path: String,
+    /// Size of the file in bytes.
+    pub size: u64,
+    /// Keccak256 checksum of the file.
+    pub checksum: B256,
+}
+
+/// Manifest of a consistent snapshot of the database and the static files of a node.
+///
+/// A snapshot directory has the same layout as a datadir, with the database in `db` and the
+/// static files in `static_files`, and the manifest in [`SNAPSHOT_MANIFEST_FILE_NAME`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotManifest {
+    /// Version of the manifest format.
+    pub version: u64,
+    /// Highest block fully persisted in the snapshot, the checkpoint of the `Finish` stage.
+    pub block_number: BlockNumber,
+    /// Whether free pages were omitted from the database copy.
+    pub compacted: bool,
+    /// Files of the snapshot, sorted by path.
+    pub files: Vec<SnapshotFile>,
+}
+
+impl SnapshotManifest {
+    /// Reads the manifest of the snapshot in the given directory.
+    pub fn read(dir: &Path) -> ProviderResult<Self> {
+        let manifest: Self = fs::read_json_file(&dir.join(SNAPSHOT_MANIFEST_FILE_NAME))
+            .map_err(|err| ProviderError::FsPathError(err.to_string()))?;
+        if manifest.version != SNAPSHOT_MANIFEST_VERSION {
+            return Err(ProviderError::FsPathError(format!(
+                "unsupported snapshot manifest version {}",
+                manifest.version
+            )))
+        }
+        Ok(manifest)
+    }
+
+    /// Verifies the size and the checksum of every file of the snapshot in the given directory.
+    pub fn verify(&self, dir: &Path) -> ProviderResult<()> {
+        for file in &self.files {
+            let path = dir.join(&file.path);
+            let (size, checksum) = checksum_file(&path)?;
+            if size != file.size || checksum != file.checksum {
+                return Err(ProviderError::SnapshotChecksumMismatch(file.path.clone()))
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Writes a consistent snapshot of the database and the static files to the given directory,
+/// which must be empty or not exist yet.
+///
+/// The database is copied first, from a single read transaction. The static files are copied
+/// afterwards, since they are always committed before the database and can only be ahead of it.
+///
+/// Static files that end below the finalized block of the database copy can't be unwound anymore
+/// and are hard-linked, unless [`SnapshotOptions::copy_static_files`] is set or the snapshot
+/// directory is on another filesystem. All other static files may still be truncated in place and
+/// are copied.
+///
+/// Static files are not rewritten or expired while they are copied. Only one snapshot can be
+/// created at a time, see [`ProviderError::SnapshotInProgress`].
+pub(crate) fn create_snapshot<DB: Database + DatabaseCopy>(
+    db: &DB,
+    static_file_provider: &StaticFileProvider,
+    dir: &Path,
+    options: SnapshotOptions,
+) -> ProviderResult<SnapshotManifest> {
+    let _guard =
+        static_file_provider.try_lock_snapshot().ok_or(ProviderError::SnapshotInProgress)?;
+    ensure_empty_dir(dir)?;
+
+    let db_dir = dir.join(SNAPSHOT_DB_DIR);
+    fs::create_dir_all(&db_dir).map_err(|err| ProviderError::FsPathError(err.to_string()))?;
+    info!(target: "providers::snapshot", ?dir, compact = options.compact, "Copying database");
+    db.copy_to(&db_dir.join(MDBX_DATA_FILE_NAME), options.compact)?;
+    create_db_version_file(&db_dir)
+        .map_err(|err| ProviderError::FsPathError(FsPathError::write(err, &db_dir).to_string()))?;
+    let (block_number, finalized_block) = snapshot_block_numbers(&db_dir)?;
+
+    let static_files_dir = dir.join(SNAPSHOT_STATIC_FILES_DIR);
+    fs::create_dir_all(&static_files_dir)
+        .map_err(|err| ProviderError::FsPathError(err.to_string()))?;
+    info!(target: "providers::snapshot", ?dir, "Copying static files");
+    snapshot_static_files(static_file_provider, &static_files_dir, finalized_block, options)?;
+
+    info!(target: "providers::snapshot", ?dir, "Computing checksums");
+    let mut files = Vec::new();
+    for subdir in [SNAPSHOT_DB_DIR, SNAPSHOT_STATIC_FILES_DIR] {
+        for path in sorted_files(&dir.join(subdir))? {
+            let (size, checksum) = checksum_file(&path)?;
+            let name = path.file_name().expect("is a file").to_string_lossy();
+            files.push(SnapshotFile { path: format!("{subdir}/{name}"), size, checksum });
+        }
+    }
+
+    let manifest = SnapshotManifest {
+        version: SNAPSHOT_MANIFEST_VERSION,
+        block_number,
+        compacted: options.compact,
+        files,
+    };
+    fs::write_json_file(&dir.join(SNAPSHOT_MANIFEST_FILE_NAME), &manifest)
+        .map_err(|err| ProviderError::FsPathError(err.to_string()))?;
+    info!(target: "providers::snapshot", ?dir, block_number, "Snapshot created");
+
+    Ok(manifest)
+}
+
+/// Verifies the snapshot in the given directory against its manifest and copies it to the given
+/// database and static files directories, which must be empty or not exist yet.
+pub fn restore_snapshot(
+    dir: &Path,
+    db_dir: &Path,
+    static_files_dir: &Path,
+) -> ProviderResult<SnapshotManifest> {
+    let manifest = SnapshotManifest::read(dir)?;
+    info!(target: "providers::snapshot", ?dir, block_number = manifest.block_number, "Verifying snapshot");
+    manifest.verify(dir)?;
+
+    ensure_empty_dir(db_dir)?;
+    ensure_empty_dir(static_files_dir)?;
+    for file in &manifest.files {
+        let invalid_path =
+            || ProviderError::FsPathError(format!("invalid snapshot file path {}", file.path));
+        let (subdir, name) = file.path.split_once('/').ok_or_else(invalid_path)?;
+        if name.is_empty() || name.contains(['/', '\\']) || name == ".." {
+            return Err(invalid_path())
+        }
+        let target_dir = match subdir {
+            SNAPSHOT_DB_DIR => db_dir,
+            SNAPSHOT_STATIC_FILES_DIR => static_files_dir,
+            _ => return Err(invalid_path()),
+        };
+        copy_file(&dir.join(&file.path), &target_dir.join(name))?;
+    }
+    info!(target: "providers::snapshot", ?dir, block_number = manifest.block_number, "Snapshot restored");
+
+    Ok(manifest)
+}
+
+/// Reads the checkpoint of the `Finish` stage and the last finalized block, if any, from the
+/// database copy in the given directory.
+fn snapshot_block_numbers(db_dir: &Path) -> ProviderResult<(BlockNumber, Option<BlockNumber>)> {
+    let block_numbers = {
+        let db = DatabaseEnv::open(
+            db_dir,
+            DatabaseEnvKind::RO,
+            DatabaseArguments::new(ClientVersion::default()),
+        )?;
+        let tx = db.tx()?;
+        let block_number = tx
+            .get::<tables::StageCheckpoints>(StageId::Finish.to_string())?
+            .map(|checkpoint| checkpoint.block_number)
+            .unwrap_or_default();
+        let finalized_block =
+            tx.get::<tables::ChainState>(tables::ChainStateKey::LastFinalizedBlock)?;
+        (block_number, finalized_block)
+    };
+
+    // opening the copy creates a lock file, which is not part of the snapshot
+    let lock_file = db_dir.join(MDBX_LOCK_FILE_NAME);
+    if lock_file.exists() {
+        fs::remove_file(&lock_file).map_err(|err| ProviderError::FsPathError(err.to_string()))?;
+    }
+
+    Ok(block_numbers)
+}
+
+/// Hard-links or copies all static files to the given directory.
+///
+/// Hard links share the data with the original files, so only static files that can't be
+/// truncated by an unwind anymore, the ones ending below `finalized_block`, are hard-linked.
+fn snapshot_static_files(
+    static_file_provider: &StaticFileProvider,
+    target_dir: &Path,
+    finalized_block: Option<BlockNumber>,
+    options: SnapshotOptions,
+) -> ProviderResult<()> {
+    // rewrites and history expiry must not replace or delete files while they are copied
+    let _files_guard = static_file_provider.lock_files();
+    let source_dir = static_file_provider.directory();
+    for path in sorted_files(source_dir)? {
+        let name = path.file_name().expect("is a file").to_string_lossy().into_owned();
+        let Some((segment, range)) = StaticFileSegment::parse_filename(&name) else { continue };
+
+        // the latest jar is appended to, and jars above the finalized block may be truncated
+        let finalized = finalized_block.is_some_and(|finalized| range.end() < finalized) &&
+            static_file_provider
+                .get_highest_static_file_block(segment)
+                .is_some_and(|highest| highest > range.end());
+
+        for extension in JAR_FILE_EXTENSIONS {
+            let source = extension.map_or_else(|| path.clone(), |ext| path.with_extension(ext));
+            if !source.exists() {
+                continue
+            }
+            let target = target_dir.join(source.file_name().expect("is a file"));
+            if finalized && !options.copy_static_files {
+                if let Err(err) = std::fs::hard_link(&source, &target) {
+                    debug!(target: "providers::snapshot", ?source, %err, "Failed to hard-link static file, copying it");
+                    copy_file(&source, &target)?;
+                }
+            } else {
+                copy_file(&source, &target)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Returns an error if the given directory exists and is not empty, and creates it otherwise.
+fn ensure_empty_dir(dir: &Path) -> ProviderResult<()> {
+    if dir.exists() &&
+        fs::read_dir(dir)
+            .map_err(|err| ProviderError::FsPathError(err.to_string()))?
+            .next()
+            .is_some()
+    {
+        return Err(ProviderError::FsPathError(format!("directory {} is not empty", dir.display())))
+    }
+    fs::create_dir_all(dir).map_err(|err| ProviderError::FsPathError(err.to_string()))
+}
+
+/// Returns the paths of all files in the given directory, sorted by name.
+fn sorted_files(dir: &Path) -> ProviderResult<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for entry in fs::read_dir(dir).map_err(|err| ProviderError::FsPathError(err.to_string()))? {
+        let entry = entry.map_err(|err| {
+            ProviderError::FsPathError(FsPathError::read_dir(err, dir).to_string())
+        })?;
+        if entry.file_type().map_or(false, |file_type| file_type.is_file()) {
+            files.push(entry.path());
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Copies a file, see [`std::fs::copy`].
+fn copy_file(source: &Path, target: &Path) -> ProviderResult<()> {
+    std::fs::copy(source, target)
+        .map_err(|err| ProviderError::FsPathError(FsPathError::write(err, target).to_string()))?;
+    Ok(())
+}
+
+/// Returns the size and the keccak256 checksum of a file.
+fn checksum_file(path: &Path) -> ProviderResult<(u64, B256)> {
+    let read_err =
+        |err: io::Error| ProviderError::FsPathError(FsPathError::read(err, path).to_string());
+
+    let mut file = File::open(path)
+        .map_err(|err| ProviderError::FsPathError(FsPathError::open(err, path).to_string()))?;
+    let mut hasher = Keccak256::new();
+    let mut buf = vec![0; 1 << 20];
+    let mut size = 0;
+    loop {
+        let read = file.read(&mut buf).map_err(read_err)?;
+        if read == 0 {
+            break
+        }
+        hasher.update(&buf[..read]);
+        size += read as u64;
+    }
+    Ok((size, hasher.finalize()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        test_utils::create_test_provider_factory, ChainStateBlockWriter, HeaderProvider,
+        StageCheckpointWriter, StaticFileProviderFactory, StaticFileWriter,
+    };
+    use alloy_primitives::U256;
+    use assert_matches::assert_matches;
+    use reth_db::version::DB_VERSION_FILE_NAME;
+    use reth_primitives::static_file::{Compression, SegmentConfig};
+    use reth_stages_types::StageCheckpoint;
+    use reth_testing_utils::generators::{self, random_header_range};
+
+    #[test]
+    fn create_verify_and_restore_snapshot() {
+        let factory = create_test_provider_factory();
+        let static_file_provider = factory.static_file_provider();
+
+        let mut writer = static_file_provider.latest_writer(StaticFileSegment::Headers).unwrap();
+        for header in random_header_range(&mut generators::rng(), 0..3, B256::ZERO) {
+            let hash = header.hash();
+            writer.append_header(&header.unseal(), U256::ZERO, &hash).unwrap();
+        }
+        writer.commit().unwrap();
+        drop(writer);
+
+        let provider = factory.provider_rw().unwrap();
+        provider.save_stage_checkpoint(StageId::Finish, StageCheckpoint::new(2)).unwrap();
+        provider.commit().unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let snapshot_dir = dir.path().join("snapshot");
+        let manifest = create_snapshot(
+            factory.db_ref(),
+            &static_file_provider,
+            &snapshot_dir,
+            SnapshotOptions { compact: true, ..Default::default() },
+        )
+        .unwrap();
+        assert_eq!(manifest.block_number, 2);
+        assert!(manifest.compacted);
+        assert!(manifest.files.iter().any(|file| file.path == "db/mdbx.dat"));
+        assert!(manifest.files.iter().all(|file| file.path != "db/mdbx.lck"));
+        assert!(manifest.files.iter().any(|file| file.path.starts_with("static_files/")));
+        assert_eq!(SnapshotManifest::read(&snapshot_dir).unwrap(), manifest);
+
+        // a snapshot can't be written to a non-empty directory
+        assert_matches!(
+            create_snapshot(
+                factory.db_ref(),
+                &static_file_provider,
+                &snapshot_dir,
+                SnapshotOptions::default()
+            ),
+            Err(ProviderError::FsPathError(_))
+        );
+
+        let db_dir = dir.path().join("restored").join("db");
+        let static_files_dir = dir.path().join("restored").join("static_files");
+        assert_eq!(restore_snapshot(&snapshot_dir, &db_dir, &static_files_dir).unwrap(), manifest);
+        let restored = StaticFileProvider::read_only(&static_files_dir, false).unwrap();
+        assert_eq!(restored.get_highest_static_file_block(StaticFileSegment::Headers), Some(2));
+        assert_eq!(snapshot_block_numbers(&db_dir).unwrap(), (2, None));
+
+        // corrupted files are detected
+        std::fs::write(snapshot_dir.join("db").join(DB_VERSION_FILE_NAME), "0").unwrap();
+        assert_matches!(
+            manifest.verify(&snapshot_dir),
+            Err(ProviderError::SnapshotChecksumMismatch(path)) if path == "db/database.version"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn hard_link_only_static_files_below_finalized_block() {
+        use std::os::unix::fs::MetadataExt;
+
+        let factory = create_test_provider_factory();
+        let dir = tempfile::tempdir().unwrap();
+        let static_file_provider = StaticFileProvider::read_write(dir.path().join("static_files"))
+            .unwrap()
+            .with_custom_blocks_per_file(10);
+
+        let mut writer = static_file_provider.latest_writer(StaticFileSegment::Headers).unwrap();
+        for header in random_header_range(&mut generators::rng(), 0..25, B256::ZERO) {
+            let hash = header.hash();
+            writer.append_header(&header.unseal(), U256::ZERO, &hash).unwrap();
+        }
+        writer.commit().unwrap();
+        drop(writer);
+
+        let provider = factory.provider_rw().unwrap();
+        provider.save_stage_checkpoint(StageId::Finish, StageCheckpoint::new(24)).unwrap();
+        provider.save_finalized_block_number(15).unwrap();
+        provider.commit().unwrap();
+
+        // only one snapshot can be created at a time
+        let guard = static_file_provider.try_lock_snapshot().unwrap();
+        assert_matches!(
+            create_snapshot(
+                factory.db_ref(),
+                &static_file_provider,
+                &dir.path().join("rejected"),
+                SnapshotOptions::default()
+            ),
+            Err(ProviderError::SnapshotInProgress)
+        );
+        drop(guard);
+
+        let snapshot_dir = dir.path().join("snapshot");
+        create_snapshot(
+            factory.db_ref(),
+            &static_file_provider,
+            &snapshot_dir,
+            SnapshotOptions::default(),
+        )
+        .unwrap();
+
+        let is_hard_link = |name: &str| {
+            let original = std::fs::metadata(static_file_provider.directory().join(name)).unwrap();
+            let copy = std::fs::metadata(snapshot_dir.join("static_files").join(name)).unwrap();
+            original.ino() == copy.ino()
+        };
+        // blocks 10..=19 are above the finalized block and may still be unwound
+        assert!(is_hard_link("static_file_headers_0_9"));
+        assert!(!is_hard_link("static_file_headers_10_19"));
+        assert!(!is_hard_link("static_file_headers_20_29"));
+    }
+
+    #[test]
+    fn snapshot_with_concurrent_rewrite() {
+        let factory = create_test_provider_factory();
+        let dir = tempfile::tempdir().unwrap();
+        let static_file_provider = StaticFileProvider::read_write(dir.path().join("static_files"))
+            .unwrap()
+            .with_custom_blocks_per_file(10)
+            .with_segment_configs([(
+                StaticFileSegment::Headers,
+                SegmentConfig::new(Compression::Lz4),
+            )]);
+
+        let headers = random_header_range(&mut generators::rng(), 0..25, B256::ZERO);
+        let mut writer = static_file_provider.latest_writer(StaticFileSegment::Headers).unwrap();
+        for header in headers.clone() {
+            let hash = header.hash();
+            writer.append_header(&header.unseal(), U256::ZERO, &hash).unwrap();
+        }
+        writer.commit().unwrap();
+        drop(writer);
+
+        let provider = factory.provider_rw().unwrap();
+        provider.save_stage_checkpoint(StageId::Finish, StageCheckpoint::new(24)).unwrap();
+        provider.commit().unwrap();
+
+        // a rewrite doesn't replace the files while they are copied
+        let guard = static_file_provider.lock_files();
+        let rewrite = std::thread::spawn({
+            let static_file_provider = static_file_provider.clone();
+            move || static_file_provider.recompress_jar(StaticFileSegment::Headers, 0)
+        });
+        std::thread::sleep(std::time::Duration::from_millis(100));
+        assert!(!rewrite.is_finished());
+        drop(guard);
+        rewrite.join().unwrap().unwrap();
+
+        let rewrites = std::thread::spawn({
+            let static_file_provider = static_file_provider.clone();
+            move || {
+                for block in [0, 10, 0, 10] {
+                    static_file_provider.recompress_jar(StaticFileSegment::Headers, block)?;
+                }
+                ProviderResult::Ok(())
+            }
+        });
+        let snapshot_dir = dir.path().join("snapshot");
+        let manifest = create_snapshot(
+            factory.db_ref(),
+            &static_file_provider,
+            &snapshot_dir,
+            SnapshotOptions { copy_static_files: true, ..Default::default() },
+        )
+        .unwrap();
+        rewrites.join().unwrap().unwrap();
+
+        let db_dir = dir.path().join("restored").join("db");
+        let static_files_dir = dir.path().join("restored").join("static_files");
+        assert_eq!(restore_snapshot(&snapshot_dir, &db_dir, &static_files_dir).unwrap(), manifest);
+        let restored = StaticFileProvider::read_only(&static_files_dir, false)
+            .unwrap()
+            .with_custom_blocks_per_file(10);
+        for header in headers {
+            assert_eq!(restored.header_by_number(header.number).unwrap(), Some(header.unseal()));
+        }
+    }
+}
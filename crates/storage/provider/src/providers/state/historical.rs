@@ -1,15 +1,17 @@
 use crate::{
     providers::{state::macros::delegate_provider_impls, StaticFileProvider},
-    AccountReader, BlockHashReader, ProviderError, StateProvider, StateRootProvider,
+    AccountReader, BlockHashReader, HashedAccountEntry, HashedAccountRange, HashedStorageRange,
+    ProviderError, StateProvider, StateRootProvider,
 };
 use alloy_primitives::{
+    keccak256,
     map::{HashMap, HashSet},
-    Address, BlockNumber, Bytes, StorageKey, StorageValue, B256,
+    Address, BlockNumber, Bytes, StorageKey, StorageValue, B256, U256,
 };
 use reth_db::{tables, BlockNumberList};
 use reth_db_api::{
     cursor::{DbCursorRO, DbDupCursorRO},
-    models::{storage_sharded_key::StorageShardedKey, ShardedKey},
+    models::{storage_sharded_key::StorageShardedKey, AccountBeforeTx, ShardedKey},
     table::Table,
     transaction::DbTx,
 };
@@ -17,14 +19,15 @@ use reth_primitives::{constants::EPOCH_SLOTS, Account, Bytecode, StaticFileSegme
 use reth_storage_api::{StateProofProvider, StorageRootProvider};
 use reth_storage_errors::provider::ProviderResult;
 use reth_trie::{
+    hashed_cursor::{HashedCursor, HashedCursorFactory, HashedPostStateCursorFactory},
     proof::{Proof, StorageProof},
     updates::TrieUpdates,
     witness::TrieWitness,
     AccountProof, HashedPostState, HashedStorage, MultiProof, StateRoot, StorageRoot, TrieInput,
 };
 use reth_trie_db::{
-    DatabaseHashedPostState, DatabaseHashedStorage, DatabaseProof, DatabaseStateRoot,
-    DatabaseStorageProof, DatabaseStorageRoot, DatabaseTrieWitness,
+    DatabaseHashedCursorFactory, DatabaseHashedPostState, DatabaseHashedStorage, DatabaseProof,
+    DatabaseStateRoot, DatabaseStorageProof, DatabaseStorageRoot, DatabaseTrieWitness,
 };
 use std::fmt::Debug;
 
@@ -49,6 +52,8 @@ pub struct HistoricalStateProviderRef<'b, TX: DbTx> {
     lowest_available_blocks: LowestAvailableBlocks,
     /// Static File provider
     static_file_provider: StaticFileProvider,
+    /// Whether the latest state is read from the hashed state tables.
+    hashed_state: bool,
 }
 
 #[derive(Debug, Eq, PartialEq)]
@@ -66,7 +71,13 @@ impl<'b, TX: DbTx> HistoricalStateProviderRef<'b, TX> {
         block_number: BlockNumber,
         static_file_provider: StaticFileProvider,
     ) -> Self {
-        Self { tx, block_number, lowest_available_blocks: Default::default(), static_file_provider }
+        Self {
+            tx,
+            block_number,
+            lowest_available_blocks: Default::default(),
+            static_file_provider,
+            hashed_state: false,
+        }
     }
 
     /// Create new `StateProvider` for historical block number and lowest block numbers at which
@@ -77,7 +88,13 @@ impl<'b, TX: DbTx> HistoricalStateProviderRef<'b, TX> {
         lowest_available_blocks: LowestAvailableBlocks,
         static_file_provider: StaticFileProvider,
     ) -> Self {
-        Self { tx, block_number, lowest_available_blocks, static_file_provider }
+        Self {
+            tx,
+            block_number,
+            lowest_available_blocks,
+            static_file_provider,
+            hashed_state: false,
+        }
     }
 
     /// Lookup an account in the `AccountsHistory` table
@@ -129,8 +146,19 @@ impl<'b, TX: DbTx> HistoricalStateProviderRef<'b, TX> {
         Ok(tip.saturating_sub(self.block_number) > limit)
     }
 
+    /// Returns `true` if the changeset of the given block was moved to static files.
+    fn is_changeset_in_static_files(
+        &self,
+        segment: StaticFileSegment,
+        block_number: BlockNumber,
+    ) -> bool {
+        self.static_file_provider
+            .get_highest_static_file_block(segment)
+            .is_some_and(|highest| block_number <= highest)
+    }
+
     /// Retrieve revert hashed state for this history provider.
-    fn revert_state(&self) -> ProviderResult<HashedPostState> {
+    pub(crate) fn revert_state(&self) -> ProviderResult<HashedPostState> {
         if !self.lowest_available_blocks.is_account_history_available(self.block_number) ||
             !self.lowest_available_blocks.is_storage_history_available(self.block_number)
         {
@@ -145,7 +173,63 @@ impl<'b, TX: DbTx> HistoricalStateProviderRef<'b, TX> {
             );
         }
 
-        Ok(HashedPostState::from_reverts(self.tx, self.block_number)?)
+        // Changesets moved to static files are read from there, while the database is only walked
+        // from the first block that is not in static files for either segment.
+        let account_changesets_end =
+            self.static_file_changesets_end(StaticFileSegment::AccountChangeSets);
+        let storage_changesets_end =
+            self.static_file_changesets_end(StaticFileSegment::StorageChangeSets);
+        let db_from = account_changesets_end
+            .map_or(self.block_number, |end| end + 1)
+            .min(storage_changesets_end.map_or(self.block_number, |end| end + 1));
+
+        let mut accounts = HashMap::<Address, Option<Account>>::default();
+        if let Some(end) = account_changesets_end {
+            for change in
+                self.static_file_provider.account_changesets_iter(self.block_number..=end)?
+            {
+                let (_, AccountBeforeTx { address, info }) = change?;
+                accounts.entry(address).or_insert(info);
+            }
+        }
+
+        let mut storages = HashMap::<Address, HashMap<B256, U256>>::default();
+        if let Some(end) = storage_changesets_end {
+            for change in
+                self.static_file_provider.storage_changesets_iter(self.block_number..=end)?
+            {
+                let (block_address, entry) = change?;
+                storages
+                    .entry(block_address.address())
+                    .or_default()
+                    .entry(entry.key)
+                    .or_insert(entry.value);
+            }
+        }
+
+        let static_file_reverts = HashedPostState {
+            accounts: accounts
+                .into_iter()
+                .map(|(address, info)| (keccak256(address), info))
+                .collect(),
+            storages: storages
+                .into_iter()
+                .map(|(address, storage)| {
+                    (
+                        keccak256(address),
+                        HashedStorage::from_iter(
+                            false,
+                            storage.into_iter().map(|(slot, value)| (keccak256(slot), value)),
+                        ),
+                    )
+                })
+                .collect(),
+        };
+
+        // Static file reverts are older than the database ones, so they take precedence.
+        let mut reverts = HashedPostState::from_reverts(self.tx, db_from)?;
+        reverts.extend(static_file_reverts);
+        Ok(reverts)
     }
 
     /// Retrieve revert hashed storage for this history provider and target address.
@@ -162,7 +246,35 @@ impl<'b, TX: DbTx> HistoricalStateProviderRef<'b, TX> {
             );
         }
 
-        Ok(HashedStorage::from_reverts(self.tx, address, self.block_number)?)
+        let Some(end) = self.static_file_changesets_end(StaticFileSegment::StorageChangeSets)
+        else {
+            return Ok(HashedStorage::from_reverts(self.tx, address, self.block_number)?)
+        };
+
+        let mut storage = HashMap::<B256, U256>::default();
+        for change in self.static_file_provider.storage_changesets_iter(self.block_number..=end)? {
+            let (block_address, entry) = change?;
+            if block_address.address() == address {
+                storage.entry(entry.key).or_insert(entry.value);
+            }
+        }
+        let static_file_reverts = HashedStorage::from_iter(
+            false,
+            storage.into_iter().map(|(slot, value)| (keccak256(slot), value)),
+        );
+
+        // Static file reverts are older than the database ones, so they take precedence.
+        let mut reverts = HashedStorage::from_reverts(self.tx, address, end + 1)?;
+        reverts.extend(&static_file_reverts);
+        Ok(reverts)
+    }
+
+    /// Returns the highest block of a changeset segment in static files, if the changesets of
+    /// this provider's block number were moved there.
+    fn static_file_changesets_end(&self, segment: StaticFileSegment) -> Option<BlockNumber> {
+        self.static_file_provider
+            .get_highest_static_file_block(segment)
+            .filter(|highest| *highest >= self.block_number)
     }
 
     fn history_info<T, K>(
@@ -245,6 +357,95 @@ impl<'b, TX: DbTx> HistoricalStateProviderRef<'b, TX> {
         self.lowest_available_blocks.storage_history_block_number = Some(block_number);
         self
     }
+
+    /// Reads the latest state from the hashed state tables instead of the plain state tables.
+    ///
+    /// This is required if the node was snap synced, see
+    /// [`snap_sync_pivot`](crate::providers::snap_sync_pivot).
+    pub const fn with_hashed_state(mut self, hashed_state: bool) -> Self {
+        self.hashed_state = hashed_state;
+        self
+    }
+
+    /// Returns up to `limit` hashed accounts with a hashed address greater than or equal to
+    /// `start`, with `overlay` applied on top of the historical state.
+    ///
+    /// If `with_storage` is set, the full storage of every returned account is included.
+    pub fn hashed_account_range(
+        &self,
+        overlay: HashedPostState,
+        start: B256,
+        limit: usize,
+        with_storage: bool,
+    ) -> ProviderResult<HashedAccountRange> {
+        let mut state = self.revert_state()?;
+        state.extend(overlay);
+        let state = state.into_sorted();
+        let cursor_factory =
+            HashedPostStateCursorFactory::new(DatabaseHashedCursorFactory::new(self.tx), &state);
+
+        let (accounts, next_key) =
+            hashed_cursor_range(&mut cursor_factory.hashed_account_cursor()?, start, limit)?;
+        let accounts = accounts
+            .into_iter()
+            .map(|(hashed_address, account)| {
+                let storage = if with_storage {
+                    let mut cursor = cursor_factory.hashed_storage_cursor(hashed_address)?;
+                    Some(hashed_cursor_range(&mut cursor, B256::ZERO, usize::MAX)?.0)
+                } else {
+                    None
+                };
+                Ok(HashedAccountEntry { hashed_address, account, storage })
+            })
+            .collect::<ProviderResult<_>>()?;
+
+        Ok(HashedAccountRange { accounts, next_key })
+    }
+
+    /// Returns up to `limit` storage slots of the given account with a hashed slot greater than or
+    /// equal to `start`, with `overlay` applied on top of the historical state.
+    pub fn hashed_storage_range(
+        &self,
+        overlay: HashedPostState,
+        address: Address,
+        start: B256,
+        limit: usize,
+    ) -> ProviderResult<HashedStorageRange> {
+        let hashed_address = keccak256(address);
+        let mut state =
+            HashedPostState::from_hashed_storage(hashed_address, self.revert_storage(address)?);
+        state.extend(overlay);
+        let state = state.into_sorted();
+        let cursor_factory =
+            HashedPostStateCursorFactory::new(DatabaseHashedCursorFactory::new(self.tx), &state);
+
+        let (storage, next_key) = hashed_cursor_range(
+            &mut cursor_factory.hashed_storage_cursor(hashed_address)?,
+            start,
+            limit,
+        )?;
+        Ok(HashedStorageRange { storage, next_key })
+    }
+}
+
+/// Collects up to `limit` entries starting at `start` from the hashed cursor.
+///
+/// Returns the entries and the key of the first entry that did not fit, if any.
+fn hashed_cursor_range<C: HashedCursor>(
+    cursor: &mut C,
+    start: B256,
+    limit: usize,
+) -> ProviderResult<(Vec<(B256, C::Value)>, Option<B256>)> {
+    let mut entries = Vec::new();
+    let mut entry = cursor.seek(start)?;
+    while let Some((key, value)) = entry {
+        if entries.len() == limit {
+            return Ok((entries, Some(key)))
+        }
+        entries.push((key, value));
+        entry = cursor.next()?;
+    }
+    Ok((entries, None))
 }
 
 impl<TX: DbTx> AccountReader for HistoricalStateProviderRef<'_, TX> {
@@ -252,16 +453,28 @@ impl<TX: DbTx> AccountReader for HistoricalStateProviderRef<'_, TX> {
     fn basic_account(&self, address: Address) -> ProviderResult<Option<Account>> {
         match self.account_history_lookup(address)? {
             HistoryInfo::NotYetWritten => Ok(None),
-            HistoryInfo::InChangeset(changeset_block_number) => Ok(self
-                .tx
-                .cursor_dup_read::<tables::AccountChangeSets>()?
-                .seek_by_key_subkey(changeset_block_number, address)?
-                .filter(|acc| acc.address == address)
-                .ok_or(ProviderError::AccountChangesetNotFound {
-                    block_number: changeset_block_number,
-                    address,
-                })?
-                .info),
+            HistoryInfo::InChangeset(changeset_block_number) => {
+                let change = if self.is_changeset_in_static_files(
+                    StaticFileSegment::AccountChangeSets,
+                    changeset_block_number,
+                ) {
+                    self.static_file_provider.account_change(changeset_block_number, address)?
+                } else {
+                    self.tx
+                        .cursor_dup_read::<tables::AccountChangeSets>()?
+                        .seek_by_key_subkey(changeset_block_number, address)?
+                        .filter(|acc| acc.address == address)
+                };
+                Ok(change
+                    .ok_or(ProviderError::AccountChangesetNotFound {
+                        block_number: changeset_block_number,
+                        address,
+                    })?
+                    .info)
+            }
+            HistoryInfo::InPlainState | HistoryInfo::MaybeInPlainState if self.hashed_state => {
+                Ok(self.tx.get::<tables::HashedAccounts>(keccak256(address))?)
+            }
             HistoryInfo::InPlainState | HistoryInfo::MaybeInPlainState => {
                 Ok(self.tx.get::<tables::PlainAccountState>(address)?)
             }
@@ -405,18 +618,42 @@ impl<TX: DbTx> StateProvider for HistoricalStateProviderRef<'_, TX> {
     ) -> ProviderResult<Option<StorageValue>> {
         match self.storage_history_lookup(address, storage_key)? {
             HistoryInfo::NotYetWritten => Ok(None),
-            HistoryInfo::InChangeset(changeset_block_number) => Ok(Some(
-                self.tx
-                    .cursor_dup_read::<tables::StorageChangeSets>()?
-                    .seek_by_key_subkey((changeset_block_number, address).into(), storage_key)?
-                    .filter(|entry| entry.key == storage_key)
-                    .ok_or_else(|| ProviderError::StorageChangesetNotFound {
-                        block_number: changeset_block_number,
+            HistoryInfo::InChangeset(changeset_block_number) => {
+                let change = if self.is_changeset_in_static_files(
+                    StaticFileSegment::StorageChangeSets,
+                    changeset_block_number,
+                ) {
+                    self.static_file_provider.storage_change(
+                        changeset_block_number,
                         address,
-                        storage_key: Box::new(storage_key),
-                    })?
-                    .value,
-            )),
+                        storage_key,
+                    )?
+                } else {
+                    self.tx
+                        .cursor_dup_read::<tables::StorageChangeSets>()?
+                        .seek_by_key_subkey((changeset_block_number, address).into(), storage_key)?
+                        .filter(|entry| entry.key == storage_key)
+                };
+                Ok(Some(
+                    change
+                        .ok_or_else(|| ProviderError::StorageChangesetNotFound {
+                            block_number: changeset_block_number,
+                            address,
+                            storage_key: Box::new(storage_key),
+                        })?
+                        .value,
+                ))
+            }
+            HistoryInfo::InPlainState | HistoryInfo::MaybeInPlainState if self.hashed_state => {
+                let hashed_slot = keccak256(storage_key);
+                Ok(self
+                    .tx
+                    .cursor_dup_read::<tables::HashedStorages>()?
+                    .seek_by_key_subkey(keccak256(address), hashed_slot)?
+                    .filter(|entry| entry.key == hashed_slot)
+                    .map(|entry| entry.value)
+                    .or(Some(StorageValue::ZERO)))
+            }
             HistoryInfo::InPlainState | HistoryInfo::MaybeInPlainState => Ok(self
                 .tx
                 .cursor_dup_read::<tables::PlainStorageState>()?
@@ -445,6 +682,8 @@ pub struct HistoricalStateProvider<TX: DbTx> {
     lowest_available_blocks: LowestAvailableBlocks,
     /// Static File provider
     static_file_provider: StaticFileProvider,
+    /// Whether the latest state is read from the hashed state tables.
+    hashed_state: bool,
 }
 
 impl<TX: DbTx> HistoricalStateProvider<TX> {
@@ -454,7 +693,13 @@ impl<TX: DbTx> HistoricalStateProvider<TX> {
         block_number: BlockNumber,
         static_file_provider: StaticFileProvider,
     ) -> Self {
-        Self { tx, block_number, lowest_available_blocks: Default::default(), static_file_provider }
+        Self {
+            tx,
+            block_number,
+            lowest_available_blocks: Default::default(),
+            static_file_provider,
+            hashed_state: false,
+        }
     }
 
     /// Set the lowest block number at which the account history is available.
@@ -475,6 +720,15 @@ impl<TX: DbTx> HistoricalStateProvider<TX> {
         self
     }
 
+    /// Reads the latest state from the hashed state tables instead of the plain state tables.
+    ///
+    /// This is required if the node was snap synced, see
+    /// [`snap_sync_pivot`](crate::providers::snap_sync_pivot).
+    pub const fn with_hashed_state(mut self, hashed_state: bool) -> Self {
+        self.hashed_state = hashed_state;
+        self
+    }
+
     /// Returns a new provider that takes the `TX` as reference
     #[inline(always)]
     fn as_ref(&self) -> HistoricalStateProviderRef<'_, TX> {
@@ -484,6 +738,7 @@ impl<TX: DbTx> HistoricalStateProvider<TX> {
             self.lowest_available_blocks,
             self.static_file_provider.clone(),
         )
+        .with_hashed_state(self.hashed_state)
     }
 }
 
@@ -521,19 +776,24 @@ impl LowestAvailableBlocks {
 #[cfg(test)]
 mod tests {
     use crate::{
-        providers::state::historical::{HistoryInfo, LowestAvailableBlocks},
+        providers::{
+            state::historical::{HistoryInfo, LowestAvailableBlocks},
+            StaticFileWriter,
+        },
         test_utils::create_test_provider_factory,
-        AccountReader, HistoricalStateProvider, HistoricalStateProviderRef, StateProvider,
+        AccountReader, HashedAccountEntry, HashedAccountRange, HashedStorageRange,
+        HistoricalStateProvider, HistoricalStateProviderRef, StateProvider,
         StaticFileProviderFactory,
     };
-    use alloy_primitives::{address, b256, Address, B256, U256};
+    use alloy_primitives::{address, b256, keccak256, Address, B256, U256};
     use reth_db::{tables, BlockNumberList};
     use reth_db_api::{
         models::{storage_sharded_key::StorageShardedKey, AccountBeforeTx, ShardedKey},
         transaction::{DbTx, DbTxMut},
     };
-    use reth_primitives::{Account, StorageEntry};
+    use reth_primitives::{Account, StaticFileSegment, StorageEntry};
     use reth_storage_errors::provider::ProviderError;
+    use reth_trie::{HashedPostState, HashedStorage};
 
     const ADDRESS: Address = address!("0000000000000000000000000000000000000001");
     const HIGHER_ADDRESS: Address = address!("0000000000000000000000000000000000000005");
@@ -836,4 +1096,245 @@ mod tests {
             Ok(HistoryInfo::MaybeInPlainState)
         );
     }
+
+    #[test]
+    fn history_provider_hashed_ranges() {
+        let factory = create_test_provider_factory();
+        let tx = factory.provider_rw().unwrap().into_tx();
+        let static_file_provider = factory.static_file_provider();
+
+        let acc_plain = Account { nonce: 100, balance: U256::ZERO, bytecode_hash: None };
+        let acc_at3 = Account { nonce: 3, balance: U256::ZERO, bytecode_hash: None };
+        let higher_acc_plain = Account { nonce: 4, balance: U256::ZERO, bytecode_hash: None };
+        let (hashed_address, higher_hashed_address) =
+            (keccak256(ADDRESS), keccak256(HIGHER_ADDRESS));
+
+        // the account changed in block 3, the higher account was created in block 4 and the slot
+        // was written in block 4
+        tx.put::<tables::AccountChangeSets>(
+            3,
+            AccountBeforeTx { address: ADDRESS, info: Some(acc_at3) },
+        )
+        .unwrap();
+        tx.put::<tables::AccountChangeSets>(
+            4,
+            AccountBeforeTx { address: HIGHER_ADDRESS, info: None },
+        )
+        .unwrap();
+        tx.put::<tables::StorageChangeSets>(
+            (4, ADDRESS).into(),
+            StorageEntry { key: STORAGE, value: U256::ZERO },
+        )
+        .unwrap();
+        tx.put::<tables::CanonicalHeaders>(4, B256::ZERO).unwrap();
+
+        // setup hashed state
+        tx.put::<tables::HashedAccounts>(hashed_address, acc_plain).unwrap();
+        tx.put::<tables::HashedAccounts>(higher_hashed_address, higher_acc_plain).unwrap();
+        tx.put::<tables::HashedStorages>(
+            hashed_address,
+            StorageEntry { key: keccak256(STORAGE), value: U256::from(1) },
+        )
+        .unwrap();
+
+        // state at the start of block 3
+        let provider = HistoricalStateProviderRef::new(&tx, 3, static_file_provider.clone());
+        let range = provider
+            .hashed_account_range(HashedPostState::default(), B256::ZERO, 10, true)
+            .unwrap();
+        assert_eq!(
+            range,
+            HashedAccountRange {
+                accounts: vec![HashedAccountEntry {
+                    hashed_address,
+                    account: acc_at3,
+                    storage: Some(Vec::new())
+                }],
+                next_key: None,
+            }
+        );
+
+        // latest state, paged
+        let mut expected =
+            vec![(hashed_address, acc_plain), (higher_hashed_address, higher_acc_plain)];
+        expected.sort_unstable_by_key(|(hashed_address, _)| *hashed_address);
+        let provider = HistoricalStateProviderRef::new(&tx, 5, static_file_provider);
+        let range = provider
+            .hashed_account_range(HashedPostState::default(), B256::ZERO, 1, false)
+            .unwrap();
+        assert_eq!(
+            range,
+            HashedAccountRange {
+                accounts: vec![HashedAccountEntry {
+                    hashed_address: expected[0].0,
+                    account: expected[0].1,
+                    storage: None
+                }],
+                next_key: Some(expected[1].0),
+            }
+        );
+        let range = provider
+            .hashed_account_range(HashedPostState::default(), expected[1].0, 1, false)
+            .unwrap();
+        assert_eq!(range.accounts[0].hashed_address, expected[1].0);
+        assert_eq!(range.next_key, None);
+
+        // storage with and without an overlay that clears the slot
+        assert_eq!(
+            provider
+                .hashed_storage_range(HashedPostState::default(), ADDRESS, B256::ZERO, 10)
+                .unwrap(),
+            HashedStorageRange {
+                storage: vec![(keccak256(STORAGE), U256::from(1))],
+                next_key: None
+            }
+        );
+        let overlay = HashedPostState::from_hashed_storage(
+            hashed_address,
+            HashedStorage::from_iter(false, [(keccak256(STORAGE), U256::ZERO)]),
+        );
+        assert_eq!(
+            provider.hashed_storage_range(overlay, ADDRESS, B256::ZERO, 10).unwrap(),
+            HashedStorageRange::default()
+        );
+    }
+
+    #[test]
+    fn history_provider_static_file_changesets() {
+        const OTHER_STORAGE: B256 =
+            b256!("0000000000000000000000000000000000000000000000000000000000000002");
+
+        let factory = create_test_provider_factory();
+        let static_file_provider = factory.static_file_provider();
+
+        let acc_plain = Account { nonce: 100, balance: U256::ZERO, bytecode_hash: None };
+        let acc_at12 = Account { nonce: 12, balance: U256::ZERO, bytecode_hash: None };
+        let acc_at7 = Account { nonce: 7, balance: U256::ZERO, bytecode_hash: None };
+        let acc_at3 = Account { nonce: 3, balance: U256::ZERO, bytecode_hash: None };
+        let higher_acc_plain = Account { nonce: 4, balance: U256::ZERO, bytecode_hash: None };
+        let slot = |key, value: u64| StorageEntry { key, value: U256::from(value) };
+
+        // Changesets of blocks `0..=9` are in static files, sorted by address and storage key.
+        let mut account_writer =
+            static_file_provider.latest_writer(StaticFileSegment::AccountChangeSets).unwrap();
+        let mut storage_writer =
+            static_file_provider.latest_writer(StaticFileSegment::StorageChangeSets).unwrap();
+        for block in 0..10 {
+            let (account_changes, storage_changes) = match block {
+                3 => (
+                    vec![
+                        AccountBeforeTx { address: ADDRESS, info: Some(acc_at3) },
+                        AccountBeforeTx { address: HIGHER_ADDRESS, info: None },
+                    ],
+                    vec![
+                        (ADDRESS, slot(STORAGE, 3)),
+                        (ADDRESS, slot(OTHER_STORAGE, 30)),
+                        (HIGHER_ADDRESS, slot(STORAGE, 33)),
+                    ],
+                ),
+                7 => (
+                    vec![AccountBeforeTx { address: ADDRESS, info: Some(acc_at7) }],
+                    vec![(ADDRESS, slot(STORAGE, 7))],
+                ),
+                _ => (Vec::new(), Vec::new()),
+            };
+            account_writer.increment_block(block).unwrap();
+            account_writer.append_account_changeset(block, account_changes).unwrap();
+            storage_writer.increment_block(block).unwrap();
+            storage_writer.append_storage_changeset(block, storage_changes).unwrap();
+        }
+        account_writer.commit().unwrap();
+        storage_writer.commit().unwrap();
+        drop(account_writer);
+        drop(storage_writer);
+
+        // The following changesets, history indices and plain state are in the database.
+        let tx = factory.provider_rw().unwrap().into_tx();
+        tx.put::<tables::AccountChangeSets>(
+            12,
+            AccountBeforeTx { address: ADDRESS, info: Some(acc_at12) },
+        )
+        .unwrap();
+        tx.put::<tables::StorageChangeSets>((12, ADDRESS).into(), slot(STORAGE, 12)).unwrap();
+        tx.put::<tables::AccountsHistory>(
+            ShardedKey { key: ADDRESS, highest_block_number: u64::MAX },
+            BlockNumberList::new([3, 7, 12]).unwrap(),
+        )
+        .unwrap();
+        tx.put::<tables::AccountsHistory>(
+            ShardedKey { key: HIGHER_ADDRESS, highest_block_number: u64::MAX },
+            BlockNumberList::new([3]).unwrap(),
+        )
+        .unwrap();
+        for (address, key, blocks) in [
+            (ADDRESS, STORAGE, vec![3, 7, 12]),
+            (ADDRESS, OTHER_STORAGE, vec![3]),
+            (HIGHER_ADDRESS, STORAGE, vec![3]),
+        ] {
+            tx.put::<tables::StoragesHistory>(
+                StorageShardedKey {
+                    address,
+                    sharded_key: ShardedKey { key, highest_block_number: u64::MAX },
+                },
+                BlockNumberList::new(blocks).unwrap(),
+            )
+            .unwrap();
+        }
+        tx.put::<tables::PlainAccountState>(ADDRESS, acc_plain).unwrap();
+        tx.put::<tables::PlainAccountState>(HIGHER_ADDRESS, higher_acc_plain).unwrap();
+        tx.put::<tables::CanonicalHeaders>(13, B256::ZERO).unwrap();
+        tx.put::<tables::PlainStorageState>(ADDRESS, slot(STORAGE, 100)).unwrap();
+        tx.put::<tables::PlainStorageState>(ADDRESS, slot(OTHER_STORAGE, 200)).unwrap();
+        tx.put::<tables::PlainStorageState>(HIGHER_ADDRESS, slot(STORAGE, 300)).unwrap();
+        tx.commit().unwrap();
+
+        let tx = factory.provider().unwrap().into_tx();
+        let at = |block_number| {
+            HistoricalStateProviderRef::new(&tx, block_number, static_file_provider.clone())
+        };
+
+        // Account changes before block 10 are read from static files, later ones from the
+        // database.
+        assert_eq!(at(2).basic_account(ADDRESS), Ok(Some(acc_at3)));
+        assert_eq!(at(3).basic_account(ADDRESS), Ok(Some(acc_at3)));
+        assert_eq!(at(4).basic_account(ADDRESS), Ok(Some(acc_at7)));
+        assert_eq!(at(8).basic_account(ADDRESS), Ok(Some(acc_at12)));
+        assert_eq!(at(13).basic_account(ADDRESS), Ok(Some(acc_plain)));
+        assert_eq!(at(3).basic_account(HIGHER_ADDRESS), Ok(None));
+        assert_eq!(at(4).basic_account(HIGHER_ADDRESS), Ok(Some(higher_acc_plain)));
+
+        // Same for storage changes, where each lookup has to find its slot among the block's
+        // changes.
+        assert_eq!(at(3).storage(ADDRESS, STORAGE), Ok(Some(U256::from(3))));
+        assert_eq!(at(3).storage(ADDRESS, OTHER_STORAGE), Ok(Some(U256::from(30))));
+        assert_eq!(at(3).storage(HIGHER_ADDRESS, STORAGE), Ok(Some(U256::from(33))));
+        assert_eq!(at(5).storage(ADDRESS, STORAGE), Ok(Some(U256::from(7))));
+        assert_eq!(at(8).storage(ADDRESS, STORAGE), Ok(Some(U256::from(12))));
+        assert_eq!(at(13).storage(ADDRESS, STORAGE), Ok(Some(U256::from(100))));
+        assert_eq!(at(4).storage(ADDRESS, OTHER_STORAGE), Ok(Some(U256::from(200))));
+
+        // Reverts combine both sources, with the older static file changes taking precedence.
+        assert_eq!(
+            at(5).revert_state(),
+            Ok(HashedPostState::default()
+                .with_accounts([(keccak256(ADDRESS), Some(acc_at7))])
+                .with_storages([(
+                    keccak256(ADDRESS),
+                    HashedStorage::from_iter(false, [(keccak256(STORAGE), U256::from(7))]),
+                )]))
+        );
+        assert_eq!(
+            at(10).revert_state(),
+            Ok(HashedPostState::default()
+                .with_accounts([(keccak256(ADDRESS), Some(acc_at12))])
+                .with_storages([(
+                    keccak256(ADDRESS),
+                    HashedStorage::from_iter(false, [(keccak256(STORAGE), U256::from(12))]),
+                )]))
+        );
+        assert_eq!(
+            at(5).revert_storage(ADDRESS),
+            Ok(HashedStorage::from_iter(false, [(keccak256(STORAGE), U256::from(7))]))
+        );
+    }
 }
@@ -3,6 +3,7 @@ use crate::{
     AccountReader, BlockHashReader, StateProvider, StateRootProvider,
 };
 use alloy_primitives::{
+    keccak256,
     map::{HashMap, HashSet},
     Address, BlockNumber, Bytes, StorageKey, StorageValue, B256,
 };
@@ -12,6 +13,7 @@ use reth_db_api::{
     transaction::DbTx,
 };
 use reth_primitives::{Account, Bytecode, StaticFileSegment};
+use reth_stages_types::StageId;
 use reth_storage_api::{StateProofProvider, StorageRootProvider};
 use reth_storage_errors::provider::{ProviderError, ProviderResult};
 use reth_trie::{
@@ -25,6 +27,17 @@ use reth_trie_db::{
     DatabaseTrieWitness,
 };
 
+/// Returns the block the state was snap synced to, if the node was synced with snap sync.
+///
+/// The plain state tables of a snap synced node only contain the accounts and storage slots that
+/// changed after this block, the complete state is held by the hashed state tables.
+pub fn snap_sync_pivot<TX: DbTx>(tx: &TX) -> ProviderResult<Option<BlockNumber>> {
+    let progress = tx.get::<tables::StageCheckpointProgresses>(StageId::SnapSync.to_string())?;
+    Ok(progress
+        .and_then(|progress| progress.try_into().ok())
+        .map(|pivot: [u8; 8]| BlockNumber::from_be_bytes(pivot)))
+}
+
 /// State provider over latest state that takes tx reference.
 #[derive(Debug)]
 pub struct LatestStateProviderRef<'b, TX: DbTx> {
@@ -32,18 +45,31 @@ pub struct LatestStateProviderRef<'b, TX: DbTx> {
     tx: &'b TX,
     /// Static File provider
     static_file_provider: StaticFileProvider,
+    /// Whether accounts and storage are read from the hashed state tables.
+    hashed_state: bool,
 }
 
 impl<'b, TX: DbTx> LatestStateProviderRef<'b, TX> {
     /// Create new state provider
     pub const fn new(tx: &'b TX, static_file_provider: StaticFileProvider) -> Self {
-        Self { tx, static_file_provider }
+        Self { tx, static_file_provider, hashed_state: false }
+    }
+
+    /// Reads accounts and storage from the hashed state tables instead of the plain state tables.
+    ///
+    /// This is required if the node was snap synced, see [`snap_sync_pivot`].
+    pub const fn with_hashed_state(mut self, hashed_state: bool) -> Self {
+        self.hashed_state = hashed_state;
+        self
     }
 }
 
 impl<TX: DbTx> AccountReader for LatestStateProviderRef<'_, TX> {
     /// Get basic account information.
     fn basic_account(&self, address: Address) -> ProviderResult<Option<Account>> {
+        if self.hashed_state {
+            return self.tx.get::<tables::HashedAccounts>(keccak256(address)).map_err(Into::into)
+        }
         self.tx.get::<tables::PlainAccountState>(address).map_err(Into::into)
     }
 }
@@ -168,6 +194,15 @@ impl<TX: DbTx> StateProvider for LatestStateProviderRef<'_, TX> {
         account: Address,
         storage_key: StorageKey,
     ) -> ProviderResult<Option<StorageValue>> {
+        if self.hashed_state {
+            let hashed_slot = keccak256(storage_key);
+            let mut cursor = self.tx.cursor_dup_read::<tables::HashedStorages>()?;
+            return Ok(cursor
+                .seek_by_key_subkey(keccak256(account), hashed_slot)?
+                .filter(|entry| entry.key == hashed_slot)
+                .map(|entry| entry.value))
+        }
+
         let mut cursor = self.tx.cursor_dup_read::<tables::PlainStorageState>()?;
         if let Some(entry) = cursor.seek_by_key_subkey(account, storage_key)? {
             if entry.key == storage_key {
@@ -190,18 +225,29 @@ pub struct LatestStateProvider<TX: DbTx> {
     db: TX,
     /// Static File provider
     static_file_provider: StaticFileProvider,
+    /// Whether accounts and storage are read from the hashed state tables.
+    hashed_state: bool,
 }
 
 impl<TX: DbTx> LatestStateProvider<TX> {
     /// Create new state provider
     pub const fn new(db: TX, static_file_provider: StaticFileProvider) -> Self {
-        Self { db, static_file_provider }
+        Self { db, static_file_provider, hashed_state: false }
+    }
+
+    /// Reads accounts and storage from the hashed state tables instead of the plain state tables.
+    ///
+    /// This is required if the node was snap synced, see [`snap_sync_pivot`].
+    pub const fn with_hashed_state(mut self, hashed_state: bool) -> Self {
+        self.hashed_state = hashed_state;
+        self
     }
 
     /// Returns a new provider that takes the `TX` as reference
     #[inline(always)]
     fn as_ref(&self) -> LatestStateProviderRef<'_, TX> {
         LatestStateProviderRef::new(&self.db, self.static_file_provider.clone())
+            .with_hashed_state(self.hashed_state)
     }
 }
 
@@ -212,9 +258,45 @@ delegate_provider_impls!(LatestStateProvider<TX> where [TX: DbTx]);
 mod tests {
     use super::*;
 
+    use crate::{test_utils::create_test_provider_factory, StaticFileProviderFactory};
+    use alloy_primitives::{address, U256};
+    use reth_db_api::transaction::DbTxMut;
+    use reth_primitives::StorageEntry;
+
     const fn assert_state_provider<T: StateProvider>() {}
     #[allow(dead_code)]
     const fn assert_latest_state_provider<T: DbTx>() {
         assert_state_provider::<LatestStateProvider<T>>();
     }
+
+    #[test]
+    fn hashed_state_after_snap_sync() {
+        let factory = create_test_provider_factory();
+        let tx = factory.provider_rw().unwrap().into_tx();
+
+        let address = address!("0000000000000000000000000000000000000001");
+        let slot = B256::with_last_byte(1);
+        let account = Account { nonce: 1, ..Default::default() };
+        tx.put::<tables::HashedAccounts>(keccak256(address), account).unwrap();
+        tx.put::<tables::HashedStorages>(
+            keccak256(address),
+            StorageEntry { key: keccak256(slot), value: U256::from(2) },
+        )
+        .unwrap();
+
+        assert_eq!(snap_sync_pivot(&tx), Ok(None));
+        let provider = LatestStateProviderRef::new(&tx, factory.static_file_provider());
+        assert_eq!(provider.basic_account(address), Ok(None));
+        assert_eq!(provider.storage(address, slot), Ok(None));
+
+        tx.put::<tables::StageCheckpointProgresses>(
+            StageId::SnapSync.to_string(),
+            10u64.to_be_bytes().to_vec(),
+        )
+        .unwrap();
+        assert_eq!(snap_sync_pivot(&tx), Ok(Some(10)));
+        let provider = provider.with_hashed_state(true);
+        assert_eq!(provider.basic_account(address), Ok(Some(account)));
+        assert_eq!(provider.storage(address, slot), Ok(Some(U256::from(2))));
+    }
 }
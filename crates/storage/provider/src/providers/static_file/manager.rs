@@ -1,56 +1,87 @@
 use super::{
-    metrics::StaticFileProviderMetrics, writer::StaticFileWriters, LoadedJar,
-    StaticFileJarProvider, StaticFileProviderRW, StaticFileProviderRWRefMut,
+    metrics::StaticFileProviderMetrics,
+    writer::{with_compression, StaticFileWriters},
+    LoadedJar, StaticFileJarProvider, StaticFileProviderRW, StaticFileProviderRWRefMut,
 };
 use crate::{
-    to_range, BlockHashReader, BlockNumReader, BlockReader, BlockSource, HeaderProvider,
-    ReceiptProvider, RequestsProvider, StageCheckpointReader, StatsReader, TransactionVariant,
+    providers::snap_sync_pivot, to_range, BlockHashReader, BlockNumReader, BlockReader,
+    BlockSource, ChangeSetReader, HeaderProvider, ReceiptProvider, RequestsProvider,
+    StageCheckpointReader, StatsReader, StorageChangeSetReader, TransactionVariant,
     TransactionsProvider, TransactionsProviderExt, WithdrawalsProvider,
 };
 use alloy_eips::BlockHashOrNumber;
 use alloy_primitives::{keccak256, Address, BlockHash, BlockNumber, TxHash, TxNumber, B256, U256};
 use dashmap::DashMap;
+use itertools::Either;
 use notify::{RecommendedWatcher, RecursiveMode, Watcher};
-use parking_lot::RwLock;
+use parking_lot::{Condvar, Mutex, MutexGuard, RwLock};
 use reth_chainspec::{ChainInfo, ChainSpecProvider};
 use reth_db::{
     lockfile::StorageLock,
-    static_file::{iter_static_files, HeaderMask, ReceiptMask, StaticFileCursor, TransactionMask},
+    static_file::{
+        iter_static_files, AccountChangeSetMask, HeaderMask, ReceiptMask, StaticFileCursor,
+        StorageChangeSetMask, TransactionMask,
+    },
     tables,
 };
 use reth_db_api::{
     cursor::DbCursorRO,
-    models::{CompactU256, StoredBlockBodyIndices},
+    models::{
+        AccountBeforeTx, BlockNumberAddress, CompactU256, CompactU64, StoredBlockBodyIndices,
+    },
     table::Table,
     transaction::DbTx,
 };
-use reth_nippy_jar::{NippyJar, NippyJarChecker, CONFIG_FILE_EXTENSION};
+use reth_era::Era1History;
+use reth_nippy_jar::{
+    NippyJar, NippyJarChecker, NippyJarCursor, NippyJarWriter, CONFIG_FILE_EXTENSION,
+};
 use reth_primitives::{
     static_file::{
-        find_fixed_range, HighestStaticFiles, SegmentHeader, SegmentRangeInclusive,
-        DEFAULT_BLOCKS_PER_STATIC_FILE,
+        find_fixed_range, Compression, HighestStaticFiles, SegmentConfig, SegmentHeader,
+        SegmentRangeInclusive, DEFAULT_BLOCKS_PER_STATIC_FILE,
     },
     Block, BlockWithSenders, Header, Receipt, SealedBlock, SealedBlockWithSenders, SealedHeader,
-    StaticFileSegment, TransactionMeta, TransactionSigned, TransactionSignedNoHash, Withdrawal,
-    Withdrawals,
+    StaticFileSegment, StorageEntry, TransactionMeta, TransactionSigned, TransactionSignedNoHash,
+    Withdrawal, Withdrawals,
 };
 use reth_stages_types::{PipelineTarget, StageId};
 use reth_storage_api::DBProvider;
 use reth_storage_errors::provider::{ProviderError, ProviderResult};
 use std::{
-    collections::{hash_map::Entry, BTreeMap, HashMap},
+    cmp::Ordering,
+    collections::{hash_map::Entry, BTreeMap, HashMap, VecDeque},
     ops::{Deref, Range, RangeBounds, RangeInclusive},
     path::{Path, PathBuf},
     sync::{mpsc, Arc},
+    time::Instant,
 };
 use strum::IntoEnumIterator;
-use tracing::{info, trace, warn};
+use tracing::{debug, info, trace, warn};
 
 /// Alias type for a map that can be queried for block ranges from a transaction
 /// segment respectively. It uses `TxNumber` to represent the transaction end of a static file
 /// range.
 type SegmentRanges = HashMap<StaticFileSegment, BTreeMap<TxNumber, SegmentRangeInclusive>>;
 
+/// First block and transaction still available in the static files of a history segment, after
+/// the history before them was expired.
+#[derive(Debug, Clone, Copy, Default)]
+struct HistoryStart {
+    block: BlockNumber,
+    tx: Option<TxNumber>,
+}
+
+/// Static files queued for a rewrite with compression dictionaries, see
+/// [`StaticFileProvider::queue_dictionary_rewrite`].
+#[derive(Debug, Default)]
+struct DictionaryRewrites {
+    /// Segments and block ranges of the queued static files.
+    pending: VecDeque<(StaticFileSegment, SegmentRangeInclusive)>,
+    /// Whether a background thread is rewriting the queued static files.
+    running: bool,
+}
+
 /// Access mode on a static file provider. RO/RW.
 #[derive(Debug, Default, PartialEq, Eq)]
 pub enum StaticFileAccess {
@@ -73,6 +104,16 @@ impl StaticFileAccess {
     }
 }
 
+/// Directory, relative to the static files one, where static files are rewritten before
+/// replacing the original ones. Each static file is rewritten in its own subdirectory.
+pub(super) const REWRITE_DIRECTORY: &str = "rewrite";
+
+/// File marking that all files of a static file rewrite are complete.
+const REWRITE_COMMIT_FILE: &str = "commit";
+
+/// Maximum number of rows sampled from a static file to train its compression dictionaries.
+const MAX_DICTIONARY_SAMPLES: usize = 100_000;
+
 /// [`StaticFileProvider`] manages all existing [`StaticFileJarProvider`].
 #[derive(Debug, Clone)]
 pub struct StaticFileProvider(pub(crate) Arc<StaticFileProviderInner>);
@@ -81,6 +122,9 @@ impl StaticFileProvider {
     /// Creates a new [`StaticFileProvider`].
     fn new(path: impl AsRef<Path>, access: StaticFileAccess) -> ProviderResult<Self> {
         let provider = Self(Arc::new(StaticFileProviderInner::new(path, access)?));
+        if !provider.is_read_only() {
+            provider.finish_interrupted_rewrites()?;
+        }
         provider.initialize_index()?;
         Ok(provider)
     }
@@ -205,6 +249,8 @@ pub struct StaticFileProviderInner {
     static_files_max_block: RwLock<HashMap<StaticFileSegment, u64>>,
     /// Available static file block ranges on disk indexed by max transactions.
     static_files_tx_index: RwLock<SegmentRanges>,
+    /// Start of the available history for each history segment whose history was expired.
+    static_files_history_start: RwLock<HashMap<StaticFileSegment, HistoryStart>>,
     /// Directory where `static_files` are located
     path: PathBuf,
     /// Maintains a writer set of [`StaticFileSegment`].
@@ -214,6 +260,19 @@ pub struct StaticFileProviderInner {
     access: StaticFileAccess,
     /// Number of blocks per file.
     blocks_per_file: u64,
+    /// Configuration of each segment, if different from [`StaticFileSegment::config`].
+    segment_configs: HashMap<StaticFileSegment, SegmentConfig>,
+    /// Era1 files used to serve expired history.
+    era1_history: Option<Arc<Era1History>>,
+    /// Finished static files waiting to be rewritten with compression dictionaries.
+    dictionary_rewrites: Mutex<DictionaryRewrites>,
+    /// Notified when all queued dictionary rewrites are done.
+    dictionary_rewrites_done: Condvar,
+    /// Held while a snapshot of the static files is created.
+    snapshot_lock: Mutex<()>,
+    /// Held while static files are replaced by a rewrite or deleted by a history expiry, and while
+    /// a snapshot copies them.
+    files_lock: Mutex<()>,
     /// Write lock for when access is [`StaticFileAccess::RW`].
     _lock_file: Option<StorageLock>,
 }
@@ -232,10 +291,17 @@ impl StaticFileProviderInner {
             writers: Default::default(),
             static_files_max_block: Default::default(),
             static_files_tx_index: Default::default(),
+            static_files_history_start: Default::default(),
             path: path.as_ref().to_path_buf(),
             metrics: None,
             access,
             blocks_per_file: DEFAULT_BLOCKS_PER_STATIC_FILE,
+            segment_configs: Default::default(),
+            era1_history: None,
+            dictionary_rewrites: Default::default(),
+            dictionary_rewrites_done: Default::default(),
+            snapshot_lock: Default::default(),
+            files_lock: Default::default(),
             _lock_file,
         };
 
@@ -251,6 +317,11 @@ impl StaticFileProviderInner {
     pub const fn find_fixed_range(&self, block: BlockNumber) -> SegmentRangeInclusive {
         find_fixed_range(block, self.blocks_per_file)
     }
+
+    /// Returns the [`SegmentConfig`] used when creating or rewriting static files of `segment`.
+    pub fn segment_config(&self, segment: StaticFileSegment) -> SegmentConfig {
+        self.segment_configs.get(&segment).copied().unwrap_or_else(|| segment.config())
+    }
 }
 
 impl StaticFileProvider {
@@ -263,6 +334,34 @@ impl StaticFileProvider {
         Self(Arc::new(provider))
     }
 
+    /// Sets the [`SegmentConfig`] used when creating or rewriting static files of each segment.
+    ///
+    /// Existing static files keep their compression until rewritten with
+    /// [`Self::recompress_jar`].
+    pub fn with_segment_configs(
+        self,
+        configs: impl IntoIterator<Item = (StaticFileSegment, SegmentConfig)>,
+    ) -> Self {
+        let mut provider =
+            Arc::try_unwrap(self.0).expect("should be called when initializing only");
+        provider.segment_configs.extend(configs);
+        Self(Arc::new(provider))
+    }
+
+    /// Sets the Era1 files used to serve history that was expired from static files, see
+    /// [`Self::expire_history`].
+    pub fn with_era1_history(self, era1_history: Era1History) -> Self {
+        let mut provider =
+            Arc::try_unwrap(self.0).expect("should be called when initializing only");
+        provider.era1_history = Some(Arc::new(era1_history));
+        Self(Arc::new(provider))
+    }
+
+    /// Returns the Era1 files used to serve expired history, if configured.
+    pub fn era1_history(&self) -> Option<&Era1History> {
+        self.era1_history.as_deref()
+    }
+
     /// Enables metrics on the [`StaticFileProvider`].
     pub fn with_metrics(self) -> Self {
         let mut provider =
@@ -320,6 +419,10 @@ impl StaticFileProvider {
         block: BlockNumber,
         path: Option<&Path>,
     ) -> ProviderResult<StaticFileJarProvider<'_>> {
+        if let Some(start) = self.history_start(segment).filter(|start| block < start.block) {
+            return Err(ProviderError::HistoryExpired(start.block))
+        }
+
         self.get_segment_provider(
             segment,
             || self.get_segment_ranges_from_block(segment, block),
@@ -335,6 +438,12 @@ impl StaticFileProvider {
         tx: TxNumber,
         path: Option<&Path>,
     ) -> ProviderResult<StaticFileJarProvider<'_>> {
+        if let Some(start) =
+            self.history_start(segment).filter(|start| start.tx.is_some_and(|start| tx < start))
+        {
+            return Err(ProviderError::HistoryExpired(start.block))
+        }
+
         self.get_segment_provider(
             segment,
             || self.get_segment_ranges_from_transaction(segment, tx),
@@ -415,6 +524,316 @@ impl StaticFileProvider {
         Ok(())
     }
 
+    /// Expires the history of all history segments before `block`, as described in
+    /// [EIP-4444](https://eips.ethereum.org/EIPS/eip-4444). See
+    /// [`StaticFileSegment::is_history`].
+    ///
+    /// Only whole static files are deleted, so the history is expired up to the start of the static
+    /// file containing `block`. The static file holding the highest block of a segment is never
+    /// deleted.
+    ///
+    /// Returns the segments and block ranges of the deleted static files.
+    ///
+    /// CAUTION: destructive. Deletes files on disk.
+    pub fn expire_history(
+        &self,
+        block: BlockNumber,
+    ) -> ProviderResult<Vec<(StaticFileSegment, SegmentRangeInclusive)>> {
+        let _files_guard = self.lock_files();
+        let mut deleted = Vec::new();
+
+        for segment in StaticFileSegment::iter().filter(StaticFileSegment::is_history) {
+            let Some(highest_block) = self.get_highest_static_file_block(segment) else { continue };
+            let expire_before = block.min(self.find_fixed_range(highest_block).start());
+
+            let mut start = self.history_start(segment).unwrap_or_default();
+            loop {
+                let fixed_block_range = self.find_fixed_range(start.block);
+                if fixed_block_range.end() >= expire_before {
+                    break
+                }
+
+                let key = (fixed_block_range.end(), segment);
+                let jar = if let Some((_, jar)) = self.map.remove(&key) {
+                    jar.jar
+                } else {
+                    NippyJar::<SegmentHeader>::load(
+                        &self.path.join(segment.filename(&fixed_block_range)),
+                    )
+                    .map_err(|e| ProviderError::NippyJar(e.to_string()))?
+                };
+                if let Some(tx_range) = jar.user_header().tx_range() {
+                    start.tx = Some(tx_range.end() + 1);
+                }
+                start.block = fixed_block_range.end() + 1;
+
+                // Readers must see the history as expired before the file disappears, otherwise
+                // they could try to open it in between.
+                self.static_files_history_start.write().insert(segment, start);
+                if let Some(index) = self.static_files_tx_index.write().get_mut(&segment) {
+                    index.retain(|_, block_range| block_range.start() > fixed_block_range.end());
+                }
+                // A reader may have reopened the file before the history start was updated.
+                self.map.remove(&key);
+
+                jar.delete().map_err(|e| ProviderError::NippyJar(e.to_string()))?;
+
+                debug!(target: "provider::static_file", ?segment, ?fixed_block_range, "Expired static file");
+                deleted.push((segment, fixed_block_range));
+            }
+        }
+
+        Ok(deleted)
+    }
+
+    /// Returns the first block whose headers, transactions and receipts are all available in
+    /// static files, or [`None`] if no history was expired.
+    pub fn earliest_history_block(&self) -> Option<BlockNumber> {
+        self.static_files_history_start.read().values().map(|start| start.block).max()
+    }
+
+    /// Returns the start of the available history of `segment`, if its history was expired.
+    fn history_start(&self, segment: StaticFileSegment) -> Option<HistoryStart> {
+        self.static_files_history_start.read().get(&segment).copied()
+    }
+
+    /// Rewrites the static file of `segment` containing `block` with the compression set by its
+    /// [`SegmentConfig`]. When using [`Compression::ZstdWithDictionary`], the dictionaries are
+    /// trained on the static file's own data.
+    ///
+    /// If a writer is open on the same static file, it's committed and closed first.
+    pub fn recompress_jar(
+        &self,
+        segment: StaticFileSegment,
+        block: BlockNumber,
+    ) -> ProviderResult<()> {
+        let fixed_block_range = self.find_fixed_range(block);
+        self.writers.close(segment, fixed_block_range.end())?;
+        self.rewrite_jar(segment, &fixed_block_range)
+    }
+
+    /// Queues a finished static file to be rewritten with compression dictionaries trained on
+    /// its data, see [`Compression::ZstdWithDictionary`].
+    ///
+    /// Training the dictionaries and rewriting the static file is slow, so it's done by a
+    /// background thread instead of the writer.
+    pub(crate) fn queue_dictionary_rewrite(
+        &self,
+        segment: StaticFileSegment,
+        fixed_block_range: SegmentRangeInclusive,
+    ) {
+        let mut rewrites = self.dictionary_rewrites.lock();
+        rewrites.pending.push_back((segment, fixed_block_range));
+        if rewrites.running {
+            return
+        }
+
+        let provider = self.clone();
+        match std::thread::Builder::new()
+            .name("static-file-rewrite".to_string())
+            .spawn(move || provider.run_dictionary_rewrites())
+        {
+            Ok(_) => rewrites.running = true,
+            Err(err) => {
+                warn!(target: "provider::static_file", %err, "Failed to spawn static file rewrite thread");
+            }
+        }
+    }
+
+    /// Rewrites the queued static files until the queue is empty.
+    fn run_dictionary_rewrites(&self) {
+        loop {
+            let next = {
+                let mut rewrites = self.dictionary_rewrites.lock();
+                let next = rewrites.pending.pop_front();
+                if next.is_none() {
+                    rewrites.running = false;
+                    self.dictionary_rewrites_done.notify_all();
+                }
+                next
+            };
+            let Some((segment, fixed_block_range)) = next else { return };
+
+            // The static file may have been truncated and reopened by a writer in the meantime.
+            if self
+                .get_highest_static_file_block(segment)
+                .map_or(true, |highest_block| highest_block <= fixed_block_range.end())
+            {
+                debug!(target: "provider::static_file", ?segment, ?fixed_block_range, "Skipping rewrite of the latest static file");
+                continue
+            }
+
+            if let Err(err) = self.rewrite_jar(segment, &fixed_block_range) {
+                warn!(target: "provider::static_file", ?segment, ?fixed_block_range, %err, "Failed to rewrite static file with compression dictionaries");
+            }
+        }
+    }
+
+    /// Blocks until all static files queued for a rewrite with compression dictionaries are
+    /// rewritten.
+    pub fn wait_for_dictionary_rewrites(&self) {
+        let mut rewrites = self.dictionary_rewrites.lock();
+        while rewrites.running {
+            self.dictionary_rewrites_done.wait(&mut rewrites);
+        }
+    }
+
+    /// Rewrites a static file with the compression set by its [`SegmentConfig`].
+    ///
+    /// The new files are written to a subdirectory of [`REWRITE_DIRECTORY`], and only replace the
+    /// original ones once complete. If interrupted while replacing them, it's finished on the next
+    /// read-write initialization of the [`StaticFileProvider`].
+    ///
+    /// CAUTION: no writer should be open on this static file.
+    pub(crate) fn rewrite_jar(
+        &self,
+        segment: StaticFileSegment,
+        fixed_block_range: &SegmentRangeInclusive,
+    ) -> ProviderResult<()> {
+        let start = Instant::now();
+        let config = self.segment_config(segment);
+        let jar =
+            NippyJar::<SegmentHeader>::load(&self.path.join(segment.filename(fixed_block_range)))
+                .map_err(|e| ProviderError::NippyJar(e.to_string()))?;
+
+        // Clears any leftovers of a previously failed rewrite.
+        let rewrite_dir =
+            self.path.join(REWRITE_DIRECTORY).join(segment.filename(fixed_block_range));
+        if rewrite_dir.exists() {
+            reth_fs_util::remove_dir_all(&rewrite_dir)
+                .map_err(|e| ProviderError::FsPathError(e.to_string()))?;
+        }
+        reth_fs_util::create_dir_all(&rewrite_dir)
+            .map_err(|e| ProviderError::FsPathError(e.to_string()))?;
+        let empty_jar = || {
+            NippyJar::new(
+                jar.columns(),
+                &rewrite_dir.join(segment.filename(fixed_block_range)),
+                jar.user_header().clone(),
+            )
+        };
+
+        let new_jar = if config.compression == Compression::ZstdWithDictionary && jar.rows() > 0 {
+            let mut dictionary_jar = empty_jar()
+                .with_zstd(true, config.max_dict_size)
+                .with_zstd_level(config.zstd_level);
+            match dictionary_jar.prepare_compression(Self::dictionary_samples(&jar)?) {
+                Ok(()) => dictionary_jar,
+                Err(err) => {
+                    warn!(target: "provider::static_file", ?segment, ?fixed_block_range, %err, "Failed to train compression dictionaries, using zstd without them");
+                    with_compression(empty_jar(), config)
+                }
+            }
+        } else {
+            with_compression(empty_jar(), config)
+        };
+
+        let mut cursor =
+            NippyJarCursor::new(&jar).map_err(|e| ProviderError::NippyJar(e.to_string()))?;
+        let mut writer =
+            NippyJarWriter::new(new_jar).map_err(|e| ProviderError::NippyJar(e.to_string()))?;
+        while let Some(row) =
+            cursor.next_row().map_err(|e| ProviderError::NippyJar(e.to_string()))?
+        {
+            for value in row {
+                writer
+                    .append_column(Some(Ok(value)))
+                    .map_err(|e| ProviderError::NippyJar(e.to_string()))?;
+            }
+        }
+        writer.commit().map_err(|e| ProviderError::NippyJar(e.to_string()))?;
+
+        // A writer may have pruned the original static file while it was being rewritten.
+        let original =
+            NippyJar::<SegmentHeader>::load(&self.path.join(segment.filename(fixed_block_range)))
+                .map_err(|e| ProviderError::NippyJar(e.to_string()))?;
+        if original.user_header() != jar.user_header() {
+            reth_fs_util::remove_dir_all(&rewrite_dir)
+                .map_err(|e| ProviderError::FsPathError(e.to_string()))?;
+            return Err(ProviderError::NippyJar(format!(
+                "static file {} changed while being rewritten",
+                segment.filename(fixed_block_range)
+            )))
+        }
+
+        // A snapshot must not copy a mix of the original and the new files.
+        let _files_guard = self.lock_files();
+
+        // From now on, the new files will replace the original ones, even if interrupted.
+        reth_fs_util::atomic_write_file(&rewrite_dir.join(REWRITE_COMMIT_FILE), |_| {
+            Ok::<_, std::io::Error>(())
+        })
+        .map_err(|e| ProviderError::FsPathError(e.to_string()))?;
+
+        // Readers use the cached original jar while its files are being replaced, and load the
+        // new one afterwards.
+        drop(self.get_or_create_jar_provider(segment, fixed_block_range)?);
+        self.finish_rewrite(&rewrite_dir)?;
+        self.map.remove(&(fixed_block_range.end(), segment));
+
+        debug!(target: "provider::static_file", ?segment, ?fixed_block_range, compression = ?config.compression, elapsed = ?start.elapsed(), "Rewrote static file");
+
+        Ok(())
+    }
+
+    /// Samples rows of `jar`, returning them per column to train compression dictionaries.
+    fn dictionary_samples(jar: &NippyJar<SegmentHeader>) -> ProviderResult<Vec<Vec<Vec<u8>>>> {
+        let step = (jar.rows() / MAX_DICTIONARY_SAMPLES).max(1);
+        let mut cursor =
+            NippyJarCursor::new(jar).map_err(|e| ProviderError::NippyJar(e.to_string()))?;
+
+        let mut columns = vec![Vec::new(); jar.columns()];
+        for row in (0..jar.rows()).step_by(step) {
+            let Some(values) =
+                cursor.row_by_number(row).map_err(|e| ProviderError::NippyJar(e.to_string()))?
+            else {
+                break
+            };
+            for (column, value) in columns.iter_mut().zip(values) {
+                column.push(value.to_vec());
+            }
+        }
+
+        Ok(columns)
+    }
+
+    /// Finishes or discards every static file rewrite left behind by an interruption.
+    fn finish_interrupted_rewrites(&self) -> ProviderResult<()> {
+        let rewrites_dir = self.path.join(REWRITE_DIRECTORY);
+        if !rewrites_dir.exists() {
+            return Ok(())
+        }
+
+        for entry in reth_fs_util::read_dir(&rewrites_dir)
+            .map_err(|e| ProviderError::FsPathError(e.to_string()))?
+            .filter_map(Result::ok)
+        {
+            self.finish_rewrite(&entry.path())?;
+        }
+
+        Ok(())
+    }
+
+    /// Replaces the original static file with the one in `rewrite_dir` if all its files were
+    /// written, or discards it otherwise.
+    fn finish_rewrite(&self, rewrite_dir: &Path) -> ProviderResult<()> {
+        let commit_file = rewrite_dir.join(REWRITE_COMMIT_FILE);
+        if commit_file.exists() {
+            for entry in reth_fs_util::read_dir(rewrite_dir)
+                .map_err(|e| ProviderError::FsPathError(e.to_string()))?
+                .filter_map(Result::ok)
+            {
+                if entry.path() != commit_file {
+                    reth_fs_util::rename(entry.path(), self.path.join(entry.file_name()))
+                        .map_err(|e| ProviderError::FsPathError(e.to_string()))?;
+                }
+            }
+        }
+
+        reth_fs_util::remove_dir_all(rewrite_dir)
+            .map_err(|e| ProviderError::FsPathError(e.to_string()))
+    }
+
     /// Given a segment and block range it returns a cached
     /// [`StaticFileJarProvider`]. TODO(joshie): we should check the size and pop N if there's too
     /// many.
@@ -535,10 +954,10 @@ impl StaticFileProvider {
                             })
                             .or_insert_with(|| BTreeMap::from([(tx_end, current_block_range)]));
                     }
-                } else if segment.is_tx_based() {
-                    // The unwinded file has no more transactions/receipts. However, the highest
-                    // block is within this files' block range. We only retain
-                    // entries with block ranges before the current one.
+                } else if segment.is_tx_based() || segment.is_change_based() {
+                    // The unwinded file has no more transactions/receipts/changes. However, the
+                    // highest block is within this files' block range. We only
+                    // retain entries with block ranges before the current one.
                     tx_index.entry(segment).and_modify(|index| {
                         index.retain(|_, block_range| block_range.start() < fixed_range.start());
                     });
@@ -558,6 +977,7 @@ impl StaticFileProvider {
             None => {
                 tx_index.remove(&segment);
                 max_block.remove(&segment);
+                self.static_files_history_start.write().remove(&segment);
             }
         };
 
@@ -568,9 +988,11 @@ impl StaticFileProvider {
     pub fn initialize_index(&self) -> ProviderResult<()> {
         let mut max_block = self.static_files_max_block.write();
         let mut tx_index = self.static_files_tx_index.write();
+        let mut history_start = self.static_files_history_start.write();
 
         max_block.clear();
         tx_index.clear();
+        history_start.clear();
 
         for (segment, ranges) in
             iter_static_files(&self.path).map_err(|e| ProviderError::NippyJar(e.to_string()))?
@@ -580,6 +1002,21 @@ impl StaticFileProvider {
                 max_block.insert(segment, block_range.end());
             }
 
+            // Update the start of the history, if it was expired
+            if let Some((block_range, _)) = ranges.first().filter(|_| segment.is_history()) {
+                if block_range.start() > 0 {
+                    history_start.insert(
+                        segment,
+                        HistoryStart {
+                            block: block_range.start(),
+                            tx: ranges
+                                .iter()
+                                .find_map(|(_, tx_range)| tx_range.as_ref().map(|r| r.start())),
+                        },
+                    );
+                }
+            }
+
             // Update tx -> block_range index
             for (block_range, tx_range) in ranges {
                 if let Some(tx_range) = tx_range {
@@ -656,6 +1093,11 @@ impl StaticFileProvider {
 
         info!(target: "reth::cli", "Verifying storage consistency.");
 
+        // Snap synced nodes have no receipts before the pivot block and store the receipts after it
+        // in the database, same as pruned nodes.
+        let has_receipt_pruning =
+            has_receipt_pruning || snap_sync_pivot(provider.tx_ref())?.is_some();
+
         let mut unwind_target: Option<BlockNumber> = None;
         let mut update_unwind_target = |new_target: BlockNumber| {
             if let Some(target) = unwind_target.as_mut() {
@@ -710,7 +1152,7 @@ impl StaticFileProvider {
             // from a pruning interruption might have decreased the number of transactions without
             // being able to update the last block of the static file segment.
             let highest_tx = self.get_highest_static_file_tx(segment);
-            if let Some(highest_tx) = highest_tx {
+            if let Some(highest_tx) = highest_tx.filter(|_| segment.is_tx_based()) {
                 let mut last_block = highest_block.unwrap_or_default();
                 loop {
                     if let Some(indices) = provider.block_body_indices(last_block)? {
@@ -760,6 +1202,9 @@ impl StaticFileProvider {
                     highest_tx,
                     highest_block,
                 )?,
+                StaticFileSegment::AccountChangeSets | StaticFileSegment::StorageChangeSets => {
+                    self.ensure_changeset_invariants(provider, segment, highest_block)?
+                }
             } {
                 update_unwind_target(unwind);
             }
@@ -844,7 +1289,9 @@ impl StaticFileProvider {
             .get_stage_checkpoint(match segment {
                 StaticFileSegment::Headers => StageId::Headers,
                 StaticFileSegment::Transactions => StageId::Bodies,
-                StaticFileSegment::Receipts => StageId::Execution,
+                StaticFileSegment::Receipts |
+                StaticFileSegment::AccountChangeSets |
+                StaticFileSegment::StorageChangeSets => StageId::Execution,
             })?
             .unwrap_or_default()
             .block_number;
@@ -889,6 +1336,46 @@ impl StaticFileProvider {
         Ok(None)
     }
 
+    /// Check invariants for the changeset segments.
+    ///
+    /// Changesets are moved to static files only after they have been written to the database,
+    /// so the database table may always overlap with the static file. The only invariant is that
+    /// the highest static file block is not ahead of the execution stage checkpoint. Otherwise,
+    /// an unwind committed to the database **but not** to the static files, and the extra static
+    /// file rows are pruned, since they have already been restored to the database.
+    ///
+    /// Changesets cannot be recovered once lost, so this never returns an unwind target.
+    fn ensure_changeset_invariants<Provider>(
+        &self,
+        provider: &Provider,
+        segment: StaticFileSegment,
+        highest_static_file_block: Option<BlockNumber>,
+    ) -> ProviderResult<Option<BlockNumber>>
+    where
+        Provider: DBProvider + StageCheckpointReader,
+    {
+        let Some(highest_static_file_block) = highest_static_file_block else { return Ok(None) };
+
+        let checkpoint_block_number =
+            provider.get_stage_checkpoint(StageId::Execution)?.unwrap_or_default().block_number;
+
+        if checkpoint_block_number < highest_static_file_block {
+            info!(
+                target: "reth::providers",
+                ?segment,
+                from = highest_static_file_block,
+                to = checkpoint_block_number,
+                "Unwinding static file segment."
+            );
+            let to_delete = self.changes_after_block(segment, checkpoint_block_number)?;
+            let mut writer = self.latest_writer(segment)?;
+            writer.prune_changesets(to_delete, checkpoint_block_number)?;
+            writer.commit()?;
+        }
+
+        Ok(None)
+    }
+
     /// Gets the highest static file block if it exists for a static file segment.
     ///
     /// If there is nothing on disk for the given segment, this will return [`None`].
@@ -912,9 +1399,204 @@ impl StaticFileProvider {
             headers: self.get_highest_static_file_block(StaticFileSegment::Headers),
             receipts: self.get_highest_static_file_block(StaticFileSegment::Receipts),
             transactions: self.get_highest_static_file_block(StaticFileSegment::Transactions),
+            account_changesets: self
+                .get_highest_static_file_block(StaticFileSegment::AccountChangeSets),
+            storage_changesets: self
+                .get_highest_static_file_block(StaticFileSegment::StorageChangeSets),
         }
     }
 
+    /// Returns the number of changes stored in a changeset segment for all blocks lower than
+    /// `block`. This is also the change number of the first change at `block` or after it.
+    pub fn changes_before_block(
+        &self,
+        segment: StaticFileSegment,
+        block: BlockNumber,
+    ) -> ProviderResult<u64> {
+        debug_assert!(segment.is_change_based());
+
+        let Some(highest_block) = self.get_highest_static_file_block(segment) else { return Ok(0) };
+        if block > highest_block {
+            return Ok(self.get_highest_static_file_tx(segment).map_or(0, |change| change + 1))
+        }
+
+        // Changes of previous static files come before the static file containing `block`.
+        let fixed_range = self.find_fixed_range(block);
+        let previous_changes = self
+            .static_files_tx_index
+            .read()
+            .get(&segment)
+            .and_then(|index| {
+                index
+                    .iter()
+                    .rev()
+                    .find(|(_, block_range)| block_range.start() < fixed_range.start())
+                    .map(|(change_end, _)| change_end + 1)
+            })
+            .unwrap_or_default();
+
+        let provider = self.get_segment_provider_from_block(segment, block, None)?;
+        let Some(change_range) = provider.user_header().tx_range().copied() else {
+            return Ok(previous_changes)
+        };
+
+        // Rows are sorted by block, so we look for the first change with a block number equal or
+        // higher than `block`.
+        let mut cursor = provider.cursor()?;
+        let (mut low, mut high) = (change_range.start(), change_range.end() + 1);
+        while low < high {
+            let mid = low + (high - low) / 2;
+            let change_block = match segment {
+                StaticFileSegment::AccountChangeSets => {
+                    cursor.get_one::<AccountChangeSetMask<CompactU64>>(mid.into())?
+                }
+                _ => cursor.get_one::<StorageChangeSetMask<CompactU64>>(mid.into())?,
+            }
+            .ok_or(ProviderError::MissingStaticFileTx(segment, mid))?;
+
+            if *change_block < block {
+                low = mid + 1;
+            } else {
+                high = mid;
+            }
+        }
+
+        Ok(low)
+    }
+
+    /// Returns the number of changes stored in a changeset segment for all blocks higher than
+    /// `block`.
+    pub fn changes_after_block(
+        &self,
+        segment: StaticFileSegment,
+        block: BlockNumber,
+    ) -> ProviderResult<u64> {
+        let next_change = self.get_highest_static_file_tx(segment).map_or(0, |change| change + 1);
+        Ok(next_change - self.changes_before_block(segment, block.saturating_add(1))?)
+    }
+
+    /// Returns the account changesets of a block range from static files, sorted by block and
+    /// address.
+    pub fn account_changesets_range(
+        &self,
+        range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<Vec<(BlockNumber, AccountBeforeTx)>> {
+        self.account_changesets_iter(range)?.collect()
+    }
+
+    /// Returns an iterator over the account changesets of a block range from static files, sorted
+    /// by block and address.
+    pub fn account_changesets_iter(
+        &self,
+        range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<impl Iterator<Item = ProviderResult<(BlockNumber, AccountBeforeTx)>> + '_>
+    {
+        let segment = StaticFileSegment::AccountChangeSets;
+        let start = self.changes_before_block(segment, *range.start())?;
+        let end = self.changes_before_block(segment, range.end().saturating_add(1))?;
+        if start >= end {
+            return Ok(Either::Left(std::iter::empty()))
+        }
+
+        Ok(Either::Right(self.fetch_range_iter(segment, start..end, |cursor, number| {
+            Ok(cursor
+                .get_two::<AccountChangeSetMask<CompactU64, AccountBeforeTx>>(number.into())?
+                .map(|(block, change)| (*block, change)))
+        })?))
+    }
+
+    /// Returns the storage changesets of a block range from static files, sorted by block,
+    /// address and storage key.
+    pub fn storage_changesets_range(
+        &self,
+        range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<Vec<(BlockNumberAddress, StorageEntry)>> {
+        self.storage_changesets_iter(range)?.collect()
+    }
+
+    /// Returns an iterator over the storage changesets of a block range from static files, sorted
+    /// by block, address and storage key.
+    pub fn storage_changesets_iter(
+        &self,
+        range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<impl Iterator<Item = ProviderResult<(BlockNumberAddress, StorageEntry)>> + '_>
+    {
+        let segment = StaticFileSegment::StorageChangeSets;
+        let start = self.changes_before_block(segment, *range.start())?;
+        let end = self.changes_before_block(segment, range.end().saturating_add(1))?;
+        if start >= end {
+            return Ok(Either::Left(std::iter::empty()))
+        }
+
+        Ok(Either::Right(self.fetch_range_iter(segment, start..end, |cursor, number| {
+            Ok(cursor
+                .get_three::<StorageChangeSetMask<CompactU64, Address, StorageEntry>>(
+                    number.into(),
+                )?
+                .map(|(block, address, entry)| (BlockNumberAddress((*block, address)), entry)))
+        })?))
+    }
+
+    /// Returns the state of an account before `block`, if it was changed in `block`.
+    pub fn account_change(
+        &self,
+        block: BlockNumber,
+        address: Address,
+    ) -> ProviderResult<Option<AccountBeforeTx>> {
+        let segment = StaticFileSegment::AccountChangeSets;
+        self.find_block_change(segment, block, |cursor, number| {
+            let change = cursor
+                .get_one::<AccountChangeSetMask<AccountBeforeTx>>(number.into())?
+                .ok_or(ProviderError::MissingStaticFileTx(segment, number))?;
+            Ok((change.address.cmp(&address), change))
+        })
+    }
+
+    /// Returns the value of a storage slot before `block`, if it was changed in `block`.
+    pub fn storage_change(
+        &self,
+        block: BlockNumber,
+        address: Address,
+        key: B256,
+    ) -> ProviderResult<Option<StorageEntry>> {
+        let segment = StaticFileSegment::StorageChangeSets;
+        self.find_block_change(segment, block, |cursor, number| {
+            let (change_address, entry) = cursor
+                .get_two::<StorageChangeSetMask<Address, StorageEntry>>(number.into())?
+                .ok_or(ProviderError::MissingStaticFileTx(segment, number))?;
+            Ok(((change_address, entry.key).cmp(&(address, key)), entry))
+        })
+    }
+
+    /// Binary searches the changes of `block` in a changeset segment, whose rows are sorted by
+    /// block and then by the searched key. `compare` decodes the change with the given number and
+    /// returns it alongside its ordering relative to the searched key.
+    fn find_block_change<T>(
+        &self,
+        segment: StaticFileSegment,
+        block: BlockNumber,
+        mut compare: impl FnMut(&mut StaticFileCursor<'_>, u64) -> ProviderResult<(Ordering, T)>,
+    ) -> ProviderResult<Option<T>> {
+        let mut low = self.changes_before_block(segment, block)?;
+        let mut high = self.changes_before_block(segment, block.saturating_add(1))?;
+        if low >= high {
+            return Ok(None)
+        }
+
+        let provider = self.get_segment_provider_from_block(segment, block, None)?;
+        let mut cursor = provider.cursor()?;
+        while low < high {
+            let mid = low + (high - low) / 2;
+            match compare(&mut cursor, mid)? {
+                (Ordering::Less, _) => low = mid + 1,
+                (Ordering::Greater, _) => high = mid,
+                (Ordering::Equal, change) => return Ok(Some(change)),
+            }
+        }
+
+        Ok(None)
+    }
+
     /// Iterates through segment `static_files` in reverse order, executing a function until it
     /// returns some object. Useful for finding objects by [`TxHash`] or [`BlockHash`].
     pub fn find_static_file<T>(
@@ -923,8 +1605,9 @@ impl StaticFileProvider {
         func: impl Fn(StaticFileJarProvider<'_>) -> ProviderResult<Option<T>>,
     ) -> ProviderResult<Option<T>> {
         if let Some(highest_block) = self.get_highest_static_file_block(segment) {
+            let lowest_block = self.history_start(segment).map_or(0, |start| start.block);
             let mut range = self.find_fixed_range(highest_block);
-            while range.end() > 0 {
+            while range.end() > 0 && range.end() >= lowest_block {
                 if let Some(res) = func(self.get_or_create_jar_provider(segment, &range)?)? {
                     return Ok(Some(res))
                 }
@@ -958,7 +1641,10 @@ impl StaticFileProvider {
             StaticFileSegment::Headers => {
                 self.get_segment_provider_from_block(segment, start, None)
             }
-            StaticFileSegment::Transactions | StaticFileSegment::Receipts => {
+            StaticFileSegment::Transactions |
+            StaticFileSegment::Receipts |
+            StaticFileSegment::AccountChangeSets |
+            StaticFileSegment::StorageChangeSets => {
                 self.get_segment_provider_from_transaction(segment, start, None)
             }
         };
@@ -1034,7 +1720,10 @@ impl StaticFileProvider {
             StaticFileSegment::Headers => {
                 self.get_segment_provider_from_block(segment, start, None)
             }
-            StaticFileSegment::Transactions | StaticFileSegment::Receipts => {
+            StaticFileSegment::Transactions |
+            StaticFileSegment::Receipts |
+            StaticFileSegment::AccountChangeSets |
+            StaticFileSegment::StorageChangeSets => {
                 self.get_segment_provider_from_transaction(segment, start, None)
             }
         };
@@ -1061,6 +1750,17 @@ impl StaticFileProvider {
         &self.path
     }
 
+    /// Returns a guard that must be held while a snapshot of the static files is created, or
+    /// [`None`] if another snapshot is being created.
+    pub(crate) fn try_lock_snapshot(&self) -> Option<MutexGuard<'_, ()>> {
+        self.snapshot_lock.try_lock()
+    }
+
+    /// Returns a guard that keeps static files from being replaced or deleted while it's held.
+    pub(crate) fn lock_files(&self) -> MutexGuard<'_, ()> {
+        self.files_lock.lock()
+    }
+
     /// Retrieves data from the database or static file, wherever it's available.
     ///
     /// # Arguments
@@ -1084,9 +1784,10 @@ impl StaticFileProvider {
         // If there is, check the maximum block or transaction number of the segment.
         let static_file_upper_bound = match segment {
             StaticFileSegment::Headers => self.get_highest_static_file_block(segment),
-            StaticFileSegment::Transactions | StaticFileSegment::Receipts => {
-                self.get_highest_static_file_tx(segment)
-            }
+            StaticFileSegment::Transactions |
+            StaticFileSegment::Receipts |
+            StaticFileSegment::AccountChangeSets |
+            StaticFileSegment::StorageChangeSets => self.get_highest_static_file_tx(segment),
         };
 
         if static_file_upper_bound
@@ -1126,9 +1827,10 @@ impl StaticFileProvider {
         // If there is, check the maximum block or transaction number of the segment.
         if let Some(static_file_upper_bound) = match segment {
             StaticFileSegment::Headers => self.get_highest_static_file_block(segment),
-            StaticFileSegment::Transactions | StaticFileSegment::Receipts => {
-                self.get_highest_static_file_tx(segment)
-            }
+            StaticFileSegment::Transactions |
+            StaticFileSegment::Receipts |
+            StaticFileSegment::AccountChangeSets |
+            StaticFileSegment::StorageChangeSets => self.get_highest_static_file_tx(segment),
         } {
             if block_or_tx_range.start <= static_file_upper_bound {
                 let end = block_or_tx_range.end.min(static_file_upper_bound + 1);
@@ -1652,6 +2354,28 @@ impl RequestsProvider for StaticFileProvider {
     }
 }
 
+impl ChangeSetReader for StaticFileProvider {
+    fn account_block_changeset(
+        &self,
+        block_number: BlockNumber,
+    ) -> ProviderResult<Vec<AccountBeforeTx>> {
+        Ok(self
+            .account_changesets_range(block_number..=block_number)?
+            .into_iter()
+            .map(|(_, change)| change)
+            .collect())
+    }
+}
+
+impl StorageChangeSetReader for StaticFileProvider {
+    fn storage_changeset(
+        &self,
+        block_number: BlockNumber,
+    ) -> ProviderResult<Vec<(BlockNumberAddress, StorageEntry)>> {
+        self.storage_changesets_range(block_number..=block_number)
+    }
+}
+
 impl StatsReader for StaticFileProvider {
     fn count_entries<T: Table>(&self) -> ProviderResult<usize> {
         match T::NAME {
@@ -1670,6 +2394,14 @@ impl StatsReader for StaticFileProvider {
                 .get_highest_static_file_tx(StaticFileSegment::Transactions)
                 .map(|txs| txs + 1)
                 .unwrap_or_default() as usize),
+            tables::AccountChangeSets::NAME => Ok(self
+                .get_highest_static_file_tx(StaticFileSegment::AccountChangeSets)
+                .map(|changes| changes + 1)
+                .unwrap_or_default() as usize),
+            tables::StorageChangeSets::NAME => Ok(self
+                .get_highest_static_file_tx(StaticFileSegment::StorageChangeSets)
+                .map(|changes| changes + 1)
+                .unwrap_or_default() as usize),
             _ => Err(ProviderError::UnsupportedProvider),
         }
     }
@@ -54,19 +54,24 @@ impl Deref for LoadedJar {
 
 #[cfg(test)]
 mod tests {
-    use super::*;
+    use super::{manager::REWRITE_DIRECTORY, *};
     use crate::{test_utils::create_test_provider_factory, HeaderProvider};
     use alloy_consensus::Transaction;
-    use alloy_primitives::{BlockHash, TxNumber, B256, U256};
+    use alloy_primitives::{Address, BlockHash, TxNumber, B256, U256};
+    use assert_matches::assert_matches;
     use rand::seq::SliceRandom;
     use reth_db::{
         test_utils::create_test_static_files_dir, CanonicalHeaders, HeaderNumbers,
         HeaderTerminalDifficulties, Headers,
     };
-    use reth_db_api::transaction::DbTxMut;
+    use reth_db_api::{models::AccountBeforeTx, transaction::DbTxMut};
+    use reth_nippy_jar::compression::Compressors;
     use reth_primitives::{
-        static_file::{find_fixed_range, SegmentRangeInclusive, DEFAULT_BLOCKS_PER_STATIC_FILE},
-        Header, Receipt, TransactionSignedNoHash,
+        static_file::{
+            find_fixed_range, Compression, SegmentConfig, SegmentRangeInclusive,
+            DEFAULT_BLOCKS_PER_STATIC_FILE,
+        },
+        Account, Header, Receipt, StorageEntry, TransactionSignedNoHash,
     };
     use reth_storage_api::{ReceiptProvider, TransactionsProvider};
     use reth_testing_utils::generators::{self, random_header_range};
@@ -547,4 +552,285 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_changeset_static_files() {
+        let blocks_per_file = 10;
+        let (static_dir, _) = create_test_static_files_dir();
+        let sf_rw = StaticFileProvider::read_write(&static_dir)
+            .expect("Failed to create static file provider")
+            .with_custom_blocks_per_file(blocks_per_file);
+
+        // Blocks `10..=19` have no changes, every other block has `block % 3` changes.
+        let changes_in_block = |block: u64| if (10..20).contains(&block) { 0 } else { block % 3 };
+        let account_change = |block: u64, index: u64| AccountBeforeTx {
+            address: Address::with_last_byte(index as u8),
+            info: Some(Account { nonce: block, ..Default::default() }),
+        };
+
+        let mut account_writer = sf_rw.latest_writer(StaticFileSegment::AccountChangeSets).unwrap();
+        let mut storage_writer = sf_rw.latest_writer(StaticFileSegment::StorageChangeSets).unwrap();
+        for block in 0..30 {
+            account_writer.increment_block(block).unwrap();
+            account_writer
+                .append_account_changeset(
+                    block,
+                    (0..changes_in_block(block)).map(|index| account_change(block, index)),
+                )
+                .unwrap();
+
+            storage_writer.increment_block(block).unwrap();
+            storage_writer
+                .append_storage_changeset(
+                    block,
+                    (0..changes_in_block(block)).map(|index| {
+                        (
+                            Address::with_last_byte(index as u8),
+                            StorageEntry { key: B256::with_last_byte(1), value: U256::from(block) },
+                        )
+                    }),
+                )
+                .unwrap();
+        }
+        account_writer.commit().unwrap();
+        storage_writer.commit().unwrap();
+        drop(account_writer);
+        drop(storage_writer);
+
+        let total_changes = (0..30).map(changes_in_block).sum::<u64>();
+        for segment in [StaticFileSegment::AccountChangeSets, StaticFileSegment::StorageChangeSets]
+        {
+            assert_eq!(sf_rw.get_highest_static_file_block(segment), Some(29));
+            assert_eq!(sf_rw.get_highest_static_file_tx(segment), Some(total_changes - 1));
+
+            for block in 0..=30 {
+                assert_eq!(
+                    sf_rw.changes_before_block(segment, block).unwrap(),
+                    (0..block).map(changes_in_block).sum::<u64>(),
+                    "block {block}"
+                );
+            }
+        }
+
+        // Ranges spanning multiple static files, including the one without changes
+        let expected_account_changes = |blocks: Range<u64>| {
+            blocks
+                .flat_map(|block| {
+                    (0..changes_in_block(block))
+                        .map(move |index| (block, account_change(block, index)))
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            sf_rw.account_changesets_range(5..=25).unwrap(),
+            expected_account_changes(5..26)
+        );
+        assert_eq!(
+            sf_rw.storage_changesets_range(5..=25).unwrap().len() as u64,
+            (5..=25).map(changes_in_block).sum::<u64>()
+        );
+
+        // Point lookups
+        assert_eq!(
+            sf_rw.account_change(23, Address::with_last_byte(1)).unwrap(),
+            Some(account_change(23, 1))
+        );
+        assert_eq!(sf_rw.account_change(24, Address::with_last_byte(0)).unwrap(), None);
+        assert_eq!(
+            sf_rw
+                .storage_change(8, Address::with_last_byte(1), B256::with_last_byte(1))
+                .unwrap()
+                .map(|entry| entry.value),
+            Some(U256::from(8))
+        );
+
+        // Every change of a block is found, while addresses it didn't change are not
+        for block in 0..30 {
+            for index in 0..=changes_in_block(block) {
+                let address = Address::with_last_byte(index as u8);
+                let changed = index < changes_in_block(block);
+                assert_eq!(
+                    sf_rw.account_change(block, address).unwrap(),
+                    changed.then(|| account_change(block, index)),
+                    "block {block}, index {index}"
+                );
+                assert_eq!(
+                    sf_rw
+                        .storage_change(block, address, B256::with_last_byte(1))
+                        .unwrap()
+                        .map(|entry| entry.value),
+                    changed.then(|| U256::from(block)),
+                    "block {block}, index {index}"
+                );
+                assert_eq!(
+                    sf_rw.storage_change(block, address, B256::with_last_byte(2)).unwrap(),
+                    None
+                );
+            }
+        }
+
+        // Unwind to block 8, which deletes the static files of the following block ranges
+        let to_delete = sf_rw.changes_after_block(StaticFileSegment::AccountChangeSets, 8).unwrap();
+        let mut account_writer = sf_rw.latest_writer(StaticFileSegment::AccountChangeSets).unwrap();
+        account_writer.prune_changesets(to_delete, 8).unwrap();
+        account_writer.commit().unwrap();
+        drop(account_writer);
+
+        let segment = StaticFileSegment::AccountChangeSets;
+        assert_eq!(sf_rw.get_highest_static_file_block(segment), Some(8));
+        assert_eq!(
+            sf_rw.get_highest_static_file_tx(segment),
+            Some((0..=8).map(changes_in_block).sum::<u64>() - 1)
+        );
+        assert_eq!(sf_rw.account_changesets_range(0..=30).unwrap(), expected_account_changes(0..9));
+    }
+
+    #[test]
+    fn test_expire_history() {
+        let blocks_per_file = 10;
+        let (static_dir, _) = create_test_static_files_dir();
+        let headers = random_header_range(&mut generators::rng(), 0..30, B256::ZERO);
+
+        {
+            let sf_rw = StaticFileProvider::read_write(&static_dir)
+                .unwrap()
+                .with_custom_blocks_per_file(blocks_per_file);
+
+            // One transaction per block, using the block number as nonce and transaction number
+            let mut headers_writer = sf_rw.latest_writer(StaticFileSegment::Headers).unwrap();
+            let mut transactions_writer =
+                sf_rw.latest_writer(StaticFileSegment::Transactions).unwrap();
+            let mut tx = TransactionSignedNoHash::default();
+            for header in headers.clone() {
+                let hash = header.hash();
+                headers_writer.append_header(&header.unseal(), U256::ZERO, &hash).unwrap();
+
+                transactions_writer.increment_block(header.number).unwrap();
+                tx.transaction.set_nonce(header.number);
+                transactions_writer.append_transaction(header.number, &tx).unwrap();
+            }
+            headers_writer.commit().unwrap();
+            transactions_writer.commit().unwrap();
+        }
+
+        let assert_expired = |sf_rw: &StaticFileProvider| {
+            assert_eq!(sf_rw.earliest_history_block(), Some(20));
+            assert_matches!(sf_rw.header_by_number(5), Err(ProviderError::HistoryExpired(20)));
+            assert_matches!(sf_rw.transaction_by_id(19), Err(ProviderError::HistoryExpired(20)));
+            assert_eq!(sf_rw.header_by_number(20).unwrap().as_ref(), Some(headers[20].header()));
+            assert_eq!(sf_rw.transaction_by_id(20).unwrap().map(|tx| tx.nonce()), Some(20));
+            assert_eq!(
+                sf_rw.header(&headers[25].hash()).unwrap().as_ref(),
+                Some(headers[25].header())
+            );
+            assert_eq!(sf_rw.header(&headers[5].hash()).unwrap(), None);
+        };
+
+        {
+            let sf_rw = StaticFileProvider::read_write(&static_dir)
+                .unwrap()
+                .with_custom_blocks_per_file(blocks_per_file);
+            assert_eq!(sf_rw.earliest_history_block(), None);
+
+            // The static file holding the highest block is never deleted
+            let deleted = sf_rw.expire_history(100).unwrap();
+            assert_eq!(
+                deleted,
+                vec![
+                    (StaticFileSegment::Headers, SegmentRangeInclusive::new(0, 9)),
+                    (StaticFileSegment::Headers, SegmentRangeInclusive::new(10, 19)),
+                    (StaticFileSegment::Transactions, SegmentRangeInclusive::new(0, 9)),
+                    (StaticFileSegment::Transactions, SegmentRangeInclusive::new(10, 19)),
+                ]
+            );
+            assert_expired(&sf_rw);
+            assert!(sf_rw.expire_history(100).unwrap().is_empty());
+        }
+
+        // The start of the history is restored from the static files on disk
+        let sf_rw = StaticFileProvider::read_write(&static_dir)
+            .unwrap()
+            .with_custom_blocks_per_file(blocks_per_file);
+        assert_expired(&sf_rw);
+    }
+
+    #[test]
+    fn test_recompress_static_files() {
+        let static_dir = tempfile::tempdir().unwrap();
+        let segment = StaticFileSegment::Headers;
+        let headers = random_header_range(&mut generators::rng(), 0..25, B256::ZERO);
+
+        let compression_of = |sf_rw: &StaticFileProvider, block| {
+            let jar = sf_rw.get_segment_provider_from_block(segment, block, None).unwrap();
+            match jar.compressor() {
+                Some(Compressors::Zstd(zstd)) if zstd.use_dict => Compression::ZstdWithDictionary,
+                Some(Compressors::Zstd(_)) => Compression::Zstd,
+                Some(Compressors::Lz4(_)) => Compression::Lz4,
+                None => Compression::Uncompressed,
+            }
+        };
+        let assert_headers = |sf_rw: &StaticFileProvider| {
+            for header in &headers {
+                assert_eq!(
+                    sf_rw.header_by_number(header.number).unwrap().as_ref(),
+                    Some(header.header())
+                );
+            }
+        };
+
+        // Finished static files are rewritten with dictionaries in the background once the writer
+        // moves on to the next one, while the latest one keeps plain zstd.
+        {
+            let sf_rw = StaticFileProvider::read_write(&static_dir)
+                .unwrap()
+                .with_custom_blocks_per_file(10)
+                .with_segment_configs([(
+                    segment,
+                    SegmentConfig::new(Compression::ZstdWithDictionary).with_zstd_level(19),
+                )]);
+
+            let mut writer = sf_rw.latest_writer(segment).unwrap();
+            let mut td = U256::ZERO;
+            for header in headers.clone() {
+                td += header.header().difficulty;
+                let hash = header.hash();
+                writer.append_header(&header.unseal(), td, &hash).unwrap();
+            }
+            writer.commit().unwrap();
+            drop(writer);
+            sf_rw.wait_for_dictionary_rewrites();
+
+            for block in [0, 10] {
+                assert_eq!(compression_of(&sf_rw, block), Compression::ZstdWithDictionary);
+            }
+            assert_eq!(compression_of(&sf_rw, 20), Compression::Zstd);
+            assert_headers(&sf_rw);
+            assert_eq!(fs::read_dir(static_dir.path().join(REWRITE_DIRECTORY)).unwrap().count(), 0);
+        }
+
+        // Existing static files keep their compression until recompressed.
+        {
+            let sf_rw = StaticFileProvider::read_write(&static_dir)
+                .unwrap()
+                .with_custom_blocks_per_file(10)
+                .with_segment_configs([(segment, SegmentConfig::new(Compression::Lz4))]);
+
+            assert_eq!(compression_of(&sf_rw, 0), Compression::ZstdWithDictionary);
+            sf_rw.recompress_jar(segment, 0).unwrap();
+            assert_eq!(compression_of(&sf_rw, 0), Compression::Lz4);
+
+            // The latest static file can be recompressed and appended to afterwards.
+            sf_rw.recompress_jar(segment, 20).unwrap();
+            assert_eq!(compression_of(&sf_rw, 20), Compression::Lz4);
+            assert_eq!(compression_of(&sf_rw, 10), Compression::ZstdWithDictionary);
+            assert_headers(&sf_rw);
+
+            let mut writer = sf_rw.latest_writer(segment).unwrap();
+            let header = Header { number: 25, ..Default::default() };
+            writer.append_header(&header, U256::ZERO, &B256::ZERO).unwrap();
+            writer.commit().unwrap();
+            drop(writer);
+            assert_eq!(sf_rw.header_by_number(25).unwrap(), Some(header));
+        }
+    }
 }
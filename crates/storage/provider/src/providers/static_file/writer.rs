@@ -2,14 +2,14 @@ use super::{
     manager::StaticFileProviderInner, metrics::StaticFileProviderMetrics, StaticFileProvider,
 };
 use crate::providers::static_file::metrics::StaticFileProviderOperation;
-use alloy_primitives::{BlockHash, BlockNumber, TxNumber, U256};
+use alloy_primitives::{Address, BlockHash, BlockNumber, TxNumber, U256};
 use parking_lot::{lock_api::RwLockWriteGuard, RawRwLock, RwLock};
 use reth_codecs::Compact;
-use reth_db_api::models::CompactU256;
+use reth_db_api::models::{AccountBeforeTx, CompactU256, CompactU64};
 use reth_nippy_jar::{NippyJar, NippyJarError, NippyJarWriter};
 use reth_primitives::{
-    static_file::{SegmentHeader, SegmentRangeInclusive},
-    Header, Receipt, StaticFileSegment, TransactionSignedNoHash,
+    static_file::{Compression, SegmentConfig, SegmentHeader, SegmentRangeInclusive},
+    Header, Receipt, StaticFileSegment, StorageEntry, TransactionSignedNoHash,
 };
 use reth_storage_errors::provider::{ProviderError, ProviderResult};
 use std::{
@@ -29,19 +29,27 @@ pub(crate) struct StaticFileWriters {
     headers: RwLock<Option<StaticFileProviderRW>>,
     transactions: RwLock<Option<StaticFileProviderRW>>,
     receipts: RwLock<Option<StaticFileProviderRW>>,
+    account_changesets: RwLock<Option<StaticFileProviderRW>>,
+    storage_changesets: RwLock<Option<StaticFileProviderRW>>,
 }
 
 impl StaticFileWriters {
+    const fn writer(&self, segment: StaticFileSegment) -> &RwLock<Option<StaticFileProviderRW>> {
+        match segment {
+            StaticFileSegment::Headers => &self.headers,
+            StaticFileSegment::Transactions => &self.transactions,
+            StaticFileSegment::Receipts => &self.receipts,
+            StaticFileSegment::AccountChangeSets => &self.account_changesets,
+            StaticFileSegment::StorageChangeSets => &self.storage_changesets,
+        }
+    }
+
     pub(crate) fn get_or_create(
         &self,
         segment: StaticFileSegment,
         create_fn: impl FnOnce() -> ProviderResult<StaticFileProviderRW>,
     ) -> ProviderResult<StaticFileProviderRWRefMut<'_>> {
-        let mut write_guard = match segment {
-            StaticFileSegment::Headers => self.headers.write(),
-            StaticFileSegment::Transactions => self.transactions.write(),
-            StaticFileSegment::Receipts => self.receipts.write(),
-        };
+        let mut write_guard = self.writer(segment).write();
 
         if write_guard.is_none() {
             *write_guard = Some(create_fn()?);
@@ -50,8 +58,31 @@ impl StaticFileWriters {
         Ok(StaticFileProviderRWRefMut(write_guard))
     }
 
+    /// Commits and closes the writer of `segment`, if it's open on the static file ending at
+    /// `fixed_block_range_end`.
+    pub(crate) fn close(
+        &self,
+        segment: StaticFileSegment,
+        fixed_block_range_end: BlockNumber,
+    ) -> ProviderResult<()> {
+        let mut write_guard = self.writer(segment).write();
+        if let Some(writer) = write_guard.as_mut() {
+            if writer.user_header().expected_block_end() == fixed_block_range_end {
+                writer.commit()?;
+                *write_guard = None;
+            }
+        }
+        Ok(())
+    }
+
     pub(crate) fn commit(&self) -> ProviderResult<()> {
-        for writer_lock in [&self.headers, &self.transactions, &self.receipts] {
+        for writer_lock in [
+            &self.headers,
+            &self.transactions,
+            &self.receipts,
+            &self.account_changesets,
+            &self.storage_changesets,
+        ] {
             let mut writer = writer_lock.write();
             if let Some(writer) = writer.as_mut() {
                 writer.commit()?;
@@ -152,7 +183,8 @@ impl StaticFileProviderRW {
             ),
             Err(ProviderError::MissingStaticFileBlock(_, _)) => {
                 let path = static_file_provider.directory().join(segment.filename(&block_range));
-                (create_jar(segment, &path, block_range), path)
+                let config = static_file_provider.segment_config(segment);
+                (create_jar(segment, &path, block_range, config), path)
             }
             Err(err) => return Err(err),
         };
@@ -217,6 +249,12 @@ impl StaticFileProviderRW {
                 StaticFileSegment::Receipts => {
                     self.prune_receipt_data(to_delete, last_block_number.expect("should exist"))?
                 }
+                segment @ (StaticFileSegment::AccountChangeSets |
+                StaticFileSegment::StorageChangeSets) => self.prune_changeset_data(
+                    segment,
+                    to_delete,
+                    last_block_number.expect("should exist"),
+                )?,
             }
         }
 
@@ -334,6 +372,13 @@ impl StaticFileProviderRW {
                     None,
                     segment,
                 );
+
+                // The finished static file won't be appended to anymore, so its dictionaries can
+                // now be trained on its data.
+                let reader = self.reader();
+                if reader.segment_config(segment).compression == Compression::ZstdWithDictionary {
+                    reader.queue_dictionary_rewrite(segment, reader.find_fixed_range(last_block));
+                }
             }
         }
 
@@ -387,7 +432,10 @@ impl StaticFileProviderRW {
                 StaticFileSegment::Headers => {
                     self.writer.user_header().block_len().unwrap_or_default()
                 }
-                StaticFileSegment::Transactions | StaticFileSegment::Receipts => {
+                StaticFileSegment::Transactions |
+                StaticFileSegment::Receipts |
+                StaticFileSegment::AccountChangeSets |
+                StaticFileSegment::StorageChangeSets => {
                     self.writer.user_header().tx_len().unwrap_or_default()
                 }
             };
@@ -399,9 +447,9 @@ impl StaticFileProviderRW {
 
                 // We only delete the file if it's NOT the first static file AND:
                 // * it's a Header segment  OR
-                // * it's a tx-based segment AND `last_block` is lower than the first block of this
-                //   file's block range. Otherwise, having no rows simply means that this block
-                //   range has no transactions, but the file should remain.
+                // * it's a tx or change based segment AND `last_block` is lower than the first
+                //   block of this file's block range. Otherwise, having no rows simply means that
+                //   this block range has no transactions or changes, but the file should remain.
                 if block_start != 0 &&
                     (segment.is_headers() || last_block.is_some_and(|b| b < block_start))
                 {
@@ -428,7 +476,7 @@ impl StaticFileProviderRW {
             }
         }
 
-        // Only Transactions and Receipts
+        // Only Transactions, Receipts and changesets
         if let Some(last_block) = last_block {
             let mut expected_block_start = self.writer.user_header().expected_block_start();
 
@@ -631,6 +679,99 @@ impl StaticFileProviderRW {
         Ok(Some(tx_number))
     }
 
+    /// Appends the account changes of a block to the static file.
+    ///
+    /// It **DOES NOT** call `increment_block()`, it should be handled elsewhere. There might be
+    /// blocks without changes and this function wouldn't be called.
+    ///
+    /// Returns the current change number as seen in the static file, if any.
+    pub fn append_account_changeset(
+        &mut self,
+        block_number: BlockNumber,
+        changes: impl IntoIterator<Item = AccountBeforeTx>,
+    ) -> ProviderResult<Option<TxNumber>> {
+        debug_assert!(self.writer.user_header().segment() == StaticFileSegment::AccountChangeSets);
+        debug_assert_eq!(self.writer.user_header().block_end(), Some(block_number));
+
+        let start = Instant::now();
+        self.ensure_no_queued_prune()?;
+
+        let mut change_number = None;
+        let mut count: u64 = 0;
+
+        for change in changes {
+            change_number = Some(self.increment_change_number());
+            self.append_column(CompactU64(block_number))?;
+            self.append_column(change)?;
+            count += 1;
+        }
+
+        if let Some(metrics) = &self.metrics {
+            metrics.record_segment_operations(
+                StaticFileSegment::AccountChangeSets,
+                StaticFileProviderOperation::Append,
+                count,
+                Some(start.elapsed()),
+            );
+        }
+
+        Ok(change_number)
+    }
+
+    /// Appends the storage changes of a block to the static file.
+    ///
+    /// It **DOES NOT** call `increment_block()`, it should be handled elsewhere. There might be
+    /// blocks without changes and this function wouldn't be called.
+    ///
+    /// Returns the current change number as seen in the static file, if any.
+    pub fn append_storage_changeset(
+        &mut self,
+        block_number: BlockNumber,
+        changes: impl IntoIterator<Item = (Address, StorageEntry)>,
+    ) -> ProviderResult<Option<TxNumber>> {
+        debug_assert!(self.writer.user_header().segment() == StaticFileSegment::StorageChangeSets);
+        debug_assert_eq!(self.writer.user_header().block_end(), Some(block_number));
+
+        let start = Instant::now();
+        self.ensure_no_queued_prune()?;
+
+        let mut change_number = None;
+        let mut count: u64 = 0;
+
+        for (address, entry) in changes {
+            change_number = Some(self.increment_change_number());
+            self.append_column(CompactU64(block_number))?;
+            self.append_column(address)?;
+            self.append_column(entry)?;
+            count += 1;
+        }
+
+        if let Some(metrics) = &self.metrics {
+            metrics.record_segment_operations(
+                StaticFileSegment::StorageChangeSets,
+                StaticFileProviderOperation::Append,
+                count,
+                Some(start.elapsed()),
+            );
+        }
+
+        Ok(change_number)
+    }
+
+    /// Increments the change range of the [`SegmentHeader`] and returns the new change number.
+    ///
+    /// The first change of a static file continues the change numbers of the previous one.
+    fn increment_change_number(&mut self) -> TxNumber {
+        if self.writer.user_header().tx_range().is_none() {
+            let segment = self.writer.user_header().segment();
+            let next = self.reader().get_highest_static_file_tx(segment).map_or(0, |n| n + 1);
+            self.writer.user_header_mut().set_tx_range(next, next);
+        } else {
+            self.writer.user_header_mut().increment_tx();
+        }
+        self.writer.user_header().tx_end().expect("qed")
+    }
+
     /// Adds an instruction to prune `to_delete`transactions during commit.
     ///
     /// Note: `last_block` refers to the block the unwinds ends at.
@@ -655,6 +796,18 @@ impl StaticFileProviderRW {
         self.queue_prune(to_delete, Some(last_block))
     }
 
+    /// Adds an instruction to prune `to_delete` account or storage changes during commit.
+    ///
+    /// Note: `last_block` refers to the block the unwinds ends at.
+    pub fn prune_changesets(
+        &mut self,
+        to_delete: u64,
+        last_block: BlockNumber,
+    ) -> ProviderResult<()> {
+        debug_assert!(self.writer.user_header().segment().is_change_based());
+        self.queue_prune(to_delete, Some(last_block))
+    }
+
     /// Adds an instruction to prune `to_delete` headers during commit.
     pub fn prune_headers(&mut self, to_delete: u64) -> ProviderResult<()> {
         debug_assert_eq!(self.writer.user_header().segment(), StaticFileSegment::Headers);
@@ -731,6 +884,30 @@ impl StaticFileProviderRW {
         Ok(())
     }
 
+    /// Prunes the last `to_delete` account or storage changes from the data file.
+    fn prune_changeset_data(
+        &mut self,
+        segment: StaticFileSegment,
+        to_delete: u64,
+        last_block: BlockNumber,
+    ) -> ProviderResult<()> {
+        let start = Instant::now();
+
+        debug_assert!(self.writer.user_header().segment() == segment);
+
+        self.truncate(to_delete, Some(last_block))?;
+
+        if let Some(metrics) = &self.metrics {
+            metrics.record_segment_operation(
+                segment,
+                StaticFileProviderOperation::Prune,
+                Some(start.elapsed()),
+            );
+        }
+
+        Ok(())
+    }
+
     /// Prunes the last `to_delete` headers from the data file.
     fn prune_header_data(&mut self, to_delete: u64) -> ProviderResult<()> {
         let start = Instant::now();
@@ -795,18 +972,31 @@ fn create_jar(
     segment: StaticFileSegment,
     path: &Path,
     expected_block_range: SegmentRangeInclusive,
+    config: SegmentConfig,
 ) -> NippyJar<SegmentHeader> {
-    let mut jar = NippyJar::new(
+    let jar = NippyJar::new(
         segment.columns(),
         path,
         SegmentHeader::new(expected_block_range, None, None, segment),
     );
 
-    // Transaction and Receipt already have the compression scheme used natively in its encoding.
-    // (zstd-dictionary)
-    if segment.is_headers() {
-        jar = jar.with_lz4();
-    }
+    with_compression(jar, config)
+}
 
-    jar
+/// Sets the compression of `jar` according to `config`.
+///
+/// Dictionaries can only be trained once there's data, so [`Compression::ZstdWithDictionary`] uses
+/// plain zstd until the finished static file is rewritten, see
+/// [`StaticFileProvider::queue_dictionary_rewrite`].
+pub(super) fn with_compression(
+    jar: NippyJar<SegmentHeader>,
+    config: SegmentConfig,
+) -> NippyJar<SegmentHeader> {
+    match config.compression {
+        Compression::Lz4 => jar.with_lz4(),
+        Compression::Zstd | Compression::ZstdWithDictionary => {
+            jar.with_zstd(false, 0).with_zstd_level(config.zstd_level)
+        }
+        Compression::Uncompressed => jar,
+    }
 }
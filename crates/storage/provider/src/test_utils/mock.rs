@@ -1,10 +1,13 @@
 use crate::{
+    providers::{SnapshotManifest, SnapshotOptions},
     traits::{BlockSource, ReceiptProvider},
-    AccountReader, BlockExecutionReader, BlockHashReader, BlockIdReader, BlockNumReader,
-    BlockReader, BlockReaderIdExt, ChainSpecProvider, ChangeSetReader, DatabaseProvider,
-    EvmEnvProvider, HeaderProvider, ReceiptProviderIdExt, RequestsProvider, StateProvider,
-    StateProviderBox, StateProviderFactory, StateReader, StateRootProvider, TransactionVariant,
-    TransactionsProvider, WithdrawalsProvider,
+    AccountHistoryReader, AccountReader, BadBlockReader, BlockExecutionReader, BlockHashReader,
+    BlockIdReader, BlockNumReader, BlockReader, BlockReaderIdExt, ChainSpecProvider,
+    ChangeSetReader, DatabaseProvider, EvmEnvProvider, HashedAccountRange, HashedStateRangeReader,
+    HashedStorageRange, HeaderProvider, RawDatabaseReader, ReceiptProviderIdExt, RequestsProvider,
+    StateProvider, StateProviderBox, StateProviderFactory, StateReader, StateRootProvider,
+    StorageSnapshotProvider, TraceIndexReader, TransactionVariant, TransactionsProvider,
+    WithdrawalsProvider,
 };
 use alloy_consensus::constants::EMPTY_ROOT_HASH;
 use alloy_eips::{BlockHashOrNumber, BlockId, BlockNumberOrTag};
@@ -16,8 +19,14 @@ use alloy_primitives::{
 };
 use parking_lot::Mutex;
 use reth_chainspec::{ChainInfo, ChainSpec};
-use reth_db::mock::{DatabaseMock, TxMock};
-use reth_db_api::models::{AccountBeforeTx, StoredBlockBodyIndices};
+use reth_db::{
+    mock::{DatabaseMock, TxMock},
+    Tables,
+};
+use reth_db_api::{
+    database_metrics::DatabaseMetadataValue,
+    models::{AccountBeforeTx, StoredBadBlock, StoredBlockBodyIndices},
+};
 use reth_evm::ConfigureEvmEnv;
 use reth_execution_types::{Chain, ExecutionOutcome};
 use reth_primitives::{
@@ -38,6 +47,7 @@ use revm::primitives::{BlockEnv, CfgEnvWithHandlerCfg};
 use std::{
     collections::BTreeMap,
     ops::{RangeBounds, RangeInclusive},
+    path::Path,
     sync::Arc,
 };
 
@@ -819,6 +829,84 @@ impl RequestsProvider for MockEthProvider {
     }
 }
 
+impl AccountHistoryReader for MockEthProvider {
+    fn account_history_blocks(
+        &self,
+        _address: Address,
+        _range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<Vec<BlockNumber>> {
+        Ok(Vec::default())
+    }
+}
+
+impl TraceIndexReader for MockEthProvider {
+    fn trace_index_tip(&self) -> ProviderResult<Option<BlockNumber>> {
+        Ok(None)
+    }
+
+    fn trace_index_blocks(
+        &self,
+        _addresses: &[Address],
+        _range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<Vec<BlockNumber>> {
+        Ok(Vec::default())
+    }
+}
+
+impl HashedStateRangeReader for MockEthProvider {
+    fn hashed_account_range(
+        &self,
+        _block_number: BlockNumber,
+        _overlay: HashedPostState,
+        _start: B256,
+        _limit: usize,
+        _with_storage: bool,
+    ) -> ProviderResult<HashedAccountRange> {
+        Ok(HashedAccountRange::default())
+    }
+
+    fn hashed_storage_range(
+        &self,
+        _block_number: BlockNumber,
+        _overlay: HashedPostState,
+        _address: Address,
+        _start: B256,
+        _limit: usize,
+    ) -> ProviderResult<HashedStorageRange> {
+        Ok(HashedStorageRange::default())
+    }
+
+    fn hashed_state_reverts(&self, _block_number: BlockNumber) -> ProviderResult<HashedPostState> {
+        Ok(HashedPostState::default())
+    }
+}
+
+impl RawDatabaseReader for MockEthProvider {
+    fn database_metadata(&self) -> ProviderResult<DatabaseMetadataValue> {
+        Ok(DatabaseMetadataValue::default())
+    }
+
+    fn raw_table_value(&self, _table: Tables, _key: Vec<u8>) -> ProviderResult<Option<Vec<u8>>> {
+        Ok(None)
+    }
+}
+
+impl StorageSnapshotProvider for MockEthProvider {
+    fn create_snapshot(
+        &self,
+        _dir: &Path,
+        _options: SnapshotOptions,
+    ) -> ProviderResult<SnapshotManifest> {
+        Err(ProviderError::UnsupportedProvider)
+    }
+}
+
+impl BadBlockReader for MockEthProvider {
+    fn bad_blocks(&self) -> ProviderResult<Vec<StoredBadBlock>> {
+        Ok(Vec::default())
+    }
+}
+
 impl ChangeSetReader for MockEthProvider {
     fn account_block_changeset(
         &self,
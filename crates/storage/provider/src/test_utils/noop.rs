@@ -1,6 +1,6 @@
 use std::{
     ops::{RangeBounds, RangeInclusive},
-    path::PathBuf,
+    path::{Path, PathBuf},
     sync::Arc,
 };
 
@@ -14,7 +14,11 @@ use reth_chain_state::{
     ForkChoiceSubscriptions,
 };
 use reth_chainspec::{ChainInfo, ChainSpec, MAINNET};
-use reth_db_api::models::{AccountBeforeTx, StoredBlockBodyIndices};
+use reth_db::Tables;
+use reth_db_api::{
+    database_metrics::DatabaseMetadataValue,
+    models::{AccountBeforeTx, StoredBadBlock, StoredBlockBodyIndices},
+};
 use reth_errors::ProviderError;
 use reth_evm::ConfigureEvmEnv;
 use reth_primitives::{
@@ -33,13 +37,15 @@ use revm::primitives::{BlockEnv, CfgEnvWithHandlerCfg};
 use tokio::sync::{broadcast, watch};
 
 use crate::{
-    providers::StaticFileProvider,
+    providers::{SnapshotManifest, SnapshotOptions, StaticFileProvider},
     traits::{BlockSource, ReceiptProvider},
-    AccountReader, BlockHashReader, BlockIdReader, BlockNumReader, BlockReader, BlockReaderIdExt,
-    ChainSpecProvider, ChangeSetReader, EvmEnvProvider, HeaderProvider, PruneCheckpointReader,
-    ReceiptProviderIdExt, RequestsProvider, StageCheckpointReader, StateProvider, StateProviderBox,
-    StateProviderFactory, StateRootProvider, StaticFileProviderFactory, TransactionVariant,
-    TransactionsProvider, WithdrawalsProvider,
+    AccountHistoryReader, AccountReader, BadBlockReader, BlockHashReader, BlockIdReader,
+    BlockNumReader, BlockReader, BlockReaderIdExt, ChainSpecProvider, ChangeSetReader,
+    EvmEnvProvider, HashedAccountRange, HashedStateRangeReader, HashedStorageRange, HeaderProvider,
+    PruneCheckpointReader, RawDatabaseReader, ReceiptProviderIdExt, RequestsProvider,
+    StageCheckpointReader, StateProvider, StateProviderBox, StateProviderFactory,
+    StateRootProvider, StaticFileProviderFactory, StorageSnapshotProvider, TraceIndexReader,
+    TransactionVariant, TransactionsProvider, WithdrawalsProvider,
 };
 
 /// Supports various api interfaces for testing purposes.
@@ -315,6 +321,16 @@ impl AccountReader for NoopProvider {
     }
 }
 
+impl AccountHistoryReader for NoopProvider {
+    fn account_history_blocks(
+        &self,
+        _address: Address,
+        _range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<Vec<BlockNumber>> {
+        Ok(Vec::default())
+    }
+}
+
 impl ChangeSetReader for NoopProvider {
     fn account_block_changeset(
         &self,
@@ -562,6 +578,74 @@ impl PruneCheckpointReader for NoopProvider {
     }
 }
 
+impl TraceIndexReader for NoopProvider {
+    fn trace_index_tip(&self) -> ProviderResult<Option<BlockNumber>> {
+        Ok(None)
+    }
+
+    fn trace_index_blocks(
+        &self,
+        _addresses: &[Address],
+        _range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<Vec<BlockNumber>> {
+        Ok(Vec::new())
+    }
+}
+
+impl HashedStateRangeReader for NoopProvider {
+    fn hashed_account_range(
+        &self,
+        _block_number: BlockNumber,
+        _overlay: HashedPostState,
+        _start: B256,
+        _limit: usize,
+        _with_storage: bool,
+    ) -> ProviderResult<HashedAccountRange> {
+        Ok(HashedAccountRange::default())
+    }
+
+    fn hashed_storage_range(
+        &self,
+        _block_number: BlockNumber,
+        _overlay: HashedPostState,
+        _address: Address,
+        _start: B256,
+        _limit: usize,
+    ) -> ProviderResult<HashedStorageRange> {
+        Ok(HashedStorageRange::default())
+    }
+
+    fn hashed_state_reverts(&self, _block_number: BlockNumber) -> ProviderResult<HashedPostState> {
+        Ok(HashedPostState::default())
+    }
+}
+
+impl RawDatabaseReader for NoopProvider {
+    fn database_metadata(&self) -> ProviderResult<DatabaseMetadataValue> {
+        Ok(DatabaseMetadataValue::default())
+    }
+
+    fn raw_table_value(&self, _table: Tables, _key: Vec<u8>) -> ProviderResult<Option<Vec<u8>>> {
+        Ok(None)
+    }
+}
+
+impl StorageSnapshotProvider for NoopProvider {
+    fn create_snapshot(
+        &self,
+        _dir: &Path,
+        _options: SnapshotOptions,
+    ) -> ProviderResult<SnapshotManifest> {
+        Err(ProviderError::UnsupportedProvider)
+    }
+}
+
+impl BadBlockReader for NoopProvider {
+    fn bad_blocks(&self) -> ProviderResult<Vec<StoredBadBlock>> {
+        Ok(Vec::new())
+    }
+}
+
 impl StaticFileProviderFactory for NoopProvider {
     fn static_file_provider(&self) -> StaticFileProvider {
         StaticFileProvider::read_only(PathBuf::default(), false).unwrap()
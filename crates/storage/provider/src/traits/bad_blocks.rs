@@ -0,0 +1,24 @@
+use reth_db_api::models::StoredBadBlock;
+use reth_errors::ProviderResult;
+use reth_primitives::SealedBlock;
+
+/// The maximum number of bad blocks that are kept in the database.
+///
+/// Once the limit is reached, the oldest bad block is evicted for every new one.
+pub const MAX_BAD_BLOCKS: usize = 10;
+
+/// Functionality to read the blocks that were rejected during validation.
+#[auto_impl::auto_impl(&, Arc)]
+pub trait BadBlockReader: Send + Sync {
+    /// Returns the stored bad blocks, ordered from the oldest to the most recent one.
+    fn bad_blocks(&self) -> ProviderResult<Vec<StoredBadBlock>>;
+}
+
+/// Functionality to record blocks that were rejected during validation.
+pub trait BadBlockWriter: Send + Sync {
+    /// Saves the given block together with the error it was rejected with.
+    ///
+    /// Blocks that are already stored are ignored. If more than [`MAX_BAD_BLOCKS`] blocks are
+    /// stored, the oldest ones are removed.
+    fn save_bad_block(&self, block: SealedBlock, error: String) -> ProviderResult<()>;
+}
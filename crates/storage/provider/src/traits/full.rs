@@ -1,9 +1,10 @@
 //! Helper provider traits to encapsulate all provider traits for simplicity.
 
 use crate::{
-    AccountReader, BlockReaderIdExt, ChainSpecProvider, ChangeSetReader, DatabaseProviderFactory,
-    EvmEnvProvider, HeaderProvider, StageCheckpointReader, StateProviderFactory,
-    StaticFileProviderFactory, TransactionsProvider,
+    AccountHistoryReader, AccountReader, BadBlockReader, BlockReaderIdExt, ChainSpecProvider,
+    ChangeSetReader, DatabaseProviderFactory, EvmEnvProvider, HashedStateRangeReader,
+    HeaderProvider, RawDatabaseReader, StageCheckpointReader, StateProviderFactory,
+    StaticFileProviderFactory, StorageSnapshotProvider, TraceIndexReader, TransactionsProvider,
 };
 use reth_chain_state::{CanonStateSubscriptions, ForkChoiceSubscriptions};
 use reth_chainspec::EthereumHardforks;
@@ -15,6 +16,12 @@ pub trait FullProvider<N: NodeTypesWithDB>:
     + StaticFileProviderFactory
     + BlockReaderIdExt
     + AccountReader
+    + AccountHistoryReader
+    + BadBlockReader
+    + TraceIndexReader
+    + HashedStateRangeReader
+    + RawDatabaseReader
+    + StorageSnapshotProvider
     + StateProviderFactory
     + EvmEnvProvider
     + ChainSpecProvider<ChainSpec = N::ChainSpec>
@@ -33,6 +40,12 @@ impl<T, N: NodeTypesWithDB> FullProvider<N> for T where
         + StaticFileProviderFactory
         + BlockReaderIdExt
         + AccountReader
+        + AccountHistoryReader
+        + BadBlockReader
+        + TraceIndexReader
+        + HashedStateRangeReader
+        + RawDatabaseReader
+        + StorageSnapshotProvider
         + StateProviderFactory
         + EvmEnvProvider
         + ChainSpecProvider<ChainSpec = N::ChainSpec>
@@ -56,6 +69,13 @@ pub trait FullRpcProvider:
     + HeaderProvider
     + TransactionsProvider
     + StageCheckpointReader
+    + AccountHistoryReader
+    + BadBlockReader
+    + TraceIndexReader
+    + HashedStateRangeReader
+    + RawDatabaseReader
+    + StorageSnapshotProvider
+    + StaticFileProviderFactory
     + Clone
     + Unpin
     + 'static
@@ -70,6 +90,13 @@ impl<T> FullRpcProvider for T where
         + HeaderProvider
         + TransactionsProvider
         + StageCheckpointReader
+        + AccountHistoryReader
+        + BadBlockReader
+        + TraceIndexReader
+        + HashedStateRangeReader
+        + RawDatabaseReader
+        + StorageSnapshotProvider
+        + StaticFileProviderFactory
         + Clone
         + Unpin
         + 'static
@@ -35,6 +35,24 @@ pub trait HistoryWriter: Send + Sync {
         storage_transitions: impl IntoIterator<Item = ((Address, B256), impl IntoIterator<Item = u64>)>,
     ) -> ProviderResult<()>;
 
+    /// Unwind and clear transaction appearance indices.
+    ///
+    /// Returns number of addresses unwound.
+    fn unwind_transaction_appearance_indices(
+        &self,
+        range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<usize>;
+
+    /// Insert transaction appearance index to database. Used inside
+    /// `IndexTransactionAppearances` stage
+    fn insert_transaction_appearance_index(
+        &self,
+        appearances: impl IntoIterator<Item = (Address, impl IntoIterator<Item = u64>)>,
+    ) -> ProviderResult<()>;
+
     /// Read account/storage changesets and update account/storage history indices.
+    ///
+    /// This also reads the transactions in the range and updates the transaction appearance
+    /// indices.
     fn update_history_indices(&self, range: RangeInclusive<BlockNumber>) -> ProviderResult<()>;
 }
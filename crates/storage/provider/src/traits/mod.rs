@@ -43,3 +43,20 @@ pub use tree_viewer::TreeViewer;
 
 mod finalized_block;
 pub use finalized_block::{ChainStateBlockReader, ChainStateBlockWriter};
+
+mod bad_blocks;
+pub use bad_blocks::{BadBlockReader, BadBlockWriter, MAX_BAD_BLOCKS};
+
+mod trace_index;
+pub use trace_index::{TraceIndexReader, TraceIndexWriter, TRACE_INDEX_ID};
+
+mod state_range;
+pub use state_range::{
+    HashedAccountEntry, HashedAccountRange, HashedStateRangeReader, HashedStorageRange,
+};
+
+mod raw_database;
+pub use raw_database::RawDatabaseReader;
+
+mod snapshot;
+pub use snapshot::StorageSnapshotProvider;
@@ -0,0 +1,16 @@
+use reth_db::Tables;
+use reth_db_api::database_metrics::DatabaseMetadataValue;
+use reth_errors::ProviderResult;
+
+/// Functionality to inspect the raw database of a running node.
+#[auto_impl::auto_impl(&, Arc)]
+pub trait RawDatabaseReader: Send + Sync {
+    /// Returns the metadata of the database, like the freelist size and the statistics of all
+    /// tables.
+    fn database_metadata(&self) -> ProviderResult<DatabaseMetadataValue>;
+
+    /// Returns the encoded value stored under the encoded key in the given table.
+    ///
+    /// For dupsort tables, the first value stored under the key is returned.
+    fn raw_table_value(&self, table: Tables, key: Vec<u8>) -> ProviderResult<Option<Vec<u8>>>;
+}
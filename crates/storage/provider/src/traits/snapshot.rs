@@ -0,0 +1,18 @@
+use crate::providers::{SnapshotManifest, SnapshotOptions};
+use reth_errors::ProviderResult;
+use std::path::Path;
+
+/// Functionality to take consistent snapshots of the storage of a running node.
+#[auto_impl::auto_impl(&, Arc)]
+pub trait StorageSnapshotProvider: Send + Sync {
+    /// Writes a snapshot of the database and the static files to the given directory, which must
+    /// be empty or not exist yet, and returns its manifest.
+    ///
+    /// The snapshot can be taken while the node is running and restored with
+    /// [`restore_snapshot`](crate::providers::restore_snapshot).
+    fn create_snapshot(
+        &self,
+        dir: &Path,
+        options: SnapshotOptions,
+    ) -> ProviderResult<SnapshotManifest>;
+}
@@ -0,0 +1,65 @@
+use alloy_primitives::{Address, BlockNumber, B256, U256};
+use reth_errors::ProviderResult;
+use reth_primitives::Account;
+use reth_trie::HashedPostState;
+
+/// An account returned by [`HashedStateRangeReader::hashed_account_range`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HashedAccountEntry {
+    /// The hashed address of the account.
+    pub hashed_address: B256,
+    /// The account.
+    pub account: Account,
+    /// The storage of the account, ordered by hashed slot, if it was requested.
+    pub storage: Option<Vec<(B256, U256)>>,
+}
+
+/// A page of hashed accounts.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct HashedAccountRange {
+    /// The accounts, ordered by hashed address.
+    pub accounts: Vec<HashedAccountEntry>,
+    /// The hashed address of the first account that did not fit into the page, if any.
+    pub next_key: Option<B256>,
+}
+
+/// A page of hashed storage slots.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct HashedStorageRange {
+    /// The storage slots and their values, ordered by hashed slot.
+    pub storage: Vec<(B256, U256)>,
+    /// The hashed slot of the first entry that did not fit into the page, if any.
+    pub next_key: Option<B256>,
+}
+
+/// Functionality to page through the hashed state at the end of a canonical block.
+#[auto_impl::auto_impl(&, Arc)]
+pub trait HashedStateRangeReader: Send + Sync {
+    /// Returns up to `limit` accounts with a hashed address greater than or equal to `start`, as
+    /// they were at the end of the given block with `overlay` applied on top.
+    ///
+    /// If `with_storage` is set, the full storage of every returned account is included.
+    fn hashed_account_range(
+        &self,
+        block_number: BlockNumber,
+        overlay: HashedPostState,
+        start: B256,
+        limit: usize,
+        with_storage: bool,
+    ) -> ProviderResult<HashedAccountRange>;
+
+    /// Returns up to `limit` storage slots of the given account with a hashed slot greater than or
+    /// equal to `start`, as they were at the end of the given block with `overlay` applied on top.
+    fn hashed_storage_range(
+        &self,
+        block_number: BlockNumber,
+        overlay: HashedPostState,
+        address: Address,
+        start: B256,
+        limit: usize,
+    ) -> ProviderResult<HashedStorageRange>;
+
+    /// Returns the reverts that turn the latest hashed state into the state at the end of the
+    /// given block.
+    fn hashed_state_reverts(&self, block_number: BlockNumber) -> ProviderResult<HashedPostState>;
+}
@@ -0,0 +1,47 @@
+use alloy_primitives::{Address, BlockNumber};
+use reth_errors::ProviderResult;
+use reth_stages_types::StageId;
+use std::{collections::BTreeMap, ops::RangeInclusive};
+
+/// The id under which the progress of the trace index is stored in the stage checkpoints table.
+///
+/// The trace index is not part of the pipeline, hence it is not one of [`StageId::ALL`].
+pub const TRACE_INDEX_ID: StageId = StageId::Other("TraceIndex");
+
+/// Functionality to read the optional index of the addresses that appeared in transaction traces.
+#[auto_impl::auto_impl(&, Arc)]
+pub trait TraceIndexReader: Send + Sync {
+    /// Returns the highest block covered by the trace index, or `None` if the index is not
+    /// maintained.
+    fn trace_index_tip(&self) -> ProviderResult<Option<BlockNumber>>;
+
+    /// Returns the numbers of the blocks in the given inclusive range in which any of the given
+    /// addresses appeared in a trace, in ascending order.
+    ///
+    /// Only the blocks up to [`Self::trace_index_tip`] are covered by the index.
+    fn trace_index_blocks(
+        &self,
+        addresses: &[Address],
+        range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<Vec<BlockNumber>>;
+}
+
+/// Functionality to maintain the index of the addresses that appeared in transaction traces.
+pub trait TraceIndexWriter: Send + Sync {
+    /// Appends the given appearances to the index and moves its tip to `tip`.
+    ///
+    /// All block numbers must be above the current tip of the index.
+    fn insert_trace_index(
+        &self,
+        appearances: BTreeMap<Address, Vec<BlockNumber>>,
+        tip: BlockNumber,
+    ) -> ProviderResult<()>;
+
+    /// Removes all blocks above `tip` from the index entries of the given addresses and moves the
+    /// tip of the index back to `tip`.
+    fn unwind_trace_index(
+        &self,
+        addresses: impl IntoIterator<Item = Address>,
+        tip: BlockNumber,
+    ) -> ProviderResult<()>;
+}
@@ -43,6 +43,27 @@ pub trait AccountExtReader: Send + Sync {
         &self,
         range: RangeInclusive<BlockNumber>,
     ) -> ProviderResult<BTreeMap<Address, Vec<BlockNumber>>>;
+
+    /// Iterate over the transactions in the given range of blocks and return all addresses that
+    /// appeared as a transaction sender or recipient alongside each specific set of blocks.
+    ///
+    /// NOTE: Get inclusive range of blocks.
+    fn transaction_appearances_with_range(
+        &self,
+        range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<BTreeMap<Address, Vec<BlockNumber>>>;
+}
+
+/// Account history reader
+#[auto_impl(&, Arc, Box)]
+pub trait AccountHistoryReader: Send + Sync {
+    /// Returns the numbers of the blocks in the given inclusive range in which the account was
+    /// changed or appeared as the sender or recipient of a transaction, in ascending order.
+    fn account_history_blocks(
+        &self,
+        address: Address,
+        range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<Vec<BlockNumber>>;
 }
 
 /// AccountChange reader
@@ -28,6 +28,8 @@ mod proofs;
 pub use proofs::triehash;
 pub use proofs::*;
 
+pub mod range_proof;
+
 pub mod root;
 
 pub use alloy_trie::{nodes::*, proof, BranchNodeCompact, HashBuilder, TrieMask, EMPTY_ROOT_HASH};
@@ -0,0 +1,294 @@
+//! Verification of range proofs, as served by the `snap` protocol.
+//!
+//! A range proof consists of the consecutive leaves of a trie, starting at a given key, and the
+//! proofs of the first key and the key of the last leaf. The root of the trie is rebuilt from the
+//! leaves and the hashes of the subtries left and right of the range, which are taken from the
+//! proof nodes. The range is complete if the rebuilt root matches the expected root.
+
+use crate::{HashBuilder, Nibbles, RlpNode, TrieNode, EMPTY_ROOT_HASH};
+use alloy_primitives::{keccak256, map::HashMap, Bytes, B256};
+use alloy_rlp::Decodable;
+use std::cmp::Ordering;
+
+/// Error returned by [`verify_range_proof`].
+#[derive(Clone, Debug, PartialEq, Eq, derive_more::Display)]
+pub enum RangeProofError {
+    /// The leaves are not in strictly ascending order or start before the first key.
+    #[display("leaves are not ordered")]
+    UnorderedLeaves,
+    /// The proof is missing a node on the path of one of the boundary keys.
+    #[display("missing proof node {_0}")]
+    MissingNode(B256),
+    /// A node of the proof could not be decoded.
+    #[display("invalid proof node: {_0}")]
+    InvalidNode(alloy_rlp::Error),
+    /// A subtrie outside of the range is embedded into its parent, which is not supported.
+    #[display("embedded node outside of the range")]
+    EmbeddedNode,
+    /// The proof shows that the trie has no leaves in the range, but some were given.
+    #[display("leaves outside of the trie")]
+    LeavesOutsideTrie,
+    /// The root rebuilt from the range does not match the expected root.
+    #[display("root mismatch: got {got}, expected {expected}")]
+    RootMismatch {
+        /// The rebuilt root.
+        got: B256,
+        /// The expected root.
+        expected: B256,
+    },
+}
+
+impl core::error::Error for RangeProofError {}
+
+impl From<alloy_rlp::Error> for RangeProofError {
+    fn from(err: alloy_rlp::Error) -> Self {
+        Self::InvalidNode(err)
+    }
+}
+
+/// Verifies that `leaves` are all leaves of the trie with the given `root` with a key between
+/// `first_key` and the key of the last leaf, both inclusive.
+///
+/// The leaves are pairs of hashed keys and RLP encoded values, in ascending order. The proof holds
+/// the nodes on the paths to `first_key` and to the key of the last leaf, in any order. If the
+/// proof is empty, the leaves must be all leaves of the trie.
+///
+/// Returns whether the trie has more leaves after the last one.
+pub fn verify_range_proof(
+    root: B256,
+    first_key: B256,
+    leaves: &[(B256, Vec<u8>)],
+    proof: &[Bytes],
+) -> Result<bool, RangeProofError> {
+    if leaves.first().is_some_and(|(key, _)| *key < first_key) ||
+        leaves.windows(2).any(|pair| pair[0].0 >= pair[1].0)
+    {
+        return Err(RangeProofError::UnorderedLeaves)
+    }
+
+    let mut left = Vec::new();
+    let mut right = Vec::new();
+    if !proof.is_empty() {
+        let nodes = proof.iter().map(|node| (keccak256(node), node)).collect::<HashMap<_, _>>();
+        let last_key = leaves.last().map_or(first_key, |(key, _)| *key);
+        collect_outside_subtries(
+            root,
+            &nodes,
+            Nibbles::unpack(first_key),
+            Ordering::Less,
+            &mut left,
+        )?;
+        collect_outside_subtries(
+            root,
+            &nodes,
+            Nibbles::unpack(last_key),
+            Ordering::Greater,
+            &mut right,
+        )?;
+    }
+
+    // the root node itself lies outside of the range
+    if left.iter().chain(&right).any(|(path, _)| path.is_empty()) {
+        return if leaves.is_empty() {
+            Ok(!right.is_empty())
+        } else {
+            Err(RangeProofError::LeavesOutsideTrie)
+        }
+    }
+
+    let mut hash_builder = HashBuilder::default();
+    for (path, hash) in &left {
+        hash_builder.add_branch(path.clone(), *hash, false);
+    }
+    for (key, value) in leaves {
+        hash_builder.add_leaf(Nibbles::unpack(key), value);
+    }
+    for (path, hash) in &right {
+        hash_builder.add_branch(path.clone(), *hash, false);
+    }
+
+    let got = hash_builder.root();
+    if got != root {
+        return Err(RangeProofError::RootMismatch { got, expected: root })
+    }
+    Ok(!right.is_empty())
+}
+
+/// Walks the path to `key` and collects the paths and hashes of all subtries that lie entirely on
+/// the given `side` of the key, in ascending order.
+fn collect_outside_subtries(
+    root: B256,
+    nodes: &HashMap<B256, &Bytes>,
+    key: Nibbles,
+    side: Ordering,
+    out: &mut Vec<(Nibbles, B256)>,
+) -> Result<(), RangeProofError> {
+    let mut path = Nibbles::default();
+    let mut current = RlpNode::word_rlp(&root);
+    loop {
+        let node = match current.as_hash() {
+            Some(hash) if hash == EMPTY_ROOT_HASH && path.is_empty() => return Ok(()),
+            Some(hash) => {
+                let node = nodes.get(&hash).ok_or(RangeProofError::MissingNode(hash))?;
+                TrieNode::decode(&mut &node[..])?
+            }
+            None => TrieNode::decode(&mut &current[..])?,
+        };
+
+        match node {
+            TrieNode::EmptyRoot => return Ok(()),
+            TrieNode::Branch(branch) => {
+                let nibble = key[path.len()];
+                let mut next = None;
+                let mut children = branch.stack.into_iter();
+                for index in 0..16u8 {
+                    if !branch.state_mask.is_bit_set(index) {
+                        continue
+                    }
+                    let child =
+                        children.next().ok_or(alloy_rlp::Error::Custom("invalid branch"))?;
+                    if index == nibble {
+                        next = Some(child);
+                    } else if index.cmp(&nibble) == side {
+                        let mut child_path = path.clone();
+                        child_path.push(index);
+                        push_subtrie(out, child_path, &child)?;
+                    }
+                }
+                let Some(child) = next else { return Ok(()) };
+                path.push(nibble);
+                current = child;
+            }
+            TrieNode::Extension(extension) => {
+                let end = (path.len() + extension.key.len()).min(key.len());
+                match extension.key[..].cmp(&key[path.len()..end]) {
+                    Ordering::Equal => {
+                        path.extend_from_slice(&extension.key);
+                        current = extension.child;
+                    }
+                    ordering => {
+                        if ordering == side {
+                            push_subtrie(out, path, &current)?;
+                        }
+                        return Ok(())
+                    }
+                }
+            }
+            TrieNode::Leaf(leaf) => {
+                if leaf.key[..].cmp(&key[path.len()..]) == side {
+                    push_subtrie(out, path, &current)?;
+                }
+                return Ok(())
+            }
+        }
+    }
+}
+
+fn push_subtrie(
+    out: &mut Vec<(Nibbles, B256)>,
+    path: Nibbles,
+    node: &RlpNode,
+) -> Result<(), RangeProofError> {
+    let hash = node.as_hash().ok_or(RangeProofError::EmbeddedNode)?;
+    out.push((path, hash));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proof::ProofRetainer;
+    use alloy_primitives::U256;
+
+    fn leaves(count: u64) -> Vec<(B256, Vec<u8>)> {
+        let mut leaves = (0..count)
+            .map(|i| {
+                (keccak256(U256::from(i).to_be_bytes::<32>()), alloy_rlp::encode(U256::from(i + 1)))
+            })
+            .collect::<Vec<_>>();
+        leaves.sort();
+        leaves
+    }
+
+    fn root_and_proof(leaves: &[(B256, Vec<u8>)], targets: &[B256]) -> (B256, Vec<Bytes>) {
+        let targets = targets.iter().map(|key| Nibbles::unpack(key)).collect::<Vec<_>>();
+        let mut hash_builder =
+            HashBuilder::default().with_proof_retainer(ProofRetainer::from_iter(targets.clone()));
+        for (key, value) in leaves {
+            hash_builder.add_leaf(Nibbles::unpack(key), value);
+        }
+        let root = hash_builder.root();
+        let nodes = hash_builder.take_proof_nodes();
+        let proof = targets
+            .iter()
+            .flat_map(|target| nodes.matching_nodes_iter(target).map(|(_, node)| node.clone()))
+            .collect();
+        (root, proof)
+    }
+
+    #[test]
+    fn verify_ranges() {
+        let leaves = leaves(100);
+
+        // the whole trie without a proof
+        let (root, _) = root_and_proof(&leaves, &[]);
+        assert_eq!(verify_range_proof(root, B256::ZERO, &leaves, &[]), Ok(false));
+
+        // a range in the middle
+        let range = &leaves[10..20];
+        let (root, proof) = root_and_proof(&leaves, &[range[0].0, range[9].0]);
+        assert_eq!(verify_range_proof(root, range[0].0, range, &proof), Ok(true));
+
+        // a range starting before its first leaf
+        let first_key = B256::from(U256::from_be_bytes(leaves[9].0 .0) + U256::from(1));
+        let (root, proof) = root_and_proof(&leaves, &[first_key, range[9].0]);
+        assert_eq!(verify_range_proof(root, first_key, range, &proof), Ok(true));
+
+        // the last range
+        let range = &leaves[90..];
+        let (root, proof) = root_and_proof(&leaves, &[range[0].0, range[9].0]);
+        assert_eq!(verify_range_proof(root, range[0].0, range, &proof), Ok(false));
+
+        // no leaves after the first key
+        let first_key = B256::repeat_byte(0xff);
+        let (root, proof) = root_and_proof(&leaves, &[first_key]);
+        assert_eq!(verify_range_proof(root, first_key, &[], &proof), Ok(false));
+    }
+
+    #[test]
+    fn reject_incomplete_ranges() {
+        let leaves = leaves(100);
+        let range = &leaves[10..20];
+        let (root, proof) = root_and_proof(&leaves, &[range[0].0, range[9].0]);
+
+        // a missing leaf in the middle of the range
+        let mut incomplete = range.to_vec();
+        incomplete.remove(5);
+        assert!(matches!(
+            verify_range_proof(root, range[0].0, &incomplete, &proof),
+            Err(RangeProofError::RootMismatch { .. })
+        ));
+
+        // a modified value
+        let mut modified = range.to_vec();
+        modified[3].1 = alloy_rlp::encode(U256::MAX);
+        assert!(matches!(
+            verify_range_proof(root, range[0].0, &modified, &proof),
+            Err(RangeProofError::RootMismatch { .. })
+        ));
+
+        // leaves before the first key
+        assert_eq!(
+            verify_range_proof(root, range[1].0, range, &proof),
+            Err(RangeProofError::UnorderedLeaves)
+        );
+
+        // a missing proof node
+        let without_root =
+            proof.iter().filter(|node| keccak256(node) != root).cloned().collect::<Vec<_>>();
+        assert_eq!(
+            verify_range_proof(root, range[0].0, range, &without_root),
+            Err(RangeProofError::MissingNode(root))
+        );
+    }
+}
@@ -13,7 +13,8 @@ use reth_chainspec::{Chain, MAINNET};
 use reth_discv4::{DiscoveryUpdate, Discv4, Discv4ConfigBuilder, DEFAULT_DISCOVERY_ADDRESS};
 use reth_ecies::stream::ECIESStream;
 use reth_eth_wire::{
-    EthMessage, EthStream, HelloMessage, P2PStream, Status, UnauthedEthStream, UnauthedP2PStream,
+    EthMessage, EthStream, HelloMessage, P2PStream, Status, StatusMessage, UnauthedEthStream,
+    UnauthedP2PStream,
 };
 use reth_network::config::rng_secret_key;
 use reth_network_peers::{mainnet_nodes, pk2id, NodeRecord};
@@ -67,7 +68,10 @@ async fn main() -> eyre::Result<()> {
 
                 println!(
                     "Successfully connected to a peer at {}:{} ({}) using eth-wire version eth/{}",
-                    peer.address, peer.tcp_port, their_hello.client_version, their_status.version
+                    peer.address,
+                    peer.tcp_port,
+                    their_hello.client_version,
+                    their_status.version()
                 );
 
                 snoop(peer, eth_stream).await;
@@ -93,7 +97,9 @@ async fn handshake_p2p(
 }
 
 // Perform a ETH Wire handshake with a peer
-async fn handshake_eth(p2p_stream: AuthedP2PStream) -> eyre::Result<(AuthedEthStream, Status)> {
+async fn handshake_eth(
+    p2p_stream: AuthedP2PStream,
+) -> eyre::Result<(AuthedEthStream, StatusMessage)> {
     let fork_filter = MAINNET.fork_filter(Head {
         timestamp: MAINNET.fork(EthereumHardfork::Shanghai).as_timestamp().unwrap(),
         ..Default::default()